@@ -3,7 +3,7 @@
 //! Maps resolved KeyAction variants to concrete state mutations and
 //! returns an Action for the main loop to execute.
 
-use super::sql_utils::{is_destructive_query, is_write_query, translate_meta_command};
+use super::sql_utils::{find_usages_sql, translate_meta_command};
 use super::*;
 
 impl App {
@@ -32,11 +32,14 @@ impl App {
             }
 
             KeyAction::NewTab => {
+                let draft = self.new_tab_copies_draft.then(|| self.current_draft());
                 if !self.new_tab() {
                     self.set_status(
                         format!("Maximum {} tabs open", self.max_tabs),
                         StatusLevel::Warning,
                     );
+                } else if let Some(draft) = draft {
+                    self.tab_mut().editor.set_content(draft);
                 }
                 Action::None
             }
@@ -86,6 +89,13 @@ impl App {
                     PanelFocus::TreeBrowser => self.tree_browser.move_up(),
                     PanelFocus::Inspector => self.inspector.scroll_up(),
                     PanelFocus::Help => self.help.scroll_up(),
+                    PanelFocus::Messages => self.messages.scroll_up(),
+                    PanelFocus::Histogram => self.histogram.scroll_up(),
+                    PanelFocus::TablePreview => self.table_preview.scroll_up(),
+                    PanelFocus::Notifications => self.notifications.scroll_up(),
+                    PanelFocus::Sessions => self.sessions.scroll_up(),
+                    PanelFocus::Dashboard => self.dashboard.scroll_up(),
+                    PanelFocus::Replication => self.replication.scroll_up(),
                     _ => {}
                 }
                 Action::None
@@ -103,19 +113,36 @@ impl App {
                     PanelFocus::TreeBrowser => self.tree_browser.move_down(),
                     PanelFocus::Inspector => self.inspector.scroll_down(),
                     PanelFocus::Help => self.help.scroll_down(),
+                    PanelFocus::Messages => self.messages.scroll_down(),
+                    PanelFocus::Histogram => self.histogram.scroll_down(),
+                    PanelFocus::TablePreview => self.table_preview.scroll_down(),
+                    PanelFocus::Notifications => self.notifications.scroll_down(),
+                    PanelFocus::Sessions => self.sessions.scroll_down(),
+                    PanelFocus::Dashboard => self.dashboard.scroll_down(),
+                    PanelFocus::Replication => self.replication.scroll_down(),
                     _ => {}
                 }
                 Action::None
             }
             KeyAction::MoveLeft => {
-                if self.focus == PanelFocus::ResultsViewer && self.tab().explain_viewer.is_none() {
-                    self.tab_mut().results_viewer.move_left();
+                if self.focus == PanelFocus::ResultsViewer {
+                    let tab = self.tab_mut();
+                    if let Some(ref mut ev) = tab.explain_viewer {
+                        ev.collapse();
+                    } else {
+                        tab.results_viewer.move_left();
+                    }
                 }
                 Action::None
             }
             KeyAction::MoveRight => {
-                if self.focus == PanelFocus::ResultsViewer && self.tab().explain_viewer.is_none() {
-                    self.tab_mut().results_viewer.move_right();
+                if self.focus == PanelFocus::ResultsViewer {
+                    let tab = self.tab_mut();
+                    if let Some(ref mut ev) = tab.explain_viewer {
+                        ev.expand();
+                    } else {
+                        tab.results_viewer.move_right();
+                    }
                 }
                 Action::None
             }
@@ -131,6 +158,13 @@ impl App {
                     }
                     PanelFocus::Inspector => self.inspector.page_up(),
                     PanelFocus::Help => self.help.page_up(),
+                    PanelFocus::Messages => self.messages.page_up(),
+                    PanelFocus::Histogram => self.histogram.page_up(),
+                    PanelFocus::TablePreview => self.table_preview.page_up(),
+                    PanelFocus::Notifications => self.notifications.page_up(),
+                    PanelFocus::Sessions => self.sessions.page_up(),
+                    PanelFocus::Dashboard => self.dashboard.page_up(),
+                    PanelFocus::Replication => self.replication.page_up(),
                     _ => {}
                 }
                 Action::None
@@ -147,6 +181,13 @@ impl App {
                     }
                     PanelFocus::Inspector => self.inspector.page_down(),
                     PanelFocus::Help => self.help.page_down(),
+                    PanelFocus::Messages => self.messages.page_down(),
+                    PanelFocus::Histogram => self.histogram.page_down(),
+                    PanelFocus::TablePreview => self.table_preview.page_down(),
+                    PanelFocus::Notifications => self.notifications.page_down(),
+                    PanelFocus::Sessions => self.sessions.page_down(),
+                    PanelFocus::Dashboard => self.dashboard.page_down(),
+                    PanelFocus::Replication => self.replication.page_down(),
                     _ => {}
                 }
                 Action::None
@@ -163,6 +204,13 @@ impl App {
                     }
                     PanelFocus::Inspector => self.inspector.scroll_to_top(),
                     PanelFocus::Help => self.help.scroll_to_top(),
+                    PanelFocus::Messages => self.messages.scroll_to_top(),
+                    PanelFocus::Histogram => self.histogram.scroll_to_top(),
+                    PanelFocus::TablePreview => self.table_preview.scroll_to_top(),
+                    PanelFocus::Notifications => self.notifications.scroll_to_top(),
+                    PanelFocus::Sessions => self.sessions.scroll_to_top(),
+                    PanelFocus::Dashboard => self.dashboard.scroll_to_top(),
+                    PanelFocus::Replication => self.replication.scroll_to_top(),
                     _ => {}
                 }
                 Action::None
@@ -179,6 +227,13 @@ impl App {
                     }
                     PanelFocus::Inspector => self.inspector.scroll_to_bottom(),
                     PanelFocus::Help => self.help.scroll_to_bottom(),
+                    PanelFocus::Messages => self.messages.scroll_to_bottom(),
+                    PanelFocus::Histogram => self.histogram.scroll_to_bottom(),
+                    PanelFocus::TablePreview => self.table_preview.scroll_to_bottom(),
+                    PanelFocus::Notifications => self.notifications.scroll_to_bottom(),
+                    PanelFocus::Sessions => self.sessions.scroll_to_bottom(),
+                    PanelFocus::Dashboard => self.dashboard.scroll_to_bottom(),
+                    PanelFocus::Replication => self.replication.scroll_to_bottom(),
                     _ => {}
                 }
                 Action::None
@@ -198,38 +253,20 @@ impl App {
 
             // ── Editor ───────────────────────────────────────
             KeyAction::ExecuteQuery => {
-                let raw_sql = self.tab().editor.get_content();
+                let mut raw_sql = self.tab().editor.get_content();
                 // Translate psql meta-commands (e.g. \dt) to SQL
-                let sql = translate_meta_command(&raw_sql).unwrap_or(raw_sql);
-                if !sql.trim().is_empty() {
-                    // Block writes in read-only mode
-                    if self.read_only
-                        && let Some(label) = is_write_query(&sql)
-                    {
-                        self.set_status(
-                            format!("Read-only mode: {} queries are blocked", label),
-                            StatusLevel::Error,
-                        );
-                        return Action::None;
+                let meta = translate_meta_command(&raw_sql);
+                if self.format_on_execute && meta.is_none() && !raw_sql.trim().is_empty() {
+                    let formatted = format_sql(&raw_sql);
+                    if formatted != raw_sql.trim_end() {
+                        self.tab_mut().editor.replace_content(formatted);
+                        self.clear_completions();
                     }
-                    // Check for destructive query
-                    if self.confirm_destructive
-                        && let Some(label) = is_destructive_query(&sql)
-                    {
-                        self.pending_confirm_sql = Some(PendingConfirm {
-                            sql,
-                            tab_id: self.tab().id,
-                            timeout_ms: self.query_timeout_ms,
-                            max_rows: self.max_result_rows,
-                        });
-                        self.set_status(
-                            format!("This query contains {}. Execute? (y/N)", label),
-                            StatusLevel::Warning,
-                        );
-                        return Action::None;
-                    }
-                    self.set_status("Executing query...".to_string(), StatusLevel::Info);
-                    self.prepare_execute_query(sql)
+                    raw_sql = self.tab().editor.get_content();
+                }
+                let sql = meta.unwrap_or(raw_sql);
+                if !sql.trim().is_empty() {
+                    self.run_editor_sql(sql)
                 } else {
                     Action::None
                 }
@@ -237,18 +274,52 @@ impl App {
             KeyAction::ExplainQuery => {
                 let sql = self.tab().editor.get_content();
                 if !sql.trim().is_empty() {
-                    let explain = if self.explain_visual {
+                    let options = self.explain_options;
+                    if self.explain_visual {
                         self.tab_mut().explain_pending = true;
-                        format!("EXPLAIN (ANALYZE, FORMAT JSON) {}", sql.trim())
-                    } else {
-                        format!("EXPLAIN ANALYZE {}", sql.trim())
-                    };
-                    self.set_status("Running EXPLAIN ANALYZE...".to_string(), StatusLevel::Info);
-                    self.prepare_execute_query(explain)
+                    }
+                    let explain = options.explain_sql(&sql, self.explain_visual);
+                    self.set_status(format!("Running {}...", options.label()), StatusLevel::Info);
+                    self.prepare_execute_query(explain, Vec::new())
                 } else {
                     Action::None
                 }
             }
+            KeyAction::EstimateRows => self.start_estimate(),
+            KeyAction::CopySelection => {
+                if let Some(text) = self.tab().editor.selected_text() {
+                    self.copy_to_clipboard(&text);
+                }
+                Action::None
+            }
+            KeyAction::CutSelection => {
+                if let Some(text) = self.tab().editor.selected_text()
+                    && self.copy_to_clipboard(&text)
+                {
+                    self.tab_mut().editor.replace_selection("");
+                    return self.update_completions();
+                }
+                Action::None
+            }
+            KeyAction::Paste => {
+                let text = match self.clipboard.as_mut().map(|c| c.get_text()) {
+                    Some(Ok(text)) => text,
+                    Some(Err(e)) => {
+                        self.set_status(format!("Clipboard error: {}", e), StatusLevel::Warning);
+                        return Action::None;
+                    }
+                    None => {
+                        let reason = self.clipboard_error.as_deref().unwrap_or("unknown reason");
+                        self.set_status(
+                            format!("Clipboard unavailable: {}", reason),
+                            StatusLevel::Warning,
+                        );
+                        return Action::None;
+                    }
+                };
+                self.tab_mut().editor.replace_selection(&text);
+                self.update_completions()
+            }
             KeyAction::CancelQuery => {
                 // Prefer cancelling the active tab; fall back to any running tab
                 let active = &self.tabs[self.active_tab];
@@ -257,13 +328,34 @@ impl App {
                 } else {
                     self.tabs.iter().find(|t| t.query_running).map(|t| t.id)
                 };
-                if let Some(tab_id) = target {
+                if let Some(idx) = target.and_then(|id| self.tab_index_by_id(id))
+                    && self.tabs[idx].cost_check.take().is_some()
+                {
+                    // Only the cost guard's EXPLAIN is out; let it finish unread
+                    self.tabs[idx].query_running = false;
+                    self.tabs[idx].query_start = None;
+                    self.set_status("Query cancelled".to_string(), StatusLevel::Warning);
+                    Action::None
+                } else if let Some(tab_id) = target {
                     self.set_status("Cancelling query...".to_string(), StatusLevel::Warning);
                     Action::CancelQuery {
                         tab_id,
                         terminate: false,
                     }
                 } else {
+                    // Nothing to cancel — Esc clears editor search highlights,
+                    // or results search highlights and then the row filter
+                    match self.focus {
+                        PanelFocus::QueryEditor => self.tab_mut().editor.clear_search(),
+                        PanelFocus::ResultsViewer => {
+                            // Search highlights go first, then the filter
+                            let viewer = &mut self.tab_mut().results_viewer;
+                            if !viewer.clear_search() && viewer.clear_filter() {
+                                self.set_status("Filter cleared".to_string(), StatusLevel::Info);
+                            }
+                        }
+                        _ => {}
+                    }
                     Action::None
                 }
             }
@@ -285,22 +377,73 @@ impl App {
             KeyAction::FormatQuery => {
                 let sql = self.tab().editor.get_content();
                 if !sql.trim().is_empty() {
-                    let formatted = sqlformat::format(
-                        &sql,
-                        &sqlformat::QueryParams::None,
-                        &sqlformat::FormatOptions {
-                            indent: sqlformat::Indent::Spaces(2),
-                            uppercase: Some(true),
-                            lines_between_queries: 1,
-                            ..Default::default()
-                        },
-                    );
-                    self.tab_mut().editor.replace_content(formatted);
+                    self.tab_mut().editor.replace_content(format_sql(&sql));
                     self.clear_completions();
                     self.set_status("Query formatted".to_string(), StatusLevel::Info);
                 }
                 Action::None
             }
+            KeyAction::Find => {
+                if self.focus != PanelFocus::ResultsViewer {
+                    self.start_search_prompt(SearchPrompt::Find);
+                } else if self.tab().results_viewer.results().is_none() {
+                    self.set_status("No results to search".to_string(), StatusLevel::Warning);
+                } else {
+                    self.start_search_prompt(SearchPrompt::Results);
+                }
+                Action::None
+            }
+            KeyAction::Replace => {
+                self.start_search_prompt(SearchPrompt::ReplaceFind);
+                Action::None
+            }
+            KeyAction::FindNext | KeyAction::FindPrev => {
+                let active = if self.focus == PanelFocus::ResultsViewer {
+                    self.tab().results_viewer.search()
+                } else {
+                    self.tab().editor.search()
+                };
+                if active.is_none() {
+                    self.set_status("No active search".to_string(), StatusLevel::Info);
+                } else {
+                    self.report_search_jump(action == KeyAction::FindNext);
+                }
+                Action::None
+            }
+            KeyAction::DuplicateLine => {
+                self.tab_mut().editor.duplicate_lines();
+                Action::None
+            }
+            KeyAction::DeleteLine => {
+                self.tab_mut().editor.delete_lines();
+                self.update_completions()
+            }
+            KeyAction::JumpToBracket => {
+                if !self.tab_mut().editor.jump_to_matching_bracket() {
+                    self.set_status("No matching parenthesis".to_string(), StatusLevel::Info);
+                }
+                Action::None
+            }
+            KeyAction::ToggleComment => {
+                self.tab_mut().editor.toggle_comment();
+                Action::None
+            }
+            KeyAction::MoveLineUp | KeyAction::MoveLineDown => {
+                let up = action == KeyAction::MoveLineUp;
+                let tab = &mut self.tabs[self.active_tab];
+                // While a completion is shown the keys cycle through them
+                if tab.completer.is_active() && tab.editor.ghost_text().is_some() {
+                    let ghost = if up {
+                        tab.completer.prev()
+                    } else {
+                        tab.completer.next()
+                    };
+                    tab.editor.set_ghost_text(ghost);
+                } else {
+                    tab.editor.move_lines(up);
+                }
+                Action::None
+            }
             KeyAction::NextCompletion => {
                 let tab = &mut self.tabs[self.active_tab];
                 if tab.completer.is_active() {
@@ -316,18 +459,25 @@ impl App {
                 Action::None
             }
             KeyAction::HistoryBack => {
-                let current = self.tab().editor.get_content();
-                let entry = self.history.back(&current).map(|e| e.to_string());
-                if let Some(text) = entry {
-                    self.tab_mut().editor.set_content(text);
+                let tab = &mut self.tabs[self.active_tab];
+                let current = tab.editor.get_content();
+                if let Some(text) = self.history.back(&mut tab.history_cursor, &current) {
+                    tab.editor.set_content(text.to_string());
                 }
                 self.clear_completions();
                 Action::None
             }
+            KeyAction::SearchHistory => {
+                self.previous_focus = self.focus;
+                self.focus = PanelFocus::HistoryBrowser;
+                self.history_browser
+                    .show(self.history.entries().cloned().collect());
+                Action::None
+            }
             KeyAction::HistoryForward => {
-                let entry = self.history.forward().map(|e| e.to_string());
-                if let Some(text) = entry {
-                    self.tab_mut().editor.set_content(text);
+                let tab = &mut self.tabs[self.active_tab];
+                if let Some(text) = self.history.forward(&mut tab.history_cursor) {
+                    tab.editor.set_content(text.to_string());
                 }
                 self.clear_completions();
                 Action::None
@@ -335,6 +485,12 @@ impl App {
 
             // ── Results ──────────────────────────────────────
             KeyAction::OpenInspector => {
+                if self.focus == PanelFocus::ResultsViewer
+                    && let Some(ref mut ev) = self.tab_mut().explain_viewer
+                {
+                    ev.toggle_collapse();
+                    return Action::None;
+                }
                 if let Some((value, col_name, data_type)) =
                     self.tab().results_viewer.selected_cell_info()
                 {
@@ -365,6 +521,126 @@ impl App {
                 self.tab_mut().results_viewer.reset_column_widths();
                 Action::None
             }
+            KeyAction::ExpandColumn => {
+                self.tab_mut().results_viewer.toggle_expand_column();
+                Action::None
+            }
+            KeyAction::SortColumn => {
+                self.tab_mut().results_viewer.sort_by_selected_column();
+                Action::None
+            }
+            KeyAction::ClearSort => {
+                self.tab_mut().results_viewer.clear_sort();
+                Action::None
+            }
+            KeyAction::ColumnHistogram => {
+                let viewer = &self.tabs[self.active_tab].results_viewer;
+                let col = viewer.selected_column();
+                if let Some(results) = viewer.results()
+                    && self.histogram.show(results, col)
+                {
+                    self.previous_focus = self.focus;
+                    self.focus = PanelFocus::Histogram;
+                } else {
+                    self.set_status("No results to profile".to_string(), StatusLevel::Warning);
+                }
+                Action::None
+            }
+            KeyAction::PreviewTable => {
+                let editor = &self.tabs[self.active_tab].editor;
+                let (line_idx, char_col) = editor.cursor();
+                let line = editor.line(line_idx).unwrap_or_default();
+                let col = line
+                    .char_indices()
+                    .nth(char_col)
+                    .map_or(line.len(), |(i, _)| i);
+                let Some(name) = completer::identifier_at(line, col) else {
+                    self.set_status(
+                        "Put the cursor on a table name to preview it".to_string(),
+                        StatusLevel::Warning,
+                    );
+                    return Action::None;
+                };
+                match self.tree_browser.find_relation(&name) {
+                    Some((schema, table, is_view)) => {
+                        self.table_preview.show(schema, table, is_view);
+                        self.previous_focus = self.focus;
+                        self.focus = PanelFocus::TablePreview;
+                    }
+                    None => self.set_status(
+                        format!("No table or view named '{}' in the loaded schemas", name),
+                        StatusLevel::Warning,
+                    ),
+                }
+                Action::None
+            }
+            KeyAction::PreviousResults => {
+                self.step_results(true);
+                Action::None
+            }
+            KeyAction::NextResults => {
+                self.step_results(false);
+                Action::None
+            }
+            KeyAction::TogglePin => {
+                let tab = self.tab_mut();
+                tab.pinned = !tab.pinned;
+                let message = if tab.pinned {
+                    "Results pinned — re-runs open in a new tab"
+                } else {
+                    "Results unpinned"
+                };
+                self.set_status(message.to_string(), StatusLevel::Info);
+                Action::None
+            }
+            KeyAction::PageLeft | KeyAction::PageRight => {
+                let tab = self.tab_mut();
+                if tab.explain_viewer.is_none() {
+                    if action == KeyAction::PageLeft {
+                        tab.results_viewer.page_left();
+                    } else {
+                        tab.results_viewer.page_right();
+                    }
+                }
+                Action::None
+            }
+            KeyAction::JumpToColumn => {
+                if self.tab().results_viewer.results().is_none() {
+                    self.set_status("No results".to_string(), StatusLevel::Warning);
+                } else {
+                    let origin = self.tab().results_viewer.selected_column();
+                    self.start_search_prompt(SearchPrompt::Column(origin));
+                }
+                Action::None
+            }
+            KeyAction::FilterRows => {
+                if self.tab().results_viewer.results().is_none() {
+                    self.set_status("No results to filter".to_string(), StatusLevel::Warning);
+                } else {
+                    self.start_row_filter_prompt();
+                }
+                Action::None
+            }
+            KeyAction::EditCell => {
+                self.start_cell_edit_prompt();
+                Action::None
+            }
+            KeyAction::InsertRow => self.open_insert_form(),
+            KeyAction::ColumnInList => {
+                match self.tab().results_viewer.column_in_list() {
+                    Ok((list, count)) => {
+                        self.tab_mut().editor.replace_selection(&list);
+                        self.focus = PanelFocus::QueryEditor;
+                        self.set_status(
+                            format!("Inserted an IN list of {} values", count),
+                            StatusLevel::Info,
+                        );
+                    }
+                    Err(e) => self.set_status(e, StatusLevel::Warning),
+                }
+                Action::None
+            }
+            KeyAction::FollowForeignKey => self.follow_foreign_key(),
             KeyAction::CopyCell => {
                 if let Some(text) = self.tab().results_viewer.selected_cell_text() {
                     self.copy_to_clipboard(&text);
@@ -377,12 +653,20 @@ impl App {
                 }
                 Action::None
             }
+            KeyAction::CopyResultsCsv => {
+                self.copy_results(ExportFormat::Csv);
+                Action::None
+            }
+            KeyAction::CopyResultsMarkdown => {
+                self.copy_results(ExportFormat::Markdown);
+                Action::None
+            }
             KeyAction::ExportCsv => {
-                self.start_export(ExportFormat::Csv);
+                self.start_export(ExportFormat::Csv, "");
                 Action::None
             }
             KeyAction::ExportJson => {
-                self.start_export(ExportFormat::Json);
+                self.start_export(ExportFormat::Json, "");
                 Action::None
             }
 
@@ -394,6 +678,17 @@ impl App {
                 Action::None
             }
 
+            // ── Sessions ─────────────────────────────────────
+            KeyAction::RefreshSessions => self.load_sessions(),
+            KeyAction::CancelSession => self.signal_session(false),
+            KeyAction::TerminateSession => self.signal_session(true),
+
+            // ── Dashboard ────────────────────────────────────
+            KeyAction::RefreshDashboard => self.refresh_dashboard(),
+
+            // ── Replication ──────────────────────────────────
+            KeyAction::RefreshReplication => self.load_replication(),
+
             // ── Tree ─────────────────────────────────────────
             KeyAction::ToggleExpand => {
                 self.tree_browser.toggle_expand();
@@ -418,6 +713,11 @@ impl App {
                             limit,
                         };
                     }
+                    // Check if a database is selected - switch to it
+                    if let Some((name, _)) = self.tree_browser.selected_database() {
+                        let name = name.to_string();
+                        return self.use_database(name);
+                    }
                     // Check if saved query is selected - load into editor
                     if let Some(sq) = self.tree_browser.selected_saved_query() {
                         let sql = sq.sql.clone();
@@ -427,6 +727,14 @@ impl App {
                         self.set_status(format!("Loaded saved query: {}", name), StatusLevel::Info);
                         return Action::None;
                     }
+                    if self.skip_unreadable_tables && self.tree_browser.selected_select_denied() {
+                        self.set_status(
+                            "No SELECT privilege on this relation \u{2014} not previewing it"
+                                .to_string(),
+                            StatusLevel::Warning,
+                        );
+                        return Action::None;
+                    }
                     // Check if table/view is selected - run paginated preview
                     if let Some(base_sql) = self.tree_browser.preview_base_query() {
                         let page_size = self.tree_browser.preview_rows();
@@ -437,6 +745,7 @@ impl App {
                             has_more: false,
                             user_has_limit: false,
                             previous_page: None,
+                            params: Vec::new(),
                         };
                         let paged_sql = pagination.paged_sql();
                         let display_sql = format!("{} LIMIT {}", base_sql, page_size);
@@ -449,6 +758,7 @@ impl App {
                         self.set_status("Executing query...".to_string(), StatusLevel::Info);
                         return Action::ExecuteQuery {
                             sql: paged_sql,
+                            params: Vec::new(),
                             tab_id,
                             timeout_ms,
                             max_rows: 0,
@@ -482,58 +792,15 @@ impl App {
 
             KeyAction::ShowDefinition => {
                 if self.focus == PanelFocus::TreeBrowser {
-                    if let Some((schema, table)) = self.tree_browser.selected_table_info() {
-                        // Validate identifiers — pg_catalog names are safe, but
-                        // defense-in-depth prevents SQL injection if the invariant
-                        // is ever violated upstream.
-                        let valid = |s: &str| {
-                            !s.is_empty()
-                                && s.chars()
-                                    .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
-                        };
-                        if !valid(&schema) || !valid(&table) {
-                            self.set_status(
-                                "Invalid identifier in selection".to_string(),
-                                StatusLevel::Warning,
-                            );
-                            return Action::None;
-                        }
-                        // Query to get table DDL — identifiers are validated above
-                        let sql = format!(
-                            "SELECT \
-                                'CREATE TABLE ' || quote_ident(n.nspname) || '.' || quote_ident(c.relname) || ' (' || \
-                                string_agg( \
-                                    quote_ident(a.attname) || ' ' || pg_catalog.format_type(a.atttypid, a.atttypmod) || \
-                                    CASE WHEN a.attnotnull THEN ' NOT NULL' ELSE '' END || \
-                                    CASE WHEN ad.adbin IS NOT NULL THEN ' DEFAULT ' || pg_get_expr(ad.adbin, ad.adrelid) ELSE '' END, \
-                                    ', ' ORDER BY a.attnum \
-                                ) || ')' AS ddl \
-                            FROM pg_class c \
-                            JOIN pg_namespace n ON n.oid = c.relnamespace \
-                            JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped \
-                            LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum \
-                            WHERE n.nspname = '{}' AND c.relname = '{}' \
-                            GROUP BY n.nspname, c.relname",
-                            schema, table
-                        );
-                        let tab_id = self.tab().id;
-                        let timeout_ms = self.query_timeout_ms;
-                        let max_rows = self.max_result_rows;
-                        self.tab_mut().query_running = true;
-                        self.tab_mut().query_start = Some(std::time::Instant::now());
+                    if let Some(target) = self.tree_browser.selected_ddl_target() {
                         self.set_status(
-                            format!("Loading definition for {}.{}...", schema, table),
+                            format!("Generating DDL for {}...", target.display_name()),
                             StatusLevel::Info,
                         );
-                        return Action::ExecuteQuery {
-                            sql,
-                            tab_id,
-                            timeout_ms,
-                            max_rows,
-                        };
+                        return Action::ShowDdl { target };
                     } else {
                         self.set_status(
-                            "Select a table or view to show definition".to_string(),
+                            "Select a table, view, index, or function to show its DDL".to_string(),
                             StatusLevel::Warning,
                         );
                     }
@@ -541,6 +808,44 @@ impl App {
                 Action::None
             }
 
+            KeyAction::FindUsages => {
+                if self.focus == PanelFocus::TreeBrowser {
+                    let target = match self.tree_browser.selected_column_info() {
+                        Some((schema, table, column)) => Some((schema, table, Some(column))),
+                        None => self
+                            .tree_browser
+                            .selected_table_info()
+                            .map(|(schema, table)| (schema, table, None)),
+                    };
+                    let Some((schema, table, column)) = target else {
+                        self.set_status(
+                            "Select a table, view, or column to find usages".to_string(),
+                            StatusLevel::Warning,
+                        );
+                        return Action::None;
+                    };
+                    let sql = find_usages_sql(&schema, &table, column.as_deref());
+                    let label = match &column {
+                        Some(column) => format!("{}.{}.{}", schema, table, column),
+                        None => format!("{}.{}", schema, table),
+                    };
+                    let tab_id = self.tab().id;
+                    let timeout_ms = self.query_timeout_ms;
+                    let max_rows = self.max_result_rows;
+                    self.tab_mut().query_running = true;
+                    self.tab_mut().query_start = Some(std::time::Instant::now());
+                    self.set_status(format!("Finding usages of {}...", label), StatusLevel::Info);
+                    return Action::ExecuteQuery {
+                        sql,
+                        params: Vec::new(),
+                        tab_id,
+                        timeout_ms,
+                        max_rows,
+                    };
+                }
+                Action::None
+            }
+
             KeyAction::DeleteSavedQuery => {
                 if self.focus == PanelFocus::TreeBrowser {
                     if let Some(name) = self.tree_browser.selected_saved_query_name() {
@@ -573,6 +878,11 @@ impl App {
 
             // ── Pagination ────────────────────────────────────
             KeyAction::NextPage => {
+                // While a results search is active, `n` steps through matches
+                if self.tab().results_viewer.search().is_some() {
+                    self.report_search_jump(true);
+                    return Action::None;
+                }
                 if self.tab().query_running {
                     return Action::None;
                 }
@@ -583,6 +893,7 @@ impl App {
                         next.current_page += 1;
                         next.has_more = false; // will be set on results
                         let sql = next.paged_sql();
+                        let params = next.params.clone();
                         let tab_id = self.tab().id;
                         let timeout_ms = self.query_timeout_ms;
                         self.tab_mut().pagination = Some(next);
@@ -591,6 +902,7 @@ impl App {
                         self.set_status("Loading next page...".to_string(), StatusLevel::Info);
                         return Action::ExecuteQuery {
                             sql,
+                            params,
                             tab_id,
                             timeout_ms,
                             max_rows: 0,
@@ -612,6 +924,7 @@ impl App {
                         prev.current_page -= 1;
                         prev.has_more = true; // previous page always has a next
                         let sql = prev.paged_sql();
+                        let params = prev.params.clone();
                         let tab_id = self.tab().id;
                         let timeout_ms = self.query_timeout_ms;
                         self.tab_mut().pagination = Some(prev);
@@ -620,6 +933,7 @@ impl App {
                         self.set_status("Loading previous page...".to_string(), StatusLevel::Info);
                         return Action::ExecuteQuery {
                             sql,
+                            params,
                             tab_id,
                             timeout_ms,
                             max_rows: 0,
@@ -640,7 +954,23 @@ impl App {
                     }
                     PanelFocus::CommandBar => {
                         self.pending_export = None;
+                        self.pending_export_mode = ExportMode::Loaded;
                         self.pending_save_query = false;
+                        self.pending_row_filter = false;
+                        self.pending_cell_edit = false;
+                        if self.pending_params.take().is_some() {
+                            self.set_status("Query cancelled".to_string(), StatusLevel::Info);
+                        }
+                        match self.pending_search.take() {
+                            Some(SearchPrompt::Results) => {
+                                self.tab_mut().results_viewer.clear_search();
+                            }
+                            Some(SearchPrompt::Column(origin)) => {
+                                self.tab_mut().results_viewer.select_column(origin);
+                            }
+                            Some(_) => self.tab_mut().editor.clear_search(),
+                            None => {}
+                        }
                         self.command_bar.deactivate();
                         self.focus = self.previous_focus;
                     }
@@ -648,26 +978,97 @@ impl App {
                         self.help.hide();
                         self.focus = self.previous_focus;
                     }
+                    PanelFocus::Messages => {
+                        self.messages.hide();
+                        self.focus = self.previous_focus;
+                    }
+                    PanelFocus::Jobs => {
+                        self.jobs.hide();
+                        self.focus = self.previous_focus;
+                    }
+                    PanelFocus::Histogram => {
+                        self.histogram.hide();
+                        self.focus = self.previous_focus;
+                    }
+                    PanelFocus::TablePreview => {
+                        self.table_preview.hide();
+                        self.focus = self.previous_focus;
+                    }
+                    PanelFocus::Notifications => {
+                        self.notifications.hide();
+                        self.focus = self.previous_focus;
+                    }
+                    PanelFocus::Sessions => {
+                        self.sessions.hide();
+                        self.focus = self.previous_focus;
+                    }
+                    PanelFocus::Dashboard => {
+                        self.dashboard.hide();
+                        self.focus = self.previous_focus;
+                    }
+                    PanelFocus::Replication => {
+                        self.replication.hide();
+                        self.focus = self.previous_focus;
+                    }
+                    PanelFocus::ServerSummary => {
+                        self.server_summary.hide();
+                        self.focus = self.previous_focus;
+                    }
                     _ => {}
                 }
                 Action::None
             }
+            KeyAction::CompletePath => {
+                self.command_bar.complete_path();
+                Action::None
+            }
             KeyAction::Submit => {
                 if self.focus == PanelFocus::CommandBar {
                     let input = self.command_bar.input_text().to_string();
                     let is_prompt = self.command_bar.is_prompt_mode();
                     let format = self.pending_export.take();
+                    let export_mode = std::mem::take(&mut self.pending_export_mode);
                     let save_query = std::mem::take(&mut self.pending_save_query);
+                    let search = self.pending_search.take();
+                    let row_filter = std::mem::take(&mut self.pending_row_filter);
+                    let cell_edit = std::mem::take(&mut self.pending_cell_edit);
+                    let params = self.pending_params.take();
                     self.command_bar.deactivate();
                     self.focus = self.previous_focus;
 
+                    // Empty filter input clears the filter
+                    if row_filter {
+                        self.finish_row_filter(&input);
+                        return Action::None;
+                    }
+
+                    // An empty cell value is a valid (empty string) edit
+                    if cell_edit {
+                        return self.finish_cell_edit(&input);
+                    }
+
+                    // So is an empty placeholder value
+                    if let Some(prompt) = params {
+                        return self.finish_param_prompt(prompt, &input);
+                    }
+
+                    // Search prompts accept empty input (e.g. replace with nothing)
+                    if let Some(prompt) = search {
+                        self.finish_search_prompt(prompt, &input);
+                        return Action::None;
+                    }
+
                     if input.is_empty() {
                         return Action::None;
                     }
 
                     if is_prompt {
                         if let Some(fmt) = format {
-                            self.execute_export(fmt, &input);
+                            match export_mode {
+                                ExportMode::All => return self.execute_export_all(fmt, &input),
+                                ExportMode::Copy => return self.execute_export_copy(fmt, &input),
+                                ExportMode::Loaded => self.execute_export(fmt, &input),
+                            }
                         } else if save_query {
                             self.finish_save_query(&input);
                         }
@@ -688,3 +1089,17 @@ impl App {
         }
     }
 }
+
+/// Format SQL the way Ctrl+Alt+F does: two-space indent, uppercase keywords
+fn format_sql(sql: &str) -> String {
+    sqlformat::format(
+        sql,
+        &sqlformat::QueryParams::None,
+        &sqlformat::FormatOptions {
+            indent: sqlformat::Indent::Spaces(2),
+            uppercase: Some(true),
+            lines_between_queries: 1,
+            ..Default::default()
+        },
+    )
+}
@@ -5,8 +5,9 @@
 
 use crossterm::event::KeyEvent;
 
-use super::sql_utils::byte_offset_to_position;
+use super::sql_utils::{byte_offset_to_position, drop_impact_sql};
 use super::*;
+use crate::db::sqlstate;
 
 impl App {
     /// Handle an application event and return resulting action
@@ -15,8 +16,10 @@ impl App {
             AppEvent::Key(key) => Ok(self.handle_key(key)),
             AppEvent::Paste(data) => {
                 if self.focus == PanelFocus::QueryEditor {
-                    self.tab_mut().editor.insert_text(&data);
-                    self.update_completions();
+                    self.tab_mut().editor.replace_selection(&data);
+                    return Ok(self.update_completions());
+                } else if self.focus == PanelFocus::TreeBrowser {
+                    self.offer_paste_insert(&data);
                 }
                 Ok(Action::None)
             }
@@ -34,13 +37,55 @@ impl App {
                 mut results,
                 tab_id,
             } => {
+                self.count(Counter::Queries, 1);
                 let time = results.execution_time;
 
-                if let Some(idx) = self.tab_index_by_id(tab_id) {
+                if let Some(mut idx) = self.tab_index_by_id(tab_id) {
                     self.tabs[idx].query_running = false;
                     self.tabs[idx].query_start = None;
                     self.tabs[idx].rows_streaming = None;
 
+                    // A snapshot's BEGIN went through: its first query follows
+                    if let Some(sql) = self.tabs[idx]
+                        .snapshot
+                        .as_mut()
+                        .and_then(|s| s.pending.take())
+                    {
+                        return Ok(self.run_snapshot_query(idx, sql));
+                    }
+
+                    // A pinned tab keeps its results; the new ones go next to it
+                    if self.tabs[idx].pinned && self.tabs[idx].results_viewer.results().is_some() {
+                        match self.open_pinned_rerun_tab(idx) {
+                            Some(new_idx) => idx = new_idx,
+                            None => {
+                                if let Some(seq) = self.tabs[idx].history_entry.take() {
+                                    self.history.record_result(seq, time, results.row_count);
+                                }
+                                let name = format!("pin{}", idx + 1);
+                                self.stashes.retain(|(n, _)| *n != name);
+                                self.stashes.push((name.clone(), results));
+                                self.set_status(
+                                    format!(
+                                        "Tab {} is pinned and {} tabs are open — /restore {} shows the new results",
+                                        idx + 1,
+                                        self.max_tabs,
+                                        name
+                                    ),
+                                    StatusLevel::Warning,
+                                );
+                                return Ok(Action::None);
+                            }
+                        }
+                    }
+
+                    // A new page of the same query replaces the shown page
+                    // rather than going into the tab's result history
+                    let paging = self.tabs[idx]
+                        .pagination
+                        .as_ref()
+                        .is_some_and(|pg| pg.previous_page.is_some());
+
                     // Process pagination: trim the +1 probe row and update state
                     let pagination_info = if let Some(ref mut pg) = self.tabs[idx].pagination {
                         pg.previous_page = None; // navigation succeeded, clear rollback
@@ -62,6 +107,10 @@ impl App {
                         None
                     };
 
+                    if let Some(seq) = self.tabs[idx].history_entry.take() {
+                        self.history.record_result(seq, time, results.row_count);
+                    }
+
                     // Route EXPLAIN JSON results to the visual tree viewer
                     if self.tabs[idx].explain_pending {
                         self.tabs[idx].explain_pending = false;
@@ -94,7 +143,12 @@ impl App {
                     }
 
                     self.tabs[idx].explain_viewer = None;
+                    if !paging {
+                        let limit = self.result_history;
+                        self.tabs[idx].archive_results(limit);
+                    }
                     self.tabs[idx].results_viewer.set_results(results);
+                    self.mask_results(idx);
                     self.tabs[idx]
                         .results_viewer
                         .set_pagination(pagination_info.clone());
@@ -140,6 +194,15 @@ impl App {
                                 },
                             );
                         }
+                    } else if let Some(tag) = self.tabs[idx]
+                        .results_viewer
+                        .results()
+                        .and_then(|r| r.command.as_ref())
+                    {
+                        self.set_status(
+                            format!("{} in {:.1}ms", tag, time.as_secs_f64() * 1000.0),
+                            StatusLevel::Success,
+                        );
                     } else {
                         let count = self.tabs[idx]
                             .results_viewer
@@ -165,28 +228,150 @@ impl App {
                             );
                         }
                     }
+                    Ok(self.schema_change_action(idx))
                 } else {
                     // Tab was closed while query was running
+                    let summary = match results.command {
+                        Some(ref tag) => tag.to_string(),
+                        None => format!("{} rows", results.row_count),
+                    };
+                    self.set_status(
+                        format!("{} in {:.1}ms", summary, time.as_secs_f64() * 1000.0),
+                        StatusLevel::Success,
+                    );
+                    Ok(Action::None)
+                }
+            }
+            AppEvent::JobCompleted { job_id, results } => {
+                self.count(Counter::Queries, 1);
+                self.deliver_job_results(job_id, results);
+                Ok(Action::None)
+            }
+            AppEvent::ExportCompleted {
+                job_id,
+                path,
+                rows,
+                bytes,
+            } => {
+                self.count(Counter::Queries, 1);
+                if let Some(job) = self.jobs.finish(job_id) {
+                    self.count(Counter::BytesExported, bytes);
                     self.set_status(
                         format!(
-                            "{} rows in {:.1}ms",
-                            results.row_count,
-                            time.as_secs_f64() * 1000.0
+                            "Exported all {} rows to {} ({} bytes) in {}",
+                            rows,
+                            path,
+                            bytes,
+                            format_elapsed(job.started.elapsed())
                         ),
                         StatusLevel::Success,
                     );
                 }
                 Ok(Action::None)
             }
+            AppEvent::ExportProgress {
+                job_id,
+                rows,
+                bytes,
+            } => {
+                if self.jobs.jobs().iter().any(|job| job.id == job_id) {
+                    self.set_status(
+                        format!(
+                            "Job #{}: {} rows ({} bytes) written so far",
+                            job_id, rows, bytes
+                        ),
+                        StatusLevel::Info,
+                    );
+                }
+                Ok(Action::None)
+            }
+            AppEvent::ImportProgress { job_id, rows } => {
+                if self.jobs.jobs().iter().any(|job| job.id == job_id) {
+                    self.set_status(
+                        format!("Job #{}: {} rows imported so far", job_id, rows),
+                        StatusLevel::Info,
+                    );
+                }
+                Ok(Action::None)
+            }
+            AppEvent::ImportCompleted {
+                job_id,
+                table,
+                rows,
+            } => {
+                self.count(Counter::Queries, 1);
+                if let Some(job) = self.jobs.finish(job_id) {
+                    self.set_status(
+                        format!(
+                            "Imported {} rows into {} in {}",
+                            rows,
+                            table,
+                            format_elapsed(job.started.elapsed())
+                        ),
+                        StatusLevel::Success,
+                    );
+                }
+                Ok(Action::None)
+            }
+            AppEvent::PoolerDetected => {
+                self.set_status(
+                    "Prepared statements don't survive this connection's pooler (pgbouncer in \
+                     transaction mode?) — switched to the simple query protocol; values come \
+                     back as text and placeholders aren't supported"
+                        .to_string(),
+                    StatusLevel::Warning,
+                );
+                Ok(Action::UseSimpleProtocol)
+            }
+            AppEvent::JobFailed { job_id, error } => {
+                self.count(Counter::Queries, 1);
+                self.count(Counter::QueryErrors, 1);
+                if let Some(job) = self.jobs.finish(job_id) {
+                    if let Some(seq) = job.history_entry {
+                        self.history
+                            .record_failure(seq, Some(job.started.elapsed()));
+                    }
+                    if error.contains("canceling statement due to user request") {
+                        self.set_status(format!("Job #{} cancelled", job_id), StatusLevel::Warning);
+                    } else {
+                        self.set_status(
+                            format!("Job #{} failed: {}", job_id, error),
+                            StatusLevel::Error,
+                        );
+                    }
+                }
+                Ok(Action::None)
+            }
             AppEvent::QueryFailed {
                 error,
                 position,
+                code,
                 tab_id,
             } => {
                 let cancelled = error.contains("canceling statement due to user request");
+                let timeout = if code.as_deref() == Some("57014")
+                    && error.contains("statement timeout")
+                {
+                    Some(format!(
+                        "Query timed out: statement_timeout is {} \u{2014} /timeout to change it",
+                        format_timeout(self.statement_timeout_ms)
+                    ))
+                } else if error.starts_with("Query timed out after") {
+                    Some(format!("{} (query_timeout_ms)", error))
+                } else {
+                    None
+                };
+                self.count(Counter::Queries, 1);
+                if !cancelled {
+                    self.count(Counter::QueryErrors, 1);
+                }
 
                 if let Some(idx) = self.tab_index_by_id(tab_id) {
                     self.tabs[idx].rows_streaming = None;
+                    if let Some(seq) = self.tabs[idx].history_entry.take() {
+                        let elapsed = self.tabs[idx].query_start.map(|t| t.elapsed());
+                        self.history.record_failure(seq, elapsed);
+                    }
                     // Transition to Failed if this tab is inside a transaction
                     if self.tabs[idx].transaction_state == TransactionState::InTransaction
                         && !cancelled
@@ -195,16 +380,30 @@ impl App {
                     }
 
                     // Roll back pagination page if a page-navigation query failed
-                    if let Some(ref mut pg) = self.tabs[idx].pagination
+                    let paging = if let Some(ref mut pg) = self.tabs[idx].pagination
                         && let Some(prev) = pg.previous_page.take()
                     {
                         pg.current_page = prev;
                         pg.has_more = true;
-                    }
+                        true
+                    } else {
+                        false
+                    };
 
                     self.tabs[idx].query_running = false;
                     self.tabs[idx].query_start = None;
-                    self.tabs[idx].results_viewer.set_error(error);
+                    if !paging {
+                        let limit = self.result_history;
+                        self.tabs[idx].archive_results(limit);
+                    }
+                    let hint = code
+                        .as_deref()
+                        .and_then(|c| sqlstate::hint(c, &error, self.tree_browser.schema()));
+                    self.tabs[idx].results_viewer.set_error(error, hint);
+                    // Outside a transaction the failed DDL changed nothing
+                    if self.tabs[idx].transaction_state == TransactionState::Idle {
+                        self.tabs[idx].schema_changed = false;
+                    }
 
                     // Jump cursor to error position if available
                     if let Some(pos) = position {
@@ -217,28 +416,46 @@ impl App {
                         self.focus = PanelFocus::ResultsViewer;
                     }
                 }
-                self.set_status(
-                    if cancelled {
-                        "Query cancelled".to_string()
-                    } else {
-                        "Query failed".to_string()
-                    },
-                    if cancelled {
-                        StatusLevel::Warning
-                    } else {
-                        StatusLevel::Error
-                    },
-                );
+                match (timeout, cancelled) {
+                    (Some(message), _) => self.set_status(message, StatusLevel::Error),
+                    (None, true) => {
+                        self.set_status("Query cancelled".to_string(), StatusLevel::Warning)
+                    }
+                    (None, false) => {
+                        self.set_status("Query failed".to_string(), StatusLevel::Error)
+                    }
+                }
                 Ok(Action::None)
             }
             AppEvent::SchemaLoaded(schema) => {
-                self.tree_browser.set_schema(schema);
+                if self.tree_browser.is_stale() {
+                    self.tree_browser.refresh_schema(schema);
+                } else {
+                    self.tree_browser.set_schema(schema);
+                }
                 self.set_status("Schema refreshed".to_string(), StatusLevel::Info);
+                Ok(Action::SaveSchemaCache)
+            }
+            AppEvent::SchemaObjectsLoaded(schema) => {
+                self.tree_browser.apply_loaded_schema(schema);
+                Ok(Action::SaveSchemaCache)
+            }
+            AppEvent::SchemaObjectsFailed { schema_name, error } => {
+                self.tree_browser.schema_load_failed(&schema_name);
+                self.set_status(
+                    format!("Loading schema {} failed: {}", schema_name, error),
+                    StatusLevel::Error,
+                );
                 Ok(Action::None)
             }
             AppEvent::SchemaFailed(err) => {
+                let cached = if self.tree_browser.is_stale() {
+                    " (showing cached schema)"
+                } else {
+                    ""
+                };
                 self.set_status(
-                    format!("Schema refresh failed: {}", err),
+                    format!("Schema refresh failed: {}{}", err, cached),
                     StatusLevel::Error,
                 );
                 Ok(Action::None)
@@ -317,10 +534,213 @@ impl App {
                 self.set_status(format!("Load more failed: {}", err), StatusLevel::Error);
                 Ok(Action::None)
             }
+            AppEvent::DdlLoaded { name, ddl } => {
+                if !self.new_tab() {
+                    self.set_status(
+                        format!("Maximum {} tabs open", self.max_tabs),
+                        StatusLevel::Warning,
+                    );
+                    return Ok(Action::None);
+                }
+                self.tab_mut().editor.set_content(ddl);
+                self.set_status(
+                    format!("DDL for {} opened in a new tab", name),
+                    StatusLevel::Info,
+                );
+                Ok(Action::None)
+            }
+            AppEvent::EstimateCompleted(results) => {
+                self.show_estimate(&results);
+                Ok(Action::None)
+            }
+            AppEvent::EstimateFailed(err) => {
+                self.set_status(format!("Estimate failed: {}", err), StatusLevel::Error);
+                Ok(Action::None)
+            }
+            AppEvent::CostChecked { results, tab_id } => {
+                Ok(self.finish_cost_check(tab_id, Ok(results)))
+            }
+            AppEvent::CostCheckFailed { error, tab_id } => {
+                Ok(self.finish_cost_check(tab_id, Err(error)))
+            }
+            AppEvent::DdlFailed(err) => {
+                self.set_status(format!("DDL failed: {}", err), StatusLevel::Error);
+                Ok(Action::None)
+            }
+            AppEvent::CellUpdated { row, tab_id } => {
+                if let Some(idx) = self.tab_index_by_id(tab_id) {
+                    let tab = &mut self.tabs[idx];
+                    tab.query_running = false;
+                    tab.query_start = None;
+                    if tab.results_viewer.finish_edit(row) {
+                        self.set_status("Updated 1 row".to_string(), StatusLevel::Success);
+                    } else {
+                        self.set_status(
+                            "No row updated — it may have been changed or deleted".to_string(),
+                            StatusLevel::Warning,
+                        );
+                    }
+                }
+                Ok(Action::None)
+            }
+            AppEvent::CellUpdateFailed { error, tab_id } => {
+                if let Some(idx) = self.tab_index_by_id(tab_id) {
+                    let tab = &mut self.tabs[idx];
+                    tab.query_running = false;
+                    tab.query_start = None;
+                    tab.results_viewer.cancel_edit();
+                    if tab.transaction_state == TransactionState::InTransaction {
+                        tab.transaction_state = TransactionState::Failed;
+                    }
+                }
+                self.set_status(format!("Update failed: {}", error), StatusLevel::Error);
+                Ok(Action::None)
+            }
+            AppEvent::TransactionStatus { state, tab_id } => {
+                if let Some(idx) = self.tab_index_by_id(tab_id) {
+                    self.tabs[idx].transaction_state = state;
+                    // Ending the transaction lets go of its snapshot
+                    if state == TransactionState::Idle {
+                        self.tabs[idx].snapshot = None;
+                    }
+                }
+                Ok(Action::None)
+            }
+            AppEvent::Notification(notification) => {
+                if !self.notifications.is_visible() {
+                    let payload = notification.payload.replace(['\n', '\r'], " ");
+                    self.set_status(
+                        format!("NOTIFY {}: {}", notification.channel, payload),
+                        StatusLevel::Info,
+                    );
+                }
+                self.notifications.push(notification);
+                Ok(Action::None)
+            }
+            AppEvent::ListenerLost(message) => {
+                self.notifications.set_channels(Vec::new());
+                self.set_status(
+                    format!("{} \u{2014} /listen again to resume", message),
+                    StatusLevel::Warning,
+                );
+                Ok(Action::Unlisten { channel: None })
+            }
+            AppEvent::SessionsLoaded(sessions) => {
+                self.sessions.set_sessions(sessions);
+                Ok(Action::None)
+            }
+            AppEvent::SessionsFailed(error) => {
+                self.set_status(error, StatusLevel::Error);
+                Ok(Action::None)
+            }
+            AppEvent::ServerSummaryLoaded(summary) => {
+                self.server_summary.show(summary);
+                // Only over the main panels, not a dialog or another overlay
+                if matches!(
+                    self.focus,
+                    PanelFocus::QueryEditor | PanelFocus::ResultsViewer | PanelFocus::TreeBrowser
+                ) {
+                    self.previous_focus = self.focus;
+                    self.focus = PanelFocus::ServerSummary;
+                } else {
+                    self.server_summary.hide();
+                }
+                Ok(Action::None)
+            }
+            AppEvent::ColumnValuesLoaded {
+                schema,
+                table,
+                column,
+                values,
+            } => {
+                self.column_values
+                    .insert((schema, table, column), values.unwrap_or_default());
+                // Show them if the cursor is still in the literal
+                if self.focus == PanelFocus::QueryEditor {
+                    return Ok(self.update_completions());
+                }
+                Ok(Action::None)
+            }
+            AppEvent::InsertColumnsLoaded {
+                schema,
+                table,
+                columns,
+            } => {
+                // Only over the main panels, not a dialog or another overlay
+                if matches!(
+                    self.focus,
+                    PanelFocus::QueryEditor | PanelFocus::ResultsViewer | PanelFocus::TreeBrowser
+                ) {
+                    self.insert_form.show(schema, table, columns);
+                    self.previous_focus = self.focus;
+                    self.focus = PanelFocus::InsertForm;
+                    self.status_message = None;
+                }
+                Ok(Action::None)
+            }
+            AppEvent::InsertColumnsFailed(error) => {
+                self.set_status(
+                    format!("Couldn't load the table's columns: {}", error),
+                    StatusLevel::Error,
+                );
+                Ok(Action::None)
+            }
+            AppEvent::HealthLoaded(health) => {
+                self.dashboard.set_health(health, std::time::Instant::now());
+                Ok(Action::None)
+            }
+            AppEvent::HealthFailed(error) => {
+                self.dashboard.set_error(error, std::time::Instant::now());
+                Ok(Action::None)
+            }
+            AppEvent::ReplicationLoaded(replication) => {
+                self.replication.set_replication(replication);
+                Ok(Action::None)
+            }
+            AppEvent::ReplicationFailed(error) => {
+                self.replication.set_error(error);
+                Ok(Action::None)
+            }
+            AppEvent::BackendSignalled {
+                pid,
+                terminate,
+                sent,
+            } => {
+                let message = match (sent, terminate) {
+                    (false, _) => format!("Session {} had already ended", pid),
+                    (true, false) => format!("Cancel sent to session {}", pid),
+                    (true, true) => format!("Session {} terminated", pid),
+                };
+                let level = if sent {
+                    StatusLevel::Success
+                } else {
+                    StatusLevel::Info
+                };
+                self.set_status(message, level);
+                // Show what changed
+                Ok(self.load_sessions())
+            }
+            AppEvent::TailRows { tab_id, results } => {
+                self.tail_rows(tab_id, results);
+                Ok(Action::None)
+            }
+            AppEvent::TailFailed { tab_id, error } => {
+                if let Some(idx) = self.tab_index_by_id(tab_id)
+                    && let Some(tail) = self.tabs[idx].tail.take()
+                {
+                    self.set_status(
+                        format!("Stopped following {}: {}", tail.relation, error),
+                        StatusLevel::Error,
+                    );
+                }
+                Ok(Action::None)
+            }
             AppEvent::ConnectionLost { tab_id, .. } => {
+                self.count(Counter::ConnectionDrops, 1);
                 // Reset only the affected tab's transaction state
                 if let Some(idx) = self.tab_index_by_id(tab_id) {
                     self.tabs[idx].transaction_state = TransactionState::Idle;
+                    self.tabs[idx].schema_changed = false;
                     self.tabs[idx].rows_streaming = None;
                 }
                 self.set_status(
@@ -340,15 +760,39 @@ impl App {
             return self.handle_confirm_key(key, pending);
         }
 
+        if let Some(sql) = self.pending_paste_insert.take() {
+            return self.handle_paste_insert_key(key, sql);
+        }
+
+        // y accepts an offered schema refresh; any other key dismisses the
+        // offer and goes on to do what it normally does
+        if std::mem::take(&mut self.pending_schema_refresh)
+            && matches!(
+                key.code,
+                crossterm::event::KeyCode::Char('y') | crossterm::event::KeyCode::Char('Y')
+            )
+            && key
+                .modifiers
+                .difference(crossterm::event::KeyModifiers::SHIFT)
+                .is_empty()
+        {
+            self.set_status("Refreshing schema...".to_string(), StatusLevel::Info);
+            return Action::LoadSchema;
+        }
+
         // Connection dialog intercepts all keys when visible
         if self.focus == PanelFocus::ConnectionDialog {
             return match self.connection_dialog.handle_key(key) {
                 DialogAction::Connect(config) => {
                     self.connection_dialog.hide();
                     self.focus = self.previous_focus;
-                    Action::Connect(config)
+                    match self.connecting_tab.take() {
+                        Some(tab_id) => Action::ConnectTab { tab_id, config },
+                        None => Action::Connect(config),
+                    }
                 }
                 DialogAction::Dismissed => {
+                    self.connecting_tab = None;
                     self.connection_dialog.hide();
                     self.focus = self.previous_focus;
                     Action::None
@@ -357,11 +801,78 @@ impl App {
             };
         }
 
+        // Insert-row form intercepts all keys when visible
+        if self.focus == PanelFocus::InsertForm {
+            match self.insert_form.handle_key(key) {
+                InsertFormAction::Submit(_) if self.tab().query_running => {
+                    self.insert_form
+                        .set_error("Wait for the running query to finish".to_string());
+                }
+                InsertFormAction::Submit(sql) => self.confirm_insert(sql),
+                InsertFormAction::Dismissed => {
+                    self.insert_form.hide();
+                    self.focus = self.previous_focus;
+                }
+                InsertFormAction::Consumed => {}
+            }
+            return Action::None;
+        }
+
+        // History browser intercepts all keys when visible
+        if self.focus == PanelFocus::HistoryBrowser {
+            match self.history_browser.handle_key(key) {
+                HistoryBrowserAction::Select(sql) => {
+                    self.history_browser.hide();
+                    self.focus = PanelFocus::QueryEditor;
+                    let tab = self.tab_mut();
+                    tab.history_cursor.reset();
+                    tab.editor.set_content(sql);
+                    self.clear_completions();
+                }
+                HistoryBrowserAction::RunInNewTab(sql) => {
+                    self.history_browser.hide();
+                    self.focus = PanelFocus::QueryEditor;
+                    return self.run_in_new_tab(&sql);
+                }
+                HistoryBrowserAction::Dismissed => {
+                    self.history_browser.hide();
+                    self.focus = self.previous_focus;
+                }
+                HistoryBrowserAction::Consumed => {}
+            }
+            return Action::None;
+        }
+
         // Tree filter mode intercepts keys when active
         if self.focus == PanelFocus::TreeBrowser && self.tree_browser.is_filter_active() {
             return self.handle_tree_filter_key(key);
         }
 
+        // Vim's normal and visual mode take plain keys, and Esc leaves insert
+        // mode, before any binding can
+        if self.focus == PanelFocus::QueryEditor && self.tab().editor.vim_claims(key) {
+            self.tab_mut().editor.handle_key(key);
+            return self.update_completions();
+        }
+
+        // With text selected, the editor's own bindings come before global
+        // ones, so Ctrl+C copies it instead of cancelling
+        if self.focus == PanelFocus::QueryEditor
+            && self.tab().editor.has_selection()
+            && let Some(key_action) = self.keymap.resolve_panel(self.focus, key)
+        {
+            return self.execute_key_action(key_action);
+        }
+
+        // Path completion comes before global bindings in the command bar,
+        // which get the key when there's no path to complete
+        if self.focus == PanelFocus::CommandBar
+            && self.keymap.resolve_panel(self.focus, key) == Some(KeyAction::CompletePath)
+            && self.command_bar.complete_path()
+        {
+            return Action::None;
+        }
+
         // Try KeyMap first — global bindings, then panel-specific
         if let Some(key_action) = self.keymap.resolve(self.focus, key) {
             // Suppress certain global actions in modal panels to avoid
@@ -379,6 +890,15 @@ impl App {
                     if self.focus == PanelFocus::CommandBar
                         || self.focus == PanelFocus::Inspector
                         || self.focus == PanelFocus::Help
+                        || self.focus == PanelFocus::Messages
+                        || self.focus == PanelFocus::Jobs
+                        || self.focus == PanelFocus::Histogram
+                        || self.focus == PanelFocus::TablePreview
+                        || self.focus == PanelFocus::Notifications
+                        || self.focus == PanelFocus::Sessions
+                        || self.focus == PanelFocus::Dashboard
+                        || self.focus == PanelFocus::Replication
+                        || self.focus == PanelFocus::ServerSummary
                         || self.focus == PanelFocus::ConnectionDialog =>
                 {
                     return Action::None;
@@ -395,11 +915,32 @@ impl App {
             PanelFocus::QueryEditor => {
                 let result = self.tab_mut().editor.handle_key(key);
                 if matches!(result, ComponentAction::Consumed) {
-                    self.update_completions();
+                    return self.update_completions();
+                }
+                result
+            }
+            PanelFocus::CommandBar => {
+                let result = self.command_bar.handle_key(key);
+                // Incremental search: highlight matches as the term is typed
+                match self.pending_search {
+                    Some(SearchPrompt::Find | SearchPrompt::ReplaceFind) => {
+                        let term = self.command_bar.input_text().to_string();
+                        self.tab_mut().editor.set_search(&term);
+                    }
+                    Some(SearchPrompt::Results) => {
+                        let term = self.command_bar.input_text().to_string();
+                        self.tab_mut().results_viewer.set_search(&term);
+                    }
+                    Some(SearchPrompt::Column(origin)) => {
+                        let term = self.command_bar.input_text().to_string();
+                        let viewer = &mut self.tab_mut().results_viewer;
+                        let col = viewer.find_column(&term).unwrap_or(origin);
+                        viewer.select_column(col);
+                    }
+                    _ => {}
                 }
                 result
             }
-            PanelFocus::CommandBar => self.command_bar.handle_key(key),
             _ => ComponentAction::Ignored,
         };
         self.process_component_action(component_action)
@@ -450,6 +991,14 @@ impl App {
                 self.tree_browser.move_down();
                 Action::None
             }
+            KeyCode::Tab => {
+                self.tree_browser.next_match();
+                Action::None
+            }
+            KeyCode::BackTab => {
+                self.tree_browser.prev_match();
+                Action::None
+            }
             KeyCode::Char(c) => {
                 self.tree_browser.filter_insert_char(c);
                 Action::None
@@ -458,10 +1007,76 @@ impl App {
         }
     }
 
+    /// Turn rows pasted onto a selected table into an INSERT, asking before
+    /// opening it. Pastes that aren't tabular, or land on anything other
+    /// than a table, are ignored as before.
+    fn offer_paste_insert(&mut self, data: &str) {
+        let Some((schema, table)) = self.tree_browser.selected_table() else {
+            return;
+        };
+        let Some(tsv) = crate::import::parse_tsv(data) else {
+            return;
+        };
+        match crate::import::insert_statement(schema, table, &tsv) {
+            Ok(insert) => {
+                let mut prompt = format!(
+                    "Generate INSERT of {} row{} into {}",
+                    insert.row_count,
+                    if insert.row_count == 1 { "" } else { "s" },
+                    crate::sql::quote_qualified(schema, &table.name)
+                );
+                if !insert.skipped.is_empty() {
+                    prompt.push_str(&format!(
+                        " (skipping unknown: {})",
+                        insert.skipped.join(", ")
+                    ));
+                }
+                prompt.push_str("? (y/N)");
+                self.pending_paste_insert = Some(insert.sql);
+                self.set_status(prompt, StatusLevel::Warning);
+            }
+            Err(e) => self.set_status(e, StatusLevel::Error),
+        }
+    }
+
+    /// Handle a y/n keypress for opening a pasted-rows INSERT in a new tab
+    fn handle_paste_insert_key(&mut self, key: KeyEvent, sql: String) -> Action {
+        use crossterm::event::KeyCode;
+        if !matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+            self.set_status("Paste cancelled".to_string(), StatusLevel::Warning);
+            return Action::None;
+        }
+        if !self.new_tab() {
+            self.set_status(
+                format!("Maximum {} tabs open", self.max_tabs),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        self.tab_mut().editor.set_content(sql);
+        self.set_status(
+            "INSERT opened in a new tab — review, then execute".to_string(),
+            StatusLevel::Info,
+        );
+        Action::None
+    }
+
     /// Handle a y/n keypress for destructive query confirmation
     fn handle_confirm_key(&mut self, key: KeyEvent, pending: PendingConfirm) -> Action {
         use crossterm::event::KeyCode;
+        // d previews what a DROP would take with it, without running it
+        if matches!(key.code, KeyCode::Char('d' | 'D'))
+            && let Some(impact) = drop_impact_sql(&pending.sql)
+        {
+            return self.show_drop_dependents(pending, impact);
+        }
         match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') if pending.over_cost => {
+                match self.tab_index_by_id(pending.tab_id) {
+                    Some(idx) => self.confirm_or_execute(idx, pending.sql, pending.params),
+                    None => Action::None,
+                }
+            }
             KeyCode::Char('y') | KeyCode::Char('Y') => {
                 self.set_status("Executing...".to_string(), StatusLevel::Info);
                 self.execute_confirmed_query(pending)
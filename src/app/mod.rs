@@ -6,36 +6,70 @@
 //! - `event_handler` — event dispatch (handle_event, handle_key)
 //! - `actions` — key action execution (execute_key_action)
 //! - `sql_utils` — SQL analysis helpers (transaction intent, meta-commands)
+//! - `tail` — following a table's new rows (`/tail`)
 
 mod actions;
 mod event_handler;
 mod sql_utils;
+mod stash;
+mod tail;
 
-use sql_utils::detect_transaction_intent;
+pub use tail::TailState;
+
+use sql_utils::{
+    ExplainOptions, PlanEstimate, SNAPSHOT_BEGIN, channel_name, contains_ddl,
+    detect_transaction_intent, drop_impact_sql, explain_estimate, follow_fk_sql, format_timeout,
+    is_destructive_query, is_plannable, is_read_write_begin, is_write_query, parse_timeout,
+    snapshot_sql, translate_meta_command,
+};
 
 use crate::commands::{Command, parse_command};
 use crate::completer::{self, Completer};
-use crate::config::ConnectionConfig;
 use crate::config::settings::Settings;
-use crate::db::QueryResults;
-use crate::db::schema::{Function, Index, SchemaTree, Table};
+use crate::config::{ConnectionConfig, ExportDirs, Snippets};
+pub use crate::db::TransactionState;
+use crate::db::ddl::DdlTarget;
+use crate::db::params::find_placeholders;
+use crate::db::provenance::CellUpdate;
+use crate::db::schema::{Function, Index, InsertColumn, Schema, SchemaTree, Table};
 use crate::db::sql_limit;
+use crate::db::transaction::TransactionControl;
+use crate::db::types::Row;
+use crate::db::{Health, Notification, QueryResults, Replication, ServerSummary, Session};
 use crate::error::Result;
-use crate::export::ExportFormat;
-use crate::history::QueryHistory;
+use crate::export::{ColumnCasts, CsvOptions, ExportFormat, RowStream};
+use crate::history::{HistoryCursor, QueryHistory};
+use crate::import::CsvImport;
 use crate::keymap::{KeyAction, KeyMap};
+use crate::mask::MaskRules;
+use crate::metrics::{Counter, Metrics};
 use crate::ui::Component;
 use crate::ui::ComponentAction;
 use crate::ui::command_bar::CommandBar;
 use crate::ui::connection_dialog::{ConnectionDialog, DialogAction};
+use crate::ui::dashboard::Dashboard;
 use crate::ui::editor::QueryEditor;
 use crate::ui::explain::ExplainViewer;
 use crate::ui::help::HelpOverlay;
+use crate::ui::histogram::ColumnHistogram;
+use crate::ui::history_browser::{HistoryBrowser, HistoryBrowserAction};
+use crate::ui::insert_form::{InsertForm, InsertFormAction};
 use crate::ui::inspector::Inspector;
+use crate::ui::jobs::{JobList, format_elapsed};
+use crate::ui::messages::MessageLog;
+use crate::ui::notifications::NotificationLog;
+use crate::ui::numbers::NumberFormat;
+use crate::ui::replication::ReplicationView;
 use crate::ui::results::ResultsViewer;
+use crate::ui::server_summary::ServerSummaryPopup;
+use crate::ui::sessions::SessionList;
+use crate::ui::table_preview::TablePreview;
 use crate::ui::theme::Theme;
 use crate::ui::tree::TreeBrowser;
+use crate::ui::vim::VimMode;
 use crossterm::event::KeyEvent;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Server-side pagination state for a query
 #[derive(Debug, Clone)]
@@ -52,6 +86,8 @@ pub struct PaginationState {
     pub user_has_limit: bool,
     /// Page before navigation (for rollback on query failure)
     previous_page: Option<usize>,
+    /// Bind values for the query's placeholders, reused for every page
+    pub params: Vec<Option<String>>,
 }
 
 impl PaginationState {
@@ -87,8 +123,12 @@ pub struct Tab {
     pub query_running: bool,
     /// When the current query started (for elapsed time display)
     pub query_start: Option<std::time::Instant>,
-    /// Client-side transaction state for this tab's connection
+    /// Transaction state for this tab's connection (updated optimistically
+    /// on execute, then confirmed by the connection when the query finishes)
     pub transaction_state: TransactionState,
+    /// The open transaction was started with `BEGIN READ WRITE`, which lets
+    /// writes through on a read-only connection
+    pub read_write: bool,
     /// Pagination state for the current result set
     pub pagination: Option<PaginationState>,
     /// Visual EXPLAIN tree viewer (replaces results panel when present)
@@ -97,6 +137,52 @@ pub struct Tab {
     explain_pending: bool,
     /// Row count received during streaming (for progress display)
     pub rows_streaming: Option<usize>,
+    /// Where this tab is while browsing query history (Ctrl+Up/Down)
+    history_cursor: HistoryCursor,
+    /// History entry of the running query, which gets its timing and row
+    /// count when the results arrive
+    history_entry: Option<usize>,
+    /// DDL ran on this tab and the tree hasn't caught up (inside a
+    /// transaction this waits for COMMIT)
+    schema_changed: bool,
+    /// Keep these results when the query re-runs: new results open in a
+    /// tab next to this one
+    pub pinned: bool,
+    /// Earlier results, oldest first, for stepping back with `[`
+    older_results: Vec<QueryResults>,
+    /// Results stepped back from, nearest last, for `]`
+    newer_results: Vec<QueryResults>,
+    /// The connection this tab uses instead of the main one (`/tabconnect`)
+    pub connection: Option<TabConnection>,
+    /// The table this tab is following (`/tail`)
+    pub tail: Option<TailState>,
+    /// The frozen view this tab's transaction holds (`/snapshot`)
+    pub snapshot: Option<Snapshot>,
+    /// Query and parameters waiting on the cost guard's EXPLAIN
+    cost_check: Option<(String, Vec<Option<String>>)>,
+    /// The SQL file loaded into the editor (`/open`)
+    pub file: Option<PathBuf>,
+}
+
+/// A connection a tab is bound to instead of the main one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabConnection {
+    /// Connection profile name
+    pub name: String,
+    /// Writes are blocked on this tab
+    pub read_only: bool,
+}
+
+/// A tab's repeatable-read, read-only transaction: its queries see the data
+/// as it was when the snapshot was taken, while other tabs see it change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Table the snapshot was taken for (`schema.table`)
+    pub relation: String,
+    /// When the snapshot's first query ran
+    pub taken_at: chrono::DateTime<chrono::Local>,
+    /// The first query, run once BEGIN has opened the transaction
+    pending: Option<String>,
 }
 
 impl Tab {
@@ -109,11 +195,35 @@ impl Tab {
             query_running: false,
             query_start: None,
             transaction_state: TransactionState::Idle,
+            read_write: false,
             pagination: None,
             explain_viewer: None,
             explain_pending: false,
             rows_streaming: None,
+            history_cursor: HistoryCursor::new(),
+            history_entry: None,
+            schema_changed: false,
+            pinned: false,
+            older_results: Vec::new(),
+            newer_results: Vec::new(),
+            connection: None,
+            tail: None,
+            snapshot: None,
+            cost_check: None,
+            file: None,
+        }
+    }
+
+    /// Keep the shown results, and any stepped back from, before a new
+    /// query's results replace them, dropping the oldest beyond `limit`
+    fn archive_results(&mut self, limit: usize) {
+        let current = self.results_viewer.take_results();
+        self.older_results.extend(current);
+        while let Some(results) = self.newer_results.pop() {
+            self.older_results.push(results);
         }
+        let excess = self.older_results.len().saturating_sub(limit);
+        self.older_results.drain(..excess);
     }
 }
 
@@ -135,7 +245,32 @@ pub struct App {
     pub command_bar: CommandBar,
     pub inspector: Inspector,
     pub help: HelpOverlay,
+    /// Log of past status messages (`/messages` overlay)
+    pub messages: MessageLog,
     pub connection_dialog: ConnectionDialog,
+    /// Tab the connection dialog is picking a connection for
+    /// (`/tabconnect`); `None` when it picks the main connection
+    connecting_tab: Option<usize>,
+    /// Searchable query history (Ctrl+R overlay)
+    pub history_browser: HistoryBrowser,
+    /// Queries running in the background (`/jobs` overlay)
+    pub jobs: JobList,
+    /// Value frequencies of a result column
+    pub histogram: ColumnHistogram,
+    /// Columns of the table named under the editor cursor
+    pub table_preview: TablePreview,
+    /// NOTIFY payloads on the channels given to `/listen`
+    pub notifications: NotificationLog,
+    /// The server's other sessions (`/sessions`)
+    pub sessions: SessionList,
+    /// Server health from the `pg_stat_*` views (`/dashboard`)
+    pub dashboard: Dashboard,
+    /// Publications and subscriptions (`/replication`)
+    pub replication: ReplicationView,
+    /// First-glance checks shown after connecting
+    pub server_summary: ServerSummaryPopup,
+    /// Form for inserting a row into a table
+    pub insert_form: InsertForm,
 
     /// Query tabs (each has its own editor + results + completer)
     pub tabs: Vec<Tab>,
@@ -149,6 +284,36 @@ pub struct App {
 
     /// Pending save-query prompt (waiting for user to type a name)
     pending_save_query: bool,
+    /// Which export the pending export prompt is for
+    pending_export_mode: ExportMode,
+
+    /// Pending editor find/replace prompt
+    pending_search: Option<SearchPrompt>,
+
+    /// Pending results row-filter prompt
+    pending_row_filter: bool,
+
+    /// Pending cell-edit prompt (waiting for the new value)
+    pending_cell_edit: bool,
+
+    /// Pending placeholder prompts for the query about to run
+    pending_params: Option<ParamPrompt>,
+
+    /// Last value entered per placeholder, offered again next time
+    param_values: HashMap<String, String>,
+
+    /// Result sets kept with /stash, oldest first
+    stashes: Vec<(String, QueryResults)>,
+
+    /// SQL snippets for /snippet, persisted in the config directory
+    snippets: Snippets,
+
+    /// Template for the file name the export prompt suggests
+    export_filename: String,
+
+    /// Directory of the last export per connection, where the export
+    /// prompt starts
+    export_dirs: ExportDirs,
 
     /// Query history for Ctrl+Up/Down navigation
     history: QueryHistory,
@@ -156,6 +321,21 @@ pub struct App {
     /// Maximum number of tabs allowed
     max_tabs: usize,
 
+    /// Earlier results each tab keeps for `[` and `]`
+    result_history: usize,
+
+    /// Editor column shaded as a line length guide (0 = off)
+    pub editor_ruler: usize,
+
+    /// Editors use vim-style modal editing (`editor_mode = "vim"`)
+    vim_editing: bool,
+
+    /// Editors indent new lines to match (`auto_indent`)
+    auto_indent: bool,
+
+    /// Separators for counts, sizes, and result decimals (`number_locale`)
+    pub number_format: NumberFormat,
+
     /// Data-driven keybinding configuration
     pub keymap: KeyMap,
 
@@ -169,12 +349,19 @@ pub struct App {
     max_result_rows: usize,
 
     /// Server-side statement timeout in milliseconds (0 = disabled)
-    /// Applied at connection time via the connection string
+    /// Applied at connection time via the connection string, and to open
+    /// connections by `/timeout`
     pub statement_timeout_ms: u64,
 
     /// Whether to prompt before executing destructive queries (DROP, TRUNCATE, etc.)
     confirm_destructive: bool,
 
+    /// Planner thresholds checked with EXPLAIN before a query runs
+    cost_guard: CostGuard,
+
+    /// Set while `/force` runs the editor's query past the cost guard
+    cost_guard_bypass: bool,
+
     /// Read-only mode — blocks write queries at client level
     pub read_only: bool,
 
@@ -184,9 +371,51 @@ pub struct App {
     /// Whether to show EXPLAIN as visual tree (true) or raw text (false)
     explain_visual: bool,
 
+    /// Options the EXPLAIN key runs with (settings, or `/explain`)
+    explain_options: ExplainOptions,
+
+    /// Whether a new tab starts with the draft of the tab it was opened from
+    new_tab_copies_draft: bool,
+
+    /// Whether the editor is formatted before each run (settings `format_on_execute`)
+    format_on_execute: bool,
+
+    /// Whether the server summary is fetched after connecting (settings
+    /// `server_summary_on_connect`)
+    pub server_summary_on_connect: bool,
+
+    /// Whether relations the role can't SELECT from are left out of
+    /// completion and previews (settings `skip_unreadable_tables`)
+    skip_unreadable_tables: bool,
+
+    /// Distinct values of columns for completing `column = '`, by
+    /// (schema, table, column). Empty while loading, or when the column has
+    /// too many values to be worth offering.
+    column_values: HashMap<(String, String, String), Vec<String>>,
+
+    /// What to do once DDL has run (settings `refresh_schema_on_ddl`)
+    schema_refresh: SchemaRefreshMode,
+
+    /// Columns whose values are masked (settings `mask_columns`)
+    mask_rules: MaskRules,
+    /// Whether `/reveal` turned masking off for the session
+    masks_revealed: bool,
+
+    /// Settings as loaded, kept to re-merge per-connection overrides on connect
+    settings: Settings,
+
+    /// Session counters, mirrored to `metrics_file` when set
+    metrics: Metrics,
+
     /// SQL pending destructive-query confirmation (waiting for y/n)
     pending_confirm_sql: Option<PendingConfirm>,
 
+    /// INSERT generated from pasted rows, waiting for y/n to open it
+    pending_paste_insert: Option<String>,
+
+    /// Schema refresh offered after DDL, answered by the next key (y)
+    pending_schema_refresh: bool,
+
     /// Status message to display
     pub status_message: Option<StatusMessage>,
 
@@ -209,7 +438,18 @@ pub enum PanelFocus {
     CommandBar,
     Inspector,
     Help,
+    Messages,
     ConnectionDialog,
+    HistoryBrowser,
+    Jobs,
+    Histogram,
+    TablePreview,
+    Notifications,
+    Sessions,
+    Dashboard,
+    Replication,
+    ServerSummary,
+    InsertForm,
 }
 
 /// Status message with severity level
@@ -226,24 +466,125 @@ pub enum StatusLevel {
     Error,
 }
 
-/// Client-side transaction state tracking.
-/// Inferred from query text (BEGIN/COMMIT/ROLLBACK) and error events.
+/// Which search input the command bar prompt is collecting
+enum SearchPrompt {
+    /// Search term for find (matches highlight as you type)
+    Find,
+    /// Search term for replace-all
+    ReplaceFind,
+    /// Replacement text for the given search term
+    ReplaceWith(String),
+    /// Search term for the results grid (matches highlight as you type)
+    Results,
+    /// Column name to jump to (the selection follows as you type); holds
+    /// the column selected before, restored on Esc
+    Column(usize),
+}
+
+/// Which rows an export writes, and how
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ExportMode {
+    /// The rows already loaded
+    #[default]
+    Loaded,
+    /// `/export all`: every row, streamed through the client
+    All,
+    /// `/export copy`: every row, formatted by the server's `COPY`
+    Copy,
+}
+
+/// Placeholder values being collected, one prompt each, before a query runs
+struct ParamPrompt {
+    sql: String,
+    /// Placeholder labels in bind order (`$1` or `:name`)
+    names: Vec<String>,
+    /// Values entered so far (None is NULL)
+    values: Vec<Option<String>>,
+}
+
+/// What to do when DDL finishes, from `refresh_schema_on_ddl`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SchemaRefreshMode {
+    /// Offer a refresh in the status bar (y to accept)
+    Ask,
+    /// Refresh the tree in the background right away
+    Auto,
+    Off,
+}
+
+impl SchemaRefreshMode {
+    fn parse(value: &str) -> std::result::Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "ask" => Ok(Self::Ask),
+            "auto" => Ok(Self::Auto),
+            "off" => Ok(Self::Off),
+            other => Err(format!("unknown value '{}' (use ask, auto, off)", other)),
+        }
+    }
+}
+
+/// What the cost guard does with a query over one of its thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CostGuardAction {
+    /// Ask before running it (y/N)
+    Warn,
+    /// Don't run it; `/force` runs the editor's query anyway
+    Refuse,
+}
+
+impl CostGuardAction {
+    fn parse(value: &str) -> std::result::Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "warn" => Ok(Self::Warn),
+            "refuse" => Ok(Self::Refuse),
+            other => Err(format!("unknown value '{}' (use warn, refuse)", other)),
+        }
+    }
+}
+
+/// Planner estimates a query is checked against before it runs
+/// (`cost_guard_rows`, `cost_guard_cost`; 0 turns a check off)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TransactionState {
-    /// No active transaction (autocommit)
-    Idle,
-    /// Inside an explicit transaction block
-    InTransaction,
-    /// Transaction block entered error state (requires ROLLBACK)
-    Failed,
+struct CostGuard {
+    rows: u64,
+    cost: u64,
+    action: CostGuardAction,
+}
+
+impl CostGuard {
+    fn is_on(&self) -> bool {
+        self.rows > 0 || self.cost > 0
+    }
+
+    /// Which threshold `estimate` goes over, for the status bar
+    fn exceeded(&self, estimate: &PlanEstimate, numbers: &NumberFormat) -> Option<String> {
+        if self.rows > 0 && estimate.rows > self.rows {
+            return Some(format!(
+                "~{} rows (cost_guard_rows is {})",
+                numbers.count(estimate.rows as usize),
+                numbers.count(self.rows as usize)
+            ));
+        }
+        if self.cost > 0 && estimate.total_cost > self.cost as f64 {
+            return Some(format!(
+                "a cost of {:.0} (cost_guard_cost is {})",
+                estimate.total_cost, self.cost
+            ));
+        }
+        None
+    }
 }
 
 /// Pending destructive query confirmation
 struct PendingConfirm {
     sql: String,
+    params: Vec<Option<String>>,
     tab_id: usize,
     timeout_ms: u64,
     max_rows: usize,
+    /// Asked because the cost guard tripped: y goes on to the destructive
+    /// check and runs the query as usual
+    over_cost: bool,
 }
 
 /// Application events from the event loop
@@ -253,7 +594,10 @@ pub enum AppEvent {
     /// Terminal resize event
     Resize,
     /// Row count progress during streaming query execution
-    QueryProgress { rows_fetched: usize, tab_id: usize },
+    QueryProgress {
+        rows_fetched: usize,
+        tab_id: usize,
+    },
     /// Query execution completed successfully
     QueryCompleted {
         results: QueryResults,
@@ -263,12 +607,36 @@ pub enum AppEvent {
     QueryFailed {
         error: String,
         position: Option<u32>, // byte offset in query
+        /// SQLSTATE, when the server reported the error
+        code: Option<String>,
+        tab_id: usize,
+    },
+    /// Cell UPDATE completed; `row` is the refreshed row (None if no row matched)
+    CellUpdated {
+        row: Option<Row>,
+        tab_id: usize,
+    },
+    /// Cell UPDATE failed
+    CellUpdateFailed {
+        error: String,
+        tab_id: usize,
+    },
+    /// Connection-reported transaction state after a query finished
+    TransactionStatus {
+        state: TransactionState,
         tab_id: usize,
     },
     /// Schema loaded successfully
     SchemaLoaded(SchemaTree),
     /// Schema loading failed
     SchemaFailed(String),
+    /// One schema's objects loaded after it was first expanded
+    SchemaObjectsLoaded(Schema),
+    /// Loading one schema's objects failed
+    SchemaObjectsFailed {
+        schema_name: String,
+        error: String,
+    },
     /// Schema search completed successfully
     SchemaSearchCompleted(SchemaTree),
     /// Schema search failed
@@ -281,10 +649,121 @@ pub enum AppEvent {
     },
     /// Load more items failed
     LoadMoreFailed(String),
+    /// Generated DDL for a tree object
+    DdlLoaded {
+        name: String,
+        ddl: String,
+    },
+    /// DDL generation failed
+    DdlFailed(String),
     /// Bracketed paste event
     Paste(String),
     /// Background database connection lost on a specific tab
-    ConnectionLost { tab_id: usize, message: String },
+    ConnectionLost {
+        tab_id: usize,
+        message: String,
+    },
+    /// A background job finished
+    JobCompleted {
+        job_id: usize,
+        results: QueryResults,
+    },
+    /// A background `/export all` finished writing its file
+    ExportCompleted {
+        job_id: usize,
+        path: String,
+        rows: usize,
+        bytes: u64,
+    },
+    /// A background `/import` has sent this many rows so far
+    ImportProgress {
+        job_id: usize,
+        rows: u64,
+    },
+    /// A background `/import` finished
+    ImportCompleted {
+        job_id: usize,
+        table: String,
+        rows: u64,
+    },
+    /// A background `/export copy` has written this much so far
+    ExportProgress {
+        job_id: usize,
+        rows: u64,
+        bytes: u64,
+    },
+    /// A query's prepared statement went missing between prepare and
+    /// execute (a transaction-pooling proxy such as pgbouncer), so its
+    /// connection switched to the simple query protocol
+    PoolerDetected,
+    /// A background job failed
+    JobFailed {
+        job_id: usize,
+        error: String,
+    },
+    /// `EXPLAIN (FORMAT JSON)` output for a row estimate
+    EstimateCompleted(QueryResults),
+    EstimateFailed(String),
+    /// `EXPLAIN (FORMAT JSON)` output for the cost guard
+    CostChecked {
+        results: QueryResults,
+        tab_id: usize,
+    },
+    /// The cost guard's EXPLAIN failed
+    CostCheckFailed {
+        error: String,
+        tab_id: usize,
+    },
+    /// A NOTIFY arrived on a channel given to `/listen`
+    Notification(Notification),
+    /// The LISTEN connection was lost
+    ListenerLost(String),
+    /// The server's other sessions, for `/sessions`
+    SessionsLoaded(Vec<Session>),
+    /// Listing or signalling sessions failed
+    SessionsFailed(String),
+    /// The checks run after connecting
+    ServerSummaryLoaded(ServerSummary),
+    /// A health snapshot for `/dashboard`
+    HealthLoaded(Health),
+    /// Fetching the health snapshot failed
+    HealthFailed(String),
+    /// Publications and subscriptions for `/replication`
+    ReplicationLoaded(Replication),
+    /// Listing publications and subscriptions failed
+    ReplicationFailed(String),
+    /// A column's distinct values for completion (None when there are too
+    /// many, or the lookup failed)
+    ColumnValuesLoaded {
+        schema: String,
+        table: String,
+        column: String,
+        values: Option<Vec<String>>,
+    },
+    /// A table's columns, for the insert-row form
+    InsertColumnsLoaded {
+        schema: String,
+        table: String,
+        columns: Vec<InsertColumn>,
+    },
+    InsertColumnsFailed(String),
+    /// A session was sent a cancel or terminate; `sent` is false when it
+    /// had already gone
+    BackendSignalled {
+        pid: i32,
+        terminate: bool,
+        sent: bool,
+    },
+    /// Rows from a followed table's poll
+    TailRows {
+        tab_id: usize,
+        results: QueryResults,
+    },
+    /// A followed table's poll failed
+    TailFailed {
+        tab_id: usize,
+        error: String,
+    },
 }
 
 /// Items loaded by load_more operations
@@ -300,10 +779,18 @@ pub enum LoadMoreItems {
 pub enum Action {
     ExecuteQuery {
         sql: String,
+        /// Bind values for `$n`/`:name` placeholders (None is NULL)
+        params: Vec<Option<String>>,
         tab_id: usize,
         timeout_ms: u64,
         max_rows: usize,
     },
+    /// Run a generated cell UPDATE on a tab's connection
+    UpdateCell {
+        update: CellUpdate,
+        tab_id: usize,
+        timeout_ms: u64,
+    },
     /// Cancel a query on a specific tab's connection.
     /// If `terminate` is true, use pg_terminate_backend() for hard kill.
     CancelQuery {
@@ -311,20 +798,137 @@ pub enum Action {
         terminate: bool,
     },
     LoadSchema,
+    /// Write the tree's schema to the on-disk cache
+    SaveSchemaCache,
     SearchSchema {
         pattern: String,
     },
+    /// Generate DDL for a tree object
+    ShowDdl {
+        target: DdlTarget,
+    },
     LoadMoreCategory {
         schema_name: String,
         category: String,
         offset: usize,
         limit: usize,
     },
+    /// Plan (without running) a query on a tab's connection to estimate
+    /// its row count
+    EstimateRows {
+        sql: String,
+        tab_id: usize,
+        timeout_ms: u64,
+    },
+    /// Plan a query on a tab's connection for the cost guard before it runs
+    CheckCost {
+        sql: String,
+        tab_id: usize,
+        timeout_ms: u64,
+    },
+    /// Run a query on a background job connection
+    RunJob {
+        job_id: usize,
+        sql: String,
+        max_rows: usize,
+    },
+    /// Stream a query's rows to a file on a background job connection
+    ExportAll {
+        job_id: usize,
+        sql: String,
+        params: Vec<Option<String>>,
+        path: String,
+        stream: RowStream,
+    },
+    /// Write a query's rows to a file with `COPY (query) TO STDOUT` on a
+    /// background job connection
+    ExportCopy {
+        job_id: usize,
+        sql: String,
+        path: String,
+        options: CsvOptions,
+    },
+    /// Cancel a background job's query
+    CancelJob {
+        job_id: usize,
+    },
+    /// Stream a CSV file into a table with COPY on a background job
+    /// connection
+    ImportCsv {
+        job_id: usize,
+        import: CsvImport,
+    },
+    /// Run user queries over the simple query protocol on every connection
+    UseSimpleProtocol,
     /// A tab was closed — main loop should clean up its connection
     TabClosed {
         tab_id: usize,
     },
+    /// Several tabs were closed at once (`/tabonly`)
+    TabsClosed {
+        tab_ids: Vec<usize>,
+    },
     Connect(ConnectionConfig),
+    /// Reconnect the main connection to another database on the server
+    UseDatabase {
+        database: String,
+        tab_id: usize,
+    },
+    /// Bind one tab to its own connection
+    ConnectTab {
+        tab_id: usize,
+        config: ConnectionConfig,
+    },
+    /// LISTEN on a channel over the dedicated listener connection
+    Listen {
+        channel: String,
+    },
+    /// UNLISTEN a channel (every channel when `None`)
+    Unlisten {
+        channel: Option<String>,
+    },
+    /// List the server's other sessions over a tab's connection
+    LoadSessions {
+        tab_id: usize,
+    },
+    /// Set `statement_timeout` on every open connection, and for the ones
+    /// opened later
+    SetStatementTimeout {
+        timeout_ms: u64,
+    },
+    /// Fetch a health snapshot over a tab's connection
+    LoadHealth {
+        tab_id: usize,
+    },
+    /// List publications and subscriptions over a tab's connection
+    LoadReplication {
+        tab_id: usize,
+    },
+    /// Fetch a column's distinct values for completing `column = '`
+    LoadColumnValues {
+        tab_id: usize,
+        schema: String,
+        table: String,
+        column: String,
+    },
+    /// Fetch a table's columns for the insert-row form
+    LoadInsertForm {
+        tab_id: usize,
+        schema: String,
+        table: String,
+    },
+    /// Cancel a session's query, or terminate the session
+    SignalBackend {
+        tab_id: usize,
+        pid: i32,
+        terminate: bool,
+    },
+    /// Fetch a followed table's new rows on its tab's connection
+    PollTail {
+        tab_id: usize,
+        sql: String,
+        timeout_ms: u64,
+    },
     /// Drop a single tab's dead connection so it auto-reconnects on next query
     ReconnectTab {
         tab_id: usize,
@@ -346,7 +950,59 @@ impl App {
             Ok(c) => (Some(c), None),
             Err(e) => (None, Some(e.to_string())),
         };
-        let (keymap, warnings) = KeyMap::from_config(&settings.keybindings);
+        let (keymap, keymap_warnings) = KeyMap::from_config(&settings.keybindings);
+        let mut warnings = settings.warnings.clone();
+        warnings.extend(keymap_warnings);
+        let explain_options = ExplainOptions::parse(&settings.settings.explain_options)
+            .unwrap_or_else(|e| {
+                warnings.push(format!("explain_options: {}", e));
+                ExplainOptions::default()
+            });
+        let schema_refresh = SchemaRefreshMode::parse(&settings.settings.refresh_schema_on_ddl)
+            .unwrap_or_else(|e| {
+                warnings.push(format!("refresh_schema_on_ddl: {}", e));
+                SchemaRefreshMode::Ask
+            });
+        let cost_guard_action = CostGuardAction::parse(&settings.settings.cost_guard_action)
+            .unwrap_or_else(|e| {
+                warnings.push(format!("cost_guard_action: {}", e));
+                CostGuardAction::Warn
+            });
+        let vim_editing = match settings
+            .settings
+            .editor_mode
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "vim" => true,
+            "standard" => false,
+            other => {
+                warnings.push(format!(
+                    "editor_mode: unknown value '{}' (use standard, vim)",
+                    other
+                ));
+                false
+            }
+        };
+        let number_format =
+            NumberFormat::parse(&settings.settings.number_locale).unwrap_or_else(|e| {
+                warnings.push(format!("number_locale: {}", e));
+                NumberFormat::default()
+            });
+        let mut metrics = Metrics::new(&settings.settings.metrics_file);
+        if let Err(e) = metrics.write() {
+            warnings.push(format!("metrics_file: {}", e));
+            metrics.disable_file();
+        }
+        let snippets = Snippets::load().unwrap_or_else(|e| {
+            warnings.push(format!("snippets: {}", e));
+            Snippets::default()
+        });
+        let export_dirs = ExportDirs::load().unwrap_or_else(|e| {
+            warnings.push(format!("export_dirs: {}", e));
+            ExportDirs::default()
+        });
         let mut app = Self {
             connection_name: None,
             is_saved_connection: false,
@@ -359,29 +1015,78 @@ impl App {
             command_bar: CommandBar::new(),
             inspector: Inspector::new(),
             help: HelpOverlay::new(),
+            messages: MessageLog::new(),
+            history_browser: HistoryBrowser::new(),
+            jobs: JobList::new(),
+            histogram: ColumnHistogram::new(),
+            table_preview: TablePreview::new(),
+            notifications: NotificationLog::new(),
+            sessions: SessionList::new(),
+            dashboard: Dashboard::new(),
+            replication: ReplicationView::new(),
+            server_summary: ServerSummaryPopup::new(),
+            insert_form: InsertForm::new(),
             connection_dialog: ConnectionDialog::new(),
-            tabs: vec![Tab::new(0)],
+            connecting_tab: None,
+            tabs: Vec::new(),
             active_tab: 0,
             next_tab_id: 1,
             pending_export: None,
             pending_save_query: false,
+            pending_export_mode: ExportMode::Loaded,
+            pending_search: None,
+            pending_row_filter: false,
+            pending_cell_edit: false,
+            pending_params: None,
+            param_values: HashMap::new(),
+            stashes: Vec::new(),
+            snippets,
+            export_filename: settings.settings.export_filename.clone(),
+            export_dirs,
             history: QueryHistory::load(settings.settings.history_size),
             max_tabs: settings.settings.max_tabs,
+            result_history: settings.settings.result_history,
+            editor_ruler: settings.settings.editor_ruler,
+            vim_editing,
+            auto_indent: settings.settings.auto_indent,
+            number_format,
             keymap,
             theme: Theme::by_name(&settings.settings.theme).unwrap_or_default(),
             query_timeout_ms: settings.settings.query_timeout_ms,
             max_result_rows: settings.settings.max_result_rows,
             statement_timeout_ms: settings.settings.statement_timeout_ms,
             confirm_destructive: settings.settings.confirm_destructive,
+            cost_guard: CostGuard {
+                rows: settings.settings.cost_guard_rows,
+                cost: settings.settings.cost_guard_cost,
+                action: cost_guard_action,
+            },
+            cost_guard_bypass: false,
             read_only: settings.settings.read_only,
             default_read_only: settings.settings.read_only,
             explain_visual: settings.settings.explain_visual,
+            explain_options,
+            new_tab_copies_draft: settings.settings.new_tab_copies_draft,
+            server_summary_on_connect: settings.settings.server_summary_on_connect,
+            skip_unreadable_tables: settings.settings.skip_unreadable_tables,
+            column_values: HashMap::new(),
+            format_on_execute: settings.settings.format_on_execute,
+            schema_refresh,
+            mask_rules: MaskRules::new(&settings.settings.mask_columns),
+            masks_revealed: false,
+            settings: settings.clone(),
+            metrics,
             pending_confirm_sql: None,
+            pending_paste_insert: None,
+            pending_schema_refresh: false,
             status_message: None,
             clipboard,
             clipboard_error,
             running: true,
         };
+        app.tabs.push(app.blank_tab(0));
+        app.tree_browser.set_number_format(number_format);
+        app.dashboard.set_number_format(number_format);
         if !warnings.is_empty() {
             app.set_status(
                 format!("Config: {}", warnings.join("; ")),
@@ -401,38 +1106,163 @@ impl App {
     ) -> Self {
         let mut app = Self::new_with_settings(settings);
         app.connection_name = Some(name.clone());
+        app.history.set_connection(Some(name.clone()));
         app.is_saved_connection = saved;
         app.read_only = app.default_read_only || connection_read_only;
+        app.apply_connection_settings(&name);
         app.tree_browser.set_schema(schema);
         app.load_saved_queries_for(&name, saved);
         app
     }
 
+    /// Run SQL from the editor, prompting first for any placeholder values
+    fn run_editor_sql(&mut self, sql: String) -> Action {
+        match find_placeholders(&sql) {
+            Ok(placeholders) if placeholders.is_empty() => self.execute_sql(sql, Vec::new()),
+            Ok(placeholders) => {
+                self.pending_params = Some(ParamPrompt {
+                    sql,
+                    names: placeholders.names,
+                    values: Vec::new(),
+                });
+                self.prompt_next_param();
+                Action::None
+            }
+            Err(e) => {
+                self.set_status(e, StatusLevel::Error);
+                Action::None
+            }
+        }
+    }
+
+    /// Open the command bar for the next placeholder's value, prefilled
+    /// with the value it had last time
+    fn prompt_next_param(&mut self) {
+        let Some(prompt) = &self.pending_params else {
+            return;
+        };
+        let index = prompt.values.len();
+        let name = &prompt.names[index];
+        let label = if prompt.names.len() > 1 {
+            format!("{} ({}/{}) = ", name, index + 1, prompt.names.len())
+        } else {
+            format!("{} = ", name)
+        };
+        let prefill = self.param_values.get(name).cloned().unwrap_or_default();
+        self.previous_focus = self.focus;
+        self.focus = PanelFocus::CommandBar;
+        self.command_bar.activate_with_prompt(label, prefill);
+    }
+
+    /// Record the submitted placeholder value (`NULL` binds NULL) and run
+    /// the query once every placeholder has one
+    fn finish_param_prompt(&mut self, mut prompt: ParamPrompt, input: &str) -> Action {
+        let name = prompt.names[prompt.values.len()].clone();
+        self.param_values.insert(name, input.to_string());
+        prompt
+            .values
+            .push((input != "NULL").then(|| input.to_string()));
+        if prompt.values.len() < prompt.names.len() {
+            self.pending_params = Some(prompt);
+            self.prompt_next_param();
+            return Action::None;
+        }
+        self.execute_sql(prompt.sql, prompt.values)
+    }
+
+    /// Run user SQL after the read-only check and the cost guard, asking for
+    /// confirmation first when it is destructive
+    fn execute_sql(&mut self, sql: String, params: Vec<Option<String>>) -> Action {
+        // Block writes in read-only mode, unless inside BEGIN READ WRITE
+        if self.tab_writes_blocked()
+            && let Some(label) = is_write_query(&sql)
+        {
+            self.set_status(
+                format!(
+                    "Read-only mode: {} queries are blocked (run them inside BEGIN READ WRITE to override)",
+                    label
+                ),
+                StatusLevel::Error,
+            );
+            return Action::None;
+        }
+        if !self.cost_guard_bypass
+            && let Some(action) = self.start_cost_check(&sql, &params)
+        {
+            self.tab_mut().cost_check = Some((sql, params));
+            return action;
+        }
+        self.confirm_or_execute(self.active_tab, sql, params)
+    }
+
+    /// Run a tab's SQL, or ask first when it is destructive
+    fn confirm_or_execute(
+        &mut self,
+        idx: usize,
+        sql: String,
+        params: Vec<Option<String>>,
+    ) -> Action {
+        if self.confirm_destructive
+            && let Some(label) = is_destructive_query(&sql)
+        {
+            let prompt = if drop_impact_sql(&sql).is_some() {
+                format!(
+                    "This query contains {}. Execute? (y/N) — d to show dependents",
+                    label
+                )
+            } else {
+                format!("This query contains {}. Execute? (y/N)", label)
+            };
+            self.pending_confirm_sql = Some(PendingConfirm {
+                sql,
+                params,
+                tab_id: self.tabs[idx].id,
+                timeout_ms: self.query_timeout_ms,
+                max_rows: self.max_result_rows,
+                over_cost: false,
+            });
+            self.set_status(prompt, StatusLevel::Warning);
+            return Action::None;
+        }
+        self.set_status("Executing query...".to_string(), StatusLevel::Info);
+        self.prepare_execute_query_in(idx, sql, params)
+    }
+
     /// Execute a query that has already passed confirmation (or didn't need it).
     /// This handles both the transaction state update and returning the Action.
-    fn prepare_execute_query(&mut self, sql: String) -> Action {
-        let tab_id = self.tab().id;
+    fn prepare_execute_query(&mut self, sql: String, params: Vec<Option<String>>) -> Action {
+        self.prepare_execute_query_in(self.active_tab, sql, params)
+    }
+
+    /// `prepare_execute_query` on the tab at `idx`
+    fn prepare_execute_query_in(
+        &mut self,
+        idx: usize,
+        sql: String,
+        params: Vec<Option<String>>,
+    ) -> Action {
+        let tab_id = self.tabs[idx].id;
         let timeout_ms = self.query_timeout_ms;
         let page_size = self.max_result_rows;
 
         // Update this tab's transaction state based on query intent
         if let Some(new_state) = detect_transaction_intent(&sql) {
-            self.tab_mut().transaction_state = new_state;
+            self.tabs[idx].transaction_state = new_state;
         }
-
-        self.tab_mut().query_running = true;
-        self.tab_mut().query_start = Some(std::time::Instant::now());
-        self.history.push(&sql);
-
-        // Auto-paginate if the query has no user LIMIT and isn't EXPLAIN/transaction control
-        let trimmed = sql.trim();
-        let is_explain = trimmed
-            .split_whitespace()
-            .next()
-            .is_some_and(|w| w.eq_ignore_ascii_case("EXPLAIN"));
-        let is_transaction = detect_transaction_intent(&sql).is_some();
-
-        if !is_explain && !is_transaction && page_size > 0 {
+        self.note_read_write(idx, &sql);
+        self.note_schema_change(idx, &sql);
+
+        self.stop_tail(idx);
+        let history_entry = self.history.push(&sql);
+        let tab = &mut self.tabs[idx];
+        tab.query_running = true;
+        tab.query_start = Some(std::time::Instant::now());
+        tab.history_cursor.reset();
+        tab.history_entry = history_entry;
+
+        // Auto-paginate if the query returns rows (not DML, EXPLAIN, or
+        // transaction control) and has no user LIMIT
+        if sql_limit::returns_rows(&sql) && page_size > 0 {
             let analysis = sql_limit::analyze_limit(&sql);
             if analysis.can_paginate() {
                 let pagination = PaginationState {
@@ -442,11 +1272,13 @@ impl App {
                     has_more: false,
                     user_has_limit: false,
                     previous_page: None,
+                    params: params.clone(),
                 };
                 let paged_sql = pagination.paged_sql();
-                self.tab_mut().pagination = Some(pagination);
+                tab.pagination = Some(pagination);
                 return Action::ExecuteQuery {
                     sql: paged_sql,
+                    params,
                     tab_id,
                     timeout_ms,
                     max_rows: 0, // LIMIT in SQL controls row count
@@ -455,28 +1287,177 @@ impl App {
         }
 
         // User has LIMIT or EXPLAIN — run as-is with max_rows safety net
-        self.tab_mut().pagination = None;
+        tab.pagination = None;
         Action::ExecuteQuery {
             sql,
+            params,
             tab_id,
             timeout_ms,
             max_rows: page_size,
         }
     }
 
+    /// When the cost guard is on, plan `sql` before running it. Only single
+    /// statements EXPLAIN can plan are checked, and not ones with
+    /// placeholders. A query that will be paginated is planned with its
+    /// first page's LIMIT, since that is what runs.
+    fn start_cost_check(&mut self, sql: &str, params: &[Option<String>]) -> Option<Action> {
+        if !self.cost_guard.is_on() || !params.is_empty() || !is_plannable(sql) {
+            return None;
+        }
+        let sql = sql.trim().trim_end_matches(';').trim_end();
+        let page_size = self.max_result_rows;
+        let planned = if sql_limit::returns_rows(sql)
+            && page_size > 0
+            && sql_limit::analyze_limit(sql).can_paginate()
+        {
+            format!("{} LIMIT {}", sql, page_size + 1)
+        } else {
+            sql.to_string()
+        };
+        self.tab_mut().query_running = true;
+        self.tab_mut().query_start = Some(std::time::Instant::now());
+        self.set_status(
+            "Checking the query's cost...".to_string(),
+            StatusLevel::Info,
+        );
+        Some(Action::CheckCost {
+            sql: format!("EXPLAIN (FORMAT JSON) {}", planned),
+            tab_id: self.tab().id,
+            timeout_ms: self.query_timeout_ms,
+        })
+    }
+
+    /// Go on with a query once its plan is back: run it when it is under the
+    /// cost guard's thresholds, otherwise ask (`warn`) or refuse. When the
+    /// EXPLAIN fails the query doesn't run either.
+    fn finish_cost_check(
+        &mut self,
+        tab_id: usize,
+        plan: std::result::Result<QueryResults, String>,
+    ) -> Action {
+        let Some(idx) = self.tab_index_by_id(tab_id) else {
+            return Action::None;
+        };
+        let Some((sql, params)) = self.tabs[idx].cost_check.take() else {
+            return Action::None;
+        };
+        self.tabs[idx].query_running = false;
+        self.tabs[idx].query_start = None;
+        let results = match plan {
+            Ok(results) => results,
+            Err(e) => {
+                self.set_status(
+                    format!("Cost check failed: {} — /force runs it unchecked", e),
+                    StatusLevel::Error,
+                );
+                return Action::None;
+            }
+        };
+        let estimate = explain_estimate(&results);
+        let Some(over) = estimate.and_then(|e| self.cost_guard.exceeded(&e, &self.number_format))
+        else {
+            return self.confirm_or_execute(idx, sql, params);
+        };
+        if self.cost_guard.action == CostGuardAction::Refuse {
+            self.set_status(
+                format!(
+                    "Refused: the planner estimates {} — /force runs it anyway",
+                    over
+                ),
+                StatusLevel::Error,
+            );
+            return Action::None;
+        }
+        self.pending_confirm_sql = Some(PendingConfirm {
+            sql,
+            params,
+            tab_id,
+            timeout_ms: self.query_timeout_ms,
+            max_rows: self.max_result_rows,
+            over_cost: true,
+        });
+        self.set_status(
+            format!("The planner estimates {}. Execute? (y/N)", over),
+            StatusLevel::Warning,
+        );
+        Action::None
+    }
+
+    /// Remember that a tab ran DDL; a ROLLBACK on the tab discards it
+    fn note_schema_change(&mut self, idx: usize, sql: &str) {
+        if self.schema_refresh == SchemaRefreshMode::Off {
+            return;
+        }
+        if contains_ddl(sql) {
+            self.tabs[idx].schema_changed = true;
+        } else if TransactionControl::parse(sql) == Some(TransactionControl::Rollback) {
+            self.tabs[idx].schema_changed = false;
+        }
+    }
+
+    /// After a tab's query finishes, refresh (or offer to refresh) the
+    /// tree if DDL it ran is now committed
+    fn schema_change_action(&mut self, idx: usize) -> Action {
+        let tab = &mut self.tabs[idx];
+        if !tab.schema_changed || tab.transaction_state != TransactionState::Idle {
+            return Action::None;
+        }
+        tab.schema_changed = false;
+        self.tree_browser.mark_stale();
+        match self.schema_refresh {
+            SchemaRefreshMode::Auto => Action::LoadSchema,
+            SchemaRefreshMode::Ask => {
+                self.pending_schema_refresh = true;
+                self.set_status(
+                    "Schema changed — refresh the tree? (y/N)".to_string(),
+                    StatusLevel::Warning,
+                );
+                Action::None
+            }
+            SchemaRefreshMode::Off => Action::None,
+        }
+    }
+
+    /// List what a pending DROP would remove, in place of running it
+    fn show_drop_dependents(&mut self, pending: PendingConfirm, impact_sql: String) -> Action {
+        if let Some(idx) = self.tab_index_by_id(pending.tab_id) {
+            let tab = &mut self.tabs[idx];
+            tab.query_running = true;
+            tab.query_start = Some(std::time::Instant::now());
+            tab.pagination = None;
+        }
+        self.set_status(
+            "Listing dependent objects (nothing was dropped)...".to_string(),
+            StatusLevel::Info,
+        );
+        Action::ExecuteQuery {
+            sql: impact_sql,
+            params: Vec::new(),
+            tab_id: pending.tab_id,
+            timeout_ms: pending.timeout_ms,
+            max_rows: pending.max_rows,
+        }
+    }
+
     /// Execute a confirmed (destructive) query
     fn execute_confirmed_query(&mut self, pending: PendingConfirm) -> Action {
+        let history_entry = self.history.push(&pending.sql);
         if let Some(idx) = self.tab_index_by_id(pending.tab_id) {
             if let Some(new_state) = detect_transaction_intent(&pending.sql) {
                 self.tabs[idx].transaction_state = new_state;
             }
+            self.note_read_write(idx, &pending.sql);
+            self.note_schema_change(idx, &pending.sql);
             self.tabs[idx].query_running = true;
             self.tabs[idx].query_start = Some(std::time::Instant::now());
+            self.tabs[idx].history_cursor.reset();
+            self.tabs[idx].history_entry = history_entry;
         }
-        self.history.push(&pending.sql);
 
         Action::ExecuteQuery {
             sql: pending.sql,
+            params: pending.params,
             tab_id: pending.tab_id,
             timeout_ms: pending.timeout_ms,
             max_rows: pending.max_rows,
@@ -493,6 +1474,21 @@ impl App {
 
     fn execute_command(&mut self, command: Command) -> Action {
         match command {
+            Command::Job { .. }
+            | Command::ExportAll { .. }
+            | Command::ExportCopy { .. }
+            | Command::Import { .. }
+                if let Some(conn) = &self.tab().connection =>
+            {
+                self.set_status(
+                    format!(
+                        "This tab is on {}; background jobs run on the main connection",
+                        conn.name
+                    ),
+                    StatusLevel::Warning,
+                );
+                Action::None
+            }
             Command::Refresh => {
                 self.set_status("Refreshing schema...".to_string(), StatusLevel::Info);
                 Action::LoadSchema
@@ -535,14 +1531,686 @@ impl App {
                 }
                 Action::None
             }
-        }
-    }
-
-    /// Reference to the active tab
-    pub fn tab(&self) -> &Tab {
-        &self.tabs[self.active_tab]
-    }
-
+            Command::Messages => {
+                self.previous_focus = self.focus;
+                self.focus = PanelFocus::Messages;
+                self.messages.show();
+                Action::None
+            }
+            Command::Explain { options } => {
+                if let Some(spec) = options {
+                    match ExplainOptions::parse(&spec) {
+                        Ok(options) => self.explain_options = options,
+                        Err(e) => {
+                            self.set_status(e, StatusLevel::Error);
+                            return Action::None;
+                        }
+                    }
+                }
+                self.set_status(
+                    format!("EXPLAIN key runs {}", self.explain_options.label()),
+                    StatusLevel::Info,
+                );
+                Action::None
+            }
+            Command::Begin => self.run_transaction_control("BEGIN"),
+            Command::Commit => self.run_transaction_control("COMMIT"),
+            Command::Rollback => self.run_transaction_control("ROLLBACK"),
+            Command::Stash { name } => {
+                match name {
+                    Some(name) => self.stash_results(name),
+                    None => self.list_stashes(),
+                }
+                Action::None
+            }
+            Command::Restore { name } => {
+                self.restore_stash(name.as_deref());
+                Action::None
+            }
+            Command::Compare { name } => {
+                self.compare_with_stash(name.as_deref());
+                Action::None
+            }
+            Command::Snippet { name } => {
+                match name {
+                    Some(name) => self.insert_snippet(&name),
+                    None => self.list_snippets(),
+                }
+                Action::None
+            }
+            Command::SaveSnippet { name } => {
+                self.save_snippet(name.as_deref());
+                Action::None
+            }
+            Command::DeleteSnippet { name } => {
+                self.delete_snippet(name.as_deref());
+                Action::None
+            }
+            Command::Job { sql } => self.start_job(sql),
+            Command::Export { format, path } => {
+                let Some(format) = format.as_deref().and_then(ExportFormat::from_name) else {
+                    self.set_status(
+                        "Usage: /export csv|tsv|json|md|xlsx [path]".to_string(),
+                        StatusLevel::Warning,
+                    );
+                    return Action::None;
+                };
+                match path {
+                    Some(path) => self.execute_export(format, &path),
+                    None => self.start_export(format, ""),
+                }
+                Action::None
+            }
+            Command::ExportAll { format, path } => {
+                let Some(format) = format.as_deref().and_then(ExportFormat::from_name) else {
+                    self.set_status(
+                        "Usage: /export all csv|tsv|json|md [path]".to_string(),
+                        StatusLevel::Warning,
+                    );
+                    return Action::None;
+                };
+                match path {
+                    Some(path) => self.execute_export_all(format, &path),
+                    None => {
+                        self.start_export(format, "");
+                        if self.pending_export.is_some() {
+                            self.pending_export_mode = ExportMode::All;
+                        }
+                        Action::None
+                    }
+                }
+            }
+            Command::ExportCopy { format, path } => {
+                let format = format.as_deref().and_then(ExportFormat::from_name);
+                let Some(format @ (ExportFormat::Csv | ExportFormat::Tsv)) = format else {
+                    self.set_status(
+                        "Usage: /export copy csv|tsv [path]".to_string(),
+                        StatusLevel::Warning,
+                    );
+                    return Action::None;
+                };
+                match path {
+                    Some(path) => self.execute_export_copy(format, &path),
+                    None => {
+                        self.start_export(format, "");
+                        if self.pending_export.is_some() {
+                            self.pending_export_mode = ExportMode::Copy;
+                        }
+                        Action::None
+                    }
+                }
+            }
+            Command::Import {
+                path,
+                table,
+                dry_run,
+            } => self.start_import(path, table, dry_run),
+            Command::Copy { format } => {
+                match format.as_deref().and_then(ExportFormat::from_name) {
+                    Some(format) if format != ExportFormat::Xlsx => self.copy_results(format),
+                    _ => self.set_status(
+                        "Usage: /copy csv|tsv|json|md".to_string(),
+                        StatusLevel::Warning,
+                    ),
+                }
+                Action::None
+            }
+            Command::Jobs => {
+                self.previous_focus = self.focus;
+                self.focus = PanelFocus::Jobs;
+                self.jobs.show();
+                Action::None
+            }
+            Command::Reveal => {
+                self.toggle_reveal();
+                Action::None
+            }
+            Command::Force => {
+                self.cost_guard_bypass = true;
+                let action = self.execute_key_action(KeyAction::ExecuteQuery);
+                self.cost_guard_bypass = false;
+                action
+            }
+            Command::CloseTabs { right_only } => self.close_other_tabs(right_only),
+            Command::TabConnect { target } => self.connect_tab(target),
+            Command::Listen { channel } => match channel {
+                Some(channel) => self.listen(&channel),
+                None => {
+                    self.previous_focus = self.focus;
+                    self.focus = PanelFocus::Notifications;
+                    self.notifications.show();
+                    Action::None
+                }
+            },
+            Command::Unlisten { channel } => self.unlisten(channel.as_deref()),
+            Command::Tail { args } => self.tail(args.as_deref()),
+            Command::Snapshot { table } => self.take_snapshot(table.as_deref()),
+            Command::Open { path } => {
+                match path {
+                    Some(path) => self.open_file(&path),
+                    None => {
+                        self.set_status("Usage: /open <file.sql>".to_string(), StatusLevel::Warning)
+                    }
+                }
+                Action::None
+            }
+            Command::Sessions => {
+                if self.connection_name.is_none() {
+                    self.set_status("Not connected".to_string(), StatusLevel::Warning);
+                    return Action::None;
+                }
+                self.previous_focus = self.focus;
+                self.focus = PanelFocus::Sessions;
+                self.sessions.show();
+                self.load_sessions()
+            }
+            Command::Timeout { value } => self.set_statement_timeout(value.as_deref()),
+            Command::Dashboard => {
+                if self.connection_name.is_none() {
+                    self.set_status("Not connected".to_string(), StatusLevel::Warning);
+                    return Action::None;
+                }
+                self.previous_focus = self.focus;
+                self.focus = PanelFocus::Dashboard;
+                self.dashboard.show();
+                self.refresh_dashboard()
+            }
+            Command::Replication => {
+                if self.connection_name.is_none() {
+                    self.set_status("Not connected".to_string(), StatusLevel::Warning);
+                    return Action::None;
+                }
+                self.previous_focus = self.focus;
+                self.focus = PanelFocus::Replication;
+                self.replication.show();
+                self.load_replication()
+            }
+            Command::Use { database } => match database {
+                Some(database) => self.use_database(database),
+                None => {
+                    self.set_status("Usage: /use <database>".to_string(), StatusLevel::Warning);
+                    Action::None
+                }
+            },
+            Command::CancelJob { job_id } => {
+                let Some(job_id) = job_id else {
+                    self.set_status("Usage: /jobs cancel <n>".to_string(), StatusLevel::Warning);
+                    return Action::None;
+                };
+                if !self.jobs.jobs().iter().any(|job| job.id == job_id) {
+                    self.set_status(format!("No running job #{}", job_id), StatusLevel::Warning);
+                    return Action::None;
+                }
+                self.set_status(format!("Cancelling job #{}...", job_id), StatusLevel::Info);
+                Action::CancelJob { job_id }
+            }
+        }
+    }
+
+    /// Start `/job` SQL on a background connection. Jobs can't ask for
+    /// confirmation, so destructive statements are left to a tab.
+    fn start_job(&mut self, sql: Option<String>) -> Action {
+        let Some(sql) = sql else {
+            self.set_status("Usage: /job <sql>".to_string(), StatusLevel::Warning);
+            return Action::None;
+        };
+        if self.read_only
+            && let Some(label) = is_write_query(&sql)
+        {
+            self.set_status(
+                format!("Read-only mode: {} queries are blocked", label),
+                StatusLevel::Error,
+            );
+            return Action::None;
+        }
+        if self.confirm_destructive
+            && let Some(label) = is_destructive_query(&sql)
+        {
+            self.set_status(
+                format!("Jobs can't confirm {} — run it from a tab instead", label),
+                StatusLevel::Error,
+            );
+            return Action::None;
+        }
+        let history_entry = self.history.push(&sql);
+        let job_id = self.jobs.start(&sql, history_entry);
+        self.set_status(
+            format!(
+                "Job #{} started — its results open in a new tab, /jobs lists running jobs",
+                job_id
+            ),
+            StatusLevel::Info,
+        );
+        Action::RunJob {
+            job_id,
+            sql,
+            max_rows: self.max_result_rows,
+        }
+    }
+
+    /// Ask the planner how many rows the editor's query would return (or
+    /// change, for UPDATE and DELETE) without running it. Uses the tab's
+    /// own connection so its transaction and temp tables are visible.
+    fn start_estimate(&mut self) -> Action {
+        let raw_sql = self.tab().editor.get_content();
+        let sql = translate_meta_command(&raw_sql).unwrap_or(raw_sql);
+        let sql = sql.trim().trim_end_matches(';').trim_end();
+        if sql.is_empty() {
+            return Action::None;
+        }
+        if self.tab().query_running {
+            self.set_status(
+                "Wait for the running query to finish".to_string(),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        if !find_placeholders(sql).is_ok_and(|p| p.is_empty()) {
+            self.set_status(
+                "Can't estimate a query with placeholders".to_string(),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        self.set_status("Estimating rows...".to_string(), StatusLevel::Info);
+        Action::EstimateRows {
+            sql: format!("EXPLAIN (FORMAT JSON) {}", sql),
+            tab_id: self.tab().id,
+            timeout_ms: self.query_timeout_ms,
+        }
+    }
+
+    /// Report the planner's estimate from `EXPLAIN (FORMAT JSON)` output
+    fn show_estimate(&mut self, results: &QueryResults) {
+        let Some(estimate) = explain_estimate(results) else {
+            self.set_status(
+                "Estimate failed: unexpected EXPLAIN output".to_string(),
+                StatusLevel::Error,
+            );
+            return;
+        };
+        self.set_status(
+            format!(
+                "~{} rows estimated (cost {:.0}) — the planner's guess from table statistics",
+                self.number_format.count(estimate.rows as usize),
+                estimate.total_cost
+            ),
+            StatusLevel::Info,
+        );
+    }
+
+    /// Open a finished job's results in a new background tab, or stash
+    /// them when no more tabs fit
+    fn deliver_job_results(&mut self, job_id: usize, results: QueryResults) {
+        let Some(job) = self.jobs.finish(job_id) else {
+            return;
+        };
+        let rows = results.row_count;
+        if let Some(seq) = job.history_entry {
+            self.history.record_result(seq, job.started.elapsed(), rows);
+        }
+        let elapsed = format_elapsed(job.started.elapsed());
+        if self.tabs.len() >= self.max_tabs {
+            let name = format!("job{}", job_id);
+            self.stashes.retain(|(n, _)| *n != name);
+            self.stashes.push((name.clone(), results));
+            self.set_status(
+                format!(
+                    "Job #{} finished: {} rows in {}, but {} tabs are open — /restore {} shows them",
+                    job_id, rows, elapsed, self.max_tabs, name
+                ),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+        let mut tab = self.blank_tab(self.next_tab_id);
+        self.next_tab_id += 1;
+        tab.editor.set_content(job.sql);
+        tab.results_viewer.set_results(results);
+        self.tabs.push(tab);
+        self.mask_results(self.tabs.len() - 1);
+        self.set_status(
+            format!(
+                "Job #{} finished: {} rows in {} — see tab {}",
+                job_id,
+                rows,
+                elapsed,
+                self.tabs.len()
+            ),
+            StatusLevel::Success,
+        );
+    }
+
+    /// A pinned tab's query finished: open a tab for the new results right
+    /// after it, taking over the query's pagination, history entry, and
+    /// pending flags, and switch to it if the pinned tab was active.
+    /// Returns its index, or `None` if all tabs are in use.
+    fn open_pinned_rerun_tab(&mut self, idx: usize) -> Option<usize> {
+        if self.tabs.len() >= self.max_tabs {
+            return None;
+        }
+        let mut tab = self.blank_tab(self.next_tab_id);
+        self.next_tab_id += 1;
+        let pinned = &mut self.tabs[idx];
+        tab.editor.set_content(pinned.editor.get_content());
+        tab.pagination = pinned.pagination.take();
+        tab.history_entry = pinned.history_entry.take();
+        tab.explain_pending = std::mem::take(&mut pinned.explain_pending);
+        tab.schema_changed = std::mem::take(&mut pinned.schema_changed);
+        self.tabs.insert(idx + 1, tab);
+        if self.active_tab >= idx {
+            self.active_tab += 1;
+        }
+        Some(idx + 1)
+    }
+
+    fn list_snippets(&mut self) {
+        if self.snippets.is_empty() {
+            self.set_status(
+                "No snippets — /snippet save <name> keeps the editor's SQL".to_string(),
+                StatusLevel::Info,
+            );
+            return;
+        }
+        let names: Vec<&str> = self.snippets.names().collect();
+        self.set_status(format!("Snippets: {}", names.join(", ")), StatusLevel::Info);
+    }
+
+    /// Insert a snippet at the editor cursor
+    fn insert_snippet(&mut self, name: &str) {
+        let Some(sql) = self.snippets.get(name).map(str::to_string) else {
+            self.set_status(format!("No snippet named '{}'", name), StatusLevel::Warning);
+            return;
+        };
+        self.tab_mut().editor.insert_text(&sql);
+        self.focus = PanelFocus::QueryEditor;
+        self.set_status(format!("Inserted snippet '{}'", name), StatusLevel::Info);
+    }
+
+    fn save_snippet(&mut self, name: Option<&str>) {
+        let Some(name) = name else {
+            self.set_status(
+                "Usage: /snippet save <name>".to_string(),
+                StatusLevel::Warning,
+            );
+            return;
+        };
+        let sql = self.tab().editor.get_content();
+        if sql.trim().is_empty() {
+            self.set_status(
+                "Editor is empty — nothing to save".to_string(),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+        match self.snippets.save(name, &sql) {
+            Ok(()) => self.set_status(format!("Saved snippet '{}'", name), StatusLevel::Success),
+            Err(e) => self.set_status(format!("Failed to save snippet: {}", e), StatusLevel::Error),
+        }
+    }
+
+    fn delete_snippet(&mut self, name: Option<&str>) {
+        let Some(name) = name else {
+            self.set_status(
+                "Usage: /snippet delete <name>".to_string(),
+                StatusLevel::Warning,
+            );
+            return;
+        };
+        match self.snippets.delete(name) {
+            Ok(true) => {
+                self.set_status(format!("Deleted snippet '{}'", name), StatusLevel::Success)
+            }
+            Ok(false) => {
+                self.set_status(format!("No snippet named '{}'", name), StatusLevel::Warning)
+            }
+            Err(e) => self.set_status(
+                format!("Failed to delete snippet: {}", e),
+                StatusLevel::Error,
+            ),
+        }
+    }
+
+    /// Copy the current results (as shown, after any row filter) into a
+    /// named stash, replacing an older stash of the same name
+    fn stash_results(&mut self, name: String) {
+        let Some(results) = self.tab().results_viewer.results().cloned() else {
+            self.set_status("No results to stash".to_string(), StatusLevel::Warning);
+            return;
+        };
+        let rows = results.rows.len();
+        self.stashes.retain(|(n, _)| *n != name);
+        self.stashes.push((name.clone(), results));
+        self.set_status(
+            format!("Stashed {} rows as '{}'", rows, name),
+            StatusLevel::Success,
+        );
+    }
+
+    fn list_stashes(&mut self) {
+        if self.stashes.is_empty() {
+            self.set_status(
+                "No stashes — /stash <name> keeps the current results".to_string(),
+                StatusLevel::Info,
+            );
+            return;
+        }
+        let list: Vec<String> = self
+            .stashes
+            .iter()
+            .map(|(name, results)| format!("{} ({} rows)", name, results.rows.len()))
+            .collect();
+        self.set_status(format!("Stashes: {}", list.join(", ")), StatusLevel::Info);
+    }
+
+    /// A stash by name, or the latest one
+    fn find_stash(&mut self, name: Option<&str>) -> Option<(String, QueryResults)> {
+        let found = match name {
+            Some(name) => self.stashes.iter().find(|(n, _)| n == name),
+            None => self.stashes.last(),
+        }
+        .cloned();
+        if found.is_none() {
+            let message = match name {
+                Some(name) => format!("No stash named '{}'", name),
+                None => "No stashes — /stash <name> keeps the current results".to_string(),
+            };
+            self.set_status(message, StatusLevel::Warning);
+        }
+        found
+    }
+
+    fn restore_stash(&mut self, name: Option<&str>) {
+        let Some((name, results)) = self.find_stash(name) else {
+            return;
+        };
+        let rows = results.rows.len();
+        self.show_results(results);
+        self.set_status(
+            format!("Showing stash '{}' ({} rows)", name, rows),
+            StatusLevel::Info,
+        );
+    }
+
+    fn compare_with_stash(&mut self, name: Option<&str>) {
+        let Some(current) = self.tab().results_viewer.results().cloned() else {
+            self.set_status("No results to compare".to_string(), StatusLevel::Warning);
+            return;
+        };
+        let Some((name, stashed)) = self.find_stash(name) else {
+            return;
+        };
+        match stash::diff_results(&stashed, &current) {
+            Ok(diff) if diff.added == 0 && diff.removed == 0 => self.set_status(
+                format!("Results match stash '{}' ({} rows)", name, diff.unchanged),
+                StatusLevel::Success,
+            ),
+            Ok(diff) => {
+                self.show_results(diff.results);
+                self.set_status(
+                    format!(
+                        "vs '{}': {} added, {} removed, {} unchanged",
+                        name, diff.added, diff.removed, diff.unchanged
+                    ),
+                    StatusLevel::Info,
+                );
+            }
+            Err(e) => self.set_status(
+                format!("Can't compare with '{}': {}", name, e),
+                StatusLevel::Error,
+            ),
+        }
+    }
+
+    /// Replace the active tab's results with rows that didn't come from a
+    /// query (stashes and their diffs)
+    fn show_results(&mut self, results: QueryResults) {
+        let tab = self.tab_mut();
+        tab.explain_viewer = None;
+        tab.pagination = None;
+        tab.results_viewer.set_results(results);
+        tab.results_viewer.set_pagination(None);
+        self.mask_results(self.active_tab);
+        self.focus = PanelFocus::ResultsViewer;
+    }
+
+    /// Swap the active tab's results for the ones it showed before (or,
+    /// after stepping back, after) them. Earlier results don't page.
+    fn step_results(&mut self, back: bool) {
+        let idx = self.active_tab;
+        self.stop_tail(idx);
+        let tab = &mut self.tabs[idx];
+        let next = if back {
+            tab.older_results.pop()
+        } else {
+            tab.newer_results.pop()
+        };
+        let Some(results) = next else {
+            let message = if back {
+                "No earlier results in this tab"
+            } else {
+                "No newer results in this tab"
+            };
+            self.set_status(message.to_string(), StatusLevel::Info);
+            return;
+        };
+        let current = tab.results_viewer.take_results();
+        if back {
+            tab.newer_results.extend(current);
+        } else {
+            tab.older_results.extend(current);
+        }
+        tab.explain_viewer = None;
+        tab.pagination = None;
+        tab.results_viewer.set_results(results);
+        tab.results_viewer.set_pagination(None);
+        let position = tab.older_results.len() + 1;
+        let total = position + tab.newer_results.len();
+        self.mask_results(idx);
+        self.focus = PanelFocus::ResultsViewer;
+        self.set_status(
+            format!("Results {} of {} in this tab", position, total),
+            StatusLevel::Info,
+        );
+    }
+
+    /// Mask the `mask_columns` columns of a tab's new results, unless
+    /// `/reveal` turned masking off
+    fn mask_results(&mut self, idx: usize) {
+        if self.masks_revealed || self.mask_rules.is_empty() {
+            return;
+        }
+        let viewer = &mut self.tabs[idx].results_viewer;
+        let Some(results) = viewer.results() else {
+            return;
+        };
+        let columns = self.mask_rules.columns(&results.columns);
+        viewer.mask_columns(columns);
+    }
+
+    /// Turn masking off for every tab, or back on
+    fn toggle_reveal(&mut self) {
+        if self.mask_rules.is_empty() {
+            self.set_status(
+                "Nothing is masked — set mask_columns in config.toml".to_string(),
+                StatusLevel::Info,
+            );
+            return;
+        }
+        self.masks_revealed = !self.masks_revealed;
+        if self.masks_revealed {
+            for tab in &mut self.tabs {
+                tab.results_viewer.reveal();
+            }
+            self.set_status(
+                "Masked values revealed — /reveal masks them again".to_string(),
+                StatusLevel::Warning,
+            );
+        } else {
+            for idx in 0..self.tabs.len() {
+                self.mask_results(idx);
+            }
+            self.set_status("Values masked again".to_string(), StatusLevel::Success);
+        }
+    }
+
+    /// Run BEGIN/COMMIT/ROLLBACK on the active tab from the command bar.
+    /// Skips statements that would be a no-op for the current state and
+    /// keeps them out of query history.
+    fn run_transaction_control(&mut self, sql: &str) -> Action {
+        if self.tab().query_running {
+            self.set_status(
+                "Wait for the running query to finish".to_string(),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        let Some(new_state) = detect_transaction_intent(sql) else {
+            return Action::None;
+        };
+        match (self.tab().transaction_state, new_state) {
+            (
+                TransactionState::InTransaction | TransactionState::Failed,
+                TransactionState::InTransaction,
+            ) => {
+                self.set_status("Already in a transaction".to_string(), StatusLevel::Warning);
+                return Action::None;
+            }
+            (TransactionState::Idle, TransactionState::Idle) => {
+                self.set_status(
+                    "No transaction in progress".to_string(),
+                    StatusLevel::Warning,
+                );
+                return Action::None;
+            }
+            _ => {}
+        }
+
+        self.note_read_write(self.active_tab, sql);
+        let tab = self.tab_mut();
+        tab.transaction_state = new_state;
+        if new_state == TransactionState::Idle {
+            tab.snapshot = None;
+        }
+        tab.query_running = true;
+        tab.query_start = Some(std::time::Instant::now());
+        tab.pagination = None;
+        let tab_id = tab.id;
+        self.set_status(format!("Executing {}...", sql), StatusLevel::Info);
+        Action::ExecuteQuery {
+            sql: sql.to_string(),
+            params: Vec::new(),
+            tab_id,
+            timeout_ms: self.query_timeout_ms,
+            max_rows: 0,
+        }
+    }
+
+    /// Reference to the active tab
+    pub fn tab(&self) -> &Tab {
+        &self.tabs[self.active_tab]
+    }
+
     /// Mutable reference to the active tab
     pub fn tab_mut(&mut self) -> &mut Tab {
         &mut self.tabs[self.active_tab]
@@ -558,6 +2226,15 @@ impl App {
         self.tabs.iter().position(|t| t.id == id)
     }
 
+    /// An empty tab with the editor and number settings applied
+    fn blank_tab(&self, id: usize) -> Tab {
+        let mut tab = Tab::new(id);
+        tab.editor.set_vim(self.vim_editing);
+        tab.editor.set_auto_indent(self.auto_indent);
+        tab.results_viewer.set_number_format(self.number_format);
+        tab
+    }
+
     /// Open a new tab and switch to it. Returns false if at capacity.
     fn new_tab(&mut self) -> bool {
         if self.tabs.len() >= self.max_tabs {
@@ -565,12 +2242,37 @@ impl App {
         }
         let id = self.next_tab_id;
         self.next_tab_id += 1;
-        self.tabs.push(Tab::new(id));
+        self.tabs.push(self.blank_tab(id));
         self.active_tab = self.tabs.len() - 1;
         self.focus = PanelFocus::QueryEditor;
         true
     }
 
+    /// The current tab's draft: its editor content, or what the editor held
+    /// before it started browsing history
+    fn current_draft(&self) -> String {
+        let tab = self.tab();
+        match tab.history_cursor.draft() {
+            Some(draft) => draft.to_string(),
+            None => tab.editor.get_content(),
+        }
+    }
+
+    /// Open `sql` in a fresh tab and execute it there, leaving the current
+    /// tab's editor and results untouched. Goes through the same read-only
+    /// and destructive-query checks as F5.
+    pub fn run_in_new_tab(&mut self, sql: &str) -> Action {
+        if !self.new_tab() {
+            self.set_status(
+                format!("Maximum {} tabs open", self.max_tabs),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        self.tab_mut().editor.set_content(sql.to_string());
+        self.execute_key_action(KeyAction::ExecuteQuery)
+    }
+
     /// Close the active tab. Returns false if it's the last tab.
     fn close_tab(&mut self) -> bool {
         if self.tabs.len() <= 1 {
@@ -583,6 +2285,50 @@ impl App {
         true
     }
 
+    /// Close the tabs other than the active one (or only those to its
+    /// right), keeping any with a running query or an open transaction.
+    fn close_other_tabs(&mut self, right_only: bool) -> Action {
+        let active_id = self.tab().id;
+        let first = if right_only { self.active_tab + 1 } else { 0 };
+        let mut tab_ids = Vec::new();
+        let mut kept = 0;
+        let mut index = 0;
+        self.tabs.retain(|tab| {
+            index += 1;
+            if index <= first || tab.id == active_id {
+                return true;
+            }
+            if tab.query_running || tab.transaction_state != TransactionState::Idle {
+                kept += 1;
+                return true;
+            }
+            tab_ids.push(tab.id);
+            false
+        });
+        self.active_tab = self.tab_index_by_id(active_id).unwrap_or(0);
+
+        let closed = match tab_ids.len() {
+            0 if kept == 0 => "No tabs to close".to_string(),
+            1 => "Closed 1 tab".to_string(),
+            n => format!("Closed {} tabs", n),
+        };
+        match kept {
+            0 => self.set_status(closed, StatusLevel::Info),
+            n => self.set_status(
+                format!(
+                    "{}; kept {} with a running query or open transaction",
+                    closed, n
+                ),
+                StatusLevel::Warning,
+            ),
+        }
+        if tab_ids.is_empty() {
+            Action::None
+        } else {
+            Action::TabsClosed { tab_ids }
+        }
+    }
+
     /// Cycle to the next tab, wrapping around.
     fn next_tab(&mut self) {
         if self.tabs.len() > 1 {
@@ -590,15 +2336,25 @@ impl App {
         }
     }
 
-    /// Recompute completions based on current cursor context.
-    fn update_completions(&mut self) {
+    /// Recompute completions based on current cursor context. Returns the
+    /// lookup to run when a column's values are needed and not loaded yet.
+    fn update_completions(&mut self) -> Action {
         let idx = self.active_tab;
+        // Vim's normal and visual mode don't type, so there's nothing to complete
+        if self.tabs[idx]
+            .editor
+            .vim_mode()
+            .is_some_and(|mode| mode != VimMode::Insert)
+        {
+            self.clear_completions();
+            return Action::None;
+        }
         let (line_idx, char_col) = self.tabs[idx].editor.cursor();
         let line = match self.tabs[idx].editor.line(line_idx) {
             Some(l) => l.to_string(),
             None => {
                 self.clear_completions();
-                return;
+                return Action::None;
             }
         };
 
@@ -615,10 +2371,15 @@ impl App {
             let next = bytes[col];
             if next.is_ascii_alphanumeric() || next == b'_' {
                 self.clear_completions();
-                return;
+                return Action::None;
             }
         }
 
+        // Inside `column = '`: the column's values
+        if let Some(value) = completer::value_context(&line, col) {
+            return self.complete_value(value.qualifier, value.column, value.prefix);
+        }
+
         let prefix = completer::word_before_cursor(&line, col);
         let prefix_start = col - prefix.len();
 
@@ -628,7 +2389,7 @@ impl App {
         // Allow empty prefix when dot-qualified (e.g., "users.")
         if prefix.is_empty() && dot_qual.is_none() {
             self.clear_completions();
-            return;
+            return Action::None;
         }
 
         let schema = self.tree_browser.schema();
@@ -648,8 +2409,85 @@ impl App {
             completer::detect_context(&text_before, None, schema)
         };
 
+        let skip_unreadable = self.skip_unreadable_tables;
+        self.tabs[idx]
+            .completer
+            .set_skip_unreadable(skip_unreadable);
         let ghost = self.tabs[idx].completer.recompute(prefix, context, schema);
         self.tabs[idx].editor.set_ghost_text(ghost);
+        Action::None
+    }
+
+    /// Offer the values of the column a string literal is compared to, or
+    /// ask for them the first time
+    fn complete_value(&mut self, qualifier: Option<&str>, column: &str, prefix: &str) -> Action {
+        let content = self.tab().editor.get_content();
+        let Some(key) = self.value_column(&content, qualifier, column) else {
+            self.clear_completions();
+            return Action::None;
+        };
+        let idx = self.active_tab;
+        match self.column_values.get(&key) {
+            Some(values) => {
+                let ghost = self.tabs[idx].completer.recompute_values(prefix, values);
+                self.tabs[idx].editor.set_ghost_text(ghost);
+                Action::None
+            }
+            None if self.connection_name.is_some() => {
+                self.clear_completions();
+                let (schema, table, column) = key.clone();
+                self.column_values.insert(key, Vec::new());
+                Action::LoadColumnValues {
+                    tab_id: self.tab().id,
+                    schema,
+                    table,
+                    column,
+                }
+            }
+            None => {
+                self.clear_completions();
+                Action::None
+            }
+        }
+    }
+
+    /// The (schema, table, column) a column in `sql` refers to: the first
+    /// table in scope with that column, or the one `qualifier` names
+    fn value_column(
+        &self,
+        sql: &str,
+        qualifier: Option<&str>,
+        column: &str,
+    ) -> Option<(String, String, String)> {
+        let tree = self.tree_browser.schema()?;
+        for (name, alias) in completer::tables_in_scope(sql) {
+            let (schema_name, table_name) = match name.rsplit_once('.') {
+                Some((s, t)) => (Some(s.trim_matches('"')), t.trim_matches('"')),
+                None => (None, name.trim_matches('"')),
+            };
+            if let Some(q) = qualifier
+                && !alias.is_some_and(|a| a.eq_ignore_ascii_case(q))
+                && !table_name.eq_ignore_ascii_case(q)
+            {
+                continue;
+            }
+            let found = tree
+                .schemas
+                .iter()
+                .filter(|s| schema_name.is_none_or(|n| s.name.eq_ignore_ascii_case(n)))
+                .flat_map(|s| s.tables.iter().map(move |t| (s, t)))
+                .filter(|(_, t)| t.name.eq_ignore_ascii_case(table_name))
+                .find_map(|(s, t)| {
+                    t.columns
+                        .iter()
+                        .find(|c| c.name.eq_ignore_ascii_case(column))
+                        .map(|c| (s.name.clone(), t.name.clone(), c.name.clone()))
+                });
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
     }
 
     /// Clear completion state and editor ghost text.
@@ -677,26 +2515,527 @@ impl App {
         };
     }
 
-    fn open_command_bar(&mut self) {
-        self.previous_focus = self.focus;
-        self.focus = PanelFocus::CommandBar;
-        self.command_bar.activate();
+    fn open_command_bar(&mut self) {
+        self.previous_focus = self.focus;
+        self.focus = PanelFocus::CommandBar;
+        self.command_bar.activate();
+    }
+
+    pub fn set_status(&mut self, message: String, level: StatusLevel) {
+        self.messages.push(level, &message);
+        self.status_message = Some(StatusMessage { message, level });
+    }
+
+    /// Whether a destructive-query confirmation prompt is active
+    pub fn is_confirm_pending(&self) -> bool {
+        self.pending_confirm_sql.is_some()
+    }
+
+    /// Show the connection picker dialog
+    pub fn show_connection_dialog(&mut self) {
+        self.connecting_tab = None;
+        self.previous_focus = self.focus;
+        self.focus = PanelFocus::ConnectionDialog;
+        self.connection_dialog.show();
+    }
+
+    /// Bind the active tab to a saved connection or URL, or pick one in the
+    /// connection dialog when no target is given
+    fn connect_tab(&mut self, target: Option<String>) -> Action {
+        let tab = self.tab();
+        if tab.query_running || tab.transaction_state != TransactionState::Idle {
+            self.set_status(
+                "Finish this tab's query or transaction before switching its connection"
+                    .to_string(),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        let tab_id = tab.id;
+        let Some(target) = target else {
+            self.show_connection_dialog();
+            self.connecting_tab = Some(tab_id);
+            return Action::None;
+        };
+        let config = if target.contains("://") {
+            ConnectionConfig::from_url(&target).map_err(|e| e.to_string())
+        } else {
+            crate::config::find_connection(&target).map_err(|e| e.to_string())
+        };
+        match config {
+            Ok(config) => Action::ConnectTab { tab_id, config },
+            Err(e) => {
+                self.set_status(
+                    format!("Could not resolve '{}': {}", target, e),
+                    StatusLevel::Error,
+                );
+                Action::None
+            }
+        }
+    }
+
+    /// Switch the main connection to another database on the same server.
+    /// Waits until no tab on it has a query running or a transaction
+    /// open, and no background job is running.
+    fn use_database(&mut self, database: String) -> Action {
+        if self.connection_name.is_none() {
+            self.set_status("Not connected".to_string(), StatusLevel::Warning);
+            return Action::None;
+        }
+        if let Some(conn) = &self.tab().connection {
+            self.set_status(
+                format!(
+                    "This tab is on {}; switch to a tab on the main connection to /use",
+                    conn.name
+                ),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        let busy = self.tabs.iter().any(|tab| {
+            tab.connection.is_none()
+                && (tab.query_running || tab.transaction_state != TransactionState::Idle)
+        });
+        if busy || !self.jobs.is_empty() {
+            self.set_status(
+                "Finish running queries, transactions, and jobs before switching databases"
+                    .to_string(),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        let current = self
+            .tree_browser
+            .schema()
+            .and_then(|s| s.databases.iter().find(|d| d.is_current));
+        if current.is_some_and(|d| d.name == database) {
+            self.set_status(format!("Already using {}", database), StatusLevel::Info);
+            return Action::None;
+        }
+        Action::UseDatabase {
+            database,
+            tab_id: self.tab().id,
+        }
+    }
+
+    /// Start listening for NOTIFY on a channel. Unquoted names fold to
+    /// lowercase, as in SQL.
+    fn listen(&mut self, channel: &str) -> Action {
+        if self.connection_name.is_none() {
+            self.set_status("Not connected".to_string(), StatusLevel::Warning);
+            return Action::None;
+        }
+        Action::Listen {
+            channel: channel_name(channel),
+        }
+    }
+
+    /// Stop listening on a channel, or on every channel
+    fn unlisten(&mut self, channel: Option<&str>) -> Action {
+        let channel = channel.map(channel_name);
+        let listening = match &channel {
+            Some(channel) => self.notifications.channels().contains(channel),
+            None => !self.notifications.channels().is_empty(),
+        };
+        if !listening {
+            let message = match channel {
+                Some(channel) => format!("Not listening on {}", channel),
+                None => "Not listening".to_string(),
+            };
+            self.set_status(message, StatusLevel::Warning);
+            return Action::None;
+        }
+        Action::Unlisten { channel }
+    }
+
+    /// Fetch the session list again
+    fn load_sessions(&mut self) -> Action {
+        Action::LoadSessions {
+            tab_id: self.tab().id,
+        }
+    }
+
+    /// Show the statement timeout, or change it on every connection
+    fn set_statement_timeout(&mut self, value: Option<&str>) -> Action {
+        let Some(value) = value else {
+            self.set_status(
+                format!(
+                    "Statement timeout: {}",
+                    format_timeout(self.statement_timeout_ms)
+                ),
+                StatusLevel::Info,
+            );
+            return Action::None;
+        };
+        let Some(timeout_ms) = parse_timeout(value) else {
+            self.set_status(
+                format!(
+                    "Invalid timeout '{}' \u{2014} e.g. /timeout 30s, 500ms, 5m, off",
+                    value
+                ),
+                StatusLevel::Error,
+            );
+            return Action::None;
+        };
+        self.statement_timeout_ms = timeout_ms;
+        self.set_status(
+            format!("Statement timeout set to {}", format_timeout(timeout_ms)),
+            StatusLevel::Success,
+        );
+        if self.connection_name.is_none() {
+            return Action::None;
+        }
+        Action::SetStatementTimeout { timeout_ms }
+    }
+
+    /// Fetch a new health snapshot, unless one is already on its way
+    fn refresh_dashboard(&mut self) -> Action {
+        if !self.dashboard.start_refresh() {
+            return Action::None;
+        }
+        Action::LoadHealth {
+            tab_id: self.tab().id,
+        }
+    }
+
+    /// Fetch the publications and subscriptions again
+    fn load_replication(&mut self) -> Action {
+        Action::LoadReplication {
+            tab_id: self.tab().id,
+        }
+    }
+
+    /// When the open dashboard is due a refresh
+    pub fn next_dashboard_refresh(&self) -> Option<std::time::Instant> {
+        self.dashboard.next_refresh()
+    }
+
+    /// The dashboard's refresh, when it's due at `now`
+    pub fn poll_dashboard(&mut self, now: std::time::Instant) -> Option<Action> {
+        if self.next_dashboard_refresh()? > now {
+            return None;
+        }
+        Some(self.refresh_dashboard())
+    }
+
+    /// Cancel the selected session's query, or terminate the session on a
+    /// second press
+    fn signal_session(&mut self, terminate: bool) -> Action {
+        let Some(session) = self.sessions.selected() else {
+            return Action::None;
+        };
+        let pid = session.pid;
+        let user = session.user.clone().unwrap_or_default();
+        if self.tab_read_only() {
+            self.set_status(
+                "Read-only mode: cancelling sessions is blocked".to_string(),
+                StatusLevel::Error,
+            );
+            return Action::None;
+        }
+        if terminate && !self.sessions.confirm_terminate(pid) {
+            let key = self
+                .keymap
+                .keys_for_action(Some(PanelFocus::Sessions), KeyAction::TerminateSession)
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            self.set_status(
+                format!(
+                    "Terminate session {} ({})? Press {} again to end it",
+                    pid, user, key
+                ),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        Action::SignalBackend {
+            tab_id: self.tab().id,
+            pid,
+            terminate,
+        }
+    }
+
+    /// Follow a table on the active tab (`table [by column] [WHERE ..]`),
+    /// or stop following when no arguments are given
+    fn tail(&mut self, args: Option<&str>) -> Action {
+        let Some(args) = args else {
+            if !self.stop_tail(self.active_tab) {
+                self.set_status(
+                    "Usage: /tail <table> [by <column>] [WHERE <condition>]".to_string(),
+                    StatusLevel::Warning,
+                );
+            }
+            return Action::None;
+        };
+        if self.connection_name.is_none() {
+            self.set_status("Not connected".to_string(), StatusLevel::Warning);
+            return Action::None;
+        }
+        if self.tab().query_running {
+            self.set_status(
+                "Wait for the running query to finish".to_string(),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        let args = match tail::parse_tail(args) {
+            Ok(args) => args,
+            Err(e) => {
+                self.set_status(e, StatusLevel::Warning);
+                return Action::None;
+            }
+        };
+        let Some((schema, table, _)) = self.tree_browser.find_relation(&args.table) else {
+            self.set_status(
+                format!(
+                    "No table or view named '{}' in the loaded schemas",
+                    args.table
+                ),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        };
+        let key = match tail::tail_key(table, args.by.as_deref()) {
+            Ok(key) => key,
+            Err(e) => {
+                self.set_status(e, StatusLevel::Warning);
+                return Action::None;
+            }
+        };
+        let mut tail = TailState::new(schema, &table.name, &key, args.filter);
+        tail.in_flight = true;
+        let action = Action::PollTail {
+            tab_id: self.tab().id,
+            sql: tail.sql(),
+            timeout_ms: self.query_timeout_ms,
+        };
+        self.set_status(
+            format!(
+                "Following {} by {} \u{2014} /tail stops",
+                tail.relation, key
+            ),
+            StatusLevel::Info,
+        );
+        self.tab_mut().tail = Some(tail);
+        action
+    }
+
+    /// Open a tab holding a read-only, repeatable-read transaction on `table`
+    /// (the tree's selected table when none is named). BEGIN runs first;
+    /// the tab's first SELECT follows it and fixes the snapshot.
+    fn take_snapshot(&mut self, table: Option<&str>) -> Action {
+        if self.connection_name.is_none() {
+            self.set_status("Not connected".to_string(), StatusLevel::Warning);
+            return Action::None;
+        }
+        let target = match table {
+            Some(name) => self
+                .tree_browser
+                .find_relation(name)
+                .map(|(schema, t, _)| (schema, t)),
+            None => self.tree_browser.selected_table(),
+        };
+        let Some((schema, t)) = target else {
+            let message = match table {
+                Some(name) => format!("No table or view named '{}' in the loaded schemas", name),
+                None => "Select a table in the tree or name one: /snapshot <table>".to_string(),
+            };
+            self.set_status(message, StatusLevel::Warning);
+            return Action::None;
+        };
+        let relation = format!("{}.{}", schema, t.name);
+        let sql = snapshot_sql(schema, &t.name);
+        if !self.new_tab() {
+            self.set_status(
+                format!("Maximum {} tabs open", self.max_tabs),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        self.set_status(
+            format!("Taking a snapshot of {}...", relation),
+            StatusLevel::Info,
+        );
+        let tab = self.tab_mut();
+        tab.editor.set_content(sql.clone());
+        tab.transaction_state = TransactionState::InTransaction;
+        tab.query_running = true;
+        tab.query_start = Some(std::time::Instant::now());
+        tab.snapshot = Some(Snapshot {
+            relation,
+            taken_at: chrono::Local::now(),
+            pending: Some(sql),
+        });
+        Action::ExecuteQuery {
+            sql: SNAPSHOT_BEGIN.to_string(),
+            params: Vec::new(),
+            tab_id: tab.id,
+            timeout_ms: self.query_timeout_ms,
+            max_rows: 0,
+        }
+    }
+
+    /// Run a snapshot tab's first query now that its transaction is open
+    fn run_snapshot_query(&mut self, idx: usize, sql: String) -> Action {
+        let history_entry = self.history.push(&sql);
+        let tab = &mut self.tabs[idx];
+        tab.query_running = true;
+        tab.query_start = Some(std::time::Instant::now());
+        tab.history_entry = history_entry;
+        tab.pagination = None;
+        let tab_id = tab.id;
+        if let Some(snapshot) = tab.snapshot.as_mut() {
+            snapshot.taken_at = chrono::Local::now();
+            let message = format!(
+                "Snapshot of {} \u{2014} queries in this tab see it until /commit or /rollback",
+                snapshot.relation
+            );
+            self.set_status(message, StatusLevel::Info);
+        }
+        Action::ExecuteQuery {
+            sql,
+            params: Vec::new(),
+            tab_id,
+            timeout_ms: self.query_timeout_ms,
+            max_rows: self.max_result_rows,
+        }
+    }
+
+    /// Stop a tab following its table. Returns whether it was.
+    fn stop_tail(&mut self, idx: usize) -> bool {
+        let Some(tail) = self.tabs[idx].tail.take() else {
+            return false;
+        };
+        self.set_status(
+            format!("Stopped following {}", tail.relation),
+            StatusLevel::Info,
+        );
+        true
+    }
+
+    /// When the next followed table is due a poll
+    pub fn next_tail_poll(&self) -> Option<std::time::Instant> {
+        self.tabs
+            .iter()
+            .filter(|t| !t.query_running)
+            .filter_map(|t| t.tail.as_ref())
+            .filter(|tail| !tail.in_flight)
+            .map(|tail| tail.next_poll)
+            .min()
+    }
+
+    /// Polls for the followed tables that are due at `now`
+    pub fn poll_tails(&mut self, now: std::time::Instant) -> Vec<Action> {
+        let timeout_ms = self.query_timeout_ms;
+        let mut actions = Vec::new();
+        for tab in self.tabs.iter_mut().filter(|t| !t.query_running) {
+            let Some(tail) = tab.tail.as_mut() else {
+                continue;
+            };
+            if tail.in_flight || tail.next_poll > now {
+                continue;
+            }
+            tail.in_flight = true;
+            actions.push(Action::PollTail {
+                tab_id: tab.id,
+                sql: tail.sql(),
+                timeout_ms,
+            });
+        }
+        actions
+    }
+
+    /// Show a followed table's first rows, or append the ones past them
+    fn tail_rows(&mut self, tab_id: usize, results: QueryResults) {
+        let Some(idx) = self.tab_index_by_id(tab_id) else {
+            return;
+        };
+        let tab = &mut self.tabs[idx];
+        // Stopped while the poll ran
+        let Some(tail) = tab.tail.as_mut() else {
+            return;
+        };
+        let started = tail.started;
+        tail.in_flight = false;
+        tail.next_poll = std::time::Instant::now() + tail::TAIL_INTERVAL;
+        tail.record(&results);
+        if started {
+            tab.results_viewer.append_rows(results.rows);
+            return;
+        }
+        let limit = self.result_history;
+        tab.archive_results(limit);
+        tab.explain_viewer = None;
+        tab.pagination = None;
+        tab.results_viewer.set_results(results);
+        tab.results_viewer.set_pagination(None);
+        self.mask_results(idx);
+        if idx == self.active_tab {
+            self.focus = PanelFocus::ResultsViewer;
+        }
+    }
+
+    /// Record the channels the listener connection is on after a
+    /// `/listen` or `/unlisten`
+    pub fn set_listen_channels(&mut self, channels: Vec<String>) {
+        let message = if channels.is_empty() {
+            "Stopped listening".to_string()
+        } else {
+            format!(
+                "Listening on {} \u{2014} /listen shows notifications",
+                channels.join(", ")
+            )
+        };
+        self.notifications.set_channels(channels);
+        self.set_status(message, StatusLevel::Success);
+    }
+
+    /// Show the schema of the database the main connection switched to.
+    /// Tabs keep their editors and results; those on the main connection
+    /// start with a fresh session.
+    pub fn switch_database(&mut self, schema: crate::db::schema::SchemaTree) {
+        self.tree_browser.set_schema(schema);
+        // The listener connection went with the old database
+        self.notifications.set_channels(Vec::new());
+        for tab in self.tabs.iter_mut().filter(|t| t.connection.is_none()) {
+            tab.transaction_state = TransactionState::Idle;
+            tab.schema_changed = false;
+        }
     }
 
-    pub fn set_status(&mut self, message: String, level: StatusLevel) {
-        self.status_message = Some(StatusMessage { message, level });
+    /// Record that a tab is now connected to `name` rather than the main
+    /// connection
+    pub fn bind_tab_connection(&mut self, tab_id: usize, name: String, read_only: bool) {
+        let read_only = self.default_read_only || read_only;
+        if let Some(idx) = self.tab_index_by_id(tab_id) {
+            let tab = &mut self.tabs[idx];
+            tab.connection = Some(TabConnection { name, read_only });
+            tab.transaction_state = TransactionState::Idle;
+        }
     }
 
-    /// Whether a destructive-query confirmation prompt is active
-    pub fn is_confirm_pending(&self) -> bool {
-        self.pending_confirm_sql.is_some()
+    /// Whether writes are blocked on the active tab: its own connection's
+    /// setting when bound, otherwise the main connection's
+    pub fn tab_read_only(&self) -> bool {
+        self.tab()
+            .connection
+            .as_ref()
+            .map_or(self.read_only, |c| c.read_only)
     }
 
-    /// Show the connection picker dialog
-    pub fn show_connection_dialog(&mut self) {
-        self.previous_focus = self.focus;
-        self.focus = PanelFocus::ConnectionDialog;
-        self.connection_dialog.show();
+    /// Whether writes are blocked on the active tab right now: it is
+    /// read-only and not inside a `BEGIN READ WRITE` transaction
+    pub fn tab_writes_blocked(&self) -> bool {
+        let tab = self.tab();
+        self.tab_read_only() && !(tab.read_write && tab.transaction_state != TransactionState::Idle)
+    }
+
+    /// Remember whether a BEGIN about to run on a tab asked for READ WRITE
+    fn note_read_write(&mut self, idx: usize, sql: &str) {
+        if TransactionControl::parse(sql) == Some(TransactionControl::Begin) {
+            self.tabs[idx].read_write = is_read_write_begin(sql);
+        }
     }
 
     /// Apply a new connection (after successful connect + schema load).
@@ -710,16 +3049,52 @@ impl App {
         schema: crate::db::schema::SchemaTree,
     ) {
         self.connection_name = Some(name.clone());
+        self.history.set_connection(Some(name.clone()));
         self.is_saved_connection = saved;
         // Per-connection read_only overrides global default
         self.read_only = self.default_read_only || connection_read_only;
+        self.apply_connection_settings(&name);
         self.tree_browser.set_schema(schema);
         self.load_saved_queries_for(&name, saved);
         // Reset all tabs to fresh state (transaction_state resets via Tab::new)
-        self.tabs = vec![Tab::new(0)];
+        self.tabs = vec![self.blank_tab(0)];
         self.active_tab = 0;
         self.next_tab_id = 1;
         self.focus = PanelFocus::QueryEditor;
+        // Jobs on the previous connection no longer deliver results
+        self.jobs.clear();
+        self.notifications.set_channels(Vec::new());
+        self.column_values.clear();
+    }
+
+    /// The `statement_timeout` a connection starts with: its
+    /// `[connections.<name>.settings]` override, or the global setting
+    pub fn statement_timeout_for(&self, connection_name: &str) -> u64 {
+        self.settings
+            .for_connection(connection_name)
+            .statement_timeout_ms
+    }
+
+    /// Apply `[connections.<name>.settings]` overrides on top of the global
+    /// settings. Connections without overrides get the global values back.
+    fn apply_connection_settings(&mut self, connection_name: &str) {
+        let merged = self.settings.for_connection(connection_name);
+        self.tree_browser.set_preview_rows(merged.preview_rows);
+        self.query_timeout_ms = merged.query_timeout_ms;
+        self.statement_timeout_ms = merged.statement_timeout_ms;
+        self.max_result_rows = merged.max_result_rows;
+        self.confirm_destructive = merged.confirm_destructive;
+        self.cost_guard = CostGuard {
+            rows: merged.cost_guard_rows,
+            cost: merged.cost_guard_cost,
+            action: CostGuardAction::parse(&merged.cost_guard_action)
+                .unwrap_or(CostGuardAction::Warn),
+        };
+        self.explain_visual = merged.explain_visual;
+        self.explain_options = ExplainOptions::parse(&merged.explain_options).unwrap_or_default();
+        self.mask_rules = MaskRules::new(&merged.mask_columns);
+        self.masks_revealed = false;
+        self.theme = Theme::by_name(&merged.theme).unwrap_or_default();
     }
 
     /// Load saved queries into the tree browser for a saved connection
@@ -734,17 +3109,31 @@ impl App {
         }
     }
 
-    fn start_export(&mut self, format: ExportFormat) {
-        if self.tab().results_viewer.results().is_none() {
+    /// Prompt for an export file name, prefilled from the `export_filename`
+    /// template in the connection's last export directory, followed by
+    /// `options` (e.g. `--delimiter ';'`)
+    fn start_export(&mut self, format: ExportFormat, options: &str) {
+        let Some(results) = self.tab().results_viewer.results() else {
             self.set_status("No results to export".to_string(), StatusLevel::Warning);
             return;
-        }
-        let now = chrono::Local::now();
-        let filename = format!(
-            "export_{}.{}",
-            now.format("%Y-%m-%d_%H%M%S"),
-            format.extension()
+        };
+        let table = results.source.as_ref().map(|s| s.table.as_str());
+        let connection = self.connection_name.as_deref();
+        let name = crate::export::file_name(
+            &self.export_filename,
+            connection,
+            table,
+            format,
+            &chrono::Local::now(),
         );
+        let mut filename = match connection.and_then(|c| self.export_dirs.get(c)) {
+            Some(dir) => dir.join(name).display().to_string(),
+            None => name,
+        };
+        if !options.is_empty() {
+            filename.push(' ');
+            filename.push_str(options);
+        }
         self.pending_export = Some(format);
         self.previous_focus = self.focus;
         self.focus = PanelFocus::CommandBar;
@@ -752,19 +3141,33 @@ impl App {
             .activate_with_prompt("Save as: ".to_string(), filename);
     }
 
-    fn execute_export(&mut self, format: ExportFormat, path: &str) {
+    /// Write the active results to the path in `input`, which may end in
+    /// `| column=cast, ...` overrides (see `ColumnCasts::parse`). CSV and
+    /// TSV also take `--delimiter`, `--quote`, and `--no-header` (see
+    /// `CsvOptions::extract`); with only those, prompts for the path.
+    fn execute_export(&mut self, format: ExportFormat, input: &str) {
+        let Some((path, casts, options)) = self.export_target(format, input) else {
+            return;
+        };
         let Some(results) = self.tab().results_viewer.results() else {
-            self.set_status("No results to export".to_string(), StatusLevel::Warning);
             return;
         };
+        let path = path.as_str();
 
         let data = match format {
-            ExportFormat::Csv => crate::export::to_csv(results),
-            ExportFormat::Json => crate::export::to_json(results),
+            ExportFormat::Csv | ExportFormat::Tsv => {
+                crate::export::to_delimited(results, &casts, options).into_bytes()
+            }
+            ExportFormat::Json => crate::export::to_json_with_casts(results, &casts).into_bytes(),
+            ExportFormat::Markdown => {
+                crate::export::to_markdown_with_casts(results, &casts).into_bytes()
+            }
+            ExportFormat::Xlsx => crate::export::to_xlsx_with_casts(results, &casts),
         };
 
         match std::fs::write(path, &data) {
             Ok(()) => {
+                self.count(Counter::BytesExported, data.len() as u64);
                 let ext = format.extension().to_uppercase();
                 self.set_status(
                     format!("Exported {} as {} ({} bytes)", path, ext, data.len()),
@@ -777,6 +3180,629 @@ impl App {
         }
     }
 
+    /// Split export input into the path, column casts, and CSV options.
+    /// Problems go to the status bar; given only options, prompts for the
+    /// path. Returns None unless the export should go ahead.
+    fn export_target(
+        &mut self,
+        format: ExportFormat,
+        input: &str,
+    ) -> Option<(String, ColumnCasts, CsvOptions)> {
+        let mut options = match format {
+            ExportFormat::Tsv => CsvOptions::tsv(),
+            _ => CsvOptions::csv(),
+        };
+        let input = match options.extract(input) {
+            // No options: the input is used as typed
+            Ok(None) => input.to_string(),
+            Ok(Some(_)) if !matches!(format, ExportFormat::Csv | ExportFormat::Tsv) => {
+                self.set_status(
+                    "Export failed: delimiter options only apply to csv and tsv".to_string(),
+                    StatusLevel::Error,
+                );
+                return None;
+            }
+            Ok(Some(rest)) if rest.is_empty() => {
+                self.start_export(format, input.trim());
+                return None;
+            }
+            Ok(Some(rest)) => rest,
+            Err(e) => {
+                self.set_status(format!("Export failed: {}", e), StatusLevel::Error);
+                return None;
+            }
+        };
+
+        let Some(results) = self.tab().results_viewer.results() else {
+            self.set_status("No results to export".to_string(), StatusLevel::Warning);
+            return None;
+        };
+
+        // "path | col=cast, ..." applies per-column overrides
+        let (path, casts) = match input.split_once('|') {
+            Some((path, spec)) => match ColumnCasts::parse(spec, &results.columns) {
+                Ok(casts) => (path.trim(), casts),
+                Err(e) => {
+                    self.set_status(format!("Export failed: {}", e), StatusLevel::Error);
+                    return None;
+                }
+            },
+            None => (input.trim(), ColumnCasts::default()),
+        };
+        let path = path.to_string();
+        self.remember_export_dir(&path);
+        Some((path, casts, options))
+    }
+
+    /// Keep the directory of an export path as the connection's starting
+    /// point for the next export prompt
+    fn remember_export_dir(&mut self, path: &str) {
+        let Some(connection) = self.connection_name.clone() else {
+            return;
+        };
+        let dir = std::path::Path::new(path)
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .and_then(|p| std::path::absolute(p).ok());
+        if let Err(e) = self.export_dirs.remember(&connection, dir) {
+            self.messages
+                .push(StatusLevel::Warning, &format!("export_dirs: {}", e));
+        }
+    }
+
+    /// The query behind a preview or paginated result, which `/export all`
+    /// and `/export copy` re-run without its LIMIT
+    fn full_query(&mut self) -> Option<PaginationState> {
+        let pagination = self.tab().pagination.clone();
+        if pagination.is_none() {
+            let message = if self.tab().results_viewer.results().is_some() {
+                "Export all needs a preview or paginated result — /export writes the loaded rows"
+            } else {
+                "No results to export"
+            };
+            self.set_status(message.to_string(), StatusLevel::Warning);
+        }
+        pagination
+    }
+
+    /// Re-run the query behind a preview or paginated result without its
+    /// LIMIT on a background connection, streaming every row to the file
+    /// in `input` (same syntax as `/export`) instead of loading it
+    fn execute_export_all(&mut self, format: ExportFormat, input: &str) -> Action {
+        let Some(pagination) = self.full_query() else {
+            return Action::None;
+        };
+        let Some((path, casts, options)) = self.export_target(format, input) else {
+            if self.pending_export.is_some() {
+                self.pending_export_mode = ExportMode::All;
+            }
+            return Action::None;
+        };
+        let mut stream = match RowStream::new(format, casts, options) {
+            Ok(stream) => stream,
+            Err(e) => {
+                self.set_status(format!("Export failed: {}", e), StatusLevel::Error);
+                return Action::None;
+            }
+        };
+        stream.mask_columns(self.tab().results_viewer.masked_columns().to_vec());
+        let job_id = self.jobs.start(&pagination.original_sql, None);
+        self.set_status(
+            format!(
+                "Exporting all rows to {} as job #{} — /jobs lists running jobs",
+                path, job_id
+            ),
+            StatusLevel::Info,
+        );
+        Action::ExportAll {
+            job_id,
+            sql: pagination.original_sql,
+            params: pagination.params,
+            path,
+            stream,
+        }
+    }
+
+    /// Like `/export all`, but the server formats the rows itself with
+    /// `COPY (query) TO STDOUT` — the fastest path for millions of rows.
+    /// Values come out in PostgreSQL's text form, so casts don't apply.
+    fn execute_export_copy(&mut self, format: ExportFormat, input: &str) -> Action {
+        let Some(pagination) = self.full_query() else {
+            return Action::None;
+        };
+        if !pagination.params.is_empty() {
+            self.set_status(
+                "COPY can't take query parameters — use /export all".to_string(),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        if !self.tab().results_viewer.masked_columns().is_empty() {
+            self.set_status(
+                "COPY can't mask columns — use /export all, or /reveal first".to_string(),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        }
+        let Some((path, casts, options)) = self.export_target(format, input) else {
+            if self.pending_export.is_some() {
+                self.pending_export_mode = ExportMode::Copy;
+            }
+            return Action::None;
+        };
+        if !casts.is_empty() {
+            self.set_status(
+                "Export failed: column casts don't apply to /export copy".to_string(),
+                StatusLevel::Error,
+            );
+            return Action::None;
+        }
+        let job_id = self.jobs.start(&pagination.original_sql, None);
+        self.set_status(
+            format!(
+                "Exporting all rows to {} with COPY as job #{} — /jobs cancel {} stops it",
+                path, job_id, job_id
+            ),
+            StatusLevel::Info,
+        );
+        Action::ExportCopy {
+            job_id,
+            sql: pagination.original_sql,
+            path,
+            options,
+        }
+    }
+
+    /// Load a SQL file into the current tab's editor and remember it as the
+    /// tab's file. The text it replaces is one undo away.
+    fn open_file(&mut self, path: &str) {
+        let file = crate::commands::paths::expand_home(path);
+        let sql = match std::fs::read_to_string(&file) {
+            Ok(sql) => sql,
+            Err(e) => {
+                self.set_status(format!("Can't open {}: {}", path, e), StatusLevel::Error);
+                return;
+            }
+        };
+        let lines = sql.lines().count();
+        let tab = self.tab_mut();
+        let had_draft = !tab.editor.get_content().trim().is_empty();
+        tab.editor.replace_content(sql);
+        tab.history_cursor.reset();
+        tab.file = Some(file);
+        self.clear_completions();
+        self.focus = PanelFocus::QueryEditor;
+        let mut message = format!("Opened {} ({} lines)", path, lines);
+        if had_draft {
+            let undo = self
+                .keymap
+                .keys_for_action(Some(PanelFocus::QueryEditor), KeyAction::Undo)
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            message.push_str(&format!("; {} brings back the previous text", undo));
+        }
+        self.set_status(message, StatusLevel::Success);
+    }
+
+    /// Map a CSV file onto a table (named, or the tree's selection) and
+    /// either count its rows (`dry_run`) or stream it in with `COPY` as a
+    /// background job, which commits on its own connection
+    fn start_import(
+        &mut self,
+        path: Option<String>,
+        table: Option<String>,
+        dry_run: bool,
+    ) -> Action {
+        let Some(path) = path else {
+            self.set_status(
+                "Usage: /import <file.csv> [table] [--dry-run]".to_string(),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        };
+        if self.read_only && !dry_run {
+            self.set_status(
+                "Read-only mode: imports are blocked".to_string(),
+                StatusLevel::Error,
+            );
+            return Action::None;
+        }
+        let target = match table.as_deref() {
+            Some(name) => self.tree_browser.find_table(name),
+            None => self.tree_browser.selected_table(),
+        };
+        let planned = target.map(|(schema, t)| CsvImport::plan(Path::new(&path), schema, t));
+        let import = match planned {
+            Some(Ok(import)) => import,
+            Some(Err(e)) => {
+                self.set_status(format!("Import failed: {}", e), StatusLevel::Error);
+                return Action::None;
+            }
+            None => {
+                let message = match table {
+                    Some(name) => format!("Unknown table '{}'", name),
+                    None => {
+                        "Select a table in the tree or name one: /import <file> <table>".to_string()
+                    }
+                };
+                self.set_status(message, StatusLevel::Warning);
+                return Action::None;
+            }
+        };
+        let skipped = if import.skipped.is_empty() {
+            String::new()
+        } else {
+            format!("; skipped: {}", import.skipped.join(", "))
+        };
+
+        if dry_run {
+            match import.count_rows() {
+                Ok(rows) => self.set_status(
+                    format!(
+                        "Dry run: {} rows from {} would go into {} ({}){}",
+                        rows,
+                        path,
+                        import.table,
+                        import.columns.join(", "),
+                        skipped
+                    ),
+                    StatusLevel::Info,
+                ),
+                Err(e) => self.set_status(format!("Import failed: {}", e), StatusLevel::Error),
+            }
+            return Action::None;
+        }
+        let job_id = self.jobs.start(&import.copy_sql(), None);
+        self.set_status(
+            format!(
+                "Importing {} into {} as job #{}{} — /jobs lists running jobs",
+                path, import.table, job_id, skipped
+            ),
+            StatusLevel::Info,
+        );
+        Action::ImportCsv { job_id, import }
+    }
+
+    /// Bump a session counter. If the metrics file can't be written, say so
+    /// once and keep counting in memory only.
+    fn count(&mut self, counter: Counter, n: u64) {
+        if let Err(e) = self.metrics.add(counter, n) {
+            let path = self.metrics.path().map(|p| p.display().to_string());
+            self.metrics.disable_file();
+            self.set_status(
+                format!("Metrics: can't write {}: {}", path.unwrap_or_default(), e),
+                StatusLevel::Warning,
+            );
+        }
+    }
+
+    /// Open the command bar to collect a find or replace term, prefilled
+    /// with the active search so F3-style repeat is one Enter away.
+    fn start_search_prompt(&mut self, prompt: SearchPrompt) {
+        let (label, prefill) = match prompt {
+            SearchPrompt::Find => ("Find: ", self.tab().editor.search().unwrap_or("")),
+            SearchPrompt::ReplaceFind => ("Replace: ", self.tab().editor.search().unwrap_or("")),
+            SearchPrompt::ReplaceWith(_) => ("Replace with: ", ""),
+            SearchPrompt::Results => (
+                "Search results: ",
+                self.tab().results_viewer.search().unwrap_or(""),
+            ),
+            SearchPrompt::Column(_) => ("Jump to column: ", ""),
+        };
+        let prefill = prefill.to_string();
+        self.pending_search = Some(prompt);
+        self.previous_focus = self.focus;
+        self.focus = PanelFocus::CommandBar;
+        self.command_bar
+            .activate_with_prompt(label.to_string(), prefill);
+    }
+
+    /// Apply the submitted find/replace prompt input to the active editor.
+    fn finish_search_prompt(&mut self, prompt: SearchPrompt, input: &str) {
+        match prompt {
+            SearchPrompt::Find => {
+                self.tab_mut().editor.set_search(input);
+                self.report_search_jump(true);
+            }
+            SearchPrompt::ReplaceFind => {
+                if input.is_empty() {
+                    self.tab_mut().editor.clear_search();
+                    return;
+                }
+                self.tab_mut().editor.set_search(input);
+                self.start_search_prompt(SearchPrompt::ReplaceWith(input.to_string()));
+            }
+            SearchPrompt::Results => {
+                self.tab_mut().results_viewer.set_search(input);
+                self.report_search_jump(true);
+            }
+            SearchPrompt::Column(origin) => {
+                let viewer = &mut self.tab_mut().results_viewer;
+                match viewer.find_column(input) {
+                    Some(col) => {
+                        viewer.select_column(col);
+                        let total = viewer.results().map_or(0, |r| r.columns.len());
+                        let name = viewer
+                            .results()
+                            .map(|r| r.columns[col].name.clone())
+                            .unwrap_or_default();
+                        self.set_status(
+                            format!("Column {}/{}: {}", col + 1, total, name),
+                            StatusLevel::Info,
+                        );
+                    }
+                    None => {
+                        viewer.select_column(origin);
+                        if !input.trim().is_empty() {
+                            self.set_status(
+                                format!("No column matches '{}'", input.trim()),
+                                StatusLevel::Warning,
+                            );
+                        }
+                    }
+                }
+            }
+            SearchPrompt::ReplaceWith(query) => {
+                let count = self.tab_mut().editor.replace_all(&query, input);
+                self.tab_mut().editor.clear_search();
+                self.clear_completions();
+                if count == 0 {
+                    self.set_status(format!("No matches for '{}'", query), StatusLevel::Warning);
+                } else {
+                    let noun = if count == 1 {
+                        "occurrence"
+                    } else {
+                        "occurrences"
+                    };
+                    self.set_status(
+                        format!("Replaced {} {} of '{}'", count, noun, query),
+                        StatusLevel::Success,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Jump to the next/previous search match in the focused editor or
+    /// results grid and report the position.
+    fn report_search_jump(&mut self, forward: bool) {
+        let in_results = self.focus == PanelFocus::ResultsViewer;
+        let query = if in_results {
+            self.tab().results_viewer.search()
+        } else {
+            self.tab().editor.search()
+        };
+        let Some(query) = query.map(String::from) else {
+            return;
+        };
+        let tab = self.tab_mut();
+        let hit = match (in_results, forward) {
+            (true, true) => tab.results_viewer.find_next(),
+            (true, false) => tab.results_viewer.find_prev(),
+            (false, true) => tab.editor.find_next(),
+            (false, false) => tab.editor.find_prev(),
+        };
+        match hit {
+            Some((idx, total)) => {
+                self.set_status(format!("Match {}/{}", idx, total), StatusLevel::Info)
+            }
+            None => self.set_status(format!("No matches for '{}'", query), StatusLevel::Warning),
+        }
+    }
+
+    /// Open the command bar to collect a results row filter, prefilled with
+    /// the active one so it can be refined.
+    fn start_row_filter_prompt(&mut self) {
+        let prefill = self
+            .tab()
+            .results_viewer
+            .filter()
+            .map(|f| f.to_string())
+            .unwrap_or_default();
+        self.pending_row_filter = true;
+        self.previous_focus = self.focus;
+        self.focus = PanelFocus::CommandBar;
+        self.command_bar
+            .activate_with_prompt("Filter rows: ".to_string(), prefill);
+    }
+
+    /// Apply the submitted row filter to the active results
+    fn finish_row_filter(&mut self, input: &str) {
+        let viewer = &mut self.tab_mut().results_viewer;
+        if let Err(e) = viewer.set_filter(input) {
+            self.set_status(e, StatusLevel::Error);
+            return;
+        }
+        match viewer.filter_counts() {
+            Some((0, _)) => {
+                self.set_status("No rows match filter".to_string(), StatusLevel::Warning)
+            }
+            Some((visible, total)) => self.set_status(
+                format!("Filter: {} of {} rows", visible, total),
+                StatusLevel::Info,
+            ),
+            None => self.set_status("Filter cleared".to_string(), StatusLevel::Info),
+        }
+    }
+
+    /// Prompt for a new value for the selected results cell
+    /// Open the rows the selected cell's foreign key refers to in a new
+    /// tab. The key comes from the schema tree, so the source table's schema
+    /// must have been loaded.
+    fn follow_foreign_key(&mut self) -> Action {
+        let (schema, table, column, value) = match self.tab().results_viewer.cell_origin() {
+            Ok(origin) => origin,
+            Err(e) => {
+                self.set_status(e, StatusLevel::Warning);
+                return Action::None;
+            }
+        };
+        let loaded = self
+            .tree_browser
+            .base_schema()
+            .and_then(|tree| tree.schemas.iter().find(|s| s.name == schema));
+        let fk = match loaded {
+            Some(s) if s.loaded => s
+                .tables
+                .iter()
+                .find(|t| t.name == table)
+                .and_then(|t| t.columns.iter().find(|c| c.name == column))
+                .and_then(|c| c.foreign_key.clone()),
+            _ => {
+                self.set_status(
+                    format!("Expand {} in the tree to load its foreign keys", schema),
+                    StatusLevel::Warning,
+                );
+                return Action::None;
+            }
+        };
+        let Some(fk) = fk else {
+            self.set_status(
+                format!("{}.{} has no foreign key", table, column),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        };
+        let Some(value) = value else {
+            self.set_status(
+                "NULL doesn't reference a row".to_string(),
+                StatusLevel::Warning,
+            );
+            return Action::None;
+        };
+        // Targets in another schema are stored qualified ("sales.orders")
+        let (target_schema, target_table) = match fk.target_table.split_once('.') {
+            Some((s, t)) => (s, t),
+            None => (schema.as_str(), fk.target_table.as_str()),
+        };
+        let sql = follow_fk_sql(target_schema, target_table, &fk.target_column, &value);
+        self.run_in_new_tab(&sql)
+    }
+
+    fn start_cell_edit_prompt(&mut self) {
+        if self.tab().query_running {
+            self.set_status(
+                "Wait for the running query to finish".to_string(),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+        if self.tab().tail.is_some() {
+            self.set_status(
+                "Followed rows are read-only \u{2014} /tail stops following".to_string(),
+                StatusLevel::Warning,
+            );
+            return;
+        }
+        if self.tab_writes_blocked() {
+            self.set_status(
+                "Read-only mode: editing cells is blocked".to_string(),
+                StatusLevel::Error,
+            );
+            return;
+        }
+        let (column, current) = match self.tab().results_viewer.edit_target() {
+            Ok(target) => target,
+            Err(e) => {
+                self.set_status(e, StatusLevel::Warning);
+                return;
+            }
+        };
+        self.pending_cell_edit = true;
+        self.previous_focus = self.focus;
+        self.focus = PanelFocus::CommandBar;
+        self.command_bar.activate_with_prompt(
+            format!("Set {} = ", column),
+            current.unwrap_or_else(|| "NULL".to_string()),
+        );
+    }
+
+    /// Generate the UPDATE for the submitted cell value. `NULL` sets NULL.
+    fn finish_cell_edit(&mut self, input: &str) -> Action {
+        let value = (input != "NULL").then_some(input);
+        let update = match self.tab_mut().results_viewer.begin_edit(value) {
+            Ok(update) => update,
+            Err(e) => {
+                self.set_status(e, StatusLevel::Error);
+                return Action::None;
+            }
+        };
+        let tab = self.tab_mut();
+        tab.query_running = true;
+        tab.query_start = Some(std::time::Instant::now());
+        let tab_id = tab.id;
+        self.set_status("Updating cell...".to_string(), StatusLevel::Info);
+        Action::UpdateCell {
+            update,
+            tab_id,
+            timeout_ms: self.query_timeout_ms,
+        }
+    }
+
+    /// Open the insert-row form for the table selected in the tree, or the
+    /// one the results were read from
+    fn open_insert_form(&mut self) -> Action {
+        if self.tab_writes_blocked() {
+            self.set_status(
+                "Read-only mode: inserting rows is blocked".to_string(),
+                StatusLevel::Error,
+            );
+            return Action::None;
+        }
+        let target = if self.focus == PanelFocus::TreeBrowser {
+            self.tree_browser
+                .selected_table()
+                .map(|(schema, table)| (schema.to_string(), table.name.clone()))
+                .ok_or("Select a table to insert a row into")
+        } else {
+            self.tab()
+                .results_viewer
+                .results()
+                .and_then(|r| r.source.as_ref())
+                .map(|s| (s.schema.clone(), s.table.clone()))
+                .ok_or("Can't tell which table these results are from: run a single-table SELECT, or pick the table in the tree")
+        };
+        let (schema, table) = match target {
+            Ok(target) => target,
+            Err(e) => {
+                self.set_status(e.to_string(), StatusLevel::Warning);
+                return Action::None;
+            }
+        };
+        self.set_status(
+            format!(
+                "Loading columns of {}...",
+                crate::sql::quote_qualified(&schema, &table)
+            ),
+            StatusLevel::Info,
+        );
+        Action::LoadInsertForm {
+            tab_id: self.tab().id,
+            schema,
+            table,
+        }
+    }
+
+    /// Ask before running the INSERT the form built
+    fn confirm_insert(&mut self, sql: String) {
+        let target = self.insert_form.target();
+        self.insert_form.hide();
+        self.focus = self.previous_focus;
+        self.pending_confirm_sql = Some(PendingConfirm {
+            sql,
+            params: Vec::new(),
+            tab_id: self.tab().id,
+            timeout_ms: self.query_timeout_ms,
+            max_rows: self.max_result_rows,
+            over_cost: false,
+        });
+        self.set_status(
+            format!("Insert 1 row into {}? (y/N)", target),
+            StatusLevel::Warning,
+        );
+    }
+
     fn start_save_query_prompt(&mut self) {
         self.pending_save_query = true;
         self.previous_focus = self.focus;
@@ -812,10 +3838,14 @@ impl App {
         self.set_status(format!("Saved query: {}", name), StatusLevel::Success);
     }
 
-    fn copy_to_clipboard(&mut self, text: &str) {
+    /// Returns whether the text reached the clipboard
+    fn copy_to_clipboard(&mut self, text: &str) -> bool {
         if let Some(clipboard) = self.clipboard.as_mut() {
             match clipboard.set_text(text) {
-                Ok(()) => self.set_status("Copied to clipboard".to_string(), StatusLevel::Success),
+                Ok(()) => {
+                    self.set_status("Copied to clipboard".to_string(), StatusLevel::Success);
+                    return true;
+                }
                 Err(e) => {
                     self.set_status(format!("Clipboard error: {}", e), StatusLevel::Warning);
                 }
@@ -827,6 +3857,34 @@ impl App {
                 StatusLevel::Warning,
             );
         }
+        false
+    }
+
+    /// Copy the active results (only the rows a filter leaves) to the
+    /// clipboard, serialized as they would be exported
+    fn copy_results(&mut self, format: ExportFormat) {
+        let Some(results) = self.tab().results_viewer.results() else {
+            self.set_status("No results to copy".to_string(), StatusLevel::Warning);
+            return;
+        };
+        let casts = ColumnCasts::default();
+        let text = match format {
+            ExportFormat::Tsv => crate::export::to_delimited(results, &casts, CsvOptions::tsv()),
+            ExportFormat::Json => crate::export::to_json(results),
+            ExportFormat::Markdown => crate::export::to_markdown(results),
+            ExportFormat::Csv | ExportFormat::Xlsx => crate::export::to_csv(results),
+        };
+        let rows = results.rows.len();
+        if self.copy_to_clipboard(&text) {
+            self.set_status(
+                format!(
+                    "Copied {} rows as {}",
+                    rows,
+                    format.extension().to_uppercase()
+                ),
+                StatusLevel::Success,
+            );
+        }
     }
 }
 
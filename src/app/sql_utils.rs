@@ -2,68 +2,76 @@
 //!
 //! Pure functions for analyzing SQL text: transaction intent detection,
 //! destructive query classification, write detection, psql meta-command
-//! translation, and error position mapping.
+//! translation, catalog queries for tree actions, and error position mapping.
 
 use super::TransactionState;
+use crate::db::QueryResults;
+use crate::db::sql_limit;
+use crate::db::transaction::TransactionControl;
+use crate::db::types::CellValue;
+use crate::sql::{quote_ident, quote_literal, quote_qualified};
 
-/// Detect the transaction intent of a SQL statement by looking at the first keyword.
+/// Detect the transaction intent of a SQL script from its last transaction-control statement.
 /// Returns the new TransactionState if the query changes it, or None if no change.
 pub(super) fn detect_transaction_intent(sql: &str) -> Option<TransactionState> {
-    let trimmed = sql.trim();
-    // Find the first word (case-insensitive)
-    let first_word = trimmed
-        .split_whitespace()
-        .next()?
-        .trim_end_matches(';')
-        .to_uppercase();
-    match first_word.as_str() {
-        "BEGIN" | "START" => Some(TransactionState::InTransaction),
-        "COMMIT" | "END" => Some(TransactionState::Idle),
-        "ROLLBACK" | "ABORT" => Some(TransactionState::Idle),
-        _ => None,
-    }
+    TransactionControl::parse(sql).map(TransactionControl::resulting_state)
 }
 
-/// Check if a SQL statement is destructive and return a label describing the operation.
-/// Returns None if the query is safe, or Some("LABEL") for destructive queries.
-pub(super) fn is_destructive_query(sql: &str) -> Option<&'static str> {
-    let trimmed = sql.trim();
-    // Normalize to uppercase for matching, but only the prefix we need
-    let upper: String = trimmed.chars().take(200).collect::<String>().to_uppercase();
-
-    if upper.starts_with("DROP TABLE")
-        || upper.starts_with("DROP INDEX")
-        || upper.starts_with("DROP SCHEMA")
-        || upper.starts_with("DROP DATABASE")
-        || upper.starts_with("DROP VIEW")
-        || upper.starts_with("DROP MATERIALIZED VIEW")
-        || upper.starts_with("DROP FUNCTION")
-        || upper.starts_with("DROP PROCEDURE")
-        || upper.starts_with("DROP TRIGGER")
-        || upper.starts_with("DROP SEQUENCE")
-        || upper.starts_with("DROP TYPE")
-        || upper.starts_with("DROP EXTENSION")
-        || upper.starts_with("DROP ROLE")
-        || upper.starts_with("DROP USER")
-    {
-        return Some("DROP");
-    }
-
-    if upper.starts_with("TRUNCATE") {
-        return Some("TRUNCATE");
+/// Whether `sql` opens a transaction with `READ WRITE`, the override for
+/// writes on a read-only connection
+pub(super) fn is_read_write_begin(sql: &str) -> bool {
+    if TransactionControl::parse(sql) != Some(TransactionControl::Begin) {
+        return false;
     }
+    let words: Vec<String> = sql
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|w| !w.is_empty())
+        .map(str::to_uppercase)
+        .collect();
+    words.windows(2).any(|w| w[0] == "READ" && w[1] == "WRITE")
+}
 
-    // DELETE without WHERE
-    if upper.starts_with("DELETE") && !upper.contains("WHERE") {
-        return Some("DELETE without WHERE");
-    }
+/// Check if a script has a destructive statement and return a label
+/// describing the first one: any DROP, TRUNCATE, ALTER TABLE ... DROP, or a
+/// DELETE/UPDATE without WHERE (also after a WITH). Strings, comments, and
+/// subqueries are skipped. Returns None if the script is safe.
+pub(super) fn is_destructive_query(sql: &str) -> Option<&'static str> {
+    crate::db::sql_limit::outer_statements(sql)
+        .iter()
+        .find_map(|tokens| destructive_statement(tokens))
+}
 
-    // ALTER TABLE ... DROP (column, constraint, etc.)
-    if upper.starts_with("ALTER TABLE") && upper.contains(" DROP ") {
-        return Some("ALTER TABLE DROP");
+/// `is_destructive_query` for one statement's top-level tokens
+fn destructive_statement(tokens: &[String]) -> Option<&'static str> {
+    let has = |word: &str| tokens.iter().any(|t| t == word);
+    match crate::db::sql_limit::statement_verb(tokens)? {
+        "DROP" => Some("DROP"),
+        "TRUNCATE" => Some("TRUNCATE"),
+        "DELETE" if !has("WHERE") => Some("DELETE without WHERE"),
+        "UPDATE" if !has("WHERE") => Some("UPDATE without WHERE"),
+        "ALTER" if tokens.get(1).is_some_and(|t| t == "TABLE") && has("DROP") => {
+            Some("ALTER TABLE DROP")
+        }
+        _ => None,
     }
+}
 
-    None
+/// Check whether a script changes the schema tree: a CREATE, ALTER, DROP,
+/// or COMMENT ON in any of its statements, outside strings and comments.
+/// Roles, databases, tablespaces, and ALTER SYSTEM don't count.
+pub(super) fn contains_ddl(sql: &str) -> bool {
+    let tokens = crate::db::sql_limit::tokenize_outer(sql);
+    tokens.iter().enumerate().any(|(i, token)| {
+        let next = tokens.get(i + 1).map(String::as_str);
+        match token.as_str() {
+            "CREATE" | "ALTER" | "DROP" => !matches!(
+                next,
+                None | Some("ROLE" | "USER" | "GROUP" | "DATABASE" | "TABLESPACE" | "SYSTEM")
+            ),
+            "COMMENT" => next == Some("ON"),
+            _ => false,
+        }
+    })
 }
 
 /// Check if a SQL statement is a write operation that should be blocked in read-only mode.
@@ -188,13 +196,14 @@ fn translate_describe_table(table_arg: &str) -> Option<String> {
         if s.is_empty() || t.is_empty() || t.contains('.') {
             return None;
         }
-        (format!("AND n.nspname = '{}'", s), t.to_string())
+        (format!("AND n.nspname = {}", quote_literal(s)), t)
     } else {
         (
             "AND n.nspname NOT IN ('pg_catalog', 'information_schema')".to_string(),
-            table.to_string(),
+            table,
         )
     };
+    let table_name = quote_literal(table_name);
 
     // CTE to resolve the table OID once, then reuse across all sections
     Some(format!(
@@ -202,7 +211,7 @@ fn translate_describe_table(table_arg: &str) -> Option<String> {
             SELECT c.oid, n.nspname, c.relname \
             FROM pg_catalog.pg_class c \
             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
-            WHERE c.relname = '{table_name}' {schema_filter} \
+            WHERE c.relname = {table_name} {schema_filter} \
             LIMIT 1\
          ) \
          SELECT section, name, definition FROM (\
@@ -260,6 +269,353 @@ fn translate_describe_table(table_arg: &str) -> Option<String> {
     ))
 }
 
+/// Catalog query listing views, materialized views, and functions whose
+/// source mentions `schema.table` (and `column`, when given).
+///
+/// A whole-word, case-insensitive text search over `pg_views.definition`,
+/// `pg_matviews.definition`, and `pg_proc.prosrc` — it finds references in
+/// dynamic SQL too, but may also list objects that only mention the name.
+pub(super) fn find_usages_sql(schema: &str, table: &str, column: Option<&str>) -> String {
+    let mentions = |source: &str| {
+        let mut cond = format!("{} ~* {}", source, quote_literal(&word_pattern(table)));
+        if let Some(column) = column {
+            cond.push_str(&format!(
+                " AND {} ~* {}",
+                source,
+                quote_literal(&word_pattern(column))
+            ));
+        }
+        cond
+    };
+    let (schema, table) = (quote_literal(schema), quote_literal(table));
+    format!(
+        "SELECT 'view' AS kind, schemaname AS schema, viewname AS name, NULL::text AS arguments \
+         FROM pg_catalog.pg_views \
+         WHERE {views} AND NOT (schemaname = {schema} AND viewname = {table}) \
+           AND schemaname NOT IN ('pg_catalog', 'information_schema') \
+         UNION ALL \
+         SELECT 'materialized view', schemaname, matviewname, NULL \
+         FROM pg_catalog.pg_matviews \
+         WHERE {views} AND NOT (schemaname = {schema} AND matviewname = {table}) \
+         UNION ALL \
+         SELECT CASE WHEN p.prokind = 'p' THEN 'procedure' ELSE 'function' END, \
+                n.nspname, p.proname, pg_catalog.pg_get_function_identity_arguments(p.oid) \
+         FROM pg_catalog.pg_proc p \
+         JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace \
+         WHERE {procs} AND n.nspname NOT IN ('pg_catalog', 'information_schema') \
+         ORDER BY 1, 2, 3",
+        views = mentions("definition"),
+        procs = mentions("p.prosrc"),
+    )
+}
+
+/// Query for the rows of `schema.table` whose `column` equals `value`, used
+/// to follow a foreign key from a result cell.
+pub(super) fn follow_fk_sql(schema: &str, table: &str, column: &str, value: &str) -> String {
+    format!(
+        "SELECT * FROM {} WHERE {} = {}",
+        quote_qualified(schema, table),
+        quote_ident(column),
+        quote_literal(value)
+    )
+}
+
+/// Opens a `/snapshot` tab's transaction. Under REPEATABLE READ the first
+/// query fixes the snapshot, and every later one in the block sees it.
+pub(super) const SNAPSHOT_BEGIN: &str = "BEGIN ISOLATION LEVEL REPEATABLE READ READ ONLY";
+
+/// First query of a `/snapshot` tab, which fixes its snapshot
+pub(super) fn snapshot_sql(schema: &str, table: &str) -> String {
+    format!("SELECT * FROM {}", quote_qualified(schema, table))
+}
+
+/// PostgreSQL regex matching `name` as a whole word (`\m...\M`), with
+/// regex metacharacters escaped
+fn word_pattern(name: &str) -> String {
+    let mut pattern = String::from("\\m");
+    for c in name.chars() {
+        if "\\.^$*+?()[]{}|".contains(c) {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push_str("\\M");
+    pattern
+}
+
+/// Options the EXPLAIN key runs with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ExplainOptions {
+    pub analyze: bool,
+    pub buffers: bool,
+    pub verbose: bool,
+    pub settings: bool,
+    pub wal: bool,
+}
+
+impl Default for ExplainOptions {
+    fn default() -> Self {
+        Self {
+            analyze: true,
+            buffers: false,
+            verbose: false,
+            settings: false,
+            wal: false,
+        }
+    }
+}
+
+impl ExplainOptions {
+    /// Parse a comma- or space-separated option list (`analyze, buffers`).
+    /// An empty list means a plain EXPLAIN that doesn't run the query.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut options = Self {
+            analyze: false,
+            ..Self::default()
+        };
+        for word in spec
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|w| !w.is_empty())
+        {
+            match word.to_ascii_lowercase().as_str() {
+                "analyze" | "analyse" => options.analyze = true,
+                "buffers" => options.buffers = true,
+                "verbose" => options.verbose = true,
+                "settings" => options.settings = true,
+                "wal" => options.wal = true,
+                other => {
+                    return Err(format!(
+                        "Unknown EXPLAIN option '{}' (use analyze, buffers, verbose, settings, wal)",
+                        other
+                    ));
+                }
+            }
+        }
+        if options.wal && !options.analyze {
+            return Err("EXPLAIN option wal requires analyze".to_string());
+        }
+        Ok(options)
+    }
+
+    /// Enabled option names, in EXPLAIN's own spelling
+    fn names(&self) -> Vec<&'static str> {
+        [
+            (self.analyze, "ANALYZE"),
+            (self.buffers, "BUFFERS"),
+            (self.verbose, "VERBOSE"),
+            (self.settings, "SETTINGS"),
+            (self.wal, "WAL"),
+        ]
+        .into_iter()
+        .filter_map(|(on, name)| on.then_some(name))
+        .collect()
+    }
+
+    /// `EXPLAIN ...` for `sql`, with JSON output for the visual tree
+    pub fn explain_sql(&self, sql: &str, json: bool) -> String {
+        let mut names = self.names();
+        if json {
+            names.push("FORMAT JSON");
+        }
+        format!("{} {}", explain_clause(&names), sql.trim())
+    }
+
+    /// Short description for status messages, e.g. `EXPLAIN (ANALYZE, BUFFERS)`
+    pub fn label(&self) -> String {
+        explain_clause(&self.names())
+    }
+}
+
+/// The planner's estimate for a whole statement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct PlanEstimate {
+    pub rows: u64,
+    pub total_cost: f64,
+}
+
+/// Row estimate from `EXPLAIN (FORMAT JSON)` output. For INSERT, UPDATE,
+/// and DELETE the top node (ModifyTable) returns nothing, so the rows its
+/// input produces — the rows it would change — are reported instead.
+pub(super) fn plan_row_estimate(json: &str) -> Option<PlanEstimate> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let plan = value.get(0)?.get("Plan")?;
+    let total_cost = plan.get("Total Cost")?.as_f64()?;
+    let counted = if plan.get("Node Type")?.as_str()? == "ModifyTable" {
+        plan.get("Plans")?.get(0)?
+    } else {
+        plan
+    };
+    Some(PlanEstimate {
+        rows: counted.get("Plan Rows")?.as_f64()? as u64,
+        total_cost,
+    })
+}
+
+/// `plan_row_estimate` of the JSON cell `EXPLAIN (FORMAT JSON)` returns
+pub(super) fn explain_estimate(results: &QueryResults) -> Option<PlanEstimate> {
+    let json = match results.rows.first()?.values.first()? {
+        CellValue::Text(s) | CellValue::Json(s) => s,
+        _ => return None,
+    };
+    plan_row_estimate(json)
+}
+
+/// Whether `sql` is a single statement `EXPLAIN` can plan: a SELECT,
+/// VALUES, TABLE, INSERT, UPDATE, DELETE, or MERGE
+pub(super) fn is_plannable(sql: &str) -> bool {
+    let statements = sql_limit::outer_statements(sql);
+    let [tokens] = statements.as_slice() else {
+        return false;
+    };
+    sql_limit::statement_verb(tokens).is_some_and(|verb| {
+        matches!(
+            verb,
+            "SELECT" | "VALUES" | "TABLE" | "INSERT" | "UPDATE" | "DELETE" | "MERGE"
+        )
+    })
+}
+
+/// `EXPLAIN` with its options; a lone ANALYZE keeps the classic spelling
+fn explain_clause(names: &[&str]) -> String {
+    match names {
+        [] => "EXPLAIN".to_string(),
+        ["ANALYZE"] => "EXPLAIN ANALYZE".to_string(),
+        _ => format!("EXPLAIN ({})", names.join(", ")),
+    }
+}
+
+/// Catalog query listing everything a `DROP` statement would take with it,
+/// or None when `sql` isn't a DROP of a table, view, sequence, index,
+/// function, type, or schema.
+///
+/// Walks `pg_depend` from the dropped objects (view rules are reported as
+/// their views). Objects marked `CASCADE` block a plain DROP and are only
+/// removed by `DROP ... CASCADE`; `automatic` ones go with it either way.
+/// Missing objects resolve to NULL, so `IF EXISTS` targets list nothing.
+pub(super) fn drop_impact_sql(sql: &str) -> Option<String> {
+    let trimmed = sql.trim().trim_end_matches(';').trim_end();
+    let mut rest = strip_keyword(trimmed, "DROP")?;
+    let (catalog, resolve) = [
+        ("MATERIALIZED VIEW", "pg_class", "to_regclass"),
+        ("FOREIGN TABLE", "pg_class", "to_regclass"),
+        ("TABLE", "pg_class", "to_regclass"),
+        ("VIEW", "pg_class", "to_regclass"),
+        ("SEQUENCE", "pg_class", "to_regclass"),
+        ("INDEX", "pg_class", "to_regclass"),
+        ("FUNCTION", "pg_proc", "to_regprocedure"),
+        ("PROCEDURE", "pg_proc", "to_regprocedure"),
+        ("ROUTINE", "pg_proc", "to_regprocedure"),
+        ("TYPE", "pg_type", "to_regtype"),
+        ("DOMAIN", "pg_type", "to_regtype"),
+        ("SCHEMA", "pg_namespace", "to_regnamespace"),
+    ]
+    .into_iter()
+    .find_map(|(kind, catalog, resolve)| {
+        rest = kind
+            .split(' ')
+            .try_fold(rest, |text, word| strip_keyword(text, word))?;
+        Some((catalog, resolve))
+    })?;
+    rest = strip_keyword(rest, "CONCURRENTLY").unwrap_or(rest);
+    if let Some(after_if) = strip_keyword(rest, "IF") {
+        rest = strip_keyword(after_if, "EXISTS")?;
+    }
+    for behavior in ["CASCADE", "RESTRICT"] {
+        if rest.len() >= behavior.len()
+            && let Some(end) = rest.get(rest.len() - behavior.len()..)
+            && end.eq_ignore_ascii_case(behavior)
+        {
+            rest = rest[..rest.len() - behavior.len()].trim_end();
+        }
+    }
+
+    let seeds: Vec<String> = split_top_level(rest)
+        .into_iter()
+        .map(|name| {
+            // A function named without its argument list resolves by name
+            let resolve = if resolve == "to_regprocedure" && !name.contains('(') {
+                "to_regproc"
+            } else {
+                resolve
+            };
+            format!(
+                "SELECT 'pg_catalog.{}'::regclass::oid, {}({})::oid, 0, 'x'::\"char\", 0",
+                catalog,
+                resolve,
+                quote_literal(&name)
+            )
+        })
+        .collect();
+    if seeds.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "WITH RECURSIVE deps(classid, objid, objsubid, deptype, depth) AS ( \
+         {seeds} \
+         UNION \
+         SELECT CASE WHEN r.oid IS NULL THEN d.classid ELSE 'pg_catalog.pg_class'::regclass::oid END, \
+                COALESCE(r.ev_class, d.objid), \
+                CASE WHEN r.oid IS NULL THEN d.objsubid ELSE 0 END, \
+                d.deptype, deps.depth + 1 \
+         FROM deps \
+         JOIN pg_catalog.pg_depend d ON d.refclassid = deps.classid AND d.refobjid = deps.objid \
+         LEFT JOIN pg_catalog.pg_rewrite r \
+           ON d.classid = 'pg_catalog.pg_rewrite'::regclass AND r.oid = d.objid \
+         WHERE d.deptype IN ('n', 'a', 'i') \
+           AND COALESCE(r.ev_class, d.objid) <> d.refobjid \
+           AND deps.depth < 10 \
+         ) \
+         SELECT min(depth) AS depth, \
+                (pg_catalog.pg_identify_object(classid, objid, objsubid)).type AS type, \
+                pg_catalog.pg_describe_object(classid, objid, objsubid) AS object, \
+                CASE WHEN bool_or(deptype = 'n') THEN 'CASCADE' ELSE 'automatic' END AS dropped_by \
+         FROM deps \
+         WHERE depth > 0 AND deptype <> 'i' AND objid IS NOT NULL \
+           AND (pg_catalog.pg_identify_object(classid, objid, objsubid)).schema \
+               IS DISTINCT FROM 'pg_toast' \
+         GROUP BY classid, objid, objsubid \
+         ORDER BY 1, 2, 3",
+        seeds = seeds.join(" UNION ALL "),
+    ))
+}
+
+/// `text` after a leading case-insensitive `keyword` and the whitespace
+/// following it, or None when it doesn't start with that word
+fn strip_keyword<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+    let head = text.get(..keyword.len())?;
+    let rest = &text[keyword.len()..];
+    (head.eq_ignore_ascii_case(keyword) && rest.starts_with(char::is_whitespace))
+        .then(|| rest.trim_start())
+}
+
+/// Split a DROP target list on commas outside parentheses and quotes
+fn split_top_level(list: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut quoted = false;
+    for c in list.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth = depth.saturating_sub(1),
+            ',' if !quoted && depth == 0 => {
+                items.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    items.push(current);
+    items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
 /// Convert a 1-based byte offset (from PostgreSQL error) to (line, col) position.
 /// PostgreSQL positions are 1-indexed, so we subtract 1 to get 0-indexed offset.
 pub(super) fn byte_offset_to_position(content: &str, offset: u32) -> (usize, usize) {
@@ -279,3 +635,49 @@ pub(super) fn byte_offset_to_position(content: &str, offset: u32) -> (usize, usi
     }
     (line, col)
 }
+
+/// The channel name `LISTEN` would use for `name` as typed: unquoted names
+/// fold to lowercase, `"Quoted"` ones keep their case.
+pub(super) fn channel_name(name: &str) -> String {
+    match name
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        Some(quoted) => quoted.replace("\"\"", "\""),
+        None => name.to_lowercase(),
+    }
+}
+
+/// A `statement_timeout` as typed for `/timeout`, in milliseconds: a
+/// number with `ms`, `s`, `m`, or `h` (seconds when bare), or `off`
+pub(super) fn parse_timeout(value: &str) -> Option<u64> {
+    let value = value.trim().to_lowercase();
+    if value == "off" {
+        return Some(0);
+    }
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let scale = match unit.trim() {
+        "ms" => 1.0,
+        "" | "s" | "sec" => 1_000.0,
+        "m" | "min" => 60_000.0,
+        "h" => 3_600_000.0,
+        _ => return None,
+    };
+    Some((number * scale).round() as u64)
+}
+
+/// A timeout in milliseconds the way `/timeout` takes it (e.g. "30s",
+/// "1m 30s", "250ms", "off")
+pub(super) fn format_timeout(ms: u64) -> String {
+    match ms {
+        0 => "off".to_string(),
+        ms if ms % 1000 != 0 => format!("{}ms", ms),
+        ms if ms < 60_000 => format!("{}s", ms / 1000),
+        ms if ms % 60_000 == 0 => format!("{}m", ms / 60_000),
+        ms => format!("{}m {}s", ms / 60_000, ms % 60_000 / 1000),
+    }
+}
@@ -0,0 +1,86 @@
+//! Result stashes
+//!
+//! Named in-memory copies of result sets (`/stash name`) that can be shown
+//! again or compared against the current results later in the session.
+
+use crate::db::QueryResults;
+use crate::db::types::{CellValue, ColumnDef, DataType, Row};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Rows that differ between a stash and the current results
+pub(super) struct ResultDiff {
+    /// Removed rows (`-`) then added rows (`+`), with a leading marker
+    /// column
+    pub results: QueryResults,
+    pub added: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// Compare two result sets as multisets of rows. Row order is ignored and
+/// a changed row shows up as one removal and one addition.
+pub(super) fn diff_results(
+    before: &QueryResults,
+    after: &QueryResults,
+) -> Result<ResultDiff, String> {
+    let names = |r: &QueryResults| r.columns.iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+    if names(before) != names(after) {
+        return Err(format!(
+            "columns differ ({} vs {})",
+            names(before).join(", "),
+            names(after).join(", ")
+        ));
+    }
+
+    let key = |row: &Row| format!("{:?}", row.values);
+    let mut remaining: HashMap<String, usize> = HashMap::new();
+    for row in &before.rows {
+        *remaining.entry(key(row)).or_default() += 1;
+    }
+    let mut added_rows = Vec::new();
+    for row in &after.rows {
+        match remaining.get_mut(&key(row)) {
+            Some(n) if *n > 0 => *n -= 1,
+            _ => added_rows.push(row),
+        }
+    }
+    let removed_rows: Vec<&Row> = before
+        .rows
+        .iter()
+        .filter(|row| match remaining.get_mut(&key(row)) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                true
+            }
+            _ => false,
+        })
+        .collect();
+
+    let marked = |marker: &str, row: &Row| Row {
+        values: std::iter::once(CellValue::Text(marker.to_string()))
+            .chain(row.values.iter().cloned())
+            .collect(),
+    };
+    let rows: Vec<Row> = removed_rows
+        .iter()
+        .map(|row| marked("-", row))
+        .chain(added_rows.iter().map(|row| marked("+", row)))
+        .collect();
+    let columns = std::iter::once(ColumnDef {
+        name: "±".to_string(),
+        data_type: DataType::Text,
+        nullable: false,
+    })
+    .chain(after.columns.iter().cloned())
+    .collect();
+
+    let (added, removed) = (added_rows.len(), removed_rows.len());
+    let row_count = rows.len();
+    Ok(ResultDiff {
+        results: QueryResults::new(columns, rows, Duration::ZERO, row_count),
+        added,
+        removed,
+        unchanged: after.rows.len() - added,
+    })
+}
@@ -0,0 +1,186 @@
+//! Following a table (`/tail`)
+//!
+//! A tailed tab polls its table for rows past the last key it has seen and
+//! appends them to the grid, like `tail -f`. The key is the table's
+//! primary key when it orders by insertion (a number or a timestamp),
+//! otherwise its first timestamp column, or the column given with `by`.
+
+use crate::db::QueryResults;
+use crate::db::schema::Table;
+use crate::db::types::{CellValue, DataType};
+use crate::sql::{quote_ident, quote_literal};
+use std::time::{Duration, Instant};
+
+/// Rows shown when the tail starts
+pub(super) const TAIL_BACKLOG: usize = 100;
+/// Most rows fetched by one poll
+pub(super) const TAIL_BATCH: usize = 1000;
+/// Time between polls
+pub(super) const TAIL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// `/tail` arguments: `table [by column] [WHERE condition]`
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct TailArgs {
+    pub table: String,
+    pub by: Option<String>,
+    pub filter: Option<String>,
+}
+
+pub(super) fn parse_tail(args: &str) -> Result<TailArgs, String> {
+    const USAGE: &str = "Usage: /tail <table> [by <column>] [WHERE <condition>]";
+    let (table, mut rest) = split_word(args);
+    if table.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let mut by = None;
+    let (word, after) = split_word(rest);
+    if word.eq_ignore_ascii_case("by") {
+        let (column, after) = split_word(after);
+        if column.is_empty() {
+            return Err(USAGE.to_string());
+        }
+        by = Some(column.to_string());
+        rest = after;
+    }
+    let (word, after) = split_word(rest);
+    let filter = if word.is_empty() {
+        None
+    } else if word.eq_ignore_ascii_case("where") && !after.is_empty() {
+        Some(after.to_string())
+    } else {
+        return Err(USAGE.to_string());
+    };
+    Ok(TailArgs {
+        table: table.to_string(),
+        by,
+        filter,
+    })
+}
+
+/// First whitespace-separated word and the trimmed rest
+fn split_word(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    match s.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (s, ""),
+    }
+}
+
+/// Column a table is tailed by: `by` when given, else an ordered primary
+/// key, else the first timestamp column
+pub(super) fn tail_key(table: &Table, by: Option<&str>) -> Result<String, String> {
+    if let Some(by) = by {
+        return table
+            .columns
+            .iter()
+            .find(|c| c.name == by)
+            .map(|c| c.name.clone())
+            .ok_or_else(|| format!("{} has no column '{}'", table.name, by));
+    }
+    let mut keys = table.columns.iter().filter(|c| c.is_primary_key);
+    if let (Some(key), None) = (keys.next(), keys.next())
+        && (is_numeric(&key.data_type) || is_temporal(&key.data_type))
+    {
+        return Ok(key.name.clone());
+    }
+    table
+        .columns
+        .iter()
+        .find(|c| is_temporal(&c.data_type))
+        .map(|c| c.name.clone())
+        .ok_or_else(|| {
+            format!(
+                "{} has no numeric key or timestamp column to follow — name one with /tail {} by <column>",
+                table.name, table.name
+            )
+        })
+}
+
+fn is_numeric(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::SmallInt | DataType::Integer | DataType::BigInt | DataType::Numeric
+    )
+}
+
+fn is_temporal(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Timestamp | DataType::TimestampTz | DataType::Date
+    )
+}
+
+/// A tab following a table
+#[derive(Debug)]
+pub struct TailState {
+    /// Table as shown in the results title (`schema.table`)
+    pub relation: String,
+    /// Quoted table for the FROM clause
+    from: String,
+    /// Key column
+    key: String,
+    filter: Option<String>,
+    /// Key of the newest row shown
+    last_key: Option<String>,
+    /// The first rows have been shown
+    pub(super) started: bool,
+    pub(super) next_poll: Instant,
+    /// A poll is running
+    pub(super) in_flight: bool,
+}
+
+impl TailState {
+    pub(super) fn new(schema: &str, table: &str, key: &str, filter: Option<String>) -> Self {
+        Self {
+            relation: format!("{}.{}", schema, table),
+            from: format!("{}.{}", quote_ident(schema), quote_ident(table)),
+            key: key.to_string(),
+            filter,
+            last_key: None,
+            started: false,
+            next_poll: Instant::now(),
+            in_flight: false,
+        }
+    }
+
+    /// The next poll's query: the newest `TAIL_BACKLOG` rows to start
+    /// with, then the rows past the last key seen
+    pub(super) fn sql(&self) -> String {
+        let key = quote_ident(&self.key);
+        let filter = self.filter.as_ref().map(|f| format!("({})", f));
+        if !self.started {
+            let clause = filter.map(|f| format!(" WHERE {}", f)).unwrap_or_default();
+            return format!(
+                "SELECT * FROM (SELECT * FROM {}{} ORDER BY {} DESC LIMIT {}) AS tail ORDER BY {}",
+                self.from, clause, key, TAIL_BACKLOG, key
+            );
+        }
+        let after = self
+            .last_key
+            .as_ref()
+            .map(|k| format!("{} > {}", key, quote_literal(k)));
+        let conditions: Vec<String> = filter.into_iter().chain(after).collect();
+        let clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        format!(
+            "SELECT * FROM {}{} ORDER BY {} LIMIT {}",
+            self.from, clause, key, TAIL_BATCH
+        )
+    }
+
+    /// Note the newest key in a batch of rows
+    pub(super) fn record(&mut self, results: &QueryResults) {
+        self.started = true;
+        let Some(col) = results.columns.iter().position(|c| c.name == self.key) else {
+            return;
+        };
+        if let Some(value) = results.rows.last().and_then(|r| r.values.get(col))
+            && !matches!(value, CellValue::Null)
+        {
+            self.last_key = Some(value.display_string(usize::MAX));
+        }
+    }
+}
@@ -23,11 +23,19 @@ fn test_with_connection_constructor() {
                 name: "users".to_string(),
                 columns: vec![],
                 row_count: None,
+                size_bytes: None,
+                select_denied: false,
             }]),
             views: PaginatedVec::default(),
             indexes: PaginatedVec::default(),
             functions: PaginatedVec::default(),
+            sequences: PaginatedVec::default(),
+            enums: PaginatedVec::default(),
+            domains: PaginatedVec::default(),
+            loaded: true,
         }]),
+        extensions: Vec::new(),
+        databases: Vec::new(),
     };
     let app = App::with_connection(
         "test-db".to_string(),
@@ -100,13 +108,100 @@ fn test_schema_loaded_event() {
     let mut app = App::new();
     let schema = SchemaTree::new();
     let action = app.handle_event(AppEvent::SchemaLoaded(schema)).unwrap();
-    assert!(matches!(action, Action::None));
+    assert!(matches!(action, Action::SaveSchemaCache));
     assert_eq!(
         app.status_message.as_ref().unwrap().message,
         "Schema refreshed"
     );
 }
 
+#[test]
+fn test_schema_loaded_clears_stale_cached_schema() {
+    use crate::db::schema::SchemaTree;
+    let mut app = App::new();
+    app.tree_browser.set_schema(SchemaTree::new());
+    app.tree_browser.mark_stale();
+
+    app.handle_event(AppEvent::SchemaFailed("timeout".to_string()))
+        .unwrap();
+    assert!(app.tree_browser.is_stale());
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .ends_with("(showing cached schema)")
+    );
+
+    app.handle_event(AppEvent::SchemaLoaded(SchemaTree::new()))
+        .unwrap();
+    assert!(!app.tree_browser.is_stale());
+}
+
+#[test]
+fn test_schema_objects_load_when_first_expanded() {
+    use crate::db::schema::{PaginatedVec, Schema, SchemaTree, Table};
+    let mut app = App::new();
+    let names = SchemaTree {
+        schemas: PaginatedVec::from_vec(vec![
+            Schema::unloaded("public".to_string()),
+            Schema::unloaded("sales".to_string()),
+        ]),
+        extensions: Vec::new(),
+        databases: Vec::new(),
+    };
+    app.handle_event(AppEvent::SchemaLoaded(names)).unwrap();
+
+    // The first schema is expanded, so only it is requested
+    assert_eq!(app.tree_browser.take_pending_loads(), vec!["public"]);
+    assert!(app.tree_browser.take_pending_loads().is_empty());
+    assert!(app.tree_browser.is_loading());
+
+    let loaded = Schema {
+        tables: PaginatedVec::from_vec(vec![Table {
+            name: "users".to_string(),
+            columns: vec![],
+            row_count: None,
+            size_bytes: None,
+            select_denied: false,
+        }]),
+        sequences: PaginatedVec::default(),
+        enums: PaginatedVec::default(),
+        domains: PaginatedVec::default(),
+        loaded: true,
+        ..Schema::unloaded("public".to_string())
+    };
+    let action = app
+        .handle_event(AppEvent::SchemaObjectsLoaded(loaded))
+        .unwrap();
+    assert!(matches!(action, Action::SaveSchemaCache));
+    assert!(!app.tree_browser.is_loading());
+    let public = &app.tree_browser.schema().unwrap().schemas.items[0];
+    assert!(public.loaded);
+    assert!(!public.tables.is_empty());
+
+    // A failed load collapses the schema so expanding it again retries
+    app.tree_browser.set_schema(SchemaTree {
+        schemas: PaginatedVec::from_vec(vec![Schema::unloaded("public".to_string())]),
+        extensions: Vec::new(),
+        databases: Vec::new(),
+    });
+    assert_eq!(app.tree_browser.take_pending_loads(), vec!["public"]);
+    app.handle_event(AppEvent::SchemaObjectsFailed {
+        schema_name: "public".to_string(),
+        error: "permission denied".to_string(),
+    })
+    .unwrap();
+    assert!(app.tree_browser.take_pending_loads().is_empty());
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("permission denied")
+    );
+}
+
 #[test]
 fn test_schema_failed_event() {
     let mut app = App::new();
@@ -462,6 +557,57 @@ fn test_query_completed_clears_running() {
     assert!(!app.tabs[0].query_running);
 }
 
+#[test]
+fn test_session_metrics_count_queries_errors_and_drops() {
+    let mut app = App::new();
+    let results =
+        crate::db::QueryResults::new(vec![], vec![], std::time::Duration::from_millis(10), 0);
+    app.handle_event(AppEvent::QueryCompleted { results, tab_id: 0 })
+        .unwrap();
+    for error in ["syntax error", "canceling statement due to user request"] {
+        app.handle_event(AppEvent::QueryFailed {
+            error: error.to_string(),
+            position: None,
+            code: None,
+            tab_id: 0,
+        })
+        .unwrap();
+    }
+    app.handle_event(AppEvent::ConnectionLost {
+        tab_id: 0,
+        message: "gone".to_string(),
+    })
+    .unwrap();
+
+    assert_eq!(app.metrics.get(Counter::Queries), 3);
+    // A cancelled query isn't an error
+    assert_eq!(app.metrics.get(Counter::QueryErrors), 1);
+    assert_eq!(app.metrics.get(Counter::ConnectionDrops), 1);
+}
+
+#[test]
+fn test_unwritable_metrics_file_warns_at_startup() {
+    let mut settings = Settings::default();
+    settings.settings.metrics_file = "/proc/vizgres/metrics.prom".to_string();
+    let app = App::new_with_settings(&settings);
+    let status = app.status_message.as_ref().unwrap();
+    assert_eq!(status.level, StatusLevel::Warning);
+    assert!(status.message.starts_with("Config: metrics_file:"));
+    assert!(app.metrics.path().is_none());
+}
+
+#[test]
+fn test_config_file_warnings_shown_at_startup() {
+    let settings = Settings::parse("[settings]\npreveiw_rows = 20\n");
+    let app = App::new_with_settings(&settings);
+    let status = app.status_message.as_ref().unwrap();
+    assert_eq!(status.level, StatusLevel::Warning);
+    assert_eq!(
+        status.message,
+        "Config: line 2: unknown key `settings.preveiw_rows`"
+    );
+}
+
 #[test]
 fn test_query_progress_updates_rows_streaming() {
     let mut app = App::new();
@@ -506,6 +652,7 @@ fn test_query_failed_clears_rows_streaming() {
     app.handle_event(AppEvent::QueryFailed {
         error: "some error".to_string(),
         position: None,
+        code: None,
         tab_id: 0,
     })
     .unwrap();
@@ -581,6 +728,7 @@ fn test_query_failed_clears_running() {
     app.handle_event(AppEvent::QueryFailed {
         error: "some error".to_string(),
         position: None,
+        code: None,
         tab_id: 0,
     })
     .unwrap();
@@ -595,6 +743,7 @@ fn test_query_cancelled_shows_warning() {
     app.handle_event(AppEvent::QueryFailed {
         error: "ERROR: canceling statement due to user request".to_string(),
         position: None,
+        code: None,
         tab_id: 0,
     })
     .unwrap();
@@ -616,11 +765,19 @@ fn test_enter_on_table_executes_preview_query() {
                 name: "users".to_string(),
                 columns: vec![],
                 row_count: None,
+                size_bytes: None,
+                select_denied: false,
             }]),
             views: PaginatedVec::default(),
             indexes: PaginatedVec::default(),
             functions: PaginatedVec::default(),
+            sequences: PaginatedVec::default(),
+            enums: PaginatedVec::default(),
+            domains: PaginatedVec::default(),
+            loaded: true,
         }]),
+        extensions: Vec::new(),
+        databases: Vec::new(),
     };
     let mut app = App::with_connection(
         "test".to_string(),
@@ -675,10 +832,16 @@ fn test_enter_on_schema_node_expands() {
                     name: "t".to_string(),
                     columns: vec![],
                     row_count: None,
+                    size_bytes: None,
+                    select_denied: false,
                 }]),
                 views: PaginatedVec::default(),
                 indexes: PaginatedVec::default(),
                 functions: PaginatedVec::default(),
+                sequences: PaginatedVec::default(),
+                enums: PaginatedVec::default(),
+                domains: PaginatedVec::default(),
+                loaded: true,
             },
             Schema {
                 name: "other".to_string(),
@@ -686,12 +849,20 @@ fn test_enter_on_schema_node_expands() {
                     name: "x".to_string(),
                     columns: vec![],
                     row_count: None,
+                    size_bytes: None,
+                    select_denied: false,
                 }]),
                 views: PaginatedVec::default(),
                 indexes: PaginatedVec::default(),
                 functions: PaginatedVec::default(),
+                sequences: PaginatedVec::default(),
+                enums: PaginatedVec::default(),
+                domains: PaginatedVec::default(),
+                loaded: true,
             },
         ]),
+        extensions: Vec::new(),
+        databases: Vec::new(),
     };
     let mut app = App::with_connection(
         "test".to_string(),
@@ -822,124 +993,610 @@ fn test_paste_event_inserts_text() {
     assert_eq!(app.tabs[0].editor.get_content(), "SELECT 1");
 }
 
-// ── Tab management tests ─────────────────────────────────
+/// App with the tree focused on a `public.users (id, name)` table node
+fn app_with_users_table_selected() -> App {
+    app_with_users_table(false)
+}
+
+/// Like `app_with_users_table_selected`, with `select_denied` set on the
+/// table
+fn app_with_users_table(select_denied: bool) -> App {
+    use crate::db::schema::{Column, PaginatedVec, Schema, SchemaTree, Table};
+    use crate::db::types::DataType;
 
-#[test]
-fn test_new_tab() {
     let mut app = App::new();
-    assert_eq!(app.tabs.len(), 1);
-    assert!(app.new_tab());
+    app.focus = PanelFocus::TreeBrowser;
+    let column = |name: &str| Column {
+        name: name.to_string(),
+        data_type: DataType::Text,
+        is_primary_key: false,
+        foreign_key: None,
+    };
+    app.tree_browser.set_schema(SchemaTree {
+        schemas: PaginatedVec::from_vec(vec![Schema {
+            name: "public".to_string(),
+            tables: PaginatedVec::from_vec(vec![Table {
+                name: "users".to_string(),
+                columns: vec![column("id"), column("name")],
+                row_count: None,
+                size_bytes: None,
+                select_denied,
+            }]),
+            views: PaginatedVec::default(),
+            indexes: PaginatedVec::default(),
+            functions: PaginatedVec::default(),
+            sequences: PaginatedVec::default(),
+            enums: PaginatedVec::default(),
+            domains: PaginatedVec::default(),
+            loaded: true,
+        }]),
+        extensions: Vec::new(),
+        databases: Vec::new(),
+    });
+    for _ in 0..5 {
+        if app.tree_browser.selected_table().is_some() {
+            break;
+        }
+        app.tree_browser.move_down();
+    }
+    assert!(app.tree_browser.selected_table().is_some());
+    app
+}
+
+#[test]
+fn test_skip_unreadable_tables_blocks_tree_preview() {
+    let enter = KeyEvent::from(crossterm::event::KeyCode::Enter);
+    let mut app = app_with_users_table(true);
+    assert!(matches!(app.handle_key(enter), Action::ExecuteQuery { .. }));
+
+    let mut app = app_with_users_table(true);
+    app.skip_unreadable_tables = true;
+    assert!(matches!(app.handle_key(enter), Action::None));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("No SELECT privilege")
+    );
+
+    // Readable tables still preview
+    let mut app = app_with_users_table(false);
+    app.skip_unreadable_tables = true;
+    assert!(matches!(app.handle_key(enter), Action::ExecuteQuery { .. }));
+}
+
+#[test]
+fn test_paste_tsv_on_table_offers_insert() {
+    let mut app = app_with_users_table_selected();
+    app.handle_event(AppEvent::Paste("id\tname\n1\tAlice\n".to_string()))
+        .unwrap();
+    let status = app.status_message.as_ref().unwrap();
+    assert!(status.message.contains("1 row into"), "{}", status.message);
+
+    app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Char('y')));
     assert_eq!(app.tabs.len(), 2);
-    assert_eq!(app.active_tab, 1);
-    assert_eq!(app.tabs[1].id, 1);
+    assert_eq!(
+        app.tab().editor.get_content(),
+        "INSERT INTO \"public\".\"users\" (\"id\", \"name\")\nVALUES\n    ('1', 'Alice');"
+    );
     assert_eq!(app.focus, PanelFocus::QueryEditor);
 }
 
 #[test]
-fn test_close_last_tab_denied() {
-    let mut app = App::new();
-    assert!(!app.close_tab());
+fn test_paste_insert_declined_leaves_tabs_alone() {
+    let mut app = app_with_users_table_selected();
+    app.handle_event(AppEvent::Paste("id\tname\n1\tAlice\n".to_string()))
+        .unwrap();
+    app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Char('n')));
     assert_eq!(app.tabs.len(), 1);
+    assert_eq!(app.tabs[0].editor.get_content(), "");
 }
 
 #[test]
-fn test_close_running_tab_denied() {
+fn test_paste_plain_text_on_table_is_ignored() {
+    let mut app = app_with_users_table_selected();
+    app.handle_event(AppEvent::Paste("SELECT 1".to_string()))
+        .unwrap();
+    assert!(app.status_message.is_none());
+    assert!(app.pending_paste_insert.is_none());
+}
+
+// ── Opening SQL files ────────────────────────────────────
+
+#[test]
+fn test_open_loads_file_into_tab() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
+    let dir = std::env::temp_dir().join(format!("vizgres-open-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("monthly.sql"), "SELECT 1\nFROM t\n").unwrap();
+
     let mut app = App::new();
-    app.tabs[0].query_running = true;
+    app.tab_mut().editor.set_content("draft".to_string());
+    app.focus = PanelFocus::ResultsViewer;
+    app.handle_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL));
+    for c in format!("open {}/mon", dir.display()).chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    app.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+    assert_eq!(
+        app.focus,
+        PanelFocus::CommandBar,
+        "Tab completes, not cycles"
+    );
+    assert!(app.command_bar.input_text().ends_with("/monthly.sql"));
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
 
-    let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
-    app.handle_key(ctrl_w);
+    assert_eq!(app.tab().editor.get_content(), "SELECT 1\nFROM t");
+    assert_eq!(app.tab().file, Some(dir.join("monthly.sql")));
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert!(app.tabs.get(1).is_none());
+    let status = app.status_message.as_ref().unwrap();
+    assert!(status.message.ends_with("brings back the previous text"));
+
+    // The draft it replaced is one undo away
+    app.handle_key(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL));
+    assert_eq!(app.tab().editor.get_content(), "draft");
+    app.handle_key(KeyEvent::new(
+        KeyCode::Char('Z'),
+        KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+    ));
+    assert_eq!(app.tab().editor.get_content(), "SELECT 1\nFROM t");
+
+    // Tab with nothing to complete goes to the usual binding
+    app.handle_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL));
+    for c in "open nowhere-xyz".chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    app.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+    assert_eq!(app.command_bar.input_text(), "open nowhere-xyz");
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
 
-    assert_eq!(app.tabs.len(), 1);
+    app.execute_command(Command::Open {
+        path: Some(format!("{}/missing.sql", dir.display())),
+    });
+    let status = app.status_message.as_ref().unwrap();
+    assert_eq!(status.level, StatusLevel::Error);
+    assert!(
+        status.message.starts_with("Can't open"),
+        "{}",
+        status.message
+    );
+    assert_eq!(app.tab().file, Some(dir.join("monthly.sql")));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ── CSV import tests ─────────────────────────────────────
+
+fn import_file(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("vizgres-app-{}-{}", std::process::id(), name));
+    std::fs::write(&path, "ID,Name,extra\n1,Alice,x\n2,Bob,y\n").unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_import_dry_run_counts_rows() {
+    let mut app = app_with_users_table_selected();
+    let path = import_file("dry.csv");
+    let action = app.execute_command(Command::Import {
+        path: Some(path.clone()),
+        table: None,
+        dry_run: true,
+    });
+    assert!(matches!(action, Action::None));
+    let status = app.status_message.as_ref().unwrap();
+    assert!(
+        status.message.contains("2 rows from")
+            && status.message.contains("(id, name); skipped: extra"),
+        "{}",
+        status.message
+    );
+    assert!(app.jobs.is_empty());
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_import_streams_as_job() {
+    let mut app = app_with_users_table_selected();
+    let path = import_file("job.csv");
+    let Action::ImportCsv { job_id, import } = app.execute_command(Command::Import {
+        path: Some(path.clone()),
+        table: Some("public.users".to_string()),
+        dry_run: false,
+    }) else {
+        panic!("Expected ImportCsv");
+    };
+    assert_eq!(
+        import.copy_sql(),
+        "COPY \"public\".\"users\" (\"id\", \"name\") FROM STDIN (FORMAT csv)"
+    );
+    assert_eq!(app.jobs.len(), 1);
+
+    app.handle_event(AppEvent::ImportProgress { job_id, rows: 1 })
+        .unwrap();
     assert!(
         app.status_message
             .as_ref()
             .unwrap()
             .message
-            .contains("Cannot close tab while query is running")
+            .contains("1 rows imported so far")
+    );
+    app.handle_event(AppEvent::ImportCompleted {
+        job_id,
+        table: import.table,
+        rows: 2,
+    })
+    .unwrap();
+    let status = app.status_message.as_ref().unwrap();
+    assert!(
+        status
+            .message
+            .starts_with("Imported 2 rows into \"public\".\"users\""),
+        "{}",
+        status.message
     );
+    assert_eq!(status.level, StatusLevel::Success);
+    assert!(app.jobs.is_empty());
+    let _ = std::fs::remove_file(path);
 }
 
 #[test]
-fn test_close_tab_adjusts_active() {
-    let mut app = App::new();
-    app.new_tab();
-    app.new_tab();
-    assert_eq!(app.tabs.len(), 3);
+fn test_import_rejections() {
+    let mut app = app_with_users_table_selected();
+    let path = import_file("reject.csv");
+    app.execute_command(Command::Import {
+        path: Some(path.clone()),
+        table: Some("orders".to_string()),
+        dry_run: false,
+    });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Unknown table 'orders'"
+    );
 
-    // Active is tab index 2 (the last one)
-    assert_eq!(app.active_tab, 2);
-    assert!(app.close_tab());
-    assert_eq!(app.tabs.len(), 2);
-    // Active should clamp to last valid index
-    assert_eq!(app.active_tab, 1);
+    app.read_only = true;
+    let action = app.execute_command(Command::Import {
+        path: Some(path.clone()),
+        table: None,
+        dry_run: false,
+    });
+    assert!(matches!(action, Action::None));
+    assert_eq!(
+        app.status_message.as_ref().unwrap().level,
+        StatusLevel::Error
+    );
+    assert!(app.jobs.is_empty());
+    let _ = std::fs::remove_file(path);
 }
 
-#[test]
-fn test_next_tab_wraps() {
-    let mut app = App::new();
-    app.new_tab();
-    app.new_tab();
-    assert_eq!(app.active_tab, 2);
+// ── Per-connection settings override tests ──────────────────
 
-    app.next_tab();
-    assert_eq!(app.active_tab, 0);
+fn settings_with_prod_overrides() -> Settings {
+    toml::from_str(
+        r#"
+[connections.prod.settings]
+query_timeout_ms = 1000
+preview_rows = 7
+confirm_destructive = false
+theme = "light"
+"#,
+    )
+    .unwrap()
+}
 
-    app.next_tab();
-    assert_eq!(app.active_tab, 1);
+#[test]
+fn test_with_connection_applies_connection_overrides() {
+    use crate::db::schema::SchemaTree;
 
-    app.next_tab();
-    assert_eq!(app.active_tab, 2);
+    let app = App::with_connection(
+        "prod".to_string(),
+        true,
+        false,
+        SchemaTree::new(),
+        &settings_with_prod_overrides(),
+    );
+    assert_eq!(app.query_timeout_ms, 1000);
+    assert_eq!(app.tree_browser.preview_rows(), 7);
+    assert!(!app.confirm_destructive);
+    assert_eq!(app.theme.results_header, Theme::light().results_header);
+    // Not overridden — global default
+    assert_eq!(app.max_result_rows, 1000);
 }
 
 #[test]
-fn test_max_tabs() {
-    let mut app = App::new();
-    // Default max_tabs is 5, already have 1, add 4 more
-    for _ in 0..4 {
-        assert!(app.new_tab());
-    }
-    assert_eq!(app.tabs.len(), 5);
-    assert!(!app.new_tab());
-    assert_eq!(app.tabs.len(), 5);
+fn test_apply_connection_restores_globals_for_other_connection() {
+    use crate::db::schema::SchemaTree;
+
+    let mut app = App::with_connection(
+        "prod".to_string(),
+        true,
+        false,
+        SchemaTree::new(),
+        &settings_with_prod_overrides(),
+    );
+    app.apply_connection("staging".to_string(), true, false, SchemaTree::new());
+    assert_eq!(app.query_timeout_ms, 30000);
+    assert_eq!(app.tree_browser.preview_rows(), 100);
+    assert!(app.confirm_destructive);
+
+    app.apply_connection("prod".to_string(), true, false, SchemaTree::new());
+    assert_eq!(app.query_timeout_ms, 1000);
 }
 
+// ── Tab management tests ─────────────────────────────────
+
 #[test]
-fn test_configurable_max_tabs() {
-    let mut settings = Settings::default();
-    settings.settings.max_tabs = 3;
-    let mut app = App::new_with_settings(&settings);
-    assert!(app.new_tab());
+fn test_new_tab() {
+    let mut app = App::new();
+    assert_eq!(app.tabs.len(), 1);
     assert!(app.new_tab());
-    assert!(!app.new_tab()); // 3rd tab fails (already have 3)
-    assert_eq!(app.tabs.len(), 3);
+    assert_eq!(app.tabs.len(), 2);
+    assert_eq!(app.active_tab, 1);
+    assert_eq!(app.tabs[1].id, 1);
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
 }
 
 #[test]
-fn test_query_routes_to_correct_tab() {
-    let mut app = App::new();
-    app.new_tab(); // tab id=1
+fn test_history_navigation_is_per_tab() {
+    use crate::history::QueryHistory;
 
-    // Set up tab 0 (id=0) as running
-    app.active_tab = 0;
-    app.tabs[0].query_running = true;
+    let mut app = App::new();
+    app.history = QueryHistory::new(100);
+    app.history.push("SELECT 1");
+    app.history.push("SELECT 2");
 
-    // Switch to tab 1
-    app.active_tab = 1;
+    app.tab_mut().editor.set_content("draft 0".to_string());
+    app.execute_key_action(KeyAction::HistoryBack);
+    app.execute_key_action(KeyAction::HistoryBack);
+    assert_eq!(app.tab().editor.get_content(), "SELECT 1");
 
-    // Complete query for tab id=0
-    let results =
-        crate::db::QueryResults::new(vec![], vec![], std::time::Duration::from_millis(10), 0);
-    app.handle_event(AppEvent::QueryCompleted { results, tab_id: 0 })
-        .unwrap();
+    // A new tab starts browsing from the newest entry
+    app.new_tab();
+    app.execute_key_action(KeyAction::HistoryBack);
+    assert_eq!(app.tab().editor.get_content(), "SELECT 2");
 
-    // Tab 0 should have cleared running flag
-    assert!(!app.tabs[0].query_running);
+    // The first tab resumes where it left off
+    app.active_tab = 0;
+    app.execute_key_action(KeyAction::HistoryForward);
+    assert_eq!(app.tab().editor.get_content(), "SELECT 2");
+    app.execute_key_action(KeyAction::HistoryForward);
+    assert_eq!(app.tab().editor.get_content(), "draft 0");
 
-    // Focus should NOT have switched to ResultsViewer (active tab is 1, result was for tab 0)
-    assert_ne!(app.focus, PanelFocus::ResultsViewer);
+    app.active_tab = 1;
+    app.execute_key_action(KeyAction::HistoryForward);
+    assert_eq!(app.tab().editor.get_content(), "");
+}
+
+#[test]
+fn test_history_records_timing_and_rows() {
+    use crate::history::QueryHistory;
+
+    let mut app = App::new();
+    app.history = QueryHistory::new(100);
+    run_and_complete(&mut app, "SELECT 1");
+    let entry = app.history.entries().next().unwrap();
+    assert_eq!(entry.sql, "SELECT 1");
+    assert!(entry.ran_at.is_some());
+    assert_eq!(entry.duration_ms, Some(1));
+    assert_eq!(entry.rows, Some(0));
+    assert_eq!(app.tabs[0].history_entry, None);
+}
+
+#[test]
+fn test_history_records_connection_and_failure() {
+    use crate::history::QueryHistory;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.history = QueryHistory::new(100);
+    app.apply_connection("prod".to_string(), false, false, SchemaTree::new());
+    app.tab_mut().editor.set_content("SELECT 1/0".to_string());
+    app.handle_key(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE));
+    app.handle_event(AppEvent::QueryFailed {
+        error: "division by zero".to_string(),
+        position: None,
+        code: None,
+        tab_id: 0,
+    })
+    .unwrap();
+    let entry = app.history.entries().next().unwrap();
+    assert_eq!(entry.connection.as_deref(), Some("prod"));
+    assert_eq!(entry.succeeded, Some(false));
+    assert_eq!(entry.rows, None);
+    assert!(entry.duration_ms.is_some());
+    assert_eq!(app.tabs[0].history_entry, None);
+}
+
+#[test]
+fn test_history_browser_loads_selected_query() {
+    use crate::history::QueryHistory;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.history = QueryHistory::new(100);
+    app.history.push("SELECT * FROM orders");
+    app.history.push("SELECT count(*) FROM users");
+    app.history.push("VACUUM");
+    app.tab_mut().editor.set_content("draft".to_string());
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+    assert_eq!(app.focus, PanelFocus::HistoryBrowser);
+    assert_eq!(app.history_browser.match_count(), 3);
+    for c in "from".chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    assert_eq!(app.history_browser.match_count(), 2);
+    app.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    assert!(!app.history_browser.is_visible());
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert_eq!(app.tab().editor.get_content(), "SELECT * FROM orders");
+
+    // Esc leaves the editor alone
+    app.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert_eq!(app.tab().editor.get_content(), "SELECT * FROM orders");
+}
+
+#[test]
+fn test_new_tab_copies_draft_when_enabled() {
+    use crate::history::QueryHistory;
+
+    let mut app = App::new();
+    app.history = QueryHistory::new(100);
+    app.history.push("SELECT 1");
+    app.tab_mut().editor.set_content("SELECT draft".to_string());
+    app.execute_key_action(KeyAction::NewTab);
+    assert_eq!(app.tab().editor.get_content(), "");
+
+    let mut settings = Settings::default();
+    settings.settings.new_tab_copies_draft = true;
+    let mut app = App::new_with_settings(&settings);
+    app.history = QueryHistory::new(100);
+    app.history.push("SELECT 1");
+    app.tab_mut().editor.set_content("SELECT draft".to_string());
+    // While browsing, the draft is what the editor held before browsing
+    app.execute_key_action(KeyAction::HistoryBack);
+    assert_eq!(app.tab().editor.get_content(), "SELECT 1");
+    app.execute_key_action(KeyAction::NewTab);
+    assert_eq!(app.tabs.len(), 2);
+    assert_eq!(app.tab().editor.get_content(), "SELECT draft");
+}
+
+#[test]
+fn test_format_on_execute() {
+    use crate::history::QueryHistory;
+
+    let mut settings = Settings::default();
+    settings.settings.format_on_execute = true;
+    let mut app = App::new_with_settings(&settings);
+    app.history = QueryHistory::new(100);
+    app.tab_mut()
+        .editor
+        .set_content("select id from users where id = 1".to_string());
+    let formatted = "SELECT\n  id\nFROM\n  users\nWHERE\n  id = 1";
+    match app.execute_key_action(KeyAction::ExecuteQuery) {
+        Action::ExecuteQuery { sql, .. } => assert!(sql.starts_with(formatted)),
+        _ => panic!("Expected ExecuteQuery"),
+    }
+    assert_eq!(app.tab().editor.get_content(), formatted);
+    assert_eq!(app.history.entries().next().unwrap().sql, formatted);
+
+    // Meta-commands run as typed
+    app.tab_mut().query_running = false;
+    app.tab_mut().editor.set_content("\\dt".to_string());
+    app.execute_key_action(KeyAction::ExecuteQuery);
+    assert_eq!(app.tab().editor.get_content(), "\\dt");
+}
+
+#[test]
+fn test_close_last_tab_denied() {
+    let mut app = App::new();
+    assert!(!app.close_tab());
+    assert_eq!(app.tabs.len(), 1);
+}
+
+#[test]
+fn test_close_running_tab_denied() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.tabs[0].query_running = true;
+
+    let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+    app.handle_key(ctrl_w);
+
+    assert_eq!(app.tabs.len(), 1);
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("Cannot close tab while query is running")
+    );
+}
+
+#[test]
+fn test_close_tab_adjusts_active() {
+    let mut app = App::new();
+    app.new_tab();
+    app.new_tab();
+    assert_eq!(app.tabs.len(), 3);
+
+    // Active is tab index 2 (the last one)
+    assert_eq!(app.active_tab, 2);
+    assert!(app.close_tab());
+    assert_eq!(app.tabs.len(), 2);
+    // Active should clamp to last valid index
+    assert_eq!(app.active_tab, 1);
+}
+
+#[test]
+fn test_next_tab_wraps() {
+    let mut app = App::new();
+    app.new_tab();
+    app.new_tab();
+    assert_eq!(app.active_tab, 2);
+
+    app.next_tab();
+    assert_eq!(app.active_tab, 0);
+
+    app.next_tab();
+    assert_eq!(app.active_tab, 1);
+
+    app.next_tab();
+    assert_eq!(app.active_tab, 2);
+}
+
+#[test]
+fn test_max_tabs() {
+    let mut app = App::new();
+    // Default max_tabs is 5, already have 1, add 4 more
+    for _ in 0..4 {
+        assert!(app.new_tab());
+    }
+    assert_eq!(app.tabs.len(), 5);
+    assert!(!app.new_tab());
+    assert_eq!(app.tabs.len(), 5);
+}
+
+#[test]
+fn test_configurable_max_tabs() {
+    let mut settings = Settings::default();
+    settings.settings.max_tabs = 3;
+    let mut app = App::new_with_settings(&settings);
+    assert!(app.new_tab());
+    assert!(app.new_tab());
+    assert!(!app.new_tab()); // 3rd tab fails (already have 3)
+    assert_eq!(app.tabs.len(), 3);
+}
+
+#[test]
+fn test_query_routes_to_correct_tab() {
+    let mut app = App::new();
+    app.new_tab(); // tab id=1
+
+    // Set up tab 0 (id=0) as running
+    app.active_tab = 0;
+    app.tabs[0].query_running = true;
+
+    // Switch to tab 1
+    app.active_tab = 1;
+
+    // Complete query for tab id=0
+    let results =
+        crate::db::QueryResults::new(vec![], vec![], std::time::Duration::from_millis(10), 0);
+    app.handle_event(AppEvent::QueryCompleted { results, tab_id: 0 })
+        .unwrap();
+
+    // Tab 0 should have cleared running flag
+    assert!(!app.tabs[0].query_running);
+
+    // Focus should NOT have switched to ResultsViewer (active tab is 1, result was for tab 0)
+    assert_ne!(app.focus, PanelFocus::ResultsViewer);
 }
 
 #[test]
@@ -1069,6 +1726,146 @@ fn test_export_opens_prompt() {
     assert!(app.command_bar.input_text().ends_with(".csv"));
 }
 
+#[test]
+fn test_export_applies_column_casts() {
+    use crate::db::types::{CellValue, ColumnDef, DataType, Row};
+
+    let mut app = App::new();
+    let results = crate::db::QueryResults::new(
+        vec![ColumnDef {
+            name: "at".to_string(),
+            data_type: DataType::TimestampTz,
+            nullable: false,
+        }],
+        vec![Row {
+            values: vec![CellValue::DateTime("2024-03-01 09:30:00 UTC".to_string())],
+        }],
+        std::time::Duration::from_millis(1),
+        1,
+    );
+    app.tabs[0].results_viewer.set_results(results);
+
+    let path = std::env::temp_dir().join(format!("vizgres-casts-{}.csv", std::process::id()));
+    app.execute_export(ExportFormat::Csv, &format!("{} | at=notz", path.display()));
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(written, "at\n2024-03-01 09:30:00\n");
+    assert_eq!(
+        app.metrics.get(Counter::BytesExported),
+        written.len() as u64
+    );
+
+    app.execute_export(ExportFormat::Csv, &format!("{} | at=tz", path.display()));
+    let msg = app.status_message.as_ref().unwrap();
+    assert_eq!(msg.level, StatusLevel::Error);
+    assert!(msg.message.contains("Unknown cast 'tz'"), "{}", msg.message);
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_export_delimiter_options() {
+    let mut app = app_with_filterable_results();
+    let path = std::env::temp_dir().join(format!("vizgres-export-{}.tsv", std::process::id()));
+    app.execute_command(Command::Export {
+        format: Some("tsv".to_string()),
+        path: Some(format!("{} --no-header", path.display())),
+    });
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(written.lines().next(), Some("active"));
+
+    // Options without a path go into the prompt after the file name
+    app.execute_command(Command::Export {
+        format: Some("csv".to_string()),
+        path: Some("--delimiter ';'".to_string()),
+    });
+    assert_eq!(app.pending_export, Some(ExportFormat::Csv));
+    assert!(
+        app.command_bar
+            .input_text()
+            .ends_with(".csv --delimiter ';'")
+    );
+    app.pending_export = None;
+    app.command_bar.deactivate();
+
+    app.execute_command(Command::Export {
+        format: Some("json".to_string()),
+        path: Some("out.json --delimiter ;".to_string()),
+    });
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("only apply to csv and tsv")
+    );
+}
+
+#[test]
+fn test_export_command_writes_markdown() {
+    let mut app = app_with_filterable_results();
+    app.execute_command(Command::Export {
+        format: Some("md".to_string()),
+        path: None,
+    });
+    assert_eq!(app.pending_export, Some(ExportFormat::Markdown));
+    assert!(app.command_bar.input_text().ends_with(".md"));
+
+    let mut app = app_with_filterable_results();
+    let path = std::env::temp_dir().join(format!("vizgres-export-{}.md", std::process::id()));
+    app.execute_command(Command::Export {
+        format: Some("markdown".to_string()),
+        path: Some(path.display().to_string()),
+    });
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(written.starts_with("| "), "{}", written);
+    assert!(written.lines().nth(1).unwrap().starts_with("| --- |"));
+
+    let path = std::env::temp_dir().join(format!("vizgres-export-{}.xlsx", std::process::id()));
+    app.execute_command(Command::Export {
+        format: Some("xlsx".to_string()),
+        path: Some(path.display().to_string()),
+    });
+    let written = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(written.starts_with(b"PK\x03\x04"));
+
+    app.execute_command(Command::Export {
+        format: Some("xml".to_string()),
+        path: None,
+    });
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Usage: /export")
+    );
+}
+
+#[test]
+fn test_copy_results_needs_results_and_text_format() {
+    let mut app = App::new();
+    app.execute_command(Command::Copy {
+        format: Some("md".to_string()),
+    });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "No results to copy"
+    );
+    app.execute_command(Command::Copy {
+        format: Some("xlsx".to_string()),
+    });
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Usage: /copy")
+    );
+}
+
 #[test]
 fn test_dismiss_clears_pending_export() {
     use crossterm::event::{KeyCode, KeyModifiers};
@@ -1109,6 +1906,33 @@ fn test_export_json_opens_prompt() {
     assert!(app.command_bar.input_text().ends_with(".json"));
 }
 
+#[test]
+fn test_export_prompt_uses_template_and_last_directory() {
+    let mut app = App::new();
+    app.export_dirs = crate::config::ExportDirs::default();
+    app.export_filename = "{connection}_{table}.{ext}".to_string();
+    app.connection_name = Some("prod".to_string());
+    let results =
+        crate::db::QueryResults::new(vec![], vec![], std::time::Duration::from_millis(1), 0);
+    app.tabs[0].results_viewer.set_results(results);
+
+    app.start_export(ExportFormat::Csv, "");
+    assert_eq!(app.command_bar.input_text(), "prod_query.csv");
+
+    // Exporting into a directory makes it the next prompt's starting point
+    let dir = std::env::temp_dir();
+    app.remember_export_dir(&dir.join("out.csv").display().to_string());
+    app.start_export(ExportFormat::Csv, "");
+    assert_eq!(
+        app.command_bar.input_text(),
+        std::path::absolute(&dir)
+            .unwrap()
+            .join("prod_query.csv")
+            .display()
+            .to_string()
+    );
+}
+
 #[test]
 fn test_execute_query_sets_running_flag() {
     use crossterm::event::{KeyCode, KeyModifiers};
@@ -1204,11 +2028,19 @@ fn test_apply_connection_resets_state() {
                 name: "users".to_string(),
                 columns: vec![],
                 row_count: None,
+                size_bytes: None,
+                select_denied: false,
             }]),
             views: PaginatedVec::default(),
             indexes: PaginatedVec::default(),
             functions: PaginatedVec::default(),
+            sequences: PaginatedVec::default(),
+            enums: PaginatedVec::default(),
+            domains: PaginatedVec::default(),
+            loaded: true,
         }]),
+        extensions: Vec::new(),
+        databases: Vec::new(),
     };
 
     app.apply_connection("new-db".to_string(), false, false, schema);
@@ -1288,6 +2120,7 @@ fn test_query_failed_with_position_moves_cursor() {
     app.handle_event(AppEvent::QueryFailed {
         error: "syntax error".to_string(),
         position: Some(6),
+        code: None,
         tab_id: 0,
     })
     .unwrap();
@@ -1308,6 +2141,7 @@ fn test_query_failed_without_position_no_cursor_move() {
     app.handle_event(AppEvent::QueryFailed {
         error: "connection error".to_string(),
         position: None,
+        code: None,
         tab_id: 0,
     })
     .unwrap();
@@ -1426,11 +2260,19 @@ fn test_tree_preview_includes_timeout() {
                 name: "users".to_string(),
                 columns: vec![],
                 row_count: None,
+                size_bytes: None,
+                select_denied: false,
             }]),
             views: PaginatedVec::default(),
             indexes: PaginatedVec::default(),
             functions: PaginatedVec::default(),
+            sequences: PaginatedVec::default(),
+            enums: PaginatedVec::default(),
+            domains: PaginatedVec::default(),
+            loaded: true,
         }]),
+        extensions: Vec::new(),
+        databases: Vec::new(),
     };
     let mut app = App::with_connection(
         "test".to_string(),
@@ -1571,6 +2413,7 @@ fn test_transaction_state_error_transitions_to_failed() {
     app.handle_event(AppEvent::QueryFailed {
         error: "relation does not exist".to_string(),
         position: None,
+        code: None,
         tab_id: 0,
     })
     .unwrap();
@@ -1583,6 +2426,34 @@ fn test_transaction_state_error_transitions_to_failed() {
     assert_eq!(app.tabs[0].transaction_state, TransactionState::Idle);
 }
 
+#[test]
+fn test_query_error_hint_suggests_table_from_tree() {
+    let mut app = app_with_users_table_selected();
+    app.tabs[0].query_running = true;
+    app.handle_event(AppEvent::QueryFailed {
+        error: "relation \"usres\" does not exist".to_string(),
+        position: None,
+        code: Some("42P01".to_string()),
+        tab_id: 0,
+    })
+    .unwrap();
+    assert_eq!(
+        app.tabs[0].results_viewer.error_hint(),
+        Some("relation not found \u{2014} did you mean users?")
+    );
+
+    // Errors without a SQLSTATE (client-side) get no hint
+    app.tabs[0].query_running = true;
+    app.handle_event(AppEvent::QueryFailed {
+        error: "connection error".to_string(),
+        position: None,
+        code: None,
+        tab_id: 0,
+    })
+    .unwrap();
+    assert_eq!(app.tabs[0].results_viewer.error_hint(), None);
+}
+
 #[test]
 fn test_transaction_state_cancel_does_not_fail() {
     let mut app = App::new();
@@ -1593,6 +2464,7 @@ fn test_transaction_state_cancel_does_not_fail() {
     app.handle_event(AppEvent::QueryFailed {
         error: "ERROR: canceling statement due to user request".to_string(),
         position: None,
+        code: None,
         tab_id: 0,
     })
     .unwrap();
@@ -1624,952 +2496,4214 @@ fn test_transaction_state_reset_on_connection_lost() {
     assert_eq!(app.tabs[0].transaction_state, TransactionState::Idle);
 }
 
-// ── Destructive query confirmation tests ─────────────────
+#[test]
+fn test_transaction_status_event_overrides_local_state() {
+    let mut app = App::new();
+    app.tabs[0].transaction_state = TransactionState::InTransaction;
+    app.handle_event(AppEvent::TransactionStatus {
+        state: TransactionState::Failed,
+        tab_id: 0,
+    })
+    .unwrap();
+    assert_eq!(app.tabs[0].transaction_state, TransactionState::Failed);
+
+    // Unknown tab id is ignored
+    app.handle_event(AppEvent::TransactionStatus {
+        state: TransactionState::Idle,
+        tab_id: 99,
+    })
+    .unwrap();
+    assert_eq!(app.tabs[0].transaction_state, TransactionState::Failed);
+}
 
 #[test]
-fn test_is_destructive_query() {
-    assert_eq!(is_destructive_query("DROP TABLE users"), Some("DROP"));
-    assert_eq!(is_destructive_query("drop table users"), Some("DROP"));
-    assert_eq!(is_destructive_query("DROP INDEX idx_name"), Some("DROP"));
-    assert_eq!(is_destructive_query("DROP SCHEMA public"), Some("DROP"));
-    assert_eq!(is_destructive_query("DROP DATABASE mydb"), Some("DROP"));
-    assert_eq!(is_destructive_query("DROP VIEW my_view"), Some("DROP"));
-    assert_eq!(
-        is_destructive_query("DROP MATERIALIZED VIEW mv"),
-        Some("DROP")
-    );
-    assert_eq!(is_destructive_query("DROP FUNCTION fn()"), Some("DROP"));
-    assert_eq!(is_destructive_query("TRUNCATE users"), Some("TRUNCATE"));
+fn test_begin_command_executes_without_history() {
+    let mut app = App::new();
+    app.history = crate::history::QueryHistory::new(10);
+    let action = app.execute_command(Command::Begin);
+    match action {
+        Action::ExecuteQuery { sql, tab_id, .. } => {
+            assert_eq!(sql, "BEGIN");
+            assert_eq!(tab_id, 0);
+        }
+        _ => panic!("expected ExecuteQuery"),
+    }
+    assert!(app.tabs[0].query_running);
     assert_eq!(
-        is_destructive_query("DELETE FROM users"),
-        Some("DELETE without WHERE")
+        app.tabs[0].transaction_state,
+        TransactionState::InTransaction
     );
-    assert_eq!(
-        is_destructive_query("ALTER TABLE users DROP COLUMN name"),
-        Some("ALTER TABLE DROP")
+    assert!(
+        app.history
+            .back(&mut crate::history::HistoryCursor::new(), "")
+            .is_none()
     );
+}
 
-    // Safe queries
-    assert_eq!(is_destructive_query("SELECT * FROM users"), None);
-    assert_eq!(is_destructive_query("INSERT INTO users VALUES(1)"), None);
-    assert_eq!(is_destructive_query("DELETE FROM users WHERE id = 1"), None);
-    assert_eq!(
-        is_destructive_query("ALTER TABLE users ADD COLUMN age int"),
-        None
+#[test]
+fn test_begin_command_rejected_inside_transaction() {
+    let mut app = App::new();
+    app.tabs[0].transaction_state = TransactionState::InTransaction;
+    let action = app.execute_command(Command::Begin);
+    assert!(matches!(action, Action::None));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("Already in a transaction")
     );
-    assert_eq!(is_destructive_query("UPDATE users SET name = 'x'"), None);
-    assert_eq!(is_destructive_query("BEGIN"), None);
-    assert_eq!(is_destructive_query("COMMIT"), None);
 }
 
 #[test]
-fn test_destructive_query_triggers_confirmation() {
-    use crossterm::event::{KeyCode, KeyModifiers};
-
+fn test_commit_and_rollback_commands_need_transaction() {
     let mut app = App::new();
-    app.focus = PanelFocus::QueryEditor;
-    app.tabs[0]
-        .editor
-        .set_content("DROP TABLE users".to_string());
+    assert!(matches!(app.execute_command(Command::Commit), Action::None));
+    assert!(matches!(
+        app.execute_command(Command::Rollback),
+        Action::None
+    ));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("No transaction in progress")
+    );
 
-    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    let action = app.handle_key(f5);
+    // ROLLBACK is allowed (and needed) from a failed transaction
+    app.tabs[0].transaction_state = TransactionState::Failed;
+    let action = app.execute_command(Command::Rollback);
+    assert!(matches!(action, Action::ExecuteQuery { ref sql, .. } if sql == "ROLLBACK"));
+    assert_eq!(app.tabs[0].transaction_state, TransactionState::Idle);
+}
 
-    // Should NOT execute yet
-    assert!(matches!(action, Action::None));
-    assert!(app.is_confirm_pending());
-    let msg = app.status_message.as_ref().unwrap();
-    assert!(msg.message.contains("DROP"));
-    assert!(msg.message.contains("(y/N)"));
+#[test]
+fn test_transaction_command_blocked_while_query_running() {
+    let mut app = App::new();
+    app.tabs[0].query_running = true;
+    assert!(matches!(app.execute_command(Command::Begin), Action::None));
+    assert_eq!(app.tabs[0].transaction_state, TransactionState::Idle);
 }
 
 #[test]
-fn test_confirm_y_executes_query() {
+fn test_run_in_new_tab_executes_in_fresh_tab() {
+    let mut app = App::new();
+    app.tabs[0].editor.set_content("SELECT 'draft'".to_string());
+    let action = app.run_in_new_tab("SELECT 1");
+    assert_eq!(app.tab_count(), 2);
+    assert_eq!(app.active_tab, 1);
+    assert_eq!(app.tabs[1].editor.get_content(), "SELECT 1");
+    assert!(matches!(action, Action::ExecuteQuery { tab_id: 1, .. }));
+    // Original tab is untouched
+    assert_eq!(app.tabs[0].editor.get_content(), "SELECT 'draft'");
+    assert!(!app.tabs[0].query_running);
+}
+
+#[test]
+fn test_history_browser_runs_query_in_new_tab() {
+    use crate::history::QueryHistory;
     use crossterm::event::{KeyCode, KeyModifiers};
 
     let mut app = App::new();
-    app.focus = PanelFocus::QueryEditor;
-    app.tabs[0]
-        .editor
-        .set_content("DROP TABLE users".to_string());
+    app.history = QueryHistory::new(100);
+    app.history.push("SELECT * FROM orders");
+    app.tab_mut().editor.set_content("draft".to_string());
 
-    // Trigger confirmation
-    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    app.handle_key(f5);
-    assert!(app.is_confirm_pending());
+    app.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+    let action = app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT));
 
-    // Press 'y' to confirm
-    let y = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
-    let action = app.handle_key(y);
-    assert!(matches!(action, Action::ExecuteQuery { .. }));
-    assert!(!app.is_confirm_pending());
+    assert!(!app.history_browser.is_visible());
+    assert_eq!(app.tab_count(), 2);
+    assert_eq!(app.active_tab, 1);
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert_eq!(app.tabs[1].editor.get_content(), "SELECT * FROM orders");
+    assert!(app.tabs[1].query_running);
+    assert!(matches!(action, Action::ExecuteQuery { tab_id: 1, .. }));
+    assert_eq!(app.tabs[0].editor.get_content(), "draft");
 }
 
 #[test]
-fn test_confirm_n_cancels_query() {
-    use crossterm::event::{KeyCode, KeyModifiers};
-
+fn test_run_in_new_tab_respects_max_tabs() {
     let mut app = App::new();
-    app.focus = PanelFocus::QueryEditor;
-    app.tabs[0]
-        .editor
-        .set_content("TRUNCATE orders".to_string());
-
-    // Trigger confirmation
-    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    app.handle_key(f5);
-    assert!(app.is_confirm_pending());
+    app.max_tabs = 1;
+    let action = app.run_in_new_tab("SELECT 1");
+    assert!(matches!(action, Action::None));
+    assert_eq!(app.tab_count(), 1);
+}
 
-    // Press 'n' to cancel
-    let n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE);
-    let action = app.handle_key(n);
+#[test]
+fn test_run_in_new_tab_blocks_writes_in_read_only() {
+    let mut app = App::new();
+    app.read_only = true;
+    let action = app.run_in_new_tab("DELETE FROM users WHERE id = 1");
     assert!(matches!(action, Action::None));
-    assert!(!app.is_confirm_pending());
-    assert_eq!(
-        app.status_message.as_ref().unwrap().message,
-        "Query cancelled"
-    );
+    assert_eq!(app.tab_count(), 2);
+    assert!(!app.tabs[1].query_running);
 }
 
+// ── Editor find & replace tests ──────────────────────────
+
 #[test]
-fn test_confirm_esc_cancels_query() {
+fn test_find_prompt_highlights_incrementally_and_jumps() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
     let mut app = App::new();
-    app.focus = PanelFocus::QueryEditor;
     app.tabs[0]
         .editor
-        .set_content("DROP TABLE users".to_string());
+        .set_content("SELECT name FROM users WHERE name = 'x'".to_string());
+    app.handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+    assert_eq!(app.focus, PanelFocus::CommandBar);
 
-    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    app.handle_key(f5);
-    assert!(app.is_confirm_pending());
+    for c in "name".chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    assert_eq!(app.tabs[0].editor.search(), Some("name"));
 
-    let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
-    let action = app.handle_key(esc);
-    assert!(matches!(action, Action::None));
-    assert!(!app.is_confirm_pending());
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert_eq!(app.tabs[0].editor.cursor(), (0, 7));
+    assert_eq!(app.status_message.as_ref().unwrap().message, "Match 1/2");
+
+    app.handle_key(KeyEvent::new(KeyCode::F(3), KeyModifiers::NONE));
+    assert_eq!(app.tabs[0].editor.cursor(), (0, 29));
+
+    // Esc in the editor clears the highlights
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert!(app.tabs[0].editor.search().is_none());
 }
 
 #[test]
-fn test_confirm_disabled_executes_immediately() {
+fn test_find_prompt_cancel_clears_search() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
-    let mut settings = Settings::default();
-    settings.settings.confirm_destructive = false;
-    let mut app = App::new_with_settings(&settings);
-    app.focus = PanelFocus::QueryEditor;
-    app.tabs[0]
-        .editor
-        .set_content("DROP TABLE users".to_string());
-
-    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    let action = app.handle_key(f5);
-    assert!(matches!(action, Action::ExecuteQuery { .. }));
-    assert!(!app.is_confirm_pending());
+    let mut app = App::new();
+    app.tabs[0].editor.set_content("SELECT 1".to_string());
+    app.handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+    app.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE));
+    assert_eq!(app.tabs[0].editor.search(), Some("s"));
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert!(app.tabs[0].editor.search().is_none());
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
 }
 
 #[test]
-fn test_safe_query_no_confirmation() {
+fn test_replace_prompt_replaces_all() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
     let mut app = App::new();
-    app.focus = PanelFocus::QueryEditor;
     app.tabs[0]
         .editor
-        .set_content("SELECT * FROM users".to_string());
+        .set_content("SELECT * FROM old_t JOIN old_t2 ON true".to_string());
+    app.handle_key(KeyEvent::new(
+        KeyCode::Char('r'),
+        KeyModifiers::CONTROL | KeyModifiers::ALT,
+    ));
+    for c in "old_".chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    // Second prompt asks for the replacement; empty replacement is allowed
+    assert_eq!(app.focus, PanelFocus::CommandBar);
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
 
-    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    let action = app.handle_key(f5);
-    assert!(matches!(action, Action::ExecuteQuery { .. }));
-    assert!(!app.is_confirm_pending());
+    assert_eq!(
+        app.tabs[0].editor.get_content(),
+        "SELECT * FROM t JOIN t2 ON true"
+    );
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("Replaced 2 occurrences")
+    );
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
 }
 
 #[test]
-fn test_delete_with_where_is_safe() {
+fn test_find_next_without_search_reports_status() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
     let mut app = App::new();
-    app.focus = PanelFocus::QueryEditor;
-    app.tabs[0]
-        .editor
-        .set_content("DELETE FROM users WHERE id = 1".to_string());
+    app.handle_key(KeyEvent::new(KeyCode::F(3), KeyModifiers::NONE));
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "No active search"
+    );
+}
 
-    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    let action = app.handle_key(f5);
-    assert!(matches!(action, Action::ExecuteQuery { .. }));
-    assert!(!app.is_confirm_pending());
+// ── Results row filter tests ─────────────────────────────
+
+fn app_with_filterable_results() -> App {
+    use crate::db::types::{CellValue, ColumnDef, DataType, Row};
+    let mut app = App::new();
+    app.tabs[0].results_viewer.set_results(QueryResults::new(
+        vec![ColumnDef {
+            name: "status".to_string(),
+            data_type: DataType::Text,
+            nullable: false,
+        }],
+        ["active", "inactive", "active"]
+            .iter()
+            .map(|s| Row {
+                values: vec![CellValue::Text(s.to_string())],
+            })
+            .collect(),
+        std::time::Duration::from_millis(1),
+        3,
+    ));
+    app.focus = PanelFocus::ResultsViewer;
+    app
 }
 
 #[test]
-fn test_apply_connection_resets_transaction_state() {
-    use crate::db::schema::SchemaTree;
-    let mut app = App::new();
-    app.tabs[0].transaction_state = TransactionState::InTransaction;
-    app.apply_connection("test-db".to_string(), false, false, SchemaTree::new());
-    assert_eq!(app.connection_name.as_deref(), Some("test-db"));
-    assert_eq!(app.tabs[0].transaction_state, TransactionState::Idle);
+fn test_column_histogram_counts_loaded_rows() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = app_with_filterable_results();
+    app.handle_key(KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT));
+    assert_eq!(app.focus, PanelFocus::Histogram);
+    assert_eq!(app.histogram.column(), "status");
+    assert_eq!(app.histogram.bars()[0].value.as_deref(), Some("active"));
+    assert_eq!(app.histogram.bars()[0].count, 2);
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
+    assert!(!app.histogram.is_visible());
+
+    // Only rows passing the filter are counted
+    app.tab_mut().results_viewer.set_filter("inactive").unwrap();
+    app.handle_key(KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT));
+    assert_eq!(app.histogram.rows(), 1);
+    assert_eq!(app.histogram.distinct(), 1);
 }
 
 #[test]
-fn test_close_tab_with_active_transaction_warns() {
+fn test_pinned_results_survive_rerun() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
-    let mut app = App::new();
-    app.focus = PanelFocus::QueryEditor;
+    let mut app = app_with_filterable_results();
+    app.handle_key(KeyEvent::new(KeyCode::Char('P'), KeyModifiers::SHIFT));
+    assert!(app.tabs[0].pinned);
 
-    // Open a second tab so we can close one
-    app.new_tab();
+    run_and_complete(&mut app, "SELECT 1");
     assert_eq!(app.tabs.len(), 2);
+    assert_eq!(app.active_tab, 1);
+    assert_eq!(app.tabs[1].editor.get_content(), "SELECT 1");
+    assert_eq!(app.tabs[1].results_viewer.results().unwrap().row_count, 0);
+    // The pinned tab still shows its original rows
+    assert_eq!(app.tabs[0].results_viewer.results().unwrap().row_count, 3);
 
-    // Set transaction state on active tab (tab 2)
-    app.tab_mut().transaction_state = TransactionState::InTransaction;
+    // Unpinned, a re-run replaces the results in place
+    app.active_tab = 0;
+    app.focus = PanelFocus::ResultsViewer;
+    app.handle_key(KeyEvent::new(KeyCode::Char('P'), KeyModifiers::SHIFT));
+    assert!(!app.tabs[0].pinned);
+    run_and_complete(&mut app, "SELECT 2");
+    assert_eq!(app.tabs.len(), 2);
+    assert_eq!(app.tabs[0].results_viewer.results().unwrap().row_count, 0);
+}
 
-    // Close tab — should succeed with warning
-    let close_key = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
-    let action = app.handle_key(close_key);
-    assert!(matches!(action, Action::TabClosed { .. }));
+#[test]
+fn test_pinned_results_stash_when_tabs_full() {
+    let mut settings = Settings::default();
+    settings.settings.max_tabs = 1;
+    let mut app = App::new_with_settings(&settings);
+    app.tabs[0]
+        .results_viewer
+        .set_results(status_results(&["a"]));
+    app.tabs[0].pinned = true;
+    run_and_complete(&mut app, "SELECT 1");
     assert_eq!(app.tabs.len(), 1);
-
-    // Should have set a warning status about uncommitted transaction
-    assert!(app.status_message.is_some());
-    let msg = &app.status_message.unwrap().message;
-    assert!(msg.contains("uncommitted transaction"));
+    assert_eq!(app.tabs[0].results_viewer.results().unwrap().row_count, 1);
+    assert!(app.stashes.iter().any(|(n, _)| n == "pin1"));
 }
 
 #[test]
-fn test_close_tab_blocks_while_query_running() {
+fn test_filter_rows_prompt_applies_filter() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
-    let mut app = App::new();
-    app.focus = PanelFocus::QueryEditor;
+    let mut app = app_with_filterable_results();
+    app.handle_key(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::CommandBar);
+    for c in "status = active".chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
 
-    // Open second tab and mark query as running
-    app.new_tab();
-    app.tab_mut().query_running = true;
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
+    assert_eq!(app.tab().results_viewer.filter_counts(), Some((2, 3)));
+    let msg = app.status_message.as_ref().unwrap();
+    assert_eq!(msg.message, "Filter: 2 of 3 rows");
+}
 
-    let close_key = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
-    let action = app.handle_key(close_key);
-    assert!(matches!(action, Action::None));
-    assert_eq!(app.tabs.len(), 2); // Tab not closed
+#[test]
+fn test_filter_rows_unknown_column_reports_error() {
+    let mut app = app_with_filterable_results();
+    app.finish_row_filter("state = active");
+    assert!(app.tab().results_viewer.filter().is_none());
+    let msg = app.status_message.as_ref().unwrap();
+    assert_eq!(msg.level, StatusLevel::Error);
 }
 
 #[test]
-fn test_transaction_state_isolated_per_tab() {
+fn test_escape_in_results_clears_filter() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
-    let mut app = App::new();
-    app.focus = PanelFocus::QueryEditor;
+    let mut app = app_with_filterable_results();
+    app.finish_row_filter("inactive");
+    assert_eq!(app.tab().results_viewer.filter_counts(), Some((1, 3)));
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert!(app.tab().results_viewer.filter().is_none());
+}
 
-    // Tab 0: start a transaction
-    app.tabs[0].editor.set_content("BEGIN".to_string());
-    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    app.handle_key(f5);
-    assert_eq!(
-        app.tabs[0].transaction_state,
-        TransactionState::InTransaction
+#[test]
+fn test_filter_rows_without_results_warns() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::ResultsViewer;
+    app.handle_key(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
+    assert_eq!(
+        app.status_message.as_ref().unwrap().level,
+        StatusLevel::Warning
     );
+}
 
-    // Open tab 1 — should start Idle
-    app.new_tab();
-    assert_eq!(app.tabs[1].transaction_state, TransactionState::Idle);
+// ── Results search tests ─────────────────────────────────
 
-    // Run a query on tab 1 — tab 0's state should be unaffected
-    app.tabs[1].editor.set_content("SELECT 1".to_string());
-    app.handle_key(f5);
-    assert_eq!(app.tabs[1].transaction_state, TransactionState::Idle);
+#[test]
+fn test_results_search_prompt_jumps_to_first_match() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = app_with_filterable_results();
+    app.handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+    assert_eq!(app.focus, PanelFocus::CommandBar);
+    for c in "inact".chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    // Highlights update as the term is typed
+    assert_eq!(app.tab().results_viewer.search_match_count(), 1);
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
     assert_eq!(
-        app.tabs[0].transaction_state,
-        TransactionState::InTransaction
+        app.tab().results_viewer.selected_cell_text().as_deref(),
+        Some("inactive")
     );
+    assert_eq!(app.status_message.as_ref().unwrap().message, "Match 1/1");
 }
 
 #[test]
-fn test_close_tab_with_failed_transaction_warns() {
+fn test_results_search_n_steps_matches_instead_of_paging() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
-    let mut app = App::new();
-    app.focus = PanelFocus::QueryEditor;
-    app.new_tab();
+    let mut app = app_with_filterable_results();
+    app.finish_search_prompt(SearchPrompt::Results, "active");
+    assert_eq!(app.status_message.as_ref().unwrap().message, "Match 2/3");
 
-    // Set failed transaction state on active tab
-    app.tab_mut().transaction_state = TransactionState::Failed;
+    let action = app.handle_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+    assert!(matches!(action, Action::None));
+    assert_eq!(app.status_message.as_ref().unwrap().message, "Match 3/3");
 
-    let close_key = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
-    let action = app.handle_key(close_key);
-    assert!(matches!(action, Action::TabClosed { .. }));
-    assert!(app.status_message.is_some());
-    assert!(
-        app.status_message
-            .unwrap()
-            .message
-            .contains("uncommitted transaction")
-    );
+    app.handle_key(KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT));
+    assert_eq!(app.status_message.as_ref().unwrap().message, "Match 2/3");
 }
 
 #[test]
-fn test_connection_lost_resets_only_affected_tab() {
+fn test_results_search_escape_clears_search_before_filter() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = app_with_filterable_results();
+    app.finish_row_filter("status = active");
+    app.finish_search_prompt(SearchPrompt::Results, "act");
+    let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+    app.handle_key(esc);
+    assert!(app.tab().results_viewer.search().is_none());
+    assert!(app.tab().results_viewer.filter().is_some());
+    app.handle_key(esc);
+    assert!(app.tab().results_viewer.filter().is_none());
+}
+
+#[test]
+fn test_results_search_dismiss_clears_highlights() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = app_with_filterable_results();
+    app.handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+    app.handle_key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+    assert!(app.tab().results_viewer.search().is_some());
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert!(app.tab().results_viewer.search().is_none());
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
+}
+
+// ── Cell edit tests ──────────────────────────────────────
+
+/// Results of `SELECT id, name, upper(name) FROM public.users`
+fn app_with_editable_results() -> App {
+    use crate::db::provenance::{ResultSource, SourceColumn};
+    use crate::db::types::{CellValue, ColumnDef, DataType, Row};
+    let column = |name: &str, data_type| ColumnDef {
+        name: name.to_string(),
+        data_type,
+        nullable: true,
+    };
+    let source_col = |name: &str, type_name: &str| {
+        Some(SourceColumn {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+        })
+    };
+    let mut results = QueryResults::new(
+        vec![
+            column("id", DataType::Integer),
+            column("name", DataType::Text),
+            column("upper", DataType::Text),
+        ],
+        vec![
+            Row {
+                values: vec![
+                    CellValue::Integer(1),
+                    CellValue::Text("ada".to_string()),
+                    CellValue::Text("ADA".to_string()),
+                ],
+            },
+            Row {
+                values: vec![CellValue::Integer(2), CellValue::Null, CellValue::Null],
+            },
+        ],
+        std::time::Duration::from_millis(1),
+        2,
+    );
+    results.source = ResultSource::new(
+        "public".to_string(),
+        "users".to_string(),
+        vec![
+            source_col("id", "integer"),
+            source_col("name", "text"),
+            None,
+        ],
+        &["id".to_string()],
+    );
     let mut app = App::new();
-    app.new_tab();
+    app.tabs[0].results_viewer.set_results(results);
+    app.focus = PanelFocus::ResultsViewer;
+    app
+}
 
-    // Both tabs have active transactions
-    app.tabs[0].transaction_state = TransactionState::InTransaction;
-    app.tabs[1].transaction_state = TransactionState::Failed;
+#[test]
+fn test_edit_cell_generates_update() {
+    use crossterm::event::{KeyCode, KeyModifiers};
 
-    // Only tab 0 loses its connection
-    app.handle_event(AppEvent::ConnectionLost {
+    let mut app = app_with_editable_results();
+    app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE));
+    app.handle_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::CommandBar);
+    assert_eq!(app.command_bar.input_text(), "ada");
+    app.handle_key(KeyEvent::new(KeyCode::Char('!'), KeyModifiers::NONE));
+    let action = app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    match action {
+        Action::UpdateCell { update, tab_id, .. } => {
+            assert_eq!(tab_id, 0);
+            assert_eq!(
+                update.sql,
+                "UPDATE \"public\".\"users\" SET \"name\" = $1::text::text \
+                 WHERE \"id\" = $2::text::integer RETURNING \"id\", \"name\""
+            );
+            assert_eq!(
+                update.params,
+                vec![Some("ada!".to_string()), Some("1".to_string())]
+            );
+        }
+        _ => panic!("expected UpdateCell"),
+    }
+    assert!(app.tab().query_running);
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
+}
+
+#[test]
+fn test_edit_cell_null_prefill_and_null_value() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = app_with_editable_results();
+    app.handle_key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+    app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE));
+    app.handle_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+    assert_eq!(app.command_bar.input_text(), "NULL");
+    let action = app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match action {
+        Action::UpdateCell { update, .. } => {
+            assert_eq!(update.params, vec![None, Some("2".to_string())]);
+        }
+        _ => panic!("expected UpdateCell"),
+    }
+}
+
+#[test]
+fn test_edit_cell_refreshes_row_on_success() {
+    use crate::db::types::{CellValue, Row};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = app_with_editable_results();
+    app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE));
+    app.handle_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    app.handle_event(AppEvent::CellUpdated {
+        row: Some(Row {
+            values: vec![CellValue::Integer(1), CellValue::Text("grace".to_string())],
+        }),
         tab_id: 0,
-        message: "server crashed".to_string(),
     })
     .unwrap();
-
-    // Tab 0 reset, tab 1 unaffected
-    assert_eq!(app.tabs[0].transaction_state, TransactionState::Idle);
-    assert_eq!(app.tabs[1].transaction_state, TransactionState::Failed);
+    assert!(!app.tab().query_running);
+    assert_eq!(
+        app.tab().results_viewer.selected_cell_text().as_deref(),
+        Some("grace")
+    );
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Updated 1 row"
+    );
 }
 
-// ── Saved queries tests ──────────────────────────
-
 #[test]
-fn test_save_query_requires_saved_connection() {
-    let mut app = App::new();
-    app.connection_name = Some("test".to_string());
-    app.is_saved_connection = false;
-    app.tab_mut().editor.set_content("SELECT 1".to_string());
+fn test_edit_cell_failure_and_missing_row() {
+    use crossterm::event::{KeyCode, KeyModifiers};
 
-    let action = app.execute_command(Command::SaveQuery {
-        name: Some("q1".to_string()),
-    });
-    assert!(matches!(action, Action::None));
-    assert!(
-        app.status_message
-            .as_ref()
-            .unwrap()
-            .message
-            .contains("Save a connection")
+    let mut app = app_with_editable_results();
+    app.handle_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    app.handle_event(AppEvent::CellUpdateFailed {
+        error: "duplicate key value".to_string(),
+        tab_id: 0,
+    })
+    .unwrap();
+    assert!(!app.tab().query_running);
+    let status = app.status_message.as_ref().unwrap();
+    assert_eq!(status.message, "Update failed: duplicate key value");
+    assert_eq!(status.level, StatusLevel::Error);
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    app.handle_event(AppEvent::CellUpdated {
+        row: None,
+        tab_id: 0,
+    })
+    .unwrap();
+    assert_eq!(
+        app.status_message.as_ref().unwrap().level,
+        StatusLevel::Warning
     );
 }
 
 #[test]
-fn test_save_query_requires_editor_content() {
-    let mut app = App::new();
-    app.connection_name = Some("test".to_string());
-    app.is_saved_connection = true;
+fn test_edit_cell_rejected_for_computed_column_and_plain_results() {
+    use crossterm::event::{KeyCode, KeyModifiers};
 
-    let action = app.execute_command(Command::SaveQuery {
-        name: Some("q1".to_string()),
-    });
-    assert!(matches!(action, Action::None));
+    let mut app = app_with_editable_results();
+    for _ in 0..2 {
+        app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE));
+    }
+    app.handle_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Column upper is computed and can't be edited"
+    );
+
+    let mut app = app_with_filterable_results();
+    app.handle_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
     assert!(
         app.status_message
             .as_ref()
             .unwrap()
             .message
-            .contains("empty")
+            .starts_with("Results aren't editable")
     );
 }
 
 #[test]
-fn test_save_query_no_name_opens_prompt() {
-    let mut app = App::new();
-    app.connection_name = Some("test".to_string());
-    app.is_saved_connection = true;
-    app.tab_mut().editor.set_content("SELECT 1".to_string());
+fn test_edit_cell_blocked_in_read_only_mode() {
+    use crossterm::event::{KeyCode, KeyModifiers};
 
-    app.execute_command(Command::SaveQuery { name: None });
+    let mut app = app_with_editable_results();
+    app.read_only = true;
+    app.handle_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
+    assert_eq!(
+        app.status_message.as_ref().unwrap().level,
+        StatusLevel::Error
+    );
+}
 
-    assert!(app.pending_save_query);
-    assert!(app.command_bar.is_prompt_mode());
-    assert_eq!(app.focus, PanelFocus::CommandBar);
+// ── Follow foreign key tests ─────────────────────────────
+
+/// Load `public.users (id, name)` into the tree, with `name` referencing
+/// `fk` when given
+fn set_users_schema(app: &mut App, fk: Option<crate::db::schema::ForeignKey>) {
+    use crate::db::schema::{Column, PaginatedVec, Schema, SchemaTree, Table};
+    use crate::db::types::DataType;
+    let mut schema = Schema::unloaded("public".to_string());
+    schema.tables = PaginatedVec::from_vec(vec![Table {
+        name: "users".to_string(),
+        columns: vec![
+            Column {
+                name: "id".to_string(),
+                data_type: DataType::Integer,
+                is_primary_key: true,
+                foreign_key: None,
+            },
+            Column {
+                name: "name".to_string(),
+                data_type: DataType::Text,
+                is_primary_key: false,
+                foreign_key: fk,
+            },
+        ],
+        row_count: None,
+        size_bytes: None,
+        select_denied: false,
+    }]);
+    schema.loaded = true;
+    app.tree_browser.set_schema(SchemaTree {
+        schemas: PaginatedVec::from_vec(vec![schema]),
+        extensions: Vec::new(),
+        databases: Vec::new(),
+    });
 }
 
 #[test]
-fn test_dismiss_clears_pending_save_query() {
+fn test_preview_table_under_editor_cursor() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
     let mut app = App::new();
-    app.connection_name = Some("test".to_string());
-    app.is_saved_connection = true;
-    app.tab_mut().editor.set_content("SELECT 1".to_string());
+    set_users_schema(&mut app, None);
+    app.tab_mut()
+        .editor
+        .set_content("SELECT * FROM Public.users u".to_string());
+    app.tab_mut().editor.set_cursor_position(0, 17);
+    app.handle_key(KeyEvent::new(KeyCode::F(4), KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::TablePreview);
+    assert_eq!(app.table_preview.name(), "public.users");
+    let columns: Vec<&str> = app
+        .table_preview
+        .columns()
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(columns, vec!["id", "name"]);
+
+    // Esc goes back to the editor with its text untouched
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert!(!app.table_preview.is_visible());
+    assert_eq!(
+        app.tab().editor.get_content(),
+        "SELECT * FROM Public.users u"
+    );
 
-    app.execute_command(Command::SaveQuery { name: None });
-    assert!(app.pending_save_query);
+    // An alias isn't a table
+    app.tab_mut().editor.set_cursor_position(0, 27);
+    app.handle_key(KeyEvent::new(KeyCode::F(4), KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "No table or view named 'u' in the loaded schemas"
+    );
+}
 
-    // Press Escape to dismiss
-    app.handle_event(AppEvent::Key(KeyEvent::from(
-        crossterm::event::KeyCode::Esc,
-    )))
-    .unwrap();
+#[test]
+fn test_column_in_list_goes_to_editor_cursor() {
+    use crate::db::types::{CellValue, ColumnDef, DataType, Row};
+    use crossterm::event::{KeyCode, KeyModifiers};
 
-    assert!(!app.pending_save_query);
-    assert!(!app.command_bar.is_active());
+    let mut app = App::new();
+    app.tab_mut()
+        .editor
+        .set_content("SELECT * FROM orders WHERE customer_id ".to_string());
+    app.tab_mut().editor.set_cursor_position(0, 39);
+    app.tab_mut().results_viewer.set_results(QueryResults::new(
+        vec![ColumnDef {
+            name: "id".to_string(),
+            data_type: DataType::Integer,
+            nullable: false,
+        }],
+        [7, 9, 7]
+            .into_iter()
+            .map(|id| Row {
+                values: vec![CellValue::Integer(id)],
+            })
+            .collect(),
+        std::time::Duration::from_millis(1),
+        3,
+    ));
+    app.focus = PanelFocus::ResultsViewer;
+    app.handle_key(KeyEvent::new(KeyCode::Char('I'), KeyModifiers::SHIFT));
+    assert_eq!(
+        app.tab().editor.get_content(),
+        "SELECT * FROM orders WHERE customer_id IN (7, 9)"
+    );
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Inserted an IN list of 2 values"
+    );
 }
 
 #[test]
-fn test_expand_on_saved_query_loads_into_editor() {
-    use crate::config::SavedQuery;
-    use crate::db::schema::SchemaTree;
+fn test_follow_foreign_key_opens_referenced_row_in_new_tab() {
+    use crate::db::schema::ForeignKey;
+    use crossterm::event::{KeyCode, KeyModifiers};
 
-    let mut app = App::new();
-    app.connection_name = Some("test".to_string());
-    app.is_saved_connection = true;
-    app.focus = PanelFocus::TreeBrowser;
+    let mut app = app_with_editable_results();
+    set_users_schema(
+        &mut app,
+        Some(ForeignKey {
+            target_table: "crm.people".to_string(),
+            target_column: "full_name".to_string(),
+        }),
+    );
+    app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE));
+    let action = app.handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE));
 
-    // Set an empty schema so the tree can rebuild
-    app.tree_browser.set_schema(SchemaTree::new());
+    assert_eq!(app.tab_count(), 2);
+    assert_eq!(
+        app.tab().editor.get_content(),
+        "SELECT * FROM \"crm\".\"people\" WHERE \"full_name\" = 'ada'"
+    );
+    assert!(matches!(action, Action::ExecuteQuery { tab_id: 1, .. }));
+}
 
-    // Add a saved query to the tree (auto-expands the section)
-    app.tree_browser.set_saved_queries(vec![SavedQuery {
-        connection: "test".to_string(),
-        name: "my-query".to_string(),
-        sql: "SELECT * FROM users".to_string(),
-    }]);
+#[test]
+fn test_follow_foreign_key_explains_why_not() {
+    use crate::db::schema::ForeignKey;
+    use crossterm::event::{KeyCode, KeyModifiers};
 
-    // Move down from header to the saved query item
-    app.tree_browser.move_down();
+    let status = |app: &App| app.status_message.as_ref().unwrap().message.clone();
+    let press = |app: &mut App, c: char| {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    };
 
-    // Press Enter (Expand)
-    let key = crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Enter);
-    let action = app.handle_event(AppEvent::Key(key)).unwrap();
+    // Schema not loaded in the tree
+    let mut app = app_with_editable_results();
+    press(&mut app, 'f');
+    assert_eq!(
+        status(&app),
+        "Expand public in the tree to load its foreign keys"
+    );
 
-    assert!(matches!(action, Action::None));
-    assert_eq!(app.tab().editor.get_content(), "SELECT * FROM users");
-    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    // Column without a foreign key
+    set_users_schema(
+        &mut app,
+        Some(ForeignKey {
+            target_table: "people".to_string(),
+            target_column: "full_name".to_string(),
+        }),
+    );
+    press(&mut app, 'f');
+    assert_eq!(status(&app), "users.id has no foreign key");
+
+    // NULL value
+    press(&mut app, 'j');
+    press(&mut app, 'l');
+    press(&mut app, 'f');
+    assert_eq!(status(&app), "NULL doesn't reference a row");
+
+    // Computed column
+    press(&mut app, 'l');
+    press(&mut app, 'f');
+    assert_eq!(
+        status(&app),
+        "Column upper is computed, not read from a table"
+    );
+    assert_eq!(app.tab_count(), 1);
 }
 
-// ── Pagination tests ─────────────────────────────
+#[test]
+fn test_follow_fk_sql_quotes_value() {
+    assert_eq!(
+        follow_fk_sql("public", "users", "id", "it's"),
+        "SELECT * FROM \"public\".\"users\" WHERE \"id\" = 'it''s'"
+    );
+}
+
+// ── Message log tests ────────────────────────────────────
 
 #[test]
-fn test_pagination_state_paged_sql() {
-    let pg = PaginationState {
-        original_sql: "SELECT * FROM users".to_string(),
-        current_page: 0,
-        page_size: 500,
-        has_more: false,
-        user_has_limit: false,
-        previous_page: None,
-    };
-    assert_eq!(pg.paged_sql(), "SELECT * FROM users LIMIT 501 OFFSET 0");
-    assert_eq!(pg.offset(), 0);
+fn test_set_status_records_message_log() {
+    let mut app = App::new();
+    app.set_status(
+        "Exported 3 rows to out.csv".to_string(),
+        StatusLevel::Success,
+    );
+    app.set_status("boom".to_string(), StatusLevel::Error);
+    let logged: Vec<&str> = app.messages.entries().map(|m| m.message.as_str()).collect();
+    assert_eq!(logged, vec!["boom", "Exported 3 rows to out.csv"]);
+}
 
-    let pg2 = PaginationState {
-        current_page: 2,
-        ..pg.clone()
-    };
-    assert_eq!(pg2.paged_sql(), "SELECT * FROM users LIMIT 501 OFFSET 1000");
-    assert_eq!(pg2.offset(), 1000);
+#[test]
+fn test_messages_command_opens_and_esc_closes() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::ResultsViewer;
+    app.execute_command(Command::Messages);
+    assert_eq!(app.focus, PanelFocus::Messages);
+    assert!(app.messages.is_visible());
+
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert!(!app.messages.is_visible());
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
 }
 
+// ── Destructive query confirmation tests ─────────────────
+
 #[test]
-fn test_pagination_state_user_limit_passthrough() {
-    let pg = PaginationState {
-        original_sql: "SELECT * FROM users LIMIT 10".to_string(),
-        current_page: 0,
-        page_size: 500,
-        has_more: false,
-        user_has_limit: true,
-        previous_page: None,
-    };
-    assert_eq!(pg.paged_sql(), "SELECT * FROM users LIMIT 10");
+fn test_is_destructive_query() {
+    assert_eq!(is_destructive_query("DROP TABLE users"), Some("DROP"));
+    assert_eq!(is_destructive_query("drop table users"), Some("DROP"));
+    assert_eq!(is_destructive_query("DROP INDEX idx_name"), Some("DROP"));
+    assert_eq!(is_destructive_query("DROP SCHEMA public"), Some("DROP"));
+    assert_eq!(is_destructive_query("DROP DATABASE mydb"), Some("DROP"));
+    assert_eq!(is_destructive_query("DROP VIEW my_view"), Some("DROP"));
+    assert_eq!(
+        is_destructive_query("DROP MATERIALIZED VIEW mv"),
+        Some("DROP")
+    );
+    assert_eq!(is_destructive_query("DROP FUNCTION fn()"), Some("DROP"));
+    assert_eq!(is_destructive_query("TRUNCATE users"), Some("TRUNCATE"));
+    assert_eq!(
+        is_destructive_query("DELETE FROM users"),
+        Some("DELETE without WHERE")
+    );
+    assert_eq!(
+        is_destructive_query("ALTER TABLE users DROP COLUMN name"),
+        Some("ALTER TABLE DROP")
+    );
+
+    // Safe queries
+    assert_eq!(is_destructive_query("SELECT * FROM users"), None);
+    assert_eq!(is_destructive_query("INSERT INTO users VALUES(1)"), None);
+    assert_eq!(is_destructive_query("DELETE FROM users WHERE id = 1"), None);
+    assert_eq!(
+        is_destructive_query("ALTER TABLE users ADD COLUMN age int"),
+        None
+    );
+    assert_eq!(
+        is_destructive_query("UPDATE users SET name = 'x' WHERE id = 1"),
+        None
+    );
+    assert_eq!(is_destructive_query("BEGIN"), None);
+    assert_eq!(is_destructive_query("COMMIT"), None);
 }
 
 #[test]
-fn test_prepare_execute_paginates_simple_query() {
+fn test_is_destructive_query_checks_every_statement() {
+    assert_eq!(
+        is_destructive_query("UPDATE users SET name = 'x'"),
+        Some("UPDATE without WHERE")
+    );
+    assert_eq!(
+        is_destructive_query("SELECT 1;\ndrop policy p on users;"),
+        Some("DROP")
+    );
+    // The WHERE has to be the statement's own
+    assert_eq!(
+        is_destructive_query("DELETE FROM users; SELECT * FROM t WHERE id = 1"),
+        Some("DELETE without WHERE")
+    );
+    assert_eq!(
+        is_destructive_query("UPDATE users SET note = 'where' -- where"),
+        Some("UPDATE without WHERE")
+    );
+    assert_eq!(
+        is_destructive_query("DELETE FROM users WHERE id IN (SELECT id FROM t)"),
+        None
+    );
+    assert_eq!(
+        is_destructive_query("WITH old AS (SELECT 1) DELETE FROM users"),
+        Some("DELETE without WHERE")
+    );
+    assert_eq!(
+        is_destructive_query("SELECT 'DROP TABLE users'; -- TRUNCATE"),
+        None
+    );
+}
+
+#[test]
+fn test_destructive_query_triggers_confirmation() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
     let mut app = App::new();
     app.focus = PanelFocus::QueryEditor;
-    app.tab_mut()
+    app.tabs[0]
         .editor
-        .set_content("SELECT * FROM users".to_string());
+        .set_content("DROP TABLE users".to_string());
 
     let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
     let action = app.handle_key(f5);
 
-    match action {
-        Action::ExecuteQuery { sql, max_rows, .. } => {
-            assert!(sql.contains("LIMIT"));
-            assert!(sql.contains("OFFSET"));
-            assert_eq!(max_rows, 0);
-        }
-        other => panic!(
-            "Expected ExecuteQuery, got {:?}",
-            std::mem::discriminant(&other)
-        ),
-    }
-    assert!(app.tab().pagination.is_some());
+    // Should NOT execute yet
+    assert!(matches!(action, Action::None));
+    assert!(app.is_confirm_pending());
+    let msg = app.status_message.as_ref().unwrap();
+    assert!(msg.message.contains("DROP"));
+    assert!(msg.message.contains("(y/N)"));
 }
 
 #[test]
-fn test_prepare_execute_skips_pagination_for_user_limit() {
+fn test_confirm_y_executes_query() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
     let mut app = App::new();
     app.focus = PanelFocus::QueryEditor;
-    app.tab_mut()
+    app.tabs[0]
         .editor
-        .set_content("SELECT * FROM users LIMIT 10".to_string());
+        .set_content("DROP TABLE users".to_string());
 
+    // Trigger confirmation
     let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    let action = app.handle_key(f5);
+    app.handle_key(f5);
+    assert!(app.is_confirm_pending());
 
-    match action {
-        Action::ExecuteQuery { sql, max_rows, .. } => {
-            assert_eq!(sql, "SELECT * FROM users LIMIT 10");
-            assert_eq!(max_rows, 1000); // safety net
-        }
-        other => panic!(
-            "Expected ExecuteQuery, got {:?}",
-            std::mem::discriminant(&other)
-        ),
-    }
-    assert!(app.tab().pagination.is_none());
+    // Press 'y' to confirm
+    let y = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+    let action = app.handle_key(y);
+    assert!(matches!(action, Action::ExecuteQuery { .. }));
+    assert!(!app.is_confirm_pending());
 }
 
 #[test]
-fn test_prepare_execute_skips_pagination_for_explain() {
+fn test_confirm_n_cancels_query() {
     use crossterm::event::{KeyCode, KeyModifiers};
 
     let mut app = App::new();
     app.focus = PanelFocus::QueryEditor;
-    app.tab_mut()
+    app.tabs[0]
         .editor
-        .set_content("SELECT * FROM users".to_string());
+        .set_content("TRUNCATE orders".to_string());
 
-    let ctrl_e = KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL);
-    let action = app.handle_key(ctrl_e);
+    // Trigger confirmation
+    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    app.handle_key(f5);
+    assert!(app.is_confirm_pending());
 
-    match action {
-        Action::ExecuteQuery { sql, .. } => {
-            assert!(sql.starts_with("EXPLAIN"));
-            // EXPLAIN should NOT have LIMIT/OFFSET appended
-            assert!(!sql.contains("OFFSET"));
-        }
-        other => panic!(
-            "Expected ExecuteQuery, got {:?}",
-            std::mem::discriminant(&other)
-        ),
-    }
-    assert!(app.tab().pagination.is_none());
+    // Press 'n' to cancel
+    let n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE);
+    let action = app.handle_key(n);
+    assert!(matches!(action, Action::None));
+    assert!(!app.is_confirm_pending());
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Query cancelled"
+    );
 }
 
 #[test]
-fn test_next_page_when_has_more() {
+fn test_confirm_esc_cancels_query() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
     let mut app = App::new();
-    app.focus = PanelFocus::ResultsViewer;
-    app.tab_mut().pagination = Some(PaginationState {
-        original_sql: "SELECT * FROM users".to_string(),
-        current_page: 0,
-        page_size: 100,
-        has_more: true,
-        user_has_limit: false,
-        previous_page: None,
-    });
+    app.focus = PanelFocus::QueryEditor;
+    app.tabs[0]
+        .editor
+        .set_content("DROP TABLE users".to_string());
 
-    let n = KeyEvent::from(crossterm::event::KeyCode::Char('n'));
-    let action = app.handle_key(n);
+    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    app.handle_key(f5);
+    assert!(app.is_confirm_pending());
 
-    match action {
-        Action::ExecuteQuery { sql, .. } => {
-            assert!(sql.contains("OFFSET 100"));
-        }
-        other => panic!(
-            "Expected ExecuteQuery, got {:?}",
-            std::mem::discriminant(&other)
-        ),
-    }
-    assert_eq!(app.tab().pagination.as_ref().unwrap().current_page, 1);
+    let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+    let action = app.handle_key(esc);
+    assert!(matches!(action, Action::None));
+    assert!(!app.is_confirm_pending());
 }
 
 #[test]
-fn test_next_page_noop_when_no_more() {
-    let mut app = App::new();
-    app.focus = PanelFocus::ResultsViewer;
-    app.tab_mut().pagination = Some(PaginationState {
-        original_sql: "SELECT * FROM users".to_string(),
-        current_page: 0,
-        page_size: 100,
-        has_more: false,
-        user_has_limit: false,
-        previous_page: None,
-    });
+fn test_confirm_disabled_executes_immediately() {
+    use crossterm::event::{KeyCode, KeyModifiers};
 
-    let n = KeyEvent::from(crossterm::event::KeyCode::Char('n'));
-    let action = app.handle_key(n);
-    assert!(matches!(action, Action::None));
+    let mut settings = Settings::default();
+    settings.settings.confirm_destructive = false;
+    let mut app = App::new_with_settings(&settings);
+    app.focus = PanelFocus::QueryEditor;
+    app.tabs[0]
+        .editor
+        .set_content("DROP TABLE users".to_string());
+
+    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    let action = app.handle_key(f5);
+    assert!(matches!(action, Action::ExecuteQuery { .. }));
+    assert!(!app.is_confirm_pending());
 }
 
 #[test]
-fn test_prev_page_when_on_page_two() {
-    let mut app = App::new();
-    app.focus = PanelFocus::ResultsViewer;
-    app.tab_mut().pagination = Some(PaginationState {
-        original_sql: "SELECT * FROM users".to_string(),
-        current_page: 2,
-        page_size: 100,
-        has_more: true,
-        user_has_limit: false,
-        previous_page: None,
-    });
+fn test_safe_query_no_confirmation() {
+    use crossterm::event::{KeyCode, KeyModifiers};
 
-    let p = KeyEvent::from(crossterm::event::KeyCode::Char('p'));
-    let action = app.handle_key(p);
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tabs[0]
+        .editor
+        .set_content("SELECT * FROM users".to_string());
 
-    match action {
+    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    let action = app.handle_key(f5);
+    assert!(matches!(action, Action::ExecuteQuery { .. }));
+    assert!(!app.is_confirm_pending());
+}
+
+#[test]
+fn test_delete_with_where_is_safe() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tabs[0]
+        .editor
+        .set_content("DELETE FROM users WHERE id = 1".to_string());
+
+    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    let action = app.handle_key(f5);
+    assert!(matches!(action, Action::ExecuteQuery { .. }));
+    assert!(!app.is_confirm_pending());
+}
+
+#[test]
+fn test_apply_connection_resets_transaction_state() {
+    use crate::db::schema::SchemaTree;
+    let mut app = App::new();
+    app.tabs[0].transaction_state = TransactionState::InTransaction;
+    app.apply_connection("test-db".to_string(), false, false, SchemaTree::new());
+    assert_eq!(app.connection_name.as_deref(), Some("test-db"));
+    assert_eq!(app.tabs[0].transaction_state, TransactionState::Idle);
+}
+
+#[test]
+fn test_close_other_tabs_keeps_busy_ones() {
+    let mut app = App::new();
+    for _ in 0..4 {
+        app.new_tab();
+    }
+    // Tabs 0..=4; tab 1 is running a query, tab 3 is in a transaction
+    app.tabs[1].query_running = true;
+    app.tabs[3].transaction_state = TransactionState::InTransaction;
+    app.active_tab = 2;
+
+    let action = app.execute_command(Command::CloseTabs { right_only: false });
+    let Action::TabsClosed { tab_ids } = action else {
+        panic!("expected TabsClosed");
+    };
+    assert_eq!(tab_ids, vec![0, 4]);
+    let ids: Vec<usize> = app.tabs.iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(app.tab().id, 2);
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Closed 2 tabs; kept 2 with a running query or open transaction"
+    );
+}
+
+#[test]
+fn test_close_tabs_to_the_right() {
+    let mut app = App::new();
+    for _ in 0..3 {
+        app.new_tab();
+    }
+    app.active_tab = 1;
+
+    let action = app.execute_command(Command::CloseTabs { right_only: true });
+    assert!(matches!(action, Action::TabsClosed { ref tab_ids } if *tab_ids == vec![2, 3]));
+    assert_eq!(app.tabs.len(), 2);
+    assert_eq!(app.tab().id, 1);
+
+    // Nothing left to the right
+    let action = app.execute_command(Command::CloseTabs { right_only: true });
+    assert!(matches!(action, Action::None));
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "No tabs to close"
+    );
+}
+
+#[test]
+fn test_tab_connect_binds_only_the_active_tab() {
+    let mut app = App::new();
+    app.new_tab();
+    let action = app.execute_command(Command::TabConnect {
+        target: Some("postgres://me@staging.internal/app".to_string()),
+    });
+    let Action::ConnectTab { tab_id, config } = action else {
+        panic!("expected ConnectTab");
+    };
+    assert_eq!(tab_id, 1);
+    assert_eq!(config.host, "staging.internal");
+
+    app.bind_tab_connection(1, "staging".to_string(), true);
+    assert_eq!(app.tab().connection.as_ref().unwrap().name, "staging");
+    assert!(app.tab_read_only());
+    assert!(app.tabs[0].connection.is_none());
+
+    // Writes are blocked on the read-only tab only
+    assert!(matches!(
+        app.execute_sql("DELETE FROM t".to_string(), Vec::new()),
+        Action::None
+    ));
+    app.active_tab = 0;
+    assert!(!app.tab_read_only());
+
+    // Background jobs stay on the main connection
+    app.active_tab = 1;
+    assert!(matches!(
+        app.execute_command(Command::Job {
+            sql: Some("SELECT 1".to_string())
+        }),
+        Action::None
+    ));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("background jobs run on the main connection")
+    );
+}
+
+#[test]
+fn test_tab_connect_without_target_opens_picker_for_tab() {
+    let mut app = App::new();
+    app.execute_command(Command::TabConnect { target: None });
+    assert_eq!(app.focus, PanelFocus::ConnectionDialog);
+    assert_eq!(app.connecting_tab, Some(0));
+
+    // Busy tabs keep their connection
+    app.connecting_tab = None;
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut().transaction_state = TransactionState::InTransaction;
+    app.execute_command(Command::TabConnect {
+        target: Some("postgres://localhost/app".to_string()),
+    });
+    assert_eq!(app.connecting_tab, None);
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Finish this tab's query or transaction")
+    );
+}
+
+fn app_on_database(current: &str) -> App {
+    use crate::db::schema::ServerDatabase;
+
+    let mut app = App::new();
+    app.connection_name = Some("prod".to_string());
+    app.tree_browser.set_schema(SchemaTree {
+        databases: ["app", "analytics"]
+            .iter()
+            .map(|name| ServerDatabase {
+                name: name.to_string(),
+                is_current: *name == current,
+            })
+            .collect(),
+        ..SchemaTree::new()
+    });
+    app
+}
+
+#[test]
+fn test_use_database_checks_before_switching() {
+    let status = |app: &App| app.status_message.as_ref().unwrap().message.clone();
+    let use_db = |app: &mut App, name: &str| {
+        app.execute_command(Command::Use {
+            database: Some(name.to_string()),
+        })
+    };
+
+    let mut app = app_on_database("app");
+    assert!(matches!(use_db(&mut app, "app"), Action::None));
+    assert_eq!(status(&app), "Already using app");
+
+    app.new_tab();
+    app.tabs[0].query_running = true;
+    assert!(matches!(use_db(&mut app, "analytics"), Action::None));
+    assert!(status(&app).starts_with("Finish running queries"));
+
+    app.tabs[0].query_running = false;
+    assert!(matches!(
+        use_db(&mut app, "analytics"),
+        Action::UseDatabase { ref database, tab_id: 1 } if database == "analytics"
+    ));
+}
+
+#[test]
+fn test_listen_folds_channel_names() {
+    let mut app = App::new();
+    let listen = |app: &mut App, name: &str| {
+        app.execute_command(Command::Listen {
+            channel: Some(name.to_string()),
+        })
+    };
+    assert!(matches!(listen(&mut app, "orders"), Action::None));
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Not connected"
+    );
+
+    app.connection_name = Some("prod".to_string());
+    assert!(matches!(
+        listen(&mut app, "Orders"),
+        Action::Listen { ref channel } if channel == "orders"
+    ));
+    assert!(matches!(
+        listen(&mut app, "\"Orders\""),
+        Action::Listen { ref channel } if channel == "Orders"
+    ));
+
+    // Nothing to stop until the listener reports its channels
+    assert!(matches!(
+        app.execute_command(Command::Unlisten { channel: None }),
+        Action::None
+    ));
+    app.set_listen_channels(vec!["orders".to_string()]);
+    assert!(matches!(
+        app.execute_command(Command::Unlisten {
+            channel: Some("ORDERS".to_string())
+        }),
+        Action::Unlisten { channel: Some(ref c) } if c == "orders"
+    ));
+}
+
+#[test]
+fn test_notifications_collect_while_listening() {
+    let mut app = App::new();
+    app.connection_name = Some("prod".to_string());
+    app.set_listen_channels(vec!["orders".to_string()]);
+
+    let notify = |payload: &str| {
+        AppEvent::Notification(crate::db::Notification {
+            channel: "orders".to_string(),
+            payload: payload.to_string(),
+            process_id: 4242,
+        })
+    };
+    app.handle_event(notify("{\"id\": 1}")).unwrap();
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "NOTIFY orders: {\"id\": 1}"
+    );
+
+    app.execute_command(Command::Listen { channel: None });
+    assert_eq!(app.focus, PanelFocus::Notifications);
+    app.handle_event(notify("second")).unwrap();
+    let payloads: Vec<&str> = app
+        .notifications
+        .entries()
+        .map(|n| n.notification.payload.as_str())
+        .collect();
+    assert_eq!(payloads, vec!["second", "{\"id\": 1}"]);
+
+    // A lost listener forgets its channels and drops the connection
+    let action = app
+        .handle_event(AppEvent::ListenerLost(
+            "Listener connection lost".to_string(),
+        ))
+        .unwrap();
+    assert!(matches!(action, Action::Unlisten { channel: None }));
+    assert!(app.notifications.channels().is_empty());
+}
+
+#[test]
+fn test_switch_database_keeps_tabs() {
+    let mut app = app_on_database("app");
+    app.tab_mut().editor.set_content("SELECT 1".to_string());
+    app.new_tab();
+    app.tab_mut().editor.set_content("SELECT 2".to_string());
+
+    app.switch_database(
+        app_on_database("analytics")
+            .tree_browser
+            .schema()
+            .unwrap()
+            .clone(),
+    );
+    assert_eq!(app.tabs.len(), 2);
+    assert_eq!(app.tabs[0].editor.get_content(), "SELECT 1");
+    assert_eq!(app.tab().editor.get_content(), "SELECT 2");
+    let current = app
+        .tree_browser
+        .schema()
+        .unwrap()
+        .databases
+        .iter()
+        .find(|d| d.is_current)
+        .unwrap();
+    assert_eq!(current.name, "analytics");
+}
+
+#[test]
+fn test_close_tab_with_active_transaction_warns() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+
+    // Open a second tab so we can close one
+    app.new_tab();
+    assert_eq!(app.tabs.len(), 2);
+
+    // Set transaction state on active tab (tab 2)
+    app.tab_mut().transaction_state = TransactionState::InTransaction;
+
+    // Close tab — should succeed with warning
+    let close_key = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+    let action = app.handle_key(close_key);
+    assert!(matches!(action, Action::TabClosed { .. }));
+    assert_eq!(app.tabs.len(), 1);
+
+    // Should have set a warning status about uncommitted transaction
+    assert!(app.status_message.is_some());
+    let msg = &app.status_message.unwrap().message;
+    assert!(msg.contains("uncommitted transaction"));
+}
+
+#[test]
+fn test_close_tab_blocks_while_query_running() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+
+    // Open second tab and mark query as running
+    app.new_tab();
+    app.tab_mut().query_running = true;
+
+    let close_key = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+    let action = app.handle_key(close_key);
+    assert!(matches!(action, Action::None));
+    assert_eq!(app.tabs.len(), 2); // Tab not closed
+}
+
+#[test]
+fn test_transaction_state_isolated_per_tab() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+
+    // Tab 0: start a transaction
+    app.tabs[0].editor.set_content("BEGIN".to_string());
+    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    app.handle_key(f5);
+    assert_eq!(
+        app.tabs[0].transaction_state,
+        TransactionState::InTransaction
+    );
+
+    // Open tab 1 — should start Idle
+    app.new_tab();
+    assert_eq!(app.tabs[1].transaction_state, TransactionState::Idle);
+
+    // Run a query on tab 1 — tab 0's state should be unaffected
+    app.tabs[1].editor.set_content("SELECT 1".to_string());
+    app.handle_key(f5);
+    assert_eq!(app.tabs[1].transaction_state, TransactionState::Idle);
+    assert_eq!(
+        app.tabs[0].transaction_state,
+        TransactionState::InTransaction
+    );
+}
+
+#[test]
+fn test_close_tab_with_failed_transaction_warns() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.new_tab();
+
+    // Set failed transaction state on active tab
+    app.tab_mut().transaction_state = TransactionState::Failed;
+
+    let close_key = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+    let action = app.handle_key(close_key);
+    assert!(matches!(action, Action::TabClosed { .. }));
+    assert!(app.status_message.is_some());
+    assert!(
+        app.status_message
+            .unwrap()
+            .message
+            .contains("uncommitted transaction")
+    );
+}
+
+#[test]
+fn test_connection_lost_resets_only_affected_tab() {
+    let mut app = App::new();
+    app.new_tab();
+
+    // Both tabs have active transactions
+    app.tabs[0].transaction_state = TransactionState::InTransaction;
+    app.tabs[1].transaction_state = TransactionState::Failed;
+
+    // Only tab 0 loses its connection
+    app.handle_event(AppEvent::ConnectionLost {
+        tab_id: 0,
+        message: "server crashed".to_string(),
+    })
+    .unwrap();
+
+    // Tab 0 reset, tab 1 unaffected
+    assert_eq!(app.tabs[0].transaction_state, TransactionState::Idle);
+    assert_eq!(app.tabs[1].transaction_state, TransactionState::Failed);
+}
+
+// ── Saved queries tests ──────────────────────────
+
+#[test]
+fn test_save_query_requires_saved_connection() {
+    let mut app = App::new();
+    app.connection_name = Some("test".to_string());
+    app.is_saved_connection = false;
+    app.tab_mut().editor.set_content("SELECT 1".to_string());
+
+    let action = app.execute_command(Command::SaveQuery {
+        name: Some("q1".to_string()),
+    });
+    assert!(matches!(action, Action::None));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("Save a connection")
+    );
+}
+
+#[test]
+fn test_save_query_requires_editor_content() {
+    let mut app = App::new();
+    app.connection_name = Some("test".to_string());
+    app.is_saved_connection = true;
+
+    let action = app.execute_command(Command::SaveQuery {
+        name: Some("q1".to_string()),
+    });
+    assert!(matches!(action, Action::None));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("empty")
+    );
+}
+
+#[test]
+fn test_save_query_no_name_opens_prompt() {
+    let mut app = App::new();
+    app.connection_name = Some("test".to_string());
+    app.is_saved_connection = true;
+    app.tab_mut().editor.set_content("SELECT 1".to_string());
+
+    app.execute_command(Command::SaveQuery { name: None });
+
+    assert!(app.pending_save_query);
+    assert!(app.command_bar.is_prompt_mode());
+    assert_eq!(app.focus, PanelFocus::CommandBar);
+}
+
+#[test]
+fn test_dismiss_clears_pending_save_query() {
+    let mut app = App::new();
+    app.connection_name = Some("test".to_string());
+    app.is_saved_connection = true;
+    app.tab_mut().editor.set_content("SELECT 1".to_string());
+
+    app.execute_command(Command::SaveQuery { name: None });
+    assert!(app.pending_save_query);
+
+    // Press Escape to dismiss
+    app.handle_event(AppEvent::Key(KeyEvent::from(
+        crossterm::event::KeyCode::Esc,
+    )))
+    .unwrap();
+
+    assert!(!app.pending_save_query);
+    assert!(!app.command_bar.is_active());
+}
+
+#[test]
+fn test_expand_on_saved_query_loads_into_editor() {
+    use crate::config::SavedQuery;
+    use crate::db::schema::SchemaTree;
+
+    let mut app = App::new();
+    app.connection_name = Some("test".to_string());
+    app.is_saved_connection = true;
+    app.focus = PanelFocus::TreeBrowser;
+
+    // Set an empty schema so the tree can rebuild
+    app.tree_browser.set_schema(SchemaTree::new());
+
+    // Add a saved query to the tree (auto-expands the section)
+    app.tree_browser.set_saved_queries(vec![SavedQuery {
+        connection: "test".to_string(),
+        name: "my-query".to_string(),
+        sql: "SELECT * FROM users".to_string(),
+    }]);
+
+    // Move down from header to the saved query item
+    app.tree_browser.move_down();
+
+    // Press Enter (Expand)
+    let key = crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Enter);
+    let action = app.handle_event(AppEvent::Key(key)).unwrap();
+
+    assert!(matches!(action, Action::None));
+    assert_eq!(app.tab().editor.get_content(), "SELECT * FROM users");
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+}
+
+// ── Pagination tests ─────────────────────────────
+
+#[test]
+fn test_pagination_state_paged_sql() {
+    let pg = PaginationState {
+        original_sql: "SELECT * FROM users".to_string(),
+        current_page: 0,
+        page_size: 500,
+        has_more: false,
+        user_has_limit: false,
+        previous_page: None,
+        params: Vec::new(),
+    };
+    assert_eq!(pg.paged_sql(), "SELECT * FROM users LIMIT 501 OFFSET 0");
+    assert_eq!(pg.offset(), 0);
+
+    let pg2 = PaginationState {
+        current_page: 2,
+        ..pg.clone()
+    };
+    assert_eq!(pg2.paged_sql(), "SELECT * FROM users LIMIT 501 OFFSET 1000");
+    assert_eq!(pg2.offset(), 1000);
+}
+
+#[test]
+fn test_pagination_state_user_limit_passthrough() {
+    let pg = PaginationState {
+        original_sql: "SELECT * FROM users LIMIT 10".to_string(),
+        current_page: 0,
+        page_size: 500,
+        has_more: false,
+        user_has_limit: true,
+        previous_page: None,
+        params: Vec::new(),
+    };
+    assert_eq!(pg.paged_sql(), "SELECT * FROM users LIMIT 10");
+}
+
+#[test]
+fn test_prepare_execute_paginates_simple_query() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("SELECT * FROM users".to_string());
+
+    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    let action = app.handle_key(f5);
+
+    match action {
+        Action::ExecuteQuery { sql, max_rows, .. } => {
+            assert!(sql.contains("LIMIT"));
+            assert!(sql.contains("OFFSET"));
+            assert_eq!(max_rows, 0);
+        }
+        other => panic!(
+            "Expected ExecuteQuery, got {:?}",
+            std::mem::discriminant(&other)
+        ),
+    }
+    assert!(app.tab().pagination.is_some());
+}
+
+#[test]
+fn test_dml_is_not_paginated_and_reports_affected_rows() {
+    use crate::db::types::CommandTag;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("UPDATE users SET active = false WHERE id < 40".to_string());
+    match app.handle_key(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE)) {
+        Action::ExecuteQuery { sql, .. } => {
+            assert_eq!(sql, "UPDATE users SET active = false WHERE id < 40")
+        }
+        _ => panic!("expected ExecuteQuery"),
+    }
+    assert!(app.tab().pagination.is_none());
+
+    let mut results = QueryResults::new(
+        Vec::new(),
+        Vec::new(),
+        std::time::Duration::from_millis(12),
+        0,
+    );
+    results.command = CommandTag::for_statement("UPDATE users SET active = false", 37);
+    app.handle_event(AppEvent::QueryCompleted { results, tab_id: 0 })
+        .unwrap();
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "UPDATE 37 in 12.0ms"
+    );
+}
+
+#[test]
+fn test_prepare_execute_skips_pagination_for_user_limit() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("SELECT * FROM users LIMIT 10".to_string());
+
+    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    let action = app.handle_key(f5);
+
+    match action {
+        Action::ExecuteQuery { sql, max_rows, .. } => {
+            assert_eq!(sql, "SELECT * FROM users LIMIT 10");
+            assert_eq!(max_rows, 1000); // safety net
+        }
+        other => panic!(
+            "Expected ExecuteQuery, got {:?}",
+            std::mem::discriminant(&other)
+        ),
+    }
+    assert!(app.tab().pagination.is_none());
+}
+
+#[test]
+fn test_prepare_execute_skips_pagination_for_explain() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("SELECT * FROM users".to_string());
+
+    let ctrl_e = KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL);
+    let action = app.handle_key(ctrl_e);
+
+    match action {
+        Action::ExecuteQuery { sql, .. } => {
+            assert!(sql.starts_with("EXPLAIN"));
+            // EXPLAIN should NOT have LIMIT/OFFSET appended
+            assert!(!sql.contains("OFFSET"));
+        }
+        other => panic!(
+            "Expected ExecuteQuery, got {:?}",
+            std::mem::discriminant(&other)
+        ),
+    }
+    assert!(app.tab().pagination.is_none());
+}
+
+#[test]
+fn test_next_page_when_has_more() {
+    let mut app = App::new();
+    app.focus = PanelFocus::ResultsViewer;
+    app.tab_mut().pagination = Some(PaginationState {
+        original_sql: "SELECT * FROM users".to_string(),
+        current_page: 0,
+        page_size: 100,
+        has_more: true,
+        user_has_limit: false,
+        previous_page: None,
+        params: Vec::new(),
+    });
+
+    let n = KeyEvent::from(crossterm::event::KeyCode::Char('n'));
+    let action = app.handle_key(n);
+
+    match action {
+        Action::ExecuteQuery { sql, .. } => {
+            assert!(sql.contains("OFFSET 100"));
+        }
+        other => panic!(
+            "Expected ExecuteQuery, got {:?}",
+            std::mem::discriminant(&other)
+        ),
+    }
+    assert_eq!(app.tab().pagination.as_ref().unwrap().current_page, 1);
+}
+
+#[test]
+fn test_next_page_noop_when_no_more() {
+    let mut app = App::new();
+    app.focus = PanelFocus::ResultsViewer;
+    app.tab_mut().pagination = Some(PaginationState {
+        original_sql: "SELECT * FROM users".to_string(),
+        current_page: 0,
+        page_size: 100,
+        has_more: false,
+        user_has_limit: false,
+        previous_page: None,
+        params: Vec::new(),
+    });
+
+    let n = KeyEvent::from(crossterm::event::KeyCode::Char('n'));
+    let action = app.handle_key(n);
+    assert!(matches!(action, Action::None));
+}
+
+#[test]
+fn test_prev_page_when_on_page_two() {
+    let mut app = App::new();
+    app.focus = PanelFocus::ResultsViewer;
+    app.tab_mut().pagination = Some(PaginationState {
+        original_sql: "SELECT * FROM users".to_string(),
+        current_page: 2,
+        page_size: 100,
+        has_more: true,
+        user_has_limit: false,
+        previous_page: None,
+        params: Vec::new(),
+    });
+
+    let p = KeyEvent::from(crossterm::event::KeyCode::Char('p'));
+    let action = app.handle_key(p);
+
+    match action {
+        Action::ExecuteQuery { sql, .. } => {
+            assert!(sql.contains("OFFSET 100"));
+        }
+        other => panic!(
+            "Expected ExecuteQuery, got {:?}",
+            std::mem::discriminant(&other)
+        ),
+    }
+    assert_eq!(app.tab().pagination.as_ref().unwrap().current_page, 1);
+}
+
+#[test]
+fn test_prev_page_noop_on_first_page() {
+    let mut app = App::new();
+    app.focus = PanelFocus::ResultsViewer;
+    app.tab_mut().pagination = Some(PaginationState {
+        original_sql: "SELECT * FROM users".to_string(),
+        current_page: 0,
+        page_size: 100,
+        has_more: true,
+        user_has_limit: false,
+        previous_page: None,
+        params: Vec::new(),
+    });
+
+    let p = KeyEvent::from(crossterm::event::KeyCode::Char('p'));
+    let action = app.handle_key(p);
+    assert!(matches!(action, Action::None));
+}
+
+#[test]
+fn test_query_completed_trims_pagination_probe_row() {
+    use crate::db::types::{CellValue, ColumnDef, DataType, Row};
+
+    let mut app = App::new();
+    app.tab_mut().query_running = true;
+    app.tab_mut().pagination = Some(PaginationState {
+        original_sql: "SELECT 1".to_string(),
+        current_page: 0,
+        page_size: 2,
+        has_more: false,
+        user_has_limit: false,
+        previous_page: None,
+        params: Vec::new(),
+    });
+
+    // Return 3 rows (page_size + 1) to indicate more exist
+    let cols = vec![ColumnDef {
+        name: "x".to_string(),
+        data_type: DataType::Integer,
+        nullable: false,
+    }];
+    let rows = vec![
+        Row {
+            values: vec![CellValue::Integer(1)],
+        },
+        Row {
+            values: vec![CellValue::Integer(2)],
+        },
+        Row {
+            values: vec![CellValue::Integer(3)],
+        },
+    ];
+    let results = QueryResults::new(cols, rows, std::time::Duration::from_millis(5), 3);
+
+    app.handle_event(AppEvent::QueryCompleted { results, tab_id: 0 })
+        .unwrap();
+
+    // Should have trimmed to page_size (2 rows) and set has_more
+    let pg = app.tab().pagination.as_ref().unwrap();
+    assert!(pg.has_more);
+    assert_eq!(app.tab().results_viewer.results().unwrap().rows.len(), 2);
+}
+
+#[test]
+fn test_query_failed_rolls_back_pagination_page() {
+    let mut app = App::new();
+    app.focus = PanelFocus::ResultsViewer;
+
+    // Set up pagination on page 1 with has_more
+    app.tab_mut().pagination = Some(PaginationState {
+        original_sql: "SELECT * FROM users".to_string(),
+        current_page: 1,
+        page_size: 100,
+        has_more: true,
+        user_has_limit: false,
+        previous_page: None,
+        params: Vec::new(),
+    });
+
+    // Navigate to page 2
+    let n = KeyEvent::from(crossterm::event::KeyCode::Char('n'));
+    let action = app.handle_key(n);
+    assert!(matches!(action, Action::ExecuteQuery { .. }));
+    assert_eq!(app.tab().pagination.as_ref().unwrap().current_page, 2);
+
+    // Query fails — should roll back to page 1
+    app.handle_event(AppEvent::QueryFailed {
+        error: "connection lost".to_string(),
+        position: None,
+        code: None,
+        tab_id: 0,
+    })
+    .unwrap();
+
+    let pg = app.tab().pagination.as_ref().unwrap();
+    assert_eq!(pg.current_page, 1);
+    assert!(pg.has_more); // restored
+    assert!(pg.previous_page.is_none()); // cleared after rollback
+}
+
+#[test]
+fn test_query_failed_no_rollback_on_initial_query() {
+    let mut app = App::new();
+    app.tab_mut().query_running = true;
+
+    // Pagination from initial execute (no previous_page)
+    app.tab_mut().pagination = Some(PaginationState {
+        original_sql: "SELECT * FROM users".to_string(),
+        current_page: 0,
+        page_size: 100,
+        has_more: false,
+        user_has_limit: false,
+        previous_page: None,
+        params: Vec::new(),
+    });
+
+    app.handle_event(AppEvent::QueryFailed {
+        error: "syntax error".to_string(),
+        position: None,
+        code: None,
+        tab_id: 0,
+    })
+    .unwrap();
+
+    // Page stays at 0 — no rollback needed
+    let pg = app.tab().pagination.as_ref().unwrap();
+    assert_eq!(pg.current_page, 0);
+}
+
+// ── is_write_query tests ──────────────────────────────────────
+
+#[test]
+fn test_is_write_query_detects_writes() {
+    assert_eq!(
+        is_write_query("INSERT INTO users VALUES (1)"),
+        Some("INSERT")
+    );
+    assert_eq!(is_write_query("update users set name='x'"), Some("UPDATE"));
+    assert_eq!(is_write_query("  DELETE FROM users"), Some("DELETE"));
+    assert_eq!(is_write_query("CREATE TABLE t (id int)"), Some("CREATE"));
+    assert_eq!(is_write_query("ALTER TABLE t ADD col int"), Some("ALTER"));
+    assert_eq!(is_write_query("DROP TABLE users"), Some("DROP"));
+    assert_eq!(is_write_query("TRUNCATE users"), Some("TRUNCATE"));
+    assert_eq!(
+        is_write_query("GRANT SELECT ON t TO u"),
+        Some("GRANT/REVOKE")
+    );
+    assert_eq!(
+        is_write_query("REVOKE ALL ON t FROM u"),
+        Some("GRANT/REVOKE")
+    );
+    assert_eq!(is_write_query("COMMENT ON TABLE t IS 'x'"), Some("COMMENT"));
+}
+
+#[test]
+fn test_is_write_query_allows_reads() {
+    assert_eq!(is_write_query("SELECT 1"), None);
+    assert_eq!(is_write_query("select * from users"), None);
+    assert_eq!(is_write_query("EXPLAIN ANALYZE SELECT 1"), None);
+    assert_eq!(is_write_query("  SHOW server_version"), None);
+    assert_eq!(is_write_query("BEGIN"), None);
+    assert_eq!(is_write_query("COMMIT"), None);
+    assert_eq!(is_write_query("ROLLBACK"), None);
+    assert_eq!(is_write_query("SET search_path TO public"), None);
+}
+
+// ── Read-only mode tests ──────────────────────────────────────
+
+#[test]
+fn test_read_only_blocks_write_query() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let mut app = App::new();
+    app.read_only = true;
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .insert_text("INSERT INTO users VALUES (1)");
+
+    let execute = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    let action = app.handle_key(execute);
+    assert!(matches!(action, Action::None));
+    assert!(app.status_message.is_some());
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("Read-only"),
+        "Expected read-only error, got: {}",
+        app.status_message.as_ref().unwrap().message,
+    );
+}
+
+#[test]
+fn test_read_only_allows_select() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let mut app = App::new();
+    app.read_only = true;
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut().editor.insert_text("SELECT * FROM users");
+
+    let execute = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    let action = app.handle_key(execute);
+    // Should proceed to execution (returns ExecuteQuery), not block
+    assert!(!matches!(action, Action::None));
+}
+
+#[test]
+fn test_read_only_not_set_allows_writes() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let mut app = App::new();
+    app.read_only = false;
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut().editor.insert_text("DROP TABLE users");
+
+    let execute = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    let _action = app.handle_key(execute);
+    // Without read_only, destructive confirmation dialog appears (not blocked)
+    assert!(
+        app.status_message.is_some()
+            && app
+                .status_message
+                .as_ref()
+                .unwrap()
+                .message
+                .contains("DROP"),
+    );
+}
+
+#[test]
+fn test_is_read_write_begin() {
+    assert!(is_read_write_begin("BEGIN READ WRITE"));
+    assert!(is_read_write_begin(
+        "start transaction isolation level serializable, read write;"
+    ));
+    assert!(!is_read_write_begin("BEGIN"));
+    assert!(!is_read_write_begin("BEGIN READ ONLY"));
+    assert!(!is_read_write_begin("SELECT 'read write'"));
+}
+
+#[test]
+fn test_read_only_lets_writes_through_inside_begin_read_write() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let execute = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    let mut app = App::new();
+    app.read_only = true;
+    app.focus = PanelFocus::QueryEditor;
+    let run = |app: &mut App, sql: &str| {
+        app.tab_mut().query_running = false;
+        app.tab_mut().editor.set_content(sql.to_string());
+        app.handle_key(execute)
+    };
+
+    // A plain BEGIN doesn't lift the block
+    assert!(matches!(
+        run(&mut app, "BEGIN"),
+        Action::ExecuteQuery { .. }
+    ));
+    assert!(matches!(
+        run(&mut app, "UPDATE users SET name = 'x' WHERE id = 1"),
+        Action::None
+    ));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("BEGIN READ WRITE")
+    );
+    run(&mut app, "ROLLBACK");
+
+    assert!(matches!(
+        run(&mut app, "BEGIN READ WRITE"),
+        Action::ExecuteQuery { .. }
+    ));
+    assert!(!app.tab_writes_blocked());
+    assert!(matches!(
+        run(&mut app, "UPDATE users SET name = 'x' WHERE id = 1"),
+        Action::ExecuteQuery { .. }
+    ));
+
+    // Once the transaction ends, writes are blocked again
+    run(&mut app, "COMMIT");
+    assert!(app.tab_writes_blocked());
+    assert!(matches!(
+        run(&mut app, "DELETE FROM users WHERE id = 1"),
+        Action::None
+    ));
+}
+
+#[test]
+fn test_apply_connection_sets_read_only() {
+    use crate::db::schema::SchemaTree;
+
+    let mut app = App::new();
+    assert!(!app.read_only);
+
+    // Connection with read_only = true enables it
+    app.apply_connection("prod".to_string(), true, true, SchemaTree::new());
+    assert!(app.read_only);
+
+    // Connection with read_only = false disables it (when global is false)
+    app.apply_connection("dev".to_string(), false, false, SchemaTree::new());
+    assert!(!app.read_only);
+}
+
+#[test]
+fn test_global_read_only_overrides_connection() {
+    use crate::config::settings::Settings;
+    use crate::db::schema::SchemaTree;
+
+    let mut settings = Settings::default();
+    settings.settings.read_only = true;
+
+    // Global read_only = true, connection read_only = false → still read-only
+    let app = App::with_connection(
+        "dev".to_string(),
+        false,
+        false,
+        SchemaTree::new(),
+        &settings,
+    );
+    assert!(app.read_only, "global read_only should override connection");
+}
+
+// ── Find usages ──────────────────────────────────────────────
+
+#[test]
+fn test_find_usages_sql_for_table() {
+    let sql = find_usages_sql("public", "users", None);
+    assert!(sql.contains("pg_catalog.pg_views"));
+    assert!(sql.contains("pg_catalog.pg_matviews"));
+    assert!(sql.contains("p.prosrc ~* E'\\\\musers\\\\M'"));
+    // The object itself isn't listed as its own usage
+    assert!(sql.contains("NOT (schemaname = 'public' AND viewname = 'users')"));
+}
+
+#[test]
+fn test_find_usages_sql_for_column_escapes_regex() {
+    let sql = find_usages_sql("public", "order.items", Some("it's"));
+    assert!(sql.contains("E'\\\\morder\\\\.items\\\\M'"), "{}", sql);
+    assert!(sql.contains("definition ~* E'\\\\mit''s\\\\M'"), "{}", sql);
+}
+
+#[test]
+fn test_find_usages_requires_tree_selection() {
+    let mut app = App::new();
+    app.focus = PanelFocus::TreeBrowser;
+    let action = app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Char('u')));
+    assert!(matches!(action, Action::None));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("find usages")
+    );
+}
+
+#[test]
+fn test_find_usages_on_table_runs_catalog_query() {
+    let mut app = app_with_users_table_selected();
+    let action = app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Char('u')));
+    match action {
+        Action::ExecuteQuery { sql, .. } => assert!(sql.contains("\\\\musers\\\\M")),
+        _ => panic!("expected ExecuteQuery"),
+    }
+    assert!(app.tab().query_running);
+}
+
+#[test]
+fn test_show_definition_requests_ddl_for_selected_table() {
+    let mut app = app_with_users_table_selected();
+    let action = app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Char('d')));
+    match action {
+        Action::ShowDdl { target } => assert_eq!(
+            target,
+            DdlTarget::Relation {
+                schema: "public".to_string(),
+                name: "users".to_string(),
+            }
+        ),
+        _ => panic!("expected ShowDdl"),
+    }
+}
+
+#[test]
+fn test_value_completion_loads_column_values_once() {
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    let mut app = app_with_users_table_selected();
+    app.connection_name = Some("prod".to_string());
+    app.focus = PanelFocus::QueryEditor;
+    let mut last = Action::None;
+    for c in "SELECT * FROM users u WHERE u.name = '".chars() {
+        last = app.handle_key(KeyEvent::from(KeyCode::Char(c)));
+    }
+    match last {
+        Action::LoadColumnValues {
+            schema,
+            table,
+            column,
+            ..
+        } => assert_eq!(
+            (schema, table, column),
+            ("public".into(), "users".into(), "name".into())
+        ),
+        _ => panic!("expected LoadColumnValues"),
+    }
+
+    // Asked for once; nothing to offer until the values arrive
+    assert!(matches!(
+        app.handle_key(KeyEvent::from(KeyCode::Char('a'))),
+        Action::None
+    ));
+    assert_eq!(app.tab().editor.ghost_text(), None);
+    app.handle_event(AppEvent::ColumnValuesLoaded {
+        schema: "public".to_string(),
+        table: "users".to_string(),
+        column: "name".to_string(),
+        values: Some(vec!["alice".to_string(), "bob".to_string()]),
+    })
+    .unwrap();
+    assert_eq!(app.tab().editor.ghost_text(), Some("lice'"));
+}
+
+#[test]
+fn test_insert_row_loads_form_and_confirms_the_insert() {
+    use crate::db::schema::InsertColumn;
+    use crate::db::types::DataType;
+
+    let mut app = app_with_users_table_selected();
+    match app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Char('i'))) {
+        Action::LoadInsertForm {
+            tab_id,
+            schema,
+            table,
+        } => {
+            assert_eq!(tab_id, 0);
+            assert_eq!((schema.as_str(), table.as_str()), ("public", "users"));
+        }
+        _ => panic!("expected LoadInsertForm"),
+    }
+
+    app.handle_event(AppEvent::InsertColumnsLoaded {
+        schema: "public".to_string(),
+        table: "users".to_string(),
+        columns: vec![InsertColumn {
+            name: "name".to_string(),
+            type_name: "text".to_string(),
+            data_type: DataType::Text,
+            not_null: true,
+            default: None,
+            generated: false,
+        }],
+    })
+    .unwrap();
+    assert_eq!(app.focus, PanelFocus::InsertForm);
+    app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Char('x')));
+    app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Enter));
+
+    // Nothing runs until the INSERT is confirmed
+    assert!(!app.insert_form.is_visible());
+    assert_eq!(app.focus, PanelFocus::TreeBrowser);
+    let pending = app.pending_confirm_sql.as_ref().unwrap();
+    assert_eq!(
+        pending.sql,
+        "INSERT INTO \"public\".\"users\" (\"name\")\nVALUES ('x')\nRETURNING *;"
+    );
+}
+
+#[test]
+fn test_ddl_loaded_opens_new_tab() {
+    let mut app = App::new();
+    app.focus = PanelFocus::TreeBrowser;
+    app.handle_event(AppEvent::DdlLoaded {
+        name: "public.users".to_string(),
+        ddl: "CREATE TABLE public.users (\n    id integer\n);\n".to_string(),
+    })
+    .unwrap();
+    assert_eq!(app.tabs.len(), 2);
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert!(
+        app.tab()
+            .editor
+            .get_content()
+            .starts_with("CREATE TABLE public.users")
+    );
+}
+
+#[test]
+fn test_translate_meta_command_dt() {
+    let sql = translate_meta_command("\\dt").unwrap();
+    assert!(sql.contains("relkind = 'r'"), "should query tables");
+    assert!(sql.contains("ORDER BY schema, name"));
+}
+
+#[test]
+fn test_translate_meta_command_dv() {
+    let sql = translate_meta_command("\\dv").unwrap();
+    assert!(sql.contains("relkind IN ('v', 'm')"), "should query views");
+}
+
+#[test]
+fn test_translate_meta_command_di() {
+    let sql = translate_meta_command("\\di").unwrap();
+    assert!(sql.contains("pg_index"), "should query indexes");
+    assert!(sql.contains("amname"));
+}
+
+#[test]
+fn test_translate_meta_command_dn() {
+    let sql = translate_meta_command("\\dn").unwrap();
+    assert!(sql.contains("pg_namespace"), "should query schemas");
+}
+
+#[test]
+fn test_translate_meta_command_d_table() {
+    let sql = translate_meta_command("\\d users").unwrap();
+    assert!(
+        sql.contains("relname = 'users'"),
+        "should filter by table name"
+    );
+    assert!(sql.contains("attnum > 0"), "should include columns");
+    // Expanded: indexes, constraints, referenced-by, triggers
+    assert!(sql.contains("pg_index"), "should include indexes");
+    assert!(sql.contains("pg_constraint"), "should include constraints");
+    assert!(sql.contains("pg_trigger"), "should include triggers");
+    assert!(
+        sql.contains("'Referenced by'"),
+        "should include referenced-by"
+    );
+}
+
+#[test]
+fn test_translate_meta_command_d_schema_table() {
+    let sql = translate_meta_command("\\d public.users").unwrap();
+    assert!(sql.contains("relname = 'users'"));
+    assert!(sql.contains("nspname = 'public'"));
+}
+
+#[test]
+fn test_translate_meta_command_d_without_arg_returns_none() {
+    assert!(
+        translate_meta_command("\\d").is_none(),
+        "\\d alone needs a table name"
+    );
+}
+
+#[test]
+fn test_translate_meta_command_rejects_invalid_table_name() {
+    assert!(translate_meta_command("\\d users; DROP TABLE--").is_none());
+    assert!(translate_meta_command("\\d 'injection'").is_none());
+    // Edge cases with dots
+    assert!(translate_meta_command("\\d .").is_none());
+    assert!(translate_meta_command("\\d schema.").is_none());
+    assert!(translate_meta_command("\\d .table").is_none());
+    assert!(translate_meta_command("\\d a.b.c").is_none());
+}
+
+#[test]
+fn test_translate_meta_command_not_meta() {
+    assert!(translate_meta_command("SELECT 1").is_none());
+    assert!(translate_meta_command("").is_none());
+}
+
+#[test]
+fn test_translate_meta_command_unknown() {
+    assert!(translate_meta_command("\\z").is_none());
+    assert!(translate_meta_command("\\c mydb").is_none());
+}
+
+#[test]
+fn test_translate_meta_command_with_whitespace() {
+    assert!(translate_meta_command("  \\dt  ").is_some());
+    assert!(translate_meta_command("  \\d  users  ").is_some());
+}
+
+// ── Query parameter tests ─────────────────────────────────────
+
+fn submit_prompt(app: &mut App, value: &str) -> Action {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    // Replace any prefill before typing
+    while !app.command_bar.input_text().is_empty() {
+        app.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+    }
+    for c in value.chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+}
+
+#[test]
+fn test_named_params_prompt_in_order_then_execute() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut().editor.set_content(
+        "SELECT * FROM users WHERE id = :id AND (name = :name OR :name IS NULL)".to_string(),
+    );
+
+    let action = app.handle_key(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE));
+    assert!(matches!(action, Action::None));
+    assert_eq!(app.focus, PanelFocus::CommandBar);
+
+    assert!(matches!(submit_prompt(&mut app, "42"), Action::None));
+    assert_eq!(app.focus, PanelFocus::CommandBar);
+
+    match submit_prompt(&mut app, "NULL") {
+        Action::ExecuteQuery { sql, params, .. } => {
+            assert!(sql.starts_with("SELECT * FROM users WHERE id = :id"));
+            assert_eq!(params, vec![Some("42".to_string()), None]);
+        }
+        other => panic!(
+            "Expected ExecuteQuery, got {:?}",
+            std::mem::discriminant(&other)
+        ),
+    }
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    let pagination = app.tab().pagination.as_ref().unwrap();
+    assert_eq!(pagination.params, vec![Some("42".to_string()), None]);
+}
+
+#[test]
+fn test_param_prompt_prefills_last_value_and_allows_empty() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("SELECT $1::text LIMIT 1".to_string());
+    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+
+    app.handle_key(f5);
+    assert!(matches!(
+        submit_prompt(&mut app, "hello"),
+        Action::ExecuteQuery { .. }
+    ));
+    app.tab_mut().query_running = false;
+
+    app.handle_key(f5);
+    assert_eq!(app.command_bar.input_text(), "hello");
+    match submit_prompt(&mut app, "") {
+        Action::ExecuteQuery { params, .. } => assert_eq!(params, vec![Some(String::new())]),
+        other => panic!(
+            "Expected ExecuteQuery, got {:?}",
+            std::mem::discriminant(&other)
+        ),
+    }
+}
+
+#[test]
+fn test_param_prompt_escape_cancels_query() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("SELECT * FROM users WHERE id = $1".to_string());
+    app.handle_key(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE));
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert!(app.pending_params.is_none());
+    assert!(!app.tab().query_running);
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Query cancelled"
+    );
+}
+
+#[test]
+fn test_destructive_query_keeps_params_through_confirmation() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("DELETE FROM users RETURNING id, :note::text".to_string());
+    app.handle_key(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE));
+    assert!(matches!(submit_prompt(&mut app, "purged"), Action::None));
+    assert!(app.pending_confirm_sql.is_some());
+
+    match app.handle_key(KeyEvent::from(KeyCode::Char('y'))) {
+        Action::ExecuteQuery { params, .. } => {
+            assert_eq!(params, vec![Some("purged".to_string())]);
+        }
+        other => panic!(
+            "Expected ExecuteQuery, got {:?}",
+            std::mem::discriminant(&other)
+        ),
+    }
+}
+
+#[test]
+fn test_mixed_placeholder_styles_report_error() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("SELECT $1, :name".to_string());
+    let action = app.handle_key(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE));
+    assert!(matches!(action, Action::None));
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert_eq!(
+        app.status_message.as_ref().unwrap().level,
+        StatusLevel::Error
+    );
+}
+
+// ── DROP impact tests ─────────────────────────────────────────
+
+#[test]
+fn test_drop_impact_sql_resolves_targets() {
+    let sql =
+        drop_impact_sql("DROP TABLE IF EXISTS public.users, \"Order Items\" CASCADE;").unwrap();
+    assert!(sql.contains("to_regclass('public.users')"), "{}", sql);
+    assert!(sql.contains("to_regclass('\"Order Items\"')"), "{}", sql);
+    assert!(sql.contains("pg_catalog.pg_depend"));
+
+    let sql = drop_impact_sql("drop function add(int, int), cleanup").unwrap();
+    assert!(sql.contains("to_regprocedure('add(int, int)')"), "{}", sql);
+    assert!(sql.contains("to_regproc('cleanup')"), "{}", sql);
+
+    let sql = drop_impact_sql("DROP MATERIALIZED VIEW mv").unwrap();
+    assert!(sql.contains("to_regclass('mv')"));
+    let sql = drop_impact_sql("DROP INDEX CONCURRENTLY idx").unwrap();
+    assert!(sql.contains("to_regclass('idx')"));
+    let sql = drop_impact_sql("DROP SCHEMA app RESTRICT").unwrap();
+    assert!(sql.contains("to_regnamespace('app')"));
+}
+
+#[test]
+fn test_drop_impact_sql_ignores_other_statements() {
+    assert_eq!(drop_impact_sql("DROP DATABASE shop"), None);
+    assert_eq!(drop_impact_sql("DROP ROLE app"), None);
+    assert_eq!(drop_impact_sql("TRUNCATE users"), None);
+    assert_eq!(drop_impact_sql("SELECT 'DROP TABLE users'"), None);
+    assert_eq!(drop_impact_sql("DROP TABLE"), None);
+}
+
+#[test]
+fn test_confirm_d_shows_drop_dependents_without_dropping() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("DROP TABLE users".to_string());
+    app.handle_key(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE));
+    let msg = app.status_message.as_ref().unwrap();
+    assert!(
+        msg.message.contains("d to show dependents"),
+        "{}",
+        msg.message
+    );
+
+    match app.handle_key(KeyEvent::from(KeyCode::Char('d'))) {
         Action::ExecuteQuery { sql, .. } => {
-            assert!(sql.contains("OFFSET 100"));
+            assert!(sql.starts_with("WITH RECURSIVE deps"), "{}", sql);
+            assert!(!sql.starts_with("DROP"));
+        }
+        other => panic!(
+            "Expected ExecuteQuery, got {:?}",
+            std::mem::discriminant(&other)
+        ),
+    }
+    assert!(app.pending_confirm_sql.is_none());
+    assert_eq!(app.tab().editor.get_content(), "DROP TABLE users");
+}
+
+#[test]
+fn test_confirm_d_cancels_non_drop_queries() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("TRUNCATE users".to_string());
+    app.handle_key(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE));
+    let action = app.handle_key(KeyEvent::from(KeyCode::Char('d')));
+    assert!(matches!(action, Action::None));
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Query cancelled"
+    );
+}
+
+// ── EXPLAIN options tests ─────────────────────────────────────
+
+#[test]
+fn test_explain_options_parse_and_build() {
+    let options = ExplainOptions::parse("analyze, BUFFERS verbose").unwrap();
+    assert_eq!(
+        options.explain_sql(" SELECT 1 ", true),
+        "EXPLAIN (ANALYZE, BUFFERS, VERBOSE, FORMAT JSON) SELECT 1"
+    );
+    assert_eq!(options.label(), "EXPLAIN (ANALYZE, BUFFERS, VERBOSE)");
+
+    let plain = ExplainOptions::parse("").unwrap();
+    assert_eq!(plain.explain_sql("SELECT 1", false), "EXPLAIN SELECT 1");
+    assert_eq!(
+        plain.explain_sql("SELECT 1", true),
+        "EXPLAIN (FORMAT JSON) SELECT 1"
+    );
+
+    assert!(ExplainOptions::parse("analyze, costs").is_err());
+    assert!(ExplainOptions::parse("wal").is_err());
+    assert!(ExplainOptions::parse("analyze wal settings").is_ok());
+}
+
+#[test]
+fn test_explain_command_changes_explain_key() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.execute_command(Command::Explain {
+        options: Some("analyze, buffers".to_string()),
+    });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "EXPLAIN key runs EXPLAIN (ANALYZE, BUFFERS)"
+    );
+
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut().editor.set_content("SELECT 1".to_string());
+    let ctrl_e = KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL);
+    match app.handle_key(ctrl_e) {
+        Action::ExecuteQuery { sql, .. } => {
+            assert_eq!(sql, "EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) SELECT 1");
         }
         other => panic!(
             "Expected ExecuteQuery, got {:?}",
             std::mem::discriminant(&other)
         ),
     }
-    assert_eq!(app.tab().pagination.as_ref().unwrap().current_page, 1);
 }
 
 #[test]
-fn test_prev_page_noop_on_first_page() {
+fn test_explain_command_rejects_unknown_option() {
+    let mut app = App::new();
+    app.execute_command(Command::Explain {
+        options: Some("costs".to_string()),
+    });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().level,
+        StatusLevel::Error
+    );
+    assert_eq!(app.explain_options, ExplainOptions::default());
+}
+
+#[test]
+fn test_invalid_explain_options_setting_warns() {
+    let mut settings = Settings::default();
+    settings.settings.explain_options = "analyze, bogus".to_string();
+    let app = App::new_with_settings(&settings);
+    let msg = app.status_message.as_ref().unwrap();
+    assert!(msg.message.contains("explain_options"), "{}", msg.message);
+    assert_eq!(app.explain_options, ExplainOptions::default());
+}
+
+// ── Row estimates ─────────────────────────────────────────────
+
+#[test]
+fn test_plan_row_estimate() {
+    let select =
+        r#"[{"Plan": {"Node Type": "Seq Scan", "Total Cost": 1234.5, "Plan Rows": 4523}}]"#;
+    assert_eq!(
+        plan_row_estimate(select),
+        Some(PlanEstimate {
+            rows: 4523,
+            total_cost: 1234.5
+        })
+    );
+    // UPDATE/DELETE report the rows they would change
+    let update = r#"[{"Plan": {"Node Type": "ModifyTable", "Total Cost": 80.0, "Plan Rows": 0,
+        "Plans": [{"Node Type": "Index Scan", "Total Cost": 8.3, "Plan Rows": 12}]}}]"#;
+    assert_eq!(plan_row_estimate(update).unwrap().rows, 12);
+    assert_eq!(plan_row_estimate("not json"), None);
+}
+
+#[test]
+fn test_estimate_rows_key_plans_editor_query() {
+    use crate::db::types::{CellValue, ColumnDef, DataType, Row};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut()
+        .editor
+        .set_content("SELECT * FROM orders WHERE status = 'open';".to_string());
+    let ctrl_alt_e = KeyEvent::new(
+        KeyCode::Char('e'),
+        KeyModifiers::CONTROL | KeyModifiers::ALT,
+    );
+    let Action::EstimateRows { sql, tab_id, .. } = app.handle_key(ctrl_alt_e) else {
+        panic!("expected EstimateRows");
+    };
+    assert_eq!(
+        sql,
+        "EXPLAIN (FORMAT JSON) SELECT * FROM orders WHERE status = 'open'"
+    );
+    assert_eq!(tab_id, app.tab().id);
+    // The editor's results are left alone
+    assert!(!app.tab().query_running);
+
+    let plan = r#"[{"Plan": {"Node Type": "Seq Scan", "Total Cost": 431.0, "Plan Rows": 18250}}]"#;
+    let results = QueryResults::new(
+        vec![ColumnDef {
+            name: "QUERY PLAN".to_string(),
+            data_type: DataType::Json,
+            nullable: false,
+        }],
+        vec![Row {
+            values: vec![CellValue::Json(plan.to_string())],
+        }],
+        std::time::Duration::from_millis(1),
+        1,
+    );
+    app.handle_event(AppEvent::EstimateCompleted(results))
+        .unwrap();
+    let msg = app.status_message.as_ref().unwrap();
+    assert!(
+        msg.message.starts_with("~18,250 rows estimated (cost 431)"),
+        "{}",
+        msg.message
+    );
+
+    app.tab_mut()
+        .editor
+        .set_content("SELECT * FROM t WHERE id = $1".to_string());
+    assert!(matches!(app.handle_key(ctrl_alt_e), Action::None));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("placeholders")
+    );
+}
+
+#[test]
+fn test_number_locale_formats_counts() {
+    let mut settings = Settings::default();
+    settings.settings.number_locale = "de".to_string();
+    settings.settings.cost_guard_rows = 1_000_000;
+    let app = App::new_with_settings(&settings);
+    assert!(app.status_message.is_none());
+
+    let estimate = PlanEstimate {
+        rows: 18_250_000,
+        total_cost: 431.0,
+    };
+    assert_eq!(
+        app.cost_guard
+            .exceeded(&estimate, &app.number_format)
+            .unwrap(),
+        "~18.250.000 rows (cost_guard_rows is 1.000.000)"
+    );
+
+    settings.settings.number_locale = "klingon".to_string();
+    let app = App::new_with_settings(&settings);
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("number_locale: unknown locale 'klingon'")
+    );
+}
+
+fn plan_results(rows: u64, cost: f64) -> QueryResults {
+    use crate::db::types::{CellValue, ColumnDef, DataType, Row};
+    let plan = format!(
+        r#"[{{"Plan": {{"Node Type": "Nested Loop", "Total Cost": {}, "Plan Rows": {}}}}}]"#,
+        cost, rows
+    );
+    QueryResults::new(
+        vec![ColumnDef {
+            name: "QUERY PLAN".to_string(),
+            data_type: DataType::Json,
+            nullable: false,
+        }],
+        vec![Row {
+            values: vec![CellValue::Json(plan)],
+        }],
+        std::time::Duration::from_millis(1),
+        1,
+    )
+}
+
+#[test]
+fn test_cost_guard_plans_before_running() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut settings = Settings::default();
+    settings.settings.cost_guard_rows = 1_000_000;
+    let mut app = App::new_with_settings(&settings);
+    app.focus = PanelFocus::QueryEditor;
+    let f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
+    let tab_id = app.tab().id;
+
+    // A paginated SELECT is planned with its first page's LIMIT
+    app.tab_mut()
+        .editor
+        .set_content("SELECT * FROM a, b ORDER BY 1;".to_string());
+    let Action::CheckCost { sql, .. } = app.handle_key(f5) else {
+        panic!("expected CheckCost");
+    };
+    assert_eq!(
+        sql,
+        "EXPLAIN (FORMAT JSON) SELECT * FROM a, b ORDER BY 1 LIMIT 1001"
+    );
+    assert!(app.tab().query_running);
+
+    // Under the threshold it runs as usual
+    let action = app
+        .handle_event(AppEvent::CostChecked {
+            results: plan_results(500, 10.0),
+            tab_id,
+        })
+        .unwrap();
+    assert!(matches!(action, Action::ExecuteQuery { .. }));
+    assert!(app.tab().pagination.is_some());
+    app.handle_event(AppEvent::QueryCompleted {
+        results: QueryResults::new(vec![], vec![], std::time::Duration::from_millis(1), 0),
+        tab_id,
+    })
+    .unwrap();
+
+    // Over it, warn asks first
+    app.tab_mut()
+        .editor
+        .set_content("UPDATE big SET x = 1 WHERE y > 0".to_string());
+    app.focus = PanelFocus::QueryEditor;
+    assert!(matches!(app.handle_key(f5), Action::CheckCost { .. }));
+    let action = app
+        .handle_event(AppEvent::CostChecked {
+            results: plan_results(50_000_000, 9e6),
+            tab_id,
+        })
+        .unwrap();
+    assert!(matches!(action, Action::None));
+    assert!(app.is_confirm_pending());
+    assert!(!app.tab().query_running);
+    let msg = &app.status_message.as_ref().unwrap().message;
+    assert!(msg.contains("~50,000,000 rows"), "{}", msg);
+    let yes = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+    assert!(matches!(app.handle_key(yes), Action::ExecuteQuery { .. }));
+    app.handle_event(AppEvent::QueryCompleted {
+        results: QueryResults::new(vec![], vec![], std::time::Duration::from_millis(1), 0),
+        tab_id,
+    })
+    .unwrap();
+
+    // Refuse blocks it until /force
+    app.cost_guard.action = CostGuardAction::Refuse;
+    app.focus = PanelFocus::QueryEditor;
+    assert!(matches!(app.handle_key(f5), Action::CheckCost { .. }));
+    let action = app
+        .handle_event(AppEvent::CostChecked {
+            results: plan_results(50_000_000, 9e6),
+            tab_id,
+        })
+        .unwrap();
+    assert!(matches!(action, Action::None));
+    assert!(!app.is_confirm_pending());
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Refused")
+    );
+    assert!(matches!(
+        app.execute_command(Command::Force),
+        Action::ExecuteQuery { .. }
+    ));
+}
+
+#[test]
+fn test_cost_guard_skips_what_explain_cannot_plan() {
+    let mut settings = Settings::default();
+    settings.settings.cost_guard_cost = 1000;
+    let mut app = App::new_with_settings(&settings);
+    for sql in [
+        "BEGIN",
+        "SELECT 1; SELECT 2",
+        "CREATE TABLE t (id int)",
+        "EXPLAIN SELECT 1",
+    ] {
+        assert!(
+            matches!(run_and_complete(&mut app, sql), Action::None),
+            "{}",
+            sql
+        );
+        assert!(app.tab().cost_check.is_none(), "{}", sql);
+    }
+}
+
+// ── Result stash tests ────────────────────────────────────────
+
+fn status_results(statuses: &[&str]) -> QueryResults {
+    use crate::db::types::{CellValue, ColumnDef, DataType, Row};
+    QueryResults::new(
+        vec![ColumnDef {
+            name: "status".to_string(),
+            data_type: DataType::Text,
+            nullable: false,
+        }],
+        statuses
+            .iter()
+            .map(|s| Row {
+                values: vec![CellValue::Text(s.to_string())],
+            })
+            .collect(),
+        std::time::Duration::from_millis(1),
+        statuses.len(),
+    )
+}
+
+// ── Masking tests ────────────────────────────────────────
+
+#[test]
+fn test_mask_columns_until_reveal() {
+    let mut settings = crate::config::Settings::default();
+    settings.settings.mask_columns = vec!["STAT*".to_string()];
+    let mut app = App::new_with_settings(&settings);
+    run_and_complete(&mut app, "SELECT status FROM orders LIMIT 1");
+    app.handle_event(AppEvent::QueryCompleted {
+        results: status_results(&["secret"]),
+        tab_id: 0,
+    })
+    .unwrap();
+    let cell = |app: &App| app.tab().results_viewer.selected_cell_text();
+    assert_eq!(cell(&app).as_deref(), Some(crate::mask::MASK));
+
+    app.execute_command(Command::Reveal);
+    assert_eq!(cell(&app).as_deref(), Some("secret"));
+    assert_eq!(
+        app.status_message.as_ref().unwrap().level,
+        StatusLevel::Warning
+    );
+    // Stashed rows come back unmasked while revealed
+    app.execute_command(Command::Stash {
+        name: Some("s".to_string()),
+    });
+    app.execute_command(Command::Restore {
+        name: Some("s".to_string()),
+    });
+    assert_eq!(cell(&app).as_deref(), Some("secret"));
+
+    app.execute_command(Command::Reveal);
+    assert_eq!(cell(&app).as_deref(), Some(crate::mask::MASK));
+    assert!(!app.tabs[0].results_viewer.masked_columns().is_empty());
+}
+
+#[test]
+fn test_reveal_without_mask_rules() {
+    let mut app = App::new();
+    app.execute_command(Command::Reveal);
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Nothing is masked — set mask_columns in config.toml"
+    );
+}
+
+#[test]
+fn test_stash_restore_and_compare() {
+    let mut app = app_with_filterable_results();
+    app.execute_command(Command::Stash {
+        name: Some("before".to_string()),
+    });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Stashed 3 rows as 'before'"
+    );
+
+    // Unchanged results match the stash
+    app.execute_command(Command::Compare { name: None });
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Results match stash 'before'")
+    );
+
+    app.tabs[0]
+        .results_viewer
+        .set_results(status_results(&["active", "inactive", "pending"]));
+    app.execute_command(Command::Compare {
+        name: Some("before".to_string()),
+    });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "vs 'before': 1 added, 1 removed, 2 unchanged"
+    );
+    let diff = app.tab().results_viewer.results().unwrap();
+    let cells: Vec<Vec<String>> = diff
+        .rows
+        .iter()
+        .map(|r| r.values.iter().map(|v| v.display_string(100)).collect())
+        .collect();
+    assert_eq!(cells, vec![vec!["-", "active"], vec!["+", "pending"]]);
+
+    app.execute_command(Command::Restore {
+        name: Some("before".to_string()),
+    });
+    assert_eq!(app.tab().results_viewer.results().unwrap().rows.len(), 3);
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
+}
+
+#[test]
+fn test_stash_errors() {
+    let mut app = App::new();
+    app.execute_command(Command::Stash {
+        name: Some("x".to_string()),
+    });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "No results to stash"
+    );
+
+    let mut app = app_with_filterable_results();
+    app.execute_command(Command::Restore {
+        name: Some("missing".to_string()),
+    });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "No stash named 'missing'"
+    );
+
+    app.execute_command(Command::Stash {
+        name: Some("s".to_string()),
+    });
+    let mut other = status_results(&["a"]);
+    other.columns[0].name = "state".to_string();
+    app.tabs[0].results_viewer.set_results(other);
+    app.execute_command(Command::Compare { name: None });
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("columns differ")
+    );
+}
+
+// ── Snippets ──────────────────────────────────
+
+/// An app whose snippets stay in memory
+fn app_with_memory_snippets() -> App {
+    let mut app = App::new();
+    app.snippets = Snippets::default();
+    app
+}
+
+#[test]
+fn test_snippet_save_list_insert_delete() {
+    let mut app = app_with_memory_snippets();
+    app.execute_command(Command::Snippet { name: None });
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("No snippets")
+    );
+
+    app.tabs[0]
+        .editor
+        .set_content("SELECT * FROM pg_locks WHERE NOT granted".to_string());
+    app.execute_command(Command::SaveSnippet {
+        name: Some("lock waits".to_string()),
+    });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Saved snippet 'lock waits'"
+    );
+    app.execute_command(Command::Snippet { name: None });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Snippets: lock waits"
+    );
+
+    app.tabs[0].editor.clear();
+    app.tabs[0].editor.insert_text("-- check\n");
+    app.focus = PanelFocus::ResultsViewer;
+    app.execute_command(Command::Snippet {
+        name: Some("lock waits".to_string()),
+    });
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert_eq!(
+        app.tabs[0].editor.get_content(),
+        "-- check\nSELECT * FROM pg_locks WHERE NOT granted"
+    );
+
+    app.execute_command(Command::DeleteSnippet {
+        name: Some("lock waits".to_string()),
+    });
+    assert!(app.snippets.is_empty());
+}
+
+#[test]
+fn test_snippet_errors() {
+    let mut app = app_with_memory_snippets();
+    app.execute_command(Command::Snippet {
+        name: Some("missing".to_string()),
+    });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "No snippet named 'missing'"
+    );
+
+    app.execute_command(Command::SaveSnippet { name: None });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Usage: /snippet save <name>"
+    );
+
+    app.execute_command(Command::SaveSnippet {
+        name: Some("empty".to_string()),
+    });
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Editor is empty")
+    );
+    assert!(app.snippets.is_empty());
+}
+
+// ── Background jobs ──────────────────────────────────
+
+#[test]
+fn test_job_results_open_in_background_tab() {
+    let mut app = App::new();
+    app.history = crate::history::QueryHistory::new(10);
+    let action = app.execute_command(Command::Job {
+        sql: Some("SELECT count(*) FROM big".to_string()),
+    });
+    let Action::RunJob { job_id, sql, .. } = action else {
+        panic!("expected RunJob");
+    };
+    assert_eq!(sql, "SELECT count(*) FROM big");
+    assert_eq!(app.jobs.len(), 1);
+
+    app.handle_event(AppEvent::JobCompleted {
+        job_id,
+        results: status_results(&["a", "b"]),
+    })
+    .unwrap();
+    assert!(app.jobs.is_empty());
+    assert_eq!(app.tabs.len(), 2);
+    // The user stays where they were
+    assert_eq!(app.active_tab, 0);
+    assert_eq!(app.tabs[1].editor.get_content(), "SELECT count(*) FROM big");
+    assert_eq!(app.tabs[1].results_viewer.results().unwrap().rows.len(), 2);
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with(&format!("Job #{} finished: 2 rows", job_id))
+    );
+}
+
+#[test]
+fn test_job_results_stashed_when_tabs_full() {
+    let mut settings = Settings::default();
+    settings.settings.max_tabs = 1;
+    let mut app = App::new_with_settings(&settings);
+    app.history = crate::history::QueryHistory::new(10);
+    let Action::RunJob { job_id, .. } = app.execute_command(Command::Job {
+        sql: Some("SELECT 1".to_string()),
+    }) else {
+        panic!("expected RunJob");
+    };
+    app.handle_event(AppEvent::JobCompleted {
+        job_id,
+        results: status_results(&["a"]),
+    })
+    .unwrap();
+    assert_eq!(app.tabs.len(), 1);
+    let name = format!("job{}", job_id);
+    assert!(app.stashes.iter().any(|(n, _)| *n == name));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .ends_with(&format!("/restore {} shows them", name))
+    );
+}
+
+#[test]
+fn test_job_refusals_and_failure() {
+    let mut app = App::new();
+    app.history = crate::history::QueryHistory::new(10);
+    assert!(matches!(
+        app.execute_command(Command::Job { sql: None }),
+        Action::None
+    ));
+    assert!(matches!(
+        app.execute_command(Command::Job {
+            sql: Some("DROP TABLE users".to_string()),
+        }),
+        Action::None
+    ));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Jobs can't confirm")
+    );
+    assert!(app.jobs.is_empty());
+
+    let Action::RunJob { job_id, .. } = app.execute_command(Command::Job {
+        sql: Some("SELECT 1/0".to_string()),
+    }) else {
+        panic!("expected RunJob");
+    };
+    app.handle_event(AppEvent::JobFailed {
+        job_id,
+        error: "division by zero".to_string(),
+    })
+    .unwrap();
+    assert!(app.jobs.is_empty());
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        format!("Job #{} failed: division by zero", job_id)
+    );
+    let entry = app.history.entries().next().unwrap();
+    assert_eq!(entry.sql, "SELECT 1/0");
+    assert_eq!(entry.succeeded, Some(false));
+}
+
+#[test]
+fn test_pooler_detected_switches_protocol() {
     let mut app = App::new();
-    app.focus = PanelFocus::ResultsViewer;
-    app.tab_mut().pagination = Some(PaginationState {
-        original_sql: "SELECT * FROM users".to_string(),
-        current_page: 0,
-        page_size: 100,
-        has_more: true,
-        user_has_limit: false,
-        previous_page: None,
-    });
-
-    let p = KeyEvent::from(crossterm::event::KeyCode::Char('p'));
-    let action = app.handle_key(p);
-    assert!(matches!(action, Action::None));
+    let action = app.handle_event(AppEvent::PoolerDetected).unwrap();
+    assert!(matches!(action, Action::UseSimpleProtocol));
+    let status = app.status_message.as_ref().unwrap();
+    assert_eq!(status.level, StatusLevel::Warning);
+    assert!(status.message.contains("simple query protocol"));
 }
 
 #[test]
-fn test_query_completed_trims_pagination_probe_row() {
-    use crate::db::types::{CellValue, ColumnDef, DataType, Row};
-
+fn test_jobs_overlay_opens_and_dismisses() {
+    use crossterm::event::{KeyCode, KeyModifiers};
     let mut app = App::new();
-    app.tab_mut().query_running = true;
-    app.tab_mut().pagination = Some(PaginationState {
-        original_sql: "SELECT 1".to_string(),
-        current_page: 0,
-        page_size: 2,
-        has_more: false,
-        user_has_limit: false,
-        previous_page: None,
-    });
-
-    // Return 3 rows (page_size + 1) to indicate more exist
-    let cols = vec![ColumnDef {
-        name: "x".to_string(),
-        data_type: DataType::Integer,
-        nullable: false,
-    }];
-    let rows = vec![
-        Row {
-            values: vec![CellValue::Integer(1)],
-        },
-        Row {
-            values: vec![CellValue::Integer(2)],
-        },
-        Row {
-            values: vec![CellValue::Integer(3)],
-        },
-    ];
-    let results = QueryResults::new(cols, rows, std::time::Duration::from_millis(5), 3);
+    app.execute_command(Command::Jobs);
+    assert_eq!(app.focus, PanelFocus::Jobs);
+    assert!(app.jobs.is_visible());
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert!(!app.jobs.is_visible());
+}
 
-    app.handle_event(AppEvent::QueryCompleted { results, tab_id: 0 })
-        .unwrap();
+#[test]
+fn test_export_all_streams_unpaged_query() {
+    let mut app = App::new();
+    app.history = crate::history::QueryHistory::new(10);
+    run_and_complete(&mut app, "SELECT * FROM events");
+    let Action::ExportAll {
+        job_id,
+        sql,
+        path,
+        stream,
+        ..
+    } = app.execute_command(Command::ExportAll {
+        format: Some("csv".to_string()),
+        path: Some("events.csv --no-header".to_string()),
+    })
+    else {
+        panic!("expected ExportAll");
+    };
+    // The page LIMIT is gone
+    assert_eq!(sql, "SELECT * FROM events");
+    assert_eq!(path, "events.csv");
+    assert_eq!(stream.row_count(), 0);
+    assert_eq!(app.jobs.len(), 1);
+
+    app.handle_event(AppEvent::ExportCompleted {
+        job_id,
+        path,
+        rows: 250_000,
+        bytes: 4096,
+    })
+    .unwrap();
+    assert!(app.jobs.is_empty());
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Exported all 250000 rows to events.csv (4096 bytes)")
+    );
 
-    // Should have trimmed to page_size (2 rows) and set has_more
-    let pg = app.tab().pagination.as_ref().unwrap();
-    assert!(pg.has_more);
-    assert_eq!(app.tab().results_viewer.results().unwrap().rows.len(), 2);
+    // XLSX is written whole, so it can't stream
+    assert!(matches!(
+        app.execute_command(Command::ExportAll {
+            format: Some("xlsx".to_string()),
+            path: Some("events.xlsx".to_string()),
+        }),
+        Action::None
+    ));
+    assert!(app.jobs.is_empty());
 }
 
 #[test]
-fn test_query_failed_rolls_back_pagination_page() {
+fn test_export_all_needs_paged_results() {
     let mut app = App::new();
-    app.focus = PanelFocus::ResultsViewer;
+    app.history = crate::history::QueryHistory::new(10);
+    run_and_complete(&mut app, "SELECT * FROM events LIMIT 5");
+    assert!(matches!(
+        app.execute_command(Command::ExportAll {
+            format: Some("csv".to_string()),
+            path: Some("events.csv".to_string()),
+        }),
+        Action::None
+    ));
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Export all needs a preview or paginated result")
+    );
 
-    // Set up pagination on page 1 with has_more
-    app.tab_mut().pagination = Some(PaginationState {
-        original_sql: "SELECT * FROM users".to_string(),
-        current_page: 1,
-        page_size: 100,
-        has_more: true,
-        user_has_limit: false,
-        previous_page: None,
+    // Without a path, the prompt's answer starts the streaming export
+    run_and_complete(&mut app, "SELECT * FROM events");
+    app.execute_command(Command::ExportAll {
+        format: Some("json".to_string()),
+        path: None,
     });
+    assert_eq!(app.pending_export, Some(ExportFormat::Json));
+    // Accept the suggested file name
+    let action = app.execute_key_action(KeyAction::Submit);
+    assert!(matches!(action, Action::ExportAll { .. }));
+}
 
-    // Navigate to page 2
-    let n = KeyEvent::from(crossterm::event::KeyCode::Char('n'));
-    let action = app.handle_key(n);
-    assert!(matches!(action, Action::ExecuteQuery { .. }));
-    assert_eq!(app.tab().pagination.as_ref().unwrap().current_page, 2);
-
-    // Query fails — should roll back to page 1
-    app.handle_event(AppEvent::QueryFailed {
-        error: "connection lost".to_string(),
-        position: None,
-        tab_id: 0,
+#[test]
+fn test_export_copy_runs_server_side() {
+    let mut app = App::new();
+    app.history = crate::history::QueryHistory::new(10);
+    run_and_complete(&mut app, "SELECT * FROM events");
+    let Action::ExportCopy {
+        job_id,
+        sql,
+        path,
+        options,
+    } = app.execute_command(Command::ExportCopy {
+        format: Some("tsv".to_string()),
+        path: Some("events.tsv --no-header".to_string()),
+    })
+    else {
+        panic!("expected ExportCopy");
+    };
+    assert_eq!(sql, "SELECT * FROM events");
+    assert_eq!(path, "events.tsv");
+    assert_eq!(options.delimiter, '\t');
+    assert!(!options.header);
+
+    app.handle_event(AppEvent::ExportProgress {
+        job_id,
+        rows: 1000,
+        bytes: 20_000,
     })
     .unwrap();
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        format!("Job #{}: 1000 rows (20000 bytes) written so far", job_id)
+    );
 
-    let pg = app.tab().pagination.as_ref().unwrap();
-    assert_eq!(pg.current_page, 1);
-    assert!(pg.has_more); // restored
-    assert!(pg.previous_page.is_none()); // cleared after rollback
+    // Only the delimited formats map onto COPY
+    assert!(matches!(
+        app.execute_command(Command::ExportCopy {
+            format: Some("json".to_string()),
+            path: Some("events.json".to_string()),
+        }),
+        Action::None
+    ));
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Usage: /export copy csv|tsv [path]"
+    );
+
+    // The prompt's answer starts the COPY export too
+    app.execute_command(Command::ExportCopy {
+        format: Some("csv".to_string()),
+        path: None,
+    });
+    let action = app.execute_key_action(KeyAction::Submit);
+    assert!(matches!(action, Action::ExportCopy { .. }));
 }
 
 #[test]
-fn test_query_failed_no_rollback_on_initial_query() {
+fn test_cancel_job() {
     let mut app = App::new();
-    app.tab_mut().query_running = true;
-
-    // Pagination from initial execute (no previous_page)
-    app.tab_mut().pagination = Some(PaginationState {
-        original_sql: "SELECT * FROM users".to_string(),
-        current_page: 0,
-        page_size: 100,
-        has_more: false,
-        user_has_limit: false,
-        previous_page: None,
+    app.execute_command(Command::Job {
+        sql: Some("SELECT pg_sleep(60)".to_string()),
+    });
+    let job_id = app.jobs.jobs()[0].id;
+
+    assert!(matches!(
+        app.execute_command(Command::CancelJob {
+            job_id: Some(job_id + 1)
+        }),
+        Action::None
+    ));
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        format!("No running job #{}", job_id + 1)
+    );
+    let action = app.execute_command(Command::CancelJob {
+        job_id: Some(job_id),
     });
+    assert!(matches!(action, Action::CancelJob { job_id: id } if id == job_id));
 
-    app.handle_event(AppEvent::QueryFailed {
-        error: "syntax error".to_string(),
-        position: None,
-        tab_id: 0,
+    app.handle_event(AppEvent::JobFailed {
+        job_id,
+        error: "canceling statement due to user request".to_string(),
     })
     .unwrap();
+    let status = app.status_message.as_ref().unwrap();
+    assert_eq!(status.message, format!("Job #{} cancelled", job_id));
+    assert_eq!(status.level, StatusLevel::Warning);
+    assert!(app.jobs.is_empty());
+}
 
-    // Page stays at 0 — no rollback needed
-    let pg = app.tab().pagination.as_ref().unwrap();
-    assert_eq!(pg.current_page, 0);
+// ── Schema refresh after DDL ──────────────────────────────────
+
+fn run_and_complete(app: &mut App, sql: &str) -> Action {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    app.focus = PanelFocus::QueryEditor;
+    app.tabs[0].editor.set_content(sql.to_string());
+    app.handle_key(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE));
+    let results =
+        crate::db::QueryResults::new(vec![], vec![], std::time::Duration::from_millis(1), 0);
+    app.handle_event(AppEvent::QueryCompleted { results, tab_id: 0 })
+        .unwrap()
 }
 
-// ── is_write_query tests ──────────────────────────────────────
+#[test]
+fn test_contains_ddl() {
+    assert!(contains_ddl("CREATE TABLE t (id int)"));
+    assert!(contains_ddl("create or replace view v as select 1"));
+    assert!(contains_ddl("ALTER TABLE t ADD COLUMN x int"));
+    assert!(contains_ddl(
+        "-- migration\nINSERT INTO t VALUES (1);\nDROP INDEX i;"
+    ));
+    assert!(contains_ddl("COMMENT ON TABLE t IS 'x'"));
+    assert!(!contains_ddl("SELECT 'CREATE TABLE t' AS sql"));
+    assert!(!contains_ddl("/* DROP TABLE t */ SELECT 1"));
+    assert!(!contains_ddl("CREATE ROLE reader"));
+    assert!(!contains_ddl("ALTER SYSTEM SET work_mem = '64MB'"));
+    assert!(!contains_ddl("UPDATE t SET comment = 'x'"));
+}
 
 #[test]
-fn test_is_write_query_detects_writes() {
-    assert_eq!(
-        is_write_query("INSERT INTO users VALUES (1)"),
-        Some("INSERT")
-    );
-    assert_eq!(is_write_query("update users set name='x'"), Some("UPDATE"));
-    assert_eq!(is_write_query("  DELETE FROM users"), Some("DELETE"));
-    assert_eq!(is_write_query("CREATE TABLE t (id int)"), Some("CREATE"));
-    assert_eq!(is_write_query("ALTER TABLE t ADD col int"), Some("ALTER"));
-    assert_eq!(is_write_query("DROP TABLE users"), Some("DROP"));
-    assert_eq!(is_write_query("TRUNCATE users"), Some("TRUNCATE"));
-    assert_eq!(
-        is_write_query("GRANT SELECT ON t TO u"),
-        Some("GRANT/REVOKE")
-    );
-    assert_eq!(
-        is_write_query("REVOKE ALL ON t FROM u"),
-        Some("GRANT/REVOKE")
+fn test_ddl_offers_schema_refresh() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut app = App::new();
+
+    let action = run_and_complete(&mut app, "SELECT 1");
+    assert!(matches!(action, Action::None));
+    assert!(!app.pending_schema_refresh);
+
+    let action = run_and_complete(&mut app, "CREATE TABLE t (id int)");
+    assert!(matches!(action, Action::None));
+    assert!(app.pending_schema_refresh);
+    assert!(app.tree_browser.is_stale());
+    assert!(
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .contains("refresh the tree?")
     );
-    assert_eq!(is_write_query("COMMENT ON TABLE t IS 'x'"), Some("COMMENT"));
+
+    let action = app.handle_key(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+    assert!(matches!(action, Action::LoadSchema));
+    assert!(!app.pending_schema_refresh);
 }
 
 #[test]
-fn test_is_write_query_allows_reads() {
-    assert_eq!(is_write_query("SELECT 1"), None);
-    assert_eq!(is_write_query("select * from users"), None);
-    assert_eq!(is_write_query("EXPLAIN ANALYZE SELECT 1"), None);
-    assert_eq!(is_write_query("  SHOW server_version"), None);
-    assert_eq!(is_write_query("BEGIN"), None);
-    assert_eq!(is_write_query("COMMIT"), None);
-    assert_eq!(is_write_query("ROLLBACK"), None);
-    assert_eq!(is_write_query("SET search_path TO public"), None);
-}
+fn test_other_key_dismisses_schema_refresh_offer() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut app = App::new();
+    run_and_complete(&mut app, "CREATE VIEW v AS SELECT 1");
+    assert!(app.pending_schema_refresh);
 
-// ── Read-only mode tests ──────────────────────────────────────
+    // The key still does its usual job (Tab cycles focus)
+    let focus = app.focus;
+    let action = app.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+    assert!(matches!(action, Action::None));
+    assert!(!app.pending_schema_refresh);
+    assert_ne!(app.focus, focus);
+}
 
 #[test]
-fn test_read_only_blocks_write_query() {
-    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+fn test_ddl_in_transaction_waits_for_commit() {
+    let mut app = App::new();
+    run_and_complete(&mut app, "BEGIN");
+    run_and_complete(&mut app, "CREATE TABLE t (id int)");
+    assert!(!app.pending_schema_refresh, "not visible until committed");
 
+    run_and_complete(&mut app, "COMMIT");
+    assert!(app.pending_schema_refresh);
+}
+
+#[test]
+fn test_rolled_back_ddl_offers_nothing() {
     let mut app = App::new();
-    app.read_only = true;
+    run_and_complete(&mut app, "BEGIN");
+    run_and_complete(&mut app, "ALTER TABLE t ADD COLUMN x int");
+    run_and_complete(&mut app, "ROLLBACK");
+    assert!(!app.pending_schema_refresh);
+    assert!(!app.tree_browser.is_stale());
+}
+
+#[test]
+fn test_refresh_schema_on_ddl_auto_and_off() {
+    let mut settings = crate::config::settings::Settings::default();
+    settings.settings.refresh_schema_on_ddl = "auto".to_string();
+    let mut app = App::new_with_settings(&settings);
+    let action = run_and_complete(&mut app, "CREATE INDEX i ON t (id)");
+    assert!(matches!(action, Action::LoadSchema));
+    assert!(!app.pending_schema_refresh);
+
+    settings.settings.refresh_schema_on_ddl = "off".to_string();
+    let mut app = App::new_with_settings(&settings);
+    let action = run_and_complete(&mut app, "CREATE INDEX i ON t (id)");
+    assert!(matches!(action, Action::None));
+    assert!(!app.pending_schema_refresh);
+    assert!(!app.tree_browser.is_stale());
+}
+
+#[test]
+fn test_vim_editor_mode_takes_plain_keys_and_esc() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let key = |code| KeyEvent::from(code);
+    let mut settings = crate::config::settings::Settings::default();
+    settings.settings.editor_mode = "vim".to_string();
+    let mut app = App::new_with_settings(&settings);
+    app.focus = PanelFocus::QueryEditor;
+    assert_eq!(app.tab().editor.vim_mode(), Some(VimMode::Normal));
+
+    for c in "iSELECT 1".chars() {
+        app.handle_key(key(KeyCode::Char(c)));
+    }
+    assert_eq!(app.tab().editor.get_content(), "SELECT 1");
+    // Esc leaves insert mode instead of clearing the search
+    app.handle_key(key(KeyCode::Esc));
+    assert_eq!(app.tab().editor.vim_mode(), Some(VimMode::Normal));
+    app.handle_key(key(KeyCode::Char('x')));
+    assert_eq!(app.tab().editor.get_content(), "SELECT ");
+
+    // New tabs start in vim mode too
+    app.handle_key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL));
+    assert_eq!(app.tab_count(), 2);
+    assert_eq!(app.tab().editor.vim_mode(), Some(VimMode::Normal));
+
+    settings.settings.editor_mode = "emacs".to_string();
+    let app = App::new_with_settings(&settings);
+    assert_eq!(app.tab().editor.vim_mode(), None);
+}
+
+#[test]
+fn test_auto_indent_setting_applies_to_every_tab() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+    let mut settings = Settings::default();
+    let mut app = App::new_with_settings(&settings);
     app.focus = PanelFocus::QueryEditor;
     app.tab_mut()
         .editor
-        .insert_text("INSERT INTO users VALUES (1)");
+        .set_content("SELECT count(".to_string());
+    app.tab_mut().editor.set_cursor_position(0, 13);
+    app.handle_key(enter);
+    assert_eq!(app.tab().editor.get_content(), "SELECT count(\n  ");
 
-    let execute = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    let action = app.handle_key(execute);
-    assert!(matches!(action, Action::None));
-    assert!(app.status_message.is_some());
+    settings.settings.auto_indent = false;
+    let mut app = App::new_with_settings(&settings);
+    app.focus = PanelFocus::QueryEditor;
+    app.handle_key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL));
+    assert_eq!(app.tab_count(), 2);
+    app.tab_mut().editor.set_content("  SELECT (".to_string());
+    app.tab_mut().editor.set_cursor_position(0, 10);
+    app.handle_key(enter);
+    assert_eq!(app.tab().editor.get_content(), "  SELECT (\n");
+}
+
+// ── Jump to column ────────────────────────────────────────────
+
+#[test]
+fn test_jump_to_column_follows_typing_and_esc_restores() {
+    use crate::db::types::{ColumnDef, DataType};
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let names = ["id", "name", "email", "last_login"];
+    let mut app = App::new();
+    app.tabs[0].results_viewer.set_results(QueryResults::new(
+        names
+            .iter()
+            .map(|n| ColumnDef {
+                name: n.to_string(),
+                data_type: DataType::Text,
+                nullable: true,
+            })
+            .collect(),
+        vec![],
+        std::time::Duration::from_millis(1),
+        0,
+    ));
+    app.focus = PanelFocus::ResultsViewer;
+    let last = names.len() - 1;
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::CommandBar);
+    for ch in "llog".chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE));
+    }
+    assert_eq!(app.tabs[0].results_viewer.selected_column(), last);
+
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert_eq!(app.focus, PanelFocus::ResultsViewer);
+    assert_eq!(app.tabs[0].results_viewer.selected_column(), 0);
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
+    for ch in "llog".chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE));
+    }
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_eq!(app.tabs[0].results_viewer.selected_column(), last);
     assert!(
         app.status_message
             .as_ref()
             .unwrap()
             .message
-            .contains("Read-only"),
-        "Expected read-only error, got: {}",
-        app.status_message.as_ref().unwrap().message,
+            .starts_with(&format!("Column {}/{}", last + 1, names.len()))
     );
 }
 
-#[test]
-fn test_read_only_allows_select() {
-    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+// ── Result history ────────────────────────────────────────────
 
+#[test]
+fn test_step_through_earlier_results() {
+    use crossterm::event::{KeyCode, KeyModifiers};
     let mut app = App::new();
-    app.read_only = true;
-    app.focus = PanelFocus::QueryEditor;
-    app.tab_mut().editor.insert_text("SELECT * FROM users");
+    let cell = |app: &App| app.tabs[0].results_viewer.selected_cell_text();
+    for status in ["a", "b", "c"] {
+        app.handle_event(AppEvent::QueryCompleted {
+            results: status_results(&[status]),
+            tab_id: 0,
+        })
+        .unwrap();
+    }
+    app.focus = PanelFocus::ResultsViewer;
+    let key = |app: &mut App, c: char| {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    };
 
-    let execute = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    let action = app.handle_key(execute);
-    // Should proceed to execution (returns ExecuteQuery), not block
-    assert!(!matches!(action, Action::None));
+    key(&mut app, '[');
+    assert_eq!(cell(&app).as_deref(), Some("b"));
+    key(&mut app, '[');
+    assert_eq!(cell(&app).as_deref(), Some("a"));
+    key(&mut app, ']');
+    assert_eq!(cell(&app).as_deref(), Some("b"));
+
+    // New results come after everything seen so far
+    app.handle_event(AppEvent::QueryCompleted {
+        results: status_results(&["d"]),
+        tab_id: 0,
+    })
+    .unwrap();
+    key(&mut app, ']');
+    assert_eq!(cell(&app).as_deref(), Some("d"));
+    key(&mut app, '[');
+    assert_eq!(cell(&app).as_deref(), Some("c"));
+    key(&mut app, '[');
+    assert_eq!(cell(&app).as_deref(), Some("b"));
 }
 
 #[test]
-fn test_read_only_not_set_allows_writes() {
-    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-
-    let mut app = App::new();
-    app.read_only = false;
-    app.focus = PanelFocus::QueryEditor;
-    app.tab_mut().editor.insert_text("DROP TABLE users");
+fn test_result_history_limit_and_failures() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+    let mut settings = crate::config::Settings::default();
+    settings.settings.result_history = 1;
+    let mut app = App::new_with_settings(&settings);
+    for status in ["a", "b"] {
+        app.handle_event(AppEvent::QueryCompleted {
+            results: status_results(&[status]),
+            tab_id: 0,
+        })
+        .unwrap();
+    }
+    app.handle_event(AppEvent::QueryFailed {
+        error: "syntax error".to_string(),
+        position: None,
+        code: None,
+        tab_id: 0,
+    })
+    .unwrap();
+    app.focus = PanelFocus::ResultsViewer;
+    let prev = KeyEvent::new(KeyCode::Char('['), KeyModifiers::NONE);
 
-    let execute = KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE);
-    let _action = app.handle_key(execute);
-    // Without read_only, destructive confirmation dialog appears (not blocked)
-    assert!(
-        app.status_message.is_some()
-            && app
-                .status_message
-                .as_ref()
-                .unwrap()
-                .message
-                .contains("DROP"),
+    // The failed run kept "b"; "a" fell off the end
+    app.handle_key(prev);
+    assert_eq!(
+        app.tabs[0].results_viewer.selected_cell_text().as_deref(),
+        Some("b")
+    );
+    app.handle_key(prev);
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "No earlier results in this tab"
     );
 }
 
-#[test]
-fn test_apply_connection_sets_read_only() {
-    use crate::db::schema::SchemaTree;
+/// Connected app whose tree has `public.logs (id bigint PK, level text,
+/// created_at timestamptz)`
+fn app_with_logs_table() -> App {
+    use crate::db::schema::{Column, PaginatedVec, Schema, SchemaTree, Table};
+    use crate::db::types::DataType;
 
     let mut app = App::new();
-    assert!(!app.read_only);
-
-    // Connection with read_only = true enables it
-    app.apply_connection("prod".to_string(), true, true, SchemaTree::new());
-    assert!(app.read_only);
-
-    // Connection with read_only = false disables it (when global is false)
-    app.apply_connection("dev".to_string(), false, false, SchemaTree::new());
-    assert!(!app.read_only);
+    app.connection_name = Some("prod".to_string());
+    let column = |name: &str, data_type, is_primary_key| Column {
+        name: name.to_string(),
+        data_type,
+        is_primary_key,
+        foreign_key: None,
+    };
+    let mut public = Schema::unloaded("public".to_string());
+    public.tables = PaginatedVec::from_vec(vec![Table {
+        name: "logs".to_string(),
+        columns: vec![
+            column("id", DataType::BigInt, true),
+            column("level", DataType::Text, false),
+            column("created_at", DataType::TimestampTz, false),
+        ],
+        row_count: None,
+        size_bytes: None,
+        select_denied: false,
+    }]);
+    app.tree_browser.set_schema(SchemaTree {
+        schemas: PaginatedVec::from_vec(vec![public]),
+        ..SchemaTree::new()
+    });
+    app
 }
 
-#[test]
-fn test_global_read_only_overrides_connection() {
-    use crate::config::settings::Settings;
-    use crate::db::schema::SchemaTree;
+fn log_rows(ids: std::ops::Range<i64>) -> QueryResults {
+    use crate::db::types::{CellValue, ColumnDef, DataType, Row};
+    let column = |name: &str, data_type| ColumnDef {
+        name: name.to_string(),
+        data_type,
+        nullable: true,
+    };
+    let rows: Vec<Row> = ids
+        .map(|id| Row {
+            values: vec![
+                CellValue::Integer(id),
+                CellValue::Text("error".to_string()),
+                CellValue::DateTime("2026-01-01 00:00:00 UTC".to_string()),
+            ],
+        })
+        .collect();
+    let count = rows.len();
+    QueryResults::new(
+        vec![
+            column("id", DataType::BigInt),
+            column("level", DataType::Text),
+            column("created_at", DataType::TimestampTz),
+        ],
+        rows,
+        std::time::Duration::from_millis(1),
+        count,
+    )
+}
+
+#[test]
+fn test_tail_polls_past_last_key() {
+    let mut app = app_with_logs_table();
+    let tail = |app: &mut App, args: &str| {
+        app.execute_command(Command::Tail {
+            args: Some(args.to_string()),
+        })
+    };
+    let Action::PollTail { tab_id, sql, .. } = tail(&mut app, "logs WHERE level = 'error'") else {
+        panic!("expected a poll");
+    };
+    assert_eq!(tab_id, 0);
+    assert_eq!(
+        sql,
+        "SELECT * FROM (SELECT * FROM \"public\".\"logs\" WHERE (level = 'error') \
+         ORDER BY \"id\" DESC LIMIT 100) AS tail ORDER BY \"id\""
+    );
+    // Not due again until the first rows arrive
+    assert!(app.poll_tails(std::time::Instant::now()).is_empty());
+    assert_eq!(app.next_tail_poll(), None);
 
-    let mut settings = Settings::default();
-    settings.settings.read_only = true;
+    app.handle_event(AppEvent::TailRows {
+        tab_id: 0,
+        results: log_rows(1..4),
+    })
+    .unwrap();
+    let due = app.next_tail_poll().unwrap();
+    let actions = app.poll_tails(due);
+    let [Action::PollTail { sql, .. }] = actions.as_slice() else {
+        panic!("expected a poll");
+    };
+    assert_eq!(
+        sql,
+        "SELECT * FROM \"public\".\"logs\" WHERE (level = 'error') AND \"id\" > '3' \
+         ORDER BY \"id\" LIMIT 1000"
+    );
 
-    // Global read_only = true, connection read_only = false → still read-only
-    let app = App::with_connection(
-        "dev".to_string(),
-        false,
-        false,
-        SchemaTree::new(),
-        &settings,
+    // `by` picks the key; unknown tables and columns are refused
+    let Action::PollTail { sql, .. } = tail(&mut app, "logs by created_at") else {
+        panic!("expected a poll");
+    };
+    assert!(sql.ends_with("AS tail ORDER BY \"created_at\""));
+    assert!(matches!(tail(&mut app, "logs by nope"), Action::None));
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "logs has no column 'nope'"
     );
-    assert!(app.read_only, "global read_only should override connection");
+    assert!(matches!(tail(&mut app, "events"), Action::None));
 }
 
 #[test]
-fn test_translate_meta_command_dt() {
-    let sql = translate_meta_command("\\dt").unwrap();
-    assert!(sql.contains("relkind = 'r'"), "should query tables");
-    assert!(sql.contains("ORDER BY schema, name"));
-}
+fn test_snapshot_opens_a_repeatable_read_tab() {
+    let mut app = app_with_users_table_selected();
+    app.connection_name = Some("prod".to_string());
+    let done = |app: &mut App, tab_id: usize| {
+        let results =
+            crate::db::QueryResults::new(vec![], vec![], std::time::Duration::from_millis(1), 0);
+        app.handle_event(AppEvent::QueryCompleted { results, tab_id })
+            .unwrap()
+    };
 
-#[test]
-fn test_translate_meta_command_dv() {
-    let sql = translate_meta_command("\\dv").unwrap();
-    assert!(sql.contains("relkind IN ('v', 'm')"), "should query views");
-}
+    // BEGIN runs first in a new tab; the SELECT waits for it
+    let Action::ExecuteQuery { sql, tab_id, .. } =
+        app.execute_command(Command::Snapshot { table: None })
+    else {
+        panic!("expected BEGIN");
+    };
+    assert_eq!(sql, "BEGIN ISOLATION LEVEL REPEATABLE READ READ ONLY");
+    assert_eq!(app.active_tab, 1);
+    assert_eq!(tab_id, app.tab().id);
+    assert_eq!(app.tab().transaction_state, TransactionState::InTransaction);
+    assert_eq!(
+        app.tab().snapshot.as_ref().unwrap().relation,
+        "public.users"
+    );
 
-#[test]
-fn test_translate_meta_command_di() {
-    let sql = translate_meta_command("\\di").unwrap();
-    assert!(sql.contains("pg_index"), "should query indexes");
-    assert!(sql.contains("amname"));
+    let Action::ExecuteQuery { sql, .. } = done(&mut app, tab_id) else {
+        panic!("expected the snapshot's first query");
+    };
+    assert_eq!(sql, "SELECT * FROM \"public\".\"users\"");
+    assert!(app.tab().query_running);
+    assert!(matches!(done(&mut app, tab_id), Action::None));
+    assert!(app.tab().snapshot.is_some());
+
+    // Ending the transaction lets go of the snapshot
+    app.execute_command(Command::Rollback);
+    assert!(app.tab().snapshot.is_none());
+
+    assert!(matches!(
+        app.execute_command(Command::Snapshot {
+            table: Some("nope".to_string())
+        }),
+        Action::None
+    ));
+    assert_eq!(app.tab_count(), 2);
 }
 
 #[test]
-fn test_translate_meta_command_dn() {
-    let sql = translate_meta_command("\\dn").unwrap();
-    assert!(sql.contains("pg_namespace"), "should query schemas");
+fn test_tail_appends_rows_and_follows() {
+    let mut app = app_with_logs_table();
+    app.execute_command(Command::Tail {
+        args: Some("logs".to_string()),
+    });
+    app.handle_event(AppEvent::TailRows {
+        tab_id: 0,
+        results: log_rows(1..4),
+    })
+    .unwrap();
+    let viewer = &mut app.tabs[0].results_viewer;
+    assert_eq!(viewer.position(), Some((1, 3)));
+    viewer.move_down();
+    viewer.move_down();
+
+    // On the last row, the selection follows new rows down
+    app.poll_tails(app.next_tail_poll().unwrap());
+    app.handle_event(AppEvent::TailRows {
+        tab_id: 0,
+        results: log_rows(4..6),
+    })
+    .unwrap();
+    assert_eq!(app.tabs[0].results_viewer.position(), Some((5, 5)));
+
+    // Followed rows can't be edited
+    app.focus = PanelFocus::ResultsViewer;
+    app.start_cell_edit_prompt();
+    assert!(!app.pending_cell_edit);
+
+    // A failed poll stops following
+    app.poll_tails(app.next_tail_poll().unwrap());
+    app.handle_event(AppEvent::TailFailed {
+        tab_id: 0,
+        error: "permission denied".to_string(),
+    })
+    .unwrap();
+    assert!(app.tabs[0].tail.is_none());
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Stopped following public.logs: permission denied"
+    );
+
+    // Running a query in the tab stops it too
+    app.execute_command(Command::Tail {
+        args: Some("logs".to_string()),
+    });
+    assert!(app.tabs[0].tail.is_some());
+    app.tabs[0].editor.set_content("SELECT 1".to_string());
+    app.execute_key_action(KeyAction::ExecuteQuery);
+    assert!(app.tabs[0].tail.is_none());
 }
 
 #[test]
-fn test_translate_meta_command_d_table() {
-    let sql = translate_meta_command("\\d users").unwrap();
-    assert!(
-        sql.contains("relname = 'users'"),
-        "should filter by table name"
-    );
-    assert!(sql.contains("attnum > 0"), "should include columns");
-    // Expanded: indexes, constraints, referenced-by, triggers
-    assert!(sql.contains("pg_index"), "should include indexes");
-    assert!(sql.contains("pg_constraint"), "should include constraints");
-    assert!(sql.contains("pg_trigger"), "should include triggers");
+fn test_sessions_cancel_and_terminate_selected() {
+    let mut app = App::new();
+    assert!(matches!(
+        app.execute_command(Command::Sessions),
+        Action::None
+    ));
+    app.connection_name = Some("prod".to_string());
+    assert!(matches!(
+        app.execute_command(Command::Sessions),
+        Action::LoadSessions { tab_id: 0 }
+    ));
+    assert_eq!(app.focus, PanelFocus::Sessions);
+
+    let session = |pid| crate::db::Session {
+        pid,
+        user: Some("app".to_string()),
+        database: Some("shop".to_string()),
+        application: None,
+        client: None,
+        state: Some("active".to_string()),
+        wait_event: Some("Lock:relation".to_string()),
+        duration: Some(std::time::Duration::from_secs(95)),
+        query: Some("UPDATE orders SET total = 0".to_string()),
+    };
+    app.handle_event(AppEvent::SessionsLoaded(vec![session(101), session(202)]))
+        .unwrap();
+    app.execute_key_action(KeyAction::MoveDown);
+    assert!(matches!(
+        app.execute_key_action(KeyAction::CancelSession),
+        Action::SignalBackend {
+            pid: 202,
+            terminate: false,
+            ..
+        }
+    ));
+
+    // Terminating asks for a second press
+    assert!(matches!(
+        app.execute_key_action(KeyAction::TerminateSession),
+        Action::None
+    ));
     assert!(
-        sql.contains("'Referenced by'"),
-        "should include referenced-by"
+        app.status_message
+            .as_ref()
+            .unwrap()
+            .message
+            .starts_with("Terminate session 202 (app)?")
     );
+    assert!(matches!(
+        app.execute_key_action(KeyAction::TerminateSession),
+        Action::SignalBackend {
+            pid: 202,
+            terminate: true,
+            ..
+        }
+    ));
+    let action = app
+        .handle_event(AppEvent::BackendSignalled {
+            pid: 202,
+            terminate: true,
+            sent: true,
+        })
+        .unwrap();
+    assert!(matches!(action, Action::LoadSessions { .. }));
+
+    // Read-only connections can't touch other sessions
+    app.read_only = true;
+    assert!(matches!(
+        app.execute_key_action(KeyAction::CancelSession),
+        Action::None
+    ));
+    app.execute_key_action(KeyAction::Dismiss);
+    assert!(!app.sessions.is_visible());
 }
 
 #[test]
-fn test_translate_meta_command_d_schema_table() {
-    let sql = translate_meta_command("\\d public.users").unwrap();
-    assert!(sql.contains("relname = 'users'"));
-    assert!(sql.contains("nspname = 'public'"));
+fn test_dashboard_refreshes_while_open() {
+    use std::time::Instant;
+
+    let mut app = App::new();
+    app.connection_name = Some("prod".to_string());
+    assert!(matches!(
+        app.execute_command(Command::Dashboard),
+        Action::LoadHealth { tab_id: 0 }
+    ));
+    assert_eq!(app.focus, PanelFocus::Dashboard);
+    // Nothing more until the first snapshot is in
+    assert_eq!(app.next_dashboard_refresh(), None);
+    assert!(matches!(
+        app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Char('r'))),
+        Action::None
+    ));
+
+    app.handle_event(AppEvent::HealthLoaded(crate::db::Health::default()))
+        .unwrap();
+    let due = app.next_dashboard_refresh().unwrap();
+    assert!(app.poll_dashboard(Instant::now()).is_none());
+    assert!(matches!(
+        app.poll_dashboard(due),
+        Some(Action::LoadHealth { tab_id: 0 })
+    ));
+
+    // Closing it stops the refreshes
+    app.handle_event(AppEvent::HealthFailed("timeout".to_string()))
+        .unwrap();
+    assert!(app.next_dashboard_refresh().is_some());
+    app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Esc));
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert_eq!(app.next_dashboard_refresh(), None);
 }
 
 #[test]
-fn test_translate_meta_command_d_without_arg_returns_none() {
-    assert!(
-        translate_meta_command("\\d").is_none(),
-        "\\d alone needs a table name"
-    );
+fn test_replication_overlay() {
+    use crossterm::event::KeyCode;
+
+    let mut app = App::new();
+    assert!(matches!(
+        app.execute_command(Command::Replication),
+        Action::None
+    ));
+    assert!(!app.replication.is_visible(), "needs a connection");
+
+    app.connection_name = Some("prod".to_string());
+    assert!(matches!(
+        app.execute_command(Command::Replication),
+        Action::LoadReplication { tab_id: 0 }
+    ));
+    assert_eq!(app.focus, PanelFocus::Replication);
+    app.handle_event(AppEvent::ReplicationLoaded(
+        crate::db::Replication::default(),
+    ))
+    .unwrap();
+    assert!(matches!(
+        app.handle_key(KeyEvent::from(KeyCode::Char('r'))),
+        Action::LoadReplication { tab_id: 0 }
+    ));
+    app.handle_key(KeyEvent::from(KeyCode::Esc));
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+    assert!(!app.replication.is_visible());
 }
 
 #[test]
-fn test_translate_meta_command_rejects_invalid_table_name() {
-    assert!(translate_meta_command("\\d users; DROP TABLE--").is_none());
-    assert!(translate_meta_command("\\d 'injection'").is_none());
-    // Edge cases with dots
-    assert!(translate_meta_command("\\d .").is_none());
-    assert!(translate_meta_command("\\d schema.").is_none());
-    assert!(translate_meta_command("\\d .table").is_none());
-    assert!(translate_meta_command("\\d a.b.c").is_none());
+fn test_timeout_command_parses_and_formats() {
+    use super::sql_utils::{format_timeout, parse_timeout};
+    assert_eq!(parse_timeout("30s"), Some(30_000));
+    assert_eq!(parse_timeout("30"), Some(30_000));
+    assert_eq!(parse_timeout("250ms"), Some(250));
+    assert_eq!(parse_timeout("1.5m"), Some(90_000));
+    assert_eq!(parse_timeout("OFF"), Some(0));
+    assert_eq!(parse_timeout("soon"), None);
+    assert_eq!(parse_timeout("5 days"), None);
+    assert_eq!(format_timeout(0), "off");
+    assert_eq!(format_timeout(250), "250ms");
+    assert_eq!(format_timeout(30_000), "30s");
+    assert_eq!(format_timeout(300_000), "5m");
+    assert_eq!(format_timeout(90_000), "1m 30s");
+
+    let mut app = App::new();
+    app.connection_name = Some("prod".to_string());
+    assert!(matches!(
+        app.execute_command(Command::Timeout {
+            value: Some("2m".to_string())
+        }),
+        Action::SetStatementTimeout {
+            timeout_ms: 120_000
+        }
+    ));
+    assert_eq!(app.statement_timeout_ms, 120_000);
+    assert!(matches!(
+        app.execute_command(Command::Timeout {
+            value: Some("later".to_string())
+        }),
+        Action::None
+    ));
+    assert_eq!(app.statement_timeout_ms, 120_000);
+    app.execute_command(Command::Timeout { value: None });
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Statement timeout: 2m"
+    );
 }
 
 #[test]
-fn test_translate_meta_command_not_meta() {
-    assert!(translate_meta_command("SELECT 1").is_none());
-    assert!(translate_meta_command("").is_none());
+fn test_statement_timeout_has_its_own_status() {
+    let mut app = App::new();
+    app.statement_timeout_ms = 30_000;
+    app.tabs[0].query_running = true;
+    app.handle_event(AppEvent::QueryFailed {
+        error: "canceling statement due to statement timeout".to_string(),
+        position: None,
+        code: Some("57014".to_string()),
+        tab_id: 0,
+    })
+    .unwrap();
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Query timed out: statement_timeout is 30s \u{2014} /timeout to change it"
+    );
+
+    app.handle_event(AppEvent::QueryFailed {
+        error: "Query timed out after 5s".to_string(),
+        position: None,
+        code: None,
+        tab_id: 0,
+    })
+    .unwrap();
+    assert_eq!(
+        app.status_message.as_ref().unwrap().message,
+        "Query timed out after 5s (query_timeout_ms)"
+    );
 }
 
 #[test]
-fn test_translate_meta_command_unknown() {
-    assert!(translate_meta_command("\\z").is_none());
-    assert!(translate_meta_command("\\c mydb").is_none());
+fn test_server_summary_shows_after_connect_and_dismisses() {
+    let summary = crate::db::ServerSummary {
+        version: "16.2".to_string(),
+        latency: std::time::Duration::from_millis(2),
+        standby: false,
+        replay_lag: None,
+        database: None,
+        oldest_transaction: None,
+    };
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.handle_event(AppEvent::ServerSummaryLoaded(summary.clone()))
+        .unwrap();
+    assert!(app.server_summary.is_visible());
+    assert_eq!(app.focus, PanelFocus::ServerSummary);
+    app.handle_key(KeyEvent::from(crossterm::event::KeyCode::Esc));
+    assert!(!app.server_summary.is_visible());
+    assert_eq!(app.focus, PanelFocus::QueryEditor);
+
+    // Not over a dialog the user is in
+    app.focus = PanelFocus::CommandBar;
+    app.handle_event(AppEvent::ServerSummaryLoaded(summary))
+        .unwrap();
+    assert!(!app.server_summary.is_visible());
+    assert_eq!(app.focus, PanelFocus::CommandBar);
 }
 
 #[test]
-fn test_translate_meta_command_with_whitespace() {
-    assert!(translate_meta_command("  \\dt  ").is_some());
-    assert!(translate_meta_command("  \\d  users  ").is_some());
+fn test_ctrl_c_copies_editor_selection_instead_of_cancelling() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let mut app = App::new();
+    app.focus = PanelFocus::QueryEditor;
+    app.tab_mut().editor.set_content("SELECT 1".to_string());
+    app.tab_mut().query_running = true;
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+
+    app.handle_key(KeyEvent::new(KeyCode::End, KeyModifiers::SHIFT));
+    assert!(app.tab().editor.has_selection());
+    assert!(matches!(app.handle_key(ctrl_c), Action::None));
+    // Still selected, and the query keeps running
+    assert!(app.tab().editor.has_selection());
+
+    app.handle_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE));
+    assert!(matches!(app.handle_key(ctrl_c), Action::CancelQuery { .. }));
 }
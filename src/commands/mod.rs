@@ -3,5 +3,6 @@
 //! Handles the command bar system (commands starting with `:`)
 
 pub mod parser;
+pub mod paths;
 
 pub use parser::{Command, parse_command};
@@ -25,6 +25,135 @@ pub enum Command {
 
     /// Save current query with optional inline name
     SaveQuery { name: Option<String> },
+
+    /// Show the status message log
+    Messages,
+
+    /// Set the EXPLAIN key's options (`analyze, buffers, ...`), or show
+    /// them when no options are given
+    Explain { options: Option<String> },
+
+    /// Open a transaction block on the active tab's connection
+    Begin,
+
+    /// Commit the active tab's transaction
+    Commit,
+
+    /// Roll back the active tab's transaction
+    Rollback,
+
+    /// Keep a copy of the current results under a name, or list the
+    /// stashes when no name is given
+    Stash { name: Option<String> },
+
+    /// Show a stash again (the latest one when no name is given)
+    Restore { name: Option<String> },
+
+    /// Diff the current results against a stash (the latest one when no
+    /// name is given)
+    Compare { name: Option<String> },
+
+    /// Insert a snippet at the cursor, or list snippets when no name is given
+    Snippet { name: Option<String> },
+
+    /// Store the editor content as a snippet
+    SaveSnippet { name: Option<String> },
+
+    /// Remove a snippet
+    DeleteSnippet { name: Option<String> },
+
+    /// Run a query in the background on its own connection; its results
+    /// open in a new tab when it finishes
+    Job { sql: Option<String> },
+
+    /// Show the running background jobs
+    Jobs,
+
+    /// Show masked values (or mask them again)
+    Reveal,
+
+    /// Run the editor's query without the cost guard's EXPLAIN check
+    Force,
+
+    /// Cancel a background job's query
+    CancelJob { job_id: Option<usize> },
+
+    /// Copy the current (filtered) results to the clipboard in a format
+    /// (`csv`, `tsv`, `json`, `md`)
+    Copy { format: Option<String> },
+
+    /// Export the current results in a format (`csv`, `json`, `md`), to
+    /// `path` or to a prompted file name
+    Export {
+        format: Option<String>,
+        path: Option<String>,
+    },
+
+    /// Re-run the current results' query without its page LIMIT and
+    /// stream every row to a file in the background
+    ExportAll {
+        format: Option<String>,
+        path: Option<String>,
+    },
+
+    /// Like `ExportAll`, but the server formats the rows with
+    /// `COPY (query) TO STDOUT` (`csv` or `tsv`)
+    ExportCopy {
+        format: Option<String>,
+        path: Option<String>,
+    },
+
+    /// Stream a CSV file into a table (the tree's selected table when none
+    /// is named) with `COPY`, or just count its rows with `--dry-run`
+    Import {
+        path: Option<String>,
+        table: Option<String>,
+        dry_run: bool,
+    },
+
+    /// Close every other tab (`right_only`: just the tabs to the right of
+    /// the current one). Tabs with a running query or open transaction
+    /// are kept.
+    CloseTabs { right_only: bool },
+
+    /// Bind the current tab to its own connection (a saved connection name
+    /// or URL), or pick one in the connection dialog when none is given
+    TabConnect { target: Option<String> },
+
+    /// Reconnect to another database on the same server, keeping the tabs
+    Use { database: Option<String> },
+
+    /// LISTEN on a channel, or show the notifications received when no
+    /// channel is given
+    Listen { channel: Option<String> },
+
+    /// Stop listening on a channel, or on every channel when none is given
+    Unlisten { channel: Option<String> },
+
+    /// Follow a table, appending new rows as they arrive
+    /// (`table [by column] [WHERE condition]`), or stop following when no
+    /// table is given
+    Tail { args: Option<String> },
+
+    /// Open a tab holding a read-only, repeatable-read snapshot of a table
+    /// (the tree's selected table when none is named)
+    Snapshot { table: Option<String> },
+
+    /// Show the server's other sessions from `pg_stat_activity`
+    Sessions,
+
+    /// Show server health from the `pg_stat_*` views
+    Dashboard,
+
+    /// Show the database's logical replication publications and
+    /// subscriptions
+    Replication,
+
+    /// Show the server-side statement timeout, or change it (e.g. `30s`)
+    Timeout { value: Option<String> },
+
+    /// Load a SQL file into the current tab's editor
+    Open { path: Option<String> },
 }
 
 /// Parse a command string into a Command enum
@@ -55,6 +184,108 @@ pub fn parse_command(input: &str) -> CommandResult<Command> {
             };
             Ok(Command::SaveQuery { name })
         }
+        "messages" | "msg" => Ok(Command::Messages),
+        "explain" => {
+            let options = (parts.len() > 1).then(|| parts[1..].join(" "));
+            Ok(Command::Explain { options })
+        }
+        "begin" => Ok(Command::Begin),
+        "commit" => Ok(Command::Commit),
+        "rollback" => Ok(Command::Rollback),
+        "stash" => Ok(Command::Stash {
+            name: parts.get(1).map(|s| s.to_string()),
+        }),
+        "restore" => Ok(Command::Restore {
+            name: parts.get(1).map(|s| s.to_string()),
+        }),
+        "compare" => Ok(Command::Compare {
+            name: parts.get(1).map(|s| s.to_string()),
+        }),
+        "job" => {
+            // Everything after the command word, spacing intact
+            let sql = input[parts[0].len()..].trim();
+            Ok(Command::Job {
+                sql: (!sql.is_empty()).then(|| sql.to_string()),
+            })
+        }
+        "jobs" if parts.get(1) == Some(&"cancel") => Ok(Command::CancelJob {
+            job_id: parts
+                .get(2)
+                .and_then(|id| id.trim_start_matches('#').parse().ok()),
+        }),
+        "jobs" => Ok(Command::Jobs),
+        "reveal" => Ok(Command::Reveal),
+        "force" => Ok(Command::Force),
+        "copy" => Ok(Command::Copy {
+            format: parts.get(1).map(|s| s.to_string()),
+        }),
+        "export" if parts.get(1) == Some(&"all") => Ok(Command::ExportAll {
+            format: parts.get(2).map(|s| s.to_string()),
+            path: (parts.len() > 3).then(|| parts[3..].join(" ")),
+        }),
+        "export" if parts.get(1) == Some(&"copy") => Ok(Command::ExportCopy {
+            format: parts.get(2).map(|s| s.to_string()),
+            path: (parts.len() > 3).then(|| parts[3..].join(" ")),
+        }),
+        "export" => Ok(Command::Export {
+            format: parts.get(1).map(|s| s.to_string()),
+            path: (parts.len() > 2).then(|| parts[2..].join(" ")),
+        }),
+        "import" => {
+            let dry_run = parts.contains(&"--dry-run");
+            let mut args = parts[1..].iter().filter(|p| **p != "--dry-run");
+            Ok(Command::Import {
+                path: args.next().map(|s| s.to_string()),
+                table: args.next().map(|s| s.to_string()),
+                dry_run,
+            })
+        }
+        "tabonly" | "tabo" => Ok(Command::CloseTabs { right_only: false }),
+        "tabclose-right" => Ok(Command::CloseTabs { right_only: true }),
+        "use" => Ok(Command::Use {
+            database: parts.get(1).map(|s| s.to_string()),
+        }),
+        "listen" => Ok(Command::Listen {
+            channel: parts.get(1).map(|s| s.to_string()),
+        }),
+        "unlisten" => Ok(Command::Unlisten {
+            channel: parts.get(1).map(|s| s.to_string()),
+        }),
+        "sessions" | "activity" => Ok(Command::Sessions),
+        "dashboard" | "health" => Ok(Command::Dashboard),
+        "replication" | "pubs" | "subs" => Ok(Command::Replication),
+        "timeout" => {
+            let value = input[parts[0].len()..].trim();
+            Ok(Command::Timeout {
+                value: (!value.is_empty()).then(|| value.to_string()),
+            })
+        }
+        "open" | "edit" | "e" => {
+            let path = input[parts[0].len()..].trim();
+            Ok(Command::Open {
+                path: (!path.is_empty()).then(|| path.to_string()),
+            })
+        }
+        "tail" => {
+            let args = input[parts[0].len()..].trim();
+            Ok(Command::Tail {
+                args: (!args.is_empty()).then(|| args.to_string()),
+            })
+        }
+        "snapshot" | "snap" => Ok(Command::Snapshot {
+            table: parts.get(1).map(|s| s.to_string()),
+        }),
+        "tabconnect" | "tc" => Ok(Command::TabConnect {
+            target: parts.get(1).map(|s| s.to_string()),
+        }),
+        "snippet" | "sn" => {
+            let rest = |from: usize| (parts.len() > from).then(|| parts[from..].join(" "));
+            match parts.get(1) {
+                Some(&"save") => Ok(Command::SaveSnippet { name: rest(2) }),
+                Some(&"delete") => Ok(Command::DeleteSnippet { name: rest(2) }),
+                _ => Ok(Command::Snippet { name: rest(1) }),
+            }
+        }
         unknown => Err(CommandError::Unknown(unknown.to_string())),
     }
 }
@@ -106,6 +337,200 @@ mod tests {
         assert_eq!(parse_command(":help").unwrap(), Command::Help);
     }
 
+    #[test]
+    fn test_parse_close_tabs() {
+        assert_eq!(
+            parse_command("/tabonly").unwrap(),
+            Command::CloseTabs { right_only: false }
+        );
+        assert_eq!(
+            parse_command(":tabo").unwrap(),
+            Command::CloseTabs { right_only: false }
+        );
+        assert_eq!(
+            parse_command("/tabclose-right").unwrap(),
+            Command::CloseTabs { right_only: true }
+        );
+    }
+
+    #[test]
+    fn test_parse_listen() {
+        assert_eq!(
+            parse_command("/listen orders").unwrap(),
+            Command::Listen {
+                channel: Some("orders".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command("/listen").unwrap(),
+            Command::Listen { channel: None }
+        );
+        assert_eq!(
+            parse_command(":unlisten").unwrap(),
+            Command::Unlisten { channel: None }
+        );
+    }
+
+    #[test]
+    fn test_parse_tail() {
+        assert_eq!(
+            parse_command("/tail logs WHERE level = 'error'").unwrap(),
+            Command::Tail {
+                args: Some("logs WHERE level = 'error'".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command(":tail").unwrap(),
+            Command::Tail { args: None }
+        );
+    }
+
+    #[test]
+    fn test_parse_open() {
+        assert_eq!(
+            parse_command("/open queries/monthly report.sql").unwrap(),
+            Command::Open {
+                path: Some("queries/monthly report.sql".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command(":e q.sql").unwrap(),
+            Command::Open {
+                path: Some("q.sql".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command("/open").unwrap(),
+            Command::Open { path: None }
+        );
+    }
+
+    #[test]
+    fn test_parse_snapshot() {
+        assert_eq!(
+            parse_command("/snapshot public.users").unwrap(),
+            Command::Snapshot {
+                table: Some("public.users".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command(":snap").unwrap(),
+            Command::Snapshot { table: None }
+        );
+    }
+
+    #[test]
+    fn test_parse_sessions() {
+        assert_eq!(parse_command("/sessions").unwrap(), Command::Sessions);
+        assert_eq!(parse_command(":activity").unwrap(), Command::Sessions);
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(
+            parse_command(":timeout 30s").unwrap(),
+            Command::Timeout {
+                value: Some("30s".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command("/timeout").unwrap(),
+            Command::Timeout { value: None }
+        );
+    }
+
+    #[test]
+    fn test_parse_dashboard() {
+        assert_eq!(parse_command("/dashboard").unwrap(), Command::Dashboard);
+        assert_eq!(parse_command("/health").unwrap(), Command::Dashboard);
+    }
+
+    #[test]
+    fn test_parse_replication() {
+        assert_eq!(parse_command("/replication").unwrap(), Command::Replication);
+        assert_eq!(parse_command(":pubs").unwrap(), Command::Replication);
+        assert_eq!(parse_command("/subs").unwrap(), Command::Replication);
+    }
+
+    #[test]
+    fn test_parse_use() {
+        assert_eq!(
+            parse_command("/use analytics").unwrap(),
+            Command::Use {
+                database: Some("analytics".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command(":use").unwrap(),
+            Command::Use { database: None }
+        );
+    }
+
+    #[test]
+    fn test_parse_tab_connect() {
+        assert_eq!(
+            parse_command("/tabconnect").unwrap(),
+            Command::TabConnect { target: None }
+        );
+        assert_eq!(
+            parse_command("/tc staging").unwrap(),
+            Command::TabConnect {
+                target: Some("staging".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_messages() {
+        assert_eq!(parse_command("/messages").unwrap(), Command::Messages);
+        assert_eq!(parse_command(":msg").unwrap(), Command::Messages);
+    }
+
+    #[test]
+    fn test_parse_explain() {
+        assert_eq!(
+            parse_command("/explain").unwrap(),
+            Command::Explain { options: None }
+        );
+        assert_eq!(
+            parse_command("/explain analyze, buffers").unwrap(),
+            Command::Explain {
+                options: Some("analyze, buffers".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_transaction_commands() {
+        assert_eq!(parse_command("/begin").unwrap(), Command::Begin);
+        assert_eq!(parse_command(":commit").unwrap(), Command::Commit);
+        assert_eq!(parse_command("/rollback").unwrap(), Command::Rollback);
+    }
+
+    #[test]
+    fn test_parse_stash_commands() {
+        assert_eq!(
+            parse_command("/stash revenue_before").unwrap(),
+            Command::Stash {
+                name: Some("revenue_before".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command(":stash").unwrap(),
+            Command::Stash { name: None }
+        );
+        assert_eq!(
+            parse_command("/restore revenue_before").unwrap(),
+            Command::Restore {
+                name: Some("revenue_before".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command("/compare").unwrap(),
+            Command::Compare { name: None }
+        );
+    }
+
     #[test]
     fn test_parse_connect() {
         assert_eq!(parse_command("/connect").unwrap(), Command::Connect);
@@ -135,4 +560,144 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_parse_snippet_commands() {
+        assert_eq!(
+            parse_command("/snippet").unwrap(),
+            Command::Snippet { name: None }
+        );
+        assert_eq!(
+            parse_command(":snippet lock waits").unwrap(),
+            Command::Snippet {
+                name: Some("lock waits".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command(":snippet save lock waits").unwrap(),
+            Command::SaveSnippet {
+                name: Some("lock waits".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command("/sn save").unwrap(),
+            Command::SaveSnippet { name: None }
+        );
+        assert_eq!(
+            parse_command("/sn delete cte").unwrap(),
+            Command::DeleteSnippet {
+                name: Some("cte".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_job_commands() {
+        assert_eq!(
+            parse_command(":job SELECT count(*)  FROM t WHERE s = 'a  b'").unwrap(),
+            Command::Job {
+                sql: Some("SELECT count(*)  FROM t WHERE s = 'a  b'".to_string())
+            }
+        );
+        assert_eq!(parse_command("/job").unwrap(), Command::Job { sql: None });
+        assert_eq!(parse_command("/jobs").unwrap(), Command::Jobs);
+        assert_eq!(parse_command("/reveal").unwrap(), Command::Reveal);
+        assert_eq!(parse_command(":force").unwrap(), Command::Force);
+        assert_eq!(
+            parse_command("/jobs cancel #3").unwrap(),
+            Command::CancelJob { job_id: Some(3) }
+        );
+        assert_eq!(
+            parse_command("/jobs cancel x").unwrap(),
+            Command::CancelJob { job_id: None }
+        );
+    }
+
+    #[test]
+    fn test_parse_copy() {
+        assert_eq!(
+            parse_command("/copy md").unwrap(),
+            Command::Copy {
+                format: Some("md".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command("/copy").unwrap(),
+            Command::Copy { format: None }
+        );
+    }
+
+    #[test]
+    fn test_parse_export() {
+        assert_eq!(
+            parse_command(":export md").unwrap(),
+            Command::Export {
+                format: Some("md".to_string()),
+                path: None
+            }
+        );
+        assert_eq!(
+            parse_command("/export csv out.csv | id=text").unwrap(),
+            Command::Export {
+                format: Some("csv".to_string()),
+                path: Some("out.csv | id=text".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command("/export").unwrap(),
+            Command::Export {
+                format: None,
+                path: None
+            }
+        );
+        assert_eq!(
+            parse_command("/export all csv big.csv --no-header").unwrap(),
+            Command::ExportAll {
+                format: Some("csv".to_string()),
+                path: Some("big.csv --no-header".to_string())
+            }
+        );
+        assert_eq!(
+            parse_command("/export all").unwrap(),
+            Command::ExportAll {
+                format: None,
+                path: None
+            }
+        );
+        assert_eq!(
+            parse_command("/export copy tsv big.tsv").unwrap(),
+            Command::ExportCopy {
+                format: Some("tsv".to_string()),
+                path: Some("big.tsv".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_import() {
+        assert_eq!(
+            parse_command("/import users.csv").unwrap(),
+            Command::Import {
+                path: Some("users.csv".to_string()),
+                table: None,
+                dry_run: false
+            }
+        );
+        assert_eq!(
+            parse_command("/import --dry-run users.csv app.users").unwrap(),
+            Command::Import {
+                path: Some("users.csv".to_string()),
+                table: Some("app.users".to_string()),
+                dry_run: true
+            }
+        );
+        assert_eq!(
+            parse_command("/import").unwrap(),
+            Command::Import {
+                path: None,
+                table: None,
+                dry_run: false
+            }
+        );
+    }
 }
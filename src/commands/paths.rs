@@ -0,0 +1,93 @@
+//! File paths typed into the command bar
+//!
+//! `/open` reads the file a path names, and Tab in the command bar completes
+//! that path against the file system.
+
+use std::path::PathBuf;
+
+/// A typed path with a leading `~/` expanded to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// `partial` extended as far as the entries of its directory agree, with a
+/// `/` after a lone directory. None when nothing in the directory matches.
+/// Dotfiles only match once the name being typed starts with a dot.
+pub fn complete_path(partial: &str) -> Option<String> {
+    let (dir, prefix) = match partial.rfind('/') {
+        Some(slash) => partial.split_at(slash + 1),
+        None => ("", partial),
+    };
+    let read_from = if dir.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_home(dir)
+    };
+    let mut matches = std::fs::read_dir(read_from)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let wanted =
+                name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.'));
+            wanted.then(|| (name, entry.path().is_dir()))
+        });
+    let (first, first_is_dir) = matches.next()?;
+    let mut common = first;
+    let mut lone = true;
+    for (name, _) in matches {
+        lone = false;
+        let shared = common
+            .char_indices()
+            .zip(name.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(common.len().min(name.len()), |((i, _), _)| i);
+        common.truncate(shared);
+    }
+    if lone && first_is_dir {
+        common.push('/');
+    }
+    Some(format!("{}{}", dir, common))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_path() {
+        let root = std::env::temp_dir().join(format!("vizgres-paths-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("queries")).unwrap();
+        for file in ["orders.sql", "orders_daily.sql", ".hidden.sql"] {
+            std::fs::write(root.join(file), "SELECT 1").unwrap();
+        }
+        let dir = format!("{}/", root.display());
+
+        let complete = |typed: &str| complete_path(&format!("{}{}", dir, typed));
+        assert_eq!(complete("ord"), Some(format!("{}orders", dir)));
+        assert_eq!(
+            complete("orders_"),
+            Some(format!("{}orders_daily.sql", dir))
+        );
+        assert_eq!(complete("q"), Some(format!("{}queries/", dir)));
+        assert_eq!(complete(".h"), Some(format!("{}.hidden.sql", dir)));
+        assert_eq!(complete("missing"), None);
+        // Everything but the dotfile shares no prefix
+        assert_eq!(complete(""), Some(dir.clone()));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_expand_home() {
+        assert_eq!(expand_home("q.sql"), PathBuf::from("q.sql"));
+        assert_eq!(expand_home("/tmp/q.sql"), PathBuf::from("/tmp/q.sql"));
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(expand_home("~/q.sql"), home.join("q.sql"));
+        }
+    }
+}
@@ -9,11 +9,44 @@ use crate::ui::highlight;
 
 const MAX_CANDIDATES: usize = 5;
 
+/// Most distinct values a column can have and still be offered inside
+/// `column = '`; columns with more aren't low-cardinality enough to help
+pub const MAX_COLUMN_VALUES: usize = 20;
+
+/// Built-in set-returning functions that are legal after FROM / JOIN.
+const BUILTIN_SET_RETURNING_FUNCTIONS: &[&str] = &[
+    "generate_series",
+    "generate_subscripts",
+    "unnest",
+    "json_array_elements",
+    "json_array_elements_text",
+    "json_each",
+    "json_each_text",
+    "json_object_keys",
+    "json_populate_recordset",
+    "json_to_recordset",
+    "jsonb_array_elements",
+    "jsonb_array_elements_text",
+    "jsonb_each",
+    "jsonb_each_text",
+    "jsonb_object_keys",
+    "jsonb_path_query",
+    "jsonb_populate_recordset",
+    "jsonb_to_recordset",
+    "regexp_matches",
+    "regexp_split_to_table",
+    "string_to_table",
+    "pg_get_keywords",
+    "pg_ls_dir",
+    "pg_options_to_table",
+];
+
 /// SQL clause context — controls which schema objects to suggest.
 pub enum SqlContext<'a> {
     /// No schema objects, keywords only (default/unknown position)
     Keyword,
-    /// Tables and views (after FROM, JOIN, INTO, UPDATE, TABLE, TRUNCATE)
+    /// Tables, views, and set-returning functions (after FROM, JOIN, INTO,
+    /// UPDATE, TABLE, TRUNCATE)
     Table,
     /// Columns and functions (after SELECT, WHERE, AND, OR, ON, SET, HAVING, etc.)
     ColumnOrFunction,
@@ -21,7 +54,7 @@ pub enum SqlContext<'a> {
     Column,
     /// Columns of a specific table (after "tablename.")
     TableColumns(&'a str),
-    /// Tables in a specific schema (after "schema.")
+    /// Tables and set-returning functions in a specific schema (after "schema.")
     SchemaTables(&'a str),
 }
 
@@ -30,6 +63,8 @@ pub struct Completer {
     candidates: Vec<String>,
     index: usize,
     prefix: String,
+    /// Leave out tables and views the role can't SELECT from
+    skip_unreadable: bool,
 }
 
 impl Completer {
@@ -38,9 +73,16 @@ impl Completer {
             candidates: Vec::new(),
             index: 0,
             prefix: String::new(),
+            skip_unreadable: false,
         }
     }
 
+    /// Leave tables and views the role can't SELECT from out of table
+    /// name candidates (settings `skip_unreadable_tables`)
+    pub fn set_skip_unreadable(&mut self, skip: bool) {
+        self.skip_unreadable = skip;
+    }
+
     /// Rebuild candidates from `prefix` filtered by SQL context.
     ///
     /// Sources: schema objects first (filtered by `context`),
@@ -73,14 +115,24 @@ impl Completer {
                 SqlContext::Keyword => { /* skip schema objects entirely */ }
 
                 SqlContext::Table => {
+                    let skip = self.skip_unreadable;
                     for s in &tree.schemas {
-                        for table in s.tables.iter() {
-                            self.try_push(&table.name, &prefix_lower, prefix);
+                        for table in s.tables.iter().chain(s.views.iter()) {
+                            if !(skip && table.select_denied) {
+                                self.try_push(&table.name, &prefix_lower, prefix);
+                            }
                         }
-                        for view in s.views.iter() {
-                            self.try_push(&view.name, &prefix_lower, prefix);
+                    }
+                    // Set-returning functions are legal table sources too;
+                    // suggest them with the opening parenthesis
+                    for s in &tree.schemas {
+                        for func in s.functions.iter().filter(|f| f.returns_set()) {
+                            self.try_push(&format!("{}(", func.name), &prefix_lower, prefix);
                         }
                     }
+                    for name in BUILTIN_SET_RETURNING_FUNCTIONS {
+                        self.try_push(&format!("{}(", name), &prefix_lower, prefix);
+                    }
                 }
 
                 SqlContext::ColumnOrFunction => {
@@ -133,11 +185,14 @@ impl Completer {
                     let schema_lower = schema_name.to_ascii_lowercase();
                     for s in &tree.schemas {
                         if s.name.to_ascii_lowercase() == schema_lower {
-                            for table in s.tables.iter() {
-                                self.try_push_dot(&table.name, &prefix_lower);
+                            let skip = self.skip_unreadable;
+                            for table in s.tables.iter().chain(s.views.iter()) {
+                                if !(skip && table.select_denied) {
+                                    self.try_push_dot(&table.name, &prefix_lower);
+                                }
                             }
-                            for view in s.views.iter() {
-                                self.try_push_dot(&view.name, &prefix_lower);
+                            for func in s.functions.iter().filter(|f| f.returns_set()) {
+                                self.try_push_dot(&format!("{}(", func.name), &prefix_lower);
                             }
                         }
                     }
@@ -169,6 +224,22 @@ impl Completer {
         self.suffix()
     }
 
+    /// Rebuild candidates from a column's values that start with `prefix`
+    /// (case-sensitive: these are data, not identifiers). Candidates are
+    /// quoted for SQL and close the literal.
+    pub fn recompute_values(&mut self, prefix: &str, values: &[String]) -> Option<String> {
+        self.candidates.clear();
+        self.index = 0;
+        self.prefix = prefix.to_string();
+        for value in values {
+            let candidate = format!("{}'", value.replace('\'', "''"));
+            if candidate.starts_with(prefix) && !self.candidates.contains(&candidate) {
+                self.candidates.push(candidate);
+            }
+        }
+        self.suffix()
+    }
+
     /// Advance to the next candidate (wraps around). Returns suffix.
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<String> {
@@ -263,6 +334,115 @@ pub fn word_before_cursor(line: &str, col: usize) -> &str {
     &line[start..col]
 }
 
+/// The (possibly schema-qualified) identifier the cursor is on or just
+/// after, as Postgres would resolve it: unquoted parts fold to lowercase
+/// and quoted parts keep their case (`Public."Order"` → `public.Order`).
+/// `col` is a byte offset into `line`.
+pub fn identifier_at(line: &str, col: usize) -> Option<String> {
+    let is_delimiter = |b: u8| b.is_ascii_whitespace() || b"(),;:=<>!+-*/'[]".contains(&b);
+    let bytes = line.as_bytes();
+    let col = col.min(line.len());
+    let mut start = col;
+    while start > 0 && !is_delimiter(bytes[start - 1]) {
+        start -= 1;
+    }
+    let mut end = col;
+    while end < bytes.len() && !is_delimiter(bytes[end]) {
+        end += 1;
+    }
+    let parts: Vec<String> = line[start..end]
+        .trim_matches('.')
+        .split('.')
+        .map(
+            |part| match part.strip_prefix('"').and_then(|p| p.strip_suffix('"')) {
+                Some(quoted) => quoted.replace("\"\"", "\""),
+                None => part.to_lowercase(),
+            },
+        )
+        .collect();
+    if parts.iter().any(String::is_empty) {
+        return None;
+    }
+    Some(parts.join("."))
+}
+
+/// A string literal being typed as the value compared to a column:
+/// `status = 'ac` or `o.status <> 'ac`
+#[derive(Debug, PartialEq, Eq)]
+pub struct ValueContext<'a> {
+    /// Table name or alias before the dot, if any
+    pub qualifier: Option<&'a str>,
+    pub column: &'a str,
+    /// What's typed inside the quotes so far
+    pub prefix: &'a str,
+}
+
+/// Detect the cursor inside a string literal right after `column =`
+/// (or `<>`, `!=`). `col` is a byte offset into `line`.
+pub fn value_context(line: &str, col: usize) -> Option<ValueContext<'_>> {
+    let before = line.get(..col)?;
+    let quote = before.rfind('\'')?;
+    // The quote opens a literal only if the ones before it are paired
+    if before[..quote].matches('\'').count() % 2 != 0 {
+        return None;
+    }
+    let prefix = &before[quote + 1..];
+    let lhs = before[..quote].trim_end();
+    let lhs = lhs
+        .strip_suffix("<>")
+        .or_else(|| lhs.strip_suffix("!="))
+        .or_else(|| lhs.strip_suffix('='))?
+        .trim_end();
+    let start = lhs
+        .char_indices()
+        .rev()
+        .find(|&(_, c)| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '"'))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let ident = &lhs[start..];
+    let (qualifier, column) = match ident.rsplit_once('.') {
+        Some((q, c)) => (Some(q.trim_matches('"')), c),
+        None => (None, ident),
+    };
+    let column = column.trim_matches('"');
+    if column.is_empty() || column.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(ValueContext {
+        qualifier,
+        column,
+        prefix,
+    })
+}
+
+/// Tables named after FROM, JOIN, or UPDATE in `sql`, in order, each with
+/// its alias if it has one (`FROM orders o`, `JOIN items AS i`)
+pub fn tables_in_scope(sql: &str) -> Vec<(&str, Option<&str>)> {
+    let tokens: Vec<&str> = sql
+        .split(|c: char| c.is_ascii_whitespace() || "(),;".contains(c))
+        .filter(|t| !t.is_empty())
+        .collect();
+    let is_keyword = |t: &str| highlight::sql_keywords().contains(t.to_ascii_uppercase().as_str());
+    let mut tables = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if !matches!(
+            token.to_ascii_uppercase().as_str(),
+            "FROM" | "JOIN" | "UPDATE"
+        ) {
+            continue;
+        }
+        // Anything that isn't a table in the schema is dropped by the caller
+        let Some(&table) = tokens.get(i + 1) else {
+            continue;
+        };
+        let mut alias = tokens.get(i + 2).copied();
+        if alias.is_some_and(|a| a.eq_ignore_ascii_case("AS")) {
+            alias = tokens.get(i + 3).copied();
+        }
+        tables.push((table, alias.filter(|a| !is_keyword(a))));
+    }
+    tables
+}
+
 /// Check for a dot-qualifier before the current prefix.
 ///
 /// If the character at `prefix_start - 1` is `.`, returns the word before the
@@ -394,6 +574,30 @@ mod tests {
         assert_eq!(word_before_cursor("abc", 10), "abc");
     }
 
+    // ── identifier_at ───────────────────────────────────────
+
+    #[test]
+    fn identifier_around_cursor() {
+        let line = "SELECT * FROM Orders o";
+        assert_eq!(identifier_at(line, 16).as_deref(), Some("orders"));
+        assert_eq!(identifier_at(line, 14).as_deref(), Some("orders"));
+        assert_eq!(identifier_at(line, 20).as_deref(), Some("orders"));
+    }
+
+    #[test]
+    fn identifier_qualified_and_quoted() {
+        let line = "JOIN sales.\"LineItem\" USING (id)";
+        assert_eq!(identifier_at(line, 8).as_deref(), Some("sales.LineItem"));
+        assert_eq!(identifier_at(line, 30).as_deref(), Some("id"));
+    }
+
+    #[test]
+    fn identifier_stops_at_casts_and_parens() {
+        assert_eq!(identifier_at("count(x::text)", 7).as_deref(), Some("x"));
+        assert_eq!(identifier_at("a, b", 2), None);
+        assert_eq!(identifier_at("", 0), None);
+    }
+
     // ── dot_qualifier ───────────────────────────────────────
 
     #[test]
@@ -424,6 +628,54 @@ mod tests {
         assert_eq!(dot_qualifier(" .foo", 2), None);
     }
 
+    // ── value_context / tables_in_scope ─────────────────────
+
+    #[test]
+    fn value_context_after_equals() {
+        let line = "WHERE status = 'ac";
+        assert_eq!(
+            value_context(line, line.len()),
+            Some(ValueContext {
+                qualifier: None,
+                column: "status",
+                prefix: "ac",
+            })
+        );
+        let line = "AND o.\"Status\"<>'";
+        assert_eq!(
+            value_context(line, line.len()),
+            Some(ValueContext {
+                qualifier: Some("o"),
+                column: "Status",
+                prefix: "",
+            })
+        );
+    }
+
+    #[test]
+    fn value_context_only_inside_an_open_literal() {
+        // Closed literal, then another comparison
+        let line = "WHERE a = 'x' AND b = 1";
+        assert_eq!(value_context(line, line.len()), None);
+        // Not a comparison to a column
+        assert_eq!(value_context("SELECT 'ab", 10), None);
+        assert_eq!(value_context("WHERE n >= '", 12), None);
+        assert_eq!(value_context("WHERE 1 = '", 11), None);
+    }
+
+    #[test]
+    fn tables_in_scope_with_aliases() {
+        let sql = "SELECT * FROM public.orders o\nJOIN items AS i ON i.order_id = o.id\nWHERE";
+        assert_eq!(
+            tables_in_scope(sql),
+            vec![("public.orders", Some("o")), ("items", Some("i"))]
+        );
+        assert_eq!(
+            tables_in_scope("UPDATE users SET name = 'x'"),
+            vec![("users", None)]
+        );
+    }
+
     // ── detect_context ──────────────────────────────────────
 
     #[test]
@@ -624,15 +876,30 @@ mod tests {
                         foreign_key: None,
                     }],
                     row_count: None,
+                    size_bytes: None,
+                    select_denied: false,
                 }]),
                 views: PaginatedVec::default(),
                 indexes: PaginatedVec::default(),
-                functions: PaginatedVec::from_vec(vec![Function {
-                    name: "update_stats".to_string(),
-                    args: "".to_string(),
-                    return_type: "void".to_string(),
-                }]),
+                functions: PaginatedVec::from_vec(vec![
+                    Function {
+                        name: "update_stats".to_string(),
+                        args: "".to_string(),
+                        return_type: "void".to_string(),
+                    },
+                    Function {
+                        name: "user_sessions".to_string(),
+                        args: "integer".to_string(),
+                        return_type: "TABLE(id integer, started timestamp)".to_string(),
+                    },
+                ]),
+                sequences: PaginatedVec::default(),
+                enums: PaginatedVec::default(),
+                domains: PaginatedVec::default(),
+                loaded: true,
             }]),
+            extensions: Vec::new(),
+            databases: Vec::new(),
         }
     }
 
@@ -647,6 +914,53 @@ mod tests {
         assert!(!c.candidates.iter().any(|c| c == "username"));
     }
 
+    #[test]
+    fn skip_unreadable_leaves_out_locked_tables() {
+        let mut c = Completer::new();
+        let mut schema = sample_schema();
+        schema.schemas.items[0].tables.items[0].select_denied = true;
+        c.recompute("us", SqlContext::Table, Some(&schema));
+        assert!(c.candidates.iter().any(|c| c == "users"));
+
+        c.set_skip_unreadable(true);
+        c.recompute("us", SqlContext::Table, Some(&schema));
+        assert!(!c.candidates.iter().any(|c| c == "users"));
+        c.recompute("", SqlContext::SchemaTables("public"), Some(&schema));
+        assert!(!c.candidates.iter().any(|c| c == "users"));
+        // Its columns are still offered
+        c.recompute("user", SqlContext::Column, Some(&schema));
+        assert!(c.candidates.iter().any(|c| c == "username"));
+    }
+
+    #[test]
+    fn table_context_suggests_user_set_returning_functions() {
+        let mut c = Completer::new();
+        let schema = sample_schema();
+        c.recompute("us", SqlContext::Table, Some(&schema));
+        assert_eq!(c.candidates[..2], ["users", "user_sessions("]);
+        // Non-SRF functions are not table sources
+        c.recompute("upd", SqlContext::Table, Some(&schema));
+        assert!(!c.candidates.iter().any(|c| c.starts_with("update_stats")));
+    }
+
+    #[test]
+    fn table_context_suggests_builtin_set_returning_functions() {
+        let mut c = Completer::new();
+        let result = c.recompute("generate_se", SqlContext::Table, Some(&SchemaTree::new()));
+        assert_eq!(result.as_deref(), Some("ries("));
+        c.recompute("unn", SqlContext::Table, None);
+        // Without a schema, only keywords are offered
+        assert!(!c.candidates.iter().any(|c| c == "unnest("));
+    }
+
+    #[test]
+    fn dot_schema_suggests_set_returning_functions() {
+        let mut c = Completer::new();
+        let schema = sample_schema();
+        c.recompute("user_", SqlContext::SchemaTables("public"), Some(&schema));
+        assert_eq!(c.candidates, vec!["user_sessions("]);
+    }
+
     #[test]
     fn column_or_function_context() {
         let mut c = Completer::new();
@@ -720,6 +1034,29 @@ mod tests {
         assert_eq!(result.unwrap(), "ers");
     }
 
+    // ── Column values ───────────────────────────────────────
+
+    #[test]
+    fn values_match_case_sensitively_and_close_the_literal() {
+        let mut c = Completer::new();
+        let values = vec![
+            "active".to_string(),
+            "Archived".to_string(),
+            "o'clock".to_string(),
+        ];
+        assert_eq!(c.recompute_values("a", &values).as_deref(), Some("ctive'"));
+        assert_eq!(c.candidates.len(), 1);
+        // Quotes in values are doubled
+        assert_eq!(
+            c.recompute_values("o", &values).as_deref(),
+            Some("''clock'")
+        );
+        // An empty prefix offers them all; a complete value just closes
+        c.recompute_values("", &values);
+        assert_eq!(c.candidates.len(), 3);
+        assert_eq!(c.recompute_values("active", &values).as_deref(), Some("'"));
+    }
+
     // ── Cycling ─────────────────────────────────────────────
 
     #[test]
@@ -0,0 +1,239 @@
+//! Config bundles
+//!
+//! `vizgres config export <file>` packs config.toml (settings, keybindings,
+//! theme), the snippet library, and optionally the saved connections into
+//! one TOML file; `vizgres config import <file>` unpacks it into another
+//! ~/.vizgres. Passwords are never written to a bundle.
+
+use super::connections::{ConnectionConfig, read_connections, write_connections};
+use super::snippets::{Snippet, Snippets};
+use crate::error::{ConfigError, ConfigResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Bundle format written by this version
+const BUNDLE_VERSION: u32 = 1;
+
+/// Everything `config export` writes
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Bundle {
+    pub version: u32,
+    /// config.toml as written, comments included
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub snippets: Vec<Snippet>,
+    /// Saved connections without their passwords
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connections: Vec<ConnectionConfig>,
+}
+
+impl Bundle {
+    /// Collect the bundle from config directory `dir`, with the saved
+    /// connections if `connections` is set
+    pub fn collect(dir: &Path, connections: bool) -> ConfigResult<Self> {
+        let config_file = dir.join("config.toml");
+        let config = if config_file.exists() {
+            Some(std::fs::read_to_string(&config_file)?)
+        } else {
+            None
+        };
+        let snippets = Snippets::load_from(dir.join("snippets.toml"))?
+            .iter()
+            .cloned()
+            .collect();
+        let connections = if connections {
+            read_connections(&dir.join("connections.toml"))?
+                .into_iter()
+                .map(|mut conn| {
+                    conn.password = None;
+                    conn
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            config,
+            snippets,
+            connections,
+        })
+    }
+
+    /// The bundle file's contents
+    pub fn to_toml(&self) -> ConfigResult<String> {
+        Ok(format!(
+            "# vizgres config bundle — apply with `vizgres config import <file>`\n{}",
+            toml::to_string_pretty(self)?
+        ))
+    }
+
+    /// Read a bundle file's contents, refusing ones from a newer vizgres
+    pub fn parse(content: &str) -> ConfigResult<Self> {
+        let bundle: Self = toml::from_str(content)?;
+        if bundle.version > BUNDLE_VERSION {
+            return Err(ConfigError::Invalid(format!(
+                "bundle version {} needs a newer vizgres (this one reads up to {})",
+                bundle.version, BUNDLE_VERSION
+            )));
+        }
+        Ok(bundle)
+    }
+
+    /// One line per part of the bundle, for printing after export or import
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.config.is_some() {
+            lines.push("settings, keybindings, and theme (config.toml)".to_string());
+        }
+        if !self.snippets.is_empty() {
+            lines.push(plural(self.snippets.len(), "snippet"));
+        }
+        if !self.connections.is_empty() {
+            lines.push(format!(
+                "{} (without passwords)",
+                plural(self.connections.len(), "connection")
+            ));
+        }
+        lines
+    }
+
+    /// Write the bundle into config directory `dir`. config.toml is
+    /// replaced (the old one is kept as config.toml.bak); snippets and
+    /// connections are merged in by name, keeping local passwords.
+    pub fn apply(&self, dir: &Path) -> ConfigResult<()> {
+        std::fs::create_dir_all(dir)?;
+        if let Some(config) = &self.config {
+            let path = dir.join("config.toml");
+            if path.exists() && std::fs::read_to_string(&path)? != *config {
+                std::fs::copy(&path, dir.join("config.toml.bak"))?;
+            }
+            std::fs::write(&path, config)?;
+        }
+        if !self.snippets.is_empty() {
+            let mut snippets = Snippets::load_from(dir.join("snippets.toml"))?;
+            for snippet in &self.snippets {
+                snippets.save(&snippet.name, &snippet.sql)?;
+            }
+        }
+        if !self.connections.is_empty() {
+            let path = dir.join("connections.toml");
+            let merged = merge_connections(read_connections(&path)?, &self.connections);
+            write_connections(&path, &merged)?;
+        }
+        Ok(())
+    }
+}
+
+/// `local` with `imported` added, replacing same-named profiles but keeping
+/// the local password
+fn merge_connections(
+    mut local: Vec<ConnectionConfig>,
+    imported: &[ConnectionConfig],
+) -> Vec<ConnectionConfig> {
+    for conn in imported {
+        let mut conn = conn.clone();
+        match local.iter_mut().find(|c| c.name == conn.name) {
+            Some(existing) => {
+                conn.password = existing.password.take();
+                *existing = conn;
+            }
+            None => {
+                conn.password = None;
+                local.push(conn);
+            }
+        }
+    }
+    local
+}
+
+fn plural(n: usize, noun: &str) -> String {
+    format!("{} {}{}", n, noun, if n == 1 { "" } else { "s" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(name: &str, host: &str, password: Option<&str>) -> ConnectionConfig {
+        let mut conn = ConnectionConfig::from_url(&format!("postgres://app@{}/db", host)).unwrap();
+        conn.name = name.to_string();
+        conn.password = password.map(str::to_string);
+        conn
+    }
+
+    #[test]
+    fn test_export_then_import() {
+        let root = std::env::temp_dir().join(format!("vizgres-bundle-{}", std::process::id()));
+        let (from, to) = (root.join("from"), root.join("to"));
+        std::fs::create_dir_all(&from).unwrap();
+        std::fs::write(
+            from.join("config.toml"),
+            "# team defaults\n[settings]\ntheme = \"light\"\n",
+        )
+        .unwrap();
+        Snippets::load_from(from.join("snippets.toml"))
+            .unwrap()
+            .save("locks", "SELECT * FROM pg_locks")
+            .unwrap();
+        write_connections(
+            &from.join("connections.toml"),
+            &[conn("prod", "db.internal", Some("hunter2"))],
+        )
+        .unwrap();
+
+        let bundle = Bundle::collect(&from, false).unwrap();
+        assert!(bundle.connections.is_empty());
+        let text = Bundle::collect(&from, true).unwrap().to_toml().unwrap();
+        assert!(!text.contains("hunter2"));
+        let bundle = Bundle::parse(&text).unwrap();
+        assert_eq!(
+            bundle.summary(),
+            [
+                "settings, keybindings, and theme (config.toml)",
+                "1 snippet",
+                "1 connection (without passwords)"
+            ]
+        );
+
+        std::fs::create_dir_all(&to).unwrap();
+        std::fs::write(to.join("config.toml"), "[settings]\nmax_tabs = 3\n").unwrap();
+        write_connections(
+            &to.join("connections.toml"),
+            &[conn("prod", "old.internal", Some("local-secret"))],
+        )
+        .unwrap();
+        bundle.apply(&to).unwrap();
+
+        let config = std::fs::read_to_string(to.join("config.toml")).unwrap();
+        assert!(config.starts_with("# team defaults"));
+        let backup = std::fs::read_to_string(to.join("config.toml.bak")).unwrap();
+        assert!(backup.contains("max_tabs = 3"));
+        let snippets = Snippets::load_from(to.join("snippets.toml")).unwrap();
+        assert_eq!(snippets.get("locks"), Some("SELECT * FROM pg_locks"));
+        let connections = read_connections(&to.join("connections.toml")).unwrap();
+        assert_eq!(connections.len(), 1);
+        assert_eq!(connections[0].host, "db.internal");
+        assert_eq!(connections[0].password.as_deref(), Some("local-secret"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_merge_connections_adds_new_profiles_without_passwords() {
+        let merged = merge_connections(
+            vec![conn("dev", "localhost", None)],
+            &[conn("staging", "stg.internal", Some("leaked"))],
+        );
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[1].name, "staging");
+        assert_eq!(merged[1].password, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_newer_bundles() {
+        assert!(Bundle::parse("version = 1\n").is_ok());
+        let err = Bundle::parse("version = 2\n").unwrap_err();
+        assert!(err.to_string().contains("newer vizgres"), "{}", err);
+    }
+}
@@ -0,0 +1,111 @@
+//! Last export directory per connection
+//!
+//! The export prompt starts in the directory the previous export to the
+//! same connection went to, kept in ~/.vizgres/export_dirs.toml so it
+//! survives restarts.
+
+use crate::error::ConfigResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExportDirsFile {
+    #[serde(default)]
+    dirs: BTreeMap<String, PathBuf>,
+}
+
+/// Connection name → directory of its last export, with the file they
+/// persist to
+#[derive(Debug, Default)]
+pub struct ExportDirs {
+    dirs: BTreeMap<String, PathBuf>,
+    /// File path for persistence (`None` = in-memory only)
+    path: Option<PathBuf>,
+}
+
+impl ExportDirs {
+    /// Load from ~/.vizgres/export_dirs.toml (empty if it doesn't exist)
+    pub fn load() -> ConfigResult<Self> {
+        let path = super::connections::ConnectionConfig::config_dir()?.join("export_dirs.toml");
+        let dirs = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            toml::from_str::<ExportDirsFile>(&content)?.dirs
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            dirs,
+            path: Some(path),
+        })
+    }
+
+    /// Directory of the last export for a connection
+    pub fn get(&self, connection: &str) -> Option<&Path> {
+        self.dirs.get(connection).map(PathBuf::as_path)
+    }
+
+    /// Record the directory an export for `connection` went to (`None` for
+    /// the working directory) and write the file if it changed
+    pub fn remember(&mut self, connection: &str, dir: Option<PathBuf>) -> ConfigResult<()> {
+        let previous = match dir {
+            Some(dir) => self.dirs.insert(connection.to_string(), dir.clone()) == Some(dir),
+            None => self.dirs.remove(connection).is_none(),
+        };
+        if previous {
+            return Ok(());
+        }
+        self.write()
+    }
+
+    fn write(&self) -> ConfigResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let file = ExportDirsFile {
+            dirs: self.dirs.clone(),
+        };
+        let content = toml::to_string_pretty(&file)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remember_per_connection() {
+        let mut dirs = ExportDirs::default();
+        dirs.remember("prod", Some(PathBuf::from("/srv/exports")))
+            .unwrap();
+        dirs.remember("staging", Some(PathBuf::from("/tmp")))
+            .unwrap();
+        assert_eq!(dirs.get("prod"), Some(Path::new("/srv/exports")));
+        assert_eq!(dirs.get("staging"), Some(Path::new("/tmp")));
+
+        // Exporting to the working directory forgets the last one
+        dirs.remember("prod", None).unwrap();
+        assert_eq!(dirs.get("prod"), None);
+    }
+
+    #[test]
+    fn test_write_and_read_back() {
+        let dir = std::env::temp_dir().join(format!("vizgres-export-dirs-{}", std::process::id()));
+        let path = dir.join("export_dirs.toml");
+        let mut dirs = ExportDirs {
+            dirs: BTreeMap::new(),
+            path: Some(path.clone()),
+        };
+        dirs.remember("prod", Some(PathBuf::from("/srv/exports")))
+            .unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let file: ExportDirsFile = toml::from_str(&content).unwrap();
+        assert_eq!(file.dirs["prod"], PathBuf::from("/srv/exports"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
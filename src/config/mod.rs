@@ -2,10 +2,18 @@
 //!
 //! Handles loading connection profiles and application settings.
 
+pub mod bundle;
 pub mod connections;
+pub mod export_dirs;
 pub mod saved_queries;
+pub mod schema_cache;
+pub mod secrets;
 pub mod settings;
+pub mod snippets;
 
+pub use bundle::Bundle;
 pub use connections::{ConnectionConfig, find_connection, load_connections, save_connections};
+pub use export_dirs::ExportDirs;
 pub use saved_queries::SavedQuery;
 pub use settings::Settings;
+pub use snippets::Snippets;
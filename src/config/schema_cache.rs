@@ -0,0 +1,196 @@
+//! Schema cache
+//!
+//! Persists the last introspected `SchemaTree` per database under
+//! ~/.vizgres/schema_cache/ so the tree and completer work as soon as a
+//! connection opens, while the fresh introspection runs in the background.
+
+use crate::config::ConnectionConfig;
+use crate::db::schema::SchemaTree;
+use crate::error::ConfigResult;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the cached schema layout changes; older files are ignored
+const CACHE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    schema: SchemaTree,
+}
+
+/// Load the cached schema for `config`'s database, if one was saved.
+/// Unreadable or outdated cache files are treated as missing.
+pub fn load_cached_schema(config: &ConnectionConfig) -> Option<SchemaTree> {
+    load_from(&cache_path(config).ok()?)
+}
+
+/// Save `schema` as the cached schema for `config`'s database
+pub fn save_cached_schema(config: &ConnectionConfig, schema: &SchemaTree) -> ConfigResult<()> {
+    save_to(&cache_path(config)?, schema)
+}
+
+/// Cache file for the database `config` points at. Keyed by user, host,
+/// port, and database rather than profile name, so a URL and a saved
+/// profile for the same database share one cache.
+fn cache_path(config: &ConnectionConfig) -> ConfigResult<PathBuf> {
+    Ok(ConnectionConfig::config_dir()?
+        .join("schema_cache")
+        .join(cache_file_name(config)))
+}
+
+/// Readable file name plus a hash of the exact key, since sanitizing alone
+/// could map two databases onto the same name
+fn cache_file_name(config: &ConnectionConfig) -> String {
+    let key = format!(
+        "{}@{}:{}/{}",
+        config.username, config.host, config.port, config.database
+    );
+    let readable: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(80)
+        .collect();
+    format!("{}-{:016x}.json", readable, fnv1a(key.as_bytes()))
+}
+
+/// FNV-1a, stable across builds (unlike `DefaultHasher`)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn load_from(path: &Path) -> Option<SchemaTree> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let file: CacheFile = serde_json::from_str(&content).ok()?;
+    (file.version == CACHE_VERSION).then_some(file.schema)
+}
+
+/// Write via a temporary file and rename, so a crash mid-write never leaves
+/// a truncated cache behind
+fn save_to(path: &Path, schema: &SchemaTree) -> ConfigResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = CacheFile {
+        version: CACHE_VERSION,
+        schema: schema.clone(),
+    };
+    let content = serde_json::to_string(&file).map_err(std::io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::{Column, PaginatedVec, Schema, Table};
+    use crate::db::types::DataType;
+
+    fn config(user: &str, host: &str, database: &str) -> ConnectionConfig {
+        ConnectionConfig::from_url(&format!("postgres://{}@{}/{}", user, host, database)).unwrap()
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("vizgres-schema-cache-{}", std::process::id()))
+            .join(name)
+    }
+
+    fn sample_schema() -> SchemaTree {
+        SchemaTree {
+            schemas: PaginatedVec::new(
+                vec![Schema {
+                    name: "public".to_string(),
+                    tables: PaginatedVec::new(
+                        vec![Table {
+                            name: "users".to_string(),
+                            columns: vec![Column {
+                                name: "tags".to_string(),
+                                data_type: DataType::Array(Box::new(DataType::Varchar(Some(20)))),
+                                is_primary_key: false,
+                                foreign_key: None,
+                            }],
+                            row_count: Some(42),
+                            size_bytes: None,
+                            select_denied: false,
+                        }],
+                        1200,
+                    ),
+                    views: PaginatedVec::default(),
+                    indexes: PaginatedVec::default(),
+                    functions: PaginatedVec::default(),
+                    sequences: PaginatedVec::default(),
+                    enums: PaginatedVec::default(),
+                    domains: PaginatedVec::default(),
+                    loaded: true,
+                }],
+                1,
+            ),
+            extensions: Vec::new(),
+            databases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_cache_file_name_is_keyed_by_database() {
+        let a = cache_file_name(&config("app", "db.internal", "shop"));
+        assert!(a.starts_with("app_db.internal_5432_shop-"), "{}", a);
+        assert!(a.ends_with(".json"));
+
+        // Same database under another profile name shares the cache
+        let mut renamed = config("app", "db.internal", "shop");
+        renamed.name = "production".to_string();
+        assert_eq!(cache_file_name(&renamed), a);
+
+        // Names that sanitize identically still get distinct files
+        let b = cache_file_name(&config("app", "db.internal", "shop_"));
+        let c = cache_file_name(&config("app", "db.internal", "shop%3F"));
+        assert_ne!(b, c);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let path = temp_path("roundtrip.json");
+        save_to(&path, &sample_schema()).unwrap();
+        let loaded = load_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let table = &loaded.schemas.items[0].tables.items[0];
+        assert_eq!(table.name, "users");
+        assert_eq!(table.row_count, Some(42));
+        assert_eq!(loaded.schemas.items[0].tables.total_count, 1200);
+        assert_eq!(
+            table.columns[0].data_type,
+            DataType::Array(Box::new(DataType::Varchar(Some(20))))
+        );
+    }
+
+    #[test]
+    fn test_outdated_or_corrupt_cache_is_ignored() {
+        let path = temp_path("outdated.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            r#"{"version":0,"schema":{"schemas":{"items":[],"total_count":0}}}"#,
+        )
+        .unwrap();
+        assert!(load_from(&path).is_none());
+
+        std::fs::write(&path, "{not json").unwrap();
+        assert!(load_from(&path).is_none());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(load_from(&temp_path("missing.json")).is_none());
+    }
+}
@@ -0,0 +1,68 @@
+//! Connection password storage in the OS keyring
+//!
+//! With the `keyring` feature, saved profiles keep their passwords in the
+//! system secret store (Secret Service on Linux, Keychain on macOS,
+//! Credential Manager on Windows) under the service name `vizgres`, keyed by
+//! profile name. Without the feature, or when the keyring is unavailable,
+//! passwords fall back to plaintext in connections.toml.
+
+/// Keyring service name every password is stored under
+#[cfg_attr(not(feature = "keyring"), allow(dead_code))]
+const SERVICE: &str = "vizgres";
+
+/// Whether this build can store passwords in the OS keyring
+pub fn is_available() -> bool {
+    cfg!(feature = "keyring")
+}
+
+/// Store the password for profile `connection` in the keyring
+#[cfg(feature = "keyring")]
+pub fn store_password(connection: &str, password: &str) -> Result<(), String> {
+    ::keyring::Entry::new(SERVICE, connection)
+        .and_then(|entry| entry.set_password(password))
+        .map_err(|e| e.to_string())
+}
+
+/// Store the password for profile `connection` in the keyring
+#[cfg(not(feature = "keyring"))]
+pub fn store_password(_connection: &str, _password: &str) -> Result<(), String> {
+    Err("built without keyring support".to_string())
+}
+
+/// Look up the keyring password for profile `connection`
+#[cfg(feature = "keyring")]
+pub fn load_password(connection: &str) -> Option<String> {
+    ::keyring::Entry::new(SERVICE, connection)
+        .and_then(|entry| entry.get_password())
+        .ok()
+}
+
+/// Look up the keyring password for profile `connection`
+#[cfg(not(feature = "keyring"))]
+pub fn load_password(_connection: &str) -> Option<String> {
+    None
+}
+
+/// Remove the keyring password for profile `connection`, if any
+#[cfg(feature = "keyring")]
+pub fn delete_password(connection: &str) {
+    if let Ok(entry) = ::keyring::Entry::new(SERVICE, connection) {
+        let _ = entry.delete_credential();
+    }
+}
+
+/// Remove the keyring password for profile `connection`, if any
+#[cfg(not(feature = "keyring"))]
+pub fn delete_password(_connection: &str) {}
+
+#[cfg(all(test, not(feature = "keyring")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_feature_falls_back_to_plaintext() {
+        assert!(!is_available());
+        assert!(store_password("prod", "secret").is_err());
+        assert_eq!(load_password("prod"), None);
+    }
+}
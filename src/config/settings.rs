@@ -2,24 +2,38 @@
 //!
 //! Manages general configuration stored in ~/.vizgres/config.toml.
 //! Settings include preview row limits, max tabs, history size,
-//! and keybinding overrides.
+//! keybinding overrides, and per-connection setting overrides.
+//!
+//! Loading is strict but forgiving: unknown keys and values of the wrong
+//! type are dropped with a warning that names the key and its line, and
+//! every other setting still applies.
 
 use crate::error::ConfigResult;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Top-level configuration file structure
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Settings {
     #[serde(default)]
     pub settings: SettingsInner,
     #[serde(default)]
     pub keybindings: KeybindingsConfig,
+    /// Per-connection overrides, keyed by connection name
+    #[serde(default)]
+    pub connections: HashMap<String, ConnectionSettings>,
+    /// Problems found while loading the file (syntax errors, unknown keys,
+    /// mistyped values), reported at startup and by `config list`
+    #[serde(skip)]
+    pub warnings: Vec<String>,
 }
 
 /// General application settings with serde defaults
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SettingsInner {
     #[serde(default = "default_preview_rows")]
     pub preview_rows: usize,
@@ -27,6 +41,9 @@ pub struct SettingsInner {
     pub max_tabs: usize,
     #[serde(default = "default_history_size")]
     pub history_size: usize,
+    /// Earlier results each tab keeps for `[` and `]` (0 = off)
+    #[serde(default = "default_result_history")]
+    pub result_history: usize,
     #[serde(default = "default_query_timeout_ms")]
     pub query_timeout_ms: u64,
     #[serde(default = "default_max_result_rows")]
@@ -42,6 +59,18 @@ pub struct SettingsInner {
     /// (DROP, TRUNCATE, DELETE without WHERE). Default: true.
     #[serde(default = "default_confirm_destructive")]
     pub confirm_destructive: bool,
+    /// EXPLAIN each query first and stop it when the planner expects more
+    /// rows than this. Default: 0 (off).
+    #[serde(default)]
+    pub cost_guard_rows: u64,
+    /// Likewise for the planner's total cost. Default: 0 (off).
+    #[serde(default)]
+    pub cost_guard_cost: u64,
+    /// What the cost guard does with a query over a threshold: "warn" to
+    /// ask before running it, or "refuse" (`/force` runs it anyway).
+    /// Default: "warn".
+    #[serde(default = "default_cost_guard_action")]
+    pub cost_guard_action: String,
     /// Default read-only mode for all connections. Per-connection `read_only`
     /// in connections.toml overrides this. Default: false.
     #[serde(default)]
@@ -50,6 +79,65 @@ pub struct SettingsInner {
     /// When false, shows raw text output like psql. Default: true.
     #[serde(default = "default_explain_visual")]
     pub explain_visual: bool,
+    /// Options for the EXPLAIN key, comma-separated: analyze, buffers,
+    /// verbose, settings, wal. Empty plans without running the query.
+    /// Default: "analyze".
+    #[serde(default = "default_explain_options")]
+    pub explain_options: String,
+    /// Start a new tab (Ctrl+T) with the draft of the tab it was opened
+    /// from instead of an empty editor. Default: false.
+    #[serde(default)]
+    pub new_tab_copies_draft: bool,
+    /// Format the editor (as Ctrl+Alt+F does) before each run, so history
+    /// entries are formatted too. Default: false.
+    #[serde(default)]
+    pub format_on_execute: bool,
+    /// Show a summary of the server (version, latency, primary or standby,
+    /// cache hit ratio, oldest open transaction) after connecting.
+    /// Default: true.
+    #[serde(default = "default_server_summary_on_connect")]
+    pub server_summary_on_connect: bool,
+    /// Leave tables and views the role can't SELECT from (locked in the
+    /// tree) out of completion, and don't preview them. Default: false.
+    #[serde(default)]
+    pub skip_unreadable_tables: bool,
+    /// Mark the editor column after this many characters, as a line length
+    /// guide. Default: 0 (off).
+    #[serde(default)]
+    pub editor_ruler: usize,
+    /// Key handling in the query editor: "standard", or "vim" for modal
+    /// editing (normal, insert, and visual mode). Default: "standard".
+    #[serde(default = "default_editor_mode")]
+    pub editor_mode: String,
+    /// Enter keeps the current line's indentation, indenting one level
+    /// further after `(`, and `)` typed at the start of a line steps back.
+    /// Default: true.
+    #[serde(default = "default_auto_indent")]
+    pub auto_indent: bool,
+    /// Locale for thousand separators in counts and sizes and the decimal
+    /// mark in results, e.g. "en", "de", "fr_FR", or "auto" for `LANG`.
+    /// Default: "en".
+    #[serde(default = "default_number_locale")]
+    pub number_locale: String,
+    /// What to do after DDL (CREATE, ALTER, DROP, COMMENT ON) runs: "ask"
+    /// to offer a schema refresh, "auto" to refresh right away, or "off".
+    /// Default: "ask".
+    #[serde(default = "default_refresh_schema_on_ddl")]
+    pub refresh_schema_on_ddl: String,
+    /// File to keep session counters in (Prometheus text format) for a
+    /// textfile collector. `~/` and `{pid}` are expanded. Default: "" (off).
+    #[serde(default)]
+    pub metrics_file: String,
+    /// Column name patterns (`*` wildcards, any case) whose values are
+    /// masked in results and exports until `/reveal`. Default: none.
+    #[serde(default)]
+    pub mask_columns: Vec<String>,
+    /// File name the export prompt suggests: `{connection}`, `{table}`,
+    /// `{date}`, `{time}`, and `{ext}` are filled in. The prompt starts in
+    /// the connection's last export directory. Default:
+    /// "export_{date}_{time}.{ext}".
+    #[serde(default = "default_export_filename")]
+    pub export_filename: String,
     /// Color theme. Options: dark, light, midnight, ember. Default: dark.
     #[serde(default = "default_theme")]
     pub theme: String,
@@ -57,6 +145,7 @@ pub struct SettingsInner {
 
 /// Keybinding overrides organized by panel context
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct KeybindingsConfig {
     #[serde(default)]
     pub global: HashMap<String, String>,
@@ -68,6 +157,77 @@ pub struct KeybindingsConfig {
     pub tree: HashMap<String, String>,
 }
 
+/// A `[connections.<name>]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionSettings {
+    #[serde(default)]
+    pub settings: SettingsOverrides,
+}
+
+/// Settings a connection may override via `[connections.<name>.settings]`.
+/// Unset fields inherit the global `[settings]` value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SettingsOverrides {
+    pub preview_rows: Option<usize>,
+    pub query_timeout_ms: Option<u64>,
+    pub statement_timeout_ms: Option<u64>,
+    pub max_result_rows: Option<usize>,
+    pub confirm_destructive: Option<bool>,
+    pub cost_guard_rows: Option<u64>,
+    pub cost_guard_cost: Option<u64>,
+    pub cost_guard_action: Option<String>,
+    pub explain_visual: Option<bool>,
+    pub explain_options: Option<String>,
+    pub mask_columns: Option<Vec<String>>,
+    pub theme: Option<String>,
+}
+
+impl SettingsOverrides {
+    /// Overridden settings as (name, value) pairs, for display
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = Vec::new();
+        if let Some(v) = self.preview_rows {
+            entries.push(("preview_rows", v.to_string()));
+        }
+        if let Some(v) = self.query_timeout_ms {
+            entries.push(("query_timeout_ms", v.to_string()));
+        }
+        if let Some(v) = self.statement_timeout_ms {
+            entries.push(("statement_timeout_ms", v.to_string()));
+        }
+        if let Some(v) = self.max_result_rows {
+            entries.push(("max_result_rows", v.to_string()));
+        }
+        if let Some(v) = self.confirm_destructive {
+            entries.push(("confirm_destructive", v.to_string()));
+        }
+        if let Some(v) = self.cost_guard_rows {
+            entries.push(("cost_guard_rows", v.to_string()));
+        }
+        if let Some(v) = self.cost_guard_cost {
+            entries.push(("cost_guard_cost", v.to_string()));
+        }
+        if let Some(ref v) = self.cost_guard_action {
+            entries.push(("cost_guard_action", v.clone()));
+        }
+        if let Some(v) = self.explain_visual {
+            entries.push(("explain_visual", v.to_string()));
+        }
+        if let Some(ref v) = self.explain_options {
+            entries.push(("explain_options", v.clone()));
+        }
+        if let Some(ref v) = self.mask_columns {
+            entries.push(("mask_columns", v.join(", ")));
+        }
+        if let Some(ref v) = self.theme {
+            entries.push(("theme", v.clone()));
+        }
+        entries
+    }
+}
+
 fn default_preview_rows() -> usize {
     100
 }
@@ -80,6 +240,14 @@ fn default_history_size() -> usize {
     500
 }
 
+fn default_result_history() -> usize {
+    5
+}
+
+fn default_export_filename() -> String {
+    "export_{date}_{time}.{ext}".to_string()
+}
+
 fn default_query_timeout_ms() -> u64 {
     30000 // 30 seconds, 0 = disabled
 }
@@ -100,10 +268,38 @@ fn default_confirm_destructive() -> bool {
     true
 }
 
+fn default_cost_guard_action() -> String {
+    "warn".to_string()
+}
+
 fn default_explain_visual() -> bool {
     true
 }
 
+fn default_server_summary_on_connect() -> bool {
+    true
+}
+
+fn default_auto_indent() -> bool {
+    true
+}
+
+fn default_explain_options() -> String {
+    "analyze".to_string()
+}
+
+fn default_editor_mode() -> String {
+    "standard".to_string()
+}
+
+fn default_number_locale() -> String {
+    "en".to_string()
+}
+
+fn default_refresh_schema_on_ddl() -> String {
+    "ask".to_string()
+}
+
 fn default_theme() -> String {
     "dark".to_string()
 }
@@ -114,13 +310,30 @@ impl Default for SettingsInner {
             preview_rows: default_preview_rows(),
             max_tabs: default_max_tabs(),
             history_size: default_history_size(),
+            result_history: default_result_history(),
             query_timeout_ms: default_query_timeout_ms(),
             max_result_rows: default_max_result_rows(),
             tree_category_limit: default_tree_category_limit(),
             statement_timeout_ms: default_statement_timeout_ms(),
             confirm_destructive: default_confirm_destructive(),
+            cost_guard_rows: 0,
+            cost_guard_cost: 0,
+            cost_guard_action: default_cost_guard_action(),
             read_only: false,
             explain_visual: default_explain_visual(),
+            explain_options: default_explain_options(),
+            new_tab_copies_draft: false,
+            format_on_execute: false,
+            server_summary_on_connect: default_server_summary_on_connect(),
+            skip_unreadable_tables: false,
+            editor_ruler: 0,
+            editor_mode: default_editor_mode(),
+            auto_indent: default_auto_indent(),
+            number_locale: default_number_locale(),
+            refresh_schema_on_ddl: default_refresh_schema_on_ddl(),
+            metrics_file: String::new(),
+            mask_columns: Vec::new(),
+            export_filename: default_export_filename(),
             theme: default_theme(),
         }
     }
@@ -128,17 +341,16 @@ impl Default for SettingsInner {
 
 impl Settings {
     /// Load settings from ~/.vizgres/config.toml.
-    /// Returns defaults if the file is missing. Prints a warning to stderr
-    /// and returns defaults if the file exists but fails to parse.
+    /// Returns defaults if the file is missing or can't be read or parsed;
+    /// problems are recorded in `warnings`.
     pub fn load() -> Self {
         let path = match Self::config_file() {
             Ok(p) => p,
             Err(e) => {
-                eprintln!(
-                    "Warning: could not determine config path: {}. Using defaults.",
+                return Self::with_warning(format!(
+                    "could not determine config path: {}. Using defaults.",
                     e
-                );
-                return Self::default();
+                ));
             }
         };
 
@@ -147,26 +359,130 @@ impl Settings {
         }
 
         match std::fs::read_to_string(&path) {
-            Ok(content) => match toml::from_str(&content) {
-                Ok(settings) => settings,
-                Err(e) => {
-                    eprintln!(
-                        "Warning: failed to parse {}: {}. Using defaults.",
-                        path.display(),
-                        e
-                    );
-                    Self::default()
-                }
-            },
+            Ok(content) => Self::parse(&content),
+            Err(e) => Self::with_warning(format!(
+                "failed to read {}: {}. Using defaults.",
+                path.display(),
+                e
+            )),
+        }
+    }
+
+    /// Parse config.toml content. Unknown keys and values of the wrong type
+    /// are dropped with a warning naming the key and its line, so the rest
+    /// of the file still applies. A syntax error falls back to defaults.
+    pub fn parse(content: &str) -> Self {
+        let mut doc: toml::Table = match toml::from_str(content) {
+            Ok(doc) => doc,
             Err(e) => {
-                eprintln!(
-                    "Warning: failed to read {}: {}. Using defaults.",
-                    path.display(),
-                    e
-                );
-                Self::default()
+                let at = e
+                    .span()
+                    .map(|span| format!("line {}: ", line_of(content, span.start)))
+                    .unwrap_or_default();
+                return Self::with_warning(format!(
+                    "{}{}. Using defaults.",
+                    at,
+                    e.message().trim_end()
+                ));
             }
+        };
+
+        let mut validator = Validator {
+            content,
+            warnings: Vec::new(),
+        };
+        if let Some(toml::Value::Table(settings)) = doc.get_mut("settings") {
+            validator.retain_valid::<SettingsInner>(settings, "settings");
+        }
+        if let Some(toml::Value::Table(keybindings)) = doc.get_mut("keybindings") {
+            for (panel, bindings) in keybindings.iter_mut() {
+                if let toml::Value::Table(bindings) = bindings {
+                    let section = format!("keybindings.{}", panel);
+                    validator.retain_valid::<HashMap<String, String>>(bindings, &section);
+                }
+            }
+            validator.retain_valid::<KeybindingsConfig>(keybindings, "keybindings");
         }
+        if let Some(toml::Value::Table(connections)) = doc.get_mut("connections") {
+            for (name, connection) in connections.iter_mut() {
+                let toml::Value::Table(connection) = connection else {
+                    continue;
+                };
+                let section = format!("connections.{}", name);
+                if let Some(toml::Value::Table(overrides)) = connection.get_mut("settings") {
+                    let section = format!("{}.settings", section);
+                    validator.retain_valid::<SettingsOverrides>(overrides, &section);
+                }
+                validator.retain_valid::<ConnectionSettings>(connection, &section);
+            }
+            validator
+                .retain_valid::<HashMap<String, ConnectionSettings>>(connections, "connections");
+        }
+        validator.retain_valid::<Settings>(&mut doc, "");
+
+        let mut warnings = validator.warnings;
+        let mut settings = toml::Value::Table(doc)
+            .try_into::<Settings>()
+            .unwrap_or_else(|e| {
+                warnings.push(format!("{}. Using defaults.", e.message().trim_end()));
+                Self::default()
+            });
+        settings.warnings = warnings;
+        settings
+    }
+
+    /// Default settings with a loading problem to report
+    fn with_warning(warning: String) -> Self {
+        Self {
+            warnings: vec![warning],
+            ..Self::default()
+        }
+    }
+
+    /// Effective settings for a connection: the global `[settings]` with any
+    /// `[connections.<name>.settings]` overrides applied on top.
+    pub fn for_connection(&self, name: &str) -> SettingsInner {
+        let mut merged = self.settings.clone();
+        let Some(overrides) = self.connections.get(name).map(|c| &c.settings) else {
+            return merged;
+        };
+        if let Some(v) = overrides.preview_rows {
+            merged.preview_rows = v;
+        }
+        if let Some(v) = overrides.query_timeout_ms {
+            merged.query_timeout_ms = v;
+        }
+        if let Some(v) = overrides.statement_timeout_ms {
+            merged.statement_timeout_ms = v;
+        }
+        if let Some(v) = overrides.max_result_rows {
+            merged.max_result_rows = v;
+        }
+        if let Some(v) = overrides.confirm_destructive {
+            merged.confirm_destructive = v;
+        }
+        if let Some(v) = overrides.cost_guard_rows {
+            merged.cost_guard_rows = v;
+        }
+        if let Some(v) = overrides.cost_guard_cost {
+            merged.cost_guard_cost = v;
+        }
+        if let Some(ref v) = overrides.cost_guard_action {
+            merged.cost_guard_action = v.clone();
+        }
+        if let Some(v) = overrides.explain_visual {
+            merged.explain_visual = v;
+        }
+        if let Some(ref v) = overrides.explain_options {
+            merged.explain_options = v.clone();
+        }
+        if let Some(ref v) = overrides.mask_columns {
+            merged.mask_columns = v.clone();
+        }
+        if let Some(ref v) = overrides.theme {
+            merged.theme = v.clone();
+        }
+        merged
     }
 
     /// Get the config file path (~/.vizgres/config.toml)
@@ -185,6 +501,80 @@ impl Settings {
     }
 }
 
+/// Checks config tables key by key, collecting warnings
+struct Validator<'a> {
+    /// The file's text, for line numbers
+    content: &'a str,
+    warnings: Vec<String>,
+}
+
+impl Validator<'_> {
+    /// Drop the keys of `table` that `T` rejects (unknown or mistyped),
+    /// with a warning for each. `section` is the table's dotted path.
+    fn retain_valid<T: DeserializeOwned>(&mut self, table: &mut toml::Table, section: &str) {
+        table.retain(|key, value| {
+            let mut single = toml::Table::new();
+            single.insert(key.to_string(), value.clone());
+            let Err(e) = toml::Value::Table(single).try_into::<T>() else {
+                return true;
+            };
+            let path = if section.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", section, key)
+            };
+            let message = e.message().trim_end();
+            let problem = if message.starts_with("unknown field") {
+                format!("unknown key `{}`", path)
+            } else {
+                format!("`{}`: {}", path, message)
+            };
+            let warning = match key_line(self.content, section, key) {
+                Some(line) => format!("line {}: {}", line, problem),
+                None => problem,
+            };
+            self.warnings.push(warning);
+            false
+        });
+    }
+}
+
+/// 1-based line number of byte offset `pos` in `content`
+fn line_of(content: &str, pos: usize) -> usize {
+    content[..pos.min(content.len())].matches('\n').count() + 1
+}
+
+/// 1-based line where `key` is set inside the `[section]` table (`""` for
+/// the top level), or where `[section.key]` opens. Dotted and inline keys
+/// aren't tracked.
+fn key_line(content: &str, section: &str, key: &str) -> Option<usize> {
+    let normalize = |path: &str| path.replace(['"', '\'', ' '], "");
+    let section = normalize(section);
+    let nested = if section.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", section, key)
+    };
+    let mut current = String::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[') {
+            current = normalize(header.split(']').next().unwrap_or(""));
+            if current == nested {
+                return Some(i + 1);
+            }
+            continue;
+        }
+        let Some((name, _)) = line.split_once('=') else {
+            continue;
+        };
+        if current == section && normalize(name) == key {
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
 /// Commented-out default template for `config edit`
 const DEFAULT_CONFIG_TEMPLATE: &str = r#"# vizgres configuration
 # https://github.com/simpllyf/vizgres
@@ -193,13 +583,30 @@ const DEFAULT_CONFIG_TEMPLATE: &str = r#"# vizgres configuration
 # preview_rows = 100
 # max_tabs = 5
 # history_size = 500
+# result_history = 5        # earlier results per tab for [ and ], 0 = off
 # query_timeout_ms = 30000  # 30 seconds client-side timeout, 0 = disabled
 # max_result_rows = 1000    # row limit for query results, 0 = unlimited
 # tree_category_limit = 500 # items per category before pagination, 0 = unlimited
 # statement_timeout_ms = 60000  # 60 seconds server-side timeout, 0 = disabled
 # confirm_destructive = true    # prompt before DROP, TRUNCATE, DELETE without WHERE
+# cost_guard_rows = 0           # EXPLAIN first; stop queries planned over this many rows, 0 = off
+# cost_guard_cost = 0           # ...or over this planner cost, 0 = off
+# cost_guard_action = "warn"    # over a threshold: warn (ask y/N) or refuse (/force overrides)
 # read_only = false             # default read-only mode for all connections
 # explain_visual = true         # visual tree for EXPLAIN, false = raw text
+# explain_options = "analyze"   # EXPLAIN options: analyze, buffers, verbose, settings, wal
+# new_tab_copies_draft = false  # new tabs start with the current tab's draft
+# format_on_execute = false     # format the query (Ctrl+Alt+F) before each run
+# server_summary_on_connect = true  # version, latency, role, cache hits, oldest xact
+# skip_unreadable_tables = false  # leave tables you can't SELECT out of completion and previews
+# editor_ruler = 0              # mark the column after this many characters, 0 = off
+# editor_mode = "standard"      # "vim" for modal editing (normal/insert/visual)
+# auto_indent = true            # Enter keeps indentation, one level more after "("
+# number_locale = "en"          # separators in counts, sizes, and decimals: en, de, fr, auto
+# refresh_schema_on_ddl = "ask" # after CREATE/ALTER/DROP: ask, auto, off
+# metrics_file = ""             # Prometheus-format session counters, {pid} = process id
+# mask_columns = []             # mask columns like ["*password*", "*ssn*"] until /reveal
+# export_filename = "export_{date}_{time}.{ext}"  # also {connection}, {table}
 # theme = "dark"                # color theme: dark, light, midnight, ember
 
 [keybindings.global]
@@ -216,12 +623,28 @@ const DEFAULT_CONFIG_TEMPLATE: &str = r#"# vizgres configuration
 # "f5" = "execute_query"
 # "ctrl+enter" = "execute_query"
 # "ctrl+e" = "explain_query"
+# "ctrl+alt+e" = "estimate_rows"
 # "ctrl+l" = "clear_editor"
 # "ctrl+z" = "undo"
 # "ctrl+shift+z" = "redo"
 # "ctrl+alt+f" = "format_query"
+# "ctrl+c" = "copy_selection"  # with text selected; otherwise Ctrl+C cancels
+# "ctrl+x" = "cut_selection"
+# "ctrl+v" = "paste"
+# "ctrl+d" = "duplicate_line"
+# "ctrl+k" = "delete_line"
+# "ctrl+/" = "toggle_comment"
+# "ctrl+b" = "jump_to_bracket"
+# "alt+up" = "move_line_up"      # cycles completions while one is shown
+# "alt+down" = "move_line_down"
+# "ctrl+f" = "find"
+# "ctrl+alt+r" = "replace"       # was ctrl+r, now the history search
+# "f3" = "find_next"
+# "shift+f3" = "find_prev"
+# "f4" = "preview_table"
 # "ctrl+up" = "history_back"
 # "ctrl+down" = "history_forward"
+# "ctrl+r" = "search_history"
 # "esc" = "cancel_query"
 
 [keybindings.results]
@@ -229,21 +652,54 @@ const DEFAULT_CONFIG_TEMPLATE: &str = r#"# vizgres configuration
 # "v" = "toggle_view_mode"
 # "y" = "copy_cell"
 # "shift+y" = "copy_row"
+# "alt+y" = "copy_results_csv"
+# "alt+m" = "copy_results_markdown"
 # "ctrl+s" = "export_csv"
 # "ctrl+j" = "export_json"
+# "shift+left" = "page_left"
+# "shift+right" = "page_right"
+# "c" = "jump_to_column"
+# "f" = "follow_foreign_key"
+# "shift+i" = "column_in_list"
 # "n" = "next_page"
 # "p" = "prev_page"
 # "shift+l" = "widen_column"
 # "shift+h" = "narrow_column"
 # "shift+r" = "reset_column_widths"
+# "=" = "expand_column"
+# "s" = "sort_column"
+# "shift+s" = "clear_sort"
+# "shift+f" = "column_histogram"
+# "shift+p" = "toggle_pin"
+# "[" = "previous_results"
+# "]" = "next_results"
+# "/" = "filter_rows"
+# "e" = "edit_cell"
+# "ctrl+f" = "find"
+# "f3" = "find_next"
+# "shift+n" = "find_prev"
+# "shift+f3" = "find_prev"
 # "esc" = "cancel_query"
 
 [keybindings.tree]
 # "enter" = "expand"
 # "space" = "toggle_expand"
 # "h" = "collapse"
+# "u" = "find_usages"
 # "x" = "delete_saved_query"
 # "esc" = "cancel_query"
+
+# Per-connection overrides, applied while that connection is active.
+# Supported: preview_rows, query_timeout_ms, statement_timeout_ms,
+# max_result_rows, confirm_destructive, cost_guard_rows, cost_guard_cost,
+# cost_guard_action, explain_visual, explain_options, mask_columns, theme.
+# [connections.production.settings]
+# statement_timeout_ms = 15000
+# max_result_rows = 200
+# confirm_destructive = true
+# cost_guard_rows = 1000000
+# cost_guard_action = "refuse"
+# theme = "ember"
 "#;
 
 #[cfg(test)]
@@ -262,11 +718,13 @@ mod tests {
         assert_eq!(settings.settings.statement_timeout_ms, 60000);
         assert!(settings.settings.confirm_destructive);
         assert!(settings.settings.explain_visual);
+        assert_eq!(settings.settings.explain_options, "analyze");
         assert_eq!(settings.settings.theme, "dark");
         assert!(settings.keybindings.global.is_empty());
         assert!(settings.keybindings.editor.is_empty());
         assert!(settings.keybindings.results.is_empty());
         assert!(settings.keybindings.tree.is_empty());
+        assert!(settings.connections.is_empty());
     }
 
     #[test]
@@ -433,6 +891,68 @@ confirm_destructive = false
         assert!(!settings.settings.confirm_destructive);
     }
 
+    #[test]
+    fn test_connection_overrides_merge_over_globals() {
+        let toml_str = r#"
+[settings]
+preview_rows = 50
+max_result_rows = 2000
+
+[connections.prod.settings]
+max_result_rows = 100
+theme = "ember"
+confirm_destructive = false
+explain_options = ""
+mask_columns = ["*password*", "email"]
+statement_timeout_ms = 5000
+cost_guard_rows = 1000000
+cost_guard_action = "refuse"
+"#;
+        let settings: Settings = toml::from_str(toml_str).unwrap();
+        let merged = settings.for_connection("prod");
+        assert_eq!(merged.max_result_rows, 100);
+        assert_eq!(merged.statement_timeout_ms, 5000);
+        assert_eq!(merged.cost_guard_rows, 1_000_000);
+        assert_eq!(merged.cost_guard_action, "refuse");
+        assert_eq!(merged.theme, "ember");
+        assert!(!merged.confirm_destructive);
+        assert_eq!(merged.explain_options, "");
+        assert_eq!(merged.mask_columns, vec!["*password*", "email"]);
+        // Not overridden — inherits the global value
+        assert_eq!(merged.preview_rows, 50);
+    }
+
+    #[test]
+    fn test_for_connection_without_overrides_returns_globals() {
+        let toml_str = r#"
+[settings]
+max_result_rows = 2000
+
+[connections.prod.settings]
+max_result_rows = 100
+"#;
+        let settings: Settings = toml::from_str(toml_str).unwrap();
+        let merged = settings.for_connection("staging");
+        assert_eq!(merged.max_result_rows, 2000);
+        assert_eq!(merged.theme, "dark");
+    }
+
+    #[test]
+    fn test_connection_overrides_entries() {
+        let overrides = SettingsOverrides {
+            preview_rows: Some(10),
+            theme: Some("light".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            overrides.entries(),
+            vec![
+                ("preview_rows", "10".to_string()),
+                ("theme", "light".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn test_read_only_defaults_false() {
         let settings: Settings = toml::from_str("").unwrap();
@@ -448,4 +968,120 @@ read_only = true
         let settings: Settings = toml::from_str(toml_str).unwrap();
         assert!(settings.settings.read_only);
     }
+
+    #[test]
+    fn test_parse_valid_file_has_no_warnings() {
+        let settings = Settings::parse(
+            r#"
+[settings]
+preview_rows = 50
+
+[keybindings.editor]
+execute_query = "ctrl+e"
+
+[connections.prod.settings]
+confirm_destructive = false
+"#,
+        );
+        assert!(settings.warnings.is_empty(), "{:?}", settings.warnings);
+        assert_eq!(settings.settings.preview_rows, 50);
+        assert_eq!(
+            settings.keybindings.editor.get("execute_query").unwrap(),
+            "ctrl+e"
+        );
+        assert_eq!(
+            settings.connections["prod"].settings.confirm_destructive,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_parse_reports_unknown_key_with_line() {
+        let settings = Settings::parse(
+            "[settings]\npreview_rows = 50\npreveiw_rows = 20\n\n[colors]\nfg = 1\n",
+        );
+        assert_eq!(
+            settings.warnings,
+            vec![
+                "line 3: unknown key `settings.preveiw_rows`",
+                "line 5: unknown key `colors`",
+            ]
+        );
+        assert_eq!(settings.settings.preview_rows, 50);
+    }
+
+    #[test]
+    fn test_parse_drops_mistyped_value_keeps_the_rest() {
+        let settings = Settings::parse("[settings]\nmax_tabs = \"ten\"\nhistory_size = 42\n");
+        assert_eq!(settings.warnings.len(), 1);
+        assert!(
+            settings.warnings[0].starts_with("line 2: `settings.max_tabs`: invalid type"),
+            "{}",
+            settings.warnings[0]
+        );
+        assert_eq!(settings.settings.max_tabs, 5);
+        assert_eq!(settings.settings.history_size, 42);
+    }
+
+    #[test]
+    fn test_parse_syntax_error_uses_defaults() {
+        let settings = Settings::parse("[settings]\npreview_rows = 50\nmax_tabs = = 3\n");
+        assert_eq!(settings.warnings.len(), 1);
+        assert!(
+            settings.warnings[0].starts_with("line 3: "),
+            "{}",
+            settings.warnings[0]
+        );
+        assert!(settings.warnings[0].ends_with("Using defaults."));
+        assert_eq!(settings.settings.preview_rows, 100);
+    }
+
+    #[test]
+    fn test_parse_checks_keybindings_and_connection_overrides() {
+        let settings = Settings::parse(
+            r#"
+[keybindings.editor]
+execute_query = 5
+format_query = "ctrl+shift+f"
+
+[keybindings.sidebar]
+x = "y"
+
+[connections.prod]
+colour = "red"
+
+[connections.prod.settings]
+max_tabz = 3
+confirm_destructive = false
+"#,
+        );
+        assert_eq!(settings.warnings.len(), 4, "{:?}", settings.warnings);
+        assert!(settings.warnings[0].starts_with("line 3: `keybindings.editor.execute_query`"));
+        assert_eq!(
+            settings.warnings[1],
+            "line 6: unknown key `keybindings.sidebar`"
+        );
+        assert_eq!(
+            settings.warnings[2],
+            "line 13: unknown key `connections.prod.settings.max_tabz`"
+        );
+        assert_eq!(
+            settings.warnings[3],
+            "line 10: unknown key `connections.prod.colour`"
+        );
+        assert_eq!(
+            settings.keybindings.editor.get("format_query").unwrap(),
+            "ctrl+shift+f"
+        );
+        assert_eq!(
+            settings.connections["prod"].settings.confirm_destructive,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_default_template_parses_cleanly() {
+        let settings = Settings::parse(DEFAULT_CONFIG_TEMPLATE);
+        assert!(settings.warnings.is_empty(), "{:?}", settings.warnings);
+    }
 }
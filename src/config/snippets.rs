@@ -0,0 +1,164 @@
+//! Snippet library
+//!
+//! Named SQL fragments (CTE boilerplate, monitoring queries) kept in
+//! ~/.vizgres/snippets.toml. Unlike saved queries they aren't tied to a
+//! connection: `/snippet <name>` inserts one into any editor.
+
+use crate::error::ConfigResult;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A named piece of SQL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub name: String,
+    pub sql: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SnippetsFile {
+    #[serde(default)]
+    snippets: Vec<Snippet>,
+}
+
+/// The snippets, sorted by name, with the file they persist to
+#[derive(Debug, Default)]
+pub struct Snippets {
+    entries: Vec<Snippet>,
+    /// File path for persistence (`None` = in-memory only)
+    path: Option<PathBuf>,
+}
+
+impl Snippets {
+    /// Load snippets from ~/.vizgres/snippets.toml (empty if it doesn't exist)
+    pub fn load() -> ConfigResult<Self> {
+        Self::load_from(super::connections::ConnectionConfig::config_dir()?.join("snippets.toml"))
+    }
+
+    /// Load snippets from `path`, which later saves write to
+    pub fn load_from(path: PathBuf) -> ConfigResult<Self> {
+        let mut entries = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            toml::from_str::<SnippetsFile>(&content)?.snippets
+        } else {
+            Vec::new()
+        };
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Self {
+            entries,
+            path: Some(path),
+        })
+    }
+
+    /// Snippets in name order
+    pub fn iter(&self) -> impl Iterator<Item = &Snippet> {
+        self.entries.iter()
+    }
+
+    /// Snippet names in order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|s| s.name.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// A snippet's SQL by name
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|s| s.name == name)
+            .map(|s| s.sql.as_str())
+    }
+
+    /// Add a snippet, replacing any with the same name, and write the file
+    pub fn save(&mut self, name: &str, sql: &str) -> ConfigResult<()> {
+        let mut entries = self.entries.clone();
+        entries.retain(|s| s.name != name);
+        entries.push(Snippet {
+            name: name.to_string(),
+            sql: sql.to_string(),
+        });
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        self.write(&entries)?;
+        self.entries = entries;
+        Ok(())
+    }
+
+    /// Remove a snippet and write the file. Returns false if there was none.
+    pub fn delete(&mut self, name: &str) -> ConfigResult<bool> {
+        if self.get(name).is_none() {
+            return Ok(false);
+        }
+        let mut entries = self.entries.clone();
+        entries.retain(|s| s.name != name);
+        self.write(&entries)?;
+        self.entries = entries;
+        Ok(true)
+    }
+
+    fn write(&self, entries: &[Snippet]) -> ConfigResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let file = SnippetsFile {
+            snippets: entries.to_vec(),
+        };
+        let content = toml::to_string_pretty(&file)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_replaces_and_sorts() {
+        let mut snippets = Snippets::default();
+        snippets.save("locks", "SELECT * FROM pg_locks").unwrap();
+        snippets.save("activity", "SELECT 1").unwrap();
+        snippets
+            .save("activity", "SELECT * FROM pg_stat_activity")
+            .unwrap();
+        assert_eq!(snippets.names().collect::<Vec<_>>(), ["activity", "locks"]);
+        assert_eq!(
+            snippets.get("activity"),
+            Some("SELECT * FROM pg_stat_activity")
+        );
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut snippets = Snippets::default();
+        snippets
+            .save("cte", "WITH x AS (\n  SELECT 1\n)\n")
+            .unwrap();
+        assert!(snippets.delete("cte").unwrap());
+        assert!(!snippets.delete("cte").unwrap());
+        assert!(snippets.is_empty());
+    }
+
+    #[test]
+    fn test_write_and_read_back() {
+        let dir = std::env::temp_dir().join(format!("vizgres-snippets-{}", std::process::id()));
+        let path = dir.join("snippets.toml");
+        let mut snippets = Snippets {
+            entries: Vec::new(),
+            path: Some(path.clone()),
+        };
+        snippets
+            .save("cte", "WITH x AS (\n  SELECT 1\n)\n")
+            .unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let file: SnippetsFile = toml::from_str(&content).unwrap();
+        assert_eq!(file.snippets.len(), 1);
+        assert_eq!(file.snippets[0].sql, "WITH x AS (\n  SELECT 1\n)\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
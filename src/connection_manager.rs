@@ -1,7 +1,8 @@
 //! Per-tab database connection management.
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
 use tokio::sync::mpsc;
 
@@ -12,21 +13,44 @@ use crate::db;
 ///
 /// Each tab gets its own PostgreSQL connection, lazily created on first query.
 /// This gives each tab independent transaction state and allows concurrent queries.
+/// A tab can also be bound to a different database than the rest (e.g.
+/// staging next to prod); schema loading and background jobs always use
+/// the main connection.
 pub struct ConnectionManager {
     /// Per-tab providers: tab_id → (provider, connection-error receiver)
     tabs: HashMap<usize, (Arc<db::PostgresProvider>, mpsc::UnboundedReceiver<String>)>,
-    /// Connection config (shared — all tabs connect to the same database)
+    /// Main connection config, shared by every tab not bound to its own
     config: Option<ConnectionConfig>,
-    /// Statement timeout for new connections
+    /// Tabs bound to their own connection: tab_id → (config, statement
+    /// timeout for its new connections)
+    tab_configs: HashMap<usize, (ConnectionConfig, u64)>,
+    /// Statement timeout for new connections on the main config
     statement_timeout_ms: u64,
+    /// Connections for background jobs (None when not connected)
+    job_pool: Option<JobPool>,
+    /// Run user queries over the simple query protocol on every connection
+    /// (the connection prefers it, or the server sits behind a
+    /// transaction-pooling proxy)
+    simple_protocol: bool,
+    /// Dedicated LISTEN connection on the main database (None until the
+    /// first `/listen`, and again once nothing is listened to)
+    listener: Option<db::Listener>,
 }
 
 impl ConnectionManager {
     pub fn new(config: Option<ConnectionConfig>, statement_timeout_ms: u64) -> Self {
+        let job_pool = config
+            .clone()
+            .map(|c| JobPool::new(c, statement_timeout_ms));
+        let simple_protocol = config.as_ref().is_some_and(|c| c.prefer_simple_protocol);
         Self {
             tabs: HashMap::new(),
             config,
+            tab_configs: HashMap::new(),
             statement_timeout_ms,
+            job_pool,
+            simple_protocol,
+            listener: None,
         }
     }
 
@@ -45,9 +69,37 @@ impl ConnectionManager {
         self.tabs.get(&tab_id).map(|(p, _)| p)
     }
 
-    /// Get any available provider (for schema operations that don't need a specific tab).
+    /// Get any available provider on the main connection (for schema
+    /// operations that don't need a specific tab).
     pub fn any_provider(&self) -> Option<&Arc<db::PostgresProvider>> {
-        self.tabs.values().next().map(|(p, _)| p)
+        self.tabs
+            .iter()
+            .find(|(tab_id, _)| !self.tab_configs.contains_key(tab_id))
+            .map(|(_, (p, _))| p)
+    }
+
+    /// Bind a tab to its own connection, dropping the one it has. It
+    /// connects on its next query, with `statement_timeout_ms`.
+    pub fn bind_tab(&mut self, tab_id: usize, config: ConnectionConfig, statement_timeout_ms: u64) {
+        self.tabs.remove(&tab_id);
+        self.tab_configs
+            .insert(tab_id, (config, statement_timeout_ms));
+    }
+
+    /// The config a tab connects with: its own, or the main one
+    pub fn tab_config(&self, tab_id: usize) -> Option<&ConnectionConfig> {
+        self.tab_configs
+            .get(&tab_id)
+            .map(|(config, _)| config)
+            .or(self.config.as_ref())
+    }
+
+    /// The statement timeout a tab's new connection starts with: its
+    /// binding's, or the main one
+    fn tab_statement_timeout(&self, tab_id: usize) -> u64 {
+        self.tab_configs
+            .get(&tab_id)
+            .map_or(self.statement_timeout_ms, |(_, timeout_ms)| *timeout_ms)
     }
 
     /// Connect a tab lazily. Returns the provider on success.
@@ -60,36 +112,160 @@ impl ConnectionManager {
         }
 
         let config = self
-            .config
-            .as_ref()
+            .tab_config(tab_id)
             .ok_or_else(|| "Not connected".to_string())?;
 
-        let (prov, rx) = db::PostgresProvider::connect(config, self.statement_timeout_ms)
+        let statement_timeout_ms = self.tab_statement_timeout(tab_id);
+        let (prov, rx) = db::PostgresProvider::connect(config, statement_timeout_ms)
             .await
             .map_err(|e| format!("Connection failed: {}", e))?;
 
+        prov.set_simple_protocol(self.simple_protocol || config.prefer_simple_protocol);
         let prov = Arc::new(prov);
         self.tabs.insert(tab_id, (Arc::clone(&prov), rx));
         Ok(prov)
     }
 
-    /// Remove a tab's connection (on tab close).
+    /// Switch every connection, open and future, to the simple query
+    /// protocol once one of them finds prepared statements don't work
+    pub fn use_simple_protocol(&mut self) {
+        self.simple_protocol = true;
+        for (prov, _) in self.tabs.values() {
+            prov.set_simple_protocol(true);
+        }
+        if let Some(pool) = &self.job_pool {
+            pool.simple_protocol.store(true, Ordering::Relaxed);
+            if let Ok(idle) = pool.idle.lock() {
+                for prov in idle.iter() {
+                    prov.set_simple_protocol(true);
+                }
+            }
+        }
+    }
+
+    /// Change the statement timeout for new connections, returning the
+    /// open ones to apply it to. Idle job connections are closed so jobs
+    /// reconnect with it.
+    pub fn set_statement_timeout(&mut self, timeout_ms: u64) -> Vec<Arc<db::PostgresProvider>> {
+        self.statement_timeout_ms = timeout_ms;
+        for (_, bound_timeout_ms) in self.tab_configs.values_mut() {
+            *bound_timeout_ms = timeout_ms;
+        }
+        if let Some(pool) = &self.job_pool {
+            pool.statement_timeout_ms
+                .store(timeout_ms, Ordering::Relaxed);
+            if let Ok(mut idle) = pool.idle.lock() {
+                idle.clear();
+            }
+        }
+        self.tabs.values().map(|(p, _)| Arc::clone(p)).collect()
+    }
+
+    /// Whether connections use the simple query protocol
+    pub fn uses_simple_protocol(&self) -> bool {
+        self.simple_protocol
+    }
+
+    /// Remove a tab's connection and any binding (on tab close).
     pub fn remove(&mut self, tab_id: usize) {
         self.tabs.remove(&tab_id);
+        self.tab_configs.remove(&tab_id);
+    }
+
+    /// Drop a tab's dead connection, keeping its binding, so it reconnects
+    /// on the next query.
+    pub fn drop_connection(&mut self, tab_id: usize) {
+        self.tabs.remove(&tab_id);
     }
 
     /// Drop all connections (on disconnect / reconnect).
     pub fn disconnect_all(&mut self) {
         self.tabs.clear();
+        self.tab_configs.clear();
         self.config = None;
+        self.job_pool = None;
+        self.simple_protocol = false;
+        self.listener = None;
+    }
+
+    /// The current connection config, if connected
+    pub fn config(&self) -> Option<&ConnectionConfig> {
+        self.config.as_ref()
     }
 
     /// Set the connection config (on new connect).
     pub fn set_config(&mut self, config: ConnectionConfig, statement_timeout_ms: u64) {
+        self.job_pool = Some(JobPool::new(config.clone(), statement_timeout_ms));
+        self.simple_protocol = config.prefer_simple_protocol;
+        self.listener = None;
         self.config = Some(config);
         self.statement_timeout_ms = statement_timeout_ms;
     }
 
+    /// Point the main connection at `config` (another database on the same
+    /// server), dropping the connections of the tabs that use it. Tabs
+    /// bound to their own connection keep it.
+    pub fn switch_main(&mut self, config: ConnectionConfig, statement_timeout_ms: u64) {
+        let bound = &self.tab_configs;
+        self.tabs.retain(|tab_id, _| bound.contains_key(tab_id));
+        self.set_config(config, statement_timeout_ms);
+    }
+
+    /// LISTEN on a channel, opening the listener connection first if
+    /// needed. Returns the notification receiver of a newly opened
+    /// connection, for the caller to forward.
+    pub async fn listen(
+        &mut self,
+        channel: &str,
+    ) -> Result<Option<mpsc::UnboundedReceiver<db::ListenerEvent>>, String> {
+        let mut opened = None;
+        let listener = match self.listener.take() {
+            Some(listener) => listener,
+            None => {
+                let config = self.config.as_ref().ok_or("Not connected")?;
+                let (listener, rx) = db::Listener::connect(config)
+                    .await
+                    .map_err(|e| format!("Listener connection failed: {}", e))?;
+                opened = Some(rx);
+                listener
+            }
+        };
+        let listener = self.listener.insert(listener);
+        if let Err(e) = listener.listen(channel).await {
+            if listener.channels().is_empty() {
+                self.listener = None;
+            }
+            return Err(format!("LISTEN failed: {}", e));
+        }
+        Ok(opened)
+    }
+
+    /// UNLISTEN a channel, or every channel when `None`. The listener
+    /// connection closes once nothing is listened to.
+    pub async fn unlisten(&mut self, channel: Option<&str>) -> Result<(), String> {
+        if let (Some(channel), Some(listener)) = (channel, self.listener.as_mut()) {
+            listener
+                .unlisten(channel)
+                .await
+                .map_err(|e| format!("UNLISTEN failed: {}", e))?;
+            if !listener.channels().is_empty() {
+                return Ok(());
+            }
+        }
+        self.listener = None;
+        Ok(())
+    }
+
+    /// Channels the listener connection is listening on
+    pub fn listen_channels(&self) -> &[String] {
+        self.listener.as_ref().map_or(&[], |l| l.channels())
+    }
+
+    /// Connections for background jobs, shareable with the tasks running them
+    pub fn job_pool(&self) -> Option<JobPool> {
+        self.job_pool.clone()
+    }
+
     /// Poll all connection-error receivers, returning the first error with its tab_id.
     /// Returns Pending if no errors ready.
     pub fn poll_connection_errors(
@@ -121,21 +297,101 @@ impl ConnectionManager {
     }
 }
 
+/// Most idle job connections kept open for reuse
+const MAX_IDLE_JOB_CONNECTIONS: usize = 2;
+
+/// Connections for background jobs, which run apart from any tab.
+///
+/// A job takes an idle connection (or opens one) and hands it back when it
+/// finishes, so a series of jobs doesn't reconnect each time.
+#[derive(Clone)]
+pub struct JobPool {
+    config: ConnectionConfig,
+    /// Statement timeout for new connections (`/timeout` changes it)
+    statement_timeout_ms: Arc<AtomicU64>,
+    idle: Arc<Mutex<Vec<Arc<db::PostgresProvider>>>>,
+    /// New connections start on the simple query protocol
+    simple_protocol: Arc<AtomicBool>,
+    /// Connections in use: job id → connection, for cancelling
+    running: Arc<Mutex<HashMap<usize, Arc<db::PostgresProvider>>>>,
+}
+
+impl JobPool {
+    fn new(config: ConnectionConfig, statement_timeout_ms: u64) -> Self {
+        Self {
+            simple_protocol: Arc::new(AtomicBool::new(config.prefer_simple_protocol)),
+            config,
+            statement_timeout_ms: Arc::new(AtomicU64::new(statement_timeout_ms)),
+            idle: Arc::new(Mutex::new(Vec::new())),
+            running: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// An idle connection, or a new one, for job `job_id`
+    pub async fn acquire(&self, job_id: usize) -> Result<Arc<db::PostgresProvider>, String> {
+        let prov = self.idle_or_new().await?;
+        if let Ok(mut running) = self.running.lock() {
+            running.insert(job_id, Arc::clone(&prov));
+        }
+        Ok(prov)
+    }
+
+    async fn idle_or_new(&self) -> Result<Arc<db::PostgresProvider>, String> {
+        while let Some(prov) = self.idle.lock().ok().and_then(|mut idle| idle.pop()) {
+            if !prov.is_closed() {
+                return Ok(prov);
+            }
+        }
+        // Jobs don't watch for lost connections; the query fails instead
+        let statement_timeout_ms = self.statement_timeout_ms.load(Ordering::Relaxed);
+        let (prov, _rx) = db::PostgresProvider::connect(&self.config, statement_timeout_ms)
+            .await
+            .map_err(|e| format!("Connection failed: {}", e))?;
+        prov.set_simple_protocol(self.simple_protocol.load(Ordering::Relaxed));
+        Ok(Arc::new(prov))
+    }
+
+    /// The connection job `job_id` is running on, if it has one yet
+    pub fn running(&self, job_id: usize) -> Option<Arc<db::PostgresProvider>> {
+        self.running.lock().ok()?.get(&job_id).cloned()
+    }
+
+    /// Return a connection after its job finishes
+    pub fn release(&self, job_id: usize, prov: Arc<db::PostgresProvider>) {
+        if let Ok(mut running) = self.running.lock() {
+            running.remove(&job_id);
+        }
+        if prov.is_closed() {
+            return;
+        }
+        if let Ok(mut idle) = self.idle.lock()
+            && idle.len() < MAX_IDLE_JOB_CONNECTIONS
+        {
+            idle.push(prov);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::connections::SslMode;
+    use crate::config::connections::{Backend, SslMode};
 
     fn test_config() -> ConnectionConfig {
         ConnectionConfig {
             name: "test".to_string(),
             host: "localhost".to_string(),
             port: 5432,
+            backend: Backend::Postgres,
             database: "testdb".to_string(),
             username: "user".to_string(),
             password: None,
             ssl_mode: SslMode::Disable,
+            ssl_root_cert: None,
+            ssl_cert: None,
+            ssl_key: None,
             read_only: false,
+            prefer_simple_protocol: false,
             is_saved: false,
         }
     }
@@ -170,10 +426,96 @@ mod tests {
     fn test_disconnect_all_clears_config() {
         let mut mgr = ConnectionManager::new(Some(test_config()), 5000);
         assert!(mgr.has_config());
+        assert!(mgr.job_pool().is_some());
 
         mgr.disconnect_all();
         assert!(!mgr.has_config());
         assert!(!mgr.has_connections());
+        assert!(mgr.job_pool().is_none());
+    }
+
+    #[test]
+    fn test_simple_protocol_lasts_until_reconnect() {
+        let mut mgr = ConnectionManager::new(Some(test_config()), 5000);
+        assert!(!mgr.uses_simple_protocol());
+        mgr.use_simple_protocol();
+        assert!(mgr.uses_simple_protocol());
+        let pool = mgr.job_pool().unwrap();
+        assert!(pool.simple_protocol.load(Ordering::Relaxed));
+
+        mgr.set_config(test_config(), 5000);
+        assert!(!mgr.uses_simple_protocol());
+    }
+
+    #[test]
+    fn test_prefer_simple_protocol_from_config() {
+        let mut config = test_config();
+        config.prefer_simple_protocol = true;
+        let mut mgr = ConnectionManager::new(Some(config.clone()), 5000);
+        assert!(mgr.uses_simple_protocol());
+        assert!(
+            mgr.job_pool()
+                .unwrap()
+                .simple_protocol
+                .load(Ordering::Relaxed)
+        );
+
+        mgr.set_config(test_config(), 5000);
+        assert!(!mgr.uses_simple_protocol());
+        mgr.set_config(config, 5000);
+        assert!(mgr.uses_simple_protocol());
+    }
+
+    #[test]
+    fn test_bound_tab_keeps_its_config_until_closed() {
+        let mut staging = test_config();
+        staging.name = "staging".to_string();
+        let mut mgr = ConnectionManager::new(Some(test_config()), 5000);
+        mgr.bind_tab(2, staging, 5000);
+        assert_eq!(mgr.tab_config(2).unwrap().name, "staging");
+        assert_eq!(mgr.tab_config(1).unwrap().name, "test");
+
+        // A lost connection reconnects to the same database
+        mgr.drop_connection(2);
+        assert_eq!(mgr.tab_config(2).unwrap().name, "staging");
+
+        mgr.remove(2);
+        assert_eq!(mgr.tab_config(2).unwrap().name, "test");
+
+        mgr.bind_tab(3, test_config(), 5000);
+        mgr.disconnect_all();
+        assert!(mgr.tab_config(3).is_none());
+    }
+
+    #[test]
+    fn test_bound_tab_uses_its_statement_timeout() {
+        let mut staging = test_config();
+        staging.name = "staging".to_string();
+        let mut mgr = ConnectionManager::new(Some(test_config()), 5000);
+        mgr.bind_tab(2, staging, 30000);
+        assert_eq!(mgr.tab_statement_timeout(2), 30000);
+        assert_eq!(mgr.tab_statement_timeout(1), 5000);
+
+        // /timeout overrides every connection, bound ones included
+        mgr.set_statement_timeout(1000);
+        assert_eq!(mgr.tab_statement_timeout(2), 1000);
+
+        mgr.remove(2);
+        assert_eq!(mgr.tab_statement_timeout(2), 1000);
+    }
+
+    #[test]
+    fn test_switch_main_keeps_bound_tabs() {
+        let mut mgr = ConnectionManager::new(Some(test_config()), 5000);
+        let mut staging = test_config();
+        staging.name = "staging".to_string();
+        mgr.bind_tab(1, staging, 5000);
+
+        let mut other = test_config();
+        other.database = "analytics".to_string();
+        mgr.switch_main(other, 5000);
+        assert_eq!(mgr.tab_config(0).unwrap().database, "analytics");
+        assert_eq!(mgr.tab_config(1).unwrap().name, "staging");
     }
 
     #[test]
@@ -0,0 +1,56 @@
+//! Server sessions from `pg_stat_activity`
+//!
+//! `/sessions` lists the other client backends on the server with what
+//! they're running, so a stuck or runaway one can be cancelled or
+//! terminated.
+
+use std::time::Duration;
+
+/// Client backends other than the one asking, running ones first and the
+/// longest-running of those at the top. `duration` is how long the
+/// current query has run, or how long the session has been in its state
+/// when it isn't running one.
+pub const SESSIONS_SQL: &str = "\
+SELECT pid, usename, datname, application_name, client_addr::text, state, \
+       wait_event_type, wait_event, \
+       EXTRACT(EPOCH FROM now() - CASE WHEN state = 'active' THEN query_start \
+                                       ELSE state_change END)::float8, \
+       query \
+FROM pg_stat_activity \
+WHERE pid <> pg_backend_pid() AND backend_type = 'client backend' \
+ORDER BY state = 'active' DESC, \
+         CASE WHEN state = 'active' THEN query_start ELSE state_change END NULLS LAST";
+
+/// One backend in `pg_stat_activity`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub pid: i32,
+    pub user: Option<String>,
+    pub database: Option<String>,
+    pub application: Option<String>,
+    pub client: Option<String>,
+    /// `active`, `idle`, `idle in transaction`, ... (None without the
+    /// privilege to see it)
+    pub state: Option<String>,
+    /// `type:event` the backend is waiting on
+    pub wait_event: Option<String>,
+    pub duration: Option<Duration>,
+    /// Current query, or the last one when idle
+    pub query: Option<String>,
+}
+
+impl Session {
+    /// `wait_event_type:wait_event`, when the backend is waiting
+    pub fn wait(event_type: Option<String>, event: Option<String>) -> Option<String> {
+        match (event_type, event) {
+            (Some(t), Some(e)) => Some(format!("{}:{}", t, e)),
+            (t, e) => t.or(e),
+        }
+    }
+
+    /// Duration from the seconds the server reported
+    pub fn duration_from_secs(secs: Option<f64>) -> Option<Duration> {
+        secs.filter(|s| s.is_finite())
+            .map(|s| Duration::from_secs_f64(s.max(0.0)))
+    }
+}
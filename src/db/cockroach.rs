@@ -0,0 +1,44 @@
+//! CockroachDB dialect
+//!
+//! CockroachDB speaks the PostgreSQL wire protocol, so `PostgresProvider`
+//! connects to it as-is when the URL scheme is `cockroachdb://`. This
+//! module holds the SQL that differs: the extra virtual schemas to hide,
+//! `SHOW CREATE` for object DDL, and cancelling without
+//! `pg_cancel_backend()` (which CockroachDB doesn't implement).
+
+use crate::sql::quote_qualified;
+
+/// Schemas CockroachDB adds to every database on top of `pg_catalog` and
+/// `information_schema`
+const INTERNAL_SCHEMAS: &[&str] = &["crdb_internal"];
+
+/// Whether a schema is one of CockroachDB's virtual schemas, hidden from
+/// the tree like `pg_catalog`
+pub fn is_internal_schema(name: &str) -> bool {
+    INTERNAL_SCHEMAS.contains(&name)
+}
+
+/// `SHOW CREATE` for a table, view, or sequence. The statement is in the
+/// `create_statement` column of its one row.
+pub fn show_create_sql(schema: &str, name: &str) -> String {
+    format!("SHOW CREATE {}", quote_qualified(schema, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_internal_schemas() {
+        assert!(is_internal_schema("crdb_internal"));
+        assert!(!is_internal_schema("public"));
+    }
+
+    #[test]
+    fn test_show_create_sql() {
+        assert_eq!(
+            show_create_sql("public", "Order Items"),
+            r#"SHOW CREATE "public"."Order Items""#
+        );
+    }
+}
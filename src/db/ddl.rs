@@ -0,0 +1,241 @@
+//! DDL generation for schema objects
+//!
+//! The provider gathers catalog details for a table, view, index, or
+//! function; this module turns them into a runnable script.
+
+/// Object to generate DDL for, as selected in the tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DdlTarget {
+    /// Table, view, materialized view, or index (kind is looked up)
+    Relation { schema: String, name: String },
+    /// Every function or procedure with this name in the schema
+    Function { schema: String, name: String },
+}
+
+impl DdlTarget {
+    /// `schema.name` for status messages
+    pub fn display_name(&self) -> String {
+        match self {
+            DdlTarget::Relation { schema, name } | DdlTarget::Function { schema, name } => {
+                format!("{}.{}", schema, name)
+            }
+        }
+    }
+}
+
+/// A table column as read from `pg_attribute`
+#[derive(Debug, Clone, Default)]
+pub struct DdlColumn {
+    /// Column name, already quoted by the server's `quote_ident()`
+    pub name: String,
+    pub data_type: String,
+    pub not_null: bool,
+    /// Default expression, or the generation expression for generated columns
+    pub default: Option<String>,
+    /// `pg_attribute.attidentity`: 'a' (ALWAYS), 'd' (BY DEFAULT), or empty
+    pub identity: String,
+    /// `pg_attribute.attgenerated`: 's' (STORED), 'v' (VIRTUAL), or empty
+    pub generated: String,
+    pub comment: Option<String>,
+}
+
+/// Everything needed to recreate a table
+#[derive(Debug, Clone, Default)]
+pub struct TableDdl {
+    /// Quoted `schema.table`
+    pub qualified: String,
+    pub columns: Vec<DdlColumn>,
+    /// Table constraints as (quoted name, `pg_get_constraintdef`)
+    pub constraints: Vec<(String, String)>,
+    /// `pg_get_partkeydef` for partitioned tables
+    pub partition_key: Option<String>,
+    /// `pg_get_indexdef` for indexes not backing a constraint
+    pub indexes: Vec<String>,
+    /// `pg_get_triggerdef` for user triggers
+    pub triggers: Vec<String>,
+    pub comment: Option<String>,
+}
+
+impl TableDdl {
+    /// Render as CREATE TABLE followed by indexes, triggers, and comments
+    pub fn to_sql(&self) -> String {
+        let mut lines: Vec<String> = self.columns.iter().map(column_clause).collect();
+        lines.extend(
+            self.constraints
+                .iter()
+                .map(|(name, def)| format!("CONSTRAINT {} {}", name, def)),
+        );
+        let mut out = format!("CREATE TABLE {} (\n", self.qualified);
+        out.push_str(
+            &lines
+                .iter()
+                .map(|l| format!("    {}", l))
+                .collect::<Vec<_>>()
+                .join(",\n"),
+        );
+        out.push_str("\n)");
+        if let Some(key) = &self.partition_key {
+            out.push_str(&format!(" PARTITION BY {}", key));
+        }
+        out.push_str(";\n");
+
+        for stmt in self.indexes.iter().chain(&self.triggers) {
+            out.push_str(&format!("\n{};", stmt));
+        }
+        if !self.indexes.is_empty() || !self.triggers.is_empty() {
+            out.push('\n');
+        }
+
+        let mut comments = Vec::new();
+        if let Some(comment) = &self.comment {
+            comments.push(comment_stmt("TABLE", &self.qualified, comment));
+        }
+        for col in &self.columns {
+            if let Some(comment) = &col.comment {
+                let target = format!("{}.{}", self.qualified, col.name);
+                comments.push(comment_stmt("COLUMN", &target, comment));
+            }
+        }
+        if !comments.is_empty() {
+            out.push('\n');
+            out.push_str(&comments.join("\n"));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// `CREATE [MATERIALIZED] VIEW` from a `pg_get_viewdef` body
+pub fn view_ddl(qualified: &str, materialized: bool, definition: &str) -> String {
+    let body = definition.trim().trim_end_matches(';');
+    if materialized {
+        format!("CREATE MATERIALIZED VIEW {} AS\n{};\n", qualified, body)
+    } else {
+        format!("CREATE OR REPLACE VIEW {} AS\n{};\n", qualified, body)
+    }
+}
+
+fn column_clause(col: &DdlColumn) -> String {
+    let mut clause = format!("{} {}", col.name, col.data_type);
+    match (col.identity.as_str(), col.generated.as_str(), &col.default) {
+        ("a", _, _) => clause.push_str(" GENERATED ALWAYS AS IDENTITY"),
+        ("d", _, _) => clause.push_str(" GENERATED BY DEFAULT AS IDENTITY"),
+        ("", "s", Some(expr)) => {
+            clause.push_str(&format!(" GENERATED ALWAYS AS ({}) STORED", expr))
+        }
+        ("", "v", Some(expr)) => clause.push_str(&format!(" GENERATED ALWAYS AS ({})", expr)),
+        (_, _, Some(expr)) => clause.push_str(&format!(" DEFAULT {}", expr)),
+        _ => {}
+    }
+    if col.not_null {
+        clause.push_str(" NOT NULL");
+    }
+    clause
+}
+
+fn comment_stmt(kind: &str, target: &str, comment: &str) -> String {
+    format!(
+        "COMMENT ON {} {} IS {};",
+        kind,
+        target,
+        crate::sql::quote_literal(comment)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str) -> DdlColumn {
+        DdlColumn {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_table_ddl_with_constraints_indexes_and_comments() {
+        let table = TableDdl {
+            qualified: "public.users".to_string(),
+            columns: vec![
+                DdlColumn {
+                    not_null: true,
+                    identity: "a".to_string(),
+                    ..column("id", "integer")
+                },
+                DdlColumn {
+                    not_null: true,
+                    comment: Some("user's email".to_string()),
+                    ..column("email", "text")
+                },
+                DdlColumn {
+                    default: Some("now()".to_string()),
+                    ..column("\"Created At\"", "timestamp with time zone")
+                },
+            ],
+            constraints: vec![("users_pkey".to_string(), "PRIMARY KEY (id)".to_string())],
+            indexes: vec!["CREATE INDEX users_email ON public.users USING btree (email)".into()],
+            comment: Some("App users".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            table.to_sql(),
+            "CREATE TABLE public.users (\n\
+             \x20   id integer GENERATED ALWAYS AS IDENTITY NOT NULL,\n\
+             \x20   email text NOT NULL,\n\
+             \x20   \"Created At\" timestamp with time zone DEFAULT now(),\n\
+             \x20   CONSTRAINT users_pkey PRIMARY KEY (id)\n\
+             );\n\
+             \n\
+             CREATE INDEX users_email ON public.users USING btree (email);\n\
+             \n\
+             COMMENT ON TABLE public.users IS 'App users';\n\
+             COMMENT ON COLUMN public.users.email IS 'user''s email';\n"
+        );
+    }
+
+    #[test]
+    fn test_generated_column_and_partition_key() {
+        let table = TableDdl {
+            qualified: "public.events".to_string(),
+            columns: vec![
+                column("at", "date"),
+                DdlColumn {
+                    generated: "s".to_string(),
+                    default: Some("EXTRACT(year FROM at)".to_string()),
+                    ..column("year", "numeric")
+                },
+            ],
+            partition_key: Some("RANGE (at)".to_string()),
+            ..Default::default()
+        };
+        let sql = table.to_sql();
+        assert!(
+            sql.contains("year numeric GENERATED ALWAYS AS (EXTRACT(year FROM at)) STORED"),
+            "{}",
+            sql
+        );
+        assert!(sql.ends_with(") PARTITION BY RANGE (at);\n"), "{}", sql);
+    }
+
+    #[test]
+    fn test_comment_with_quote_and_backslash() {
+        assert_eq!(
+            comment_stmt("TABLE", "public.t", r"it's C:\temp"),
+            r"COMMENT ON TABLE public.t IS E'it''s C:\\temp';"
+        );
+    }
+
+    #[test]
+    fn test_view_ddl() {
+        assert_eq!(
+            view_ddl("public.v", false, " SELECT 1;"),
+            "CREATE OR REPLACE VIEW public.v AS\nSELECT 1;\n"
+        );
+        assert_eq!(
+            view_ddl("public.mv", true, " SELECT 1"),
+            "CREATE MATERIALIZED VIEW public.mv AS\nSELECT 1;\n"
+        );
+    }
+}
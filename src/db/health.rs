@@ -0,0 +1,171 @@
+//! Server health from the `pg_stat_*` views
+//!
+//! `/dashboard` shows connection counts, the database's cache hit ratio,
+//! replication state, the longest-running queries, and sessions waiting on
+//! locks, refreshed while it's open. Each part is one of the queries below.
+//! A shorter `ServerSummary` is shown once on connect.
+
+use std::time::Duration;
+
+/// Client backends by state, against `max_connections`
+pub const CONNECTIONS_SQL: &str = "\
+SELECT count(*) FILTER (WHERE state = 'active'), \
+       count(*) FILTER (WHERE state = 'idle'), \
+       count(*) FILTER (WHERE state LIKE 'idle in transaction%'), \
+       count(*), \
+       current_setting('max_connections')::int8 \
+FROM pg_stat_activity \
+WHERE backend_type = 'client backend'";
+
+/// Block and transaction counters for the current database
+pub const DATABASE_SQL: &str = "\
+SELECT datname, blks_hit, blks_read, xact_commit, xact_rollback, deadlocks \
+FROM pg_stat_database \
+WHERE datname = current_database()";
+
+/// Whether this is a standby, and how far behind its replay is
+pub const RECOVERY_SQL: &str = "\
+SELECT pg_is_in_recovery(), \
+       EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())::float8";
+
+/// Standbys streaming from this server. The WAL position is only asked
+/// for on a primary, where `pg_current_wal_lsn` works.
+pub const REPLICATION_SQL: &str = "\
+SELECT application_name, client_addr::text, state, \
+       EXTRACT(EPOCH FROM replay_lag)::float8, \
+       CASE WHEN pg_is_in_recovery() THEN NULL \
+            ELSE pg_wal_lsn_diff(pg_current_wal_lsn(), replay_lsn)::int8 END \
+FROM pg_stat_replication \
+ORDER BY application_name";
+
+/// Other sessions' running queries, longest first
+pub const LONGEST_SQL: &str = "\
+SELECT pid, usename, EXTRACT(EPOCH FROM now() - query_start)::float8, query, \
+       '{}'::int4[] \
+FROM pg_stat_activity \
+WHERE state = 'active' AND pid <> pg_backend_pid() \
+  AND backend_type = 'client backend' \
+ORDER BY query_start \
+LIMIT 5";
+
+/// Sessions waiting on a lock, with the sessions holding it
+pub const LOCK_WAITS_SQL: &str = "\
+SELECT pid, usename, EXTRACT(EPOCH FROM now() - query_start)::float8, query, \
+       pg_blocking_pids(pid) \
+FROM pg_stat_activity \
+WHERE wait_event_type = 'Lock' \
+ORDER BY query_start \
+LIMIT 10";
+
+/// The open transaction that started first, other than the asker's
+pub const OLDEST_TRANSACTION_SQL: &str = "\
+SELECT pid, usename, EXTRACT(EPOCH FROM now() - xact_start)::float8, query, \
+       '{}'::int4[] \
+FROM pg_stat_activity \
+WHERE xact_start IS NOT NULL AND pid <> pg_backend_pid() \
+ORDER BY xact_start \
+LIMIT 1";
+
+/// The first-glance checks run on connect
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerSummary {
+    /// `server_version`
+    pub version: String,
+    /// Round trip of a trivial query
+    pub latency: Duration,
+    pub standby: bool,
+    /// On a standby, time since the last replayed transaction
+    pub replay_lag: Option<Duration>,
+    pub database: Option<DatabaseStats>,
+    /// `duration` is how long the transaction has been open
+    pub oldest_transaction: Option<ServerQuery>,
+}
+
+/// One snapshot of the server's health
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Health {
+    pub connections: Connections,
+    /// Counters for the current database (None when the view has no row
+    /// for it)
+    pub database: Option<DatabaseStats>,
+    /// The server is a standby
+    pub standby: bool,
+    /// On a standby, time since the last replayed transaction
+    pub replay_lag: Option<Duration>,
+    /// Standbys streaming from this server
+    pub replicas: Vec<Replica>,
+    pub longest: Vec<ServerQuery>,
+    pub lock_waits: Vec<ServerQuery>,
+}
+
+/// Client connections by state
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Connections {
+    pub active: i64,
+    pub idle: i64,
+    pub idle_in_transaction: i64,
+    pub total: i64,
+    /// `max_connections`
+    pub max: i64,
+}
+
+/// `pg_stat_database` counters
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DatabaseStats {
+    pub name: String,
+    pub blocks_hit: i64,
+    pub blocks_read: i64,
+    pub commits: i64,
+    pub rollbacks: i64,
+    pub deadlocks: i64,
+}
+
+impl DatabaseStats {
+    /// Share of block reads served from shared buffers, as a percentage
+    /// (None before anything was read)
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let total = self.blocks_hit + self.blocks_read;
+        (total > 0).then(|| self.blocks_hit as f64 * 100.0 / total as f64)
+    }
+}
+
+/// A standby in `pg_stat_replication`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replica {
+    pub name: Option<String>,
+    pub client: Option<String>,
+    /// `streaming`, `catchup`, ...
+    pub state: Option<String>,
+    pub lag: Option<Duration>,
+    /// WAL not yet replayed on it, in bytes
+    pub behind_bytes: Option<i64>,
+}
+
+/// A session running a query, or waiting on a lock
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerQuery {
+    pub pid: i32,
+    pub user: Option<String>,
+    /// How long the query has run
+    pub duration: Option<Duration>,
+    pub query: Option<String>,
+    /// Sessions holding the lock it waits on
+    pub blocked_by: Vec<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hit_ratio() {
+        let stats = |hit, read| DatabaseStats {
+            blocks_hit: hit,
+            blocks_read: read,
+            ..DatabaseStats::default()
+        };
+        assert_eq!(stats(0, 0).hit_ratio(), None);
+        assert_eq!(stats(99, 1).hit_ratio(), Some(99.0));
+        assert_eq!(stats(0, 5).hit_ratio(), Some(0.0));
+    }
+}
@@ -0,0 +1,151 @@
+//! LISTEN/NOTIFY on a dedicated connection
+//!
+//! Notifications arrive on the connection that issued `LISTEN`, whenever
+//! the server sends them, so they get a connection of their own rather
+//! than sharing a tab's (where a long query would hold them back). The
+//! connection task forwards each NOTIFY as it comes in.
+
+use crate::config::ConnectionConfig;
+use crate::db::tls::make_tls_config;
+use crate::error::{DbError, DbResult};
+use crate::sql::quote_ident;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::mpsc;
+use tokio_postgres::{AsyncMessage, Client, Connection};
+
+/// A NOTIFY received on a channel being listened to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub channel: String,
+    pub payload: String,
+    /// Backend PID of the session that sent it
+    pub process_id: i32,
+}
+
+/// What the listener connection reports
+#[derive(Debug)]
+pub enum ListenerEvent {
+    Notification(Notification),
+    /// The connection went away; nothing more will arrive
+    Closed(String),
+}
+
+/// Connection that `LISTEN`s on a set of channels
+pub struct Listener {
+    client: Client,
+    channels: Vec<String>,
+}
+
+impl Listener {
+    /// Open the listening connection. Notifications, and the connection
+    /// closing, arrive on the returned receiver; dropping the listener
+    /// closes the connection.
+    pub async fn connect(
+        config: &ConnectionConfig,
+    ) -> DbResult<(Self, mpsc::UnboundedReceiver<ListenerEvent>)> {
+        // No statement_timeout: the connection only runs LISTEN/UNLISTEN
+        let conn_string = config.connection_string_with_password(0);
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let client = match make_tls_config(config)? {
+            None => {
+                let (client, connection) =
+                    tokio_postgres::connect(&conn_string, tokio_postgres::NoTls)
+                        .await
+                        .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+                forward_messages(connection, tx);
+                client
+            }
+            Some(tls_config) => {
+                let tls = tokio_postgres_rustls::MakeRustlsConnect::new(tls_config);
+                let (client, connection) = tokio_postgres::connect(&conn_string, tls)
+                    .await
+                    .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
+                forward_messages(connection, tx);
+                client
+            }
+        };
+
+        Ok((
+            Self {
+                client,
+                channels: Vec::new(),
+            },
+            rx,
+        ))
+    }
+
+    /// Start listening on a channel (named exactly, as the server knows it)
+    pub async fn listen(&mut self, channel: &str) -> DbResult<()> {
+        if self.channels.iter().any(|c| c == channel) {
+            return Ok(());
+        }
+        self.run(&format!("LISTEN {}", quote_ident(channel)))
+            .await?;
+        self.channels.push(channel.to_string());
+        Ok(())
+    }
+
+    /// Stop listening on a channel
+    pub async fn unlisten(&mut self, channel: &str) -> DbResult<()> {
+        self.run(&format!("UNLISTEN {}", quote_ident(channel)))
+            .await?;
+        self.channels.retain(|c| c != channel);
+        Ok(())
+    }
+
+    /// Channels being listened to, in the order they were added
+    pub fn channels(&self) -> &[String] {
+        &self.channels
+    }
+
+    async fn run(&self, sql: &str) -> DbResult<()> {
+        self.client
+            .batch_execute(sql)
+            .await
+            .map_err(|e| DbError::QueryFailed {
+                message: e
+                    .as_db_error()
+                    .map_or_else(|| e.to_string(), |db| db.message().to_string()),
+                position: None,
+                code: None,
+            })
+    }
+}
+
+/// Drive the connection, passing its notifications on until it closes
+fn forward_messages<S, T>(
+    mut connection: Connection<S, T>,
+    tx: mpsc::UnboundedSender<ListenerEvent>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        loop {
+            match std::future::poll_fn(|cx| connection.poll_message(cx)).await {
+                Some(Ok(AsyncMessage::Notification(n))) => {
+                    let notification = Notification {
+                        channel: n.channel().to_string(),
+                        payload: n.payload().to_string(),
+                        process_id: n.process_id(),
+                    };
+                    if tx.send(ListenerEvent::Notification(notification)).is_err() {
+                        return;
+                    }
+                }
+                // Notices (e.g. from UNLISTEN of an unknown channel) aren't shown
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    let _ = tx.send(ListenerEvent::Closed(format!(
+                        "Listener connection lost: {}",
+                        e
+                    )));
+                    return;
+                }
+                // Closed because the listener was dropped
+                None => return,
+            }
+        }
+    });
+}
@@ -0,0 +1,565 @@
+//! Canned database for `vizgres --demo`
+//!
+//! A small shop schema (customers, products, orders) with generated rows,
+//! served through the `Database` trait so the TUI runs without a server.
+//! Queries are matched, not executed: a SELECT reading from one of the
+//! tables returns its rows, honouring a single `WHERE column = value`,
+//! LIMIT, OFFSET, and `count(*)`, while other conditions, ORDER BY, and
+//! joins are ignored.
+
+use std::time::{Duration, Instant};
+
+use crate::db::Database;
+use crate::db::schema::{
+    Column, ForeignKey, Function, Index, PaginatedVec, Schema, SchemaTree, ServerDatabase, Table,
+};
+use crate::db::types::{CellValue, ColumnDef, DataType, QueryResults, Row};
+use crate::error::{DbError, DbResult};
+
+/// The one schema the demo database has
+const SCHEMA: &str = "public";
+
+const FIRST_NAMES: &[&str] = &[
+    "Ada", "Grace", "Linus", "Margaret", "Ken", "Barbara", "Dennis", "Frances",
+];
+const LAST_NAMES: &[&str] = &["Lovelace", "Hopper", "Torvalds", "Hamilton", "Thompson"];
+const COUNTRIES: &[&str] = &["US", "DE", "FR", "JP", "BR", "IN", "GB", "CA"];
+/// Name, category, and price in cents
+const PRODUCTS: &[(&str, Option<&str>, i64)] = &[
+    ("Mechanical keyboard", Some("hardware"), 12900),
+    ("Ergonomic mouse", Some("hardware"), 4900),
+    ("27\" monitor", Some("hardware"), 32900),
+    ("USB-C dock", Some("hardware"), 18900),
+    ("Laptop stand", Some("accessories"), 3900),
+    ("Desk mat", Some("accessories"), 2400),
+    ("Cable kit", Some("accessories"), 1500),
+    ("SQL pocket guide", Some("books"), 1999),
+    ("Postgres internals", Some("books"), 4999),
+    ("Noise-cancelling headphones", Some("audio"), 24900),
+    ("Webcam", Some("video"), 8900),
+    ("Coffee mug", None, 1200),
+];
+const STATUSES: &[&str] = &["pending", "shipped", "delivered", "delivered", "cancelled"];
+const CUSTOMER_COUNT: usize = 40;
+const ORDER_COUNT: usize = 150;
+
+/// A table of the demo database with its rows
+struct DemoTable {
+    table: Table,
+    /// Which columns may hold NULL
+    nullable: Vec<bool>,
+    rows: Vec<Row>,
+}
+
+/// `Database` implementation answering from canned data
+pub struct MockProvider {
+    tables: Vec<DemoTable>,
+}
+
+impl MockProvider {
+    pub fn new() -> Self {
+        Self {
+            tables: vec![customers(), products(), orders()],
+        }
+    }
+
+    fn schema(&self) -> Schema {
+        let tables: Vec<Table> = self.tables.iter().map(|t| t.table.clone()).collect();
+        let indexes = self
+            .tables
+            .iter()
+            .map(|t| Index {
+                name: format!("{}_pkey", t.table.name),
+                columns: vec!["id".to_string()],
+                is_unique: true,
+                is_primary: true,
+                table_name: t.table.name.clone(),
+            })
+            .chain(std::iter::once(Index {
+                name: "orders_customer_id_idx".to_string(),
+                columns: vec!["customer_id".to_string()],
+                is_unique: false,
+                is_primary: false,
+                table_name: "orders".to_string(),
+            }))
+            .collect();
+        Schema {
+            tables: PaginatedVec::from_vec(tables),
+            indexes: PaginatedVec::from_vec(indexes),
+            loaded: true,
+            ..Schema::unloaded(SCHEMA.to_string())
+        }
+    }
+
+    fn tree(&self, schema: Schema) -> SchemaTree {
+        SchemaTree {
+            schemas: PaginatedVec::from_vec(vec![schema]),
+            extensions: Vec::new(),
+            databases: vec![ServerDatabase {
+                name: "demo".to_string(),
+                is_current: true,
+            }],
+        }
+    }
+
+    /// Answer `sql` from the canned rows
+    fn query(&self, sql: &str, max_rows: usize) -> DbResult<QueryResults> {
+        let start = Instant::now();
+        let query = DemoQuery::parse(sql).ok_or_else(|| DbError::QueryFailed {
+            message: "The demo database only answers SELECT ... FROM customers, products, \
+                      or orders"
+                .to_string(),
+            position: None,
+            code: None,
+        })?;
+        let demo = self
+            .tables
+            .iter()
+            .find(|t| t.table.name == query.table)
+            .ok_or_else(|| DbError::QueryFailed {
+                message: format!("relation \"{}\" does not exist", query.table),
+                position: None,
+                code: Some("42P01".to_string()),
+            })?;
+
+        let filter = match query.filter {
+            Some((ref column, ref value)) => {
+                let idx = demo
+                    .table
+                    .columns
+                    .iter()
+                    .position(|c| c.name == *column)
+                    .ok_or_else(|| DbError::QueryFailed {
+                        message: format!("column \"{}\" does not exist", column),
+                        position: None,
+                        code: Some("42703".to_string()),
+                    })?;
+                Some((idx, value.as_str()))
+            }
+            None => None,
+        };
+        let matching: Vec<&Row> = demo
+            .rows
+            .iter()
+            .filter(|row| {
+                filter.is_none_or(|(idx, value)| {
+                    row.values[idx].display_string(usize::MAX).to_lowercase() == value
+                })
+            })
+            .collect();
+
+        if query.count {
+            let column = ColumnDef {
+                name: "count".to_string(),
+                data_type: DataType::BigInt,
+                nullable: false,
+            };
+            let row = Row {
+                values: vec![CellValue::Integer(matching.len() as i64)],
+            };
+            return Ok(QueryResults::new(
+                vec![column],
+                vec![row],
+                start.elapsed(),
+                1,
+            ));
+        }
+
+        let columns = demo
+            .table
+            .columns
+            .iter()
+            .zip(&demo.nullable)
+            .map(|(c, &nullable)| ColumnDef {
+                name: c.name.clone(),
+                data_type: c.data_type.clone(),
+                nullable,
+            })
+            .collect();
+        let mut rows: Vec<Row> = matching
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        let truncated = max_rows > 0 && rows.len() > max_rows;
+        if truncated {
+            rows.truncate(max_rows);
+        }
+        let row_count = rows.len();
+        // Pretend the rows took a moment, so timings look plausible
+        let elapsed = start.elapsed() + Duration::from_micros(300 + row_count as u64 * 4);
+        Ok(QueryResults::new_truncated(
+            columns, rows, elapsed, row_count, truncated,
+        ))
+    }
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Database for MockProvider {
+    async fn execute_query(
+        &self,
+        sql: &str,
+        _timeout_ms: u64,
+        max_rows: usize,
+    ) -> DbResult<QueryResults> {
+        self.query(sql, max_rows)
+    }
+
+    async fn get_schema(&self, _limit: usize) -> DbResult<SchemaTree> {
+        Ok(self.tree(self.schema()))
+    }
+
+    async fn list_schemas(&self) -> DbResult<SchemaTree> {
+        Ok(self.tree(self.schema()))
+    }
+
+    async fn load_schema(&self, schema_name: &str, _limit: usize) -> DbResult<Schema> {
+        if schema_name == SCHEMA {
+            Ok(self.schema())
+        } else {
+            Err(DbError::SchemaLoadFailed(format!(
+                "schema \"{}\" does not exist",
+                schema_name
+            )))
+        }
+    }
+
+    async fn search_schema(&self, pattern: &str) -> DbResult<SchemaTree> {
+        let pattern = pattern.to_lowercase();
+        let mut schema = self.schema();
+        schema
+            .tables
+            .items
+            .retain(|t| t.name.to_lowercase().contains(&pattern));
+        schema.tables.total_count = schema.tables.items.len();
+        schema
+            .indexes
+            .items
+            .retain(|i| i.name.to_lowercase().contains(&pattern));
+        schema.indexes.total_count = schema.indexes.items.len();
+        Ok(self.tree(schema))
+    }
+
+    async fn load_more_tables(&self, _: &str, _: usize, _: usize) -> DbResult<Vec<Table>> {
+        Ok(Vec::new())
+    }
+
+    async fn load_more_views(&self, _: &str, _: usize, _: usize) -> DbResult<Vec<Table>> {
+        Ok(Vec::new())
+    }
+
+    async fn load_more_functions(&self, _: &str, _: usize, _: usize) -> DbResult<Vec<Function>> {
+        Ok(Vec::new())
+    }
+
+    async fn load_more_indexes(&self, _: &str, _: usize, _: usize) -> DbResult<Vec<Index>> {
+        Ok(Vec::new())
+    }
+}
+
+/// What a demo query asks for
+#[derive(Debug, PartialEq, Eq)]
+struct DemoQuery {
+    table: String,
+    count: bool,
+    /// Column and (lowercased, unquoted) value of `WHERE column = value`
+    filter: Option<(String, String)>,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+impl DemoQuery {
+    /// Pick the table, filter, LIMIT, and OFFSET out of a SELECT
+    fn parse(sql: &str) -> Option<Self> {
+        let lower = sql.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| c.is_whitespace() || c == ';')
+            .filter(|w| !w.is_empty())
+            .collect();
+        if words.first() != Some(&"select") {
+            return None;
+        }
+        let from = words.iter().position(|&w| w == "from")?;
+        let table = words
+            .get(from + 1)?
+            .rsplit('.')
+            .next()?
+            .trim_matches(|c| c == '"' || c == ')')
+            .to_string();
+        let number_after = |keyword: &str| {
+            words
+                .iter()
+                .position(|&w| w == keyword)
+                .and_then(|i| words.get(i + 1))
+                .and_then(|w| w.parse::<usize>().ok())
+        };
+        let unquote = |w: &str| w.trim_matches(|c| c == '"' || c == '\'').to_string();
+        let filter = words
+            .iter()
+            .position(|&w| w == "where")
+            .and_then(|i| words.get(i + 1..i + 4))
+            .filter(|cond| cond[1] == "=")
+            .map(|cond| (unquote(cond[0]), unquote(cond[2])));
+        let select_list = words[1..from].concat();
+        Some(Self {
+            table,
+            count: select_list == "count(*)",
+            filter,
+            limit: number_after("limit"),
+            offset: number_after("offset").unwrap_or(0),
+        })
+    }
+}
+
+fn column(name: &str, data_type: DataType) -> Column {
+    Column {
+        name: name.to_string(),
+        data_type,
+        is_primary_key: name == "id",
+        foreign_key: None,
+    }
+}
+
+fn references(mut column: Column, table: &str) -> Column {
+    column.foreign_key = Some(ForeignKey {
+        target_table: table.to_string(),
+        target_column: "id".to_string(),
+    });
+    column
+}
+
+fn demo_table(name: &str, columns: Vec<Column>, nullable: Vec<bool>, rows: Vec<Row>) -> DemoTable {
+    DemoTable {
+        table: Table {
+            name: name.to_string(),
+            columns,
+            row_count: Some(rows.len() as i64),
+            size_bytes: Some(8192 * (1 + rows.len() as i64 / 50)),
+            select_denied: false,
+        },
+        nullable,
+        rows,
+    }
+}
+
+/// Price in cents as a numeric's text
+fn money(cents: i64) -> CellValue {
+    CellValue::Text(format!("{}.{:02}", cents / 100, cents % 100))
+}
+
+fn customers() -> DemoTable {
+    let rows = (0..CUSTOMER_COUNT)
+        .map(|i| {
+            let first = FIRST_NAMES[i % FIRST_NAMES.len()];
+            let last = LAST_NAMES[(i / FIRST_NAMES.len()) % LAST_NAMES.len()];
+            let country = if i % 13 == 12 {
+                CellValue::Null
+            } else {
+                CellValue::Text(COUNTRIES[i % COUNTRIES.len()].to_string())
+            };
+            Row {
+                values: vec![
+                    CellValue::Integer(i as i64 + 1),
+                    CellValue::Text(format!("{} {}", first, last)),
+                    CellValue::Text(format!(
+                        "{}.{}@example.com",
+                        first.to_lowercase(),
+                        last.to_lowercase()
+                    )),
+                    country,
+                    CellValue::DateTime(format!(
+                        "2024-{:02}-{:02} 09:{:02}:00+00",
+                        i % 12 + 1,
+                        i % 28 + 1,
+                        i % 60
+                    )),
+                    CellValue::Boolean(i % 7 != 3),
+                ],
+            }
+        })
+        .collect();
+    demo_table(
+        "customers",
+        vec![
+            column("id", DataType::Integer),
+            column("name", DataType::Text),
+            column("email", DataType::Text),
+            column("country", DataType::Char(Some(2))),
+            column("created_at", DataType::TimestampTz),
+            column("active", DataType::Boolean),
+        ],
+        vec![false, false, false, true, false, false],
+        rows,
+    )
+}
+
+fn products() -> DemoTable {
+    let rows = PRODUCTS
+        .iter()
+        .enumerate()
+        .map(|(i, &(name, category, cents))| Row {
+            values: vec![
+                CellValue::Integer(i as i64 + 1),
+                CellValue::Text(name.to_string()),
+                category.map_or(CellValue::Null, |c| CellValue::Text(c.to_string())),
+                money(cents),
+                CellValue::Integer((i as i64 * 37) % 120),
+            ],
+        })
+        .collect();
+    demo_table(
+        "products",
+        vec![
+            column("id", DataType::Integer),
+            column("name", DataType::Text),
+            column("category", DataType::Text),
+            column("price", DataType::Numeric),
+            column("stock", DataType::Integer),
+        ],
+        vec![false, false, true, false, false],
+        rows,
+    )
+}
+
+fn orders() -> DemoTable {
+    let rows = (0..ORDER_COUNT)
+        .map(|i| {
+            let product = (i * 5) % PRODUCTS.len();
+            let quantity = i % 4 + 1;
+            Row {
+                values: vec![
+                    CellValue::Integer(i as i64 + 1),
+                    CellValue::Integer(((i * 7) % CUSTOMER_COUNT) as i64 + 1),
+                    CellValue::Integer(product as i64 + 1),
+                    CellValue::Integer(quantity as i64),
+                    money(PRODUCTS[product].2 * quantity as i64),
+                    CellValue::Text(STATUSES[i % STATUSES.len()].to_string()),
+                    CellValue::DateTime(format!(
+                        "2025-{:02}-{:02} {:02}:{:02}:00+00",
+                        i % 12 + 1,
+                        i % 28 + 1,
+                        (i * 3) % 24,
+                        (i * 11) % 60
+                    )),
+                ],
+            }
+        })
+        .collect();
+    demo_table(
+        "orders",
+        vec![
+            column("id", DataType::Integer),
+            references(column("customer_id", DataType::Integer), "customers"),
+            references(column("product_id", DataType::Integer), "products"),
+            column("quantity", DataType::Integer),
+            column("total", DataType::Numeric),
+            column("status", DataType::Text),
+            column("ordered_at", DataType::TimestampTz),
+        ],
+        vec![false; 7],
+        rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_demo_query() {
+        assert_eq!(
+            DemoQuery::parse("SELECT * FROM \"public\".\"orders\" LIMIT 101 OFFSET 200"),
+            Some(DemoQuery {
+                table: "orders".to_string(),
+                count: false,
+                filter: None,
+                limit: Some(101),
+                offset: 200,
+            })
+        );
+        let count = DemoQuery::parse("select COUNT(*)\nfrom customers;").unwrap();
+        assert!(count.count);
+        assert_eq!(count.table, "customers");
+        assert_eq!(DemoQuery::parse("UPDATE orders SET x = 1"), None);
+        assert_eq!(DemoQuery::parse("SELECT 1"), None);
+        let lookup = DemoQuery::parse("SELECT * FROM customers WHERE \"id\" = '7'").unwrap();
+        assert_eq!(lookup.filter, Some(("id".to_string(), "7".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_queries_answer_from_canned_rows() {
+        let db = MockProvider::new();
+        let page = db
+            .execute_query("SELECT * FROM orders LIMIT 10 OFFSET 145", 0, 0)
+            .await
+            .unwrap();
+        assert_eq!(page.rows.len(), 5);
+        assert_eq!(page.columns.len(), 7);
+        assert!(matches!(page.rows[0].values[0], CellValue::Integer(146)));
+
+        let capped = db
+            .execute_query("SELECT * FROM orders", 0, 20)
+            .await
+            .unwrap();
+        assert_eq!(capped.rows.len(), 20);
+        assert!(capped.truncated);
+
+        let count = db
+            .execute_query("SELECT count(*) FROM customers", 0, 0)
+            .await
+            .unwrap();
+        assert!(matches!(
+            count.rows[0].values[0],
+            CellValue::Integer(n) if n == CUSTOMER_COUNT as i64
+        ));
+
+        let shipped = db
+            .execute_query("SELECT count(*) FROM orders WHERE status = 'SHIPPED'", 0, 0)
+            .await
+            .unwrap();
+        assert!(matches!(
+            shipped.rows[0].values[0],
+            CellValue::Integer(n) if n == (ORDER_COUNT / STATUSES.len()) as i64
+        ));
+
+        let err = db.execute_query("SELECT * FROM nope", 0, 0).await;
+        assert!(
+            err.unwrap_err()
+                .to_string()
+                .contains("\"nope\" does not exist")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_schema_matches_rows() {
+        let db = MockProvider::new();
+        let tree = db.get_schema(0).await.unwrap();
+        let schema = tree.schemas.first().unwrap();
+        assert!(schema.loaded);
+        assert_eq!(schema.tables.len(), 3);
+        for demo in &db.tables {
+            assert!(
+                demo.rows
+                    .iter()
+                    .all(|r| r.values.len() == demo.table.columns.len())
+            );
+            assert_eq!(demo.nullable.len(), demo.table.columns.len());
+        }
+        let found = db.search_schema("ORD").await.unwrap();
+        let names: Vec<&str> = found
+            .schemas
+            .first()
+            .unwrap()
+            .tables
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["orders"]);
+    }
+}
@@ -1,16 +1,36 @@
 //! Database layer
 //!
 //! PostgreSQL connection, query execution, and schema introspection.
+//! CockroachDB is served by `PostgresProvider` as a dialect flag
+//! (`db::cockroach`); `MockProvider` is the only other `Database`.
 
+pub mod activity;
+pub mod cockroach;
+pub mod ddl;
+pub mod health;
+pub mod listener;
+pub mod mock;
+pub mod params;
 pub mod postgres;
+pub mod provenance;
+pub mod replication;
 pub mod schema;
 pub mod sql_limit;
+pub mod sqlstate;
+pub mod tls;
+pub mod transaction;
 pub mod types;
 
+pub use activity::Session;
+pub use health::{Health, ServerSummary};
+pub use listener::{Listener, ListenerEvent, Notification};
+pub use mock::MockProvider;
 pub use postgres::PostgresProvider;
+pub use replication::Replication;
+pub use transaction::TransactionState;
 pub use types::QueryResults;
 
-use crate::db::schema::{Function, Index, SchemaTree, Table};
+use crate::db::schema::{Function, Index, Schema, SchemaTree, Table};
 use crate::error::DbResult;
 
 /// Trait abstracting database operations for testability.
@@ -35,6 +55,18 @@ pub trait Database: Send + Sync {
         limit: usize,
     ) -> impl std::future::Future<Output = DbResult<SchemaTree>> + Send;
 
+    /// List schema names without their objects (`Schema::loaded` is false),
+    /// so the tree can show them before anything else is introspected.
+    fn list_schemas(&self) -> impl std::future::Future<Output = DbResult<SchemaTree>> + Send;
+
+    /// Load one schema's objects with optional limit per category. Pass 0
+    /// for unlimited.
+    fn load_schema(
+        &self,
+        schema_name: &str,
+        limit: usize,
+    ) -> impl std::future::Future<Output = DbResult<Schema>> + Send;
+
     /// Search schema objects by name pattern (case-insensitive substring match).
     /// Returns a SchemaTree containing only matching objects and their containers.
     fn search_schema(
@@ -79,4 +111,5 @@ pub trait Database: Send + Sync {
 const _: fn() = || {
     fn assert_impl<T: Database + Send + Sync>() {}
     assert_impl::<PostgresProvider>();
+    assert_impl::<MockProvider>();
 };
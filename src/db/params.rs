@@ -0,0 +1,196 @@
+//! Query parameter placeholders
+//!
+//! Finds `$1`-style and `:name` placeholders outside strings, comments, and
+//! quoted identifiers, so the app can prompt for their values and the query
+//! runs with real bind parameters. Named placeholders are rewritten to
+//! positional ones in order of first appearance; a name used twice binds
+//! the same parameter.
+
+/// Placeholders found in a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholders {
+    /// One label per bind parameter, in parameter order (`$1`, `$2`, ... or
+    /// `:name`). Empty when the query has no placeholders.
+    pub names: Vec<String>,
+    /// The query with named placeholders rewritten to `$n`
+    pub sql: String,
+}
+
+impl Placeholders {
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Scan `sql` for placeholders.
+///
+/// `::type` casts, `:=`, and slices like `arr[1:n]` are not placeholders,
+/// and `PREPARE` statements are left alone since their `$n` belong to the
+/// prepared statement. Fails when `$n` and `:name` styles are mixed.
+pub fn find_placeholders(sql: &str) -> Result<Placeholders, String> {
+    let none = || Placeholders {
+        names: Vec::new(),
+        sql: sql.to_string(),
+    };
+    let first_word = sql.split_whitespace().next().unwrap_or("");
+    if first_word.eq_ignore_ascii_case("PREPARE") {
+        return Ok(none());
+    }
+
+    let chars: Vec<char> = sql.chars().collect();
+    let len = chars.len();
+    let mut out = String::with_capacity(sql.len());
+    let mut max_positional = 0;
+    let mut named: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < len {
+        let ch = chars[i];
+        let start = i;
+
+        if ch == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < len && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if ch == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            let mut nest = 1;
+            while i < len && nest > 0 {
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    nest += 1;
+                    i += 2;
+                } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    nest -= 1;
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+        } else if ch == '\'' || ch == '"' {
+            // String literal or quoted identifier; doubled quotes escape
+            i += 1;
+            while i < len {
+                if chars[i] == ch {
+                    i += 1;
+                    if chars.get(i) != Some(&ch) {
+                        break;
+                    }
+                }
+                i += 1;
+            }
+        } else if ch == '$' && chars.get(i + 1).is_some_and(char::is_ascii_digit) {
+            i += 1;
+            while i < len && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let n: usize = chars[start + 1..i]
+                .iter()
+                .collect::<String>()
+                .parse()
+                .map_err(|_| "Parameter number out of range".to_string())?;
+            max_positional = max_positional.max(n);
+        } else if ch == '$'
+            && let Some(tag_len) = super::sql_limit::find_dollar_tag(&chars, i)
+        {
+            // Dollar-quoted body: copy through the closing tag
+            let tag = &chars[i..i + tag_len];
+            i += tag_len;
+            while i < len && !chars[i..].starts_with(tag) {
+                i += 1;
+            }
+            i = (i + tag_len).min(len);
+        } else if ch == ':' && chars.get(i + 1) == Some(&':') {
+            i += 2;
+        } else if ch == ':'
+            && chars
+                .get(i + 1)
+                .is_some_and(|c| c.is_alphabetic() || *c == '_')
+            && !(i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_'))
+        {
+            i += 1;
+            while i < len && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let name: String = chars[start + 1..i].iter().collect();
+            let position = match named.iter().position(|n| *n == name) {
+                Some(p) => p,
+                None => {
+                    named.push(name);
+                    named.len() - 1
+                }
+            };
+            out.push_str(&format!("${}", position + 1));
+            continue;
+        } else {
+            i += 1;
+        }
+        out.extend(&chars[start..i]);
+    }
+
+    match (max_positional, named.is_empty()) {
+        (0, true) => Ok(none()),
+        (0, false) => Ok(Placeholders {
+            names: named.into_iter().map(|n| format!(":{}", n)).collect(),
+            sql: out,
+        }),
+        (n, true) => Ok(Placeholders {
+            names: (1..=n).map(|i| format!("${}", i)).collect(),
+            sql: out,
+        }),
+        _ => Err("Can't mix $n and :name placeholders in one query".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_placeholders() {
+        let p = find_placeholders("SELECT * FROM users").unwrap();
+        assert!(p.is_empty());
+        assert_eq!(p.sql, "SELECT * FROM users");
+    }
+
+    #[test]
+    fn test_positional_placeholders() {
+        let p = find_placeholders("SELECT * FROM t WHERE a = $2 AND b = $1").unwrap();
+        assert_eq!(p.names, vec!["$1", "$2"]);
+        assert_eq!(p.sql, "SELECT * FROM t WHERE a = $2 AND b = $1");
+    }
+
+    #[test]
+    fn test_named_placeholders_rewritten_in_order() {
+        let p =
+            find_placeholders("SELECT * FROM t WHERE a = :id OR b > :min_age OR c = :id").unwrap();
+        assert_eq!(p.names, vec![":id", ":min_age"]);
+        assert_eq!(p.sql, "SELECT * FROM t WHERE a = $1 OR b > $2 OR c = $1");
+    }
+
+    #[test]
+    fn test_casts_slices_and_quoted_text_are_not_placeholders() {
+        let sql = "SELECT x::int, arr[1:n], ':nope', \"a:b\", $$ :body $1 $$, \
+                   $fn$ :tag $fn$ -- :comment $1\n/* :block */ FROM t";
+        let p = find_placeholders(sql).unwrap();
+        assert!(p.is_empty(), "{:?}", p.names);
+        assert_eq!(p.sql, sql);
+    }
+
+    #[test]
+    fn test_named_after_cast_and_in_parens() {
+        let p = find_placeholders("SELECT (:a)::text, :b||'x'").unwrap();
+        assert_eq!(p.names, vec![":a", ":b"]);
+        assert_eq!(p.sql, "SELECT ($1)::text, $2||'x'");
+    }
+
+    #[test]
+    fn test_prepare_is_left_alone() {
+        let p = find_placeholders("PREPARE q(int) AS SELECT $1").unwrap();
+        assert!(p.is_empty());
+    }
+
+    #[test]
+    fn test_mixed_styles_rejected() {
+        assert!(find_placeholders("SELECT $1, :name").is_err());
+    }
+}
@@ -1,21 +1,41 @@
 //! PostgreSQL database provider
 //!
-//! Concrete implementation using tokio-postgres.
+//! Concrete implementation using tokio-postgres. Also serves CockroachDB,
+//! switching to its dialect (`db::cockroach`) where the SQL differs.
 
 use crate::config::ConnectionConfig;
-use crate::config::connections::SslMode;
+use crate::config::connections::Backend;
 use crate::db::Database;
+use crate::db::activity::{self, Session};
+use crate::db::cockroach;
+use crate::db::ddl::{DdlColumn, DdlTarget, TableDdl, view_ddl};
+use crate::db::health::{
+    self, Connections, DatabaseStats, Health, Replica, ServerQuery, ServerSummary,
+};
+use crate::db::params::find_placeholders;
+use crate::db::provenance::{CellUpdate, ResultSource, SourceColumn, is_simple_select};
+use crate::db::replication::{self, Publication, Replication, Subscription};
 use crate::db::schema::{
-    Column, ForeignKey, Function, Index, PaginatedVec, Schema, SchemaTree, Table,
+    Column, Domain, EnumType, Extension, ForeignKey, Function, Index, InsertColumn, PaginatedVec,
+    Schema, SchemaTree, Sequence, ServerDatabase, Table,
 };
-use crate::db::types::{CellValue, ColumnDef, DataType, QueryResults, Row};
+use crate::db::tls::make_tls_config;
+use crate::db::transaction::{TransactionState, TransactionTracker};
+use crate::db::types::{CellValue, ColumnDef, CommandTag, DataType, QueryResults, Row};
 use crate::error::{DbError, DbResult};
+use crate::sql::{quote_ident, quote_qualified};
 use rust_decimal::Decimal;
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::{Mutex, mpsc};
 use tokio::time::{Duration, timeout};
-use tokio_postgres::Client;
-use tokio_postgres::types::Type;
+use tokio_postgres::types::{Format, IsNull, ToSql, Type, to_sql_checked};
+use tokio_postgres::{Client, Statement};
+
+/// How long the distinct-values lookup behind value completion may run; it
+/// holds the control connection that cancels go through
+const COLUMN_VALUES_TIMEOUT_MS: u64 = 1000;
 
 /// Result of a cancel operation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -36,8 +56,9 @@ pub struct PostgresProvider {
     client: Client,
     /// Token for cancelling in-flight queries (fallback mechanism)
     cancel_token: tokio_postgres::CancelToken,
-    /// SSL mode (needed to cancel over the right transport)
-    ssl_mode: SslMode,
+    /// TLS settings, None when SSL is disabled (needed to cancel over the
+    /// right transport)
+    tls_config: Option<rustls::ClientConfig>,
     /// Connection string for establishing control connection (no statement_timeout)
     control_conn_string: String,
     /// Control connection for pg_cancel_backend() (lazy initialized)
@@ -46,6 +67,14 @@ pub struct PostgresProvider {
     /// Backend PID of the main connection (stable for connection lifetime)
     /// Fetched once at connect time, used for pg_cancel_backend/pg_terminate_backend
     backend_pid: i32,
+    /// Transaction block state, updated after every user query
+    transaction: TransactionTracker,
+    /// SQL dialect of the server, from the connection URL scheme. CockroachDB
+    /// is this provider with a few queries swapped, not a `Database` of its own.
+    dialect: Backend,
+    /// Run user queries over the simple query protocol (set once prepared
+    /// statements turn out not to survive a transaction-pooling proxy)
+    simple_protocol: AtomicBool,
 }
 
 impl PostgresProvider {
@@ -64,8 +93,9 @@ impl PostgresProvider {
         let conn_string = config.connection_string_with_password(statement_timeout_ms);
         let (conn_err_tx, conn_err_rx) = mpsc::unbounded_channel();
 
-        let client = match config.ssl_mode {
-            SslMode::Disable => {
+        let tls_config = make_tls_config(config)?;
+        let client = match tls_config.clone() {
+            None => {
                 let (client, connection) =
                     tokio_postgres::connect(&conn_string, tokio_postgres::NoTls)
                         .await
@@ -77,8 +107,7 @@ impl PostgresProvider {
                 });
                 client
             }
-            SslMode::Prefer | SslMode::Require => {
-                let tls_config = make_tls_config();
+            Some(tls_config) => {
                 let tls = tokio_postgres_rustls::MakeRustlsConnect::new(tls_config);
                 let (client, connection) = tokio_postgres::connect(&conn_string, tls)
                     .await
@@ -100,7 +129,6 @@ impl PostgresProvider {
             .unwrap_or(0);
 
         let cancel_token = client.cancel_token();
-        let ssl_mode = config.ssl_mode;
         // Control connection doesn't need statement_timeout
         let control_conn_string = config.connection_string_with_password(0);
 
@@ -108,10 +136,13 @@ impl PostgresProvider {
             Self {
                 client,
                 cancel_token,
-                ssl_mode,
+                tls_config,
                 control_conn_string,
                 control_conn: Mutex::new(None),
                 backend_pid: pid,
+                transaction: TransactionTracker::new(),
+                dialect: config.backend,
+                simple_protocol: AtomicBool::new(config.prefer_simple_protocol),
             },
             conn_err_rx,
         ))
@@ -128,8 +159,8 @@ impl PostgresProvider {
         }
 
         // Establish control connection with same SSL mode
-        let client = match self.ssl_mode {
-            SslMode::Disable => {
+        let client = match self.tls_config.clone() {
+            None => {
                 let (client, connection) =
                     tokio_postgres::connect(&self.control_conn_string, tokio_postgres::NoTls)
                         .await
@@ -143,8 +174,7 @@ impl PostgresProvider {
                 });
                 client
             }
-            SslMode::Prefer | SslMode::Require => {
-                let tls_config = make_tls_config();
+            Some(tls_config) => {
                 let tls = tokio_postgres_rustls::MakeRustlsConnect::new(tls_config);
                 let (client, connection) = tokio_postgres::connect(&self.control_conn_string, tls)
                     .await
@@ -176,12 +206,14 @@ impl PostgresProvider {
                 .map_err(|e| DbError::QueryFailed {
                     message: format!("pg_cancel_backend failed: {}", e),
                     position: None,
+                    code: None,
                 })?;
             Ok(row.get(0))
         } else {
             Err(DbError::QueryFailed {
                 message: "Control connection not available".to_string(),
                 position: None,
+                code: None,
             })
         }
     }
@@ -201,12 +233,14 @@ impl PostgresProvider {
                 .map_err(|e| DbError::QueryFailed {
                     message: format!("pg_terminate_backend failed: {}", e),
                     position: None,
+                    code: None,
                 })?;
             Ok(row.get(0))
         } else {
             Err(DbError::QueryFailed {
                 message: "Control connection not available".to_string(),
                 position: None,
+                code: None,
             })
         }
     }
@@ -223,9 +257,10 @@ impl PostgresProvider {
     /// Falls back to CancelToken if control connection fails.
     pub async fn cancel_query_enhanced(&self, terminate: bool) -> DbResult<CancelResult> {
         let pid = self.backend_pid;
-        if pid == 0 {
-            // PID unknown — skip pg_cancel_backend/pg_terminate_backend and fall
-            // back to the CancelToken protocol which doesn't require a PID.
+        if pid == 0 || self.dialect == Backend::Cockroach {
+            // PID unknown (or no pg_cancel_backend, on CockroachDB) — skip
+            // pg_cancel_backend/pg_terminate_backend and fall back to the
+            // CancelToken protocol which doesn't require a PID.
             let _ = self.cancel_query().await;
             return Ok(CancelResult::FellBackToToken);
         }
@@ -257,6 +292,272 @@ impl PostgresProvider {
         }
     }
 
+    /// The server's other client sessions, over the control connection so
+    /// they can be listed while this connection's query runs
+    pub async fn list_sessions(&self) -> DbResult<Vec<Session>> {
+        self.ensure_control_connection().await?;
+        let guard = self.control_conn.lock().await;
+        let Some(ref ctrl) = *guard else {
+            return Err(DbError::QueryFailed {
+                message: "Control connection not available".to_string(),
+                position: None,
+                code: None,
+            });
+        };
+        let rows = ctrl
+            .query(activity::SESSIONS_SQL, &[])
+            .await
+            .map_err(extract_query_error)?;
+        Ok(rows
+            .iter()
+            .map(|row| Session {
+                pid: row.get(0),
+                user: row.get(1),
+                database: row.get(2),
+                application: row.get::<_, Option<String>>(3).filter(|a| !a.is_empty()),
+                client: row.get(4),
+                state: row.get(5),
+                wait_event: Session::wait(row.get(6), row.get(7)),
+                duration: Session::duration_from_secs(row.get(8)),
+                query: row.get::<_, Option<String>>(9).filter(|q| !q.is_empty()),
+            })
+            .collect())
+    }
+
+    /// Up to `limit + 1` distinct values of a column as text, for
+    /// completing `column = '` (None when there are more than `limit`).
+    /// Runs on the control connection with a short statement timeout, so a
+    /// large table can't hold it up.
+    pub async fn column_values(
+        &self,
+        schema: &str,
+        table: &str,
+        column: &str,
+        limit: usize,
+    ) -> DbResult<Option<Vec<String>>> {
+        use tokio_postgres::SimpleQueryMessage;
+
+        self.ensure_control_connection().await?;
+        let guard = self.control_conn.lock().await;
+        let Some(ref ctrl) = *guard else {
+            return Err(DbError::QueryFailed {
+                message: "Control connection not available".to_string(),
+                position: None,
+                code: None,
+            });
+        };
+        let column = quote_ident(column);
+        let sql = format!(
+            "SET LOCAL statement_timeout = {};
+             SELECT DISTINCT {col}::text FROM {} WHERE {col} IS NOT NULL ORDER BY 1 LIMIT {}",
+            COLUMN_VALUES_TIMEOUT_MS,
+            quote_qualified(schema, table),
+            limit + 1,
+            col = column,
+        );
+        let values: Vec<String> = ctrl
+            .simple_query(&sql)
+            .await
+            .map_err(extract_query_error)?
+            .iter()
+            .filter_map(|message| match message {
+                SimpleQueryMessage::Row(row) => row.get(0).map(str::to_string),
+                _ => None,
+            })
+            .collect();
+        Ok((values.len() <= limit).then_some(values))
+    }
+
+    /// A snapshot of the server's health for `/dashboard`, over the control
+    /// connection
+    pub async fn server_health(&self) -> DbResult<Health> {
+        self.ensure_control_connection().await?;
+        let guard = self.control_conn.lock().await;
+        let Some(ref ctrl) = *guard else {
+            return Err(DbError::QueryFailed {
+                message: "Control connection not available".to_string(),
+                position: None,
+                code: None,
+            });
+        };
+        let query = |sql| async move { ctrl.query(sql, &[]).await.map_err(extract_query_error) };
+
+        let row = ctrl
+            .query_one(health::CONNECTIONS_SQL, &[])
+            .await
+            .map_err(extract_query_error)?;
+        let connections = Connections {
+            active: row.get(0),
+            idle: row.get(1),
+            idle_in_transaction: row.get(2),
+            total: row.get(3),
+            max: row.get(4),
+        };
+        let database = query(health::DATABASE_SQL)
+            .await?
+            .first()
+            .map(database_stats);
+        let row = ctrl
+            .query_one(health::RECOVERY_SQL, &[])
+            .await
+            .map_err(extract_query_error)?;
+        let (standby, replay_lag) = recovery(&row);
+        let replicas = query(health::REPLICATION_SQL)
+            .await?
+            .iter()
+            .map(|row| Replica {
+                name: row.get::<_, Option<String>>(0).filter(|n| !n.is_empty()),
+                client: row.get(1),
+                state: row.get(2),
+                lag: Session::duration_from_secs(row.get(3)),
+                behind_bytes: row.get(4),
+            })
+            .collect();
+        let longest = query(health::LONGEST_SQL)
+            .await?
+            .iter()
+            .map(server_query)
+            .collect();
+        let lock_waits = query(health::LOCK_WAITS_SQL)
+            .await?
+            .iter()
+            .map(server_query)
+            .collect();
+        Ok(Health {
+            connections,
+            database,
+            standby,
+            replay_lag,
+            replicas,
+            longest,
+            lock_waits,
+        })
+    }
+
+    /// The first-glance checks shown on connect: version, round-trip
+    /// latency, primary or standby, cache hit ratio, and the oldest open
+    /// transaction
+    pub async fn server_summary(&self) -> DbResult<ServerSummary> {
+        self.ensure_control_connection().await?;
+        let guard = self.control_conn.lock().await;
+        let Some(ref ctrl) = *guard else {
+            return Err(DbError::QueryFailed {
+                message: "Control connection not available".to_string(),
+                position: None,
+                code: None,
+            });
+        };
+        let query = |sql| async move { ctrl.query(sql, &[]).await.map_err(extract_query_error) };
+
+        let start = std::time::Instant::now();
+        ctrl.simple_query("SELECT 1")
+            .await
+            .map_err(extract_query_error)?;
+        let latency = start.elapsed();
+        let version = ctrl
+            .query_one("SELECT current_setting('server_version')", &[])
+            .await
+            .map_err(extract_query_error)?
+            .get(0);
+        let row = ctrl
+            .query_one(health::RECOVERY_SQL, &[])
+            .await
+            .map_err(extract_query_error)?;
+        let (standby, replay_lag) = recovery(&row);
+        let database = query(health::DATABASE_SQL)
+            .await?
+            .first()
+            .map(database_stats);
+        let oldest_transaction = query(health::OLDEST_TRANSACTION_SQL)
+            .await?
+            .first()
+            .map(server_query);
+        Ok(ServerSummary {
+            version,
+            latency,
+            standby,
+            replay_lag,
+            database,
+            oldest_transaction,
+        })
+    }
+
+    /// The current database's publications and subscriptions for
+    /// `/replication`, over the control connection
+    pub async fn replication(&self) -> DbResult<Replication> {
+        self.ensure_control_connection().await?;
+        let guard = self.control_conn.lock().await;
+        let Some(ref ctrl) = *guard else {
+            return Err(DbError::QueryFailed {
+                message: "Control connection not available".to_string(),
+                position: None,
+                code: None,
+            });
+        };
+        let query = |sql| async move { ctrl.query(sql, &[]).await.map_err(extract_query_error) };
+
+        let mut publications: Vec<Publication> = query(replication::PUBLICATIONS_SQL)
+            .await?
+            .iter()
+            .map(|row| Publication {
+                name: row.get(0),
+                owner: row.get(1),
+                all_tables: row.get(2),
+                operations: Publication::operations([
+                    row.get(3),
+                    row.get(4),
+                    row.get(5),
+                    row.get(6),
+                ]),
+                tables: Vec::new(),
+            })
+            .collect();
+        for row in query(replication::PUBLICATION_TABLES_SQL).await? {
+            let name: String = row.get(0);
+            if let Some(publication) = publications
+                .iter_mut()
+                .find(|p| p.name == name && !p.all_tables)
+            {
+                publication.tables.push(row.get(1));
+            }
+        }
+        let subscriptions = query(replication::SUBSCRIPTIONS_SQL)
+            .await?
+            .iter()
+            .map(|row| Subscription {
+                name: row.get(0),
+                owner: row.get(1),
+                enabled: row.get(2),
+                publications: row.get(3),
+                worker_pid: row.get(4),
+                received_lsn: row.get(5),
+                last_message: Session::duration_from_secs(row.get(6)),
+                syncing_tables: row.get(7),
+            })
+            .collect();
+        Ok(Replication {
+            publications,
+            subscriptions,
+        })
+    }
+
+    /// Cancel another session's query (`pg_cancel_backend`) or end the
+    /// session (`pg_terminate_backend`). Returns false when the backend
+    /// was already gone.
+    pub async fn signal_backend(&self, pid: i32, terminate: bool) -> DbResult<bool> {
+        if terminate {
+            self.terminate_via_backend(pid).await
+        } else {
+            self.cancel_via_backend(pid).await
+        }
+    }
+
+    /// Whether a schema is internal to the server and kept out of the tree
+    /// (beyond the `pg_%` and `information_schema` ones every query skips)
+    fn hides_schema(&self, name: &str) -> bool {
+        self.dialect == Backend::Cockroach && cockroach::is_internal_schema(name)
+    }
+
     /// Get the backend PID of this connection (stable for its lifetime)
     pub fn current_backend_pid(&self) -> i32 {
         self.backend_pid
@@ -264,16 +565,17 @@ impl PostgresProvider {
 
     /// Send a cancel request for the currently running query.
     pub async fn cancel_query(&self) -> DbResult<()> {
-        match self.ssl_mode {
-            SslMode::Disable => self.cancel_token.cancel_query(tokio_postgres::NoTls).await,
-            SslMode::Prefer | SslMode::Require => {
-                let tls = tokio_postgres_rustls::MakeRustlsConnect::new(make_tls_config());
+        match self.tls_config.clone() {
+            None => self.cancel_token.cancel_query(tokio_postgres::NoTls).await,
+            Some(tls_config) => {
+                let tls = tokio_postgres_rustls::MakeRustlsConnect::new(tls_config);
                 self.cancel_token.cancel_query(tls).await
             }
         }
         .map_err(|e| crate::error::DbError::QueryFailed {
             message: format!("Cancel failed: {}", e),
             position: None,
+            code: None,
         })
     }
 
@@ -285,36 +587,145 @@ impl PostgresProvider {
     ///
     /// Server-side `statement_timeout` is set at connection level (via the
     /// connection string) so every query is automatically protected.
+    ///
+    /// `params` bind the query's `$n`/`:name` placeholders, in the order
+    /// `find_placeholders` reports them.
+    ///
+    /// Behind a transaction-pooling proxy such as pgbouncer, a statement
+    /// may be prepared on one server connection and executed on another,
+    /// which fails before it runs. The query then runs again over the
+    /// simple protocol, which the connection keeps using from then on.
     async fn execute_query_inner(
         &self,
         sql: &str,
+        params: &[Option<String>],
         max_rows: usize,
         progress: Option<mpsc::UnboundedSender<usize>>,
+    ) -> DbResult<QueryResults> {
+        if self.uses_simple_protocol() {
+            return self.execute_simple(sql, params, max_rows).await;
+        }
+        match self.execute_prepared(sql, params, max_rows, progress).await {
+            Err(DbError::QueryFailed { message, .. }) if is_pooler_statement_error(&message) => {
+                self.set_simple_protocol(true);
+                self.execute_simple(sql, params, max_rows).await
+            }
+            result => result,
+        }
+    }
+
+    /// Whether user queries go over the simple query protocol
+    pub fn uses_simple_protocol(&self) -> bool {
+        self.simple_protocol.load(Ordering::Relaxed)
+    }
+
+    /// Change `statement_timeout` for the rest of the session (0 turns it
+    /// off). Inside a transaction it lasts only if the transaction commits.
+    pub async fn set_statement_timeout(&self, timeout_ms: u64) -> DbResult<()> {
+        self.client
+            .batch_execute(&format!("SET statement_timeout = {}", timeout_ms))
+            .await
+            .map_err(extract_query_error)
+    }
+
+    /// Switch user queries to (or back from) the simple query protocol
+    pub fn set_simple_protocol(&self, on: bool) {
+        self.simple_protocol.store(on, Ordering::Relaxed);
+    }
+
+    /// Run a user query over the simple query protocol, which needs no
+    /// prepared statements. Values arrive as text, so every column is text.
+    /// With several statements, the last one's rows are kept, as psql shows.
+    async fn execute_simple(
+        &self,
+        sql: &str,
+        params: &[Option<String>],
+        max_rows: usize,
     ) -> DbResult<QueryResults> {
         use futures::TryStreamExt;
+        use tokio_postgres::SimpleQueryMessage;
 
+        if !params.is_empty() {
+            return Err(DbError::QueryFailed {
+                message: "Placeholders need prepared statements, which this connection's \
+                          pooler doesn't support — write the values into the query"
+                    .to_string(),
+                position: None,
+                code: None,
+            });
+        }
         let start = std::time::Instant::now();
-
-        let stmt = self
+        let stream = self
             .client
-            .prepare(sql)
+            .simple_query_raw(sql)
             .await
             .map_err(extract_query_error)?;
+        futures::pin_mut!(stream);
 
-        let columns: Vec<ColumnDef> = stmt
-            .columns()
-            .iter()
-            .map(|col| ColumnDef {
-                name: col.name().to_string(),
-                data_type: pg_type_to_datatype(col.type_()),
-                nullable: true,
-            })
-            .collect();
+        let mut columns = Vec::new();
+        let mut rows = Vec::new();
+        let mut truncated = false;
+        let mut affected = 0;
+        while let Some(message) = stream.try_next().await.map_err(extract_query_error)? {
+            match message {
+                SimpleQueryMessage::RowDescription(cols) => {
+                    columns = cols
+                        .iter()
+                        .map(|col| ColumnDef {
+                            name: col.name().to_string(),
+                            data_type: DataType::Text,
+                            nullable: true,
+                        })
+                        .collect();
+                    rows.clear();
+                    truncated = false;
+                }
+                SimpleQueryMessage::Row(row) => {
+                    if max_rows > 0 && rows.len() >= max_rows {
+                        truncated = true;
+                        continue;
+                    }
+                    let values = (0..row.len())
+                        .map(|i| match row.get(i) {
+                            Some(text) => CellValue::Text(text.to_string()),
+                            None => CellValue::Null,
+                        })
+                        .collect();
+                    rows.push(Row { values });
+                }
+                SimpleQueryMessage::CommandComplete(n) => affected = n,
+                _ => {}
+            }
+        }
+
+        let row_count = rows.len();
+        let mut results =
+            QueryResults::new_truncated(columns, rows, start.elapsed(), row_count, truncated);
+        results.command = CommandTag::for_statement(sql, affected);
+        Ok(results)
+    }
+
+    /// Run a user query as a prepared statement, streaming its rows
+    async fn execute_prepared(
+        &self,
+        sql: &str,
+        params: &[Option<String>],
+        max_rows: usize,
+        progress: Option<mpsc::UnboundedSender<usize>>,
+    ) -> DbResult<QueryResults> {
+        use futures::TryStreamExt;
+
+        let start = std::time::Instant::now();
+
+        let sql = positional_sql(sql, params)?;
+        let sql = sql.as_ref();
+        let (stmt, columns) = self.prepare_user_query(sql).await?;
 
         // Use streaming to limit memory when max_rows is set
+        let params: Vec<TextParam> = params.iter().map(|p| TextParam(p.as_deref())).collect();
         let row_stream = self
             .client
-            .query_raw(&stmt, std::iter::empty::<i32>())
+            .query_raw(&stmt, params.iter().map(|p| p as &(dyn ToSql + Sync)))
             .await
             .map_err(extract_query_error)?;
 
@@ -361,25 +772,250 @@ impl PostgresProvider {
         }
 
         let row_count = rows.len();
-        Ok(QueryResults::new_truncated(
-            columns,
-            rows,
-            start.elapsed(),
-            row_count,
-            truncated,
-        ))
+        let mut results =
+            QueryResults::new_truncated(columns, rows, start.elapsed(), row_count, truncated);
+        // Known once the stream has run out (not when cut off at max_rows)
+        if let Some(affected) = row_stream.rows_affected() {
+            results.command = CommandTag::for_statement(sql, affected);
+        }
+        if is_simple_select(sql) {
+            results.source = self.resolve_source(&stmt).await;
+        }
+        Ok(results)
+    }
+
+    /// Prepare a user query and describe its result columns
+    async fn prepare_user_query(&self, sql: &str) -> DbResult<(Statement, Vec<ColumnDef>)> {
+        let stmt = self
+            .client
+            .prepare(sql)
+            .await
+            .map_err(extract_query_error)?;
+
+        let columns: Vec<ColumnDef> = stmt
+            .columns()
+            .iter()
+            .map(|col| ColumnDef {
+                name: col.name().to_string(),
+                data_type: pg_type_to_datatype(col.type_()),
+                nullable: true,
+            })
+            .collect();
+        Ok((stmt, columns))
+    }
+
+    /// Run a query and hand its rows to `sink` in batches instead of
+    /// collecting them, for exports larger than the grid should hold. The
+    /// first call comes before any row is read (with no rows), so the sink
+    /// always learns the columns. An error from the sink stops the query.
+    /// Returns the number of rows read. Over the simple protocol the rows
+    /// are read in full before the sink gets them.
+    pub async fn stream_query<F>(
+        &self,
+        sql: &str,
+        params: &[Option<String>],
+        mut sink: F,
+    ) -> DbResult<usize>
+    where
+        F: FnMut(&[ColumnDef], &[Row]) -> Result<(), String> + Send,
+    {
+        use futures::TryStreamExt;
+
+        const BATCH_ROWS: usize = 1000;
+
+        let sink_failed = |message| DbError::QueryFailed {
+            message,
+            position: None,
+            code: None,
+        };
+        if self.uses_simple_protocol() {
+            let query = async {
+                let results = self.execute_simple(sql, params, 0).await?;
+                sink(&results.columns, &[]).map_err(sink_failed)?;
+                for batch in results.rows.chunks(BATCH_ROWS) {
+                    sink(&results.columns, batch).map_err(sink_failed)?;
+                }
+                Ok(results.rows.len())
+            };
+            return self.run_tracked(sql, 0, query).await;
+        }
+
+        let query = async {
+            let positional = positional_sql(sql, params)?;
+            let (stmt, columns) = self.prepare_user_query(positional.as_ref()).await?;
+            sink(&columns, &[]).map_err(sink_failed)?;
+
+            let params: Vec<TextParam> = params.iter().map(|p| TextParam(p.as_deref())).collect();
+            let row_stream = self
+                .client
+                .query_raw(&stmt, params.iter().map(|p| p as &(dyn ToSql + Sync)))
+                .await
+                .map_err(extract_query_error)?;
+            futures::pin_mut!(row_stream);
+
+            let mut batch = Vec::with_capacity(BATCH_ROWS);
+            let mut total = 0;
+            while let Some(pg_row) = row_stream.try_next().await.map_err(extract_query_error)? {
+                let values = columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, col_def)| extract_cell_value(&pg_row, i, &col_def.data_type))
+                    .collect();
+                batch.push(Row { values });
+                if batch.len() == BATCH_ROWS {
+                    total += batch.len();
+                    sink(&columns, &batch).map_err(sink_failed)?;
+                    batch.clear();
+                }
+            }
+            total += batch.len();
+            if !batch.is_empty() {
+                sink(&columns, &batch).map_err(sink_failed)?;
+            }
+            Ok(total)
+        };
+        self.run_tracked(sql, 0, query).await
+    }
+
+    /// Run a `COPY ... FROM STDIN`, sending the chunks `next_chunk` returns
+    /// until it returns `None`. An error from `next_chunk` aborts the COPY,
+    /// so nothing is written. Returns the number of rows copied.
+    pub async fn copy_in<F>(&self, copy_sql: &str, mut next_chunk: F) -> DbResult<u64>
+    where
+        F: FnMut() -> Result<Option<Vec<u8>>, String> + Send,
+    {
+        use futures::SinkExt;
+
+        let query = async {
+            let sink = self
+                .client
+                .copy_in::<_, std::io::Cursor<Vec<u8>>>(copy_sql)
+                .await
+                .map_err(extract_query_error)?;
+            futures::pin_mut!(sink);
+            // Dropping the sink unfinished sends CopyFail
+            while let Some(chunk) = next_chunk().map_err(|message| DbError::QueryFailed {
+                message,
+                position: None,
+                code: None,
+            })? {
+                sink.send(std::io::Cursor::new(chunk))
+                    .await
+                    .map_err(extract_query_error)?;
+            }
+            sink.as_mut().finish().await.map_err(extract_query_error)
+        };
+        self.run_tracked(copy_sql, 0, query).await
+    }
+
+    /// Run `COPY (sql) TO STDOUT (options)`, handing each chunk the server
+    /// sends to `sink`. An error from the sink cancels the COPY. Returns the
+    /// number of bytes received. `sql` must be one query without parameters.
+    pub async fn copy_out<F>(&self, sql: &str, options: &str, mut sink: F) -> DbResult<u64>
+    where
+        F: FnMut(&[u8]) -> Result<(), String> + Send,
+    {
+        use futures::TryStreamExt;
+
+        let query_sql = sql.trim().trim_end_matches(';').trim_end();
+        let copy_sql = format!("COPY ({}) TO STDOUT ({})", query_sql, options);
+        let query = async {
+            let stream = self
+                .client
+                .copy_out(copy_sql.as_str())
+                .await
+                .map_err(extract_query_error)?;
+            futures::pin_mut!(stream);
+            let mut bytes = 0u64;
+            while let Some(chunk) = stream.try_next().await.map_err(extract_query_error)? {
+                bytes += chunk.len() as u64;
+                if let Err(message) = sink(&chunk) {
+                    // Otherwise the server keeps sending the rest
+                    let _ = self.cancel_query().await;
+                    return Err(DbError::QueryFailed {
+                        message,
+                        position: None,
+                        code: None,
+                    });
+                }
+            }
+            Ok(bytes)
+        };
+        self.run_tracked(&copy_sql, 0, query).await
+    }
+
+    /// Work out which table a simple SELECT read from, using the column
+    /// origins in the row description. Results only get a source when every
+    /// table-backed column comes from one table whose primary key is included.
+    /// Lookup failures just leave the results read-only.
+    async fn resolve_source(&self, stmt: &Statement) -> Option<ResultSource> {
+        let origins: Vec<Option<(u32, i16)>> = stmt
+            .columns()
+            .iter()
+            .map(|col| col.table_oid().zip(col.column_id()))
+            .collect();
+        let table_oid = origins.iter().flatten().next()?.0;
+        if origins.iter().flatten().any(|(oid, _)| *oid != table_oid) {
+            return None;
+        }
+
+        let rows = self
+            .client
+            .query(
+                "SELECT n.nspname, c.relname, a.attnum, a.attname,
+                        format_type(a.atttypid, a.atttypmod),
+                        COALESCE(a.attnum = ANY(i.indkey), false)
+                 FROM pg_class c
+                 JOIN pg_namespace n ON n.oid = c.relnamespace
+                 JOIN pg_attribute a ON a.attrelid = c.oid
+                      AND a.attnum > 0 AND NOT a.attisdropped
+                 LEFT JOIN pg_index i ON i.indrelid = c.oid AND i.indisprimary
+                 WHERE c.oid = $1 AND c.relkind IN ('r', 'p')
+                 ORDER BY a.attnum",
+                &[&table_oid],
+            )
+            .await
+            .ok()?;
+        let first = rows.first()?;
+        let schema: String = first.get(0);
+        let table: String = first.get(1);
+
+        let mut attributes = HashMap::new();
+        let mut primary_key = Vec::new();
+        for row in &rows {
+            let attnum: i16 = row.get(2);
+            let name: String = row.get(3);
+            if row.get::<_, bool>(5) {
+                primary_key.push(name.clone());
+            }
+            let type_name: String = row.get(4);
+            attributes.insert(attnum, SourceColumn { name, type_name });
+        }
+        let columns = origins
+            .iter()
+            .map(|origin| origin.and_then(|(_, attnum)| attributes.get(&attnum).cloned()))
+            .collect();
+        ResultSource::new(schema, table, columns, &primary_key)
     }
 
     /// Inner schema loading logic. Pass limit=0 for unlimited.
     ///
-    /// Uses efficient two-phase loading when limit > 0:
-    /// 1. Query table/view NAMES with LIMIT
-    /// 2. Query columns and constraints only for those limited names
+    /// Lists the schemas, then loads each one's objects in turn.
     async fn get_schema_inner(&self, limit: usize) -> DbResult<SchemaTree> {
+        let mut tree = self.list_schemas_inner().await?;
+        for schema in &mut tree.schemas.items {
+            *schema = self.load_schema_inner(&schema.name, limit).await?;
+        }
+        Ok(tree)
+    }
+
+    /// Schema names only (pg_ internal schemas and information_schema
+    /// excluded), each still to be loaded, plus the installed extensions
+    /// and the databases on the server
+    async fn list_schemas_inner(&self) -> DbResult<SchemaTree> {
         let map_err =
             |e: tokio_postgres::Error| crate::error::DbError::SchemaLoadFailed(e.to_string());
 
-        // Query 1: Schemas (exclude pg_ internal and information_schema)
         let schema_rows = self
             .client
             .query(
@@ -392,213 +1028,155 @@ impl PostgresProvider {
             .await
             .map_err(&map_err)?;
 
-        let schema_names: Vec<String> = schema_rows.iter().map(|r| r.get(0)).collect();
-
-        // Count queries for pagination metadata (only if limit > 0)
-        let table_counts: HashMap<String, i64>;
-        let view_counts: HashMap<String, i64>;
-        let func_counts: HashMap<String, i64>;
-        let index_counts: HashMap<String, i64>;
-
-        if limit > 0 {
-            // Count tables per schema
-            let table_count_rows = self
-                .client
-                .query(
-                    "SELECT n.nspname, COUNT(DISTINCT c.oid)::bigint
-                     FROM pg_class c
-                     JOIN pg_namespace n ON n.oid = c.relnamespace
-                     WHERE c.relkind = 'r'
-                       AND n.nspname NOT LIKE 'pg_%'
-                       AND n.nspname != 'information_schema'
-                     GROUP BY n.nspname",
-                    &[],
-                )
-                .await
-                .map_err(&map_err)?;
-            table_counts = table_count_rows
-                .iter()
-                .map(|r| (r.get::<_, String>(0), r.get::<_, i64>(1)))
-                .collect();
-
-            // Count views per schema
-            let view_count_rows = self
-                .client
-                .query(
-                    "SELECT n.nspname, COUNT(DISTINCT c.oid)::bigint
-                     FROM pg_class c
-                     JOIN pg_namespace n ON n.oid = c.relnamespace
-                     WHERE c.relkind IN ('v', 'm')
-                       AND n.nspname NOT LIKE 'pg_%'
-                       AND n.nspname != 'information_schema'
-                     GROUP BY n.nspname",
-                    &[],
-                )
-                .await
-                .map_err(&map_err)?;
-            view_counts = view_count_rows
-                .iter()
-                .map(|r| (r.get::<_, String>(0), r.get::<_, i64>(1)))
-                .collect();
-
-            // Count functions per schema
-            let func_count_rows = self
-                .client
-                .query(
-                    "SELECT n.nspname, COUNT(*)::bigint
-                     FROM pg_proc p
-                     JOIN pg_namespace n ON n.oid = p.pronamespace
-                     WHERE n.nspname NOT LIKE 'pg_%'
-                       AND n.nspname != 'information_schema'
-                       AND p.prokind IN ('f', 'p')
-                     GROUP BY n.nspname",
-                    &[],
-                )
-                .await
-                .map_err(&map_err)?;
-            func_counts = func_count_rows
-                .iter()
-                .map(|r| (r.get::<_, String>(0), r.get::<_, i64>(1)))
-                .collect();
-
-            // Count indexes per schema
-            let index_count_rows = self
-                .client
-                .query(
-                    "SELECT n.nspname, COUNT(DISTINCT ci.oid)::bigint
-                     FROM pg_index ix
-                     JOIN pg_class ci ON ci.oid = ix.indexrelid
-                     JOIN pg_class ct ON ct.oid = ix.indrelid
-                     JOIN pg_namespace n ON n.oid = ct.relnamespace
-                     WHERE n.nspname NOT LIKE 'pg_%'
-                       AND n.nspname != 'information_schema'
-                     GROUP BY n.nspname",
-                    &[],
-                )
-                .await
-                .map_err(&map_err)?;
-            index_counts = index_count_rows
-                .iter()
-                .map(|r| (r.get::<_, String>(0), r.get::<_, i64>(1)))
-                .collect();
-        } else {
-            table_counts = HashMap::new();
-            view_counts = HashMap::new();
-            func_counts = HashMap::new();
-            index_counts = HashMap::new();
-        }
-
-        // Estimated row counts from pg_stat_user_tables (fast, no seq scan)
-        let row_count_rows = self
+        let extension_rows = self
             .client
             .query(
-                "SELECT schemaname, relname, n_live_tup::bigint \
-                 FROM pg_stat_user_tables \
-                 WHERE schemaname = ANY($1)",
-                &[&schema_names],
+                "SELECT e.extname, e.extversion, n.nspname \
+                 FROM pg_extension e \
+                 JOIN pg_namespace n ON n.oid = e.extnamespace \
+                 ORDER BY e.extname",
+                &[],
             )
             .await
             .map_err(&map_err)?;
-        let mut row_counts_by_schema: HashMap<String, HashMap<String, i64>> = HashMap::new();
-        for row in &row_count_rows {
-            let schema: String = row.get(0);
-            let relname: String = row.get(1);
-            let count: i64 = row.get(2);
-            row_counts_by_schema
-                .entry(schema)
-                .or_default()
-                .insert(relname, count);
-        }
 
-        // Build schemas using two-phase loading for efficiency
-        let mut schemas = Vec::new();
+        let database_rows = self
+            .client
+            .query(
+                "SELECT datname, datname = current_database() FROM pg_database \
+                 WHERE datallowconn AND NOT datistemplate \
+                 ORDER BY datname",
+                &[],
+            )
+            .await
+            .map_err(&map_err)?;
 
-        for schema_name in &schema_names {
-            let row_counts = row_counts_by_schema
-                .get(schema_name)
-                .cloned()
-                .unwrap_or_default();
-
-            // Phase 1: Get limited table/view names
-            let table_names = self.load_relation_names(schema_name, "r", 0, limit).await?;
-            let view_names = self
-                .load_relation_names(schema_name, "v,m", 0, limit)
-                .await?;
+        Ok(SchemaTree {
+            schemas: PaginatedVec::from_vec(
+                schema_rows
+                    .iter()
+                    .map(|r| r.get::<_, String>(0))
+                    .filter(|name| !self.hides_schema(name))
+                    .map(Schema::unloaded)
+                    .collect(),
+            ),
+            extensions: extension_rows
+                .iter()
+                .map(|r| Extension {
+                    name: r.get(0),
+                    version: r.get(1),
+                    schema: r.get(2),
+                })
+                .collect(),
+            databases: database_rows
+                .iter()
+                .map(|r| ServerDatabase {
+                    name: r.get(0),
+                    is_current: r.get(1),
+                })
+                .collect(),
+        })
+    }
 
-            // Phase 2: Get columns and constraints only for those tables/views
-            let tables = if table_names.is_empty() {
-                Vec::new()
-            } else {
-                let columns = self
-                    .load_columns_for_relations(schema_name, &table_names)
-                    .await?;
-                let (pk_set, fk_map) = self
-                    .load_constraints_for_tables(schema_name, &table_names)
-                    .await?;
-                assemble_tables(
-                    schema_name,
-                    table_names,
-                    columns,
-                    pk_set,
-                    fk_map,
-                    &row_counts,
-                )
-            };
+    /// Load one schema's tables, views, functions, indexes, sequences, and
+    /// types. Pass limit=0 for unlimited.
+    ///
+    /// Uses efficient two-phase loading when limit > 0:
+    /// 1. Query table/view NAMES with LIMIT
+    /// 2. Query columns and constraints only for those limited names
+    ///
+    /// Sequences, enums, and domains are few per schema and load in full.
+    async fn load_schema_inner(&self, schema_name: &str, limit: usize) -> DbResult<Schema> {
+        let map_err =
+            |e: tokio_postgres::Error| crate::error::DbError::SchemaLoadFailed(e.to_string());
 
-            let views = if view_names.is_empty() {
-                Vec::new()
-            } else {
-                let columns = self
-                    .load_columns_for_relations(schema_name, &view_names)
-                    .await?;
-                // Views don't have PK/FK constraints or row counts
-                assemble_tables(
-                    schema_name,
-                    view_names,
-                    columns,
-                    HashSet::new(),
-                    HashMap::new(),
-                    &HashMap::new(),
-                )
-            };
+        // Phase 1: Get limited table/view names
+        let table_names = self.load_relation_names(schema_name, "r", 0, limit).await?;
+        let view_names = self
+            .load_relation_names(schema_name, "v,m", 0, limit)
+            .await?;
 
-            // Functions and indexes with LIMIT (simple queries, already efficient)
-            let functions = self.load_functions_limited(schema_name, 0, limit).await?;
-            let indexes = self.load_indexes_limited(schema_name, 0, limit).await?;
+        // Phase 2: Get columns, constraints, and stats only for those tables/views
+        let tables = if table_names.is_empty() {
+            Vec::new()
+        } else {
+            let stats = self.load_table_stats(schema_name, &table_names).await?;
+            let columns = self
+                .load_columns_for_relations(schema_name, &table_names)
+                .await?;
+            let (pk_set, fk_map) = self
+                .load_constraints_for_tables(schema_name, &table_names)
+                .await?;
+            let denied = self.load_select_denied(schema_name, &table_names).await?;
+            let mut tables =
+                assemble_tables(schema_name, table_names, columns, pk_set, fk_map, &stats);
+            mark_select_denied(&mut tables, &denied);
+            tables
+        };
 
-            // Get total counts (from COUNT queries if limit > 0, else from vec length)
-            let table_total = if limit > 0 {
-                *table_counts.get(schema_name).unwrap_or(&0) as usize
-            } else {
-                tables.len()
-            };
-            let view_total = if limit > 0 {
-                *view_counts.get(schema_name).unwrap_or(&0) as usize
-            } else {
-                views.len()
-            };
-            let index_total = if limit > 0 {
-                *index_counts.get(schema_name).unwrap_or(&0) as usize
-            } else {
-                indexes.len()
-            };
-            let func_total = if limit > 0 {
-                *func_counts.get(schema_name).unwrap_or(&0) as usize
-            } else {
-                functions.len()
-            };
+        let views = if view_names.is_empty() {
+            Vec::new()
+        } else {
+            let columns = self
+                .load_columns_for_relations(schema_name, &view_names)
+                .await?;
+            let denied = self.load_select_denied(schema_name, &view_names).await?;
+            // Views don't have PK/FK constraints or row counts
+            let mut views = assemble_tables(
+                schema_name,
+                view_names,
+                columns,
+                HashSet::new(),
+                HashMap::new(),
+                &HashMap::new(),
+            );
+            mark_select_denied(&mut views, &denied);
+            views
+        };
 
-            schemas.push(Schema {
-                name: schema_name.clone(),
-                tables: PaginatedVec::new(tables, table_total),
-                views: PaginatedVec::new(views, view_total),
-                indexes: PaginatedVec::new(indexes, index_total),
-                functions: PaginatedVec::new(functions, func_total),
-            });
-        }
+        // Functions and indexes with LIMIT (simple queries, already efficient)
+        let functions = self.load_functions_limited(schema_name, 0, limit).await?;
+        let indexes = self.load_indexes_limited(schema_name, 0, limit).await?;
+        let sequences = self.load_sequences(schema_name).await?;
+        let (enums, domains) = self.load_types(schema_name).await?;
 
-        Ok(SchemaTree {
-            schemas: PaginatedVec::from_vec(schemas),
+        // Total counts for pagination metadata (only needed if limit > 0)
+        let (table_total, view_total, func_total, index_total) = if limit > 0 {
+            let counts = self
+                .client
+                .query_one(
+                    "SELECT
+                        (SELECT COUNT(*) FROM pg_class c
+                         WHERE c.relnamespace = n.oid AND c.relkind = 'r')::bigint,
+                        (SELECT COUNT(*) FROM pg_class c
+                         WHERE c.relnamespace = n.oid AND c.relkind IN ('v', 'm'))::bigint,
+                        (SELECT COUNT(*) FROM pg_proc p
+                         WHERE p.pronamespace = n.oid AND p.prokind IN ('f', 'p'))::bigint,
+                        (SELECT COUNT(*) FROM pg_index ix
+                         JOIN pg_class ct ON ct.oid = ix.indrelid
+                         WHERE ct.relnamespace = n.oid)::bigint
+                     FROM pg_namespace n
+                     WHERE n.nspname = $1",
+                    &[&schema_name],
+                )
+                .await
+                .map_err(&map_err)?;
+            let count = |i: usize| counts.get::<_, i64>(i) as usize;
+            (count(0), count(1), count(2), count(3))
+        } else {
+            (tables.len(), views.len(), functions.len(), indexes.len())
+        };
+
+        Ok(Schema {
+            name: schema_name.to_string(),
+            tables: PaginatedVec::new(tables, table_total),
+            views: PaginatedVec::new(views, view_total),
+            indexes: PaginatedVec::new(indexes, index_total),
+            functions: PaginatedVec::new(functions, func_total),
+            sequences: PaginatedVec::from_vec(sequences),
+            enums: PaginatedVec::from_vec(enums),
+            domains: PaginatedVec::from_vec(domains),
+            loaded: true,
         })
     }
 
@@ -646,6 +1224,67 @@ impl PostgresProvider {
         Ok(rows.iter().map(|r| r.get(0)).collect())
     }
 
+    /// Estimated row counts (pg_class.reltuples, no scan) and total on-disk
+    /// sizes for specific tables in a schema. Tables never analyzed report
+    /// reltuples = -1 and get no row estimate.
+    async fn load_table_stats(
+        &self,
+        schema_name: &str,
+        table_names: &[String],
+    ) -> DbResult<HashMap<String, TableStats>> {
+        let map_err =
+            |e: tokio_postgres::Error| crate::error::DbError::SchemaLoadFailed(e.to_string());
+
+        let rows = self
+            .client
+            .query(
+                "SELECT c.relname, c.reltuples::bigint, pg_total_relation_size(c.oid)
+                 FROM pg_class c
+                 JOIN pg_namespace n ON n.oid = c.relnamespace
+                 WHERE n.nspname = $1 AND c.relname = ANY($2) AND c.relkind = 'r'",
+                &[&schema_name, &table_names],
+            )
+            .await
+            .map_err(&map_err)?;
+
+        Ok(rows
+            .iter()
+            .map(|r| {
+                let rows: i64 = r.get(1);
+                let stats = TableStats {
+                    row_count: (rows >= 0).then_some(rows),
+                    size_bytes: r.get(2),
+                };
+                (r.get(0), stats)
+            })
+            .collect())
+    }
+
+    /// Which of these tables/views the current role can't SELECT from
+    async fn load_select_denied(
+        &self,
+        schema_name: &str,
+        relation_names: &[String],
+    ) -> DbResult<HashSet<String>> {
+        let map_err =
+            |e: tokio_postgres::Error| crate::error::DbError::SchemaLoadFailed(e.to_string());
+
+        let rows = self
+            .client
+            .query(
+                "SELECT c.relname
+                 FROM pg_class c
+                 JOIN pg_namespace n ON n.oid = c.relnamespace
+                 WHERE n.nspname = $1 AND c.relname = ANY($2)
+                   AND NOT has_table_privilege(c.oid, 'SELECT')",
+                &[&schema_name, &relation_names],
+            )
+            .await
+            .map_err(&map_err)?;
+
+        Ok(rows.iter().map(|r| r.get(0)).collect())
+    }
+
     /// Load columns for specific tables/views in a schema.
     async fn load_columns_for_relations(
         &self,
@@ -865,12 +1504,92 @@ impl PostgresProvider {
             .collect())
     }
 
+    /// Load a schema's sequences with their last values. pg_sequences
+    /// reports NULL for sequences never used or not readable by us.
+    async fn load_sequences(&self, schema_name: &str) -> DbResult<Vec<Sequence>> {
+        let map_err =
+            |e: tokio_postgres::Error| crate::error::DbError::SchemaLoadFailed(e.to_string());
+
+        let rows = self
+            .client
+            .query(
+                "SELECT sequencename::text, last_value
+                 FROM pg_sequences
+                 WHERE schemaname = $1
+                 ORDER BY sequencename",
+                &[&schema_name],
+            )
+            .await
+            .map_err(&map_err)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| Sequence {
+                name: row.get(0),
+                last_value: row.get(1),
+            })
+            .collect())
+    }
+
+    /// Load a schema's enum types (with labels in sort order) and domains
+    /// (with their base types).
+    async fn load_types(&self, schema_name: &str) -> DbResult<(Vec<EnumType>, Vec<Domain>)> {
+        let map_err =
+            |e: tokio_postgres::Error| crate::error::DbError::SchemaLoadFailed(e.to_string());
+
+        let enum_rows = self
+            .client
+            .query(
+                "SELECT t.typname::text,
+                        array_agg(e.enumlabel::text ORDER BY e.enumsortorder)
+                 FROM pg_type t
+                 JOIN pg_namespace n ON n.oid = t.typnamespace
+                 JOIN pg_enum e ON e.enumtypid = t.oid
+                 WHERE n.nspname = $1
+                 GROUP BY t.typname
+                 ORDER BY t.typname",
+                &[&schema_name],
+            )
+            .await
+            .map_err(&map_err)?;
+
+        let domain_rows = self
+            .client
+            .query(
+                "SELECT t.typname::text, format_type(t.typbasetype, t.typtypmod)
+                 FROM pg_type t
+                 JOIN pg_namespace n ON n.oid = t.typnamespace
+                 WHERE t.typtype = 'd' AND n.nspname = $1
+                 ORDER BY t.typname",
+                &[&schema_name],
+            )
+            .await
+            .map_err(&map_err)?;
+
+        let enums = enum_rows
+            .iter()
+            .map(|row| EnumType {
+                name: row.get(0),
+                labels: row.get(1),
+            })
+            .collect();
+        let domains = domain_rows
+            .iter()
+            .map(|row| Domain {
+                name: row.get(0),
+                base_type: row.get(1),
+            })
+            .collect();
+        Ok((enums, domains))
+    }
+
     /// Search schema objects by name pattern (case-insensitive substring match).
     /// Returns tables, views, functions, indexes, and columns that match the pattern.
     async fn search_schema_inner(&self, pattern: &str) -> DbResult<SchemaTree> {
         let map_err = |e: tokio_postgres::Error| DbError::QueryFailed {
             message: e.to_string(),
             position: None,
+            code: None,
         };
 
         // Escape special LIKE characters and create pattern
@@ -927,11 +1646,17 @@ impl PostgresProvider {
             .await
             .map_err(&map_err)?;
 
-        let schema_names: Vec<String> = schema_rows.iter().map(|r| r.get(0)).collect();
+        let schema_names: Vec<String> = schema_rows
+            .iter()
+            .map(|r| r.get::<_, String>(0))
+            .filter(|name| !self.hides_schema(name))
+            .collect();
 
         if schema_names.is_empty() {
             return Ok(SchemaTree {
                 schemas: PaginatedVec::from_vec(vec![]),
+                extensions: Vec::new(),
+                databases: Vec::new(),
             });
         }
 
@@ -943,7 +1668,8 @@ impl PostgresProvider {
                 "SELECT n.nspname, c.relname, c.relkind::text, a.attname,
                         format_type(a.atttypid, a.atttypmod) AS formatted_type,
                         c.relname ILIKE $1 AS table_matches,
-                        a.attname ILIKE $1 AS col_matches
+                        a.attname ILIKE $1 AS col_matches,
+                        NOT has_table_privilege(c.oid, 'SELECT') AS select_denied
                  FROM pg_class c
                  JOIN pg_namespace n ON n.oid = c.relnamespace
                  JOIN pg_attribute a ON a.attrelid = c.oid
@@ -1099,7 +1825,7 @@ impl PostgresProvider {
         }
 
         // Build tables and views from rel_rows
-        let mut rel_map: HashMap<(String, String), (String, Vec<Column>)> = HashMap::new();
+        let mut rel_map: HashMap<(String, String), (String, bool, Vec<Column>)> = HashMap::new();
         let mut rel_order: HashMap<String, Vec<String>> = HashMap::new();
 
         for row in &rel_rows {
@@ -1108,6 +1834,7 @@ impl PostgresProvider {
             let relkind: String = row.get(2);
             let col_name: String = row.get(3);
             let type_name: String = row.get(4);
+            let select_denied: bool = row.get(7);
 
             let key = (schema.clone(), relname.clone());
             let entry = rel_map.entry(key).or_insert_with(|| {
@@ -1115,13 +1842,13 @@ impl PostgresProvider {
                     .entry(schema.clone())
                     .or_default()
                     .push(relname.clone());
-                (relkind.clone(), Vec::new())
+                (relkind.clone(), select_denied, Vec::new())
             });
 
             let is_pk = pk_set.contains(&(schema.clone(), relname.clone(), col_name.clone()));
             let fk = fk_map.remove(&(schema, relname, col_name.clone()));
 
-            entry.1.push(Column {
+            entry.2.push(Column {
                 name: col_name,
                 data_type: datatype_from_format_type(&type_name),
                 is_primary_key: is_pk,
@@ -1137,13 +1864,15 @@ impl PostgresProvider {
 
             if let Some(rel_names) = rel_order.get(schema_name) {
                 for relname in rel_names {
-                    if let Some((relkind, columns)) =
+                    if let Some((relkind, select_denied, columns)) =
                         rel_map.remove(&(schema_name.clone(), relname.clone()))
                     {
                         let table = Table {
                             name: relname.clone(),
                             columns,
                             row_count: None,
+                            size_bytes: None,
+                            select_denied,
                         };
                         match relkind.as_str() {
                             "r" => tables.push(table),
@@ -1160,11 +1889,17 @@ impl PostgresProvider {
                 views: PaginatedVec::from_vec(views),
                 indexes: PaginatedVec::from_vec(index_map.remove(schema_name).unwrap_or_default()),
                 functions: PaginatedVec::from_vec(func_map.remove(schema_name).unwrap_or_default()),
+                sequences: PaginatedVec::default(),
+                enums: PaginatedVec::default(),
+                domains: PaginatedVec::default(),
+                loaded: true,
             });
         }
 
         Ok(SchemaTree {
             schemas: PaginatedVec::from_vec(schemas),
+            extensions: Vec::new(),
+            databases: Vec::new(),
         })
     }
 
@@ -1176,9 +1911,6 @@ impl PostgresProvider {
         offset: usize,
         limit: usize,
     ) -> DbResult<Vec<Table>> {
-        let map_err =
-            |e: tokio_postgres::Error| crate::error::DbError::SchemaLoadFailed(e.to_string());
-
         // Phase 1: Get table names with offset/limit
         let table_names = self
             .load_relation_names(schema_name, "r", offset, limit)
@@ -1187,38 +1919,19 @@ impl PostgresProvider {
             return Ok(Vec::new());
         }
 
-        // Get row counts for these tables
-        let row_count_rows = self
-            .client
-            .query(
-                "SELECT relname, n_live_tup::bigint \
-                 FROM pg_stat_user_tables \
-                 WHERE schemaname = $1 AND relname = ANY($2)",
-                &[&schema_name, &table_names],
-            )
-            .await
-            .map_err(&map_err)?;
-        let row_counts: HashMap<String, i64> = row_count_rows
-            .iter()
-            .map(|r| (r.get::<_, String>(0), r.get::<_, i64>(1)))
-            .collect();
-
-        // Phase 2: Get columns and constraints for those tables
+        // Phase 2: Get columns, constraints, and stats for those tables
+        let stats = self.load_table_stats(schema_name, &table_names).await?;
         let columns = self
             .load_columns_for_relations(schema_name, &table_names)
             .await?;
         let (pk_set, fk_map) = self
             .load_constraints_for_tables(schema_name, &table_names)
             .await?;
+        let denied = self.load_select_denied(schema_name, &table_names).await?;
 
-        Ok(assemble_tables(
-            schema_name,
-            table_names,
-            columns,
-            pk_set,
-            fk_map,
-            &row_counts,
-        ))
+        let mut tables = assemble_tables(schema_name, table_names, columns, pk_set, fk_map, &stats);
+        mark_select_denied(&mut tables, &denied);
+        Ok(tables)
     }
 
     /// Load more views for a specific schema with offset and limit.
@@ -1241,15 +1954,18 @@ impl PostgresProvider {
         let columns = self
             .load_columns_for_relations(schema_name, &view_names)
             .await?;
+        let denied = self.load_select_denied(schema_name, &view_names).await?;
 
-        Ok(assemble_tables(
+        let mut views = assemble_tables(
             schema_name,
             view_names,
             columns,
             HashSet::new(),
             HashMap::new(),
             &HashMap::new(), // views don't have row counts
-        ))
+        );
+        mark_select_denied(&mut views, &denied);
+        Ok(views)
     }
 
     /// Load more functions for a specific schema with offset and limit.
@@ -1282,36 +1998,322 @@ impl PostgresProvider {
     pub async fn execute_query_with_progress(
         &self,
         sql: &str,
+        params: &[Option<String>],
         timeout_ms: u64,
         max_rows: usize,
         progress: mpsc::UnboundedSender<usize>,
     ) -> DbResult<QueryResults> {
-        let query_future = self.execute_query_inner(sql, max_rows, Some(progress));
+        self.execute_query_tracked(sql, params, timeout_ms, max_rows, Some(progress))
+            .await
+    }
 
-        if timeout_ms == 0 {
-            query_future.await
-        } else {
-            match timeout(Duration::from_millis(timeout_ms), query_future).await {
-                Ok(result) => result,
-                Err(_) => {
-                    let _ = self.cancel_query_enhanced(false).await;
-                    Err(DbError::Timeout(timeout_ms))
+    /// Whether the connection to the server has gone away
+    pub fn is_closed(&self) -> bool {
+        self.client.is_closed()
+    }
+
+    /// Transaction state of this connection after the last user query.
+    pub fn transaction_state(&self) -> TransactionState {
+        self.transaction.state()
+    }
+
+    /// Columns of a table with what the insert-row form needs: nullability,
+    /// defaults, and which ones the server generates
+    pub async fn insert_columns(&self, schema: &str, table: &str) -> DbResult<Vec<InsertColumn>> {
+        let rows = self
+            .client
+            .query(
+                "SELECT a.attname, format_type(a.atttypid, a.atttypmod), a.attnotnull,
+                        pg_get_expr(ad.adbin, ad.adrelid),
+                        a.attidentity::text, a.attgenerated::text
+                 FROM pg_attribute a
+                 JOIN pg_class c ON c.oid = a.attrelid
+                 JOIN pg_namespace n ON n.oid = c.relnamespace
+                 LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum
+                 WHERE n.nspname = $1 AND c.relname = $2
+                   AND a.attnum > 0 AND NOT a.attisdropped
+                 ORDER BY a.attnum",
+                &[&schema, &table],
+            )
+            .await
+            .map_err(extract_query_error)?;
+        if rows.is_empty() {
+            return Err(DbError::QueryFailed {
+                message: format!("{}.{} not found", schema, table),
+                position: None,
+                code: None,
+            });
+        }
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let type_name: String = row.get(1);
+                let identity: String = row.get(4);
+                let generated: String = row.get(5);
+                let default: Option<String> = row.get(3);
+                InsertColumn {
+                    name: row.get(0),
+                    data_type: datatype_from_format_type(&type_name),
+                    type_name,
+                    not_null: row.get(2),
+                    default: if identity.is_empty() {
+                        default
+                    } else {
+                        Some("identity".to_string())
+                    },
+                    generated: identity == "a" || !generated.is_empty(),
+                }
+            })
+            .collect())
+    }
+
+    /// Generate a DDL script for a table, view, index, or function(s).
+    pub async fn object_ddl(&self, target: &DdlTarget) -> DbResult<String> {
+        let map_err =
+            |e: tokio_postgres::Error| crate::error::DbError::SchemaLoadFailed(e.to_string());
+        let not_found = || {
+            crate::error::DbError::SchemaLoadFailed(format!("{} not found", target.display_name()))
+        };
+
+        let (schema, name) = match target {
+            DdlTarget::Function { schema, name } => {
+                let rows = self
+                    .client
+                    .query(
+                        "SELECT pg_get_functiondef(p.oid)
+                         FROM pg_proc p
+                         JOIN pg_namespace n ON n.oid = p.pronamespace
+                         WHERE n.nspname = $1 AND p.proname = $2
+                           AND p.prokind IN ('f', 'p')
+                         ORDER BY pg_get_function_identity_arguments(p.oid)",
+                        &[schema, name],
+                    )
+                    .await
+                    .map_err(&map_err)?;
+                if rows.is_empty() {
+                    return Err(not_found());
+                }
+                let defs: Vec<String> = rows
+                    .iter()
+                    .map(|r| format!("{};\n", r.get::<_, String>(0).trim_end()))
+                    .collect();
+                return Ok(defs.join("\n"));
+            }
+            DdlTarget::Relation { schema, name } => (schema, name),
+        };
+
+        // CockroachDB writes its own DDL for tables, views, and sequences;
+        // anything else (indexes) takes the catalog route below
+        if self.dialect == Backend::Cockroach
+            && let Ok(row) = self
+                .client
+                .query_one(&cockroach::show_create_sql(schema, name), &[])
+                .await
+        {
+            let statement: String = row.try_get("create_statement").map_err(&map_err)?;
+            return Ok(format!("{};\n", statement.trim_end().trim_end_matches(';')));
+        }
+
+        let rel = self
+            .client
+            .query_opt(
+                "SELECT c.oid, c.relkind::text,
+                        quote_ident(n.nspname) || '.' || quote_ident(c.relname),
+                        obj_description(c.oid, 'pg_class')
+                 FROM pg_class c
+                 JOIN pg_namespace n ON n.oid = c.relnamespace
+                 WHERE n.nspname = $1 AND c.relname = $2",
+                &[schema, name],
+            )
+            .await
+            .map_err(&map_err)?
+            .ok_or_else(not_found)?;
+        let oid: u32 = rel.get(0);
+        let relkind: String = rel.get(1);
+        let qualified: String = rel.get(2);
+
+        let indexes = |constraint_backed: bool| {
+            let sql = if constraint_backed {
+                "SELECT pg_get_indexdef(i.indexrelid) FROM pg_index i
+                 WHERE i.indrelid = $1
+                 ORDER BY i.indexrelid"
+            } else {
+                "SELECT pg_get_indexdef(i.indexrelid) FROM pg_index i
+                 WHERE i.indrelid = $1
+                   AND NOT EXISTS (SELECT 1 FROM pg_constraint con
+                                   WHERE con.conindid = i.indexrelid AND con.conrelid = $1)
+                 ORDER BY i.indexrelid"
+            };
+            async move {
+                let rows = self.client.query(sql, &[&oid]).await.map_err(map_err)?;
+                Ok::<Vec<String>, DbError>(rows.iter().map(|r| r.get(0)).collect())
+            }
+        };
+
+        match relkind.as_str() {
+            "i" | "I" => {
+                let row = self
+                    .client
+                    .query_one("SELECT pg_get_indexdef($1::oid)", &[&oid])
+                    .await
+                    .map_err(&map_err)?;
+                Ok(format!("{};\n", row.get::<_, String>(0)))
+            }
+            "v" | "m" => {
+                let row = self
+                    .client
+                    .query_one("SELECT pg_get_viewdef($1::oid, true)", &[&oid])
+                    .await
+                    .map_err(&map_err)?;
+                let mut ddl = view_ddl(&qualified, relkind == "m", &row.get::<_, String>(0));
+                // Indexes on a materialized view are the one thing
+                // pg_get_viewdef leaves out
+                for index in indexes(true).await? {
+                    ddl.push_str(&format!("\n{};\n", index));
                 }
+                Ok(ddl)
+            }
+            "r" | "p" => {
+                let column_rows = self
+                    .client
+                    .query(
+                        "SELECT quote_ident(a.attname), format_type(a.atttypid, a.atttypmod), a.attnotnull,
+                                pg_get_expr(ad.adbin, ad.adrelid),
+                                a.attidentity::text, a.attgenerated::text,
+                                col_description(a.attrelid, a.attnum)
+                         FROM pg_attribute a
+                         LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum
+                         WHERE a.attrelid = $1 AND a.attnum > 0 AND NOT a.attisdropped
+                         ORDER BY a.attnum",
+                        &[&oid],
+                    )
+                    .await
+                    .map_err(&map_err)?;
+                // NOT NULL constraints (contype 'n', PG 18+) are already on
+                // the columns
+                let constraint_rows = self
+                    .client
+                    .query(
+                        "SELECT quote_ident(conname), pg_get_constraintdef(oid)
+                         FROM pg_constraint
+                         WHERE conrelid = $1 AND contype <> 'n'
+                         ORDER BY CASE contype WHEN 'p' THEN 0 WHEN 'u' THEN 1
+                                  WHEN 'c' THEN 2 WHEN 'x' THEN 3 ELSE 4 END, conname",
+                        &[&oid],
+                    )
+                    .await
+                    .map_err(&map_err)?;
+                let trigger_rows = self
+                    .client
+                    .query(
+                        "SELECT pg_get_triggerdef(oid) FROM pg_trigger
+                         WHERE tgrelid = $1 AND NOT tgisinternal
+                         ORDER BY tgname",
+                        &[&oid],
+                    )
+                    .await
+                    .map_err(&map_err)?;
+                let partition_key = if relkind == "p" {
+                    self.client
+                        .query_one("SELECT pg_get_partkeydef($1::oid)", &[&oid])
+                        .await
+                        .map_err(&map_err)?
+                        .get(0)
+                } else {
+                    None
+                };
+
+                let table = TableDdl {
+                    qualified,
+                    columns: column_rows
+                        .iter()
+                        .map(|r| DdlColumn {
+                            name: r.get(0),
+                            data_type: r.get(1),
+                            not_null: r.get(2),
+                            default: r.get(3),
+                            identity: r.get(4),
+                            generated: r.get(5),
+                            comment: r.get(6),
+                        })
+                        .collect(),
+                    constraints: constraint_rows
+                        .iter()
+                        .map(|r| (r.get(0), r.get(1)))
+                        .collect(),
+                    partition_key,
+                    indexes: indexes(false).await?,
+                    triggers: trigger_rows.iter().map(|r| r.get(0)).collect(),
+                    comment: rel.get(3),
+                };
+                Ok(table.to_sql())
             }
+            _ => Err(crate::error::DbError::SchemaLoadFailed(format!(
+                "Can't generate DDL for {} (relkind '{}')",
+                target.display_name(),
+                relkind
+            ))),
         }
     }
-}
 
-impl Database for PostgresProvider {
-    async fn execute_query(
+    /// Run a generated cell UPDATE and return the refreshed row (None when
+    /// no row matched the key any more).
+    pub async fn execute_cell_update(
+        &self,
+        update: &CellUpdate,
+        timeout_ms: u64,
+    ) -> DbResult<Option<Row>> {
+        let query_future = async {
+            let params: Vec<&(dyn ToSql + Sync)> = update
+                .params
+                .iter()
+                .map(|p| p as &(dyn ToSql + Sync))
+                .collect();
+            let rows = self
+                .client
+                .query(&update.sql, &params)
+                .await
+                .map_err(extract_query_error)?;
+            Ok(rows.first().map(|pg_row| {
+                let values = pg_row
+                    .columns()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, col)| {
+                        extract_cell_value(pg_row, i, &pg_type_to_datatype(col.type_()))
+                    })
+                    .collect();
+                Row { values }
+            }))
+        };
+        self.run_tracked(&update.sql, timeout_ms, query_future)
+            .await
+    }
+
+    /// Run a user query with client-side timeout protection and record its
+    /// outcome in the transaction tracker.
+    async fn execute_query_tracked(
         &self,
         sql: &str,
+        params: &[Option<String>],
         timeout_ms: u64,
         max_rows: usize,
+        progress: Option<mpsc::UnboundedSender<usize>>,
     ) -> DbResult<QueryResults> {
-        let query_future = self.execute_query_inner(sql, max_rows, None);
+        let query_future = self.execute_query_inner(sql, params, max_rows, progress);
+        self.run_tracked(sql, timeout_ms, query_future).await
+    }
 
-        if timeout_ms == 0 {
+    /// Await a statement with client-side timeout protection (cancelling it
+    /// on the server when the timeout fires) and record its outcome in the
+    /// transaction tracker.
+    async fn run_tracked<T>(
+        &self,
+        sql: &str,
+        timeout_ms: u64,
+        query_future: impl std::future::Future<Output = DbResult<T>>,
+    ) -> DbResult<T> {
+        let result = if timeout_ms == 0 {
             query_future.await
         } else {
             match timeout(Duration::from_millis(timeout_ms), query_future).await {
@@ -1322,13 +2324,70 @@ impl Database for PostgresProvider {
                     Err(DbError::Timeout(timeout_ms))
                 }
             }
+        };
+
+        match result {
+            Ok(_) => self.transaction.record_success(sql),
+            Err(_) => self.transaction.record_failure(sql),
+        }
+        result
+    }
+}
+
+/// A query parameter sent in text format, so the server parses it as
+/// whatever type it inferred for the placeholder (as if typed in psql)
+#[derive(Debug)]
+struct TextParam<'a>(Option<&'a str>);
+
+impl ToSql for TextParam<'_> {
+    fn to_sql(
+        &self,
+        _ty: &Type,
+        out: &mut bytes::BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        match self.0 {
+            Some(value) => {
+                out.extend_from_slice(value.as_bytes());
+                Ok(IsNull::No)
+            }
+            None => Ok(IsNull::Yes),
         }
     }
 
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    fn encode_format(&self, _ty: &Type) -> Format {
+        Format::Text
+    }
+
+    to_sql_checked!();
+}
+
+impl Database for PostgresProvider {
+    async fn execute_query(
+        &self,
+        sql: &str,
+        timeout_ms: u64,
+        max_rows: usize,
+    ) -> DbResult<QueryResults> {
+        self.execute_query_tracked(sql, &[], timeout_ms, max_rows, None)
+            .await
+    }
+
     async fn get_schema(&self, limit: usize) -> DbResult<SchemaTree> {
         self.get_schema_inner(limit).await
     }
 
+    async fn list_schemas(&self) -> DbResult<SchemaTree> {
+        self.list_schemas_inner().await
+    }
+
+    async fn load_schema(&self, schema_name: &str, limit: usize) -> DbResult<Schema> {
+        self.load_schema_inner(schema_name, limit).await
+    }
+
     async fn search_schema(&self, pattern: &str) -> DbResult<SchemaTree> {
         self.search_schema_inner(pattern).await
     }
@@ -1373,19 +2432,26 @@ impl Database for PostgresProvider {
     }
 }
 
-/// Assemble Table structs from names, columns, constraints, and optional row counts.
+/// Row estimate and on-disk size of a table, shown next to it in the tree
+#[derive(Debug, Clone, Copy, Default)]
+struct TableStats {
+    row_count: Option<i64>,
+    size_bytes: Option<i64>,
+}
+
+/// Assemble Table structs from names, columns, constraints, and optional stats.
 fn assemble_tables(
     _schema_name: &str,
     table_names: Vec<String>,
     mut columns: HashMap<String, Vec<(String, String)>>,
     pk_set: HashSet<(String, String)>,
     mut fk_map: HashMap<(String, String), ForeignKey>,
-    row_counts: &HashMap<String, i64>,
+    stats: &HashMap<String, TableStats>,
 ) -> Vec<Table> {
     table_names
         .into_iter()
         .map(|name| {
-            let row_count = row_counts.get(&name).copied();
+            let stats = stats.get(&name).copied().unwrap_or_default();
             let cols = columns.remove(&name).unwrap_or_default();
             let columns = cols
                 .into_iter()
@@ -1403,12 +2469,56 @@ fn assemble_tables(
             Table {
                 name,
                 columns,
-                row_count,
+                row_count: stats.row_count,
+                size_bytes: stats.size_bytes,
+                select_denied: false,
             }
         })
         .collect()
 }
 
+/// A `pg_stat_database` row from `health::DATABASE_SQL`
+fn database_stats(row: &tokio_postgres::Row) -> DatabaseStats {
+    DatabaseStats {
+        name: row.get(0),
+        blocks_hit: row.get(1),
+        blocks_read: row.get(2),
+        commits: row.get(3),
+        rollbacks: row.get(4),
+        deadlocks: row.get(5),
+    }
+}
+
+/// Whether the server is a standby, and its replay lag if so, from
+/// `health::RECOVERY_SQL`
+fn recovery(row: &tokio_postgres::Row) -> (bool, Option<std::time::Duration>) {
+    let standby: bool = row.get(0);
+    let lag = if standby {
+        Session::duration_from_secs(row.get(1))
+    } else {
+        None
+    };
+    (standby, lag)
+}
+
+/// A `pid, user, seconds, query, blocking pids` row from the health queries
+fn server_query(row: &tokio_postgres::Row) -> ServerQuery {
+    ServerQuery {
+        pid: row.get(0),
+        user: row.get(1),
+        duration: Session::duration_from_secs(row.get(2)),
+        query: row.get::<_, Option<String>>(3).filter(|q| !q.is_empty()),
+        blocked_by: row.get(4),
+    }
+}
+
+/// Flag the tables the current role can't SELECT from
+fn mark_select_denied(tables: &mut [Table], denied: &HashSet<String>) {
+    for table in tables {
+        table.select_denied = denied.contains(&table.name);
+    }
+}
+
 /// Map tokio_postgres Type to our DataType enum
 fn pg_type_to_datatype(pg_type: &Type) -> DataType {
     match *pg_type {
@@ -1499,6 +2609,28 @@ fn datatype_from_format_type(type_name: &str) -> DataType {
     }
 }
 
+/// `sql` with `:name` placeholders rewritten to `$n` when there are values
+/// to bind (otherwise run as written)
+fn positional_sql<'a>(sql: &'a str, params: &[Option<String>]) -> DbResult<Cow<'a, str>> {
+    if params.is_empty() {
+        return Ok(Cow::Borrowed(sql));
+    }
+    let positional = find_placeholders(sql).map_err(|message| DbError::QueryFailed {
+        message,
+        position: None,
+        code: None,
+    })?;
+    Ok(Cow::Owned(positional.sql))
+}
+
+/// Whether a query failed because its prepared statement went missing (or
+/// collided with another client's) between prepare and execute — what a
+/// transaction-pooling proxy such as pgbouncer does to prepared statements
+fn is_pooler_statement_error(message: &str) -> bool {
+    message.starts_with("prepared statement")
+        && (message.ends_with("does not exist") || message.ends_with("already exists"))
+}
+
 /// Extract error information from a tokio_postgres error, preserving position if available.
 fn extract_query_error(e: tokio_postgres::Error) -> crate::error::DbError {
     if let Some(db_err) = e.as_db_error() {
@@ -1509,35 +2641,17 @@ fn extract_query_error(e: tokio_postgres::Error) -> crate::error::DbError {
         crate::error::DbError::QueryFailed {
             message: db_err.message().to_string(),
             position,
+            code: Some(db_err.code().code().to_string()),
         }
     } else {
         crate::error::DbError::QueryFailed {
             message: e.to_string(),
             position: None,
+            code: None,
         }
     }
 }
 
-/// Build a rustls ClientConfig that trusts OS certificates (with Mozilla roots as fallback)
-fn make_tls_config() -> rustls::ClientConfig {
-    let mut root_store = rustls::RootCertStore::empty();
-
-    let native_certs = rustls_native_certs::load_native_certs();
-    let mut loaded = 0;
-    for cert in native_certs.certs {
-        if root_store.add(cert).is_ok() {
-            loaded += 1;
-        }
-    }
-    if loaded == 0 {
-        root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
-    }
-
-    rustls::ClientConfig::builder()
-        .with_root_certificates(root_store)
-        .with_no_client_auth()
-}
-
 /// Extract a cell value from a tokio_postgres Row based on the column's DataType.
 ///
 /// This function attempts to extract values using the expected type first,
@@ -1866,9 +2980,9 @@ mod tests {
 
         let pks = HashSet::new();
         let fks = HashMap::new();
-        let row_counts = HashMap::new();
+        let stats = HashMap::new();
 
-        let tables = assemble_tables("public", names, columns, pks, fks, &row_counts);
+        let tables = assemble_tables("public", names, columns, pks, fks, &stats);
         assert_eq!(tables.len(), 2);
         assert_eq!(tables[0].name, "users");
         assert_eq!(tables[0].columns.len(), 2);
@@ -1900,9 +3014,9 @@ mod tests {
             },
         );
 
-        let row_counts = HashMap::new();
+        let stats = HashMap::new();
 
-        let tables = assemble_tables("public", names, columns, pks, fks, &row_counts);
+        let tables = assemble_tables("public", names, columns, pks, fks, &stats);
         assert_eq!(tables.len(), 1);
 
         let order_table = &tables[0];
@@ -1919,10 +3033,25 @@ mod tests {
         let columns = HashMap::new();
         let pks = HashSet::new();
         let fks = HashMap::new();
-        let row_counts = HashMap::new();
+        let stats = HashMap::new();
 
-        let tables = assemble_tables("public", names, columns, pks, fks, &row_counts);
+        let tables = assemble_tables("public", names, columns, pks, fks, &stats);
         assert_eq!(tables.len(), 1);
         assert!(tables[0].columns.is_empty());
     }
+
+    // ── is_pooler_statement_error ────────────────────────────────
+
+    #[test]
+    fn test_pooler_statement_errors() {
+        assert!(is_pooler_statement_error(
+            "prepared statement \"s0\" does not exist"
+        ));
+        assert!(is_pooler_statement_error(
+            "prepared statement \"s3\" already exists"
+        ));
+        assert!(!is_pooler_statement_error(
+            "relation \"users\" does not exist"
+        ));
+    }
 }
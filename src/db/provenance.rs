@@ -0,0 +1,316 @@
+//! Query result provenance
+//!
+//! Records which table a result set was read from so individual cells can be
+//! written back with a generated `UPDATE`. Only simple single-table SELECTs
+//! whose results include the table's whole primary key are editable.
+
+use crate::db::sql_limit::tokenize_outer;
+use crate::db::types::{CellValue, Row};
+use crate::sql::{quote_ident, quote_qualified};
+
+/// Outer-level keywords that make a SELECT more than a plain read of one table
+const NON_SIMPLE_KEYWORDS: &[&str] = &[
+    "JOIN",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+    "GROUP",
+    "HAVING",
+    "DISTINCT",
+    "WINDOW",
+];
+
+/// Whether `sql` is a plain `SELECT ... FROM table` (no joins, set operations,
+/// grouping, or DISTINCT at the outer level), so each result row is exactly
+/// one table row.
+pub fn is_simple_select(sql: &str) -> bool {
+    let tokens = tokenize_outer(sql);
+    tokens.first().is_some_and(|t| t == "SELECT")
+        && tokens.iter().any(|t| t == "FROM")
+        && !tokens
+            .iter()
+            .any(|t| NON_SIMPLE_KEYWORDS.contains(&t.as_str()))
+}
+
+/// Table column a result column was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceColumn {
+    /// Column name in the table
+    pub name: String,
+    /// Column type as formatted by `format_type()` (used to cast parameters)
+    pub type_name: String,
+}
+
+/// The table a result set's rows map onto, one result row per table row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultSource {
+    /// Schema of the source table
+    pub schema: String,
+    /// Source table name
+    pub table: String,
+    /// Per result column: the table column it reads (None for computed columns)
+    pub columns: Vec<Option<SourceColumn>>,
+    /// Result column indices holding the table's primary key
+    pub key_columns: Vec<usize>,
+}
+
+/// A generated, parameterized UPDATE for a single cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellUpdate {
+    /// `UPDATE ... SET col = $1 WHERE key = $2 ... RETURNING ...`
+    pub sql: String,
+    /// Text parameters: the new value, then the key values (None = NULL)
+    pub params: Vec<Option<String>>,
+    /// Result column indices filled by the RETURNING clause, in order
+    pub returning: Vec<usize>,
+}
+
+impl ResultSource {
+    /// Build a source for results whose columns map to `columns`. Returns
+    /// None unless every `primary_key` column appears in the results.
+    pub fn new(
+        schema: String,
+        table: String,
+        columns: Vec<Option<SourceColumn>>,
+        primary_key: &[String],
+    ) -> Option<Self> {
+        if primary_key.is_empty() {
+            return None;
+        }
+        let key_columns = primary_key
+            .iter()
+            .map(|key| {
+                columns
+                    .iter()
+                    .position(|c| c.as_ref().is_some_and(|c| &c.name == key))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            schema,
+            table,
+            columns,
+            key_columns,
+        })
+    }
+
+    /// Table column behind result column `col`, if it isn't computed
+    pub fn column(&self, col: usize) -> Option<&SourceColumn> {
+        self.columns.get(col).and_then(|c| c.as_ref())
+    }
+
+    /// Generate the UPDATE that sets result column `col` of `row` to `value`
+    /// (None = NULL). The statement returns every table-backed result column
+    /// so the row can be refreshed from what was actually stored.
+    pub fn update_cell(
+        &self,
+        row: &Row,
+        col: usize,
+        value: Option<&str>,
+    ) -> Result<CellUpdate, String> {
+        let target = self.column(col).ok_or("Computed columns can't be edited")?;
+
+        let mut params = vec![value.map(str::to_string)];
+        let mut conditions = Vec::with_capacity(self.key_columns.len());
+        for &key_col in &self.key_columns {
+            let key = self.column(key_col).expect("key columns are table-backed");
+            let text = row
+                .values
+                .get(key_col)
+                .and_then(key_param)
+                .ok_or_else(|| format!("Can't identify row: unusable key {}", key.name))?;
+            params.push(Some(text));
+            conditions.push(format!(
+                "{} = {}",
+                quote_ident(&key.name),
+                text_param(params.len(), &key.type_name)
+            ));
+        }
+
+        let returning: Vec<usize> = (0..self.columns.len())
+            .filter(|&c| self.columns[c].is_some())
+            .collect();
+        let returning_list: Vec<String> = returning
+            .iter()
+            .filter_map(|&c| self.column(c))
+            .map(|c| quote_ident(&c.name))
+            .collect();
+
+        let sql = format!(
+            "UPDATE {} SET {} = {} WHERE {} RETURNING {}",
+            quote_qualified(&self.schema, &self.table),
+            quote_ident(&target.name),
+            text_param(1, &target.type_name),
+            conditions.join(" AND "),
+            returning_list.join(", ")
+        );
+        Ok(CellUpdate {
+            sql,
+            params,
+            returning,
+        })
+    }
+}
+
+/// Placeholder for a text parameter cast to the column's type, e.g.
+/// `$1::text::integer`, so every parameter can be sent as a string.
+fn text_param(index: usize, type_name: &str) -> String {
+    format!("${}::text::{}", index, type_name)
+}
+
+/// Text form of a key value for a WHERE parameter. NULL, binary, and array
+/// keys can't be round-tripped reliably through their display form.
+fn key_param(cell: &CellValue) -> Option<String> {
+    match cell {
+        CellValue::Null | CellValue::Binary(_) | CellValue::Array(_) => None,
+        other => Some(other.display_string(usize::MAX)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn col(name: &str, type_name: &str) -> Option<SourceColumn> {
+        Some(SourceColumn {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+        })
+    }
+
+    fn users_source() -> ResultSource {
+        ResultSource::new(
+            "public".to_string(),
+            "users".to_string(),
+            vec![col("id", "integer"), col("name", "text"), None],
+            &["id".to_string()],
+        )
+        .unwrap()
+    }
+
+    fn row(values: Vec<CellValue>) -> Row {
+        Row { values }
+    }
+
+    #[test]
+    fn test_is_simple_select() {
+        assert!(is_simple_select("SELECT * FROM users"));
+        assert!(is_simple_select(
+            "select id, name from public.users where id > 5 order by name limit 10"
+        ));
+        assert!(is_simple_select(
+            "SELECT * FROM users WHERE id IN (SELECT user_id FROM a JOIN b ON true)"
+        ));
+        assert!(!is_simple_select("SELECT 1"));
+        assert!(!is_simple_select("SELECT * FROM a JOIN b ON a.id = b.id"));
+        assert!(!is_simple_select("SELECT id FROM a UNION SELECT id FROM b"));
+        assert!(!is_simple_select("SELECT DISTINCT name FROM users"));
+        assert!(!is_simple_select(
+            "SELECT name, count(*) FROM users GROUP BY name"
+        ));
+        assert!(!is_simple_select("WITH x AS (SELECT 1) SELECT * FROM x"));
+        assert!(!is_simple_select("UPDATE users SET name = 'x'"));
+    }
+
+    #[test]
+    fn test_source_requires_primary_key_in_results() {
+        let source = ResultSource::new(
+            "public".to_string(),
+            "users".to_string(),
+            vec![col("name", "text")],
+            &["id".to_string()],
+        );
+        assert!(source.is_none());
+
+        let no_pk = ResultSource::new(
+            "public".to_string(),
+            "logs".to_string(),
+            vec![col("msg", "text")],
+            &[],
+        );
+        assert!(no_pk.is_none());
+
+        assert_eq!(users_source().key_columns, vec![0]);
+    }
+
+    #[test]
+    fn test_update_cell_sql_and_params() {
+        let source = users_source();
+        let update = source
+            .update_cell(
+                &row(vec![
+                    CellValue::Integer(42),
+                    CellValue::Text("old".into()),
+                    CellValue::Integer(1),
+                ]),
+                1,
+                Some("new"),
+            )
+            .unwrap();
+        assert_eq!(
+            update.sql,
+            "UPDATE \"public\".\"users\" SET \"name\" = $1::text::text \
+             WHERE \"id\" = $2::text::integer RETURNING \"id\", \"name\""
+        );
+        assert_eq!(
+            update.params,
+            vec![Some("new".to_string()), Some("42".to_string())]
+        );
+        assert_eq!(update.returning, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_update_cell_null_and_composite_key() {
+        let source = ResultSource::new(
+            "app".to_string(),
+            "Members".to_string(),
+            vec![
+                col("org_id", "uuid"),
+                col("user_id", "bigint"),
+                col("role", "character varying(20)"),
+            ],
+            &["org_id".to_string(), "user_id".to_string()],
+        )
+        .unwrap();
+        let update = source
+            .update_cell(
+                &row(vec![
+                    CellValue::Uuid("a0ee-1".into()),
+                    CellValue::Integer(7),
+                    CellValue::Text("admin".into()),
+                ]),
+                2,
+                None,
+            )
+            .unwrap();
+        assert!(
+            update
+                .sql
+                .starts_with("UPDATE \"app\".\"Members\" SET \"role\"")
+        );
+        assert!(
+            update
+                .sql
+                .contains("WHERE \"org_id\" = $2::text::uuid AND \"user_id\" = $3::text::bigint")
+        );
+        assert_eq!(update.params[0], None);
+        assert_eq!(update.params[1].as_deref(), Some("a0ee-1"));
+    }
+
+    #[test]
+    fn test_update_cell_rejects_computed_column_and_null_key() {
+        let source = users_source();
+        let values = vec![
+            CellValue::Integer(1),
+            CellValue::Text("a".into()),
+            CellValue::Integer(2),
+        ];
+        assert!(source.update_cell(&row(values), 2, Some("x")).is_err());
+
+        let null_key = vec![
+            CellValue::Null,
+            CellValue::Text("a".into()),
+            CellValue::Integer(2),
+        ];
+        assert!(source.update_cell(&row(null_key), 1, Some("x")).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Logical replication from `pg_publication` and `pg_subscription`
+//!
+//! `/replication` lists the publications in the current database with the
+//! tables they publish, and its subscriptions with whether their apply
+//! worker is running and how recently it heard from the publisher.
+
+use std::time::Duration;
+
+/// Publications with the operations they publish
+pub const PUBLICATIONS_SQL: &str = "\
+SELECT pubname, pg_get_userbyid(pubowner), puballtables, \
+       pubinsert, pubupdate, pubdelete, pubtruncate \
+FROM pg_publication \
+ORDER BY pubname";
+
+/// The tables each publication publishes
+pub const PUBLICATION_TABLES_SQL: &str = "\
+SELECT pubname, schemaname || '.' || tablename \
+FROM pg_publication_tables \
+ORDER BY pubname, schemaname, tablename";
+
+/// The current database's subscriptions with their apply worker (not the
+/// per-table sync workers) and the count of tables still being copied.
+/// `subconninfo` may hold a password and is left out.
+pub const SUBSCRIPTIONS_SQL: &str = "\
+SELECT s.subname, pg_get_userbyid(s.subowner), s.subenabled, s.subpublication, \
+       w.pid, w.received_lsn::text, \
+       EXTRACT(EPOCH FROM now() - w.last_msg_receipt_time)::float8, \
+       (SELECT count(*) FROM pg_subscription_rel r \
+        WHERE r.srsubid = s.oid AND r.srsubstate <> 'r') \
+FROM pg_subscription s \
+LEFT JOIN LATERAL ( \
+    SELECT pid, received_lsn, last_msg_receipt_time \
+    FROM pg_stat_subscription st \
+    WHERE st.subid = s.oid AND st.relid IS NULL \
+    ORDER BY st.received_lsn IS NULL, st.pid \
+    LIMIT 1) w ON true \
+WHERE s.subdbid = (SELECT oid FROM pg_database WHERE datname = current_database()) \
+ORDER BY s.subname";
+
+/// The publications and subscriptions of the current database
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Replication {
+    pub publications: Vec<Publication>,
+    pub subscriptions: Vec<Subscription>,
+}
+
+/// A row of `pg_publication`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Publication {
+    pub name: String,
+    pub owner: Option<String>,
+    /// `FOR ALL TABLES`
+    pub all_tables: bool,
+    /// `insert`, `update`, `delete`, and `truncate`, as published
+    pub operations: Vec<String>,
+    /// `schema.table`, from `pg_publication_tables`
+    pub tables: Vec<String>,
+}
+
+impl Publication {
+    /// The published operations from the `pubinsert`, `pubupdate`,
+    /// `pubdelete`, and `pubtruncate` flags
+    pub fn operations(flags: [bool; 4]) -> Vec<String> {
+        ["insert", "update", "delete", "truncate"]
+            .iter()
+            .zip(flags)
+            .filter(|(_, on)| *on)
+            .map(|(op, _)| op.to_string())
+            .collect()
+    }
+}
+
+/// A row of `pg_subscription` with its apply worker from
+/// `pg_stat_subscription`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Subscription {
+    pub name: String,
+    pub owner: Option<String>,
+    pub enabled: bool,
+    /// Publications it subscribes to
+    pub publications: Vec<String>,
+    /// The apply worker's pid, when one is running
+    pub worker_pid: Option<i32>,
+    /// Last WAL position received
+    pub received_lsn: Option<String>,
+    /// Time since the last message from the publisher
+    pub last_message: Option<Duration>,
+    /// Tables whose initial copy isn't done yet
+    pub syncing_tables: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operations() {
+        assert_eq!(
+            Publication::operations([true, true, true, true]),
+            ["insert", "update", "delete", "truncate"]
+        );
+        assert_eq!(
+            Publication::operations([true, false, false, true]),
+            ["insert", "truncate"]
+        );
+        assert!(Publication::operations([false; 4]).is_empty());
+    }
+}
@@ -3,9 +3,10 @@
 //! Structures for representing database schema hierarchies.
 
 use crate::db::types::DataType;
+use serde::{Deserialize, Serialize};
 
 /// A collection with pagination metadata
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaginatedVec<T> {
     /// Items in the current page
     pub items: Vec<T>,
@@ -80,14 +81,20 @@ impl<'a, T> IntoIterator for &'a PaginatedVec<T> {
 }
 
 /// Complete database schema tree
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SchemaTree {
     /// All schemas in the database (with pagination metadata)
     pub schemas: PaginatedVec<Schema>,
+    /// Installed extensions (database-wide, not tied to a schema)
+    #[serde(default)]
+    pub extensions: Vec<Extension>,
+    /// Databases on the server that accept connections
+    #[serde(default)]
+    pub databases: Vec<ServerDatabase>,
 }
 
 /// A database schema (namespace)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Schema {
     /// Schema name
     pub name: String,
@@ -99,21 +106,64 @@ pub struct Schema {
     pub indexes: PaginatedVec<Index>,
     /// Functions and procedures in this schema (with pagination metadata)
     pub functions: PaginatedVec<Function>,
+    /// Sequences in this schema
+    #[serde(default)]
+    pub sequences: PaginatedVec<Sequence>,
+    /// Enum types in this schema
+    #[serde(default)]
+    pub enums: PaginatedVec<EnumType>,
+    /// Domains in this schema
+    #[serde(default)]
+    pub domains: PaginatedVec<Domain>,
+    /// Whether the objects above have been introspected. The tree starts
+    /// with schema names only and loads a schema the first time it's
+    /// expanded.
+    #[serde(default = "default_loaded")]
+    pub loaded: bool,
+}
+
+impl Schema {
+    /// A schema known by name only, with its objects still to be loaded
+    pub fn unloaded(name: String) -> Self {
+        Self {
+            name,
+            tables: PaginatedVec::default(),
+            views: PaginatedVec::default(),
+            indexes: PaginatedVec::default(),
+            functions: PaginatedVec::default(),
+            sequences: PaginatedVec::default(),
+            enums: PaginatedVec::default(),
+            domains: PaginatedVec::default(),
+            loaded: false,
+        }
+    }
+}
+
+/// Schemas cached before lazy loading always held their objects
+fn default_loaded() -> bool {
+    true
 }
 
 /// A database table
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Table {
     /// Table name
     pub name: String,
     /// Columns in this table
     pub columns: Vec<Column>,
-    /// Estimated row count from pg_stat_user_tables (None for views)
+    /// Estimated row count from pg_class.reltuples (None for views and
+    /// tables never analyzed)
     pub row_count: Option<i64>,
+    /// Total on-disk size including indexes and TOAST (None for views)
+    #[serde(default)]
+    pub size_bytes: Option<i64>,
+    /// The current role can't SELECT from it (`has_table_privilege`)
+    #[serde(default)]
+    pub select_denied: bool,
 }
 
 /// A table column
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Column {
     /// Column name
     pub name: String,
@@ -125,8 +175,22 @@ pub struct Column {
     pub foreign_key: Option<ForeignKey>,
 }
 
+/// A column as the insert-row form shows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsertColumn {
+    pub name: String,
+    /// `format_type()` of the column, e.g. `character varying(40)`
+    pub type_name: String,
+    pub data_type: DataType,
+    pub not_null: bool,
+    /// Default expression (`identity` for identity columns)
+    pub default: Option<String>,
+    /// Generated, or an identity `GENERATED ALWAYS`: the server fills it in
+    pub generated: bool,
+}
+
 /// A foreign key reference from a column to another table's column
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForeignKey {
     /// Target table (e.g. "users" or "other_schema.users")
     pub target_table: String,
@@ -135,7 +199,7 @@ pub struct ForeignKey {
 }
 
 /// A database index
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Index {
     /// Index name (e.g. "users_pkey")
     pub name: String,
@@ -150,7 +214,7 @@ pub struct Index {
 }
 
 /// A stored function or procedure
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Function {
     /// Function name
     pub name: String,
@@ -160,11 +224,69 @@ pub struct Function {
     pub return_type: String,
 }
 
+impl Function {
+    /// Whether this is a set-returning function (`SETOF ...` or `TABLE(...)`),
+    /// which can be used like a table after FROM.
+    pub fn returns_set(&self) -> bool {
+        let ret = self.return_type.trim_start().to_ascii_uppercase();
+        ret.starts_with("SETOF ") || ret.starts_with("TABLE(") || ret.starts_with("TABLE (")
+    }
+}
+
+/// A sequence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sequence {
+    /// Sequence name
+    pub name: String,
+    /// Last value handed out (None if never used or not readable)
+    pub last_value: Option<i64>,
+}
+
+/// An enum type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnumType {
+    /// Type name
+    pub name: String,
+    /// Labels in sort order
+    pub labels: Vec<String>,
+}
+
+/// A domain (a base type with optional constraints)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Domain {
+    /// Domain name
+    pub name: String,
+    /// Underlying type (e.g. "character varying(255)")
+    pub base_type: String,
+}
+
+/// An installed extension
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Extension {
+    /// Extension name (e.g. "pgcrypto")
+    pub name: String,
+    /// Installed version
+    pub version: String,
+    /// Schema holding the extension's objects
+    pub schema: String,
+}
+
+/// A database on the connected server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerDatabase {
+    /// Database name
+    pub name: String,
+    /// Whether this is the database the connection is using
+    pub is_current: bool,
+}
+
 impl SchemaTree {
     /// Create a new empty schema tree
     pub fn new() -> Self {
         Self {
             schemas: PaginatedVec::default(),
+            extensions: Vec::new(),
+            databases: Vec::new(),
         }
     }
 }
@@ -185,6 +307,21 @@ mod tests {
         assert!(tree.schemas.is_empty());
     }
 
+    #[test]
+    fn test_function_returns_set() {
+        let func = |ret: &str| Function {
+            name: "f".to_string(),
+            args: String::new(),
+            return_type: ret.to_string(),
+        };
+        assert!(func("SETOF integer").returns_set());
+        assert!(func("TABLE(id integer)").returns_set());
+        assert!(func("setof record").returns_set());
+        assert!(!func("integer").returns_set());
+        assert!(!func("void").returns_set());
+        assert!(!func("tablefunc_result").returns_set());
+    }
+
     #[test]
     fn test_paginated_vec_new() {
         let pv: PaginatedVec<i32> = PaginatedVec::new(vec![1, 2, 3], 10);
@@ -53,7 +53,56 @@ pub fn analyze_limit(sql: &str) -> LimitAnalysis {
 
 /// Extract uppercase keyword tokens at parenthesis depth 0,
 /// skipping comments, strings, and quoted identifiers.
-fn tokenize_outer(sql: &str) -> Vec<String> {
+pub(crate) fn tokenize_outer(sql: &str) -> Vec<String> {
+    scan_outer(sql, false)
+}
+
+/// `tokenize_outer` per statement of a script, split at top-level `;`.
+/// Empty statements are dropped.
+pub(crate) fn outer_statements(sql: &str) -> Vec<Vec<String>> {
+    scan_outer(sql, true)
+        .split(|t| t == ";")
+        .filter(|s| !s.is_empty())
+        .map(<[String]>::to_vec)
+        .collect()
+}
+
+/// The command a statement runs, from its top-level tokens: the first
+/// keyword, or after a WITH the statement the CTEs lead into
+pub(crate) fn statement_verb(tokens: &[String]) -> Option<&str> {
+    let first = tokens.first()?;
+    if first != "WITH" {
+        return Some(first);
+    }
+    tokens
+        .iter()
+        .find(|t| {
+            matches!(
+                t.as_str(),
+                "SELECT" | "INSERT" | "UPDATE" | "DELETE" | "MERGE"
+            )
+        })
+        .map(String::as_str)
+}
+
+/// `statement_verb` of the last statement in a script, which is the one
+/// whose results are shown
+pub fn last_verb(sql: &str) -> Option<String> {
+    outer_statements(sql)
+        .last()
+        .and_then(|tokens| statement_verb(tokens))
+        .map(str::to_string)
+}
+
+/// Whether the last statement of a script is a query that pagination can
+/// add LIMIT/OFFSET to (a SELECT, VALUES, or TABLE)
+pub fn returns_rows(sql: &str) -> bool {
+    last_verb(sql).is_some_and(|v| matches!(v.as_str(), "SELECT" | "VALUES" | "TABLE"))
+}
+
+/// Scanner behind `tokenize_outer`; with `semicolons`, each top-level `;`
+/// is kept as a token
+fn scan_outer(sql: &str, semicolons: bool) -> Vec<String> {
     let mut tokens = Vec::new();
     let chars: Vec<char> = sql.chars().collect();
     let len = chars.len();
@@ -155,6 +204,12 @@ fn tokenize_outer(sql: &str) -> Vec<String> {
             continue;
         }
 
+        if semicolons && depth == 0 && ch == ';' {
+            tokens.push(";".to_string());
+            i += 1;
+            continue;
+        }
+
         // At depth 0, collect keyword tokens
         if depth == 0 && ch.is_ascii_alphabetic() {
             let start = i;
@@ -174,7 +229,7 @@ fn tokenize_outer(sql: &str) -> Vec<String> {
 
 /// Check if position `i` starts a dollar-quote tag: $, optional identifier chars, $
 /// Returns the length of the tag (including both $ delimiters) or None.
-fn find_dollar_tag(chars: &[char], i: usize) -> Option<usize> {
+pub(super) fn find_dollar_tag(chars: &[char], i: usize) -> Option<usize> {
     if chars[i] != '$' {
         return None;
     }
@@ -359,6 +414,37 @@ mod tests {
         assert!(a.has_outer_limit);
     }
 
+    #[test]
+    fn test_outer_statements_split_at_top_level_semicolons() {
+        let statements = outer_statements("SELECT ';' FROM t; ; DELETE FROM (x; y)\n");
+        assert_eq!(
+            statements,
+            vec![
+                vec!["SELECT".to_string(), "FROM".to_string(), "T".to_string()],
+                vec!["DELETE".to_string(), "FROM".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_last_verb_and_returns_rows() {
+        assert_eq!(
+            last_verb("BEGIN; update t set a = 1").as_deref(),
+            Some("UPDATE")
+        );
+        assert_eq!(
+            last_verb("WITH x AS (SELECT 1) DELETE FROM t").as_deref(),
+            Some("DELETE")
+        );
+        assert_eq!(last_verb("-- nothing"), None);
+        assert!(returns_rows(
+            "WITH x AS (DELETE FROM t RETURNING *) SELECT * FROM x"
+        ));
+        assert!(returns_rows("VALUES (1), (2)"));
+        assert!(!returns_rows("UPDATE t SET a = 1 WHERE id = 2"));
+        assert!(!returns_rows("CREATE TABLE t (id int)"));
+    }
+
     #[test]
     fn test_explain_analyze() {
         let a = analyze_limit("EXPLAIN ANALYZE SELECT * FROM users");
@@ -0,0 +1,350 @@
+//! Guidance for server errors by SQLSTATE
+//!
+//! The server's message says what went wrong; the SQLSTATE says what kind
+//! of problem it is. `hint` turns the common codes (and, failing that,
+//! their class) into a line on what to do about it, suggesting the closest
+//! name from the schema tree when a relation, column, schema, or function
+//! isn't found.
+
+use crate::db::schema::SchemaTree;
+
+/// A line of guidance for an error with SQLSTATE `code` and server
+/// `message`, or None when there's nothing to add
+pub fn hint(code: &str, message: &str, schema: Option<&SchemaTree>) -> Option<String> {
+    let hint = match code {
+        "42P01" => not_found("relation", quoted(message), schema.map(relation_names)),
+        "42703" => {
+            let names = schema.map(|s| column_names(s, relation_of(message)));
+            not_found("column", column_of(message), names)
+        }
+        "3F000" => not_found(
+            "schema",
+            quoted(message),
+            schema.map(|s| s.schemas.items.iter().map(|s| s.name.clone()).collect()),
+        ),
+        "42883" => {
+            let name = message
+                .strip_prefix("function ")
+                .and_then(|rest| rest.split('(').next())
+                .map(|name| name.rsplit('.').next().unwrap_or(name).to_string());
+            match suggestion(name.as_deref(), schema.map(function_names)) {
+                Some(name) => format!("function not found \u{2014} did you mean {}?", name),
+                None => "no function matches the name and argument types \u{2014} \
+                         add explicit casts if the arguments are literals"
+                    .to_string(),
+            }
+        }
+        "42P02" => "undefined parameter \u{2014} bind a value for each $n".to_string(),
+        "42601" => "syntax error \u{2014} the cursor is at the position the server \
+                    reported"
+            .to_string(),
+        "42501" => "permission denied \u{2014} the table's owner or a superuser can GRANT \
+                    access"
+            .to_string(),
+        "42702" => "ambiguous column \u{2014} qualify it with its table name or alias".to_string(),
+        "42804" | "42846" => {
+            "type mismatch \u{2014} add an explicit cast (value::type)".to_string()
+        }
+        "22P02" => {
+            "invalid input for the column's type \u{2014} check the value's format".to_string()
+        }
+        "22001" => {
+            "value too long for the column \u{2014} shorten it or widen the column".to_string()
+        }
+        "22003" => "number out of range for the column's type".to_string(),
+        "22012" => "division by zero \u{2014} NULLIF(divisor, 0) turns it into NULL".to_string(),
+        "23505" => "duplicate key \u{2014} a row with this key already exists; \
+                    INSERT ... ON CONFLICT can update it instead"
+            .to_string(),
+        "23503" => "foreign key violation \u{2014} the referenced row is missing, or \
+                    other rows still reference this one"
+            .to_string(),
+        "23502" => "a NOT NULL column got no value \u{2014} supply one or give the column \
+                    a default"
+            .to_string(),
+        "23514" => "check constraint violated \u{2014} the row fails the table's CHECK".to_string(),
+        "25P02" => {
+            "the transaction failed \u{2014} /rollback to keep working on this tab".to_string()
+        }
+        "25006" => "read-only transaction \u{2014} writes need a read-write connection".to_string(),
+        "40001" => "serialization failure \u{2014} another transaction got there first; \
+                    run it again"
+            .to_string(),
+        "40P01" => {
+            "deadlock \u{2014} the server cancelled this transaction; run it again".to_string()
+        }
+        "55P03" => "lock not available \u{2014} another session holds it".to_string(),
+        "57014" => "the query was cancelled (by request or statement_timeout)".to_string(),
+        "53100" => "the server is out of disk space".to_string(),
+        "53200" => "the server ran out of memory \u{2014} try a smaller result or lower \
+                    work_mem"
+            .to_string(),
+        "53300" => "too many connections \u{2014} close idle sessions or use a pooler".to_string(),
+        _ => class_hint(code)?.to_string(),
+    };
+    Some(hint)
+}
+
+/// Guidance for a whole SQLSTATE class (the first two characters)
+fn class_hint(code: &str) -> Option<&'static str> {
+    Some(match code.get(..2)? {
+        "08" => "connection problem \u{2014} the next query reconnects",
+        "22" => "a value doesn't fit its type or format",
+        "23" => "the change breaks one of the table's constraints",
+        "28" => "authentication failed \u{2014} check the user and password",
+        "40" => "the transaction was rolled back \u{2014} run it again",
+        "42" => "syntax or access problem \u{2014} check the names and your privileges",
+        "53" => "the server is short on resources",
+        "54" => "a server limit was exceeded",
+        "57" => "the server stopped the query (cancelled, shutting down, or restarting)",
+        "58" => "the server hit a system error",
+        "XX" => "internal server error",
+        _ => return None,
+    })
+}
+
+/// "<kind> not found", with the closest known name when there is one
+fn not_found(kind: &str, name: Option<String>, candidates: Option<Vec<String>>) -> String {
+    match suggestion(name.as_deref(), candidates) {
+        Some(name) => format!("{} not found \u{2014} did you mean {}?", kind, name),
+        None if kind == "relation" => "relation not found \u{2014} check the name and the \
+                                       search_path, or /refresh if it was just created"
+            .to_string(),
+        None => format!("{} not found", kind),
+    }
+}
+
+/// The candidate closest to `name`, if it's close enough to be a typo
+fn suggestion(name: Option<&str>, candidates: Option<Vec<String>>) -> Option<String> {
+    let name = name?.to_lowercase();
+    let unqualified = name.rsplit('.').next().unwrap_or(&name);
+    let max_distance = (unqualified.chars().count() / 3).clamp(1, 3);
+    candidates?
+        .into_iter()
+        .filter_map(|candidate| {
+            let bare = candidate.rsplit('.').next().unwrap_or(&candidate);
+            let distance = edit_distance(unqualified, &bare.to_lowercase());
+            (distance > 0 && distance <= max_distance).then_some((distance, candidate))
+        })
+        .min_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)))
+        .map(|(_, candidate)| candidate)
+}
+
+/// Edit distance between two strings, by character, counting a swap of
+/// adjacent characters (a common typo) as one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // d[i][j]: distance between the first i chars of a and first j of b
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j - 1] + cost)
+                .min(d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// The first double-quoted name in a server message
+fn quoted(message: &str) -> Option<String> {
+    let start = message.find('"')? + 1;
+    let len = message[start..].find('"')?;
+    Some(message[start..start + len].to_string())
+}
+
+/// The column in `column "x" does not exist` or `column t.x does not exist`
+fn column_of(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("column ")?;
+    if rest.starts_with('"') {
+        return quoted(rest);
+    }
+    let name = rest.split_whitespace().next()?;
+    Some(name.rsplit('.').next().unwrap_or(name).to_string())
+}
+
+/// The relation in `column "x" of relation "t" does not exist`
+fn relation_of(message: &str) -> Option<String> {
+    let (_, rest) = message.split_once(" of relation ")?;
+    quoted(rest)
+}
+
+/// Tables and views, qualified outside `public`
+fn relation_names(schema: &SchemaTree) -> Vec<String> {
+    schema
+        .schemas
+        .items
+        .iter()
+        .flat_map(|s| {
+            s.tables
+                .items
+                .iter()
+                .chain(&s.views.items)
+                .map(move |t| qualified(&s.name, &t.name))
+        })
+        .collect()
+}
+
+/// Columns of `relation` (every loaded table's columns when None)
+fn column_names(schema: &SchemaTree, relation: Option<String>) -> Vec<String> {
+    let mut names: Vec<String> = schema
+        .schemas
+        .items
+        .iter()
+        .flat_map(|s| s.tables.items.iter().chain(&s.views.items))
+        .filter(|t| relation.as_ref().is_none_or(|r| t.name == *r))
+        .flat_map(|t| t.columns.iter().map(|c| c.name.clone()))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn function_names(schema: &SchemaTree) -> Vec<String> {
+    schema
+        .schemas
+        .items
+        .iter()
+        .flat_map(|s| {
+            s.functions
+                .items
+                .iter()
+                .map(move |f| qualified(&s.name, &f.name))
+        })
+        .collect()
+}
+
+fn qualified(schema: &str, name: &str) -> String {
+    if schema == "public" {
+        name.to_string()
+    } else {
+        format!("{}.{}", schema, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::{Column, PaginatedVec, Schema, Table};
+    use crate::db::types::DataType;
+
+    fn shop() -> SchemaTree {
+        let table = |name: &str, columns: &[&str]| Table {
+            name: name.to_string(),
+            columns: columns
+                .iter()
+                .map(|c| Column {
+                    name: c.to_string(),
+                    data_type: DataType::Text,
+                    is_primary_key: false,
+                    foreign_key: None,
+                })
+                .collect(),
+            row_count: None,
+            size_bytes: None,
+            select_denied: false,
+        };
+        let mut public = Schema::unloaded("public".to_string());
+        public.tables = PaginatedVec::from_vec(vec![
+            table("orders", &["id", "customer_id", "total"]),
+            table("customers", &["id", "email"]),
+        ]);
+        let mut audit = Schema::unloaded("audit".to_string());
+        audit.tables = PaginatedVec::from_vec(vec![table("order_log", &["id"])]);
+        SchemaTree {
+            schemas: PaginatedVec::from_vec(vec![public, audit]),
+            ..SchemaTree::new()
+        }
+    }
+
+    #[test]
+    fn test_relation_not_found_suggests_closest_table() {
+        let schema = shop();
+        assert_eq!(
+            hint("42P01", "relation \"ordrs\" does not exist", Some(&schema)).unwrap(),
+            "relation not found \u{2014} did you mean orders?"
+        );
+        assert_eq!(
+            hint(
+                "42P01",
+                "relation \"ordr_log\" does not exist",
+                Some(&schema)
+            )
+            .unwrap(),
+            "relation not found \u{2014} did you mean audit.order_log?"
+        );
+        // Nothing close: general advice instead
+        assert!(
+            hint(
+                "42P01",
+                "relation \"invoices\" does not exist",
+                Some(&schema)
+            )
+            .unwrap()
+            .contains("search_path")
+        );
+    }
+
+    #[test]
+    fn test_column_not_found_suggests_closest_column() {
+        let schema = shop();
+        assert_eq!(
+            hint("42703", "column \"emial\" does not exist", Some(&schema)).unwrap(),
+            "column not found \u{2014} did you mean email?"
+        );
+        assert_eq!(
+            hint("42703", "column o.totl does not exist", Some(&schema)).unwrap(),
+            "column not found \u{2014} did you mean total?"
+        );
+        // Limited to the named relation's columns
+        assert_eq!(
+            hint(
+                "42703",
+                "column \"emal\" of relation \"orders\" does not exist",
+                Some(&schema)
+            )
+            .unwrap(),
+            "column not found"
+        );
+    }
+
+    #[test]
+    fn test_known_codes_and_class_fallback() {
+        assert!(
+            hint("23505", "duplicate key", None)
+                .unwrap()
+                .contains("ON CONFLICT")
+        );
+        assert!(
+            hint("25P02", "current transaction is aborted", None)
+                .unwrap()
+                .contains("/rollback")
+        );
+        // Unlisted code in a known class
+        assert_eq!(
+            hint("23P01", "conflicting key value", None).unwrap(),
+            "the change breaks one of the table's constraints"
+        );
+        assert_eq!(hint("P0001", "raised by a function", None), None);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("orders", "orders"), 0);
+        assert_eq!(edit_distance("ordrs", "orders"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("emial", "email"), 1);
+    }
+}
@@ -0,0 +1,234 @@
+//! TLS configuration for PostgreSQL connections
+//!
+//! Builds the rustls client config for a connection's `sslmode`. Server
+//! certificates are checked against `sslrootcert` when set, otherwise the OS
+//! trust store (Mozilla roots as fallback). `sslcert`/`sslkey` present a client
+//! certificate for mutual TLS, and `verify-ca` accepts a trusted chain whose
+//! certificate doesn't name the host.
+
+use crate::config::ConnectionConfig;
+use crate::config::connections::SslMode;
+use crate::error::{DbError, DbResult};
+use rustls::client::WebPkiServerVerifier;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{CertificateError, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Build the TLS config for `config`, or None when SSL is disabled.
+///
+/// Fails if a certificate or key file can't be read, or if only one of
+/// `sslcert`/`sslkey` is set.
+pub fn make_tls_config(config: &ConnectionConfig) -> DbResult<Option<rustls::ClientConfig>> {
+    if config.ssl_mode == SslMode::Disable {
+        return Ok(None);
+    }
+
+    let roots = match &config.ssl_root_cert {
+        Some(path) => load_root_certs(path)?,
+        None => system_roots(),
+    };
+
+    let builder = if config.ssl_mode == SslMode::VerifyCa {
+        let inner = WebPkiServerVerifier::builder(Arc::new(roots))
+            .build()
+            .map_err(|e| tls_error(format!("Invalid root certificates: {}", e)))?;
+        rustls::ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(SkipHostnameVerifier { inner }))
+    } else {
+        rustls::ClientConfig::builder().with_root_certificates(roots)
+    };
+
+    let tls = match (&config.ssl_cert, &config.ssl_key) {
+        (Some(cert), Some(key)) => builder
+            .with_client_auth_cert(load_cert_chain(cert)?, load_private_key(key)?)
+            .map_err(|e| tls_error(format!("Invalid client certificate: {}", e)))?,
+        (None, None) => builder.with_no_client_auth(),
+        (Some(_), None) => return Err(tls_error("sslcert requires sslkey".to_string())),
+        (None, Some(_)) => return Err(tls_error("sslkey requires sslcert".to_string())),
+    };
+    Ok(Some(tls))
+}
+
+/// Trust OS certificates, falling back to Mozilla roots if none load
+fn system_roots() -> RootCertStore {
+    let mut root_store = RootCertStore::empty();
+
+    let native_certs = rustls_native_certs::load_native_certs();
+    let mut loaded = 0;
+    for cert in native_certs.certs {
+        if root_store.add(cert).is_ok() {
+            loaded += 1;
+        }
+    }
+    if loaded == 0 {
+        root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    }
+    root_store
+}
+
+/// Load the CA certificates in `path` as the only trusted roots
+fn load_root_certs(path: &Path) -> DbResult<RootCertStore> {
+    let mut root_store = RootCertStore::empty();
+    for cert in load_cert_chain(path)? {
+        root_store.add(cert).map_err(|e| {
+            tls_error(format!(
+                "Invalid CA certificate in {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+    }
+    Ok(root_store)
+}
+
+/// Read every PEM certificate in `path`
+fn load_cert_chain(path: &Path) -> DbResult<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| tls_error(format!("Can't read {}: {}", path.display(), e)))?;
+    if certs.is_empty() {
+        return Err(tls_error(format!(
+            "No certificates found in {}",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+/// Read the first PEM private key (PKCS#8, PKCS#1, or SEC1) in `path`
+fn load_private_key(path: &Path) -> DbResult<PrivateKeyDer<'static>> {
+    PrivateKeyDer::from_pem_file(path)
+        .map_err(|e| tls_error(format!("Can't read key {}: {}", path.display(), e)))
+}
+
+fn tls_error(message: String) -> DbError {
+    DbError::ConnectionFailed(format!("TLS setup failed: {}", message))
+}
+
+/// `verify-ca`: full chain verification against the trusted roots, but a
+/// certificate issued for another host name is accepted.
+#[derive(Debug)]
+struct SkipHostnameVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+}
+
+impl ServerCertVerifier for SkipHostnameVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        match self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        ) {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            other => other,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn config(ssl_mode: SslMode) -> ConnectionConfig {
+        let mut config = ConnectionConfig::from_url("postgres://u@localhost/db").unwrap();
+        config.ssl_mode = ssl_mode;
+        config
+    }
+
+    fn error_message(result: DbResult<Option<rustls::ClientConfig>>) -> String {
+        match result {
+            Err(e) => e.to_string(),
+            Ok(_) => panic!("expected TLS setup to fail"),
+        }
+    }
+
+    #[test]
+    fn test_disable_has_no_tls_config() {
+        assert!(
+            make_tls_config(&config(SslMode::Disable))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_verify_modes_build_with_system_roots() {
+        for mode in [
+            SslMode::Prefer,
+            SslMode::Require,
+            SslMode::VerifyCa,
+            SslMode::VerifyFull,
+        ] {
+            let tls = make_tls_config(&config(mode)).unwrap().unwrap();
+            assert!(!tls.client_auth_cert_resolver.has_certs());
+        }
+    }
+
+    #[test]
+    fn test_client_cert_requires_key() {
+        let mut cfg = config(SslMode::VerifyFull);
+        cfg.ssl_cert = Some(PathBuf::from("client.crt"));
+        assert!(error_message(make_tls_config(&cfg)).contains("sslcert requires sslkey"));
+
+        let mut cfg = config(SslMode::VerifyFull);
+        cfg.ssl_key = Some(PathBuf::from("client.key"));
+        assert!(error_message(make_tls_config(&cfg)).contains("sslkey requires sslcert"));
+    }
+
+    #[test]
+    fn test_missing_root_cert_file_fails() {
+        let mut cfg = config(SslMode::VerifyCa);
+        cfg.ssl_root_cert = Some(PathBuf::from("/nonexistent/vizgres-root.crt"));
+        let message = error_message(make_tls_config(&cfg));
+        assert!(message.contains("vizgres-root.crt"), "{}", message);
+    }
+
+    #[test]
+    fn test_root_cert_file_without_certificates_fails() {
+        let path = std::env::temp_dir().join(format!("vizgres-empty-{}.crt", std::process::id()));
+        std::fs::write(&path, "not a certificate\n").unwrap();
+        let mut cfg = config(SslMode::VerifyFull);
+        cfg.ssl_root_cert = Some(path.clone());
+        let message = error_message(make_tls_config(&cfg));
+        std::fs::remove_file(&path).unwrap();
+        assert!(message.contains("No certificates found"), "{}", message);
+    }
+}
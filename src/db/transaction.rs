@@ -0,0 +1,254 @@
+//! Transaction state tracking
+//!
+//! Each connection tracks whether it is inside an explicit transaction block.
+//! The state is derived from the transaction-control statements that succeed
+//! or fail on that connection, mirroring PostgreSQL's own rules: any error
+//! inside a block aborts it until ROLLBACK (or ROLLBACK TO SAVEPOINT).
+
+use crate::db::sql_limit::outer_statements;
+use std::sync::Mutex;
+
+/// Transaction state of a single connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionState {
+    /// No active transaction (autocommit)
+    Idle,
+    /// Inside an explicit transaction block
+    InTransaction,
+    /// Transaction block entered error state (requires ROLLBACK)
+    Failed,
+}
+
+/// Transaction-control statement kinds that change `TransactionState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionControl {
+    /// BEGIN / START TRANSACTION
+    Begin,
+    /// COMMIT / END
+    Commit,
+    /// ROLLBACK / ABORT
+    Rollback,
+    /// ROLLBACK TO [SAVEPOINT] name — recovers a failed block
+    RollbackToSavepoint,
+}
+
+impl TransactionControl {
+    /// Classify a SQL script by its last transaction-control statement, so
+    /// `BEGIN; UPDATE ...; COMMIT` counts as a COMMIT.
+    /// Returns None for scripts with no statement that controls transactions.
+    pub fn parse(sql: &str) -> Option<Self> {
+        outer_statements(sql)
+            .iter()
+            .rev()
+            .find_map(|tokens| Self::classify(tokens))
+    }
+
+    /// Classify one statement by its leading keywords
+    fn classify(tokens: &[String]) -> Option<Self> {
+        let mut words = tokens.iter().map(String::as_str);
+        match words.next()? {
+            "BEGIN" | "START" => Some(Self::Begin),
+            "COMMIT" | "END" => Some(Self::Commit),
+            "ROLLBACK" | "ABORT" => {
+                // ROLLBACK [WORK | TRANSACTION] TO [SAVEPOINT] name
+                let to_savepoint = words
+                    .find(|w| !matches!(*w, "WORK" | "TRANSACTION"))
+                    .is_some_and(|w| w == "TO");
+                if to_savepoint {
+                    Some(Self::RollbackToSavepoint)
+                } else {
+                    Some(Self::Rollback)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// State the connection ends up in once this statement succeeds.
+    pub fn resulting_state(self) -> TransactionState {
+        match self {
+            Self::Begin | Self::RollbackToSavepoint => TransactionState::InTransaction,
+            Self::Commit | Self::Rollback => TransactionState::Idle,
+        }
+    }
+}
+
+/// Thread-safe transaction state for one connection, updated after every
+/// statement the connection runs.
+#[derive(Debug)]
+pub struct TransactionTracker {
+    state: Mutex<TransactionState>,
+}
+
+impl TransactionTracker {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(TransactionState::Idle),
+        }
+    }
+
+    /// Current transaction state
+    pub fn state(&self) -> TransactionState {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record that `sql` completed successfully.
+    pub fn record_success(&self, sql: &str) {
+        let Some(control) = TransactionControl::parse(sql) else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        // ROLLBACK TO outside a block is an error, so success implies we were in one
+        *state = control.resulting_state();
+    }
+
+    /// Record that `sql` failed (including cancellation and timeouts).
+    pub fn record_failure(&self, sql: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        *state = match TransactionControl::parse(sql) {
+            // A failed COMMIT still ends the block; ROLLBACK never leaves one open
+            Some(TransactionControl::Commit | TransactionControl::Rollback) => {
+                TransactionState::Idle
+            }
+            _ if *state == TransactionState::Idle => TransactionState::Idle,
+            _ => TransactionState::Failed,
+        };
+    }
+}
+
+impl Default for TransactionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transaction_control() {
+        use TransactionControl::*;
+        assert_eq!(TransactionControl::parse("BEGIN"), Some(Begin));
+        assert_eq!(TransactionControl::parse("begin;"), Some(Begin));
+        assert_eq!(
+            TransactionControl::parse("START TRANSACTION ISOLATION LEVEL SERIALIZABLE"),
+            Some(Begin)
+        );
+        assert_eq!(TransactionControl::parse("COMMIT"), Some(Commit));
+        assert_eq!(TransactionControl::parse("end"), Some(Commit));
+        assert_eq!(TransactionControl::parse("ROLLBACK"), Some(Rollback));
+        assert_eq!(TransactionControl::parse("abort;"), Some(Rollback));
+        assert_eq!(TransactionControl::parse("SELECT 1"), None);
+        assert_eq!(TransactionControl::parse("   "), None);
+    }
+
+    #[test]
+    fn test_parse_rollback_to_savepoint() {
+        use TransactionControl::*;
+        assert_eq!(
+            TransactionControl::parse("ROLLBACK TO SAVEPOINT sp1"),
+            Some(RollbackToSavepoint)
+        );
+        assert_eq!(
+            TransactionControl::parse("rollback work to sp1"),
+            Some(RollbackToSavepoint)
+        );
+        assert_eq!(
+            TransactionControl::parse("ROLLBACK TRANSACTION"),
+            Some(Rollback)
+        );
+    }
+
+    #[test]
+    fn test_parse_script_uses_last_control_statement() {
+        use TransactionControl::*;
+        assert_eq!(
+            TransactionControl::parse("BEGIN; UPDATE t SET a = 1; COMMIT;"),
+            Some(Commit)
+        );
+        assert_eq!(
+            TransactionControl::parse("UPDATE t SET a = 1; COMMIT"),
+            Some(Commit)
+        );
+        assert_eq!(
+            TransactionControl::parse("BEGIN;\nINSERT INTO t VALUES (1);"),
+            Some(Begin)
+        );
+        assert_eq!(
+            TransactionControl::parse("SAVEPOINT sp; DELETE FROM t; ROLLBACK TO sp; SELECT 1"),
+            Some(RollbackToSavepoint)
+        );
+        // Keywords in strings, comments, and function bodies don't count
+        assert_eq!(
+            TransactionControl::parse("SELECT 'COMMIT'; -- ROLLBACK\nSELECT 1"),
+            None
+        );
+        assert_eq!(
+            TransactionControl::parse("BEGIN; DO $$ BEGIN COMMIT; END $$"),
+            Some(Begin)
+        );
+    }
+
+    #[test]
+    fn test_tracker_begin_commit() {
+        let t = TransactionTracker::new();
+        assert_eq!(t.state(), TransactionState::Idle);
+        t.record_success("BEGIN");
+        assert_eq!(t.state(), TransactionState::InTransaction);
+        t.record_success("SELECT 1");
+        assert_eq!(t.state(), TransactionState::InTransaction);
+        t.record_success("COMMIT");
+        assert_eq!(t.state(), TransactionState::Idle);
+    }
+
+    #[test]
+    fn test_tracker_error_aborts_block() {
+        let t = TransactionTracker::new();
+        t.record_success("BEGIN");
+        t.record_failure("SELECT 1/0");
+        assert_eq!(t.state(), TransactionState::Failed);
+        // Further statements keep failing until ROLLBACK
+        t.record_failure("SELECT 1");
+        assert_eq!(t.state(), TransactionState::Failed);
+        t.record_success("ROLLBACK");
+        assert_eq!(t.state(), TransactionState::Idle);
+    }
+
+    #[test]
+    fn test_tracker_error_outside_block_stays_idle() {
+        let t = TransactionTracker::new();
+        t.record_failure("SELECT 1/0");
+        assert_eq!(t.state(), TransactionState::Idle);
+    }
+
+    #[test]
+    fn test_tracker_rollback_to_savepoint_recovers() {
+        let t = TransactionTracker::new();
+        t.record_success("BEGIN");
+        t.record_success("SAVEPOINT sp1");
+        t.record_failure("INSERT INTO t VALUES (1)");
+        assert_eq!(t.state(), TransactionState::Failed);
+        t.record_success("ROLLBACK TO SAVEPOINT sp1");
+        assert_eq!(t.state(), TransactionState::InTransaction);
+    }
+
+    #[test]
+    fn test_tracker_script_with_commit_ends_block() {
+        let t = TransactionTracker::new();
+        t.record_success("BEGIN; UPDATE t SET a = 1; COMMIT;");
+        assert_eq!(t.state(), TransactionState::Idle);
+        t.record_success("BEGIN; UPDATE t SET a = 1");
+        assert_eq!(t.state(), TransactionState::InTransaction);
+        t.record_success("UPDATE t SET a = 2; COMMIT");
+        assert_eq!(t.state(), TransactionState::Idle);
+    }
+
+    #[test]
+    fn test_tracker_failed_commit_ends_block() {
+        let t = TransactionTracker::new();
+        t.record_success("BEGIN");
+        t.record_failure("COMMIT");
+        assert_eq!(t.state(), TransactionState::Idle);
+    }
+}
@@ -3,8 +3,12 @@
 //! Core data structures for representing database query results,
 //! data types, and values.
 
+use std::cmp::Ordering;
 use std::time::Duration;
 
+use crate::db::provenance::ResultSource;
+use serde::{Deserialize, Serialize};
+
 /// Query execution results
 #[derive(Debug, Clone)]
 pub struct QueryResults {
@@ -18,6 +22,33 @@ pub struct QueryResults {
     pub row_count: usize,
     /// Whether results were truncated due to row limit
     pub truncated: bool,
+    /// Table the rows were read from, when they can be edited in place
+    pub source: Option<ResultSource>,
+    /// What a DML statement did, e.g. `UPDATE 37` (None for queries)
+    pub command: Option<CommandTag>,
+}
+
+/// Command tag of an INSERT, UPDATE, DELETE, or MERGE: the command and the
+/// rows it affected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandTag {
+    pub command: String,
+    pub rows: u64,
+}
+
+impl CommandTag {
+    /// The tag for `sql` having affected `rows`, when its last statement is DML
+    pub fn for_statement(sql: &str, rows: u64) -> Option<Self> {
+        let command = crate::db::sql_limit::last_verb(sql)?;
+        matches!(command.as_str(), "INSERT" | "UPDATE" | "DELETE" | "MERGE")
+            .then_some(Self { command, rows })
+    }
+}
+
+impl std::fmt::Display for CommandTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.command, self.rows)
+    }
 }
 
 impl QueryResults {
@@ -39,6 +70,8 @@ impl QueryResults {
             execution_time,
             row_count,
             truncated: false,
+            source: None,
+            command: None,
         }
     }
 
@@ -61,6 +94,8 @@ impl QueryResults {
             execution_time,
             row_count,
             truncated,
+            source: None,
+            command: None,
         }
     }
 }
@@ -77,7 +112,7 @@ pub struct ColumnDef {
 }
 
 /// Database data types
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DataType {
     // Integer types
     SmallInt,
@@ -237,12 +272,74 @@ impl CellValue {
     pub fn is_null(&self) -> bool {
         matches!(self, CellValue::Null)
     }
+
+    /// Ordering used for client-side sorting.
+    ///
+    /// Integers and floats compare numerically with each other, NULLs sort
+    /// after everything else (PostgreSQL's default for ascending order), and
+    /// values of different kinds are grouped by kind.
+    pub fn sort_cmp(&self, other: &CellValue) -> Ordering {
+        use CellValue::*;
+        match (self, other) {
+            (Null, Null) => Ordering::Equal,
+            (Null, _) => Ordering::Greater,
+            (_, Null) => Ordering::Less,
+            (Integer(a), Integer(b)) => a.cmp(b),
+            (Float(a), Float(b)) => a.total_cmp(b),
+            (Integer(a), Float(b)) => (*a as f64).total_cmp(b),
+            (Float(a), Integer(b)) => a.total_cmp(&(*b as f64)),
+            (Boolean(a), Boolean(b)) => a.cmp(b),
+            (Text(a), Text(b))
+            | (Json(a), Json(b))
+            | (DateTime(a), DateTime(b))
+            | (Uuid(a), Uuid(b)) => a.cmp(b),
+            (Binary(a), Binary(b)) => a.cmp(b),
+            (Array(a), Array(b)) => a
+                .iter()
+                .zip(b)
+                .map(|(x, y)| x.sort_cmp(y))
+                .find(|o| o.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            _ => self.kind_rank().cmp(&other.kind_rank()),
+        }
+    }
+
+    /// Rank of each variant, used to order values of different kinds
+    fn kind_rank(&self) -> u8 {
+        match self {
+            CellValue::Integer(_) | CellValue::Float(_) => 0,
+            CellValue::Boolean(_) => 1,
+            CellValue::DateTime(_) => 2,
+            CellValue::Text(_) => 3,
+            CellValue::Uuid(_) => 4,
+            CellValue::Json(_) => 5,
+            CellValue::Array(_) => 6,
+            CellValue::Binary(_) => 7,
+            CellValue::Null => 8,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_command_tag_only_for_dml() {
+        let tag = CommandTag::for_statement("update users set name = 'x'", 37).unwrap();
+        assert_eq!(tag.to_string(), "UPDATE 37");
+        assert_eq!(
+            CommandTag::for_statement("WITH d AS (SELECT 1) INSERT INTO t SELECT * FROM d", 2)
+                .map(|t| t.command),
+            Some("INSERT".to_string())
+        );
+        assert_eq!(CommandTag::for_statement("SELECT * FROM users", 5), None);
+        assert_eq!(
+            CommandTag::for_statement("CREATE TABLE t (id int)", 0),
+            None
+        );
+    }
+
     #[test]
     fn test_datatype_display_name() {
         assert_eq!(DataType::Integer.display_name(), "integer");
@@ -260,6 +357,44 @@ mod tests {
         assert_eq!(val.display_string(100), "Hello, world!");
     }
 
+    #[test]
+    fn test_cell_value_sort_cmp_numeric_across_variants() {
+        assert_eq!(
+            CellValue::Integer(2).sort_cmp(&CellValue::Float(10.5)),
+            Ordering::Less
+        );
+        assert_eq!(
+            CellValue::Integer(9).sort_cmp(&CellValue::Integer(10)),
+            Ordering::Less
+        );
+        assert_eq!(
+            CellValue::Float(-1.0).sort_cmp(&CellValue::Integer(-1)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_cell_value_sort_cmp_nulls_last() {
+        assert_eq!(
+            CellValue::Null.sort_cmp(&CellValue::Integer(1)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            CellValue::Text("a".to_string()).sort_cmp(&CellValue::Null),
+            Ordering::Less
+        );
+        assert_eq!(CellValue::Null.sort_cmp(&CellValue::Null), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_cell_value_sort_cmp_arrays_elementwise() {
+        let short = CellValue::Array(vec![CellValue::Integer(1)]);
+        let long = CellValue::Array(vec![CellValue::Integer(1), CellValue::Integer(0)]);
+        let bigger = CellValue::Array(vec![CellValue::Integer(2)]);
+        assert_eq!(short.sort_cmp(&long), Ordering::Less);
+        assert_eq!(long.sort_cmp(&bigger), Ordering::Less);
+    }
+
     #[test]
     fn test_cell_value_is_null() {
         assert!(CellValue::Null.is_null());
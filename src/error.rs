@@ -37,6 +37,8 @@ pub enum DbError {
     QueryFailed {
         message: String,
         position: Option<u32>, // byte offset in query
+        /// SQLSTATE, when the server reported the error
+        code: Option<String>,
     },
 
     /// Schema introspection failed
@@ -113,7 +115,9 @@ pub fn connection_hint(error: &str) -> Option<&'static str> {
         return Some("Connection timed out — server may be unreachable or firewalled");
     }
     if lower.contains("ssl") || lower.contains("tls") {
-        return Some("SSL/TLS error — try ssl_mode = \"disable\" or check certificates");
+        return Some(
+            "SSL/TLS error — try ssl_mode = \"disable\" or check ssl_root_cert/ssl_cert/ssl_key",
+        );
     }
     if lower.contains("too many connections") || lower.contains("remaining connection slots") {
         return Some(
@@ -0,0 +1,1437 @@
+//! Query results export (CSV / TSV / JSON / Markdown / XLSX / text table)
+//!
+//! Pure serialization functions — no filesystem I/O. The caller writes the
+//! returned string (or bytes, for XLSX) to disk.
+
+mod xlsx;
+
+pub use xlsx::{to_xlsx, to_xlsx_with_casts};
+
+use crate::db::types::{CellValue, ColumnDef, QueryResults, Row};
+use crate::sql::quote_literal;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::str::FromStr;
+
+/// Export format selector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// Tab-separated values (CSV rules with a tab delimiter)
+    Tsv,
+    Json,
+    /// GitHub-flavored Markdown table
+    Markdown,
+    /// Excel workbook with typed cells
+    Xlsx,
+}
+
+impl ExportFormat {
+    /// Parse a format name as typed in `/export <format>`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "tsv" => Some(ExportFormat::Tsv),
+            "json" => Some(ExportFormat::Json),
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            "xlsx" | "excel" => Some(ExportFormat::Xlsx),
+            _ => None,
+        }
+    }
+
+    /// File extension for this format (without leading dot)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// Fill in an export file name template: `{connection}`, `{table}` (the
+/// results' source table, or "query"), `{date}` (2024-05-01), `{time}`
+/// (143005), and `{ext}`. Characters that don't belong in a file name are
+/// replaced with `_` in the substituted values.
+pub fn file_name(
+    template: &str,
+    connection: Option<&str>,
+    table: Option<&str>,
+    format: ExportFormat,
+    now: &chrono::DateTime<chrono::Local>,
+) -> String {
+    let safe = |value: &str| -> String {
+        value
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+    template
+        .replace("{connection}", &safe(connection.unwrap_or("vizgres")))
+        .replace("{table}", &safe(table.unwrap_or("query")))
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H%M%S").to_string())
+        .replace("{ext}", format.extension())
+}
+
+/// Dialect for delimited-text export (CSV and TSV)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    /// Wraps fields that contain the delimiter, itself, or a line break
+    pub quote: char,
+    /// Whether the first line names the columns
+    pub header: bool,
+}
+
+impl CsvOptions {
+    /// RFC 4180: comma-separated, double-quoted, with a header line
+    pub fn csv() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            header: true,
+        }
+    }
+
+    /// Tab-separated, otherwise as `csv()`
+    pub fn tsv() -> Self {
+        Self {
+            delimiter: '\t',
+            ..Self::csv()
+        }
+    }
+
+    /// Pull `--delimiter X`, `--quote X`, and `--no-header` out of an export
+    /// path input, returning what's left (path and any `| casts`), or `None`
+    /// if there were no options. Values may be quoted (`--delimiter '|'`);
+    /// `tab` or `\t` means a tab.
+    pub fn extract(&mut self, input: &str) -> Result<Option<String>, String> {
+        let mut kept = Vec::new();
+        let mut found = false;
+        let mut words = option_words(input).into_iter();
+        while let Some((raw, word)) = words.next() {
+            match word.as_str() {
+                "--delimiter" | "-d" => {
+                    let (_, value) = words.next().ok_or("--delimiter needs a character")?;
+                    self.delimiter = option_char(&value, "Delimiter")?;
+                }
+                "--quote" | "-q" => {
+                    let (_, value) = words.next().ok_or("--quote needs a character")?;
+                    self.quote = option_char(&value, "Quote")?;
+                }
+                "--no-header" => self.header = false,
+                _ => {
+                    kept.push(raw);
+                    continue;
+                }
+            }
+            found = true;
+        }
+        if self.delimiter == self.quote {
+            return Err("Delimiter and quote must differ".to_string());
+        }
+        Ok(found.then(|| kept.join(" ")))
+    }
+
+    /// The same dialect as `COPY ... TO STDOUT` options
+    pub fn copy_options(&self) -> String {
+        format!(
+            "FORMAT csv, DELIMITER {}, QUOTE {}, HEADER {}",
+            quote_literal(&self.delimiter.to_string()),
+            quote_literal(&self.quote.to_string()),
+            self.header
+        )
+    }
+}
+
+/// Counts the rows in CSV text fed in chunks, as `COPY ... TO STDOUT`
+/// sends it: a line break inside a quoted field doesn't end a row
+#[derive(Debug, Clone)]
+pub struct CsvRowCounter {
+    quote: u8,
+    header: bool,
+    quoted: bool,
+    lines: u64,
+}
+
+impl CsvRowCounter {
+    pub fn new(options: &CsvOptions) -> Self {
+        Self {
+            quote: u8::try_from(options.quote).unwrap_or(b'"'),
+            header: options.header,
+            quoted: false,
+            lines: 0,
+        }
+    }
+
+    pub fn feed(&mut self, chunk: &[u8]) {
+        for &b in chunk {
+            if b == self.quote {
+                // A doubled quote toggles twice
+                self.quoted = !self.quoted;
+            } else if b == b'\n' && !self.quoted {
+                self.lines += 1;
+            }
+        }
+    }
+
+    /// Data rows so far, not counting the header line
+    pub fn rows(&self) -> u64 {
+        if self.header {
+            self.lines.saturating_sub(1)
+        } else {
+            self.lines
+        }
+    }
+}
+
+/// Split on whitespace, keeping '…' and "…" together. Each word comes back
+/// as written and with its quotes removed.
+fn option_words(input: &str) -> Vec<(&str, String)> {
+    let mut words = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut end = input.len();
+        let mut word = String::new();
+        let mut quote = None;
+        while let Some(&(i, c)) = chars.peek() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => word.push(c),
+                None if c == '\'' || c == '"' => quote = Some(c),
+                None if c.is_whitespace() => {
+                    end = i;
+                    break;
+                }
+                None => word.push(c),
+            }
+            chars.next();
+        }
+        words.push((&input[start..end], word));
+    }
+    words
+}
+
+/// A single-character option value (`tab` and `\t` spell a tab)
+fn option_char(value: &str, what: &str) -> Result<char, String> {
+    let mut chars = value.chars();
+    match (value, chars.next(), chars.next()) {
+        ("tab" | "\\t", _, _) => Ok('\t'),
+        (_, Some(c), None) if c != '\n' && c != '\r' => Ok(c),
+        _ => Err(format!("{} must be one character, got '{}'", what, value)),
+    }
+}
+
+/// How one column's values are rewritten on export, for consumers that
+/// don't understand PostgreSQL's formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnCast {
+    /// Export as a plain string (JSON: no numbers, booleans, or objects)
+    Text,
+    /// Drop the UTC offset from timestamps (`... 12:00:00+02` → `... 12:00:00`)
+    NoTimezone,
+    /// Keep only the date part of timestamps
+    Date,
+    /// Numbers rounded to a fixed count of decimal places
+    Fixed(u32),
+}
+
+impl ColumnCast {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "text" => Some(ColumnCast::Text),
+            "notz" => Some(ColumnCast::NoTimezone),
+            "date" => Some(ColumnCast::Date),
+            other => {
+                let places = other.strip_prefix("fixed:")?.parse().ok()?;
+                (places <= 28).then_some(ColumnCast::Fixed(places))
+            }
+        }
+    }
+
+    /// Rewrite a non-NULL value's export string
+    fn apply(self, value: String) -> String {
+        match self {
+            ColumnCast::Text => value,
+            ColumnCast::NoTimezone => strip_timezone(&value).to_string(),
+            ColumnCast::Date => value
+                .split([' ', 'T'])
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            ColumnCast::Fixed(places) => {
+                match Decimal::from_str(&value).or_else(|_| Decimal::from_scientific(&value)) {
+                    Ok(d) => format!(
+                        "{:.*}",
+                        places as usize,
+                        d.round_dp_with_strategy(places, RoundingStrategy::MidpointAwayFromZero)
+                    ),
+                    // Not a number (NaN, Infinity, text) — left as is
+                    Err(_) => value,
+                }
+            }
+        }
+    }
+}
+
+/// Per-column export overrides, indexed like the result columns
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnCasts {
+    casts: Vec<Option<ColumnCast>>,
+}
+
+impl ColumnCasts {
+    /// Parse `column=cast` pairs separated by commas, e.g.
+    /// `created_at=notz, total=fixed:2, id=text`. Casts are `text`, `notz`,
+    /// `date`, and `fixed:N`. Column names match exactly, or ignoring case
+    /// when that is unambiguous.
+    pub fn parse(spec: &str, columns: &[ColumnDef]) -> Result<Self, String> {
+        let mut casts = vec![None; columns.len()];
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let Some((column, cast)) = pair.rsplit_once('=') else {
+                return Err(format!("Expected column=cast, got '{}'", pair));
+            };
+            let (column, cast) = (column.trim(), cast.trim());
+            let idx = column_index(columns, column)
+                .ok_or_else(|| format!("Unknown column '{}'", column))?;
+            casts[idx] = Some(ColumnCast::parse(cast).ok_or_else(|| {
+                format!("Unknown cast '{}' (use text, notz, date, or fixed:N)", cast)
+            })?);
+        }
+        Ok(Self { casts })
+    }
+
+    /// Whether no column has a cast
+    pub fn is_empty(&self) -> bool {
+        self.casts.iter().all(Option::is_none)
+    }
+
+    fn get(&self, column: usize) -> Option<ColumnCast> {
+        self.casts.get(column).copied().flatten()
+    }
+}
+
+fn column_index(columns: &[ColumnDef], name: &str) -> Option<usize> {
+    if let Some(idx) = columns.iter().position(|c| c.name == name) {
+        return Some(idx);
+    }
+    let mut matches = columns
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.name.eq_ignore_ascii_case(name));
+    match (matches.next(), matches.next()) {
+        (Some((idx, _)), None) => Some(idx),
+        _ => None,
+    }
+}
+
+/// Cut a trailing UTC offset (` UTC`, `Z`, `+02`, `-05:30`) off a timestamp.
+/// Only looks after the time part, so dates keep their dashes.
+fn strip_timezone(value: &str) -> &str {
+    if let Some(rest) = value.strip_suffix(" UTC") {
+        return rest;
+    }
+    if let Some(rest) = value.strip_suffix('Z') {
+        return rest;
+    }
+    let Some(time_start) = value.find(':') else {
+        return value;
+    };
+    match value[time_start..].find(['+', '-']) {
+        Some(offset) => value[..time_start + offset].trim_end(),
+        None => value,
+    }
+}
+
+/// Serialize query results as RFC 4180 CSV.
+pub fn to_csv(results: &QueryResults) -> String {
+    to_csv_with_casts(results, &ColumnCasts::default())
+}
+
+/// Serialize query results as RFC 4180 CSV, rewriting cast columns.
+pub fn to_csv_with_casts(results: &QueryResults, casts: &ColumnCasts) -> String {
+    to_delimited(results, casts, CsvOptions::csv())
+}
+
+/// Serialize query results as delimited text in the given dialect,
+/// rewriting cast columns.
+pub fn to_delimited(results: &QueryResults, casts: &ColumnCasts, options: CsvOptions) -> String {
+    let mut out = String::new();
+    if options.header {
+        delimited_header_into(&mut out, &results.columns, options);
+    }
+    for row in &results.rows {
+        delimited_row_into(&mut out, row, casts, options);
+    }
+    out
+}
+
+fn delimited_header_into(out: &mut String, columns: &[ColumnDef], options: CsvOptions) {
+    for (i, col) in columns.iter().enumerate() {
+        if i > 0 {
+            out.push(options.delimiter);
+        }
+        csv_escape_into(out, &col.name, options);
+    }
+    out.push('\n');
+}
+
+fn delimited_row_into(out: &mut String, row: &Row, casts: &ColumnCasts, options: CsvOptions) {
+    for (i, cell) in row.values.iter().enumerate() {
+        if i > 0 {
+            out.push(options.delimiter);
+        }
+        csv_escape_into(out, &cast_export_string(cell, casts.get(i)), options);
+    }
+    out.push('\n');
+}
+
+/// Serialize query results as a JSON array of objects with typed values.
+pub fn to_json(results: &QueryResults) -> String {
+    to_json_with_casts(results, &ColumnCasts::default())
+}
+
+/// Serialize query results as JSON; cast columns become strings.
+pub fn to_json_with_casts(results: &QueryResults, casts: &ColumnCasts) -> String {
+    let rows: Vec<serde_json::Value> = results
+        .rows
+        .iter()
+        .map(|row| json_object(row, &results.columns, casts))
+        .collect();
+
+    serde_json::to_string_pretty(&rows).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Serialize query results as JSON Lines: one compact object per row.
+pub fn to_jsonl(results: &QueryResults) -> String {
+    let mut out = String::new();
+    for row in &results.rows {
+        let obj = json_object(row, &results.columns, &ColumnCasts::default());
+        out.push_str(&obj.to_string());
+        out.push('\n');
+    }
+    out
+}
+
+fn json_object(row: &Row, columns: &[ColumnDef], casts: &ColumnCasts) -> serde_json::Value {
+    let mut obj = serde_json::Map::new();
+    for (i, cell) in row.values.iter().enumerate() {
+        let key = columns.get(i).map_or("?", |c| c.name.as_str());
+        let value = match casts.get(i) {
+            Some(cast) if !matches!(cell, CellValue::Null) => {
+                serde_json::Value::String(cast.apply(cell_to_export_string(cell)))
+            }
+            _ => cell_to_json(cell),
+        };
+        obj.insert(key.to_string(), value);
+    }
+    serde_json::Value::Object(obj)
+}
+
+/// Serialize query results as a GitHub-flavored Markdown table.
+pub fn to_markdown(results: &QueryResults) -> String {
+    to_markdown_with_casts(results, &ColumnCasts::default())
+}
+
+/// Serialize query results as a Markdown table, rewriting cast columns.
+pub fn to_markdown_with_casts(results: &QueryResults, casts: &ColumnCasts) -> String {
+    let mut out = String::new();
+    markdown_header_into(&mut out, &results.columns);
+    for row in &results.rows {
+        markdown_row_into(&mut out, row, casts);
+    }
+    out
+}
+
+fn markdown_header_into(out: &mut String, columns: &[ColumnDef]) {
+    out.push('|');
+    for col in columns {
+        out.push(' ');
+        markdown_escape_into(out, &col.name);
+        out.push_str(" |");
+    }
+    out.push_str("\n|");
+    for _ in columns {
+        out.push_str(" --- |");
+    }
+    out.push('\n');
+}
+
+fn markdown_row_into(out: &mut String, row: &Row, casts: &ColumnCasts) {
+    out.push('|');
+    for (i, cell) in row.values.iter().enumerate() {
+        out.push(' ');
+        markdown_escape_into(out, &cast_export_string(cell, casts.get(i)));
+        out.push_str(" |");
+    }
+    out.push('\n');
+}
+
+/// Render query results as a psql-style aligned table with a row count
+/// footer, for printing to a terminal; without `header`, only the rows.
+/// Numbers are right-aligned, NULLs are blank, and line breaks inside
+/// values show as `\\n`.
+pub fn to_text_table(results: &QueryResults, header: bool) -> String {
+    use crate::ui::unicode::display_width;
+
+    let rows: Vec<Vec<String>> = results
+        .rows
+        .iter()
+        .map(|row| {
+            row.values
+                .iter()
+                .map(|cell| cell_to_export_string(cell).replace('\n', "\\n"))
+                .collect()
+        })
+        .collect();
+    let mut widths: Vec<usize> = results
+        .columns
+        .iter()
+        .map(|c| if header { display_width(&c.name) } else { 0 })
+        .collect();
+    for row in &rows {
+        for (width, text) in widths.iter_mut().zip(row) {
+            *width = (*width).max(display_width(text));
+        }
+    }
+
+    let mut out = String::new();
+    let pad = |out: &mut String, n: usize| out.extend(std::iter::repeat_n(' ', n));
+    if header {
+        for (i, (col, &width)) in results.columns.iter().zip(&widths).enumerate() {
+            out.push_str(if i == 0 { " " } else { " | " });
+            out.push_str(&col.name);
+            pad(&mut out, width - display_width(&col.name));
+        }
+        out.push_str(" \n");
+        for (i, &width) in widths.iter().enumerate() {
+            out.push_str(if i == 0 { "-" } else { "-+-" });
+            out.extend(std::iter::repeat_n('-', width));
+        }
+        out.push_str("-\n");
+    }
+    for (row, cells) in results.rows.iter().zip(&rows) {
+        for (i, (text, &width)) in cells.iter().zip(&widths).enumerate() {
+            out.push_str(if i == 0 { " " } else { " | " });
+            let fill = width - display_width(text);
+            if matches!(row.values[i], CellValue::Integer(_) | CellValue::Float(_)) {
+                pad(&mut out, fill);
+                out.push_str(text);
+            } else {
+                out.push_str(text);
+                pad(&mut out, fill);
+            }
+        }
+        out.push_str(" \n");
+    }
+    if header {
+        let n = results.rows.len();
+        out.push_str(&format!("({} row{})\n", n, if n == 1 { "" } else { "s" }));
+    }
+    out
+}
+
+/// Incremental export for result sets too large to load: the opening text,
+/// then batches of rows, then the closing text, each returned for the
+/// caller to append to the file. The output matches the `to_*` functions
+/// for the same rows. XLSX can't be streamed (the workbook is written as a
+/// whole).
+#[derive(Debug, Clone)]
+pub struct RowStream {
+    format: ExportFormat,
+    casts: ColumnCasts,
+    options: CsvOptions,
+    columns: Vec<ColumnDef>,
+    rows: usize,
+    /// Columns written as the mask placeholder
+    masked: Vec<usize>,
+}
+
+impl RowStream {
+    pub fn new(
+        format: ExportFormat,
+        casts: ColumnCasts,
+        options: CsvOptions,
+    ) -> Result<Self, String> {
+        if format == ExportFormat::Xlsx {
+            return Err("xlsx can't be streamed — use csv, tsv, json, or md".to_string());
+        }
+        Ok(Self {
+            format,
+            casts,
+            options,
+            columns: Vec::new(),
+            rows: 0,
+            masked: Vec::new(),
+        })
+    }
+
+    /// Header (or opening bracket), once the columns are known
+    pub fn begin(&mut self, columns: &[ColumnDef]) -> String {
+        self.columns = columns.to_vec();
+        let mut out = String::new();
+        match self.format {
+            ExportFormat::Csv | ExportFormat::Tsv => {
+                if self.options.header {
+                    delimited_header_into(&mut out, columns, self.options);
+                }
+            }
+            ExportFormat::Markdown => markdown_header_into(&mut out, columns),
+            ExportFormat::Json => out.push('['),
+            ExportFormat::Xlsx => {}
+        }
+        out
+    }
+
+    /// The next batch of rows
+    /// Write the mask placeholder in these columns
+    pub fn mask_columns(&mut self, columns: Vec<usize>) {
+        self.masked = columns;
+    }
+
+    pub fn rows(&mut self, rows: &[Row]) -> String {
+        let mut out = String::new();
+        for row in rows {
+            let masked;
+            let row = if self.masked.is_empty() {
+                row
+            } else {
+                let mut copy = row.clone();
+                crate::mask::mask_row(&mut copy, &self.masked);
+                masked = copy;
+                &masked
+            };
+            match self.format {
+                ExportFormat::Csv | ExportFormat::Tsv => {
+                    delimited_row_into(&mut out, row, &self.casts, self.options)
+                }
+                ExportFormat::Markdown => markdown_row_into(&mut out, row, &self.casts),
+                ExportFormat::Json => {
+                    // Laid out as serde_json's pretty printer lays out an array
+                    out.push_str(if self.rows == 0 { "\n" } else { ",\n" });
+                    let object = json_object(row, &self.columns, &self.casts);
+                    let text = serde_json::to_string_pretty(&object).unwrap_or_default();
+                    for (i, line) in text.lines().enumerate() {
+                        if i > 0 {
+                            out.push('\n');
+                        }
+                        out.push_str("  ");
+                        out.push_str(line);
+                    }
+                }
+                ExportFormat::Xlsx => {}
+            }
+            self.rows += 1;
+        }
+        out
+    }
+
+    /// Closing text, after the last batch
+    pub fn finish(&self) -> String {
+        match self.format {
+            ExportFormat::Json if self.rows == 0 => "]".to_string(),
+            ExportFormat::Json => "\n]".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Rows written so far
+    pub fn row_count(&self) -> usize {
+        self.rows
+    }
+}
+
+/// Full untruncated value string for CSV export (NULL → empty string).
+fn cell_to_export_string(cell: &CellValue) -> String {
+    match cell {
+        CellValue::Null => String::new(),
+        CellValue::Integer(i) => i.to_string(),
+        CellValue::Float(f) => f.to_string(),
+        CellValue::Text(s) => s.clone(),
+        CellValue::Boolean(b) => b.to_string(),
+        CellValue::Json(s) => s.clone(),
+        CellValue::Binary(b) => hex_encode(b),
+        CellValue::DateTime(s) => s.clone(),
+        CellValue::Uuid(s) => s.clone(),
+        CellValue::Array(arr) => {
+            let items: Vec<String> = arr.iter().map(cell_to_export_string).collect();
+            format!("{{{}}}", items.join(","))
+        }
+    }
+}
+
+/// Export string with the column's cast applied (NULL stays empty).
+fn cast_export_string(cell: &CellValue, cast: Option<ColumnCast>) -> String {
+    let text = cell_to_export_string(cell);
+    match cast {
+        Some(cast) if !matches!(cell, CellValue::Null) => cast.apply(text),
+        _ => text,
+    }
+}
+
+/// Convert a CellValue to a serde_json::Value with type preservation.
+fn cell_to_json(cell: &CellValue) -> serde_json::Value {
+    match cell {
+        CellValue::Null => serde_json::Value::Null,
+        CellValue::Integer(i) => serde_json::json!(*i),
+        CellValue::Float(f) => {
+            if f.is_finite() {
+                serde_json::json!(*f)
+            } else {
+                // NaN / Infinity aren't valid JSON numbers
+                serde_json::Value::String(f.to_string())
+            }
+        }
+        CellValue::Text(s) => serde_json::Value::String(s.clone()),
+        CellValue::Boolean(b) => serde_json::Value::Bool(*b),
+        CellValue::Json(s) => {
+            serde_json::from_str(s).unwrap_or_else(|_| serde_json::Value::String(s.clone()))
+        }
+        CellValue::Binary(b) => serde_json::Value::String(hex_encode(b)),
+        CellValue::DateTime(s) => serde_json::Value::String(s.clone()),
+        CellValue::Uuid(s) => serde_json::Value::String(s.clone()),
+        CellValue::Array(arr) => serde_json::Value::Array(arr.iter().map(cell_to_json).collect()),
+    }
+}
+
+/// Quote a field if it contains the delimiter, the quote character, or a
+/// newline (RFC 4180, generalized to the dialect).
+fn csv_escape_into(out: &mut String, field: &str, options: CsvOptions) {
+    let CsvOptions {
+        delimiter, quote, ..
+    } = options;
+    if field.contains([delimiter, quote, '\n', '\r']) {
+        out.push(quote);
+        for c in field.chars() {
+            if c == quote {
+                out.push(quote);
+            }
+            out.push(c);
+        }
+        out.push(quote);
+    } else {
+        out.push_str(field);
+    }
+}
+
+/// Escape a table cell: `|` would end the cell and line breaks the row, so
+/// pipes are backslash-escaped and line breaks become `<br>`. Other
+/// characters Markdown would format (`*`, `_`, backticks, brackets, `<`)
+/// are escaped so values show literally.
+fn markdown_escape_into(out: &mut String, field: &str) {
+    let mut chars = field.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | '|' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#' | '~' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\r' => {
+                if chars.peek() != Some(&'\n') {
+                    out.push_str("<br>");
+                }
+            }
+            '\n' => out.push_str("<br>"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Hex-encode binary data (e.g. `\xdeadbeef`).
+fn hex_encode(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 + bytes.len() * 2);
+    s.push_str("\\x");
+    for b in bytes {
+        s.push_str(&format!("{:02x}", b));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::{ColumnDef, DataType, Row};
+    use std::time::Duration;
+
+    fn sample_results() -> QueryResults {
+        QueryResults::new(
+            vec![
+                ColumnDef {
+                    name: "id".to_string(),
+                    data_type: DataType::Integer,
+                    nullable: false,
+                },
+                ColumnDef {
+                    name: "name".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                },
+            ],
+            vec![
+                Row {
+                    values: vec![CellValue::Integer(1), CellValue::Text("Alice".to_string())],
+                },
+                Row {
+                    values: vec![CellValue::Integer(2), CellValue::Text("Bob".to_string())],
+                },
+            ],
+            Duration::from_millis(42),
+            2,
+        )
+    }
+
+    #[test]
+    fn test_format_extension() {
+        assert_eq!(ExportFormat::Csv.extension(), "csv");
+        assert_eq!(ExportFormat::Json.extension(), "json");
+        assert_eq!(ExportFormat::Markdown.extension(), "md");
+        assert_eq!(ExportFormat::Xlsx.extension(), "xlsx");
+        assert_eq!(ExportFormat::Tsv.extension(), "tsv");
+    }
+
+    #[test]
+    fn test_format_from_name() {
+        assert_eq!(ExportFormat::from_name("MD"), Some(ExportFormat::Markdown));
+        assert_eq!(
+            ExportFormat::from_name("markdown"),
+            Some(ExportFormat::Markdown)
+        );
+        assert_eq!(ExportFormat::from_name("csv"), Some(ExportFormat::Csv));
+        assert_eq!(ExportFormat::from_name("excel"), Some(ExportFormat::Xlsx));
+        assert_eq!(ExportFormat::from_name("xml"), None);
+    }
+
+    #[test]
+    fn test_file_name_template() {
+        use chrono::TimeZone;
+        let now = chrono::Local
+            .with_ymd_and_hms(2024, 5, 1, 14, 30, 5)
+            .unwrap();
+        assert_eq!(
+            file_name(
+                "{connection}_{table}_{date}.{ext}",
+                Some("prod db"),
+                Some("orders"),
+                ExportFormat::Csv,
+                &now
+            ),
+            "prod_db_orders_2024-05-01.csv"
+        );
+        assert_eq!(
+            file_name(
+                "export_{date}_{time}.{ext}",
+                None,
+                None,
+                ExportFormat::Xlsx,
+                &now
+            ),
+            "export_2024-05-01_143005.xlsx"
+        );
+        // Unknown source falls back to "query"; path separators don't leak
+        assert_eq!(
+            file_name(
+                "{connection}/{table}.{ext}",
+                Some("a/b"),
+                None,
+                ExportFormat::Json,
+                &now
+            ),
+            "a_b/query.json"
+        );
+    }
+
+    #[test]
+    fn test_basic_markdown() {
+        let md = to_markdown(&sample_results());
+        assert_eq!(
+            md,
+            "| id | name |\n| --- | --- |\n| 1 | Alice |\n| 2 | Bob |\n"
+        );
+    }
+
+    #[test]
+    fn test_text_table() {
+        let mut results = sample_results();
+        results.rows[1].values[1] = CellValue::Null;
+        results.rows.push(Row {
+            values: vec![
+                CellValue::Integer(100),
+                CellValue::Text("two\nlines".to_string()),
+            ],
+        });
+        assert_eq!(
+            to_text_table(&results, true),
+            " id  | name       \n\
+             -----+------------\n   \
+             1 | Alice      \n   \
+             2 |            \n \
+             100 | two\\nlines \n\
+             (3 rows)\n"
+        );
+        assert_eq!(
+            to_text_table(&sample_results(), false),
+            " 1 | Alice \n 2 | Bob   \n"
+        );
+    }
+
+    #[test]
+    fn test_jsonl_one_object_per_line() {
+        let mut results = sample_results();
+        results.rows[1].values[1] = CellValue::Null;
+        assert_eq!(
+            to_jsonl(&results),
+            "{\"id\":1,\"name\":\"Alice\"}\n{\"id\":2,\"name\":null}\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_escaping() {
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "a|b".to_string(),
+                data_type: DataType::Text,
+                nullable: true,
+            }],
+            vec![
+                Row {
+                    values: vec![CellValue::Text("x | *y*\r\nz_1 <b>".to_string())],
+                },
+                Row {
+                    values: vec![CellValue::Text("C:\\tmp".to_string())],
+                },
+                Row {
+                    values: vec![CellValue::Null],
+                },
+            ],
+            Duration::from_millis(1),
+            3,
+        );
+        let md = to_markdown(&results);
+        let lines: Vec<&str> = md.lines().collect();
+        assert_eq!(lines[0], "| a\\|b |");
+        assert_eq!(lines[2], "| x \\| \\*y\\*<br>z\\_1 \\<b\\> |");
+        assert_eq!(lines[3], "| C:\\\\tmp |");
+        assert_eq!(lines[4], "|  |");
+    }
+
+    #[test]
+    fn test_basic_csv() {
+        let csv = to_csv(&sample_results());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "id,name");
+        assert_eq!(lines[1], "1,Alice");
+        assert_eq!(lines[2], "2,Bob");
+    }
+
+    #[test]
+    fn test_tsv_and_custom_dialect() {
+        let mut results = sample_results();
+        results.rows[1].values[1] = CellValue::Text("B|o'b".to_string());
+        let tsv = to_delimited(&results, &ColumnCasts::default(), CsvOptions::tsv());
+        assert_eq!(tsv, "id\tname\n1\tAlice\n2\tB|o'b\n");
+
+        let options = CsvOptions {
+            delimiter: '|',
+            quote: '\'',
+            header: false,
+        };
+        let out = to_delimited(&results, &ColumnCasts::default(), options);
+        assert_eq!(out, "1|Alice\n2|'B|o''b'\n");
+    }
+
+    #[test]
+    fn test_extract_csv_options() {
+        let mut options = CsvOptions::csv();
+        assert_eq!(options.extract("out.csv | id=text"), Ok(None));
+
+        let rest = options
+            .extract("out.csv --delimiter '|' --no-header | id=text")
+            .unwrap();
+        assert_eq!(rest.as_deref(), Some("out.csv | id=text"));
+        assert_eq!(options.delimiter, '|');
+        assert!(!options.header);
+
+        let mut options = CsvOptions::csv();
+        assert_eq!(options.extract("-d tab -q \"'\""), Ok(Some(String::new())));
+        assert_eq!((options.delimiter, options.quote), ('\t', '\''));
+
+        let mut options = CsvOptions::csv();
+        assert!(options.extract("x.csv --delimiter ab").is_err());
+        assert!(options.extract("x.csv --quote").is_err());
+        assert!(options.extract("x.csv --quote ,").is_err());
+    }
+
+    #[test]
+    fn test_csv_escaping_commas() {
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "val".to_string(),
+                data_type: DataType::Text,
+                nullable: false,
+            }],
+            vec![Row {
+                values: vec![CellValue::Text("a,b".to_string())],
+            }],
+            Duration::from_millis(1),
+            1,
+        );
+        let csv = to_csv(&results);
+        assert!(csv.contains("\"a,b\""));
+    }
+
+    #[test]
+    fn test_csv_escaping_quotes() {
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "val".to_string(),
+                data_type: DataType::Text,
+                nullable: false,
+            }],
+            vec![Row {
+                values: vec![CellValue::Text("say \"hi\"".to_string())],
+            }],
+            Duration::from_millis(1),
+            1,
+        );
+        let csv = to_csv(&results);
+        assert!(csv.contains("\"say \"\"hi\"\"\""));
+    }
+
+    #[test]
+    fn test_csv_escaping_newlines() {
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "val".to_string(),
+                data_type: DataType::Text,
+                nullable: false,
+            }],
+            vec![Row {
+                values: vec![CellValue::Text("line1\nline2".to_string())],
+            }],
+            Duration::from_millis(1),
+            1,
+        );
+        let csv = to_csv(&results);
+        assert!(csv.contains("\"line1\nline2\""));
+    }
+
+    #[test]
+    fn test_csv_null_is_empty() {
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "val".to_string(),
+                data_type: DataType::Text,
+                nullable: true,
+            }],
+            vec![Row {
+                values: vec![CellValue::Null],
+            }],
+            Duration::from_millis(1),
+            1,
+        );
+        let csv = to_csv(&results);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[1], "");
+    }
+
+    #[test]
+    fn test_csv_empty_results() {
+        let results = QueryResults::new(
+            vec![
+                ColumnDef {
+                    name: "a".to_string(),
+                    data_type: DataType::Integer,
+                    nullable: false,
+                },
+                ColumnDef {
+                    name: "b".to_string(),
+                    data_type: DataType::Text,
+                    nullable: false,
+                },
+            ],
+            vec![],
+            Duration::from_millis(1),
+            0,
+        );
+        let csv = to_csv(&results);
+        assert_eq!(csv, "a,b\n");
+    }
+
+    #[test]
+    fn test_json_typed_values() {
+        let json_str = to_json(&sample_results());
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0]["id"], 1);
+        assert_eq!(parsed[0]["name"], "Alice");
+        assert_eq!(parsed[1]["id"], 2);
+        assert_eq!(parsed[1]["name"], "Bob");
+    }
+
+    #[test]
+    fn test_json_null_and_bool() {
+        let results = QueryResults::new(
+            vec![
+                ColumnDef {
+                    name: "flag".to_string(),
+                    data_type: DataType::Boolean,
+                    nullable: false,
+                },
+                ColumnDef {
+                    name: "missing".to_string(),
+                    data_type: DataType::Text,
+                    nullable: true,
+                },
+            ],
+            vec![Row {
+                values: vec![CellValue::Boolean(true), CellValue::Null],
+            }],
+            Duration::from_millis(1),
+            1,
+        );
+        let json_str = to_json(&results);
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(parsed[0]["flag"], true);
+        assert!(parsed[0]["missing"].is_null());
+    }
+
+    #[test]
+    fn test_json_passthrough() {
+        let inner = serde_json::json!({"nested": [1, 2, 3]});
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "data".to_string(),
+                data_type: DataType::Jsonb,
+                nullable: false,
+            }],
+            vec![Row {
+                values: vec![CellValue::Json(inner.to_string())],
+            }],
+            Duration::from_millis(1),
+            1,
+        );
+        let json_str = to_json(&results);
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(parsed[0]["data"], inner);
+    }
+
+    #[test]
+    fn test_json_arrays() {
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "tags".to_string(),
+                data_type: DataType::Array(Box::new(DataType::Text)),
+                nullable: false,
+            }],
+            vec![Row {
+                values: vec![CellValue::Array(vec![
+                    CellValue::Text("a".to_string()),
+                    CellValue::Text("b".to_string()),
+                ])],
+            }],
+            Duration::from_millis(1),
+            1,
+        );
+        let json_str = to_json(&results);
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(parsed[0]["tags"], serde_json::json!(["a", "b"]));
+    }
+
+    #[test]
+    fn test_json_empty_results() {
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "x".to_string(),
+                data_type: DataType::Integer,
+                nullable: false,
+            }],
+            vec![],
+            Duration::from_millis(1),
+            0,
+        );
+        let json_str = to_json(&results);
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str).unwrap();
+        assert!(parsed.is_empty());
+    }
+
+    #[test]
+    fn test_csv_binary_hex_encoded() {
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "data".to_string(),
+                data_type: DataType::Bytea,
+                nullable: false,
+            }],
+            vec![Row {
+                values: vec![CellValue::Binary(vec![0xde, 0xad, 0xbe, 0xef])],
+            }],
+            Duration::from_millis(1),
+            1,
+        );
+        let csv = to_csv(&results);
+        assert!(csv.contains("\\xdeadbeef"));
+    }
+
+    #[test]
+    fn test_json_nan_infinity_as_strings() {
+        let results = QueryResults::new(
+            vec![
+                ColumnDef {
+                    name: "a".to_string(),
+                    data_type: DataType::Double,
+                    nullable: false,
+                },
+                ColumnDef {
+                    name: "b".to_string(),
+                    data_type: DataType::Double,
+                    nullable: false,
+                },
+                ColumnDef {
+                    name: "c".to_string(),
+                    data_type: DataType::Double,
+                    nullable: false,
+                },
+            ],
+            vec![Row {
+                values: vec![
+                    CellValue::Float(f64::NAN),
+                    CellValue::Float(f64::INFINITY),
+                    CellValue::Float(f64::NEG_INFINITY),
+                ],
+            }],
+            Duration::from_millis(1),
+            1,
+        );
+        // Should not panic and should produce valid JSON
+        let json_str = to_json(&results);
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(parsed[0]["a"], "NaN");
+        assert_eq!(parsed[0]["b"], "inf");
+        assert_eq!(parsed[0]["c"], "-inf");
+    }
+
+    #[test]
+    fn test_csv_nan_infinity() {
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "x".to_string(),
+                data_type: DataType::Double,
+                nullable: false,
+            }],
+            vec![Row {
+                values: vec![CellValue::Float(f64::NAN)],
+            }],
+            Duration::from_millis(1),
+            1,
+        );
+        let csv = to_csv(&results);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[1], "NaN");
+    }
+
+    #[test]
+    fn test_csv_header_needs_escaping() {
+        let results = QueryResults::new(
+            vec![ColumnDef {
+                name: "col,name".to_string(),
+                data_type: DataType::Text,
+                nullable: false,
+            }],
+            vec![],
+            Duration::from_millis(1),
+            0,
+        );
+        let csv = to_csv(&results);
+        assert_eq!(csv, "\"col,name\"\n");
+    }
+
+    fn typed_results() -> QueryResults {
+        let col = |name: &str, data_type| ColumnDef {
+            name: name.to_string(),
+            data_type,
+            nullable: true,
+        };
+        QueryResults::new(
+            vec![
+                col("created_at", DataType::TimestampTz),
+                col("Total", DataType::Numeric),
+                col("active", DataType::Boolean),
+            ],
+            vec![
+                Row {
+                    values: vec![
+                        CellValue::DateTime("2024-03-01 09:30:00 UTC".to_string()),
+                        CellValue::Text("12.345".to_string()),
+                        CellValue::Boolean(true),
+                    ],
+                },
+                Row {
+                    values: vec![CellValue::Null, CellValue::Null, CellValue::Null],
+                },
+            ],
+            Duration::from_millis(1),
+            2,
+        )
+    }
+
+    #[test]
+    fn test_csv_with_casts() {
+        let results = typed_results();
+        let casts = ColumnCasts::parse("created_at=notz, total=fixed:2", &results.columns).unwrap();
+        let csv = to_csv_with_casts(&results, &casts);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[1], "2024-03-01 09:30:00,12.35,true");
+        // NULLs stay empty
+        assert_eq!(lines[2], ",,");
+    }
+
+    #[test]
+    fn test_json_casts_become_strings() {
+        let results = typed_results();
+        let casts = ColumnCasts::parse("active=text,created_at=date", &results.columns).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json_with_casts(&results, &casts)).unwrap();
+        assert_eq!(json[0]["active"], "true");
+        assert_eq!(json[0]["created_at"], "2024-03-01");
+        assert_eq!(json[0]["Total"], "12.345");
+        assert_eq!(json[1]["active"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_parse_casts_errors() {
+        let columns = typed_results().columns;
+        assert!(
+            ColumnCasts::parse("nope=text", &columns)
+                .unwrap_err()
+                .contains("Unknown column 'nope'")
+        );
+        assert!(
+            ColumnCasts::parse("total=bogus", &columns)
+                .unwrap_err()
+                .contains("Unknown cast 'bogus'")
+        );
+        assert!(ColumnCasts::parse("total", &columns).is_err());
+        assert_eq!(
+            ColumnCasts::parse(" ", &columns).unwrap(),
+            ColumnCasts {
+                casts: vec![None; 3]
+            }
+        );
+    }
+
+    #[test]
+    fn test_strip_timezone() {
+        assert_eq!(
+            strip_timezone("2024-03-01 09:30:00+02"),
+            "2024-03-01 09:30:00"
+        );
+        assert_eq!(
+            strip_timezone("2024-03-01 09:30:00.5-05:30"),
+            "2024-03-01 09:30:00.5"
+        );
+        assert_eq!(
+            strip_timezone("2024-03-01T09:30:00Z"),
+            "2024-03-01T09:30:00"
+        );
+        assert_eq!(strip_timezone("2024-03-01"), "2024-03-01");
+    }
+
+    #[test]
+    fn test_fixed_cast_leaves_non_numbers() {
+        assert_eq!(ColumnCast::Fixed(1).apply("NaN".to_string()), "NaN");
+        assert_eq!(ColumnCast::Fixed(0).apply("2.5".to_string()), "3");
+        assert_eq!(ColumnCast::Fixed(3).apply("7".to_string()), "7.000");
+    }
+
+    /// Stream `results` in batches of one row
+    fn streamed(results: &QueryResults, format: ExportFormat, options: CsvOptions) -> String {
+        let mut stream = RowStream::new(format, ColumnCasts::default(), options).unwrap();
+        let mut out = stream.begin(&results.columns);
+        for row in &results.rows {
+            out.push_str(&stream.rows(std::slice::from_ref(row)));
+        }
+        out.push_str(&stream.finish());
+        out
+    }
+
+    #[test]
+    fn test_stream_matches_whole_export() {
+        let results = sample_results();
+        let casts = ColumnCasts::default();
+        assert_eq!(
+            streamed(&results, ExportFormat::Csv, CsvOptions::csv()),
+            to_csv(&results)
+        );
+        assert_eq!(
+            streamed(&results, ExportFormat::Tsv, CsvOptions::tsv()),
+            to_delimited(&results, &casts, CsvOptions::tsv())
+        );
+        assert_eq!(
+            streamed(&results, ExportFormat::Json, CsvOptions::csv()),
+            to_json(&results)
+        );
+        assert_eq!(
+            streamed(&results, ExportFormat::Markdown, CsvOptions::csv()),
+            to_markdown(&results)
+        );
+
+        let empty = QueryResults::new(results.columns.clone(), vec![], Duration::ZERO, 0);
+        assert_eq!(
+            streamed(&empty, ExportFormat::Json, CsvOptions::csv()),
+            to_json(&empty)
+        );
+    }
+
+    #[test]
+    fn test_stream_rejects_xlsx() {
+        let stream = RowStream::new(
+            ExportFormat::Xlsx,
+            ColumnCasts::default(),
+            CsvOptions::csv(),
+        );
+        assert!(stream.is_err());
+    }
+
+    #[test]
+    fn test_copy_options() {
+        assert_eq!(
+            CsvOptions::csv().copy_options(),
+            "FORMAT csv, DELIMITER ',', QUOTE '\"', HEADER true"
+        );
+        let mut options = CsvOptions::tsv();
+        options.quote = '\'';
+        options.header = false;
+        assert_eq!(
+            options.copy_options(),
+            "FORMAT csv, DELIMITER '\t', QUOTE '''', HEADER false"
+        );
+    }
+
+    #[test]
+    fn test_csv_row_counter_across_chunks() {
+        let mut counter = CsvRowCounter::new(&CsvOptions::csv());
+        for chunk in ["id,note\n1,\"a\n", "b \"\"x\"\"\"\n2,", "\n"] {
+            counter.feed(chunk.as_bytes());
+        }
+        assert_eq!(counter.rows(), 2);
+
+        let mut options = CsvOptions::csv();
+        options.header = false;
+        let mut counter = CsvRowCounter::new(&options);
+        assert_eq!(counter.rows(), 0);
+        counter.feed(b"1\n2\n");
+        assert_eq!(counter.rows(), 2);
+    }
+}
@@ -0,0 +1,459 @@
+//! XLSX (Excel) export
+//!
+//! Writes a single-sheet workbook: a bold, frozen header row, numbers and
+//! booleans as typed cells, and dates and timestamps as date-formatted
+//! serials so spreadsheets can sort and compute with them. Everything else
+//! is an inline string. The zip container is written by hand (deflated
+//! entries, no shared strings table) to keep the format self-contained.
+
+use std::io::Write;
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use flate2::Crc;
+use flate2::write::DeflateEncoder;
+use rust_decimal::Decimal;
+
+use super::{ColumnCast, ColumnCasts, cell_to_export_string, strip_timezone};
+use crate::db::types::{CellValue, DataType, QueryResults};
+
+/// Longest text Excel accepts in one cell
+const MAX_CELL_CHARS: usize = 32_767;
+
+/// Cell style indices into `cellXfs` in styles.xml
+const STYLE_DATE: u8 = 1;
+const STYLE_DATETIME: u8 = 2;
+const STYLE_TIME: u8 = 3;
+const STYLE_HEADER: u8 = 4;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Results" sheetId="1" r:id="rId1"/></sheets></workbook>"#;
+
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><numFmts count="3"><numFmt numFmtId="164" formatCode="yyyy\-mm\-dd"/><numFmt numFmtId="165" formatCode="yyyy\-mm\-dd\ hh:mm:ss"/><numFmt numFmtId="166" formatCode="hh:mm:ss"/></numFmts><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="5"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="164" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="165" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="166" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs><cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles></styleSheet>"#;
+
+/// How one value is written to the sheet
+#[derive(Debug, PartialEq)]
+enum XlsxCell {
+    Number(String),
+    Bool(bool),
+    /// A date/time serial with its number format
+    Date(f64, u8),
+    Text(String),
+}
+
+/// Serialize query results as an XLSX workbook.
+pub fn to_xlsx(results: &QueryResults) -> Vec<u8> {
+    to_xlsx_with_casts(results, &ColumnCasts::default())
+}
+
+/// Serialize query results as an XLSX workbook, rewriting cast columns.
+/// `text` casts become strings and `fixed:N` stays numeric.
+pub fn to_xlsx_with_casts(results: &QueryResults, casts: &ColumnCasts) -> Vec<u8> {
+    let mut zip = ZipWriter::default();
+    zip.add("[Content_Types].xml", CONTENT_TYPES.as_bytes());
+    zip.add("_rels/.rels", ROOT_RELS.as_bytes());
+    zip.add("xl/workbook.xml", WORKBOOK.as_bytes());
+    zip.add("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.as_bytes());
+    zip.add("xl/styles.xml", STYLES.as_bytes());
+    zip.add(
+        "xl/worksheets/sheet1.xml",
+        sheet_xml(results, casts).as_bytes(),
+    );
+    zip.finish()
+}
+
+fn sheet_xml(results: &QueryResults, casts: &ColumnCasts) -> String {
+    let mut out = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews><sheetData>"#,
+    );
+    let letters: Vec<String> = (0..results.columns.len()).map(column_letters).collect();
+
+    out.push_str(r#"<row r="1">"#);
+    for (i, col) in results.columns.iter().enumerate() {
+        let cell_ref = format!("{}1", letters[i]);
+        write_cell(
+            &mut out,
+            &cell_ref,
+            &XlsxCell::Text(col.name.clone()),
+            Some(STYLE_HEADER),
+        );
+    }
+    out.push_str("</row>");
+
+    for (r, row) in results.rows.iter().enumerate() {
+        let row_num = r + 2;
+        out.push_str(&format!(r#"<row r="{}">"#, row_num));
+        for (i, cell) in row.values.iter().enumerate() {
+            if matches!(cell, CellValue::Null) {
+                continue;
+            }
+            let data_type = results.columns.get(i).map(|c| &c.data_type);
+            let value = typed_cell(cell, data_type, casts.get(i));
+            let cell_ref = format!("{}{}", letters[i], row_num);
+            write_cell(&mut out, &cell_ref, &value, None);
+        }
+        out.push_str("</row>");
+    }
+    out.push_str("</sheetData></worksheet>");
+    out
+}
+
+/// Decide how a non-NULL value is written, after any cast
+fn typed_cell(
+    cell: &CellValue,
+    data_type: Option<&DataType>,
+    cast: Option<ColumnCast>,
+) -> XlsxCell {
+    if let Some(cast) = cast {
+        let text = cast.apply(cell_to_export_string(cell));
+        return match cast {
+            ColumnCast::Text => XlsxCell::Text(text),
+            ColumnCast::Fixed(_) => number(&text).unwrap_or(XlsxCell::Text(text)),
+            ColumnCast::NoTimezone | ColumnCast::Date => {
+                date(&text).unwrap_or(XlsxCell::Text(text))
+            }
+        };
+    }
+    match cell {
+        CellValue::Integer(i) => XlsxCell::Number(i.to_string()),
+        CellValue::Float(f) if f.is_finite() => XlsxCell::Number(f.to_string()),
+        CellValue::Boolean(b) => XlsxCell::Bool(*b),
+        CellValue::DateTime(s) => date(s).unwrap_or_else(|| XlsxCell::Text(s.clone())),
+        // numeric arrives as text to keep its precision
+        CellValue::Text(s) if data_type == Some(&DataType::Numeric) => {
+            number(s).unwrap_or_else(|| XlsxCell::Text(s.clone()))
+        }
+        _ => XlsxCell::Text(cell_to_export_string(cell)),
+    }
+}
+
+/// A plain decimal number (not NaN or Infinity), as written
+fn number(text: &str) -> Option<XlsxCell> {
+    Decimal::from_str(text)
+        .or_else(|_| Decimal::from_scientific(text))
+        .ok()
+        .map(|_| XlsxCell::Number(text.to_string()))
+}
+
+/// A date, timestamp, or time of day as an Excel serial. Time zones are
+/// dropped: the sheet shows the same wall-clock time vizgres does.
+fn date(text: &str) -> Option<XlsxCell> {
+    let text = strip_timezone(text.trim());
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?;
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(text, format) {
+            let days = (dt.date() - epoch).num_days() as f64;
+            return Some(XlsxCell::Date(
+                days + day_fraction(dt.time()),
+                STYLE_DATETIME,
+            ));
+        }
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Some(XlsxCell::Date((d - epoch).num_days() as f64, STYLE_DATE));
+    }
+    if let Ok(t) = NaiveTime::parse_from_str(text, "%H:%M:%S%.f") {
+        return Some(XlsxCell::Date(day_fraction(t), STYLE_TIME));
+    }
+    None
+}
+
+fn day_fraction(t: NaiveTime) -> f64 {
+    let secs = t.num_seconds_from_midnight() as f64 + t.nanosecond() as f64 / 1e9;
+    secs / 86_400.0
+}
+
+fn write_cell(out: &mut String, cell_ref: &str, value: &XlsxCell, style: Option<u8>) {
+    let style = match (style, value) {
+        (Some(s), _) | (None, &XlsxCell::Date(_, s)) => format!(r#" s="{}""#, s),
+        _ => String::new(),
+    };
+    match value {
+        XlsxCell::Number(n) => {
+            out.push_str(&format!(r#"<c r="{}"{}><v>{}</v></c>"#, cell_ref, style, n))
+        }
+        XlsxCell::Date(serial, _) => out.push_str(&format!(
+            r#"<c r="{}"{}><v>{}</v></c>"#,
+            cell_ref, style, serial
+        )),
+        XlsxCell::Bool(b) => out.push_str(&format!(
+            r#"<c r="{}"{} t="b"><v>{}</v></c>"#,
+            cell_ref,
+            style,
+            u8::from(*b)
+        )),
+        XlsxCell::Text(s) => {
+            out.push_str(&format!(
+                r#"<c r="{}"{} t="inlineStr"><is><t xml:space="preserve">"#,
+                cell_ref, style
+            ));
+            xml_escape_into(out, s);
+            out.push_str("</t></is></c>");
+        }
+    }
+}
+
+/// Escape text for XML, dropping control characters XML can't carry and
+/// cutting it to Excel's per-cell limit
+fn xml_escape_into(out: &mut String, text: &str) {
+    for c in text.chars().take(MAX_CELL_CHARS) {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+            c => out.push(c),
+        }
+    }
+}
+
+/// Spreadsheet column name for a 0-based index (0 → A, 26 → AA)
+fn column_letters(mut index: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap_or_default()
+}
+
+/// Minimal zip archive writer: deflated entries, no zip64, fixed timestamps
+#[derive(Default)]
+struct ZipWriter {
+    data: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+impl ZipWriter {
+    /// MS-DOS date for 1980-01-01, the earliest a zip can record
+    const DOS_DATE: u16 = 0x21;
+
+    fn add(&mut self, name: &str, content: &[u8]) {
+        let mut crc = Crc::new();
+        crc.update(content);
+        let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        // Writing to a Vec can't fail
+        let _ = encoder.write_all(content);
+        let compressed = encoder.finish().unwrap_or_default();
+        let offset = self.data.len() as u32;
+
+        let mut header = Vec::new();
+        push_u16(&mut header, 20); // version needed
+        push_u16(&mut header, 0); // flags
+        push_u16(&mut header, 8); // deflate
+        push_u16(&mut header, 0); // time
+        push_u16(&mut header, Self::DOS_DATE);
+        push_u32(&mut header, crc.sum());
+        push_u32(&mut header, compressed.len() as u32);
+        push_u32(&mut header, content.len() as u32);
+        push_u16(&mut header, name.len() as u16);
+        push_u16(&mut header, 0); // extra field length
+
+        push_u32(&mut self.data, 0x0403_4b50);
+        self.data.extend_from_slice(&header);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(&compressed);
+
+        push_u32(&mut self.central, 0x0201_4b50);
+        push_u16(&mut self.central, 20); // version made by
+        self.central.extend_from_slice(&header);
+        push_u16(&mut self.central, 0); // comment length
+        push_u16(&mut self.central, 0); // disk number
+        push_u16(&mut self.central, 0); // internal attributes
+        push_u32(&mut self.central, 0); // external attributes
+        push_u32(&mut self.central, offset);
+        self.central.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let central_offset = self.data.len() as u32;
+        let central_size = self.central.len() as u32;
+        self.data.append(&mut self.central);
+        push_u32(&mut self.data, 0x0605_4b50);
+        push_u16(&mut self.data, 0); // this disk
+        push_u16(&mut self.data, 0); // disk with the central directory
+        push_u16(&mut self.data, self.entries);
+        push_u16(&mut self.data, self.entries);
+        push_u32(&mut self.data, central_size);
+        push_u32(&mut self.data, central_offset);
+        push_u16(&mut self.data, 0); // comment length
+        self.data
+    }
+}
+
+fn push_u16(out: &mut Vec<u8>, n: u16) {
+    out.extend_from_slice(&n.to_le_bytes());
+}
+
+fn push_u32(out: &mut Vec<u8>, n: u32) {
+    out.extend_from_slice(&n.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::{ColumnDef, Row};
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+    use std::time::Duration;
+
+    /// Read every entry of a zip written by `ZipWriter` back out
+    fn unzip(data: &[u8]) -> Vec<(String, String)> {
+        let u16_at = |i: usize| u16::from_le_bytes([data[i], data[i + 1]]) as usize;
+        let u32_at =
+            |i: usize| u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+        let mut files = Vec::new();
+        let mut pos = 0;
+        while u32_at(pos) == 0x0403_4b50 {
+            let size = u32_at(pos + 18) as usize;
+            let name_len = u16_at(pos + 26);
+            let name = String::from_utf8(data[pos + 30..pos + 30 + name_len].to_vec()).unwrap();
+            let start = pos + 30 + name_len;
+            let mut content = String::new();
+            DeflateDecoder::new(&data[start..start + size])
+                .read_to_string(&mut content)
+                .unwrap();
+            let mut crc = Crc::new();
+            crc.update(content.as_bytes());
+            assert_eq!(crc.sum(), u32_at(pos + 14), "{}", name);
+            files.push((name, content));
+            pos = start + size;
+        }
+        assert_eq!(u32_at(pos), 0x0201_4b50);
+        files
+    }
+
+    fn sheet(data: &[u8]) -> String {
+        unzip(data)
+            .into_iter()
+            .find(|(name, _)| name == "xl/worksheets/sheet1.xml")
+            .unwrap()
+            .1
+    }
+
+    fn typed_results() -> QueryResults {
+        let col = |name: &str, data_type| ColumnDef {
+            name: name.to_string(),
+            data_type,
+            nullable: true,
+        };
+        QueryResults::new(
+            vec![
+                col("id", DataType::Integer),
+                col("total", DataType::Numeric),
+                col("paid", DataType::Boolean),
+                col("created_at", DataType::TimestampTz),
+                col("note", DataType::Text),
+            ],
+            vec![
+                Row {
+                    values: vec![
+                        CellValue::Integer(7),
+                        CellValue::Text("12.50".to_string()),
+                        CellValue::Boolean(true),
+                        CellValue::DateTime("2024-03-01 12:00:00+02".to_string()),
+                        CellValue::Text("a < b & \"c\"\u{1}".to_string()),
+                    ],
+                },
+                Row {
+                    values: vec![
+                        CellValue::Integer(8),
+                        CellValue::Text("NaN".to_string()),
+                        CellValue::Null,
+                        CellValue::DateTime("infinity".to_string()),
+                        CellValue::Null,
+                    ],
+                },
+            ],
+            Duration::from_millis(1),
+            2,
+        )
+    }
+
+    #[test]
+    fn test_workbook_parts() {
+        let names: Vec<String> = unzip(&to_xlsx(&typed_results()))
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "[Content_Types].xml",
+                "_rels/.rels",
+                "xl/workbook.xml",
+                "xl/_rels/workbook.xml.rels",
+                "xl/styles.xml",
+                "xl/worksheets/sheet1.xml"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typed_cells() {
+        let xml = sheet(&to_xlsx(&typed_results()));
+        assert!(xml.contains(
+            r#"<c r="A1" s="4" t="inlineStr"><is><t xml:space="preserve">id</t></is></c>"#
+        ));
+        assert!(xml.contains(r#"<c r="A2"><v>7</v></c>"#));
+        assert!(xml.contains(r#"<c r="B2"><v>12.50</v></c>"#));
+        assert!(xml.contains(r#"<c r="C2" t="b"><v>1</v></c>"#));
+        // 2024-03-01 is serial 45352; noon is half a day
+        assert!(xml.contains(r#"<c r="D2" s="2"><v>45352.5</v></c>"#));
+        assert!(xml.contains("a &lt; b &amp; &quot;c&quot;</t>"));
+        // Not numbers or dates: kept as text; NULLs are left out
+        assert!(xml.contains(r#"<c r="B3" t="inlineStr"><is><t xml:space="preserve">NaN</t>"#));
+        assert!(xml.contains(r#"<t xml:space="preserve">infinity</t>"#));
+        assert!(!xml.contains(r#"r="C3""#));
+    }
+
+    #[test]
+    fn test_casts() {
+        let results = typed_results();
+        let casts = ColumnCasts::parse("id=text, created_at=date, total=fixed:1", &results.columns)
+            .unwrap();
+        let xml = sheet(&to_xlsx_with_casts(&results, &casts));
+        assert!(xml.contains(r#"<c r="A2" t="inlineStr"><is><t xml:space="preserve">7</t>"#));
+        assert!(xml.contains(r#"<c r="B2"><v>12.5</v></c>"#));
+        assert!(xml.contains(r#"<c r="D2" s="1"><v>45352</v></c>"#));
+    }
+
+    #[test]
+    fn test_dates_and_times() {
+        assert_eq!(date("1900-03-01"), Some(XlsxCell::Date(61.0, STYLE_DATE)));
+        assert_eq!(date("06:00:00"), Some(XlsxCell::Date(0.25, STYLE_TIME)));
+        assert_eq!(
+            date("2024-03-01T18:00:00Z"),
+            Some(XlsxCell::Date(45352.75, STYLE_DATETIME))
+        );
+        assert_eq!(date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_column_letters() {
+        assert_eq!(column_letters(0), "A");
+        assert_eq!(column_letters(25), "Z");
+        assert_eq!(column_letters(26), "AA");
+        assert_eq!(column_letters(701), "ZZ");
+        assert_eq!(column_letters(702), "AAA");
+    }
+}
@@ -1,29 +1,134 @@
 //! Query history with shell-like navigation
 //!
 //! Ring buffer of executed queries, navigable with Ctrl+Up/Down.
-//! Saves the current editor content as a "draft" when entering browse mode,
-//! and restores it when navigating past the newest entry.
+//! Browsing state lives in a per-tab `HistoryCursor`, so each tab resumes
+//! where it left off. The cursor saves the editor content as a "draft" when
+//! entering browse mode, and restores it when navigating past the newest entry.
 //!
-//! History is persisted to `~/.vizgres/history` using null-byte separators
-//! (multi-line SQL is preserved). Persistence is best-effort: failures
-//! are silently ignored so the app never crashes over history I/O.
-
+//! Each entry remembers when it ran and on which connection and, once the
+//! query finishes, how long it took and whether it failed or how many rows
+//! came back. The Ctrl+R browser and `vizgres history` show all of it.
+//!
+//! History is persisted to `~/.vizgres/history.jsonl`, one JSON object per
+//! entry (multi-line SQL is preserved). The older `~/.vizgres/history` file,
+//! plain SQL separated by null bytes, is read when the new file doesn't
+//! exist yet. Persistence is best-effort: failures are silently ignored so
+//! the app never crashes over history I/O.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// Separator between entries in the legacy history file. Null bytes never
+/// appear in SQL, so this cleanly handles multi-line queries without escaping.
+const LEGACY_ENTRY_SEPARATOR: char = '\0';
+
+/// An executed query and what's known about its run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub sql: String,
+    /// When it last ran, in Unix seconds (unknown for imported entries)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ran_at: Option<i64>,
+    /// How long it took, once it completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Rows it returned, once it completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows: Option<usize>,
+    /// Connection it ran on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<String>,
+    /// Whether it succeeded, once it finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub succeeded: Option<bool>,
+}
+
+impl HistoryEntry {
+    fn new(sql: String) -> Self {
+        Self {
+            sql,
+            ran_at: None,
+            duration_ms: None,
+            rows: None,
+            connection: None,
+            succeeded: None,
+        }
+    }
+
+    /// When it ran as local "YYYY-MM-DD HH:MM" (empty if unknown)
+    pub fn ran_at_label(&self) -> String {
+        self.ran_at
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
 
-/// Separator between history entries on disk. Null bytes never appear in SQL,
-/// so this cleanly handles multi-line queries without escaping.
-const ENTRY_SEPARATOR: char = '\0';
+    /// "840ms" below a second, "3.2s" from there (empty if unknown)
+    pub fn duration_label(&self) -> String {
+        match self.duration_ms {
+            Some(ms) if ms < 1000 => format!("{}ms", ms),
+            Some(ms) => format!("{:.1}s", ms as f64 / 1000.0),
+            None => String::new(),
+        }
+    }
+
+    /// "failed", "N rows", or empty while unknown
+    pub fn outcome_label(&self) -> String {
+        if self.succeeded == Some(false) {
+            return "failed".to_string();
+        }
+        self.rows.map(|n| format!("{} rows", n)).unwrap_or_default()
+    }
+
+    /// The SQL with its line breaks and indentation collapsed to single spaces
+    pub fn one_line_sql(&self) -> String {
+        self.sql.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
 
 pub struct QueryHistory {
-    entries: VecDeque<String>,
+    entries: VecDeque<HistoryEntry>,
     capacity: usize,
-    /// `None` = not browsing, `Some(i)` = showing `entries[i]`
+    /// Number of entries dropped from the front so far. Entry `i` has
+    /// sequence number `evicted + i`, which stays stable as entries are added.
+    evicted: usize,
+    /// File path for persistence (`None` = in-memory only)
+    path: Option<PathBuf>,
+    /// Connection stamped on new entries
+    connection: Option<String>,
+}
+
+/// One tab's position while browsing history
+#[derive(Debug, Clone, Default)]
+pub struct HistoryCursor {
+    /// `None` = not browsing, `Some(n)` = showing the entry with sequence number `n`
     position: Option<usize>,
     /// Editor content saved when entering browse mode
     draft: Option<String>,
-    /// File path for persistence (`None` = in-memory only)
-    path: Option<PathBuf>,
+}
+
+impl HistoryCursor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_browsing(&self) -> bool {
+        self.position.is_some()
+    }
+
+    /// Editor content saved when browsing started (None when not browsing)
+    pub fn draft(&self) -> Option<&str> {
+        self.draft.as_deref()
+    }
+
+    /// Leave browse mode, forgetting the draft
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.draft = None;
+    }
 }
 
 impl QueryHistory {
@@ -34,29 +139,38 @@ impl QueryHistory {
         Self {
             entries: VecDeque::with_capacity(capacity),
             capacity,
-            position: None,
-            draft: None,
+            evicted: 0,
             path: None,
+            connection: None,
         }
     }
 
-    /// Load history from `~/.vizgres/history`, creating an empty history
-    /// if the file doesn't exist or can't be read.
+    /// Load history from `~/.vizgres/history.jsonl` (or the legacy
+    /// `~/.vizgres/history`), creating an empty history if neither exists
+    /// or can't be read.
     pub fn load(capacity: usize) -> Self {
-        let path = dirs::home_dir().map(|h| h.join(".vizgres").join("history"));
-        Self::load_from(path, capacity)
+        let dir = dirs::home_dir().map(|h| h.join(".vizgres"));
+        let path = dir.as_ref().map(|d| d.join("history.jsonl"));
+        let legacy = dir.map(|d| d.join("history"));
+        let mut history = Self::load_from(path, capacity);
+        if history.entries.is_empty()
+            && let Some(legacy) = legacy
+        {
+            history.import_legacy(&legacy);
+        }
+        history
     }
 
     fn load_from(path: Option<PathBuf>, capacity: usize) -> Self {
         assert!(capacity > 0, "QueryHistory capacity must be > 0");
-        let mut entries: VecDeque<String> = path
+        // Lines that don't parse (e.g. a write cut short) are skipped
+        let mut entries: VecDeque<HistoryEntry> = path
             .as_ref()
             .and_then(|p| std::fs::read_to_string(p).ok())
             .map(|content| {
                 content
-                    .split(ENTRY_SEPARATOR)
-                    .filter(|s| !s.is_empty())
-                    .map(String::from)
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
                     .collect()
             })
             .unwrap_or_default();
@@ -69,10 +183,27 @@ impl QueryHistory {
         Self {
             entries,
             capacity,
-            position: None,
-            draft: None,
+            evicted: 0,
             path,
+            connection: None,
+        }
+    }
+
+    /// Take over the entries of a legacy null-separated history file,
+    /// which is left in place
+    fn import_legacy(&mut self, legacy: &std::path::Path) {
+        let Ok(content) = std::fs::read_to_string(legacy) else {
+            return;
+        };
+        self.entries = content
+            .split(LEGACY_ENTRY_SEPARATOR)
+            .filter(|s| !s.is_empty())
+            .map(|s| HistoryEntry::new(s.to_string()))
+            .collect();
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
         }
+        self.save();
     }
 
     /// Write all entries to disk. Best-effort: errors are silently ignored.
@@ -81,80 +212,130 @@ impl QueryHistory {
         if let Some(dir) = path.parent() {
             let _ = std::fs::create_dir_all(dir);
         }
-        let content: String = self
-            .entries
-            .iter()
-            .map(|s| s.as_str())
-            .collect::<Vec<_>>()
-            .join(&ENTRY_SEPARATOR.to_string());
+        let mut content = String::new();
+        for entry in &self.entries {
+            if let Ok(line) = serde_json::to_string(entry) {
+                content.push_str(&line);
+                content.push('\n');
+            }
+        }
         let _ = std::fs::write(path, content);
     }
 
     /// Record an executed query. Trims whitespace, skips empty,
-    /// deduplicates consecutive entries, drops oldest at capacity.
-    pub fn push(&mut self, query: &str) {
+    /// deduplicates consecutive entries (refreshing the run time), drops
+    /// oldest at capacity. Returns the entry's sequence number for
+    /// `record_result`. Cursors are left alone; the executing tab resets
+    /// its own.
+    pub fn push(&mut self, query: &str) -> Option<usize> {
         let trimmed = query.trim().to_string();
         if trimmed.is_empty() {
-            return;
-        }
-        // Skip consecutive duplicates
-        if self.entries.back() == Some(&trimmed) {
-            self.reset_position();
-            return;
+            return None;
         }
-        if self.entries.len() == self.capacity {
+        let mut entry = HistoryEntry::new(trimmed);
+        entry.ran_at = Some(chrono::Utc::now().timestamp());
+        entry.connection = self.connection.clone();
+        // A consecutive duplicate replaces the previous run
+        if self
+            .entries
+            .back()
+            .is_some_and(|e| e.sql == entry.sql && e.connection == entry.connection)
+        {
+            self.entries.pop_back();
+        } else if self.entries.len() == self.capacity {
             self.entries.pop_front();
+            self.evicted += 1;
         }
-        self.entries.push_back(trimmed);
-        self.reset_position();
+        self.entries.push_back(entry);
+        self.save();
+        Some(self.evicted + self.entries.len() - 1)
+    }
+
+    /// Set the connection name stamped on entries pushed from now on
+    pub fn set_connection(&mut self, name: Option<String>) {
+        self.connection = name;
+    }
+
+    /// Note how long the entry with sequence number `seq` took and how many
+    /// rows it returned. Ignored if the entry has since been evicted.
+    pub fn record_result(&mut self, seq: usize, duration: Duration, rows: usize) {
+        let Some(entry) = self.entry_mut(seq) else {
+            return;
+        };
+        entry.duration_ms = Some(duration.as_millis() as u64);
+        entry.rows = Some(rows);
+        entry.succeeded = Some(true);
+        self.save();
+    }
+
+    /// Note that the entry with sequence number `seq` failed, after
+    /// `duration` if known. Ignored if the entry has since been evicted.
+    pub fn record_failure(&mut self, seq: usize, duration: Option<Duration>) {
+        let Some(entry) = self.entry_mut(seq) else {
+            return;
+        };
+        entry.duration_ms = duration.map(|d| d.as_millis() as u64);
+        entry.rows = None;
+        entry.succeeded = Some(false);
         self.save();
     }
 
-    /// Navigate to an older entry. On first call, saves `current_content` as draft.
-    /// Returns `None` when already at the oldest entry.
-    pub fn back(&mut self, current_content: &str) -> Option<&str> {
+    fn entry_mut(&mut self, seq: usize) -> Option<&mut HistoryEntry> {
+        seq.checked_sub(self.evicted)
+            .and_then(|i| self.entries.get_mut(i))
+    }
+
+    /// All entries, newest first
+    pub fn entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().rev()
+    }
+
+    /// Move `cursor` to an older entry. On first call, saves `current_content`
+    /// as the cursor's draft. Returns `None` when already at the oldest entry.
+    pub fn back<'a>(
+        &'a self,
+        cursor: &mut HistoryCursor,
+        current_content: &str,
+    ) -> Option<&'a str> {
         if self.entries.is_empty() {
             return None;
         }
-        let new_pos = match self.position {
+        let new_pos = match cursor.position {
             None => {
                 // Entering browse mode — save draft
-                self.draft = Some(current_content.to_string());
-                self.entries.len() - 1
+                cursor.draft = Some(current_content.to_string());
+                self.evicted + self.entries.len() - 1
+            }
+            // An entry evicted while this tab was browsing continues from the oldest
+            Some(p) => {
+                let older = p.saturating_sub(1).max(self.evicted);
+                if older == p {
+                    return None; // already at oldest
+                }
+                older
             }
-            Some(0) => return None, // already at oldest
-            Some(p) => p - 1,
         };
-        self.position = Some(new_pos);
-        Some(&self.entries[new_pos])
+        cursor.position = Some(new_pos);
+        Some(&self.entries[new_pos - self.evicted].sql)
     }
 
-    /// Navigate to a newer entry. When moving past the newest,
+    /// Move `cursor` to a newer entry. When moving past the newest,
     /// restores the draft and exits browse mode.
     /// Returns `None` when not browsing.
-    pub fn forward(&mut self) -> Option<&str> {
-        let pos = self.position?;
-        if pos + 1 < self.entries.len() {
-            self.position = Some(pos + 1);
-            Some(&self.entries[pos + 1])
+    pub fn forward<'a>(&'a self, cursor: &'a mut HistoryCursor) -> Option<&'a str> {
+        let pos = cursor.position?;
+        let next = (pos + 1).max(self.evicted);
+        if next - self.evicted < self.entries.len() {
+            cursor.position = Some(next);
+            Some(&self.entries[next - self.evicted].sql)
         } else {
             // Past newest — restore draft
-            self.position = None;
+            cursor.position = None;
             // Return draft content; caller will set_content with it
-            self.draft.as_deref()
+            cursor.draft.as_deref()
         }
     }
 
-    fn reset_position(&mut self) {
-        self.position = None;
-        self.draft = None;
-    }
-
-    #[cfg(test)]
-    fn is_browsing(&self) -> bool {
-        self.position.is_some()
-    }
-
     #[cfg(test)]
     fn len(&self) -> usize {
         self.entries.len()
@@ -175,7 +356,7 @@ mod tests {
         let h = QueryHistory::new(100);
         assert!(h.is_empty());
         assert_eq!(h.len(), 0);
-        assert!(!h.is_browsing());
+        assert!(!HistoryCursor::new().is_browsing());
     }
 
     #[test]
@@ -190,7 +371,7 @@ mod tests {
     fn test_push_trims_whitespace() {
         let mut h = QueryHistory::new(100);
         h.push("  SELECT 1  \n  ");
-        assert_eq!(h.entries[0], "SELECT 1");
+        assert_eq!(h.entries[0].sql, "SELECT 1");
     }
 
     #[test]
@@ -235,102 +416,153 @@ mod tests {
         h.push("c");
         h.push("d");
         assert_eq!(h.len(), 3);
-        assert_eq!(h.entries[0], "b");
-        assert_eq!(h.entries[2], "d");
+        assert_eq!(h.entries[0].sql, "b");
+        assert_eq!(h.entries[2].sql, "d");
     }
 
     #[test]
     fn test_back_from_empty() {
-        let mut h = QueryHistory::new(100);
-        assert!(h.back("draft").is_none());
+        let mut c = HistoryCursor::new();
+        let h = QueryHistory::new(100);
+        assert!(h.back(&mut c, "draft").is_none());
     }
 
     #[test]
     fn test_back_returns_newest_first() {
+        let mut c = HistoryCursor::new();
         let mut h = QueryHistory::new(100);
         h.push("SELECT 1");
         h.push("SELECT 2");
-        assert_eq!(h.back("draft"), Some("SELECT 2"));
+        assert_eq!(h.back(&mut c, "draft"), Some("SELECT 2"));
     }
 
     #[test]
     fn test_back_stops_at_oldest() {
+        let mut c = HistoryCursor::new();
         let mut h = QueryHistory::new(100);
         h.push("SELECT 1");
         h.push("SELECT 2");
-        h.back("draft"); // → SELECT 2
-        h.back("draft"); // → SELECT 1
-        assert!(h.back("draft").is_none()); // at oldest
+        h.back(&mut c, "draft"); // → SELECT 2
+        h.back(&mut c, "draft"); // → SELECT 1
+        assert!(h.back(&mut c, "draft").is_none()); // at oldest
     }
 
     #[test]
     fn test_forward_without_browsing() {
+        let mut c = HistoryCursor::new();
         let mut h = QueryHistory::new(100);
         h.push("SELECT 1");
-        assert!(h.forward().is_none());
+        assert!(h.forward(&mut c).is_none());
     }
 
     #[test]
     fn test_forward_returns_newer() {
+        let mut c = HistoryCursor::new();
         let mut h = QueryHistory::new(100);
         h.push("SELECT 1");
         h.push("SELECT 2");
-        h.back("draft"); // → SELECT 2
-        h.back("draft"); // → SELECT 1
-        assert_eq!(h.forward(), Some("SELECT 2"));
+        h.back(&mut c, "draft"); // → SELECT 2
+        h.back(&mut c, "draft"); // → SELECT 1
+        assert_eq!(h.forward(&mut c), Some("SELECT 2"));
     }
 
     #[test]
     fn test_forward_past_newest_restores_draft() {
+        let mut c = HistoryCursor::new();
         let mut h = QueryHistory::new(100);
         h.push("SELECT 1");
-        h.back("my draft"); // → SELECT 1
-        let restored = h.forward(); // past newest
+        h.back(&mut c, "my draft"); // → SELECT 1
+        let restored = h.forward(&mut c); // past newest
         assert_eq!(restored, Some("my draft"));
-        assert!(!h.is_browsing());
+        assert!(!c.is_browsing());
     }
 
     #[test]
-    fn test_push_resets_position() {
+    fn test_cursor_survives_push_from_another_tab() {
+        let mut c = HistoryCursor::new();
         let mut h = QueryHistory::new(100);
         h.push("SELECT 1");
         h.push("SELECT 2");
-        h.back("draft");
-        assert!(h.is_browsing());
+        assert_eq!(h.back(&mut c, "draft"), Some("SELECT 2"));
         h.push("SELECT 3");
-        assert!(!h.is_browsing());
+        assert!(c.is_browsing());
+        assert_eq!(h.back(&mut c, "ignored"), Some("SELECT 1"));
+        assert_eq!(h.forward(&mut c), Some("SELECT 2"));
+        assert_eq!(h.forward(&mut c), Some("SELECT 3"));
+        assert_eq!(h.forward(&mut c), Some("draft"));
+    }
+
+    #[test]
+    fn test_cursors_are_independent() {
+        let mut h = QueryHistory::new(100);
+        h.push("a");
+        h.push("b");
+        let mut tab1 = HistoryCursor::new();
+        let mut tab2 = HistoryCursor::new();
+        h.back(&mut tab1, "draft 1");
+        assert_eq!(h.back(&mut tab1, "draft 1"), Some("a"));
+        assert_eq!(h.back(&mut tab2, "draft 2"), Some("b"));
+        assert_eq!(tab1.draft(), Some("draft 1"));
+        assert_eq!(h.forward(&mut tab2), Some("draft 2"));
+        assert_eq!(h.forward(&mut tab1), Some("b"));
+    }
+
+    #[test]
+    fn test_cursor_on_evicted_entry_continues_from_oldest() {
+        let mut c = HistoryCursor::new();
+        let mut h = QueryHistory::new(3);
+        h.push("a");
+        h.push("b");
+        h.push("c");
+        h.back(&mut c, "draft");
+        h.back(&mut c, "draft");
+        assert_eq!(h.back(&mut c, "draft"), Some("a"));
+        h.push("d"); // evicts "a" while the cursor is on it
+        h.push("e"); // evicts "b"
+        assert_eq!(h.back(&mut c, "draft"), Some("c"));
+        assert!(h.back(&mut c, "draft").is_none());
+        assert_eq!(h.forward(&mut c), Some("d"));
+
+        let mut c = HistoryCursor::new();
+        h.back(&mut c, "draft");
+        h.back(&mut c, "draft");
+        assert_eq!(h.back(&mut c, "draft"), Some("c"));
+        h.push("f"); // evicts "c"
+        assert_eq!(h.forward(&mut c), Some("d"));
     }
 
     #[test]
     fn test_back_forward_round_trip() {
+        let mut c = HistoryCursor::new();
         let mut h = QueryHistory::new(100);
         h.push("a");
         h.push("b");
         h.push("c");
-        assert_eq!(h.back("draft"), Some("c"));
-        assert_eq!(h.back("draft"), Some("b"));
-        assert_eq!(h.back("draft"), Some("a"));
-        assert!(h.back("draft").is_none());
-        assert_eq!(h.forward(), Some("b"));
-        assert_eq!(h.forward(), Some("c"));
-        assert_eq!(h.forward(), Some("draft"));
-        assert!(!h.is_browsing());
+        assert_eq!(h.back(&mut c, "draft"), Some("c"));
+        assert_eq!(h.back(&mut c, "draft"), Some("b"));
+        assert_eq!(h.back(&mut c, "draft"), Some("a"));
+        assert!(h.back(&mut c, "draft").is_none());
+        assert_eq!(h.forward(&mut c), Some("b"));
+        assert_eq!(h.forward(&mut c), Some("c"));
+        assert_eq!(h.forward(&mut c), Some("draft"));
+        assert!(!c.is_browsing());
     }
 
     #[test]
     fn test_re_enter_browse_after_draft_restore() {
+        let mut c = HistoryCursor::new();
         let mut h = QueryHistory::new(100);
         h.push("SELECT 1");
 
         // First browse cycle
-        h.back("draft1");
-        h.forward(); // restores draft1
-        assert!(!h.is_browsing());
+        h.back(&mut c, "draft1");
+        h.forward(&mut c); // restores draft1
+        assert!(!c.is_browsing());
 
         // Second browse cycle with new draft
-        assert_eq!(h.back("draft2"), Some("SELECT 1"));
-        assert_eq!(h.forward(), Some("draft2"));
-        assert!(!h.is_browsing());
+        assert_eq!(h.back(&mut c, "draft2"), Some("SELECT 1"));
+        assert_eq!(h.forward(&mut c), Some("draft2"));
+        assert!(!c.is_browsing());
     }
 
     #[test]
@@ -345,7 +577,7 @@ mod tests {
         let dir =
             std::env::temp_dir().join(format!("vizgres-test-{}-{}", std::process::id(), name));
         let _ = std::fs::create_dir_all(&dir);
-        dir.join("history")
+        dir.join("history.jsonl")
     }
 
     fn cleanup(path: &std::path::Path) {
@@ -375,9 +607,9 @@ mod tests {
         }
         let h = QueryHistory::load_from(Some(path.clone()), 100);
         assert_eq!(h.len(), 3);
-        assert_eq!(h.entries[0], "SELECT 1");
-        assert_eq!(h.entries[1], "SELECT 2");
-        assert_eq!(h.entries[2], "SELECT 3");
+        assert_eq!(h.entries[0].sql, "SELECT 1");
+        assert_eq!(h.entries[1].sql, "SELECT 2");
+        assert_eq!(h.entries[2].sql, "SELECT 3");
         cleanup(&path);
     }
 
@@ -392,8 +624,8 @@ mod tests {
         }
         let h = QueryHistory::load_from(Some(path.clone()), 100);
         assert_eq!(h.len(), 2);
-        assert_eq!(h.entries[0], "SELECT *\nFROM users\nWHERE id = 1");
-        assert_eq!(h.entries[1], "INSERT INTO t\nVALUES (1, 'hello')");
+        assert_eq!(h.entries[0].sql, "SELECT *\nFROM users\nWHERE id = 1");
+        assert_eq!(h.entries[1].sql, "INSERT INTO t\nVALUES (1, 'hello')");
         cleanup(&path);
     }
 
@@ -410,9 +642,9 @@ mod tests {
         // Reload with smaller capacity — keeps newest
         let h = QueryHistory::load_from(Some(path.clone()), 3);
         assert_eq!(h.len(), 3);
-        assert_eq!(h.entries[0], "SELECT 7");
-        assert_eq!(h.entries[1], "SELECT 8");
-        assert_eq!(h.entries[2], "SELECT 9");
+        assert_eq!(h.entries[0].sql, "SELECT 7");
+        assert_eq!(h.entries[1].sql, "SELECT 8");
+        assert_eq!(h.entries[2].sql, "SELECT 9");
         cleanup(&path);
     }
 
@@ -433,7 +665,7 @@ mod tests {
         // Load a second instance — should see the entry
         let h2 = QueryHistory::load_from(Some(path.clone()), 100);
         assert_eq!(h2.len(), 1);
-        assert_eq!(h2.entries[0], "first");
+        assert_eq!(h2.entries[0].sql, "first");
 
         // Push more and verify
         h.push("second");
@@ -441,4 +673,93 @@ mod tests {
         assert_eq!(h3.len(), 2);
         cleanup(&path);
     }
+
+    #[test]
+    fn test_record_result_persists_metadata() {
+        let path = temp_history_path("metadata");
+        cleanup(&path);
+        {
+            let mut h = QueryHistory::load_from(Some(path.clone()), 100);
+            let seq = h.push("SELECT 1").unwrap();
+            h.record_result(seq, Duration::from_millis(42), 7);
+        }
+        let h = QueryHistory::load_from(Some(path.clone()), 100);
+        let entry = h.entries().next().unwrap();
+        assert_eq!(entry.sql, "SELECT 1");
+        assert!(entry.ran_at.is_some());
+        assert_eq!(entry.duration_ms, Some(42));
+        assert_eq!(entry.rows, Some(7));
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_duplicate_push_starts_a_new_run() {
+        let mut h = QueryHistory::new(100);
+        let first = h.push("SELECT 1").unwrap();
+        h.record_result(first, Duration::from_millis(5), 1);
+        let second = h.push("SELECT 1").unwrap();
+        assert_eq!(first, second);
+        assert_eq!(h.len(), 1);
+        assert_eq!(h.entries().next().unwrap().rows, None);
+        assert_eq!(h.push("   "), None);
+    }
+
+    #[test]
+    fn test_record_failure_and_connection() {
+        let mut h = QueryHistory::new(100);
+        h.push("SELECT 1");
+        h.set_connection(Some("prod".to_string()));
+        let seq = h.push("SELECT 1").unwrap();
+        // Same SQL on another connection is a separate entry
+        assert_eq!(h.len(), 2);
+        h.record_failure(seq, Some(Duration::from_millis(1500)));
+        let entry = h.entries().next().unwrap();
+        assert_eq!(entry.connection.as_deref(), Some("prod"));
+        assert_eq!(entry.succeeded, Some(false));
+        assert_eq!(entry.outcome_label(), "failed");
+        assert_eq!(entry.duration_label(), "1.5s");
+        let older = h.entries().nth(1).unwrap();
+        assert_eq!(older.connection, None);
+        assert_eq!(older.outcome_label(), "");
+    }
+
+    #[test]
+    fn test_entry_labels() {
+        let mut entry = HistoryEntry::new("SELECT *\n  FROM t".to_string());
+        entry.duration_ms = Some(840);
+        entry.rows = Some(3);
+        entry.succeeded = Some(true);
+        assert_eq!(entry.duration_label(), "840ms");
+        assert_eq!(entry.outcome_label(), "3 rows");
+        assert_eq!(entry.one_line_sql(), "SELECT * FROM t");
+        assert_eq!(entry.ran_at_label(), "");
+    }
+
+    #[test]
+    fn test_record_result_ignores_evicted_entry() {
+        let mut h = QueryHistory::new(2);
+        let seq = h.push("a").unwrap();
+        h.push("b");
+        h.push("c");
+        h.record_result(seq, Duration::from_millis(5), 1);
+        assert!(h.entries().all(|e| e.rows.is_none()));
+    }
+
+    #[test]
+    fn test_legacy_file_is_imported() {
+        let path = temp_history_path("legacy");
+        cleanup(&path);
+        let legacy = path.with_file_name("history");
+        std::fs::create_dir_all(legacy.parent().unwrap()).unwrap();
+        std::fs::write(&legacy, "SELECT 1\0SELECT *\nFROM t\0").unwrap();
+
+        let mut h = QueryHistory::load_from(Some(path.clone()), 100);
+        h.import_legacy(&legacy);
+        let sqls: Vec<&str> = h.entries().map(|e| e.sql.as_str()).collect();
+        assert_eq!(sqls, vec!["SELECT *\nFROM t", "SELECT 1"]);
+
+        let reloaded = QueryHistory::load_from(Some(path.clone()), 100);
+        assert_eq!(reloaded.len(), 2);
+        cleanup(&path);
+    }
 }
@@ -0,0 +1,472 @@
+//! Tabular data import (pasted TSV, CSV files)
+//!
+//! Parsing and SQL generation — no database I/O. Pasting spreadsheet
+//! cells onto a table in the tree turns them into a multi-row `INSERT` the
+//! user reviews in the editor before running; `/import` maps a CSV file's
+//! columns onto a table and re-encodes its rows for `COPY ... FROM STDIN`.
+
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use crate::db::schema::Table;
+use crate::sql::{quote_ident, quote_literal, quote_qualified};
+
+/// Pasted rows: a header line naming the columns, then the data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tsv {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// A generated `INSERT` and what went into it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasteInsert {
+    pub sql: String,
+    pub row_count: usize,
+    /// Pasted headers that match no column of the table (left out)
+    pub skipped: Vec<String>,
+}
+
+/// Parse tab-separated text with a header line.
+///
+/// Returns None unless there are at least two columns and one data row, so
+/// ordinary text pastes aren't mistaken for tabular data. Cells quoted the
+/// way spreadsheets copy them (`"a ""b""\tc"`) may contain tabs, newlines,
+/// and doubled quotes. Blank lines are ignored.
+pub fn parse_tsv(text: &str) -> Option<Tsv> {
+    let mut lines = split_records(text)
+        .into_iter()
+        .filter(|record| !(record.len() == 1 && record[0].is_empty()));
+    let headers: Vec<String> = lines
+        .next()?
+        .into_iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    if headers.len() < 2 {
+        return None;
+    }
+    let rows: Vec<Vec<String>> = lines.collect();
+    if rows.is_empty() {
+        return None;
+    }
+    Some(Tsv { headers, rows })
+}
+
+/// Split `text` into records of cells, honouring spreadsheet quoting
+fn split_records(text: &str) -> Vec<Vec<String>> {
+    CsvRecords::new(text.as_bytes(), '\t')
+        .filter_map(Result::ok)
+        .collect()
+}
+
+/// Records of delimited text, read one at a time so a large file needn't
+/// fit in memory. Cells quoted with `"` may hold the delimiter, line
+/// breaks, and doubled quotes.
+pub struct CsvRecords<R> {
+    reader: R,
+    delimiter: char,
+    line: String,
+}
+
+impl<R: BufRead> CsvRecords<R> {
+    pub fn new(reader: R, delimiter: char) -> Self {
+        Self {
+            reader,
+            delimiter,
+            line: String::new(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CsvRecords<R> {
+    type Item = std::io::Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = Vec::new();
+        let mut cell = String::new();
+        let mut at_cell_start = true;
+        let mut quoted = false;
+        let mut started = false;
+
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) if !started => return None,
+                // The last record had no line break
+                Ok(0) => {
+                    record.push(cell);
+                    return Some(Ok(record));
+                }
+                Ok(_) => started = true,
+                Err(e) => return Some(Err(e)),
+            }
+            let mut chars = self.line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if quoted {
+                    if c == '"' {
+                        if chars.peek() == Some(&'"') {
+                            chars.next();
+                            cell.push('"');
+                        } else {
+                            quoted = false;
+                        }
+                    } else {
+                        cell.push(c);
+                    }
+                    continue;
+                }
+                match c {
+                    '"' if at_cell_start => {
+                        quoted = true;
+                        at_cell_start = false;
+                    }
+                    c if c == self.delimiter => {
+                        record.push(std::mem::take(&mut cell));
+                        at_cell_start = true;
+                    }
+                    '\r' if chars.peek() == Some(&'\n') => {}
+                    '\n' => {
+                        record.push(cell);
+                        return Some(Ok(record));
+                    }
+                    _ => {
+                        cell.push(c);
+                        at_cell_start = false;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether a record is a blank line
+fn is_blank(record: &[String]) -> bool {
+    record.len() == 1 && record[0].is_empty()
+}
+
+/// Map headers onto `table`'s columns by name — exact match first, then
+/// ignoring case. Returns (header index, column name) pairs and the
+/// headers that match no column (or one already taken).
+fn map_headers<'t>(table: &'t Table, headers: &[String]) -> (Vec<(usize, &'t str)>, Vec<String>) {
+    let mut mapped: Vec<(usize, &str)> = Vec::new();
+    let mut skipped = Vec::new();
+    for (i, header) in headers.iter().enumerate() {
+        let column = table
+            .columns
+            .iter()
+            .find(|c| c.name == *header)
+            .or_else(|| {
+                table
+                    .columns
+                    .iter()
+                    .find(|c| c.name.eq_ignore_ascii_case(header))
+            });
+        match column {
+            Some(col) if !mapped.iter().any(|(_, name)| *name == col.name) => {
+                mapped.push((i, &col.name));
+            }
+            _ => skipped.push(header.clone()),
+        }
+    }
+    (mapped, skipped)
+}
+
+/// A CSV file mapped onto a table's columns, ready to stream with `COPY`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvImport {
+    pub path: PathBuf,
+    /// Target table, quoted and schema-qualified
+    pub table: String,
+    /// Table columns filled, in file order
+    pub columns: Vec<String>,
+    /// File column feeding each of `columns`
+    sources: Vec<usize>,
+    /// File headers that match no column (left out)
+    pub skipped: Vec<String>,
+    delimiter: char,
+}
+
+impl CsvImport {
+    /// Read the file's header line and map it onto `table`'s columns the
+    /// way pasted rows are mapped. A `.tsv` file is tab-separated. Fails
+    /// when the file can't be read or no header matches a column.
+    pub fn plan(path: &Path, schema: &str, table: &Table) -> Result<Self, String> {
+        let is_tsv = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("tsv"));
+        let delimiter = if is_tsv { '\t' } else { ',' };
+        let headers: Vec<String> = Self::open(path, delimiter)?
+            .find(|record| !matches!(record, Ok(r) if is_blank(r)))
+            .transpose()
+            .map_err(|e| format!("{}: {}", path.display(), e))?
+            .ok_or_else(|| format!("{} is empty", path.display()))?
+            .into_iter()
+            .map(|h| h.trim().to_string())
+            .collect();
+
+        let qualified = quote_qualified(schema, &table.name);
+        let (mapped, skipped) = map_headers(table, &headers);
+        if mapped.is_empty() {
+            return Err(format!(
+                "No column of {} matches a column of {}",
+                path.display(),
+                qualified
+            ));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            table: qualified,
+            columns: mapped.iter().map(|(_, name)| name.to_string()).collect(),
+            sources: mapped.iter().map(|(i, _)| *i).collect(),
+            skipped,
+            delimiter,
+        })
+    }
+
+    fn open(
+        path: &Path,
+        delimiter: char,
+    ) -> Result<CsvRecords<std::io::BufReader<std::fs::File>>, String> {
+        let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(CsvRecords::new(std::io::BufReader::new(file), delimiter))
+    }
+
+    /// The statement that receives the rows
+    pub fn copy_sql(&self) -> String {
+        let columns: Vec<String> = self.columns.iter().map(|c| quote_ident(c)).collect();
+        format!(
+            "COPY {} ({}) FROM STDIN (FORMAT csv)",
+            self.table,
+            columns.join(", ")
+        )
+    }
+
+    /// The file's data records (after the header), blank lines skipped
+    pub fn records(&self) -> Result<impl Iterator<Item = std::io::Result<Vec<String>>>, String> {
+        Ok(Self::open(&self.path, self.delimiter)?
+            .filter(|record| !matches!(record, Ok(r) if is_blank(r)))
+            .skip(1))
+    }
+
+    /// Number of data rows in the file, for a dry run
+    pub fn count_rows(&self) -> Result<usize, String> {
+        let mut rows = 0;
+        for record in self.records()? {
+            record.map_err(|e| format!("{}: {}", self.path.display(), e))?;
+            rows += 1;
+        }
+        Ok(rows)
+    }
+
+    /// Append one record to `COPY`'s CSV input: the mapped cells, with
+    /// empty (and missing) ones as NULL
+    pub fn copy_line_into(&self, record: &[String], out: &mut Vec<u8>) {
+        for (n, &i) in self.sources.iter().enumerate() {
+            if n > 0 {
+                out.push(b',');
+            }
+            let value = record.get(i).map_or("", String::as_str);
+            // Unquoted empty is NULL; `\.` alone would end the data
+            if value.contains([',', '"', '\n', '\r']) || value == "\\." {
+                out.push(b'"');
+                out.extend_from_slice(value.replace('"', "\"\"").as_bytes());
+                out.push(b'"');
+            } else {
+                out.extend_from_slice(value.as_bytes());
+            }
+        }
+        out.push(b'\n');
+    }
+}
+
+/// Build a multi-row `INSERT` into `schema.table` from pasted rows.
+///
+/// Headers map to columns by name — exact match first, then ignoring case.
+/// Unmatched headers are skipped (and reported), empty cells become NULL,
+/// and every other value is a quoted literal so PostgreSQL casts it to the
+/// column's type. Fails when no header matches a column.
+pub fn insert_statement(schema: &str, table: &Table, tsv: &Tsv) -> Result<PasteInsert, String> {
+    let (mapped, skipped) = map_headers(table, &tsv.headers);
+    if mapped.is_empty() {
+        return Err(format!(
+            "No pasted column matches a column of {}",
+            quote_qualified(schema, &table.name)
+        ));
+    }
+
+    let columns: Vec<String> = mapped.iter().map(|(_, name)| quote_ident(name)).collect();
+    let values: Vec<String> = tsv
+        .rows
+        .iter()
+        .map(|row| {
+            let cells: Vec<String> = mapped
+                .iter()
+                .map(|(i, _)| match row.get(*i).map(String::as_str) {
+                    None | Some("") => "NULL".to_string(),
+                    Some(value) => quote_literal(value),
+                })
+                .collect();
+            format!("    ({})", cells.join(", "))
+        })
+        .collect();
+
+    let sql = format!(
+        "INSERT INTO {} ({})\nVALUES\n{};",
+        quote_qualified(schema, &table.name),
+        columns.join(", "),
+        values.join(",\n")
+    );
+    Ok(PasteInsert {
+        sql,
+        row_count: tsv.rows.len(),
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::Column;
+    use crate::db::types::DataType;
+
+    fn table(columns: &[&str]) -> Table {
+        Table {
+            name: "users".to_string(),
+            columns: columns
+                .iter()
+                .map(|name| Column {
+                    name: name.to_string(),
+                    data_type: DataType::Text,
+                    is_primary_key: false,
+                    foreign_key: None,
+                })
+                .collect(),
+            row_count: None,
+            size_bytes: None,
+            select_denied: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_tsv_with_header() {
+        let tsv = parse_tsv("id\tname\r\n1\tAlice\r\n2\tBob\r\n").unwrap();
+        assert_eq!(tsv.headers, vec!["id", "name"]);
+        assert_eq!(tsv.rows, vec![vec!["1", "Alice"], vec!["2", "Bob"]]);
+    }
+
+    #[test]
+    fn test_parse_tsv_rejects_plain_text() {
+        assert_eq!(parse_tsv("SELECT 1"), None);
+        assert_eq!(parse_tsv("one\ntwo\nthree"), None);
+        // Header only, no data rows
+        assert_eq!(parse_tsv("id\tname\n"), None);
+    }
+
+    #[test]
+    fn test_parse_tsv_spreadsheet_quoting() {
+        let tsv = parse_tsv("id\tnote\n1\t\"line one\nline \"\"two\"\"\tstill\"\n\n2\t\n").unwrap();
+        assert_eq!(tsv.rows[0], vec!["1", "line one\nline \"two\"\tstill"]);
+        assert_eq!(tsv.rows[1], vec!["2", ""]);
+        assert_eq!(tsv.rows.len(), 2);
+    }
+
+    #[test]
+    fn test_insert_statement_maps_headers_by_name() {
+        let tsv = parse_tsv("Name\tid\tshoe_size\nAlice\t1\t38\nO'Brien\t2\t\n").unwrap();
+        let insert = insert_statement("public", &table(&["id", "name"]), &tsv).unwrap();
+        assert_eq!(
+            insert.sql,
+            "INSERT INTO \"public\".\"users\" (\"name\", \"id\")\n\
+             VALUES\n    ('Alice', '1'),\n    ('O''Brien', '2');"
+        );
+        assert_eq!(insert.row_count, 2);
+        assert_eq!(insert.skipped, vec!["shoe_size"]);
+    }
+
+    #[test]
+    fn test_insert_statement_empty_and_missing_cells_are_null() {
+        let tsv = Tsv {
+            headers: vec!["id".to_string(), "name".to_string()],
+            rows: vec![vec!["1".to_string(), String::new()], vec!["2".to_string()]],
+        };
+        let insert = insert_statement("public", &table(&["id", "name"]), &tsv).unwrap();
+        assert!(insert.sql.contains("('1', NULL),\n    ('2', NULL);"));
+    }
+
+    #[test]
+    fn test_insert_statement_prefers_exact_case_and_skips_duplicates() {
+        let tsv = parse_tsv("name\tName\tNAME\n1\t2\t3\n").unwrap();
+        let insert = insert_statement("public", &table(&["name", "Name"]), &tsv).unwrap();
+        assert!(insert.sql.contains("(\"name\", \"Name\")"));
+        assert_eq!(insert.skipped, vec!["NAME"]);
+    }
+
+    fn write_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("vizgres-{}-{}", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_csv_records_quoting_across_lines() {
+        let text = "id,note\r\n1,\"a, \"\"b\"\"\nc\"\n\n2,";
+        let records: Vec<Vec<String>> = CsvRecords::new(text.as_bytes(), ',')
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                vec!["id", "note"],
+                vec!["1", "a, \"b\"\nc"],
+                vec![""],
+                vec!["2", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csv_import_maps_and_reencodes() {
+        let path = write_file(
+            "import.csv",
+            "\nName,shoe_size,ID\nAlice,38,1\n\n\"O'Brien, \"\"Pat\"\"\",,2\n,40,\n",
+        );
+        let import = CsvImport::plan(&path, "public", &table(&["id", "name"])).unwrap();
+        assert_eq!(import.columns, vec!["name", "id"]);
+        assert_eq!(import.skipped, vec!["shoe_size"]);
+        assert_eq!(
+            import.copy_sql(),
+            "COPY \"public\".\"users\" (\"name\", \"id\") FROM STDIN (FORMAT csv)"
+        );
+        assert_eq!(import.count_rows().unwrap(), 3);
+
+        let mut out = Vec::new();
+        for record in import.records().unwrap() {
+            import.copy_line_into(&record.unwrap(), &mut out);
+        }
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Alice,1\n\"O'Brien, \"\"Pat\"\"\",2\n,\n"
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_csv_import_tsv_and_errors() {
+        let path = write_file("import.tsv", "id\tname\n1\tAlice\n");
+        let import = CsvImport::plan(&path, "public", &table(&["id", "name"])).unwrap();
+        assert_eq!(import.columns, vec!["id", "name"]);
+        let err = CsvImport::plan(&path, "public", &table(&["email"])).unwrap_err();
+        assert!(err.contains("No column of"));
+        let _ = std::fs::remove_file(&path);
+
+        let missing = std::env::temp_dir().join("vizgres-no-such-file.csv");
+        assert!(CsvImport::plan(&missing, "public", &table(&["id"])).is_err());
+    }
+
+    #[test]
+    fn test_insert_statement_without_matching_columns_fails() {
+        let tsv = parse_tsv("a\tb\n1\t2\n").unwrap();
+        let err = insert_statement("public", &table(&["id"]), &tsv).unwrap_err();
+        assert!(err.contains("\"public\".\"users\""));
+    }
+}
@@ -53,12 +53,34 @@ pub enum KeyAction {
     // Editor-specific
     ExecuteQuery,
     ExplainQuery,
+    EstimateRows,
     ClearEditor,
     HistoryBack,
     HistoryForward,
+    SearchHistory,
     Undo,
     Redo,
     FormatQuery,
+    Find,
+    Replace,
+    FindNext,
+    FindPrev,
+    /// Show the columns of the table named under the cursor
+    PreviewTable,
+    /// Copy / cut the editor's selection to the clipboard
+    CopySelection,
+    CutSelection,
+    /// Paste the clipboard over the editor's selection (or at the cursor)
+    Paste,
+    /// Line operations on the cursor's line, or the selected lines
+    DuplicateLine,
+    DeleteLine,
+    MoveLineUp,
+    MoveLineDown,
+    /// Comment or uncomment the cursor's line, or the selected lines
+    ToggleComment,
+    /// Move to the partner of the parenthesis at the cursor
+    JumpToBracket,
 
     // Query cancellation (works from editor, results, tree)
     CancelQuery,
@@ -68,12 +90,36 @@ pub enum KeyAction {
     ToggleViewMode,
     CopyCell,
     CopyRow,
+    /// Copy the (filtered) result set as CSV
+    CopyResultsCsv,
+    /// Copy the (filtered) result set as a Markdown table
+    CopyResultsMarkdown,
     ExportCsv,
     ExportJson,
+    PageLeft,
+    PageRight,
+    JumpToColumn,
+    FollowForeignKey,
+    /// Open the insert-row form for the selected (or results') table
+    InsertRow,
+    /// Insert the column's values into the editor as an `IN (...)` list
+    ColumnInList,
 
     // Inspector-specific
     CopyContent,
 
+    // Sessions overlay
+    RefreshSessions,
+    CancelSession,
+    /// End the selected session (pressed twice)
+    TerminateSession,
+
+    // Dashboard overlay
+    RefreshDashboard,
+
+    // Replication overlay
+    RefreshReplication,
+
     // Tree-specific
     ToggleExpand,
     Expand,
@@ -81,12 +127,31 @@ pub enum KeyAction {
     FilterTree,
     CopyName,
     ShowDefinition,
+    FindUsages,
     DeleteSavedQuery,
 
     // Column resize
     WidenColumn,
     NarrowColumn,
     ResetColumnWidths,
+    ExpandColumn,
+
+    // Client-side sorting
+    SortColumn,
+    ClearSort,
+    ColumnHistogram,
+    /// Keep the tab's results when its query re-runs (new results open in
+    /// a tab alongside)
+    TogglePin,
+    /// Show the tab's previous / next results
+    PreviousResults,
+    NextResults,
+
+    // Row filtering
+    FilterRows,
+
+    // Cell editing
+    EditCell,
 
     // Pagination
     NextPage,
@@ -107,6 +172,10 @@ pub enum KeyAction {
     // Modal dismiss/submit
     Dismiss,
     Submit,
+
+    // Command bar
+    /// Complete the file path of `/open`
+    CompletePath,
 }
 
 /// Keybinding configuration — maps key combos to semantic actions per context.
@@ -128,6 +197,16 @@ impl KeyMap {
         self.panels.get(&focus).and_then(|m| m.get(&bind)).copied()
     }
 
+    /// Resolve a key with the panel's own bindings only, for keys a panel
+    /// takes over from global ones in some states (Ctrl+C copies the
+    /// editor's selection rather than cancelling)
+    pub fn resolve_panel(&self, focus: PanelFocus, key: KeyEvent) -> Option<KeyAction> {
+        self.panels
+            .get(&focus)
+            .and_then(|m| m.get(&KeyBind::from(key)))
+            .copied()
+    }
+
     /// Build a KeyMap from defaults plus user overrides.
     /// Returns the keymap and a list of warning messages for invalid entries.
     pub fn from_config(config: &KeybindingsConfig) -> (Self, Vec<String>) {
@@ -315,29 +394,68 @@ pub fn parse_key_action(s: &str) -> Result<KeyAction, String> {
         "end" => Ok(KeyAction::End),
         "execute_query" => Ok(KeyAction::ExecuteQuery),
         "explain_query" => Ok(KeyAction::ExplainQuery),
+        "estimate_rows" => Ok(KeyAction::EstimateRows),
         "clear_editor" => Ok(KeyAction::ClearEditor),
         "history_back" => Ok(KeyAction::HistoryBack),
         "history_forward" => Ok(KeyAction::HistoryForward),
+        "search_history" => Ok(KeyAction::SearchHistory),
         "undo" => Ok(KeyAction::Undo),
         "redo" => Ok(KeyAction::Redo),
         "format_query" => Ok(KeyAction::FormatQuery),
+        "find" => Ok(KeyAction::Find),
+        "replace" => Ok(KeyAction::Replace),
+        "find_next" => Ok(KeyAction::FindNext),
+        "find_prev" => Ok(KeyAction::FindPrev),
+        "preview_table" => Ok(KeyAction::PreviewTable),
+        "copy_selection" => Ok(KeyAction::CopySelection),
+        "cut_selection" => Ok(KeyAction::CutSelection),
+        "paste" => Ok(KeyAction::Paste),
+        "duplicate_line" => Ok(KeyAction::DuplicateLine),
+        "delete_line" => Ok(KeyAction::DeleteLine),
+        "move_line_up" => Ok(KeyAction::MoveLineUp),
+        "move_line_down" => Ok(KeyAction::MoveLineDown),
+        "toggle_comment" => Ok(KeyAction::ToggleComment),
+        "jump_to_bracket" => Ok(KeyAction::JumpToBracket),
         "cancel_query" => Ok(KeyAction::CancelQuery),
         "open_inspector" => Ok(KeyAction::OpenInspector),
         "toggle_view_mode" => Ok(KeyAction::ToggleViewMode),
         "copy_cell" => Ok(KeyAction::CopyCell),
         "copy_row" => Ok(KeyAction::CopyRow),
+        "copy_results_csv" => Ok(KeyAction::CopyResultsCsv),
+        "copy_results_markdown" => Ok(KeyAction::CopyResultsMarkdown),
         "export_csv" => Ok(KeyAction::ExportCsv),
         "export_json" => Ok(KeyAction::ExportJson),
+        "page_left" => Ok(KeyAction::PageLeft),
+        "page_right" => Ok(KeyAction::PageRight),
+        "jump_to_column" => Ok(KeyAction::JumpToColumn),
+        "follow_foreign_key" => Ok(KeyAction::FollowForeignKey),
+        "insert_row" => Ok(KeyAction::InsertRow),
+        "column_in_list" => Ok(KeyAction::ColumnInList),
         "widen_column" => Ok(KeyAction::WidenColumn),
         "narrow_column" => Ok(KeyAction::NarrowColumn),
         "reset_column_widths" => Ok(KeyAction::ResetColumnWidths),
+        "expand_column" => Ok(KeyAction::ExpandColumn),
+        "sort_column" => Ok(KeyAction::SortColumn),
+        "clear_sort" => Ok(KeyAction::ClearSort),
+        "column_histogram" => Ok(KeyAction::ColumnHistogram),
+        "toggle_pin" => Ok(KeyAction::TogglePin),
+        "previous_results" => Ok(KeyAction::PreviousResults),
+        "next_results" => Ok(KeyAction::NextResults),
+        "filter_rows" => Ok(KeyAction::FilterRows),
+        "edit_cell" => Ok(KeyAction::EditCell),
         "copy_content" => Ok(KeyAction::CopyContent),
+        "refresh_sessions" => Ok(KeyAction::RefreshSessions),
+        "cancel_session" => Ok(KeyAction::CancelSession),
+        "terminate_session" => Ok(KeyAction::TerminateSession),
+        "refresh_dashboard" => Ok(KeyAction::RefreshDashboard),
+        "refresh_replication" => Ok(KeyAction::RefreshReplication),
         "toggle_expand" => Ok(KeyAction::ToggleExpand),
         "expand" => Ok(KeyAction::Expand),
         "collapse" => Ok(KeyAction::Collapse),
         "filter_tree" => Ok(KeyAction::FilterTree),
         "copy_name" => Ok(KeyAction::CopyName),
         "show_definition" => Ok(KeyAction::ShowDefinition),
+        "find_usages" => Ok(KeyAction::FindUsages),
         "delete_saved_query" => Ok(KeyAction::DeleteSavedQuery),
         "next_page" => Ok(KeyAction::NextPage),
         "prev_page" => Ok(KeyAction::PrevPage),
@@ -349,6 +467,7 @@ pub fn parse_key_action(s: &str) -> Result<KeyAction, String> {
         "next_tab" => Ok(KeyAction::NextTab),
         "dismiss" => Ok(KeyAction::Dismiss),
         "submit" => Ok(KeyAction::Submit),
+        "complete_path" => Ok(KeyAction::CompletePath),
         other => Err(format!("unknown action: {}", other)),
     }
 }
@@ -488,6 +607,13 @@ impl Default for KeyMap {
             },
             KeyAction::ExplainQuery,
         );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::CONTROL | KeyModifiers::ALT,
+            },
+            KeyAction::EstimateRows,
+        );
         editor.insert(
             KeyBind {
                 code: KeyCode::Char('l'),
@@ -509,6 +635,13 @@ impl Default for KeyMap {
             },
             KeyAction::HistoryForward,
         );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::SearchHistory,
+        );
         editor.insert(
             KeyBind {
                 code: KeyCode::Char('z'),
@@ -530,6 +663,41 @@ impl Default for KeyMap {
             },
             KeyAction::FormatQuery,
         );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('f'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::Find,
+        );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL | KeyModifiers::ALT,
+            },
+            KeyAction::Replace,
+        );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::F(3),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::FindNext,
+        );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::F(3),
+                modifiers: KeyModifiers::SHIFT,
+            },
+            KeyAction::FindPrev,
+        );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::F(4),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::PreviewTable,
+        );
         editor.insert(
             KeyBind {
                 code: KeyCode::Esc,
@@ -537,19 +705,77 @@ impl Default for KeyMap {
             },
             KeyAction::CancelQuery,
         );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::CopySelection,
+        );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('x'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::CutSelection,
+        );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('v'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::Paste,
+        );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('d'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::DuplicateLine,
+        );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('k'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::DeleteLine,
+        );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('/'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::ToggleComment,
+        );
+        // What most terminals send for Ctrl+/
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('7'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::ToggleComment,
+        );
+        editor.insert(
+            KeyBind {
+                code: KeyCode::Char('b'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::JumpToBracket,
+        );
+        // These also cycle completions while one is shown
         editor.insert(
             KeyBind {
                 code: KeyCode::Down,
                 modifiers: KeyModifiers::ALT,
             },
-            KeyAction::NextCompletion,
+            KeyAction::MoveLineDown,
         );
         editor.insert(
             KeyBind {
                 code: KeyCode::Up,
                 modifiers: KeyModifiers::ALT,
             },
-            KeyAction::PrevCompletion,
+            KeyAction::MoveLineUp,
         );
         panels.insert(PanelFocus::QueryEditor, editor);
 
@@ -584,6 +810,20 @@ impl Default for KeyMap {
             },
             KeyAction::CopyRow,
         );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('y'),
+                modifiers: KeyModifiers::ALT,
+            },
+            KeyAction::CopyResultsCsv,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('m'),
+                modifiers: KeyModifiers::ALT,
+            },
+            KeyAction::CopyResultsMarkdown,
+        );
         results.insert(
             KeyBind {
                 code: KeyCode::Char('s'),
@@ -633,6 +873,97 @@ impl Default for KeyMap {
             },
             KeyAction::ResetColumnWidths,
         );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('='),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::ExpandColumn,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('s'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::SortColumn,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('S'),
+                modifiers: KeyModifiers::SHIFT,
+            },
+            KeyAction::ClearSort,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('F'),
+                modifiers: KeyModifiers::SHIFT,
+            },
+            KeyAction::ColumnHistogram,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('P'),
+                modifiers: KeyModifiers::SHIFT,
+            },
+            KeyAction::TogglePin,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('['),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::PreviousResults,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char(']'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::NextResults,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('/'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::FilterRows,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::EditCell,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('f'),
+                modifiers: KeyModifiers::CONTROL,
+            },
+            KeyAction::Find,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::F(3),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::FindNext,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('N'),
+                modifiers: KeyModifiers::SHIFT,
+            },
+            KeyAction::FindPrev,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::F(3),
+                modifiers: KeyModifiers::SHIFT,
+            },
+            KeyAction::FindPrev,
+        );
         results.insert(
             KeyBind {
                 code: KeyCode::Char('n'),
@@ -647,6 +978,48 @@ impl Default for KeyMap {
             },
             KeyAction::PrevPage,
         );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Left,
+                modifiers: KeyModifiers::SHIFT,
+            },
+            KeyAction::PageLeft,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Right,
+                modifiers: KeyModifiers::SHIFT,
+            },
+            KeyAction::PageRight,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::JumpToColumn,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('f'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::FollowForeignKey,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('i'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::InsertRow,
+        );
+        results.insert(
+            KeyBind {
+                code: KeyCode::Char('I'),
+                modifiers: KeyModifiers::SHIFT,
+            },
+            KeyAction::ColumnInList,
+        );
         panels.insert(PanelFocus::ResultsViewer, results);
 
         // ── Tree ─────────────────────────────────────────────────
@@ -742,6 +1115,13 @@ impl Default for KeyMap {
             },
             KeyAction::ShowDefinition,
         );
+        tree.insert(
+            KeyBind {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::FindUsages,
+        );
         tree.insert(
             KeyBind {
                 code: KeyCode::Char('x'),
@@ -749,6 +1129,13 @@ impl Default for KeyMap {
             },
             KeyAction::DeleteSavedQuery,
         );
+        tree.insert(
+            KeyBind {
+                code: KeyCode::Char('i'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::InsertRow,
+        );
         panels.insert(PanelFocus::TreeBrowser, tree);
 
         // ── Inspector ────────────────────────────────────────────
@@ -782,6 +1169,154 @@ impl Default for KeyMap {
         insert_scroll_nav(&mut help);
         panels.insert(PanelFocus::Help, help);
 
+        // ── Message log overlay ──────────────────────────────────
+        let mut messages = HashMap::new();
+        messages.insert(
+            KeyBind {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::Dismiss,
+        );
+        insert_scroll_nav(&mut messages);
+        panels.insert(PanelFocus::Messages, messages);
+
+        // ── Background jobs overlay ──────────────────────────────
+        let mut jobs = HashMap::new();
+        jobs.insert(
+            KeyBind {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::Dismiss,
+        );
+        panels.insert(PanelFocus::Jobs, jobs);
+
+        // ── Column histogram overlay ─────────────────────────────
+        let mut histogram = HashMap::new();
+        histogram.insert(
+            KeyBind {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::Dismiss,
+        );
+        insert_scroll_nav(&mut histogram);
+        panels.insert(PanelFocus::Histogram, histogram);
+
+        // ── Table preview overlay ────────────────────────────────
+        let mut table_preview = HashMap::new();
+        table_preview.insert(
+            KeyBind {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::Dismiss,
+        );
+        insert_scroll_nav(&mut table_preview);
+        panels.insert(PanelFocus::TablePreview, table_preview);
+
+        // ── Notifications overlay ────────────────────────────────
+        let mut notifications = HashMap::new();
+        notifications.insert(
+            KeyBind {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::Dismiss,
+        );
+        insert_scroll_nav(&mut notifications);
+        panels.insert(PanelFocus::Notifications, notifications);
+
+        // ── Sessions overlay ─────────────────────────────────────
+        let mut sessions = HashMap::new();
+        sessions.insert(
+            KeyBind {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::Dismiss,
+        );
+        sessions.insert(
+            KeyBind {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::RefreshSessions,
+        );
+        sessions.insert(
+            KeyBind {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::CancelSession,
+        );
+        sessions.insert(
+            KeyBind {
+                code: KeyCode::Char('x'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::TerminateSession,
+        );
+        insert_scroll_nav(&mut sessions);
+        panels.insert(PanelFocus::Sessions, sessions);
+
+        // ── Dashboard overlay ────────────────────────────────────
+        let mut dashboard = HashMap::new();
+        dashboard.insert(
+            KeyBind {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::Dismiss,
+        );
+        dashboard.insert(
+            KeyBind {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::RefreshDashboard,
+        );
+        insert_scroll_nav(&mut dashboard);
+        panels.insert(PanelFocus::Dashboard, dashboard);
+
+        // ── Replication overlay ──────────────────────────────────
+        let mut replication = HashMap::new();
+        replication.insert(
+            KeyBind {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::Dismiss,
+        );
+        replication.insert(
+            KeyBind {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::RefreshReplication,
+        );
+        insert_scroll_nav(&mut replication);
+        panels.insert(PanelFocus::Replication, replication);
+
+        // ── Server summary (after connecting) ────────────────────
+        let mut server_summary = HashMap::new();
+        server_summary.insert(
+            KeyBind {
+                code: KeyCode::Esc,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::Dismiss,
+        );
+        server_summary.insert(
+            KeyBind {
+                code: KeyCode::Enter,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::Dismiss,
+        );
+        panels.insert(PanelFocus::ServerSummary, server_summary);
+
         // ── Command bar ──────────────────────────────────────────
         let mut command_bar = HashMap::new();
         command_bar.insert(
@@ -798,6 +1333,13 @@ impl Default for KeyMap {
             },
             KeyAction::Dismiss,
         );
+        command_bar.insert(
+            KeyBind {
+                code: KeyCode::Tab,
+                modifiers: KeyModifiers::NONE,
+            },
+            KeyAction::CompletePath,
+        );
         panels.insert(PanelFocus::CommandBar, command_bar);
 
         Self { global, panels }
@@ -958,6 +1500,12 @@ mod tests {
             km.resolve(PanelFocus::CommandBar, esc),
             Some(KeyAction::Dismiss)
         );
+        // Tab completes paths ahead of the global CycleFocus
+        let tab = KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(
+            km.resolve_panel(PanelFocus::CommandBar, tab),
+            Some(KeyAction::CompletePath)
+        );
     }
 
     #[test]
@@ -1076,6 +1624,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_editor_ctrl_c_copies_only_through_resolve_panel() {
+        let km = KeyMap::default();
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(
+            km.resolve(PanelFocus::QueryEditor, ctrl_c),
+            Some(KeyAction::CancelQuery)
+        );
+        assert_eq!(
+            km.resolve_panel(PanelFocus::QueryEditor, ctrl_c),
+            Some(KeyAction::CopySelection)
+        );
+        let ctrl_x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(
+            km.resolve(PanelFocus::QueryEditor, ctrl_x),
+            Some(KeyAction::CutSelection)
+        );
+    }
+
+    #[test]
+    fn test_editor_line_operation_bindings() {
+        let km = KeyMap::default();
+        let resolve =
+            |code, modifiers| km.resolve(PanelFocus::QueryEditor, KeyEvent::new(code, modifiers));
+        assert_eq!(
+            resolve(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            Some(KeyAction::DuplicateLine)
+        );
+        assert_eq!(
+            resolve(KeyCode::Char('k'), KeyModifiers::CONTROL),
+            Some(KeyAction::DeleteLine)
+        );
+        assert_eq!(
+            resolve(KeyCode::Up, KeyModifiers::ALT),
+            Some(KeyAction::MoveLineUp)
+        );
+        assert_eq!(
+            resolve(KeyCode::Down, KeyModifiers::ALT),
+            Some(KeyAction::MoveLineDown)
+        );
+        assert_eq!(
+            parse_key_action("move_line_down"),
+            Ok(KeyAction::MoveLineDown)
+        );
+        assert_eq!(
+            resolve(KeyCode::Char('b'), KeyModifiers::CONTROL),
+            Some(KeyAction::JumpToBracket)
+        );
+        for key in ['/', '7'] {
+            assert_eq!(
+                resolve(KeyCode::Char(key), KeyModifiers::CONTROL),
+                Some(KeyAction::ToggleComment)
+            );
+        }
+    }
+
     #[test]
     fn test_inspector_bindings() {
         let km = KeyMap::default();
@@ -1225,6 +1829,49 @@ mod tests {
         assert_eq!(km.resolve(PanelFocus::ResultsViewer, ctrl_alt_f), None);
     }
 
+    #[test]
+    fn test_find_replace_bindings() {
+        let km = KeyMap::default();
+        let ctrl_f = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL);
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        let ctrl_alt_r = KeyEvent::new(
+            KeyCode::Char('r'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+        );
+        let f3 = KeyEvent::new(KeyCode::F(3), KeyModifiers::NONE);
+        let shift_f3 = KeyEvent::new(KeyCode::F(3), KeyModifiers::SHIFT);
+        assert_eq!(
+            km.resolve(PanelFocus::QueryEditor, ctrl_f),
+            Some(KeyAction::Find)
+        );
+        assert_eq!(
+            km.resolve(PanelFocus::QueryEditor, ctrl_alt_r),
+            Some(KeyAction::Replace)
+        );
+        assert_eq!(
+            km.resolve(PanelFocus::QueryEditor, ctrl_r),
+            Some(KeyAction::SearchHistory)
+        );
+        assert_eq!(
+            km.resolve(PanelFocus::QueryEditor, f3),
+            Some(KeyAction::FindNext)
+        );
+        assert_eq!(
+            km.resolve(PanelFocus::QueryEditor, shift_f3),
+            Some(KeyAction::FindPrev)
+        );
+        // Results search shares the find keys; replace stays editor-only
+        assert_eq!(
+            km.resolve(PanelFocus::ResultsViewer, ctrl_f),
+            Some(KeyAction::Find)
+        );
+        assert_eq!(
+            km.resolve(PanelFocus::ResultsViewer, shift_f3),
+            Some(KeyAction::FindPrev)
+        );
+        assert_eq!(km.resolve(PanelFocus::ResultsViewer, ctrl_r), None);
+    }
+
     #[test]
     fn test_export_keybindings_resolve() {
         let km = KeyMap::default();
@@ -1370,6 +2017,78 @@ mod tests {
             KeyAction::ExplainQuery
         );
         assert_eq!(parse_key_action("copy_cell").unwrap(), KeyAction::CopyCell);
+        assert_eq!(
+            parse_key_action("copy_results_csv").unwrap(),
+            KeyAction::CopyResultsCsv
+        );
+        assert_eq!(
+            parse_key_action("copy_results_markdown").unwrap(),
+            KeyAction::CopyResultsMarkdown
+        );
+        assert_eq!(
+            parse_key_action("expand_column").unwrap(),
+            KeyAction::ExpandColumn
+        );
+        assert_eq!(
+            parse_key_action("sort_column").unwrap(),
+            KeyAction::SortColumn
+        );
+        assert_eq!(
+            parse_key_action("clear_sort").unwrap(),
+            KeyAction::ClearSort
+        );
+        assert_eq!(
+            parse_key_action("column_histogram").unwrap(),
+            KeyAction::ColumnHistogram
+        );
+        assert_eq!(
+            parse_key_action("toggle_pin").unwrap(),
+            KeyAction::TogglePin
+        );
+        assert_eq!(
+            parse_key_action("previous_results").unwrap(),
+            KeyAction::PreviousResults
+        );
+        assert_eq!(
+            parse_key_action("next_results").unwrap(),
+            KeyAction::NextResults
+        );
+        assert_eq!(
+            parse_key_action("filter_rows").unwrap(),
+            KeyAction::FilterRows
+        );
+        assert_eq!(parse_key_action("edit_cell").unwrap(), KeyAction::EditCell);
+        assert_eq!(parse_key_action("page_left").unwrap(), KeyAction::PageLeft);
+        assert_eq!(
+            parse_key_action("jump_to_column").unwrap(),
+            KeyAction::JumpToColumn
+        );
+        assert_eq!(
+            parse_key_action("follow_foreign_key").unwrap(),
+            KeyAction::FollowForeignKey
+        );
+        assert_eq!(
+            parse_key_action("insert_row").unwrap(),
+            KeyAction::InsertRow
+        );
+        assert_eq!(
+            parse_key_action("search_history").unwrap(),
+            KeyAction::SearchHistory
+        );
+        assert_eq!(
+            parse_key_action("estimate_rows").unwrap(),
+            KeyAction::EstimateRows
+        );
+        assert_eq!(
+            parse_key_action("find_usages").unwrap(),
+            KeyAction::FindUsages
+        );
+        assert_eq!(parse_key_action("find").unwrap(), KeyAction::Find);
+        assert_eq!(parse_key_action("find_prev").unwrap(), KeyAction::FindPrev);
+        assert_eq!(
+            parse_key_action("preview_table").unwrap(),
+            KeyAction::PreviewTable
+        );
         assert_eq!(parse_key_action("show_help").unwrap(), KeyAction::ShowHelp);
         assert_eq!(parse_key_action("new_tab").unwrap(), KeyAction::NewTab);
         assert_eq!(parse_key_action("dismiss").unwrap(), KeyAction::Dismiss);
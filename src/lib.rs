@@ -19,6 +19,10 @@
 //! - [`db`]: Database connectivity and schema introspection
 //! - [`ui`]: Terminal user interface components
 //! - [`commands`]: Command parsing for the command bar
+//! - [`import`]: Pasted tabular data to INSERT statements
+//! - [`mask`]: Column masking rules for sensitive result values
+//! - [`metrics`]: Session counters in Prometheus text format
+//! - [`sql`]: Identifier and literal quoting for generated SQL
 //! - [`error`]: Error types and result aliases
 //! - [`app`]: Application state and event handling
 //!
@@ -58,7 +62,11 @@ pub mod db;
 pub mod error;
 pub mod export;
 pub mod history;
+pub mod import;
 pub mod keymap;
+pub mod mask;
+pub mod metrics;
+pub mod sql;
 pub mod ui;
 
 pub use error::{CommandError, ConfigError, DbError, Result, VizgresError};
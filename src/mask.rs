@@ -0,0 +1,145 @@
+//! Result masking
+//!
+//! Columns whose names match a `mask_columns` pattern show a placeholder
+//! instead of their values — in the grid, the inspector, clipboard copies,
+//! and exports — until `/reveal`, for screen-sharing production data.
+//! Patterns ignore case, and `*` matches any run of characters.
+
+use crate::db::types::{CellValue, ColumnDef, Row};
+
+/// Text shown in place of a masked value
+pub const MASK: &str = "••••••";
+
+/// Compiled `mask_columns` patterns
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaskRules {
+    patterns: Vec<String>,
+}
+
+impl MaskRules {
+    pub fn new(patterns: &[String]) -> Self {
+        Self {
+            patterns: patterns
+                .iter()
+                .map(|p| p.trim().to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Whether a column of this name is masked
+    pub fn matches(&self, column: &str) -> bool {
+        let column = column.to_lowercase();
+        self.patterns.iter().any(|p| glob_match(p, &column))
+    }
+
+    /// Indexes of the masked columns
+    pub fn columns(&self, columns: &[ColumnDef]) -> Vec<usize> {
+        columns
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| self.matches(&c.name))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Put the placeholder in `columns` of `row`, returning the values it
+/// replaced by column. NULLs stay visible.
+pub fn mask_row(row: &mut Row, columns: &[usize]) -> Vec<(usize, CellValue)> {
+    let mut replaced = Vec::new();
+    for &col in columns {
+        if let Some(cell) = row.values.get_mut(col)
+            && !matches!(cell, CellValue::Null)
+        {
+            let value = std::mem::replace(cell, CellValue::Text(MASK.to_string()));
+            replaced.push((col, value));
+        }
+    }
+    replaced
+}
+
+/// Match `text` against `pattern`, where `*` matches any run of characters
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Last `*` seen, and the text position it currently stands in for
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((sp, st)) = star {
+            // Let the `*` swallow one more character and retry
+            p = sp + 1;
+            t = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::DataType;
+
+    fn rules(patterns: &[&str]) -> MaskRules {
+        MaskRules::new(&patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_glob_patterns() {
+        let rules = rules(&["*password*", "SSN", "card_*_number", " "]);
+        assert!(rules.matches("password"));
+        assert!(rules.matches("User_Password_Hash"));
+        assert!(rules.matches("ssn"));
+        assert!(!rules.matches("ssn_last4"));
+        assert!(rules.matches("card_visa_number"));
+        assert!(rules.matches("card__number"));
+        assert!(!rules.matches("card_number"));
+        assert!(!rules.matches("email"));
+        assert!(!rules.matches(""));
+    }
+
+    #[test]
+    fn test_masked_columns() {
+        let columns: Vec<ColumnDef> = ["id", "email", "api_token"]
+            .iter()
+            .map(|name| ColumnDef {
+                name: name.to_string(),
+                data_type: DataType::Text,
+                nullable: true,
+            })
+            .collect();
+        assert_eq!(rules(&["*token*", "email"]).columns(&columns), vec![1, 2]);
+        assert!(MaskRules::new(&[]).is_empty());
+        assert!(MaskRules::new(&[]).columns(&columns).is_empty());
+    }
+
+    #[test]
+    fn test_mask_row_keeps_nulls() {
+        let mut row = Row {
+            values: vec![
+                CellValue::Integer(1),
+                CellValue::Text("hunter2".to_string()),
+                CellValue::Null,
+            ],
+        };
+        let replaced = mask_row(&mut row, &[1, 2, 9]);
+        assert!(matches!(row.values[0], CellValue::Integer(1)));
+        assert!(matches!(&row.values[1], CellValue::Text(s) if s == MASK));
+        assert!(matches!(row.values[2], CellValue::Null));
+        assert!(matches!(&replaced[..], [(1, CellValue::Text(s))] if s == "hunter2"));
+    }
+}
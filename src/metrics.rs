@@ -0,0 +1,216 @@
+//! Session usage metrics
+//!
+//! Counters for the running session (queries, errors, bytes exported,
+//! connection drops), written in the Prometheus text format to the file
+//! named by the `metrics_file` setting after every change. Point a
+//! node_exporter textfile collector at it to watch vizgres sessions on a
+//! shared host. The file is removed when the session ends.
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A session counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    /// Queries that finished, successfully or not
+    Queries,
+    /// Queries that failed
+    QueryErrors,
+    /// Bytes written by exports
+    BytesExported,
+    /// Connections lost unexpectedly
+    ConnectionDrops,
+}
+
+impl Counter {
+    const ALL: [Counter; 4] = [
+        Counter::Queries,
+        Counter::QueryErrors,
+        Counter::BytesExported,
+        Counter::ConnectionDrops,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Counter::Queries => "vizgres_queries_total",
+            Counter::QueryErrors => "vizgres_query_errors_total",
+            Counter::BytesExported => "vizgres_exported_bytes_total",
+            Counter::ConnectionDrops => "vizgres_connection_drops_total",
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Counter::Queries => "Queries executed, including failed ones.",
+            Counter::QueryErrors => "Queries that returned an error.",
+            Counter::BytesExported => "Bytes written by result exports.",
+            Counter::ConnectionDrops => "Database connections lost unexpectedly.",
+        }
+    }
+}
+
+/// Counters for one session, optionally mirrored to a file
+#[derive(Debug)]
+pub struct Metrics {
+    path: Option<PathBuf>,
+    started: SystemTime,
+    counts: [u64; Counter::ALL.len()],
+}
+
+impl Metrics {
+    /// Metrics written to `path` (empty = kept in memory only). `~/` is the
+    /// home directory and `{pid}` the process id, so concurrent sessions
+    /// can write separate files.
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: resolve_path(path),
+            started: SystemTime::now(),
+            counts: [0; Counter::ALL.len()],
+        }
+    }
+
+    /// The file metrics are written to, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Current value of a counter
+    pub fn get(&self, counter: Counter) -> u64 {
+        self.counts[counter as usize]
+    }
+
+    /// Add `n` to a counter and rewrite the metrics file
+    pub fn add(&mut self, counter: Counter, n: u64) -> io::Result<()> {
+        self.counts[counter as usize] += n;
+        self.write()
+    }
+
+    /// Write the metrics file (a no-op without one). The file is replaced
+    /// atomically so collectors never read a partial write.
+    pub fn write(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, self.render())?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Stop writing the metrics file (after a write failed)
+    pub fn disable_file(&mut self) {
+        self.path = None;
+    }
+
+    /// The counters in Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let started = self
+            .started
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let _ = writeln!(
+            out,
+            "# HELP vizgres_session_start_time_seconds Unix time the session started."
+        );
+        let _ = writeln!(out, "# TYPE vizgres_session_start_time_seconds gauge");
+        let _ = writeln!(
+            out,
+            "vizgres_session_start_time_seconds{{pid=\"{}\"}} {}",
+            std::process::id(),
+            started
+        );
+        for counter in Counter::ALL {
+            let _ = writeln!(out, "# HELP {} {}", counter.name(), counter.help());
+            let _ = writeln!(out, "# TYPE {} counter", counter.name());
+            let _ = writeln!(
+                out,
+                "{}{{pid=\"{}\"}} {}",
+                counter.name(),
+                std::process::id(),
+                self.get(counter)
+            );
+        }
+        out
+    }
+}
+
+impl Drop for Metrics {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Expand `~/` and `{pid}` in a configured path; empty means no file
+fn resolve_path(path: &str) -> Option<PathBuf> {
+    let path = path.trim();
+    if path.is_empty() {
+        return None;
+    }
+    let path = path.replace("{pid}", &std::process::id().to_string());
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+        None => Some(PathBuf::from(path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let mut metrics = Metrics::new("");
+        metrics.add(Counter::Queries, 3).unwrap();
+        metrics.add(Counter::BytesExported, 1024).unwrap();
+        let text = metrics.render();
+        let pid = std::process::id();
+        assert!(text.contains("# TYPE vizgres_queries_total counter\n"));
+        assert!(text.contains(&format!("vizgres_queries_total{{pid=\"{}\"}} 3\n", pid)));
+        assert!(text.contains(&format!(
+            "vizgres_exported_bytes_total{{pid=\"{}\"}} 1024\n",
+            pid
+        )));
+        assert!(text.contains(&format!(
+            "vizgres_connection_drops_total{{pid=\"{}\"}} 0\n",
+            pid
+        )));
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        assert_eq!(resolve_path("  "), None);
+        let pid = std::process::id();
+        assert_eq!(
+            resolve_path("/tmp/vizgres-{pid}.prom"),
+            Some(PathBuf::from(format!("/tmp/vizgres-{}.prom", pid)))
+        );
+        assert_eq!(
+            resolve_path("~/metrics.prom"),
+            dirs::home_dir().map(|h| h.join("metrics.prom"))
+        );
+    }
+
+    #[test]
+    fn test_file_written_on_change_and_removed_on_drop() {
+        let dir = std::env::temp_dir().join(format!("vizgres-metrics-{}", std::process::id()));
+        let path = dir.join("session.prom");
+        let mut metrics = Metrics::new(path.to_str().unwrap());
+        metrics.add(Counter::QueryErrors, 1).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("vizgres_query_errors_total{"));
+        assert!(!path.with_extension("tmp").exists());
+        drop(metrics);
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir(&dir);
+    }
+}
@@ -0,0 +1,73 @@
+//! SQL quoting helpers
+//!
+//! Every piece of SQL vizgres generates from schema names or cell values
+//! (tree previews, copied names, generated statements) goes through these
+//! functions so mixed-case, reserved, and exotic identifiers round-trip
+//! correctly and values can't break out of their literal.
+
+/// Quote an identifier for use in SQL, e.g. `Users` → `"Users"`.
+///
+/// Always quotes (even when PostgreSQL wouldn't require it) so the result
+/// is case-preserving; embedded double quotes are doubled.
+pub fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Quote a schema-qualified name, e.g. `("public", "users")` → `"public"."users"`.
+pub fn quote_qualified(schema: &str, name: &str) -> String {
+    format!("{}.{}", quote_ident(schema), quote_ident(name))
+}
+
+/// Quote a string literal for use in SQL, e.g. `it's` → `'it''s'`.
+///
+/// Matches PostgreSQL's `quote_literal()`: single quotes are doubled, and
+/// values containing backslashes use the `E'...'` form with backslashes
+/// doubled, so the result is correct regardless of
+/// `standard_conforming_strings`.
+pub fn quote_literal(value: &str) -> String {
+    let escaped = value.replace('\'', "''");
+    if escaped.contains('\\') {
+        format!("E'{}'", escaped.replace('\\', "\\\\"))
+    } else {
+        format!("'{}'", escaped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_ident_simple() {
+        assert_eq!(quote_ident("users"), "\"users\"");
+    }
+
+    #[test]
+    fn test_quote_ident_preserves_case_and_spaces() {
+        assert_eq!(quote_ident("My Table"), "\"My Table\"");
+    }
+
+    #[test]
+    fn test_quote_ident_doubles_embedded_quotes() {
+        assert_eq!(quote_ident("we\"ird"), "\"we\"\"ird\"");
+    }
+
+    #[test]
+    fn test_quote_qualified() {
+        assert_eq!(quote_qualified("public", "Users"), "\"public\".\"Users\"");
+        assert_eq!(quote_qualified("a.b", "c"), "\"a.b\".\"c\"");
+    }
+
+    #[test]
+    fn test_quote_literal() {
+        assert_eq!(quote_literal("hello"), "'hello'");
+        assert_eq!(quote_literal("it's"), "'it''s'");
+        assert_eq!(quote_literal(""), "''");
+    }
+
+    #[test]
+    fn test_quote_literal_backslashes_use_escape_form() {
+        assert_eq!(quote_literal(r"C:\temp"), r"E'C:\\temp'");
+        assert_eq!(quote_literal(r"a\'b"), r"E'a\\''b'");
+    }
+}
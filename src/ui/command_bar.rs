@@ -5,6 +5,7 @@
 use crate::ui::Component;
 use crate::ui::ComponentAction;
 use crate::ui::theme::Theme;
+use crate::ui::unicode::{next_grapheme, prev_grapheme};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
@@ -62,6 +63,29 @@ impl CommandBar {
     pub fn input_text(&self) -> &str {
         &self.input
     }
+
+    /// Complete the file path of an `/open` command typed up to the end of
+    /// the input. False when there's no such command or nothing matches.
+    pub fn complete_path(&mut self) -> bool {
+        if self.prompt.is_some() || self.cursor != self.input.len() {
+            return false;
+        }
+        let command = self.input.trim_start_matches(['/', ':']);
+        let Some((word, path)) = command.split_once(' ') else {
+            return false;
+        };
+        if !matches!(word, "open" | "edit" | "e") {
+            return false;
+        }
+        let path = path.trim_start();
+        let Some(completed) = crate::commands::paths::complete_path(path) else {
+            return false;
+        };
+        self.input.truncate(self.input.len() - path.len());
+        self.input.push_str(&completed);
+        self.cursor = self.input.len();
+        true
+    }
 }
 
 impl Default for CommandBar {
@@ -85,35 +109,18 @@ impl Component for CommandBar {
             }
             KeyCode::Backspace => {
                 if self.cursor > 0 {
-                    // Find previous char boundary
-                    let prev = self.input[..self.cursor]
-                        .char_indices()
-                        .next_back()
-                        .map(|(i, _)| i)
-                        .unwrap_or(0);
-                    self.input.remove(prev);
+                    let prev = prev_grapheme(&self.input, self.cursor);
+                    self.input.drain(prev..self.cursor);
                     self.cursor = prev;
                 }
                 ComponentAction::Consumed
             }
             KeyCode::Left => {
-                if self.cursor > 0 {
-                    self.cursor = self.input[..self.cursor]
-                        .char_indices()
-                        .next_back()
-                        .map(|(i, _)| i)
-                        .unwrap_or(0);
-                }
+                self.cursor = prev_grapheme(&self.input, self.cursor);
                 ComponentAction::Consumed
             }
             KeyCode::Right => {
-                if self.cursor < self.input.len() {
-                    self.cursor = self.input[self.cursor..]
-                        .char_indices()
-                        .nth(1)
-                        .map(|(i, _)| self.cursor + i)
-                        .unwrap_or(self.input.len());
-                }
+                self.cursor = next_grapheme(&self.input, self.cursor);
                 ComponentAction::Consumed
             }
             KeyCode::Home => {
@@ -205,6 +212,34 @@ mod tests {
         KeyEvent::new(code, KeyModifiers::NONE)
     }
 
+    #[test]
+    fn test_complete_path_only_for_open() {
+        let dir = std::env::temp_dir().join(format!("vizgres-bar-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("report.sql"), "SELECT 1").unwrap();
+        let typed = format!("{}/rep", dir.display());
+
+        let mut bar = CommandBar::new();
+        bar.activate();
+        for c in format!("open {}", typed).chars() {
+            bar.handle_key(key(KeyCode::Char(c)));
+        }
+        assert!(bar.complete_path());
+        assert_eq!(
+            bar.input_text(),
+            format!("open {}/report.sql", dir.display())
+        );
+
+        bar.activate_with_prompt("Export to: ".to_string(), format!("open {}", typed));
+        assert!(!bar.complete_path(), "prompts aren't commands");
+        bar.activate();
+        for c in format!("import {}", typed).chars() {
+            bar.handle_key(key(KeyCode::Char(c)));
+        }
+        assert!(!bar.complete_path());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_multibyte_insert_and_cursor() {
         let mut bar = CommandBar::new();
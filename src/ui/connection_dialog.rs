@@ -4,8 +4,10 @@
 //! field, an optional name field for saving connections, and a list of
 //! previously saved connections. Follows the Inspector/Help popup pattern.
 
-use crate::config::connections::{ConnectionConfig, load_connections, save_connections};
+use crate::config::connections::{ConnectionConfig, PgEnv, load_connections, save_connections};
+use crate::config::secrets;
 use crate::ui::theme::Theme;
+use crate::ui::unicode::{display_width, next_grapheme, prev_grapheme};
 use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
 
@@ -58,11 +60,26 @@ impl ConnectionDialog {
         }
     }
 
-    /// Show the dialog, loading saved connections from disk
+    /// Show the dialog, loading saved connections from disk. The URL starts
+    /// out as the PG* environment's default connection, if there is one.
     pub fn show(&mut self) {
+        self.show_with_env(&PgEnv::from_env());
+    }
+
+    fn show_with_env(&mut self, env: &PgEnv) {
         self.visible = true;
-        self.url_input.clear();
-        self.url_cursor = 0;
+        // PGPASSWORD stays out of the visible URL; it is applied on connect
+        self.url_input = env
+            .default_config()
+            .map(|config| {
+                ConnectionConfig {
+                    password: None,
+                    ..config
+                }
+                .to_url()
+            })
+            .unwrap_or_default();
+        self.url_cursor = self.url_input.len();
         self.name_input.clear();
         self.name_cursor = 0;
         self.read_only = false;
@@ -209,12 +226,7 @@ impl ConnectionDialog {
             }
             KeyCode::Backspace => {
                 if *cursor > 0 {
-                    // Find the byte position of the previous char boundary
-                    let prev = input[..*cursor]
-                        .char_indices()
-                        .next_back()
-                        .map(|(i, _)| i)
-                        .unwrap_or(0);
+                    let prev = prev_grapheme(input, *cursor);
                     input.drain(prev..*cursor);
                     *cursor = prev;
                     self.error = None;
@@ -222,33 +234,16 @@ impl ConnectionDialog {
             }
             KeyCode::Delete => {
                 if *cursor < input.len() {
-                    let next = *cursor
-                        + input[*cursor..]
-                            .chars()
-                            .next()
-                            .map(|c| c.len_utf8())
-                            .unwrap_or(0);
+                    let next = next_grapheme(input, *cursor);
                     input.drain(*cursor..next);
                     self.error = None;
                 }
             }
             KeyCode::Left => {
-                if *cursor > 0 {
-                    *cursor = input[..*cursor]
-                        .char_indices()
-                        .next_back()
-                        .map(|(i, _)| i)
-                        .unwrap_or(0);
-                }
+                *cursor = prev_grapheme(input, *cursor);
             }
             KeyCode::Right => {
-                if *cursor < input.len() {
-                    *cursor += input[*cursor..]
-                        .chars()
-                        .next()
-                        .map(|c| c.len_utf8())
-                        .unwrap_or(0);
-                }
+                *cursor = next_grapheme(input, *cursor);
             }
             KeyCode::Home => {
                 *cursor = 0;
@@ -292,7 +287,8 @@ impl ConnectionDialog {
     /// Delete the currently selected connection from the list and disk
     fn delete_selected(&mut self) {
         if self.selected < self.connections.len() {
-            self.connections.remove(self.selected);
+            let removed = self.connections.remove(self.selected);
+            secrets::delete_password(&removed.name);
             if self.selected >= self.connections.len() && self.selected > 0 {
                 self.selected -= 1;
             }
@@ -522,15 +518,20 @@ impl ConnectionDialog {
             y += 1;
         }
 
-        // Warning line
+        // Password storage line
         if y < area.y + area.height {
-            frame.render_widget(
-                Paragraph::new(Span::styled(
+            let line = if secrets::is_available() {
+                Span::styled(
+                    "  Passwords are stored in the OS keyring",
+                    theme.dialog_hint,
+                )
+            } else {
+                Span::styled(
                     "  \u{26a0} Passwords are stored in plaintext",
                     theme.dialog_warning,
-                )),
-                Rect::new(x, y, inner_width, 1),
-            );
+                )
+            };
+            frame.render_widget(Paragraph::new(line), Rect::new(x, y, inner_width, 1));
         }
     }
 }
@@ -542,19 +543,20 @@ impl Default for ConnectionDialog {
 }
 
 /// Helper for showing a visible slice of a string with cursor position
-struct VisibleSlice {
-    text: String,
-    cursor_offset: usize,
+pub(crate) struct VisibleSlice {
+    pub(crate) text: String,
+    pub(crate) cursor_offset: usize,
 }
 
 /// Get the visible portion of a string that fits within `width` terminal columns,
 /// keeping the cursor visible. Returns the display text and cursor column offset.
-/// Note: cursor is a byte index, width is display columns.
-fn visible_slice(input: &str, cursor: usize, width: usize) -> VisibleSlice {
-    use unicode_width::UnicodeWidthChar;
+/// Note: cursor is a byte index, width is display columns. The window never
+/// starts or ends inside a grapheme cluster.
+pub(crate) fn visible_slice(input: &str, cursor: usize, width: usize) -> VisibleSlice {
+    use unicode_segmentation::UnicodeSegmentation;
 
-    let total_width = super::unicode::display_width(input);
-    let cursor_width = super::unicode::display_width(&input[..cursor]);
+    let total_width = display_width(input);
+    let cursor_width = display_width(&input[..cursor]);
 
     if total_width <= width {
         return VisibleSlice {
@@ -570,32 +572,25 @@ fn visible_slice(input: &str, cursor: usize, width: usize) -> VisibleSlice {
         0
     };
 
-    // Walk characters to find the visible window by display columns
+    // Walk graphemes to find the visible window by display columns; a wide
+    // one straddling `start_col` is skipped whole
     let mut col = 0;
-    let mut start_byte = 0;
-    let mut found_start = start_col == 0;
-    let mut end_byte = input.len();
-    let mut visible_cols = 0;
-
-    for (i, ch) in input.char_indices() {
-        let cw = UnicodeWidthChar::width(ch).unwrap_or(0);
-        if !found_start && col >= start_col {
+    let mut start_byte = input.len();
+    let mut skipped_cols = 0;
+    for (i, g) in input.grapheme_indices(true) {
+        if col >= start_col {
             start_byte = i;
-            found_start = true;
+            skipped_cols = col;
+            break;
         }
-        if found_start {
-            if visible_cols + cw > width {
-                end_byte = i;
-                break;
-            }
-            visible_cols += cw;
-        }
-        col += cw;
+        col += display_width(g);
     }
+    let rest = &input[start_byte..];
+    let end_byte = start_byte + super::unicode::fit_width(rest, width);
 
     VisibleSlice {
         text: input[start_byte..end_byte].to_string(),
-        cursor_offset: cursor_width - start_col,
+        cursor_offset: cursor_width.saturating_sub(skipped_cols),
     }
 }
 
@@ -627,6 +622,24 @@ mod tests {
         assert!(dialog.url_input.is_empty());
     }
 
+    #[test]
+    fn test_dialog_prefills_url_from_pg_env() {
+        let mut dialog = ConnectionDialog::new();
+        let env = PgEnv {
+            host: Some("db.internal".to_string()),
+            database: Some("shop".to_string()),
+            user: Some("app".to_string()),
+            password: Some("secret".to_string()),
+            ..PgEnv::default()
+        };
+        dialog.show_with_env(&env);
+        assert_eq!(dialog.url_input, "postgres://app@db.internal/shop");
+        assert_eq!(dialog.url_cursor, dialog.url_input.len());
+
+        dialog.show_with_env(&PgEnv::default());
+        assert!(dialog.url_input.is_empty());
+    }
+
     #[test]
     fn test_dialog_tab_cycles_focus() {
         let mut dialog = ConnectionDialog::new();
@@ -653,11 +666,16 @@ mod tests {
             name: "test".to_string(),
             host: "localhost".to_string(),
             port: 5432,
+            backend: crate::config::connections::Backend::Postgres,
             database: "mydb".to_string(),
             username: "user".to_string(),
             password: None,
             ssl_mode: crate::config::connections::SslMode::Prefer,
+            ssl_root_cert: None,
+            ssl_cert: None,
+            ssl_key: None,
             read_only: false,
+            prefer_simple_protocol: false,
             is_saved: false,
         }];
 
@@ -754,11 +772,16 @@ mod tests {
             name: "my-db".to_string(),
             host: "localhost".to_string(),
             port: 5432,
+            backend: crate::config::connections::Backend::Postgres,
             database: "mydb".to_string(),
             username: "user".to_string(),
             password: Some("pass".to_string()),
             ssl_mode: crate::config::connections::SslMode::Prefer,
+            ssl_root_cert: None,
+            ssl_cert: None,
+            ssl_key: None,
             read_only: false,
+            prefer_simple_protocol: false,
             is_saved: false,
         }];
 
@@ -828,22 +851,32 @@ mod tests {
                 name: "db1".to_string(),
                 host: "h1".to_string(),
                 port: 5432,
+                backend: crate::config::connections::Backend::Postgres,
                 database: "d1".to_string(),
                 username: "u1".to_string(),
                 password: None,
                 ssl_mode: crate::config::connections::SslMode::Prefer,
+                ssl_root_cert: None,
+                ssl_cert: None,
+                ssl_key: None,
                 read_only: false,
+                prefer_simple_protocol: false,
                 is_saved: false,
             },
             ConnectionConfig {
                 name: "db2".to_string(),
                 host: "h2".to_string(),
                 port: 5432,
+                backend: crate::config::connections::Backend::Postgres,
                 database: "d2".to_string(),
                 username: "u2".to_string(),
                 password: None,
                 ssl_mode: crate::config::connections::SslMode::Prefer,
+                ssl_root_cert: None,
+                ssl_cert: None,
+                ssl_key: None,
                 read_only: false,
+                prefer_simple_protocol: false,
                 is_saved: false,
             },
         ];
@@ -961,6 +994,29 @@ mod tests {
         assert!(crate::ui::unicode::display_width(&result.text) <= 6);
     }
 
+    #[test]
+    fn test_visible_slice_cursor_after_skipped_wide_char() {
+        let input = "日本語テスト文字列";
+        let result = visible_slice(input, input.len(), 6);
+        // "文" straddles the scroll edge and is skipped whole, so the cursor
+        // sits right after "字列"
+        assert_eq!(result.text, "字列");
+        assert_eq!(result.cursor_offset, 4);
+    }
+
+    #[test]
+    fn test_backspace_deletes_whole_grapheme() {
+        let mut dialog = ConnectionDialog::new();
+        dialog.show();
+        dialog.url_input = "ae\u{301}".to_string();
+        dialog.url_cursor = dialog.url_input.len();
+        dialog.handle_key(key(KeyCode::Left));
+        assert_eq!(dialog.url_cursor, 1);
+        dialog.handle_key(key(KeyCode::End));
+        dialog.handle_key(key(KeyCode::Backspace));
+        assert_eq!(dialog.url_input, "a");
+    }
+
     #[test]
     fn test_visible_slice_emoji() {
         // "a☕b☕c" — ☕ is 2 display cols each, total = 1+2+1+2+1 = 7 cols
@@ -1099,11 +1155,16 @@ mod tests {
             name: "prod".to_string(),
             host: "db.example.com".to_string(),
             port: 5432,
+            backend: crate::config::connections::Backend::Postgres,
             database: "prod".to_string(),
             username: "admin".to_string(),
             password: None,
             ssl_mode: crate::config::connections::SslMode::Prefer,
+            ssl_root_cert: None,
+            ssl_cert: None,
+            ssl_key: None,
             read_only: true,
+            prefer_simple_protocol: false,
             is_saved: true,
         }];
 
@@ -0,0 +1,371 @@
+//! Server health dashboard overlay
+//!
+//! `/dashboard` shows a snapshot of the server's health from the
+//! `pg_stat_*` views and fetches a new one every few seconds while it's
+//! open. Follows the same overlay pattern as MessageLog.
+
+use crate::db::Health;
+use crate::db::health::ServerQuery;
+use crate::ui::jobs::format_elapsed;
+use crate::ui::numbers::NumberFormat;
+use crate::ui::theme::Theme;
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+use std::time::{Duration, Instant};
+
+/// Time between refreshes while the dashboard is open
+pub const DASHBOARD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Width of the label column
+const LABEL_WIDTH: usize = 13;
+
+/// Health snapshot with its refresh schedule
+pub struct Dashboard {
+    health: Option<Health>,
+    /// Why the last refresh failed
+    error: Option<String>,
+    /// When the next refresh is due
+    next_refresh: Option<Instant>,
+    /// A refresh is running
+    in_flight: bool,
+    scroll: usize,
+    visible: bool,
+    /// How counts and sizes are written (`number_locale`)
+    numbers: NumberFormat,
+}
+
+impl Dashboard {
+    pub fn new() -> Self {
+        Self {
+            health: None,
+            error: None,
+            next_refresh: None,
+            in_flight: false,
+            scroll: 0,
+            visible: false,
+            numbers: NumberFormat::default(),
+        }
+    }
+
+    /// Change how counts and sizes are written
+    pub fn set_number_format(&mut self, numbers: NumberFormat) {
+        self.numbers = numbers;
+    }
+
+    /// When the next refresh is due, while open and none is running
+    pub fn next_refresh(&self) -> Option<Instant> {
+        if !self.visible || self.in_flight {
+            return None;
+        }
+        self.next_refresh
+    }
+
+    /// Note that a refresh was sent, unless one is already running
+    pub fn start_refresh(&mut self) -> bool {
+        if self.in_flight {
+            return false;
+        }
+        self.in_flight = true;
+        true
+    }
+
+    /// Show a new snapshot and schedule the next refresh
+    pub fn set_health(&mut self, health: Health, now: Instant) {
+        self.health = Some(health);
+        self.error = None;
+        self.in_flight = false;
+        self.next_refresh = Some(now + DASHBOARD_INTERVAL);
+    }
+
+    /// Show why a refresh failed, keeping the last snapshot, and try again
+    /// on schedule
+    pub fn set_error(&mut self, error: String, now: Instant) {
+        self.error = Some(error);
+        self.in_flight = false;
+        self.next_refresh = Some(now + DASHBOARD_INTERVAL);
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+        self.scroll = 0;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll = (self.scroll + 1).min(self.max_scroll());
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(10);
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll = (self.scroll + 10).min(self.max_scroll());
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines().len().saturating_sub(1)
+    }
+
+    /// The dashboard as `(label, text)` lines; section headings have an
+    /// empty text
+    fn lines(&self) -> Vec<(String, String)> {
+        let mut lines = Vec::new();
+        if let Some(ref error) = self.error {
+            lines.push(("Error".to_string(), error.clone()));
+        }
+        let Some(ref health) = self.health else {
+            return lines;
+        };
+
+        let c = &health.connections;
+        let percent = if c.max > 0 { c.total * 100 / c.max } else { 0 };
+        lines.push((
+            "Connections".to_string(),
+            format!(
+                "{} of {} ({}%) \u{2014} {} active, {} idle, {} idle in transaction",
+                c.total, c.max, percent, c.active, c.idle, c.idle_in_transaction
+            ),
+        ));
+
+        if let Some(ref db) = health.database {
+            let ratio = db
+                .hit_ratio()
+                .map_or_else(|| "\u{2014}".to_string(), |r| format!("{:.2}%", r));
+            lines.push((
+                "Cache hits".to_string(),
+                format!("{} in {}", ratio, db.name),
+            ));
+            lines.push((
+                "Transactions".to_string(),
+                format!(
+                    "{} commits, {} rollbacks, {} deadlocks",
+                    self.numbers.compact(db.commits),
+                    self.numbers.compact(db.rollbacks),
+                    self.numbers.compact(db.deadlocks)
+                ),
+            ));
+        }
+
+        let replication = if health.standby {
+            match health.replay_lag {
+                Some(lag) => format!("standby, last replay {} ago", format_elapsed(lag)),
+                None => "standby, nothing replayed yet".to_string(),
+            }
+        } else if health.replicas.is_empty() {
+            "primary, no standbys".to_string()
+        } else {
+            format!("primary, {} standby(s)", health.replicas.len())
+        };
+        lines.push(("Replication".to_string(), replication));
+        for replica in &health.replicas {
+            let mut text = format!(
+                "  {} {} {}",
+                replica.name.as_deref().unwrap_or("?"),
+                replica.client.as_deref().unwrap_or("local"),
+                replica.state.as_deref().unwrap_or("?")
+            );
+            if let Some(lag) = replica.lag {
+                text.push_str(&format!(", lag {}", format_elapsed(lag)));
+            }
+            if let Some(bytes) = replica.behind_bytes {
+                text.push_str(&format!(", {} behind", self.numbers.size(bytes)));
+            }
+            lines.push((String::new(), text));
+        }
+
+        lines.push((String::new(), String::new()));
+        lines.push(("Longest-running queries".to_string(), String::new()));
+        if health.longest.is_empty() {
+            lines.push((String::new(), "none running".to_string()));
+        }
+        lines.extend(
+            health
+                .longest
+                .iter()
+                .map(|q| (String::new(), query_line(q))),
+        );
+
+        lines.push((String::new(), String::new()));
+        lines.push(("Lock waits".to_string(), String::new()));
+        if health.lock_waits.is_empty() {
+            lines.push((String::new(), "none waiting".to_string()));
+        }
+        lines.extend(
+            health
+                .lock_waits
+                .iter()
+                .map(|q| (String::new(), query_line(q))),
+        );
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if area.height == 0 {
+            return;
+        }
+
+        if self.health.is_none() && self.error.is_none() {
+            frame.render_widget(
+                Paragraph::new("Loading server health...").style(theme.help_desc),
+                area,
+            );
+            return;
+        }
+
+        let lines: Vec<Line> = self
+            .lines()
+            .into_iter()
+            .skip(self.scroll)
+            .take(area.height as usize)
+            .map(|(label, text)| {
+                if text.is_empty() {
+                    return Line::from(Span::styled(label, theme.results_header));
+                }
+                let style = if label == "Error" {
+                    theme.status_error
+                } else {
+                    theme.help_desc
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:<LABEL_WIDTH$}", label), theme.help_key),
+                    Span::styled(text, style),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `pid user duration [blocked by ..] query` on one line
+fn query_line(q: &ServerQuery) -> String {
+    let query = q
+        .query
+        .as_deref()
+        .unwrap_or("")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let blocked_by = if q.blocked_by.is_empty() {
+        String::new()
+    } else {
+        let pids: Vec<String> = q.blocked_by.iter().map(|p| p.to_string()).collect();
+        format!("blocked by {}  ", pids.join(", "))
+    };
+    format!(
+        "{:>7}  {:<12} {:>8}  {}{}",
+        q.pid,
+        q.user.as_deref().unwrap_or(""),
+        q.duration.map(format_elapsed).unwrap_or_default(),
+        blocked_by,
+        query
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::health::{Connections, DatabaseStats};
+
+    fn health() -> Health {
+        Health {
+            connections: Connections {
+                active: 3,
+                idle: 5,
+                idle_in_transaction: 1,
+                total: 9,
+                max: 100,
+            },
+            database: Some(DatabaseStats {
+                name: "shop".to_string(),
+                blocks_hit: 995,
+                blocks_read: 5,
+                commits: 12_000,
+                rollbacks: 4,
+                deadlocks: 0,
+            }),
+            lock_waits: vec![ServerQuery {
+                pid: 42,
+                user: Some("app".to_string()),
+                duration: Some(Duration::from_secs(75)),
+                query: Some("UPDATE orders\n   SET total = 0".to_string()),
+                blocked_by: vec![7, 8],
+            }],
+            ..Health::default()
+        }
+    }
+
+    fn text(dashboard: &Dashboard, label: &str) -> String {
+        dashboard
+            .lines()
+            .into_iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, text)| text)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_summary_lines() {
+        let mut dashboard = Dashboard::new();
+        dashboard.set_health(health(), Instant::now());
+        assert_eq!(
+            text(&dashboard, "Connections"),
+            "9 of 100 (9%) \u{2014} 3 active, 5 idle, 1 idle in transaction"
+        );
+        assert_eq!(text(&dashboard, "Cache hits"), "99.50% in shop");
+        assert_eq!(text(&dashboard, "Replication"), "primary, no standbys");
+        let lines = dashboard.lines();
+        assert!(lines.iter().any(|(_, t)| t == "none running"));
+        assert!(lines.iter().any(|(_, t)| {
+            t == "     42  app              1:15  blocked by 7, 8  UPDATE orders SET total = 0"
+        }));
+    }
+
+    #[test]
+    fn test_refresh_schedule() {
+        let mut dashboard = Dashboard::new();
+        let now = Instant::now();
+        assert_eq!(dashboard.next_refresh(), None);
+        dashboard.show();
+        assert!(dashboard.start_refresh());
+        assert!(!dashboard.start_refresh(), "one refresh at a time");
+        dashboard.set_health(health(), now);
+        assert_eq!(dashboard.next_refresh(), Some(now + DASHBOARD_INTERVAL));
+
+        // A failure keeps the last snapshot and tries again later
+        assert!(dashboard.start_refresh());
+        assert_eq!(dashboard.next_refresh(), None);
+        dashboard.set_error("permission denied".to_string(), now);
+        assert_eq!(text(&dashboard, "Error"), "permission denied");
+        assert!(dashboard.lines().iter().any(|(l, _)| l == "Connections"));
+        assert_eq!(dashboard.next_refresh(), Some(now + DASHBOARD_INTERVAL));
+
+        dashboard.hide();
+        assert_eq!(dashboard.next_refresh(), None);
+    }
+}
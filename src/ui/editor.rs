@@ -1,27 +1,54 @@
 //! Query editor widget
 //!
 //! Multi-line SQL editor with line numbers and cursor.
+//!
+//! Built to stay responsive on multi-thousand-line scripts: undo steps store
+//! only the lines an edit touched, and syntax highlighting is cached per line
+//! and recomputed only from the first edited line onward, as far as the
+//! viewport reaches.
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
 
 use crate::ui::Component;
 use crate::ui::ComponentAction;
 use crate::ui::highlight::{self, TokenKind};
 use crate::ui::theme::Theme;
+use crate::ui::vim::{InsertAt, Motion, Operator, VimCommand, VimMode, VimState};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
 
-/// Maximum number of undo snapshots to retain
+/// Maximum number of undo steps to retain
 const UNDO_CAPACITY: usize = 100;
 
-/// Frozen editor state for undo/redo
-#[derive(Clone)]
-struct EditorSnapshot {
-    lines: Vec<String>,
+/// One level of auto-indent, as Ctrl+Alt+F formats
+const INDENT: &str = "  ";
+
+/// How many lines past the cursor's the bracket matcher looks for a partner
+const BRACKET_SCAN_LINES: usize = 1000;
+
+/// (line, char column) in the buffer
+type Pos = (usize, usize);
+
+/// One undo/redo step: the buffer's lines `first..first + n` replaced `old`,
+/// where `n` is whatever is left after the `outside` lines the edit never
+/// touched. Applying it swaps the two, yielding the inverse step.
+struct LineEdit {
+    first: usize,
+    old: Vec<String>,
+    outside: usize,
     cursor: (usize, usize),
 }
 
+/// Syntax highlighting for one line, cached between frames
+struct HighlightedLine {
+    /// Whether the line ends inside a block comment
+    ends_in_comment: bool,
+    /// Tokens, computed once the line has been on screen
+    tokens: Option<Vec<(TokenKind, Range<usize>)>>,
+}
+
 /// Edit operation categories for coalescing
 #[derive(PartialEq)]
 enum EditOp {
@@ -32,6 +59,13 @@ enum EditOp {
     Clear,
 }
 
+/// Text taken by a vim delete or yank, for `p`
+struct Register {
+    text: String,
+    /// Whole lines (`dd`, `yy`, `V`), which paste as lines of their own
+    linewise: bool,
+}
+
 /// Get character count of a string (not byte count).
 #[inline]
 fn char_count(s: &str) -> usize {
@@ -48,6 +82,102 @@ fn char_to_byte_idx(s: &str, char_idx: usize) -> usize {
         .unwrap_or(s.len())
 }
 
+/// Char column of the grapheme boundary before `col` — one step left that
+/// keeps combining marks and emoji sequences whole.
+fn prev_grapheme_col(s: &str, col: usize) -> usize {
+    let byte = super::unicode::prev_grapheme(s, char_to_byte_idx(s, col));
+    char_count(&s[..byte])
+}
+
+/// Char column of the grapheme boundary after `col`
+fn next_grapheme_col(s: &str, col: usize) -> usize {
+    let byte = super::unicode::next_grapheme(s, char_to_byte_idx(s, col));
+    char_count(&s[..byte])
+}
+
+/// `col` clamped to the line and moved back to the start of the grapheme
+/// it falls inside, for a cursor arriving from another line
+fn snap_to_grapheme(s: &str, col: usize) -> usize {
+    let len = char_count(s);
+    if col >= len {
+        len
+    } else {
+        prev_grapheme_col(s, next_grapheme_col(s, col))
+    }
+}
+
+/// Find all case-insensitive occurrences of `query` in `line`.
+/// Returns non-overlapping (start, end) char ranges.
+fn find_in_line(line: &str, query: &[char]) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let chars: Vec<char> = line.chars().collect();
+    let eq = |a: char, b: char| a == b || a.to_lowercase().eq(b.to_lowercase());
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i + query.len() <= chars.len() {
+        if chars[i..i + query.len()]
+            .iter()
+            .zip(query)
+            .all(|(&a, &b)| eq(a, b))
+        {
+            matches.push((i, i + query.len()));
+            i += query.len();
+        } else {
+            i += 1;
+        }
+    }
+    matches
+}
+
+/// Byte ranges of the tabs in `line` and of its trailing whitespace. The
+/// trailing run is left out while `typing_at` (a char index) is at or past
+/// its start, so the space just typed isn't flagged.
+fn whitespace_ranges(line: &str, typing_at: Option<usize>) -> Vec<Range<usize>> {
+    let trimmed = line.trim_end_matches([' ', '\t']).len();
+    let trailing =
+        trimmed < line.len() && typing_at.is_none_or(|col| col < line[..trimmed].chars().count());
+    let mut ranges: Vec<Range<usize>> = line[..trimmed]
+        .match_indices('\t')
+        .map(|(i, _)| i..i + 1)
+        .collect();
+    if trailing {
+        ranges.push(trimmed..line.len());
+    }
+    ranges
+}
+
+/// Split styled byte ranges so each highlight's style is patched over the
+/// segments it covers. Both inputs must be sorted and non-overlapping.
+fn overlay_highlights(
+    segments: Vec<(Style, Range<usize>)>,
+    highlights: &[(Range<usize>, Style)],
+) -> Vec<(Style, Range<usize>)> {
+    if highlights.is_empty() {
+        return segments;
+    }
+    let mut out = Vec::with_capacity(segments.len() + highlights.len() * 2);
+    for (style, range) in segments {
+        let mut pos = range.start;
+        for (hl, hl_style) in highlights {
+            if hl.end <= pos || hl.start >= range.end {
+                continue;
+            }
+            if hl.start > pos {
+                out.push((style, pos..hl.start));
+            }
+            let end = hl.end.min(range.end);
+            out.push((style.patch(*hl_style), pos.max(hl.start)..end));
+            pos = end;
+        }
+        if pos < range.end {
+            out.push((style, pos..range.end));
+        }
+    }
+    out
+}
+
 /// Query editor component
 pub struct QueryEditor {
     /// Lines of text
@@ -60,10 +190,10 @@ pub struct QueryEditor {
     scroll_offset: usize,
 
     /// Undo history (most recent at end)
-    undo_stack: Vec<EditorSnapshot>,
+    undo_stack: Vec<LineEdit>,
 
     /// Redo history (most recent at end)
-    redo_stack: Vec<EditorSnapshot>,
+    redo_stack: Vec<LineEdit>,
 
     /// Tracks current coalescing run
     last_op: Option<EditOp>,
@@ -73,6 +203,25 @@ pub struct QueryEditor {
 
     /// Ghost text suffix shown after cursor for auto-completion
     ghost_text: Option<String>,
+
+    /// Active search term; matches are highlighted while set
+    search: Option<String>,
+
+    /// Highlighting for a prefix of the lines, truncated at the first edit
+    highlight_cache: RefCell<Vec<HighlightedLine>>,
+
+    /// Vim keystroke state, when `editor_mode = "vim"`
+    vim: Option<VimState>,
+
+    /// Where the selection started: vim's visual mode, or Shift+movement
+    anchor: Option<(usize, usize)>,
+
+    /// Vim's unnamed register
+    register: Option<Register>,
+
+    /// Enter keeps the line's indentation (one more level after `(`) and
+    /// `)` typed at the start of a line takes one back
+    auto_indent: bool,
 }
 
 impl QueryEditor {
@@ -86,9 +235,20 @@ impl QueryEditor {
             last_op: None,
             visible_height: Cell::new(0),
             ghost_text: None,
+            search: None,
+            highlight_cache: RefCell::new(Vec::new()),
+            vim: None,
+            anchor: None,
+            register: None,
+            auto_indent: false,
         }
     }
 
+    /// Turn auto-indent on Enter and `)` on or off
+    pub fn set_auto_indent(&mut self, on: bool) {
+        self.auto_indent = on;
+    }
+
     /// Get the full content as a single string
     pub fn get_content(&self) -> String {
         self.lines.join("\n")
@@ -96,21 +256,23 @@ impl QueryEditor {
 
     /// Clear all editor content (undoable)
     pub fn clear(&mut self) {
-        self.maybe_snapshot(EditOp::Clear);
+        self.maybe_snapshot(EditOp::Clear, 0, self.lines.len());
         self.lines = vec![String::new()];
         self.cursor = (0, 0);
+        self.anchor = None;
         self.scroll_offset = 0;
     }
 
     /// Replace all content, preserving undo history.
     /// Used by format operations — Ctrl+Z reverts to pre-format state.
     pub fn replace_content(&mut self, content: String) {
-        self.maybe_snapshot(EditOp::Clear);
+        self.maybe_snapshot(EditOp::Clear, 0, self.lines.len());
         self.lines = content.lines().map(String::from).collect();
         if self.lines.is_empty() {
             self.lines.push(String::new());
         }
         self.cursor = (0, 0);
+        self.anchor = None;
         self.scroll_offset = 0;
     }
 
@@ -122,84 +284,128 @@ impl QueryEditor {
             self.lines.push(String::new());
         }
         self.cursor = (0, 0);
+        self.anchor = None;
         self.scroll_offset = 0;
         self.undo_stack.clear();
         self.redo_stack.clear();
         self.last_op = None;
+        self.highlight_cache.get_mut().clear();
     }
 
-    /// Snapshot current state before a mutation, with coalescing.
+    /// Record the undo step for a mutation of lines `first..first + count`,
+    /// before it happens, with coalescing.
     ///
     /// Consecutive same-type coalescable operations (Insert, Backspace,
-    /// DeleteForward) are grouped into a single undo step. NewLine and Clear
-    /// always create a new snapshot.
-    fn maybe_snapshot(&mut self, op: EditOp) {
+    /// DeleteForward) are grouped into a single undo step by widening the
+    /// previous step's line range. NewLine and Clear always start a new step.
+    fn maybe_snapshot(&mut self, op: EditOp, first: usize, count: usize) {
         let coalescable = matches!(
             op,
             EditOp::Insert | EditOp::Backspace | EditOp::DeleteForward
         );
         let coalesced = coalescable && self.last_op.as_ref() == Some(&op);
-
-        if !coalesced {
-            self.undo_stack.push(EditorSnapshot {
-                lines: self.lines.clone(),
-                cursor: self.cursor,
-            });
-            if self.undo_stack.len() > UNDO_CAPACITY {
-                self.undo_stack.remove(0);
+        self.highlight_cache.get_mut().truncate(first);
+
+        match self.undo_stack.last_mut() {
+            Some(step) if coalesced => {
+                let len = self.lines.len() - step.outside;
+                let start = first.min(step.first);
+                let end = (first + count).max(step.first + len);
+                let mut old = self.lines[start..step.first].to_vec();
+                old.append(&mut step.old);
+                old.extend_from_slice(&self.lines[step.first + len..end]);
+                step.first = start;
+                step.old = old;
+                step.outside = self.lines.len() - (end - start);
+            }
+            _ => {
+                self.undo_stack.push(LineEdit {
+                    first,
+                    old: self.lines[first..first + count].to_vec(),
+                    outside: self.lines.len() - count,
+                    cursor: self.cursor,
+                });
+                if self.undo_stack.len() > UNDO_CAPACITY {
+                    self.undo_stack.remove(0);
+                }
+                self.redo_stack.clear();
             }
-            self.redo_stack.clear();
         }
         self.last_op = Some(op);
     }
 
+    /// Swap `step`'s lines back in, returning the step that reverses it
+    fn apply_line_edit(&mut self, step: LineEdit) -> LineEdit {
+        let len = self.lines.len() - step.outside;
+        let old = self
+            .lines
+            .splice(step.first..step.first + len, step.old)
+            .collect();
+        self.highlight_cache.get_mut().truncate(step.first);
+        let inverse = LineEdit {
+            first: step.first,
+            old,
+            outside: step.outside,
+            cursor: self.cursor,
+        };
+        self.cursor = step.cursor;
+        self.anchor = None;
+        self.last_op = None;
+        self.ensure_cursor_visible();
+        inverse
+    }
+
     /// Restore the previous editor state
     pub fn undo(&mut self) {
-        if let Some(snapshot) = self.undo_stack.pop() {
-            self.redo_stack.push(EditorSnapshot {
-                lines: self.lines.clone(),
-                cursor: self.cursor,
-            });
-            self.lines = snapshot.lines;
-            self.cursor = snapshot.cursor;
-            self.last_op = None;
-            self.ensure_cursor_visible();
+        if let Some(step) = self.undo_stack.pop() {
+            let inverse = self.apply_line_edit(step);
+            self.redo_stack.push(inverse);
         }
     }
 
     /// Re-apply a previously undone change
     pub fn redo(&mut self) {
-        if let Some(snapshot) = self.redo_stack.pop() {
-            self.undo_stack.push(EditorSnapshot {
-                lines: self.lines.clone(),
-                cursor: self.cursor,
-            });
-            self.lines = snapshot.lines;
-            self.cursor = snapshot.cursor;
-            self.last_op = None;
-            self.ensure_cursor_visible();
+        if let Some(step) = self.redo_stack.pop() {
+            let inverse = self.apply_line_edit(step);
+            self.undo_stack.push(inverse);
         }
     }
 
     fn insert_char(&mut self, c: char) {
-        self.maybe_snapshot(EditOp::Insert);
+        self.maybe_snapshot(EditOp::Insert, self.cursor.0, 1);
         let line = &mut self.lines[self.cursor.0];
         // Handle cursor beyond line length (char-based)
         let char_col = self.cursor.1.min(char_count(line));
-        let byte_idx = char_to_byte_idx(line, char_col);
+        let mut byte_idx = char_to_byte_idx(line, char_col);
+        // A `)` opening a line lines up with the line that opened it
+        if c == ')' && self.auto_indent && line[..byte_idx].trim().is_empty() {
+            let dedent = if line[..byte_idx].ends_with('\t') {
+                1
+            } else {
+                line[..byte_idx].len() - line[..byte_idx].trim_end_matches(' ').len()
+            }
+            .min(INDENT.len());
+            line.replace_range(byte_idx - dedent..byte_idx, "");
+            byte_idx -= dedent;
+        }
         line.insert(byte_idx, c);
-        self.cursor.1 = char_col + 1;
+        self.cursor.1 = line[..byte_idx].chars().count() + 1;
     }
 
     fn backspace(&mut self) {
-        self.maybe_snapshot(EditOp::Backspace);
+        if self.cursor.1 == 0 && self.cursor.0 > 0 {
+            self.maybe_snapshot(EditOp::Backspace, self.cursor.0 - 1, 2);
+        } else {
+            self.maybe_snapshot(EditOp::Backspace, self.cursor.0, 1);
+        }
         if self.cursor.1 > 0 {
             let line = &self.lines[self.cursor.0];
             let char_col = self.cursor.1.min(char_count(line));
             if char_col > 0 {
-                let byte_idx = char_to_byte_idx(line, char_col - 1);
-                self.lines[self.cursor.0].remove(byte_idx);
-                self.cursor.1 = char_col - 1;
+                let prev_col = prev_grapheme_col(line, char_col);
+                let range = char_to_byte_idx(line, prev_col)..char_to_byte_idx(line, char_col);
+                self.lines[self.cursor.0].drain(range);
+                self.cursor.1 = prev_col;
             }
         } else if self.cursor.0 > 0 {
             // Join with previous line
@@ -211,13 +417,19 @@ impl QueryEditor {
     }
 
     fn delete_forward(&mut self) {
-        self.maybe_snapshot(EditOp::DeleteForward);
+        let line_char_len = char_count(&self.lines[self.cursor.0]);
+        let joins_next = self.cursor.1 >= line_char_len && self.cursor.0 < self.lines.len() - 1;
+        self.maybe_snapshot(
+            EditOp::DeleteForward,
+            self.cursor.0,
+            if joins_next { 2 } else { 1 },
+        );
         let line = &self.lines[self.cursor.0];
-        let line_char_len = char_count(line);
         let char_col = self.cursor.1.min(line_char_len);
         if char_col < line_char_len {
-            let byte_idx = char_to_byte_idx(line, char_col);
-            self.lines[self.cursor.0].remove(byte_idx);
+            let next_col = next_grapheme_col(line, char_col);
+            let range = char_to_byte_idx(line, char_col)..char_to_byte_idx(line, next_col);
+            self.lines[self.cursor.0].drain(range);
         } else if self.cursor.0 < self.lines.len() - 1 {
             // Join next line into current
             let next_line = self.lines.remove(self.cursor.0 + 1);
@@ -226,21 +438,45 @@ impl QueryEditor {
     }
 
     fn new_line(&mut self) {
-        self.maybe_snapshot(EditOp::NewLine);
+        self.maybe_snapshot(EditOp::NewLine, self.cursor.0, 1);
         let line = &self.lines[self.cursor.0];
         let char_col = self.cursor.1.min(char_count(line));
         let byte_idx = char_to_byte_idx(line, char_col);
         let rest = self.lines[self.cursor.0][byte_idx..].to_string();
         self.lines[self.cursor.0].truncate(byte_idx);
+        if !self.auto_indent {
+            self.cursor.0 += 1;
+            self.cursor.1 = 0;
+            self.lines.insert(self.cursor.0, rest);
+            return;
+        }
+        // Keep the line's indentation, one level deeper after `(`; a `)`
+        // right after the cursor goes on a line of its own below
+        let head = &self.lines[self.cursor.0];
+        let mut indent = head[..head.len() - head.trim_start().len()].to_string();
+        let opens = head.trim_end().ends_with('(');
+        let rest = rest.trim_start();
+        let mut new_lines = Vec::new();
+        if opens && rest.starts_with(')') {
+            new_lines.push(format!("{}{}", indent, INDENT));
+            new_lines.push(format!("{}{}", indent, rest));
+            indent.push_str(INDENT);
+        } else {
+            if opens {
+                indent.push_str(INDENT);
+            }
+            new_lines.push(format!("{}{}", indent, rest));
+        }
         self.cursor.0 += 1;
-        self.cursor.1 = 0;
-        self.lines.insert(self.cursor.0, rest);
+        self.cursor.1 = char_count(&indent);
+        let at = self.cursor.0;
+        self.lines.splice(at..at, new_lines);
     }
 
     fn move_up(&mut self) {
         if self.cursor.0 > 0 {
             self.cursor.0 -= 1;
-            self.cursor.1 = self.cursor.1.min(char_count(&self.lines[self.cursor.0]));
+            self.cursor.1 = snap_to_grapheme(&self.lines[self.cursor.0], self.cursor.1);
         }
         self.last_op = None;
     }
@@ -248,14 +484,14 @@ impl QueryEditor {
     fn move_down(&mut self) {
         if self.cursor.0 < self.lines.len() - 1 {
             self.cursor.0 += 1;
-            self.cursor.1 = self.cursor.1.min(char_count(&self.lines[self.cursor.0]));
+            self.cursor.1 = snap_to_grapheme(&self.lines[self.cursor.0], self.cursor.1);
         }
         self.last_op = None;
     }
 
     fn move_left(&mut self) {
         if self.cursor.1 > 0 {
-            self.cursor.1 -= 1;
+            self.cursor.1 = prev_grapheme_col(&self.lines[self.cursor.0], self.cursor.1);
         } else if self.cursor.0 > 0 {
             self.cursor.0 -= 1;
             self.cursor.1 = char_count(&self.lines[self.cursor.0]);
@@ -266,7 +502,7 @@ impl QueryEditor {
     fn move_right(&mut self) {
         let line_char_len = char_count(&self.lines[self.cursor.0]);
         if self.cursor.1 < line_char_len {
-            self.cursor.1 += 1;
+            self.cursor.1 = next_grapheme_col(&self.lines[self.cursor.0], self.cursor.1);
         } else if self.cursor.0 < self.lines.len() - 1 {
             self.cursor.0 += 1;
             self.cursor.1 = 0;
@@ -289,7 +525,7 @@ impl QueryEditor {
         if text.is_empty() {
             return;
         }
-        self.maybe_snapshot(EditOp::Clear);
+        self.maybe_snapshot(EditOp::Clear, self.cursor.0, 1);
 
         let text = text.replace('\r', "");
         let line = &self.lines[self.cursor.0];
@@ -322,7 +558,6 @@ impl QueryEditor {
     }
 
     /// Get current ghost text, if any.
-    #[cfg(test)]
     pub fn ghost_text(&self) -> Option<&str> {
         self.ghost_text.as_deref()
     }
@@ -332,6 +567,12 @@ impl QueryEditor {
         self.cursor
     }
 
+    /// Cursor line and line count (1-based), for the panel's position
+    /// indicator.
+    pub fn position(&self) -> (usize, usize) {
+        (self.cursor.0 + 1, self.lines.len())
+    }
+
     /// Set cursor position (char-based), clamping to valid bounds.
     pub fn set_cursor_position(&mut self, line: usize, col: usize) {
         let line = line.min(self.lines.len().saturating_sub(1));
@@ -345,12 +586,184 @@ impl QueryEditor {
         self.lines.get(idx).map(|s| s.as_str())
     }
 
+    /// Whether text is selected (with Shift and the movement keys)
+    pub fn has_selection(&self) -> bool {
+        self.selection().is_some()
+    }
+
+    /// The selected text, if any
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        Some(self.text_between(start, end))
+    }
+
+    /// Put `text` in place of the selection, or at the cursor when nothing
+    /// is selected (a paste, or an empty string for a cut)
+    pub fn replace_selection(&mut self, text: &str) {
+        self.delete_selection();
+        self.insert_text(text);
+    }
+
+    /// Ends of the Shift+movement selection, in order. None when it is
+    /// empty, or when the anchor is vim's visual mode's.
+    fn selection(&self) -> Option<((usize, usize), (usize, usize))> {
+        let anchor = self.anchor?;
+        if anchor == self.cursor || self.vim_mode().is_some_and(VimMode::is_visual) {
+            return None;
+        }
+        Some(min_max(anchor, self.cursor))
+    }
+
+    /// Remove the selected text, leaving the cursor where it began.
+    /// Returns false when nothing is selected.
+    fn delete_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection() else {
+            return false;
+        };
+        self.remove_range(start, end);
+        true
+    }
+
+    /// Remove the characters from `start` up to `end` as one undo step
+    fn remove_range(&mut self, start: (usize, usize), end: (usize, usize)) {
+        self.maybe_snapshot(EditOp::Clear, start.0, end.0 - start.0 + 1);
+        let tail_at = char_to_byte_idx(&self.lines[end.0], end.1);
+        let tail = self.lines[end.0][tail_at..].to_string();
+        let cut_at = char_to_byte_idx(&self.lines[start.0], start.1);
+        self.lines[start.0].truncate(cut_at);
+        self.lines[start.0].push_str(&tail);
+        self.lines.drain(start.0 + 1..=end.0);
+        self.cursor = start;
+        self.anchor = None;
+        self.last_op = None;
+    }
+
+    /// Lines the line operations act on: the cursor's, or every line the
+    /// selection touches (not counting a last line it only reaches the
+    /// start of)
+    fn line_span(&self) -> (usize, usize) {
+        match self.selection() {
+            Some((start, end)) if end.1 == 0 && end.0 > start.0 => (start.0, end.0 - 1),
+            Some((start, end)) => (start.0, end.0),
+            None => (self.cursor.0, self.cursor.0),
+        }
+    }
+
+    /// Copy the current line (or selected lines) below itself, moving the
+    /// cursor and selection onto the copy. One undo step.
+    pub fn duplicate_lines(&mut self) {
+        let (first, last) = self.line_span();
+        let count = last - first + 1;
+        self.maybe_snapshot(EditOp::Clear, last + 1, 0);
+        let copy = self.lines[first..=last].to_vec();
+        self.lines.splice(last + 1..last + 1, copy);
+        self.cursor.0 += count;
+        if let Some(anchor) = self.anchor.as_mut() {
+            anchor.0 += count;
+        }
+        self.last_op = None;
+        self.ensure_cursor_visible();
+    }
+
+    /// Remove the current line (or selected lines). One undo step.
+    pub fn delete_lines(&mut self) {
+        let (first, last) = self.line_span();
+        self.maybe_snapshot(EditOp::Clear, first, last - first + 1);
+        self.lines.drain(first..=last);
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        let line = first.min(self.lines.len() - 1);
+        self.cursor = (line, snap_to_grapheme(&self.lines[line], self.cursor.1));
+        self.anchor = None;
+        self.last_op = None;
+        self.ensure_cursor_visible();
+    }
+
+    /// Swap the current line (or selected lines) with the line above or
+    /// below, keeping the cursor and selection on them. One undo step;
+    /// false at the top or bottom.
+    pub fn move_lines(&mut self, up: bool) -> bool {
+        let (first, last) = self.line_span();
+        if (up && first == 0) || (!up && last + 1 >= self.lines.len()) {
+            return false;
+        }
+        let (start, end) = if up {
+            (first - 1, last)
+        } else {
+            (first, last + 1)
+        };
+        self.maybe_snapshot(EditOp::Clear, start, end - start + 1);
+        let block = &mut self.lines[start..=end];
+        if up {
+            block.rotate_left(1);
+        } else {
+            block.rotate_right(1);
+        }
+        let shift = |line: &mut usize| {
+            if up {
+                *line -= 1;
+            } else {
+                *line += 1;
+            }
+        };
+        shift(&mut self.cursor.0);
+        if let Some(anchor) = self.anchor.as_mut() {
+            shift(&mut anchor.0);
+        }
+        self.last_op = None;
+        self.ensure_cursor_visible();
+        true
+    }
+
+    /// Comment out the current line (or selected lines) with `-- ` at their
+    /// shared indentation, or uncomment them if every non-blank one is
+    /// already commented. Blank lines are left alone. One undo step.
+    pub fn toggle_comment(&mut self) {
+        let (first, last) = self.line_span();
+        let indent = |line: &str| line.len() - line.trim_start().len();
+        let filled: Vec<usize> = (first..=last)
+            .filter(|&i| !self.lines[i].trim().is_empty())
+            .collect();
+        if filled.is_empty() {
+            return;
+        }
+        self.maybe_snapshot(EditOp::Clear, first, last - first + 1);
+        let uncomment = filled
+            .iter()
+            .all(|&i| self.lines[i].trim_start().starts_with("--"));
+        let column = filled
+            .iter()
+            .map(|&i| indent(&self.lines[i]))
+            .min()
+            .unwrap_or(0);
+        for i in filled {
+            let line = &mut self.lines[i];
+            let at = if uncomment { indent(line) } else { column };
+            // Indentation is whitespace, so byte and char columns agree
+            let (removed, added) = if uncomment {
+                let marker = if line[at..].starts_with("-- ") { 3 } else { 2 };
+                line.replace_range(at..at + marker, "");
+                (marker, 0)
+            } else {
+                line.insert_str(at, "-- ");
+                (0, 3)
+            };
+            for pos in std::iter::once(&mut self.cursor).chain(self.anchor.as_mut()) {
+                if pos.0 == i && pos.1 >= at {
+                    pos.1 = pos.1.saturating_sub(removed).max(at) + added;
+                }
+            }
+        }
+        self.last_op = None;
+    }
+
     /// Accept ghost text: insert it at cursor and clear. Returns true if accepted.
     pub fn accept_ghost_text(&mut self) -> bool {
         if let Some(text) = self.ghost_text.take() {
             // Break coalescing so acceptance is its own undo step
             self.last_op = None;
-            self.maybe_snapshot(EditOp::Insert);
+            self.maybe_snapshot(EditOp::Insert, self.cursor.0, 1);
             let line = &self.lines[self.cursor.0];
             let char_col = self.cursor.1.min(char_count(line));
             let byte_idx = char_to_byte_idx(line, char_col);
@@ -362,6 +775,235 @@ impl QueryEditor {
         }
     }
 
+    /// Set the search term (case-insensitive). Empty clears the search.
+    pub fn set_search(&mut self, query: &str) {
+        self.search = if query.is_empty() {
+            None
+        } else {
+            Some(query.to_string())
+        };
+    }
+
+    /// Stop highlighting search matches.
+    pub fn clear_search(&mut self) {
+        self.search = None;
+    }
+
+    /// Shade the column after `width` characters down the editor, over
+    /// whatever `render` drew there
+    pub fn render_ruler(&self, frame: &mut Frame, area: Rect, width: usize, theme: &Theme) {
+        let line_num_width = format!("{}", self.lines.len()).len().max(2) as u16;
+        let x = area.x as usize + line_num_width as usize + 1 + width;
+        if x >= (area.x + area.width) as usize {
+            return;
+        }
+        let buf = frame.buffer_mut();
+        for y in area.y..area.y + area.height {
+            if let Some(cell) = buf.cell_mut(Position::new(x as u16, y)) {
+                cell.set_style(theme.editor_ruler);
+            }
+        }
+    }
+
+    /// Current search term, if any.
+    pub fn search(&self) -> Option<&str> {
+        self.search.as_deref()
+    }
+
+    /// All matches of the current search as (line, start_col, end_col), char-based.
+    pub fn search_matches(&self) -> Vec<(usize, usize, usize)> {
+        let Some(ref query) = self.search else {
+            return Vec::new();
+        };
+        let query: Vec<char> = query.chars().collect();
+        self.lines
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, line)| {
+                find_in_line(line, &query)
+                    .into_iter()
+                    .map(move |(s, e)| (idx, s, e))
+            })
+            .collect()
+    }
+
+    /// Move the cursor to the next match after the cursor, wrapping around.
+    /// Returns the 1-based match index and total match count.
+    pub fn find_next(&mut self) -> Option<(usize, usize)> {
+        let matches = self.search_matches();
+        let idx = matches
+            .iter()
+            .position(|&(l, s, _)| (l, s) > self.cursor)
+            .unwrap_or(0);
+        self.jump_to_match(&matches, idx)
+    }
+
+    /// Move the cursor to the previous match before the cursor, wrapping around.
+    /// Returns the 1-based match index and total match count.
+    pub fn find_prev(&mut self) -> Option<(usize, usize)> {
+        let matches = self.search_matches();
+        let idx = matches
+            .iter()
+            .rposition(|&(l, s, _)| (l, s) < self.cursor)
+            .unwrap_or(matches.len().saturating_sub(1));
+        self.jump_to_match(&matches, idx)
+    }
+
+    fn jump_to_match(
+        &mut self,
+        matches: &[(usize, usize, usize)],
+        idx: usize,
+    ) -> Option<(usize, usize)> {
+        let &(line, start, _) = matches.get(idx)?;
+        self.cursor = (line, start);
+        self.last_op = None;
+        self.ensure_cursor_visible();
+        Some((idx + 1, matches.len()))
+    }
+
+    /// Replace every case-insensitive occurrence of `query` with `replacement`
+    /// as a single undo step. Returns the number of replacements.
+    pub fn replace_all(&mut self, query: &str, replacement: &str) -> usize {
+        let needle: Vec<char> = query.chars().collect();
+        let counts: Vec<usize> = self
+            .lines
+            .iter()
+            .map(|l| find_in_line(l, &needle).len())
+            .collect();
+        let total: usize = counts.iter().sum();
+        if total == 0 {
+            return 0;
+        }
+        let first = counts.iter().position(|&n| n > 0).unwrap_or(0);
+        let last = counts.iter().rposition(|&n| n > 0).unwrap_or(first);
+        self.maybe_snapshot(EditOp::Clear, first, last - first + 1);
+        let replacement = replacement.replace(['\r', '\n'], " ");
+        for line in &mut self.lines {
+            let matches = find_in_line(line, &needle);
+            if matches.is_empty() {
+                continue;
+            }
+            let mut out = String::with_capacity(line.len());
+            let mut last = 0;
+            for (s, e) in matches {
+                out.push_str(&line[char_to_byte_idx(line, last)..char_to_byte_idx(line, s)]);
+                out.push_str(&replacement);
+                last = e;
+            }
+            out.push_str(&line[char_to_byte_idx(line, last)..]);
+            *line = out;
+        }
+        let line = &self.lines[self.cursor.0];
+        self.cursor.1 = self.cursor.1.min(char_count(line));
+        self.anchor = None;
+        total
+    }
+
+    /// Extend the highlight cache through line `end` (exclusive). Lines above
+    /// the viewport only get their block-comment state; visible lines get
+    /// tokens, computed at most once per edit.
+    fn fill_highlight_cache(&self, cache: &mut Vec<HighlightedLine>, end: usize) {
+        let starts_in_comment =
+            |cache: &[HighlightedLine], idx: usize| idx > 0 && cache[idx - 1].ends_in_comment;
+        for idx in cache.len()..end {
+            let in_comment = starts_in_comment(cache, idx);
+            let line = &self.lines[idx];
+            cache.push(if idx < self.scroll_offset {
+                HighlightedLine {
+                    ends_in_comment: highlight::scan_block_comment_state(line, in_comment),
+                    tokens: None,
+                }
+            } else {
+                let (tokens, ends_in_comment) = highlight::highlight_sql(line, in_comment);
+                HighlightedLine {
+                    ends_in_comment,
+                    tokens: Some(tokens),
+                }
+            });
+        }
+        for idx in self.scroll_offset..end {
+            if cache[idx].tokens.is_none() {
+                let in_comment = starts_in_comment(cache, idx);
+                cache[idx].tokens = Some(highlight::highlight_sql(&self.lines[idx], in_comment).0);
+            }
+        }
+    }
+
+    /// Parentheses in line `idx` outside strings and comments, as
+    /// (char column, is opening), highlighting the line if need be
+    fn code_brackets(&self, cache: &mut Vec<HighlightedLine>, idx: usize) -> Vec<(usize, bool)> {
+        if cache.len() <= idx {
+            self.fill_highlight_cache(cache, idx + 1);
+        }
+        if cache[idx].tokens.is_none() {
+            let in_comment = idx > 0 && cache[idx - 1].ends_in_comment;
+            cache[idx].tokens = Some(highlight::highlight_sql(&self.lines[idx], in_comment).0);
+        }
+        let tokens = cache[idx].tokens.as_deref().unwrap_or_default();
+        let quoted = |byte: usize| {
+            tokens.iter().any(|(kind, range)| {
+                matches!(kind, TokenKind::String | TokenKind::Comment) && range.contains(&byte)
+            })
+        };
+        self.lines[idx]
+            .char_indices()
+            .enumerate()
+            .filter(|&(_, (byte, c))| matches!(c, '(' | ')') && !quoted(byte))
+            .map(|(col, (_, c))| (col, c == '('))
+            .collect()
+    }
+
+    /// The parenthesis at the cursor (or just before it) and its partner,
+    /// None for the partner when it has none
+    fn bracket_pair(&self, cache: &mut Vec<HighlightedLine>) -> Option<(Pos, Option<Pos>)> {
+        let (line, cursor_col) = self.cursor;
+        let brackets = self.code_brackets(cache, line);
+        let &(col, open) = brackets
+            .iter()
+            .find(|(c, _)| *c == cursor_col)
+            .or_else(|| brackets.iter().find(|(c, _)| *c + 1 == cursor_col))?;
+        let mut depth = 0;
+        let lines: Box<dyn Iterator<Item = usize>> = if open {
+            Box::new(line..(line + BRACKET_SCAN_LINES).min(self.lines.len()))
+        } else {
+            Box::new((line.saturating_sub(BRACKET_SCAN_LINES)..=line).rev())
+        };
+        for idx in lines {
+            let mut brackets = self.code_brackets(cache, idx);
+            if !open {
+                brackets.reverse();
+            }
+            for (c, o) in brackets {
+                let past_cursor = idx != line || if open { c > col } else { c < col };
+                if !past_cursor {
+                    continue;
+                }
+                if o == open {
+                    depth += 1;
+                } else if depth == 0 {
+                    return Some(((line, col), Some((idx, c))));
+                } else {
+                    depth -= 1;
+                }
+            }
+        }
+        Some(((line, col), None))
+    }
+
+    /// Move the cursor to the partner of the parenthesis at (or just
+    /// before) it. False when there's none to jump to.
+    pub fn jump_to_matching_bracket(&mut self) -> bool {
+        let pair = self.bracket_pair(&mut self.highlight_cache.borrow_mut());
+        let Some((_, Some(partner))) = pair else {
+            return false;
+        };
+        self.cursor = partner;
+        self.anchor = None;
+        self.last_op = None;
+        self.ensure_cursor_visible();
+        true
+    }
+
     fn ensure_cursor_visible(&mut self) {
         let h = self.visible_height.get();
         if h == 0 {
@@ -375,6 +1017,417 @@ impl QueryEditor {
     }
 }
 
+/// Word class for vim's word motions: blanks, word characters, punctuation
+fn word_class(c: char) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+/// Vim-style modal editing (`editor_mode = "vim"`)
+impl QueryEditor {
+    /// Turn vim editing on (starting in normal mode) or off
+    pub fn set_vim(&mut self, on: bool) {
+        self.vim = on.then(VimState::new);
+        self.anchor = None;
+    }
+
+    /// The vim mode, or None when vim editing is off
+    pub fn vim_mode(&self) -> Option<VimMode> {
+        self.vim.as_ref().map(VimState::mode)
+    }
+
+    /// Whether vim takes `key` ahead of the keymap (see `vim::claims_key`)
+    pub fn vim_claims(&self, key: KeyEvent) -> bool {
+        self.vim_mode()
+            .is_some_and(|mode| crate::ui::vim::claims_key(mode, key))
+    }
+
+    fn set_vim_mode(&mut self, mode: VimMode) {
+        let Some(vim) = self.vim.as_mut() else {
+            return;
+        };
+        let from = vim.mode();
+        vim.set_mode(mode);
+        self.anchor = mode.is_visual().then(|| self.anchor.unwrap_or(self.cursor));
+        self.last_op = None;
+        // Leaving insert mode steps back onto the last character typed
+        if from == VimMode::Insert && mode != VimMode::Insert && self.cursor.1 > 0 {
+            self.cursor.1 = prev_grapheme_col(&self.lines[self.cursor.0], self.cursor.1);
+        }
+    }
+
+    fn handle_vim_key(&mut self, key: KeyEvent) -> ComponentAction {
+        if key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return ComponentAction::Ignored;
+        }
+        self.ghost_text = None;
+        let command = self.vim.as_mut().and_then(|vim| vim.key(key));
+        if let Some(command) = command {
+            self.apply_vim(command);
+        }
+        if self.vim_mode() != Some(VimMode::Insert) {
+            self.clamp_to_last_char();
+        }
+        self.ensure_cursor_visible();
+        ComponentAction::Consumed
+    }
+
+    fn apply_vim(&mut self, command: VimCommand) {
+        match command {
+            VimCommand::Move { motion, count } => {
+                self.cursor = self.motion_target(motion, count);
+                self.last_op = None;
+            }
+            VimCommand::Operate { op, motion, count } => {
+                // `cw` on a word changes just the word, like `ce`
+                let on_word = self
+                    .char_at(self.cursor)
+                    .is_some_and(|c| !c.is_whitespace());
+                let motion = if op == Operator::Change && motion == Motion::WordForward && on_word {
+                    Motion::WordEnd
+                } else {
+                    motion
+                };
+                let target = self.motion_target(motion, count);
+                if motion.linewise() {
+                    let (first, last) = min_max(self.cursor.0, target.0);
+                    self.operate_lines(op, first, last);
+                    return;
+                }
+                let (start, mut end) = min_max(self.cursor, target);
+                if motion.inclusive() {
+                    end.1 = next_grapheme_col(&self.lines[end.0], end.1);
+                } else if motion == Motion::WordForward && end.0 > start.0 {
+                    // A word motion off the end of a line stops at the line's end
+                    let line = &self.lines[end.0];
+                    if line.chars().take(end.1).all(char::is_whitespace) {
+                        end = (end.0 - 1, char_count(&self.lines[end.0 - 1]));
+                    }
+                }
+                self.operate_range(op, start, end);
+            }
+            VimCommand::OperateLines { op, count } => {
+                let last = (self.cursor.0 + count - 1).min(self.lines.len() - 1);
+                self.operate_lines(op, self.cursor.0, last);
+            }
+            VimCommand::OperateSelection(op) => {
+                let mode = self.vim_mode();
+                let Some(anchor) = self.anchor else {
+                    return;
+                };
+                self.set_vim_mode(VimMode::Normal);
+                if mode == Some(VimMode::VisualLine) {
+                    let (first, last) = min_max(anchor.0, self.cursor.0);
+                    self.operate_lines(op, first, last);
+                } else {
+                    let (start, mut end) = min_max(anchor, self.cursor);
+                    end.1 = next_grapheme_col(&self.lines[end.0], end.1);
+                    self.operate_range(op, start, end);
+                }
+            }
+            VimCommand::DeleteChars(count) => {
+                let line = &self.lines[self.cursor.0];
+                let mut end = self.cursor.1;
+                for _ in 0..count {
+                    end = next_grapheme_col(line, end);
+                }
+                if end > self.cursor.1 {
+                    self.operate_range(Operator::Delete, self.cursor, (self.cursor.0, end));
+                }
+            }
+            VimCommand::Insert(at) => {
+                let line = &self.lines[self.cursor.0];
+                match at {
+                    InsertAt::Cursor => {}
+                    InsertAt::AfterCursor => self.cursor.1 = next_grapheme_col(line, self.cursor.1),
+                    InsertAt::LineStart => self.cursor.1 = first_non_blank(line),
+                    InsertAt::LineEnd => self.cursor.1 = char_count(line),
+                    InsertAt::LineBelow | InsertAt::LineAbove => {
+                        self.maybe_snapshot(EditOp::NewLine, self.cursor.0, 1);
+                        if at == InsertAt::LineBelow {
+                            self.cursor.0 += 1;
+                        }
+                        self.lines.insert(self.cursor.0, String::new());
+                        self.cursor.1 = 0;
+                    }
+                }
+                self.set_vim_mode(VimMode::Insert);
+            }
+            VimCommand::Paste { before, count } => self.paste_register(before, count),
+            VimCommand::Undo(count) => {
+                for _ in 0..count {
+                    self.undo();
+                }
+            }
+            VimCommand::SetMode(mode) => self.set_vim_mode(mode),
+        }
+    }
+
+    /// Where `motion` takes the cursor
+    fn motion_target(&self, motion: Motion, count: Option<usize>) -> (usize, usize) {
+        let n = count.unwrap_or(1).max(1);
+        let (line, col) = self.cursor;
+        let last = self.lines.len() - 1;
+        let text = &self.lines[line];
+        let to_line = |l: usize| (l, first_non_blank(&self.lines[l]));
+        match motion {
+            Motion::Left => (line, (0..n).fold(col, |c, _| prev_grapheme_col(text, c))),
+            Motion::Right => (line, (0..n).fold(col, |c, _| next_grapheme_col(text, c))),
+            Motion::Up => {
+                let l = line.saturating_sub(n);
+                (l, snap_to_grapheme(&self.lines[l], col))
+            }
+            Motion::Down => {
+                let l = (line + n).min(last);
+                (l, snap_to_grapheme(&self.lines[l], col))
+            }
+            Motion::WordForward => (0..n).fold(self.cursor, |p, _| self.next_word_start(p)),
+            Motion::WordBackward => (0..n).fold(self.cursor, |p, _| self.prev_word_start(p)),
+            Motion::WordEnd => (0..n).fold(self.cursor, |p, _| self.word_end(p)),
+            Motion::LineStart => (line, 0),
+            Motion::FirstNonBlank => to_line(line),
+            Motion::LineEnd => {
+                let l = (line + n - 1).min(last);
+                (l, char_count(&self.lines[l]))
+            }
+            Motion::FirstLine => to_line(count.map_or(0, |c| c - 1).min(last)),
+            Motion::LastLine => to_line(count.map_or(last, |c| c - 1).min(last)),
+        }
+    }
+
+    fn char_at(&self, (line, col): (usize, usize)) -> Option<char> {
+        self.lines.get(line)?.chars().nth(col)
+    }
+
+    /// Start of the word after `pos`; an empty line counts as a word
+    fn next_word_start(&self, (mut line, mut col): (usize, usize)) -> (usize, usize) {
+        let chars: Vec<char> = self.lines[line].chars().collect();
+        if let Some(&c) = chars.get(col) {
+            let class = word_class(c);
+            while col < chars.len() && word_class(chars[col]) == class {
+                col += 1;
+            }
+        }
+        loop {
+            let chars: Vec<char> = self.lines[line].chars().collect();
+            while col < chars.len() && word_class(chars[col]) == 0 {
+                col += 1;
+            }
+            if col < chars.len() || line + 1 >= self.lines.len() {
+                return (line, col);
+            }
+            line += 1;
+            col = 0;
+            if self.lines[line].is_empty() {
+                return (line, 0);
+            }
+        }
+    }
+
+    /// Start of the word before `pos`
+    fn prev_word_start(&self, (mut line, mut col): (usize, usize)) -> (usize, usize) {
+        let mut chars: Vec<char> = self.lines[line].chars().collect();
+        loop {
+            if col == 0 {
+                if line == 0 {
+                    return (0, 0);
+                }
+                line -= 1;
+                chars = self.lines[line].chars().collect();
+                col = chars.len();
+                if chars.is_empty() {
+                    return (line, 0);
+                }
+                continue;
+            }
+            col -= 1;
+            if word_class(chars[col]) != 0 {
+                break;
+            }
+        }
+        let class = word_class(chars[col]);
+        while col > 0 && word_class(chars[col - 1]) == class {
+            col -= 1;
+        }
+        (line, col)
+    }
+
+    /// Last character of the word at or after the one following `pos`
+    fn word_end(&self, (mut line, col): (usize, usize)) -> (usize, usize) {
+        let mut col = col + 1;
+        let mut chars: Vec<char> = self.lines[line].chars().collect();
+        loop {
+            while col < chars.len() && word_class(chars[col]) == 0 {
+                col += 1;
+            }
+            if col < chars.len() {
+                break;
+            }
+            if line + 1 >= self.lines.len() {
+                return (line, chars.len().saturating_sub(1));
+            }
+            line += 1;
+            col = 0;
+            chars = self.lines[line].chars().collect();
+        }
+        let class = word_class(chars[col]);
+        while col + 1 < chars.len() && word_class(chars[col + 1]) == class {
+            col += 1;
+        }
+        (line, col)
+    }
+
+    /// Text from `start` up to (not including) `end`
+    fn text_between(&self, start: (usize, usize), end: (usize, usize)) -> String {
+        let from = |l: usize, c: usize| char_to_byte_idx(&self.lines[l], c);
+        if start.0 == end.0 {
+            let line = &self.lines[start.0];
+            return line[from(start.0, start.1)..from(end.0, end.1).max(from(start.0, start.1))]
+                .to_string();
+        }
+        let mut text = self.lines[start.0][from(start.0, start.1)..].to_string();
+        for line in &self.lines[start.0 + 1..end.0] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text.push('\n');
+        text.push_str(&self.lines[end.0][..from(end.0, end.1)]);
+        text
+    }
+
+    /// Apply an operator to the characters from `start` up to `end`
+    fn operate_range(&mut self, op: Operator, start: (usize, usize), end: (usize, usize)) {
+        self.register = Some(Register {
+            text: self.text_between(start, end),
+            linewise: false,
+        });
+        if op != Operator::Yank {
+            self.remove_range(start, end);
+        }
+        self.cursor = start;
+        self.last_op = None;
+        if op == Operator::Change {
+            self.set_vim_mode(VimMode::Insert);
+        }
+    }
+
+    /// Apply an operator to lines `first..=last`
+    fn operate_lines(&mut self, op: Operator, first: usize, last: usize) {
+        self.register = Some(Register {
+            text: self.lines[first..=last].join("\n"),
+            linewise: true,
+        });
+        self.last_op = None;
+        match op {
+            Operator::Yank => self.cursor.0 = first,
+            Operator::Delete => {
+                self.maybe_snapshot(EditOp::Clear, first, last - first + 1);
+                self.lines.drain(first..=last);
+                if self.lines.is_empty() {
+                    self.lines.push(String::new());
+                }
+                let line = first.min(self.lines.len() - 1);
+                self.cursor = (line, first_non_blank(&self.lines[line]));
+            }
+            Operator::Change => {
+                self.maybe_snapshot(EditOp::Clear, first, last - first + 1);
+                self.lines
+                    .splice(first..=last, std::iter::once(String::new()));
+                self.cursor = (first, 0);
+                self.set_vim_mode(VimMode::Insert);
+            }
+        }
+    }
+
+    /// `p`/`P`: put the register's text after or before the cursor (below
+    /// or above its line for whole lines), `count` times
+    fn paste_register(&mut self, before: bool, count: usize) {
+        let Some(register) = self.register.as_ref() else {
+            return;
+        };
+        if register.linewise {
+            let at = if before {
+                self.cursor.0
+            } else {
+                self.cursor.0 + 1
+            };
+            let pasted: Vec<String> = std::iter::repeat_n(register.text.as_str(), count)
+                .flat_map(|text| text.split('\n'))
+                .map(String::from)
+                .collect();
+            self.maybe_snapshot(EditOp::Clear, at, 0);
+            self.lines.splice(at..at, pasted);
+            self.cursor = (at, first_non_blank(&self.lines[at]));
+        } else {
+            let text = register.text.repeat(count);
+            if !before {
+                self.cursor.1 = next_grapheme_col(&self.lines[self.cursor.0], self.cursor.1);
+            }
+            self.insert_text(&text);
+            // On the last character pasted, as vim leaves it
+            self.cursor.1 = prev_grapheme_col(&self.lines[self.cursor.0], self.cursor.1);
+        }
+        self.last_op = None;
+    }
+
+    /// Outside insert mode the cursor sits on a character, not past the end
+    fn clamp_to_last_char(&mut self) {
+        let line = &self.lines[self.cursor.0];
+        let len = char_count(line);
+        if self.cursor.1 >= len {
+            self.cursor.1 = if len == 0 {
+                0
+            } else {
+                prev_grapheme_col(line, len)
+            };
+        }
+    }
+
+    /// The selection as char ranges per line: (line, start, end). Vim's
+    /// visual mode takes in the character under the cursor; a Shift+movement
+    /// selection stops before it.
+    fn selection_ranges(&self) -> Vec<(usize, usize, usize)> {
+        let Some(anchor) = self.anchor else {
+            return Vec::new();
+        };
+        let mode = self.vim_mode().filter(|m| m.is_visual());
+        let (start, end) = min_max(anchor, self.cursor);
+        (start.0..=end.0)
+            .map(|l| {
+                let len = char_count(&self.lines[l]);
+                if mode == Some(VimMode::VisualLine) {
+                    return (l, 0, len.max(1));
+                }
+                let from = if l == start.0 { start.1 } else { 0 };
+                let to = match mode {
+                    _ if l != end.0 => len + 1,
+                    Some(_) => next_grapheme_col(&self.lines[l], end.1).max(from + 1),
+                    None => end.1,
+                };
+                (l, from, to)
+            })
+            .filter(|(_, from, to)| from < to)
+            .collect()
+    }
+}
+
+/// Column of the first non-blank character in `line`
+fn first_non_blank(line: &str) -> usize {
+    line.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+fn min_max<T: Ord>(a: T, b: T) -> (T, T) {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
 impl Default for QueryEditor {
     fn default() -> Self {
         Self::new()
@@ -383,6 +1436,59 @@ impl Default for QueryEditor {
 
 impl Component for QueryEditor {
     fn handle_key(&mut self, key: KeyEvent) -> ComponentAction {
+        match self.vim_mode() {
+            Some(VimMode::Insert) if key.code == KeyCode::Esc => {
+                self.ghost_text = None;
+                self.set_vim_mode(VimMode::Normal);
+                return ComponentAction::Consumed;
+            }
+            Some(VimMode::Insert) | None => {}
+            Some(_) => return self.handle_vim_key(key),
+        }
+
+        // Shift+movement extends the selection from where it began
+        if key.modifiers == KeyModifiers::SHIFT
+            && matches!(
+                key.code,
+                KeyCode::Up
+                    | KeyCode::Down
+                    | KeyCode::Left
+                    | KeyCode::Right
+                    | KeyCode::Home
+                    | KeyCode::End
+            )
+        {
+            self.ghost_text = None;
+            self.anchor.get_or_insert(self.cursor);
+            match key.code {
+                KeyCode::Up => self.move_up(),
+                KeyCode::Down => self.move_down(),
+                KeyCode::Left => self.move_left(),
+                KeyCode::Right => self.move_right(),
+                KeyCode::Home => self.move_home(),
+                _ => self.move_end(),
+            }
+            self.ensure_cursor_visible();
+            return ComponentAction::Consumed;
+        }
+
+        // Typing replaces the selection, and Backspace or Delete removes
+        // just the selection; any other key lets go of it
+        let typing = match key.code {
+            KeyCode::Char(_) => !key.modifiers.contains(KeyModifiers::CONTROL),
+            KeyCode::Enter | KeyCode::Backspace | KeyCode::Delete => true,
+            _ => false,
+        };
+        if typing && self.delete_selection() {
+            if matches!(key.code, KeyCode::Backspace | KeyCode::Delete) {
+                self.ghost_text = None;
+                self.ensure_cursor_visible();
+                return ComponentAction::Consumed;
+            }
+        } else if !key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.anchor = None;
+        }
+
         // Right arrow: accept ghost text before clearing (so it can be inserted)
         if key.code == KeyCode::Right
             && key.modifiers == KeyModifiers::NONE
@@ -459,11 +1565,25 @@ impl Component for QueryEditor {
         let content_x = area.x + line_num_width + 1; // +1 for space after line number
         let content_width = area.width.saturating_sub(line_num_width + 1);
 
-        // Pre-scan lines above viewport for block-comment state
-        let mut in_block_comment = false;
-        for line in &self.lines[..self.scroll_offset] {
-            in_block_comment = highlight::scan_block_comment_state(line, in_block_comment);
-        }
+        let search_query: Option<Vec<char>> = self.search.as_ref().map(|q| q.chars().collect());
+
+        let selection = self.selection_ranges();
+        let visible_end = (self.scroll_offset + visible_height).min(self.lines.len());
+        let mut highlight_cache = self.highlight_cache.borrow_mut();
+        self.fill_highlight_cache(&mut highlight_cache, visible_end);
+        // The bracket at the cursor and its partner, or alone in the
+        // warning style when it has none
+        let brackets: Vec<((usize, usize), Style)> = match focused
+            .then(|| self.bracket_pair(&mut highlight_cache))
+            .flatten()
+        {
+            Some((at, Some(partner))) => vec![
+                (at, theme.editor_bracket_match),
+                (partner, theme.editor_bracket_match),
+            ],
+            Some((at, None)) => vec![(at, theme.status_warning.add_modifier(Modifier::BOLD))],
+            None => Vec::new(),
+        };
 
         for i in 0..visible_height {
             let line_idx = self.scroll_offset + i;
@@ -480,12 +1600,13 @@ impl Component for QueryEditor {
 
                 // Highlighted line content
                 let line = &self.lines[line_idx];
-                let visible_str = super::unicode::truncate_to_width(line, content_width as usize);
-                let max_byte = visible_str.len().min(line.len());
-                let (tokens, next_bc) = highlight::highlight_sql(line, in_block_comment);
-                in_block_comment = next_bc;
+                let max_byte = super::unicode::fit_width(line, content_width as usize);
+                let tokens = highlight_cache[line_idx]
+                    .tokens
+                    .as_deref()
+                    .unwrap_or_default();
 
-                let spans: Vec<Span> = tokens
+                let segments: Vec<(Style, Range<usize>)> = tokens
                     .iter()
                     .filter_map(|(kind, range)| {
                         // Clamp byte ranges to visible width (converted to bytes)
@@ -501,9 +1622,60 @@ impl Component for QueryEditor {
                             TokenKind::Comment => theme.editor_comment,
                             TokenKind::Normal => theme.editor_text,
                         };
-                        Some(Span::styled(&line[start..end], style))
+                        Some((style, start..end))
+                    })
+                    .collect();
+
+                let typing_at = (focused && line_idx == self.cursor.0).then_some(self.cursor.1);
+                let whitespace: Vec<(Range<usize>, Style)> = whitespace_ranges(line, typing_at)
+                    .into_iter()
+                    .map(|range| (range, theme.editor_whitespace))
+                    .collect();
+                let segments = overlay_highlights(segments, &whitespace);
+                let mut bracket_marks: Vec<(Range<usize>, Style)> = brackets
+                    .iter()
+                    .filter(|((l, _), _)| *l == line_idx)
+                    .map(|&((_, c), style)| {
+                        (
+                            char_to_byte_idx(line, c)..char_to_byte_idx(line, c + 1),
+                            style,
+                        )
+                    })
+                    .collect();
+                bracket_marks.sort_by_key(|(range, _)| range.start);
+                let segments = overlay_highlights(segments, &bracket_marks);
+
+                // Search matches: the one under the cursor gets its own style
+                let segments = match search_query {
+                    Some(ref query) => {
+                        let highlights: Vec<(Range<usize>, Style)> = find_in_line(line, query)
+                            .into_iter()
+                            .map(|(s, e)| {
+                                let style = if line_idx == self.cursor.0 && s == self.cursor.1 {
+                                    theme.editor_search_current
+                                } else {
+                                    theme.editor_search_match
+                                };
+                                (char_to_byte_idx(line, s)..char_to_byte_idx(line, e), style)
+                            })
+                            .collect();
+                        overlay_highlights(segments, &highlights)
+                    }
+                    None => segments,
+                };
+                let selected: Vec<(Range<usize>, Style)> = selection
+                    .iter()
+                    .filter(|(l, _, _)| *l == line_idx)
+                    .map(|&(_, s, e)| {
+                        let range = char_to_byte_idx(line, s)..char_to_byte_idx(line, e);
+                        (range, theme.editor_selection)
                     })
                     .collect();
+                let segments = overlay_highlights(segments, &selected);
+                let spans: Vec<Span> = segments
+                    .into_iter()
+                    .map(|(style, range)| Span::styled(&line[range], style))
+                    .collect();
 
                 frame.render_widget(
                     Paragraph::new(Line::from(spans)),
@@ -758,6 +1930,95 @@ mod tests {
         assert_eq!(editor.get_content(), "ac");
     }
 
+    #[test]
+    fn test_undo_step_keeps_only_touched_lines() {
+        let mut editor = QueryEditor::new();
+        let script: Vec<String> = (0..5000).map(|i| format!("SELECT {};", i)).collect();
+        editor.set_content(script.join("\n"));
+        editor.cursor = (2500, 0);
+        editor.insert_char('x');
+        editor.insert_char('y');
+        assert_eq!(editor.undo_stack.len(), 1);
+        assert_eq!(editor.undo_stack[0].old, vec!["SELECT 2500;"]);
+
+        editor.undo();
+        assert_eq!(editor.get_content(), script.join("\n"));
+        assert_eq!(editor.redo_stack[0].old, vec!["xySELECT 2500;"]);
+    }
+
+    #[test]
+    fn test_undo_coalesced_backspace_across_lines() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("ab\ncd\nef".to_string());
+        editor.cursor = (2, 1);
+        // Deletes 'e', joins with line 1, then deletes 'd'
+        editor.backspace();
+        editor.backspace();
+        editor.backspace();
+        assert_eq!(editor.get_content(), "ab\ncf");
+        assert_eq!(editor.undo_stack.len(), 1);
+
+        editor.undo();
+        assert_eq!(editor.get_content(), "ab\ncd\nef");
+        assert_eq!(editor.cursor, (2, 1));
+        editor.redo();
+        assert_eq!(editor.get_content(), "ab\ncf");
+    }
+
+    #[test]
+    fn test_undo_redo_mixed_edits_roundtrip() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("SELECT 1\nFROM t\nWHERE x".to_string());
+        let mut states = vec![editor.get_content()];
+
+        editor.cursor = (1, 6);
+        editor.insert_text(" AS a\nJOIN u");
+        states.push(editor.get_content());
+        editor.cursor = (0, 8);
+        editor.delete_forward();
+        editor.delete_forward();
+        states.push(editor.get_content());
+        editor.new_line();
+        states.push(editor.get_content());
+        editor.replace_all("x", "y");
+        states.push(editor.get_content());
+        editor.clear();
+        states.push(editor.get_content());
+
+        for expected in states.iter().rev().skip(1) {
+            editor.undo();
+            assert_eq!(&editor.get_content(), expected);
+        }
+        for expected in states.iter().skip(1) {
+            editor.redo();
+            assert_eq!(&editor.get_content(), expected);
+        }
+    }
+
+    #[test]
+    fn test_highlight_cache_recomputes_from_edit() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("a\nb\nc\nd".to_string());
+        editor.fill_highlight_cache(editor.highlight_cache.borrow_mut().as_mut(), 4);
+        assert_eq!(editor.highlight_cache.borrow().len(), 4);
+
+        editor.cursor = (2, 0);
+        editor.insert_char('x');
+        assert_eq!(editor.highlight_cache.borrow().len(), 2);
+
+        // Opening a block comment above changes the state of every line below
+        editor.cursor = (0, 0);
+        editor.insert_text("/*");
+        assert!(editor.highlight_cache.borrow().is_empty());
+        editor.fill_highlight_cache(editor.highlight_cache.borrow_mut().as_mut(), 4);
+        let cache = editor.highlight_cache.borrow();
+        assert!(cache.iter().all(|line| line.ends_in_comment));
+        assert_eq!(
+            cache[3].tokens.as_deref(),
+            Some(&[(TokenKind::Comment, 0..1)][..])
+        );
+    }
+
     // ── Scroll tests ────────────────────────────────────────
 
     #[test]
@@ -838,6 +2099,125 @@ mod tests {
         assert_eq!(editor.cursor, (2, 12));
     }
 
+    #[test]
+    fn test_line_operations_are_separate_undo_steps() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("SELECT *\nFROM t\nWHERE x".to_string());
+        editor.set_cursor_position(1, 2);
+
+        editor.duplicate_lines();
+        assert_eq!(editor.get_content(), "SELECT *\nFROM t\nFROM t\nWHERE x");
+        assert_eq!(editor.cursor(), (2, 2));
+        editor.move_lines(true);
+        editor.move_lines(true);
+        assert_eq!(editor.get_content(), "FROM t\nSELECT *\nFROM t\nWHERE x");
+        assert!(!editor.move_lines(true), "already at the top");
+        editor.delete_lines();
+        assert_eq!(editor.get_content(), "SELECT *\nFROM t\nWHERE x");
+        assert_eq!(editor.cursor(), (0, 2));
+
+        editor.undo();
+        assert_eq!(editor.get_content(), "FROM t\nSELECT *\nFROM t\nWHERE x");
+        editor.undo();
+        editor.undo();
+        assert_eq!(editor.get_content(), "SELECT *\nFROM t\nFROM t\nWHERE x");
+        editor.undo();
+        assert_eq!(editor.get_content(), "SELECT *\nFROM t\nWHERE x");
+    }
+
+    #[test]
+    fn test_toggle_comment_respects_indentation() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("SELECT *\n  FROM t\n\n    WHERE x".to_string());
+        editor.set_cursor_position(1, 4);
+        editor.toggle_comment();
+        assert_eq!(editor.line(1), Some("  -- FROM t"));
+        assert_eq!(editor.cursor(), (1, 7));
+        editor.toggle_comment();
+        assert_eq!(editor.line(1), Some("  FROM t"));
+        assert_eq!(editor.cursor(), (1, 4));
+
+        // A selection from line 1 to 3 comments at the shallowest indent,
+        // skipping the blank line
+        for _ in 0..2 {
+            editor.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT));
+        }
+        editor.handle_key(KeyEvent::new(KeyCode::End, KeyModifiers::SHIFT));
+        editor.toggle_comment();
+        assert_eq!(
+            editor.get_content(),
+            "SELECT *\n  -- FROM t\n\n  --   WHERE x"
+        );
+        // Uncomments only when every line is commented
+        editor.set_content("-- a\nb".to_string());
+        editor.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT));
+        editor.handle_key(KeyEvent::new(KeyCode::End, KeyModifiers::SHIFT));
+        editor.toggle_comment();
+        assert_eq!(editor.get_content(), "-- -- a\n-- b");
+        editor.toggle_comment();
+        assert_eq!(editor.get_content(), "-- a\nb");
+        editor.undo();
+        assert_eq!(editor.get_content(), "-- -- a\n-- b");
+    }
+
+    #[test]
+    fn test_line_operations_take_the_selected_lines() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("a\nb\nc\nd".to_string());
+        // Shift+Down twice from "a" selects a and b, ending at the start of c
+        for _ in 0..2 {
+            editor.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT));
+        }
+        editor.move_lines(false);
+        assert_eq!(editor.get_content(), "c\na\nb\nd");
+        assert_eq!(editor.selected_text().as_deref(), Some("a\nb\n"));
+        editor.duplicate_lines();
+        assert_eq!(editor.get_content(), "c\na\nb\na\nb\nd");
+        editor.delete_lines();
+        assert_eq!(editor.get_content(), "c\na\nb\nd");
+        assert!(!editor.has_selection());
+
+        let mut editor = QueryEditor::new();
+        editor.delete_lines();
+        assert_eq!(editor.get_content(), "");
+    }
+
+    #[test]
+    fn test_auto_indent_enter_and_close_paren() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut editor = QueryEditor::new();
+        editor.set_auto_indent(true);
+        editor.set_content("  WHERE id IN ()".to_string());
+        editor.set_cursor_position(0, 15);
+
+        // Between the parens: indent a level and put `)` on its own line
+        editor.handle_key(key(KeyCode::Enter));
+        assert_eq!(editor.get_content(), "  WHERE id IN (\n    \n  )");
+        assert_eq!(editor.cursor(), (1, 4));
+        for c in "SELECT 1".chars() {
+            editor.handle_key(key(KeyCode::Char(c)));
+        }
+        // Plain lines keep the indentation
+        editor.handle_key(key(KeyCode::Enter));
+        assert_eq!(editor.line(2), Some("    "));
+        editor.handle_key(key(KeyCode::Char(')')));
+        assert_eq!(editor.line(2), Some("  )"));
+        assert_eq!(editor.cursor(), (2, 3));
+
+        editor.undo();
+        editor.undo();
+        assert_eq!(editor.line(1), Some("    SELECT 1"));
+
+        // Off, Enter starts at column 0 and `)` goes where it's typed
+        let mut editor = QueryEditor::new();
+        editor.set_content("  f(".to_string());
+        editor.set_cursor_position(0, 4);
+        editor.handle_key(key(KeyCode::Enter));
+        editor.handle_key(key(KeyCode::Char(' ')));
+        editor.handle_key(key(KeyCode::Char(')')));
+        assert_eq!(editor.get_content(), "  f(\n )");
+    }
+
     #[test]
     fn test_insert_text_is_undoable() {
         let mut editor = QueryEditor::new();
@@ -1141,4 +2521,327 @@ mod tests {
         editor.backspace();
         assert_eq!(editor.get_content(), "Hello café  世界!");
     }
+
+    #[test]
+    fn test_grapheme_clusters_move_and_delete_whole() {
+        let mut editor = QueryEditor::new();
+        // "e" + combining acute, then a two-codepoint flag: 5 chars, 3 graphemes
+        editor.set_content("xe\u{301}\u{1f1f3}\u{1f1f4}".to_string());
+        editor.move_end();
+        editor.move_left();
+        assert_eq!(editor.cursor(), (0, 3));
+        editor.move_left();
+        assert_eq!(editor.cursor(), (0, 1));
+        editor.move_right();
+        assert_eq!(editor.cursor(), (0, 3));
+
+        editor.backspace();
+        assert_eq!(editor.get_content(), "x\u{1f1f3}\u{1f1f4}");
+        editor.delete_forward();
+        assert_eq!(editor.get_content(), "x");
+    }
+
+    #[test]
+    fn test_vertical_move_snaps_out_of_grapheme() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("abc\nxe\u{301}z".to_string());
+        editor.set_cursor_position(0, 2);
+        editor.move_down();
+        // Column 2 is the combining mark; the cursor goes before its base
+        assert_eq!(editor.cursor(), (1, 1));
+    }
+
+    // ── Find & replace ───────────────────────────────────────
+
+    #[test]
+    fn test_search_matches_case_insensitive() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("SELECT id FROM users\nwhere Id = 1".to_string());
+        editor.set_search("id");
+        assert_eq!(editor.search_matches(), vec![(0, 7, 9), (1, 6, 8)]);
+        editor.set_search("");
+        assert!(editor.search().is_none());
+        assert!(editor.search_matches().is_empty());
+    }
+
+    #[test]
+    fn test_find_next_and_prev_wrap() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("a x a\nx a".to_string());
+        editor.set_search("a");
+        assert_eq!(editor.find_next(), Some((2, 3)));
+        assert_eq!(editor.cursor(), (0, 4));
+        assert_eq!(editor.find_next(), Some((3, 3)));
+        assert_eq!(editor.cursor(), (1, 2));
+        // Wraps to the first match
+        assert_eq!(editor.find_next(), Some((1, 3)));
+        assert_eq!(editor.cursor(), (0, 0));
+        // Backwards wraps to the last match
+        assert_eq!(editor.find_prev(), Some((3, 3)));
+        assert_eq!(editor.cursor(), (1, 2));
+    }
+
+    #[test]
+    fn test_find_next_without_matches() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("SELECT 1".to_string());
+        editor.set_search("zzz");
+        assert_eq!(editor.find_next(), None);
+        assert_eq!(editor.cursor(), (0, 0));
+    }
+
+    #[test]
+    fn test_replace_all_is_single_undo_step() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("select * from foo\nwhere foo.id = 1".to_string());
+        assert_eq!(editor.replace_all("FOO", "bar"), 2);
+        assert_eq!(editor.get_content(), "select * from bar\nwhere bar.id = 1");
+        editor.undo();
+        assert_eq!(editor.get_content(), "select * from foo\nwhere foo.id = 1");
+    }
+
+    #[test]
+    fn test_replace_all_utf8_and_empty_replacement() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("'café' || 'café'".to_string());
+        assert_eq!(editor.replace_all("é", ""), 2);
+        assert_eq!(editor.get_content(), "'caf' || 'caf'");
+        assert_eq!(editor.replace_all("nothing", "x"), 0);
+    }
+
+    #[test]
+    fn test_overlay_highlights_splits_segments() {
+        let base = Style::default();
+        let hl = Style::default().bg(Color::Yellow);
+        let out = overlay_highlights(vec![(base, 0..6), (base, 6..10)], &[(4..8, hl)]);
+        let ranges: Vec<Range<usize>> = out.iter().map(|(_, r)| r.clone()).collect();
+        assert_eq!(ranges, vec![0..4, 4..6, 6..8, 8..10]);
+        assert_eq!(out[1].0.bg, Some(Color::Yellow));
+        assert_eq!(out[2].0.bg, Some(Color::Yellow));
+        assert_eq!(out[3].0.bg, None);
+    }
+
+    #[test]
+    fn test_whitespace_ranges() {
+        assert_eq!(whitespace_ranges("SELECT 1", None), vec![]);
+        assert_eq!(whitespace_ranges("SELECT 1  ", None), vec![8..10]);
+        assert_eq!(
+            whitespace_ranges("\tSELECT\t1 \t", None),
+            vec![0..1, 7..8, 9..11]
+        );
+        assert_eq!(whitespace_ranges("   ", None), vec![0..3]);
+        // Typing at the end of the line: the trailing run isn't flagged yet
+        assert_eq!(whitespace_ranges("SELECT ", Some(7)), vec![]);
+        assert_eq!(whitespace_ranges("SELECT ", Some(2)), vec![6..7]);
+    }
+
+    #[test]
+    fn test_render_ruler_shades_one_column() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("SELECT 1".to_string());
+        let theme = Theme::default();
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(20, 2)).unwrap();
+        terminal
+            .draw(|f| {
+                editor.render(f, f.area(), false, &theme);
+                editor.render_ruler(f, f.area(), 5, &theme);
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        // Two columns of line numbers and a space come first
+        let ruler_bg = theme.editor_ruler.bg;
+        assert_eq!(buffer[(8, 0)].bg, ruler_bg.unwrap());
+        assert_eq!(buffer[(8, 1)].bg, ruler_bg.unwrap());
+        assert_eq!(buffer[(8, 0)].symbol(), "T");
+        assert_ne!(Some(buffer[(7, 0)].bg), ruler_bg);
+    }
+
+    #[test]
+    fn test_jump_to_matching_bracket_skips_strings_and_comments() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("SELECT count(\n  ')' -- )\n  (1)\n) x".to_string());
+        editor.set_cursor_position(0, 12);
+        assert!(editor.jump_to_matching_bracket());
+        assert_eq!(editor.cursor(), (3, 0));
+        // Just after a bracket counts too
+        editor.set_cursor_position(3, 1);
+        assert!(editor.jump_to_matching_bracket());
+        assert_eq!(editor.cursor(), (0, 12));
+
+        editor.set_cursor_position(1, 3);
+        assert!(!editor.jump_to_matching_bracket(), "inside a string");
+        editor.set_content("SELECT (1".to_string());
+        editor.set_cursor_position(0, 7);
+        assert!(!editor.jump_to_matching_bracket(), "no partner");
+    }
+
+    #[test]
+    fn test_render_marks_bracket_pairs_and_strays() {
+        let theme = Theme::default();
+        let render = |editor: &QueryEditor| {
+            let mut terminal =
+                ratatui::Terminal::new(ratatui::backend::TestBackend::new(20, 1)).unwrap();
+            terminal
+                .draw(|f| editor.render(f, f.area(), true, &theme))
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+        let mut editor = QueryEditor::new();
+        // Line numbers take three columns
+        editor.set_content("f(a(b))".to_string());
+        editor.set_cursor_position(0, 3);
+        let buffer = render(&editor);
+        let bracket_bg = theme.editor_bracket_match.bg.unwrap();
+        assert_eq!(buffer[(3 + 3, 0)].bg, bracket_bg);
+        assert_eq!(buffer[(3 + 5, 0)].bg, bracket_bg);
+        assert_ne!(buffer[(3 + 1, 0)].bg, bracket_bg);
+
+        editor.set_content("x)".to_string());
+        editor.set_cursor_position(0, 2);
+        let buffer = render(&editor);
+        assert_eq!(buffer[(3 + 1, 0)].fg, theme.status_warning.fg.unwrap());
+    }
+
+    #[test]
+    fn test_shift_arrows_select_and_typing_replaces() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("SELECT name\nFROM users".to_string());
+        editor.set_cursor_position(0, 7);
+        editor.handle_key(KeyEvent::new(KeyCode::End, KeyModifiers::SHIFT));
+        assert_eq!(editor.selected_text().as_deref(), Some("name"));
+        assert_eq!(editor.selection_ranges(), vec![(0, 7, 11)]);
+
+        editor.handle_key(KeyEvent::from(KeyCode::Char('*')));
+        assert_eq!(editor.get_content(), "SELECT *\nFROM users");
+        assert!(!editor.has_selection());
+
+        // Across lines, then Backspace removes just the selection
+        editor.set_cursor_position(0, 6);
+        editor.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT));
+        assert_eq!(editor.selected_text().as_deref(), Some(" *\nFROM u"));
+        editor.handle_key(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(editor.get_content(), "SELECTsers");
+        assert_eq!(editor.cursor(), (0, 6));
+        editor.undo();
+        assert_eq!(editor.get_content(), "SELECT *\nFROM users");
+    }
+
+    #[test]
+    fn test_plain_movement_drops_selection() {
+        let mut editor = QueryEditor::new();
+        editor.set_content("abc".to_string());
+        editor.handle_key(KeyEvent::new(KeyCode::Right, KeyModifiers::SHIFT));
+        editor.handle_key(KeyEvent::new(KeyCode::Right, KeyModifiers::SHIFT));
+        assert_eq!(editor.selected_text().as_deref(), Some("ab"));
+        editor.handle_key(KeyEvent::from(KeyCode::Left));
+        assert!(!editor.has_selection());
+        assert!(editor.selection_ranges().is_empty());
+
+        editor.handle_key(KeyEvent::new(KeyCode::Home, KeyModifiers::SHIFT));
+        editor.replace_selection("x\ny");
+        assert_eq!(editor.get_content(), "x\nybc");
+    }
+
+    fn vim_editor(content: &str) -> QueryEditor {
+        let mut editor = QueryEditor::new();
+        editor.set_content(content.to_string());
+        editor.set_vim(true);
+        editor
+    }
+
+    /// Type `keys` (`\x1b` is Esc) into the editor
+    fn vim_keys(editor: &mut QueryEditor, keys: &str) {
+        for c in keys.chars() {
+            let code = if c == '\x1b' {
+                KeyCode::Esc
+            } else {
+                KeyCode::Char(c)
+            };
+            editor.handle_key(KeyEvent::from(code));
+        }
+    }
+
+    #[test]
+    fn test_vim_motions_and_counts() {
+        let mut editor = vim_editor("select one two  three\n  four");
+        assert_eq!(editor.vim_mode(), Some(VimMode::Normal));
+        vim_keys(&mut editor, "3w");
+        assert_eq!(editor.cursor(), (0, 16));
+        vim_keys(&mut editor, "w");
+        assert_eq!(editor.cursor(), (1, 2));
+        vim_keys(&mut editor, "be");
+        assert_eq!(editor.cursor(), (0, 20));
+        vim_keys(&mut editor, "0$");
+        assert_eq!(editor.cursor(), (0, 20), "$ stops on the last character");
+        vim_keys(&mut editor, "j^");
+        assert_eq!(editor.cursor(), (1, 2));
+        vim_keys(&mut editor, "gg");
+        assert_eq!(editor.cursor(), (0, 0));
+        vim_keys(&mut editor, "G");
+        assert_eq!(editor.cursor(), (1, 2));
+        // Plain characters move rather than type
+        assert_eq!(editor.get_content(), "select one two  three\n  four");
+    }
+
+    #[test]
+    fn test_vim_operators() {
+        let mut editor = vim_editor("SELECT id, name FROM users");
+        vim_keys(&mut editor, "dw");
+        assert_eq!(editor.get_content(), "id, name FROM users");
+        vim_keys(&mut editor, "2dw");
+        assert_eq!(editor.get_content(), "name FROM users");
+        vim_keys(&mut editor, "cwtitle\x1b");
+        assert_eq!(editor.get_content(), "title FROM users");
+        assert_eq!(editor.vim_mode(), Some(VimMode::Normal));
+        assert_eq!(
+            editor.cursor(),
+            (0, 4),
+            "Esc steps back onto the last typed char"
+        );
+        vim_keys(&mut editor, "wD");
+        assert_eq!(editor.get_content(), "title ");
+        vim_keys(&mut editor, "0yeP");
+        assert_eq!(editor.get_content(), "titletitle ");
+        vim_keys(&mut editor, "u");
+        assert_eq!(editor.get_content(), "title ");
+        vim_keys(&mut editor, "$x");
+        assert_eq!(editor.get_content(), "title");
+    }
+
+    #[test]
+    fn test_vim_linewise_operators_and_paste() {
+        let mut editor = vim_editor("a\nb\nc\nd");
+        vim_keys(&mut editor, "dd");
+        assert_eq!(editor.get_content(), "b\nc\nd");
+        vim_keys(&mut editor, "2yyGp");
+        assert_eq!(editor.get_content(), "b\nc\nd\nb\nc");
+        assert_eq!(editor.cursor(), (3, 0));
+        vim_keys(&mut editor, "dk");
+        assert_eq!(editor.get_content(), "b\nc\nc");
+        vim_keys(&mut editor, "ggccx\x1bonew\x1b");
+        assert_eq!(editor.get_content(), "x\nnew\nc\nc");
+        vim_keys(&mut editor, "dG");
+        assert_eq!(editor.get_content(), "x");
+    }
+
+    #[test]
+    fn test_vim_visual_selection() {
+        let mut editor = vim_editor("SELECT 1\nFROM t");
+        vim_keys(&mut editor, "ve");
+        assert_eq!(editor.vim_mode(), Some(VimMode::Visual));
+        assert_eq!(editor.selection_ranges(), vec![(0, 0, 6)]);
+        vim_keys(&mut editor, "d");
+        assert_eq!(editor.get_content(), " 1\nFROM t");
+        assert_eq!(editor.vim_mode(), Some(VimMode::Normal));
+        assert!(editor.selection_ranges().is_empty());
+
+        vim_keys(&mut editor, "Vjy");
+        assert_eq!(editor.get_content(), " 1\nFROM t");
+        vim_keys(&mut editor, "P");
+        assert_eq!(editor.get_content(), " 1\nFROM t\n 1\nFROM t");
+
+        vim_keys(&mut editor, "0vlcX\x1b");
+        assert_eq!(editor.get_content(), "X\nFROM t\n 1\nFROM t");
+    }
 }
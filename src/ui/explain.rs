@@ -1,7 +1,9 @@
 //! EXPLAIN ANALYZE tree viewer
 //!
-//! Parses JSON-format EXPLAIN output into a navigable tree with color-coded
-//! timing information. Supports toggling between visual tree and raw text view.
+//! Parses JSON-format EXPLAIN output into a navigable, collapsible tree with
+//! color-coded timing, estimated vs. actual row counts, and the nodes that
+//! spend the most time of their own highlighted. Supports toggling between
+//! visual tree and raw text view.
 
 use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
@@ -22,6 +24,8 @@ struct PlanNode {
     actual_rows: Option<u64>,
     actual_time: Option<f64>,
     actual_loops: Option<u64>,
+    /// Shared buffer hits and reads (EXPLAIN with BUFFERS)
+    buffers: Option<(u64, u64)>,
     children: Vec<PlanNode>,
 }
 
@@ -31,10 +35,15 @@ struct ExplainRow {
     depth: usize,
     node_type: String,
     relation: Option<String>,
+    plan_rows: u64,
     actual_rows: Option<u64>,
     actual_time: Option<f64>,
+    /// Time spent in this node itself across all loops, children excluded
+    self_time: Option<f64>,
+    buffers: Option<(u64, u64)>,
     total_cost: f64,
     has_children: bool,
+    collapsed: bool,
 }
 
 /// View mode for the explain viewer
@@ -48,6 +57,9 @@ enum ViewMode {
 #[derive(Debug)]
 pub struct ExplainViewer {
     rows: Vec<ExplainRow>,
+    /// Indices into `rows` not hidden under a collapsed node (tree mode
+    /// selection indexes this)
+    visible: Vec<usize>,
     raw_text: String,
     planning_time: Option<f64>,
     execution_time_ms: Option<f64>,
@@ -57,6 +69,8 @@ pub struct ExplainViewer {
     view_mode: ViewMode,
     /// Maximum actual_time across all nodes, for color scaling
     max_time: f64,
+    /// Maximum self time across all nodes, for highlighting the costliest
+    max_self_time: f64,
     /// Cached line count for raw text view (avoids O(n) recount on every keystroke)
     raw_text_line_count: usize,
     /// Viewport height captured during render(), used by page_up/page_down navigation.
@@ -85,9 +99,12 @@ impl ExplainViewer {
         // Build raw text representation
         let raw_text = Self::build_raw_text(&root_node, planning_time, execution_time_ms);
         let raw_text_line_count = raw_text.lines().count();
+        let max_self_time = rows.iter().filter_map(|r| r.self_time).fold(0.0, f64::max);
+        let visible = (0..rows.len()).collect();
 
         Some(Self {
             rows,
+            visible,
             raw_text,
             planning_time,
             execution_time_ms,
@@ -96,6 +113,7 @@ impl ExplainViewer {
             scroll_offset: 0,
             view_mode: ViewMode::Tree,
             max_time,
+            max_self_time,
             raw_text_line_count,
             page_height: Cell::new(20),
         })
@@ -120,6 +138,13 @@ impl ExplainViewer {
         let actual_rows = obj.get("Actual Rows").and_then(|v| v.as_u64());
         let actual_time = obj.get("Actual Total Time").and_then(|v| v.as_f64());
         let actual_loops = obj.get("Actual Loops").and_then(|v| v.as_u64());
+        let buffers = match (
+            obj.get("Shared Hit Blocks").and_then(|v| v.as_u64()),
+            obj.get("Shared Read Blocks").and_then(|v| v.as_u64()),
+        ) {
+            (None, None) => None,
+            (hit, read) => Some((hit.unwrap_or(0), read.unwrap_or(0))),
+        };
 
         let children = obj
             .get("Plans")
@@ -136,6 +161,7 @@ impl ExplainViewer {
             actual_rows,
             actual_time,
             actual_loops,
+            buffers,
             children,
         })
     }
@@ -150,16 +176,31 @@ impl ExplainViewer {
             depth,
             node_type: node.node_type.clone(),
             relation: node.relation.clone(),
+            plan_rows: node.plan_rows,
             actual_rows: node.actual_rows,
             actual_time: node.actual_time,
+            self_time: Self::self_time(node),
+            buffers: node.buffers,
             total_cost: node.total_cost,
             has_children: !node.children.is_empty(),
+            collapsed: false,
         });
         for child in &node.children {
             Self::flatten(child, depth + 1, rows, max_time);
         }
     }
 
+    /// Total time of `node` over all its loops
+    fn inclusive_time(node: &PlanNode) -> Option<f64> {
+        Some(node.actual_time? * node.actual_loops.unwrap_or(1) as f64)
+    }
+
+    /// Time `node` spent itself: its inclusive time minus its children's
+    fn self_time(node: &PlanNode) -> Option<f64> {
+        let children: f64 = node.children.iter().filter_map(Self::inclusive_time).sum();
+        Some((Self::inclusive_time(node)? - children).max(0.0))
+    }
+
     fn build_raw_text(
         node: &PlanNode,
         planning_time: Option<f64>,
@@ -207,11 +248,28 @@ impl ExplainViewer {
             node.plan_rows,
             actual
         ));
+        if let Some((hit, read)) = node.buffers {
+            let pad = if depth == 0 {
+                "  ".to_string()
+            } else {
+                format!("{}      ", "      ".repeat(depth - 1))
+            };
+            lines.push(format!("{}Buffers: shared hit={} read={}", pad, hit, read));
+        }
         for child in &node.children {
             Self::raw_text_node(child, depth + 1, lines);
         }
     }
 
+    /// Selected node (or raw text line) and their count (1-based)
+    pub fn position(&self) -> Option<(usize, usize)> {
+        let total = match self.view_mode {
+            ViewMode::Tree => self.visible.len(),
+            ViewMode::RawText => self.raw_text_line_count,
+        };
+        (total > 0).then(|| (self.selected + 1, total))
+    }
+
     // Navigation
 
     pub fn move_up(&mut self) {
@@ -222,7 +280,7 @@ impl ExplainViewer {
 
     pub fn move_down(&mut self) {
         let max = match self.view_mode {
-            ViewMode::Tree => self.rows.len().saturating_sub(1),
+            ViewMode::Tree => self.visible.len().saturating_sub(1),
             ViewMode::RawText => self.raw_text_line_count.saturating_sub(1),
         };
         if self.selected < max {
@@ -238,7 +296,7 @@ impl ExplainViewer {
     pub fn page_down(&mut self) {
         let page = self.page_height.get().max(1);
         let max = match self.view_mode {
-            ViewMode::Tree => self.rows.len().saturating_sub(1),
+            ViewMode::Tree => self.visible.len().saturating_sub(1),
             ViewMode::RawText => self.raw_text_line_count.saturating_sub(1),
         };
         self.selected = (self.selected + page).min(max);
@@ -250,12 +308,81 @@ impl ExplainViewer {
 
     pub fn go_to_bottom(&mut self) {
         let max = match self.view_mode {
-            ViewMode::Tree => self.rows.len().saturating_sub(1),
+            ViewMode::Tree => self.visible.len().saturating_sub(1),
             ViewMode::RawText => self.raw_text_line_count.saturating_sub(1),
         };
         self.selected = max;
     }
 
+    /// Fold or unfold the selected node's children
+    pub fn toggle_collapse(&mut self) {
+        match self.selected_row() {
+            Some(row) if self.rows[row].collapsed => self.expand(),
+            Some(_) => self.collapse(),
+            None => {}
+        }
+    }
+
+    /// Fold the selected node's children; on a leaf or folded node, move
+    /// to its parent instead
+    pub fn collapse(&mut self) {
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        if self.rows[row].has_children && !self.rows[row].collapsed {
+            self.rows[row].collapsed = true;
+            self.rebuild_visible(row);
+        } else if let Some(parent) = (0..row)
+            .rev()
+            .find(|&i| self.rows[i].depth < self.rows[row].depth)
+        {
+            self.select_row(parent);
+        }
+    }
+
+    /// Unfold the selected node's children
+    pub fn expand(&mut self) {
+        if let Some(row) = self.selected_row()
+            && self.rows[row].collapsed
+        {
+            self.rows[row].collapsed = false;
+            self.rebuild_visible(row);
+        }
+    }
+
+    /// Index into `rows` of the selected tree node
+    fn selected_row(&self) -> Option<usize> {
+        if self.view_mode != ViewMode::Tree {
+            return None;
+        }
+        self.visible.get(self.selected).copied()
+    }
+
+    fn select_row(&mut self, row: usize) {
+        if let Some(pos) = self.visible.iter().position(|&i| i == row) {
+            self.selected = pos;
+        }
+    }
+
+    /// Recompute which rows show after a fold change, keeping `keep` selected
+    fn rebuild_visible(&mut self, keep: usize) {
+        self.visible.clear();
+        let mut hidden_below: Option<usize> = None;
+        for (i, row) in self.rows.iter().enumerate() {
+            if let Some(depth) = hidden_below {
+                if row.depth > depth {
+                    continue;
+                }
+                hidden_below = None;
+            }
+            self.visible.push(i);
+            if row.collapsed {
+                hidden_below = Some(row.depth);
+            }
+        }
+        self.select_row(keep);
+    }
+
     pub fn toggle_view_mode(&mut self) {
         self.view_mode = match self.view_mode {
             ViewMode::Tree => ViewMode::RawText,
@@ -280,6 +407,32 @@ impl ExplainViewer {
         }
     }
 
+    /// Highlight for a node by its self time relative to the costliest node
+    fn cost_highlight(&self, row: &ExplainRow) -> Option<Color> {
+        let self_time = row.self_time?;
+        if self.max_self_time <= 0.0 {
+            return None;
+        }
+        let ratio = self_time / self.max_self_time;
+        if ratio > 0.6 {
+            Some(Color::Red)
+        } else if ratio > 0.2 {
+            Some(Color::Yellow)
+        } else {
+            None
+        }
+    }
+
+    /// How far the planner's row estimate was off, when it was off by at
+    /// least 10x in either direction
+    fn misestimate(row: &ExplainRow) -> Option<f64> {
+        let actual = row.actual_rows? as f64;
+        let planned = row.plan_rows as f64;
+        let factor = actual.max(1.0) / planned.max(1.0);
+        let factor = if factor < 1.0 { 1.0 / factor } else { factor };
+        (factor >= 10.0).then_some(factor)
+    }
+
     fn format_time(ms: f64) -> String {
         if ms >= 1000.0 {
             format!("{:.2}s", ms / 1000.0)
@@ -353,7 +506,7 @@ impl Component for ExplainViewer {
             ));
         }
         footer_spans.push(Span::styled(
-            format!("│ {} nodes │ t=toggle raw ", self.rows.len()),
+            format!("│ {} nodes │ Enter=fold t=toggle raw ", self.rows.len()),
             Style::default().fg(Color::DarkGray),
         ));
         let mode_label = match self.view_mode {
@@ -386,19 +539,19 @@ impl ExplainViewer {
         for vis_row in 0..content_height {
             let item_idx = scroll_offset + vis_row;
             let y = area.y + vis_row as u16;
-            if item_idx >= self.rows.len() {
+            let Some(&row_idx) = self.visible.get(item_idx) else {
                 break;
-            }
+            };
 
-            let row = &self.rows[item_idx];
+            let row = &self.rows[row_idx];
             let is_selected = focused && item_idx == self.selected;
 
             // Build tree prefix
             let indent = "  ".repeat(row.depth);
-            let indicator = if row.has_children {
-                "├─ "
-            } else {
-                "── "
+            let indicator = match (row.has_children, row.collapsed) {
+                (true, true) => "▸ ",
+                (true, false) => "▾ ",
+                (false, _) => "─ ",
             };
 
             // Node type + relation
@@ -409,11 +562,28 @@ impl ExplainViewer {
 
             // Metrics
             let time_str = row.actual_time.map(Self::format_time).unwrap_or_default();
-            let rows_str = row
-                .actual_rows
-                .map(|r| format!("{} rows", Self::format_rows(r)))
+            let self_str = row
+                .self_time
+                .filter(|_| row.has_children)
+                .map(|t| format!(" (self {})", Self::format_time(t)))
+                .unwrap_or_default();
+            let rows_str = match row.actual_rows {
+                Some(r) => format!(
+                    "{} rows (est {})",
+                    Self::format_rows(r),
+                    Self::format_rows(row.plan_rows)
+                ),
+                None => format!("est {} rows", Self::format_rows(row.plan_rows)),
+            };
+            let misestimate = Self::misestimate(row);
+            let misestimate_str = misestimate
+                .map(|f| format!(" ×{:.0} off", f))
                 .unwrap_or_default();
             let cost_str = format!("cost {:.0}", row.total_cost);
+            let buffers_str = row
+                .buffers
+                .map(|(hit, read)| format!("  buffers {} hit/{} read", hit, read))
+                .unwrap_or_default();
 
             // Build spans
             let mut spans = Vec::new();
@@ -421,8 +591,16 @@ impl ExplainViewer {
             if is_selected {
                 // Selected row: uniform highlight
                 let full = format!(
-                    "{}{}{}  {}  {}  {}",
-                    indent, indicator, label, time_str, rows_str, cost_str
+                    "{}{}{}  {}{}  {}{}  {}{}",
+                    indent,
+                    indicator,
+                    label,
+                    time_str,
+                    self_str,
+                    rows_str,
+                    misestimate_str,
+                    cost_str,
+                    buffers_str
                 );
                 let padded = super::unicode::pad_to_width(&full, area.width as usize);
                 spans.push(Span::styled(
@@ -438,11 +616,11 @@ impl ExplainViewer {
                     format!("{}{}", indent, indicator),
                     Style::default().fg(Color::DarkGray),
                 ));
-                // Node type
+                // Node type (the nodes spending the most time of their own stand out)
                 spans.push(Span::styled(
                     label,
                     Style::default()
-                        .fg(Color::White)
+                        .fg(self.cost_highlight(row).unwrap_or(Color::White))
                         .add_modifier(Modifier::BOLD),
                 ));
                 // Time (color-coded)
@@ -451,12 +629,19 @@ impl ExplainViewer {
                         format!("  {}", time_str),
                         Style::default().fg(self.time_color(time)),
                     ));
+                    if !self_str.is_empty() {
+                        spans.push(Span::styled(self_str, Style::default().fg(Color::DarkGray)));
+                    }
                 }
-                // Rows
-                if !rows_str.is_empty() {
+                // Rows, actual vs. estimated
+                spans.push(Span::styled(
+                    format!("  {}", rows_str),
+                    Style::default().fg(Color::Gray),
+                ));
+                if misestimate.is_some() {
                     spans.push(Span::styled(
-                        format!("  {}", rows_str),
-                        Style::default().fg(Color::Gray),
+                        misestimate_str,
+                        Style::default().fg(Color::Yellow),
                     ));
                 }
                 // Cost
@@ -464,6 +649,12 @@ impl ExplainViewer {
                     format!("  {}", cost_str),
                     Style::default().fg(Color::DarkGray),
                 ));
+                if !buffers_str.is_empty() {
+                    spans.push(Span::styled(
+                        buffers_str,
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
             }
 
             let line_area = Rect::new(area.x, y, area.width, 1);
@@ -666,6 +857,22 @@ mod tests {
         assert!(viewer.raw_text.contains("Execution Time:"));
     }
 
+    #[test]
+    fn test_buffers_parsed_and_shown_in_raw_text() {
+        let json = r#"[{"Plan": {"Node Type": "Seq Scan", "Relation Name": "t",
+            "Total Cost": 1.0, "Plan Rows": 1, "Actual Rows": 1,
+            "Actual Total Time": 0.01, "Actual Loops": 1,
+            "Shared Hit Blocks": 12, "Shared Read Blocks": 3}}]"#;
+        let viewer = ExplainViewer::from_json(json, Duration::from_millis(1)).unwrap();
+        assert_eq!(viewer.rows[0].buffers, Some((12, 3)));
+        assert!(viewer.raw_text.contains("  Buffers: shared hit=12 read=3"));
+
+        let viewer =
+            ExplainViewer::from_json(sample_explain_json(), Duration::from_millis(1)).unwrap();
+        assert_eq!(viewer.rows[0].buffers, None);
+        assert!(!viewer.raw_text.contains("Buffers"));
+    }
+
     #[test]
     fn test_invalid_json_returns_none() {
         assert!(ExplainViewer::from_json("not json", Duration::from_millis(1)).is_none());
@@ -674,6 +881,54 @@ mod tests {
         assert!(ExplainViewer::from_json("[{}]", Duration::from_millis(1)).is_none());
     }
 
+    #[test]
+    fn test_self_time_excludes_children() {
+        let viewer =
+            ExplainViewer::from_json(sample_explain_json(), Duration::from_millis(1)).unwrap();
+        // Hash Join 0.046 - (orders 0.008 + Hash 0.015)
+        assert!((viewer.rows[0].self_time.unwrap() - 0.023).abs() < 0.0001);
+        // Hash 0.015 - users scan 0.004
+        assert!((viewer.rows[2].self_time.unwrap() - 0.011).abs() < 0.0001);
+        assert!((viewer.max_self_time - 0.023).abs() < 0.0001);
+        assert_eq!(viewer.cost_highlight(&viewer.rows[0]), Some(Color::Red));
+        assert_eq!(viewer.cost_highlight(&viewer.rows[3]), None);
+    }
+
+    #[test]
+    fn test_collapse_hides_children_and_expand_restores() {
+        let mut viewer =
+            ExplainViewer::from_json(sample_explain_json(), Duration::from_millis(1)).unwrap();
+        viewer.selected = 2; // Hash
+        viewer.collapse();
+        assert_eq!(viewer.visible, vec![0, 1, 2]);
+        assert_eq!(viewer.selected, 2);
+        viewer.go_to_bottom();
+        assert_eq!(viewer.selected, 2);
+
+        // Collapsing a folded node moves to its parent
+        viewer.collapse();
+        assert_eq!(viewer.selected, 0);
+        viewer.toggle_collapse();
+        assert_eq!(viewer.visible, vec![0]);
+        viewer.toggle_collapse();
+        assert_eq!(viewer.visible, vec![0, 1, 2]);
+
+        viewer.selected = 2;
+        viewer.expand();
+        assert_eq!(viewer.visible, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_misestimate_flags_tenfold_errors() {
+        let mut viewer =
+            ExplainViewer::from_json(sample_explain_json(), Duration::from_millis(1)).unwrap();
+        assert_eq!(ExplainViewer::misestimate(&viewer.rows[0]), None);
+        viewer.rows[1].actual_rows = Some(5000);
+        assert_eq!(ExplainViewer::misestimate(&viewer.rows[1]), Some(500.0));
+        viewer.rows[1].actual_rows = Some(0);
+        assert_eq!(ExplainViewer::misestimate(&viewer.rows[1]), Some(10.0));
+    }
+
     #[test]
     fn test_format_time() {
         assert_eq!(ExplainViewer::format_time(0.001), "1µs");
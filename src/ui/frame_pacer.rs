@@ -0,0 +1,120 @@
+//! Frame pacing for the main loop
+//!
+//! Frames are drawn only when state changed (the dirty flag) or, while a
+//! query is running, on a slow tick that keeps the elapsed-time display
+//! live. Redraws are capped at one per `FRAME_INTERVAL` so bursts of events
+//! (streaming progress, key repeat) coalesce into a single frame, and an
+//! idle app draws nothing at all.
+
+use std::time::{Duration, Instant};
+
+/// Minimum time between frames (~60 fps)
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Redraw interval while something on screen animates (running query timers)
+pub const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Decides when the main loop should draw the next frame
+#[derive(Debug)]
+pub struct FramePacer {
+    /// State changed since the last frame
+    dirty: bool,
+    /// When the last frame was drawn
+    last_frame: Option<Instant>,
+}
+
+impl FramePacer {
+    /// A pacer whose first frame is due immediately
+    pub fn new() -> Self {
+        Self {
+            dirty: true,
+            last_frame: None,
+        }
+    }
+
+    /// Record that state changed and the screen needs redrawing
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// When the next frame is due, or None when there is nothing to draw.
+    /// `animating` keeps frames coming on `TICK_INTERVAL` without changes.
+    pub fn next_frame(&self, now: Instant, animating: bool) -> Option<Instant> {
+        let interval = if self.dirty {
+            FRAME_INTERVAL
+        } else if animating {
+            TICK_INTERVAL
+        } else {
+            return None;
+        };
+        Some(match self.last_frame {
+            Some(last) => (last + interval).max(now),
+            None => now,
+        })
+    }
+
+    /// Whether a frame should be drawn right now
+    pub fn should_draw(&self, now: Instant, animating: bool) -> bool {
+        self.next_frame(now, animating)
+            .is_some_and(|due| due <= now)
+    }
+
+    /// Record that a frame was drawn at `now`
+    pub fn frame_drawn(&mut self, now: Instant) {
+        self.dirty = false;
+        self.last_frame = Some(now);
+    }
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_frame_is_due_immediately() {
+        let pacer = FramePacer::new();
+        let now = Instant::now();
+        assert!(pacer.is_dirty());
+        assert!(pacer.should_draw(now, false));
+    }
+
+    #[test]
+    fn test_idle_draws_nothing() {
+        let mut pacer = FramePacer::new();
+        let now = Instant::now();
+        pacer.frame_drawn(now);
+        assert_eq!(pacer.next_frame(now + Duration::from_secs(60), false), None);
+    }
+
+    #[test]
+    fn test_dirty_frames_are_capped() {
+        let mut pacer = FramePacer::new();
+        let start = Instant::now();
+        pacer.frame_drawn(start);
+        pacer.mark_dirty();
+
+        let soon = start + Duration::from_millis(5);
+        assert!(!pacer.should_draw(soon, false));
+        assert_eq!(pacer.next_frame(soon, false), Some(start + FRAME_INTERVAL));
+        assert!(pacer.should_draw(start + FRAME_INTERVAL, false));
+    }
+
+    #[test]
+    fn test_animation_ticks_without_changes() {
+        let mut pacer = FramePacer::new();
+        let start = Instant::now();
+        pacer.frame_drawn(start);
+        assert!(!pacer.should_draw(start + FRAME_INTERVAL, true));
+        assert!(pacer.should_draw(start + TICK_INTERVAL, true));
+    }
+}
@@ -163,6 +163,15 @@ impl HelpOverlay {
                 key,
                 desc,
             ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::EstimateRows)
+                ),
+                "Estimate matching rows",
+                key,
+                desc,
+            ),
             help_line(
                 &format!(
                     "  {}",
@@ -193,6 +202,80 @@ impl HelpOverlay {
                 key,
                 desc,
             ),
+            help_line("  Shift+arrows/Home/End", "Select text", key, desc),
+            help_line(
+                &format!(
+                    "  {} / {} / {}",
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::CopySelection),
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::CutSelection),
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::Paste)
+                ),
+                "Copy / cut / paste",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {} / {}",
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::DuplicateLine),
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::DeleteLine)
+                ),
+                "Duplicate / delete line",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::ToggleComment)
+                ),
+                "Toggle -- comment",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::JumpToBracket)
+                ),
+                "Jump to matching parenthesis",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!("  {}", fmt(Some(PanelFocus::QueryEditor), KeyAction::Find)),
+                "Find (highlights matches)",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {} / {}",
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::FindNext),
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::FindPrev)
+                ),
+                "Next / previous match",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::PreviewTable)
+                ),
+                "Preview table under cursor",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::Replace)
+                ),
+                "Replace all",
+                key,
+                desc,
+            ),
             help_line(
                 &format!(
                     "  {}",
@@ -202,13 +285,23 @@ impl HelpOverlay {
                 key,
                 desc,
             ),
-            help_line("  Right", "Accept completion", key, desc),
             help_line(
                 &format!(
                     "  {}",
-                    fmt(Some(PanelFocus::QueryEditor), KeyAction::NextCompletion)
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::SearchHistory)
                 ),
-                "Cycle completions",
+                "Search query history (Alt+Enter runs in a new tab)",
+                key,
+                desc,
+            ),
+            help_line("  Right", "Accept completion", key, desc),
+            help_line(
+                &format!(
+                    "  {} / {}",
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::MoveLineUp),
+                    fmt(Some(PanelFocus::QueryEditor), KeyAction::MoveLineDown)
+                ),
+                "Move line / cycle completions",
                 key,
                 desc,
             ),
@@ -225,6 +318,25 @@ impl HelpOverlay {
             Line::from(Span::styled("Results", section)),
             help_line("  j/k  \u{2191}/\u{2193}", "Navigate rows", key, desc),
             help_line("  h/l  \u{2190}/\u{2192}", "Navigate columns", key, desc),
+            help_line(
+                &format!(
+                    "  {} / {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::PageLeft),
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::PageRight)
+                ),
+                "Page columns left / right",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::JumpToColumn)
+                ),
+                "Jump to column by name",
+                key,
+                desc,
+            ),
             help_line(
                 &format!(
                     "  {}",
@@ -273,6 +385,125 @@ impl HelpOverlay {
                 key,
                 desc,
             ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::ExpandColumn)
+                ),
+                "Expand column to fit (toggle)",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::SortColumn)
+                ),
+                "Sort by column (asc/desc)",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::ClearSort)
+                ),
+                "Clear sort",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::ColumnHistogram)
+                ),
+                "Column value frequencies",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::TogglePin)
+                ),
+                "Pin results (re-runs open a new tab)",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {} / {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::PreviousResults),
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::NextResults)
+                ),
+                "Previous / next results in this tab",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::FilterRows)
+                ),
+                "Filter rows (text or col = value)",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::EditCell)
+                ),
+                "Edit cell (runs UPDATE by primary key)",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::FollowForeignKey)
+                ),
+                "Follow foreign key (referenced row in a new tab)",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::InsertRow)
+                ),
+                "Insert a row into the results' table",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::ColumnInList)
+                ),
+                "Column's values into the editor as IN (...)",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::Find)
+                ),
+                "Search cells",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {} / {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::FindNext),
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::FindPrev)
+                ),
+                "Next / previous match (n while searching)",
+                key,
+                desc,
+            ),
             help_line(
                 &format!(
                     "  {}",
@@ -291,6 +522,27 @@ impl HelpOverlay {
                 key,
                 desc,
             ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::ResultsViewer), KeyAction::CopyResultsCsv)
+                ),
+                "Copy all rows as CSV",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(
+                        Some(PanelFocus::ResultsViewer),
+                        KeyAction::CopyResultsMarkdown
+                    )
+                ),
+                "Copy all rows as a Markdown table",
+                key,
+                desc,
+            ),
             help_line(
                 &format!(
                     "  {}",
@@ -365,7 +617,7 @@ impl HelpOverlay {
                     "  {}",
                     fmt(Some(PanelFocus::TreeBrowser), KeyAction::FilterTree)
                 ),
-                "Filter (type to search locally)",
+                "Fuzzy filter (Tab = next match)",
                 key,
                 desc,
             ),
@@ -383,7 +635,16 @@ impl HelpOverlay {
                     "  {}",
                     fmt(Some(PanelFocus::TreeBrowser), KeyAction::ShowDefinition)
                 ),
-                "Show table definition",
+                "Open DDL in a new tab",
+                key,
+                desc,
+            ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::TreeBrowser), KeyAction::FindUsages)
+                ),
+                "Find views/functions using table or column",
                 key,
                 desc,
             ),
@@ -396,6 +657,15 @@ impl HelpOverlay {
                 key,
                 desc,
             ),
+            help_line(
+                &format!(
+                    "  {}",
+                    fmt(Some(PanelFocus::TreeBrowser), KeyAction::InsertRow)
+                ),
+                "Insert a row into the table",
+                key,
+                desc,
+            ),
             help_line("  Enter", "Search database / Load more", key, desc),
             help_line("  Esc", "Clear filter and restore tree", key, desc),
             blank.clone(),
@@ -426,6 +696,140 @@ impl HelpOverlay {
             help_line("  /connect", "Connection picker", key, desc),
             help_line("  /refresh", "Reload schema", key, desc),
             help_line("  /save-query [name]", "Save current query", key, desc),
+            help_line("  /messages", "Show recent status messages", key, desc),
+            help_line("  /explain [options]", "Set EXPLAIN options", key, desc),
+            help_line("  /begin", "Start a transaction", key, desc),
+            help_line("  /commit", "Commit the transaction", key, desc),
+            help_line("  /rollback", "Roll back the transaction", key, desc),
+            help_line("  /stash [name]", "Keep results / list stashes", key, desc),
+            help_line("  /restore [name]", "Show a stash again", key, desc),
+            help_line(
+                "  /compare [name]",
+                "Diff results against a stash",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /snippet [name]",
+                "Insert a snippet / list them",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /snippet save <name>",
+                "Save the editor as a snippet",
+                key,
+                desc,
+            ),
+            help_line("  /snippet delete <name>", "Remove a snippet", key, desc),
+            help_line("  /job <sql>", "Run a query in the background", key, desc),
+            help_line("  /jobs", "List running background jobs", key, desc),
+            help_line("  /jobs cancel <n>", "Cancel a background job", key, desc),
+            help_line("  /reveal", "Show or re-mask masked columns", key, desc),
+            help_line("  /force", "Run the query past the cost guard", key, desc),
+            help_line("  /tabonly", "Close all other tabs", key, desc),
+            help_line(
+                "  /tabclose-right",
+                "Close the tabs to the right",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /open <file>",
+                "Load a SQL file (Tab completes the path)",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /use <database>",
+                "Switch to another database on the server",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /listen [channel]",
+                "LISTEN on a channel, or show notifications",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /unlisten [channel]",
+                "Stop listening (on all channels if none)",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /tail [table [WHERE ..]]",
+                "Follow a table's new rows (stop if none)",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /snapshot [table]",
+                "Freeze a table's rows in a new tab",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /timeout [30s|off]",
+                "Show or change statement_timeout",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /dashboard",
+                "Server health: connections, cache, locks",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /replication",
+                "Publications and subscriptions (r refresh)",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /sessions",
+                "Server sessions: c cancel, x x terminate",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /tabconnect [name|url]",
+                "Connect this tab to another database",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /copy <fmt>",
+                "Copy results as csv, tsv, json, or md",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /export <fmt> [path]",
+                "Export as csv, tsv, json, md, or xlsx",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /export all <fmt> [path]",
+                "Export every row, past the page LIMIT",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /export copy <fmt> [path]",
+                "Export every row with server-side COPY",
+                key,
+                desc,
+            ),
+            help_line(
+                "  /import <file> [table]",
+                "Load a CSV into a table via COPY",
+                key,
+                desc,
+            ),
             blank.clone(),
             Line::from(Span::styled(
                 "Meta-Commands (type in editor, then execute)",
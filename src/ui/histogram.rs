@@ -0,0 +1,307 @@
+//! Column value-frequency overlay
+//!
+//! Counts how often each value appears in one column of the loaded rows
+//! (after any row filter) and draws the most common ones as a bar list —
+//! a quick look at a categorical column without writing a GROUP BY.
+//! Follows the same overlay pattern as MessageLog.
+
+use std::collections::HashMap;
+
+use crate::db::types::{CellValue, QueryResults};
+use crate::ui::theme::Theme;
+use crate::ui::unicode::{display_width, pad_to_width, truncate_to_width};
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+/// Most values listed; the rest are summed into one "other values" line
+const MAX_BARS: usize = 100;
+
+/// Widest value label, in terminal columns
+const MAX_LABEL_WIDTH: usize = 30;
+
+/// One value and how many rows have it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bar {
+    /// The value's text (`None` for NULL)
+    pub value: Option<String>,
+    pub count: usize,
+}
+
+/// Value frequencies for one result column, most common first
+pub struct ColumnHistogram {
+    column: String,
+    bars: Vec<Bar>,
+    /// Rows counted
+    rows: usize,
+    /// Distinct values, including those past `MAX_BARS`
+    distinct: usize,
+    /// Rows whose values didn't make the list
+    other: usize,
+    visible: bool,
+    scroll_offset: usize,
+}
+
+impl ColumnHistogram {
+    pub fn new() -> Self {
+        Self {
+            column: String::new(),
+            bars: Vec::new(),
+            rows: 0,
+            distinct: 0,
+            other: 0,
+            visible: false,
+            scroll_offset: 0,
+        }
+    }
+
+    /// Count the values of column `col` and show the overlay. Returns false
+    /// if the column doesn't exist.
+    pub fn show(&mut self, results: &QueryResults, col: usize) -> bool {
+        let Some(column) = results.columns.get(col) else {
+            return false;
+        };
+        let mut counts: HashMap<Option<String>, usize> = HashMap::new();
+        for row in &results.rows {
+            let value = match row.values.get(col) {
+                None | Some(CellValue::Null) => None,
+                Some(cell) => Some(cell.display_string(usize::MAX)),
+            };
+            *counts.entry(value).or_default() += 1;
+        }
+        let mut bars: Vec<Bar> = counts
+            .into_iter()
+            .map(|(value, count)| Bar { value, count })
+            .collect();
+        // Most common first; ties in value order so the list is stable
+        bars.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+
+        self.column = column.name.clone();
+        self.rows = results.rows.len();
+        self.distinct = bars.len();
+        self.other = bars.iter().skip(MAX_BARS).map(|b| b.count).sum();
+        bars.truncate(MAX_BARS);
+        self.bars = bars;
+        self.visible = true;
+        self.scroll_offset = 0;
+        true
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.bars.clear();
+        self.scroll_offset = 0;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Name of the counted column
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// The listed values, most common first
+    pub fn bars(&self) -> &[Bar] {
+        &self.bars
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn distinct(&self) -> usize {
+        self.distinct
+    }
+
+    /// Rows whose values are past the listed ones
+    pub fn other(&self) -> usize {
+        self.other
+    }
+
+    fn line_count(&self) -> usize {
+        self.bars.len() + usize::from(self.other > 0)
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        if self.scroll_offset + 1 < self.line_count() {
+            self.scroll_offset += 1;
+        }
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(20);
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_offset = (self.scroll_offset + 20).min(self.line_count().saturating_sub(1));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_offset = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_offset = self.line_count().saturating_sub(1);
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if area.height == 0 {
+            return;
+        }
+
+        if self.rows == 0 {
+            frame.render_widget(
+                Paragraph::new("No rows loaded").style(theme.help_desc),
+                area,
+            );
+            return;
+        }
+
+        let label_width = self
+            .bars
+            .iter()
+            .map(|b| b.value.as_deref().map_or(4, display_width))
+            .max()
+            .unwrap_or(0)
+            .clamp(4, MAX_LABEL_WIDTH)
+            .min(area.width as usize / 3);
+        // "  1234  (12.3%)" after the bar
+        let count_width = self.rows.to_string().len();
+        let bar_width = (area.width as usize).saturating_sub(label_width + count_width + 13);
+        let max_count = self.bars.first().map_or(1, |b| b.count);
+
+        let other = (self.other > 0).then(|| {
+            let label = format!("({} other values)", self.distinct - self.bars.len());
+            (label, self.other)
+        });
+        let lines = self
+            .bars
+            .iter()
+            .map(|b| {
+                let (label, style) = match &b.value {
+                    Some(v) => (v.as_str(), theme.help_desc),
+                    None => ("NULL", theme.help_key),
+                };
+                (label.to_string(), style, b.count)
+            })
+            .chain(other.map(|(label, n)| (label, theme.help_key, n)));
+
+        for (i, (label, style, count)) in lines
+            .skip(self.scroll_offset)
+            .take(area.height as usize)
+            .enumerate()
+        {
+            let filled = if count == 0 || bar_width == 0 {
+                0
+            } else {
+                (count * bar_width / max_count).clamp(1, bar_width)
+            };
+            let pct = count as f64 * 100.0 / self.rows as f64;
+            let line = Line::from(vec![
+                Span::styled(
+                    pad_to_width(&truncate_to_width(&label, label_width), label_width),
+                    style,
+                ),
+                Span::raw(" "),
+                Span::styled("\u{2588}".repeat(filled), theme.status_info),
+                Span::raw(" ".repeat(bar_width - filled)),
+                Span::styled(
+                    format!("  {:>w$}  ({:>5.1}%)", count, pct, w = count_width),
+                    theme.help_key,
+                ),
+            ]);
+            frame.render_widget(
+                Paragraph::new(line),
+                Rect::new(area.x, area.y + i as u16, area.width, 1),
+            );
+        }
+    }
+}
+
+impl Default for ColumnHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::{ColumnDef, DataType, Row};
+    use std::time::Duration;
+
+    fn results(values: Vec<CellValue>) -> QueryResults {
+        let n = values.len();
+        QueryResults::new(
+            vec![ColumnDef {
+                name: "status".to_string(),
+                data_type: DataType::Text,
+                nullable: true,
+            }],
+            values
+                .into_iter()
+                .map(|v| Row { values: vec![v] })
+                .collect(),
+            Duration::from_millis(1),
+            n,
+        )
+    }
+
+    fn text(s: &str) -> CellValue {
+        CellValue::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_counts_most_common_first() {
+        let mut histogram = ColumnHistogram::new();
+        assert!(histogram.show(
+            &results(vec![
+                text("open"),
+                text("closed"),
+                CellValue::Null,
+                text("open"),
+                text("NULL"),
+            ]),
+            0
+        ));
+        assert_eq!(histogram.column(), "status");
+        assert_eq!(histogram.rows(), 5);
+        assert_eq!(histogram.distinct(), 4);
+        assert_eq!(
+            histogram.bars()[0],
+            Bar {
+                value: Some("open".to_string()),
+                count: 2
+            }
+        );
+        // NULL and the text "NULL" are different values; ties sort by value
+        let rest: Vec<Option<&str>> = histogram.bars()[1..]
+            .iter()
+            .map(|b| b.value.as_deref())
+            .collect();
+        assert_eq!(rest, vec![None, Some("NULL"), Some("closed")]);
+        assert_eq!(histogram.other(), 0);
+    }
+
+    #[test]
+    fn test_long_tail_is_summed() {
+        let values = (0..MAX_BARS + 5).map(|i| text(&i.to_string())).collect();
+        let mut histogram = ColumnHistogram::new();
+        histogram.show(&results(values), 0);
+        assert_eq!(histogram.bars().len(), MAX_BARS);
+        assert_eq!(histogram.distinct(), MAX_BARS + 5);
+        assert_eq!(histogram.other(), 5);
+    }
+
+    #[test]
+    fn test_unknown_column() {
+        let mut histogram = ColumnHistogram::new();
+        assert!(!histogram.show(&results(vec![text("a")]), 3));
+        assert!(!histogram.is_visible());
+    }
+}
@@ -0,0 +1,315 @@
+//! Query history browser overlay
+//!
+//! Ctrl+R opens a searchable list of past queries, newest first, with when
+//! and where each ran, how long it took, and how many rows it returned (or
+//! that it failed). Typing
+//! fuzzy-filters the list; Enter puts the selected query in the editor, and
+//! Alt+Enter runs it in a new tab.
+//! Like the connection dialog, it takes every key while visible.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+use crate::history::HistoryEntry;
+use crate::ui::theme::Theme;
+use crate::ui::tree::fuzzy_match;
+use crate::ui::unicode::{display_width, prev_grapheme};
+
+/// Result of a key press in the history browser
+#[derive(Debug, PartialEq, Eq)]
+pub enum HistoryBrowserAction {
+    /// User picked a query
+    Select(String),
+    /// User picked a query to run in a new tab (Alt+Enter)
+    RunInNewTab(String),
+    /// User closed the browser (Esc)
+    Dismissed,
+    /// Key was consumed by the browser
+    Consumed,
+}
+
+/// Searchable snapshot of the query history
+pub struct HistoryBrowser {
+    visible: bool,
+    /// Entries when the browser opened, newest first
+    entries: Vec<HistoryEntry>,
+    query: String,
+    /// Indices into `entries` that match `query`
+    matches: Vec<usize>,
+    /// Position in `matches`
+    selected: usize,
+}
+
+impl HistoryBrowser {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            entries: Vec::new(),
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    /// Open the browser on `entries` (newest first) with an empty search
+    pub fn show(&mut self, entries: Vec<HistoryEntry>) {
+        self.entries = entries;
+        self.query.clear();
+        self.visible = true;
+        self.refilter();
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.entries.clear();
+        self.matches.clear();
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// The search text
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Number of entries matching the search
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The highlighted entry
+    pub fn selected_entry(&self) -> Option<&HistoryEntry> {
+        self.matches.get(self.selected).map(|&i| &self.entries[i])
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> HistoryBrowserAction {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return HistoryBrowserAction::Dismissed,
+            KeyCode::Enter => {
+                let run = key.modifiers.contains(KeyModifiers::ALT);
+                return match self.selected_entry() {
+                    Some(entry) if run => HistoryBrowserAction::RunInNewTab(entry.sql.clone()),
+                    Some(entry) => HistoryBrowserAction::Select(entry.sql.clone()),
+                    None => HistoryBrowserAction::Consumed,
+                };
+            }
+            // Ctrl+R again steps to the next older match, as in a shell
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('r' | 'n') if ctrl => self.move_selection(1),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Char('p') if ctrl => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::Backspace if !self.query.is_empty() => {
+                self.query
+                    .truncate(prev_grapheme(&self.query, self.query.len()));
+                self.refilter();
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.refilter();
+            }
+            KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+                self.query.push(c);
+                self.refilter();
+            }
+            _ => {}
+        }
+        HistoryBrowserAction::Consumed
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let last = self.matches.len() - 1;
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    fn refilter(&mut self) {
+        let pattern = self.query.to_lowercase();
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| fuzzy_match(&e.sql, &pattern))
+            .map(|(i, _)| i)
+            .collect();
+        self.selected = 0;
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if area.height == 0 {
+            return;
+        }
+
+        let prompt = "Search: ";
+        let input = Line::from(vec![
+            Span::styled(prompt, theme.dialog_label),
+            Span::styled(self.query.as_str(), theme.dialog_input_focused),
+        ]);
+        frame.render_widget(
+            Paragraph::new(input),
+            Rect::new(area.x, area.y, area.width, 1),
+        );
+        let cursor_x = area.x + (prompt.len() + display_width(&self.query)) as u16;
+        frame.set_cursor_position((cursor_x.min(area.x + area.width.saturating_sub(1)), area.y));
+
+        let list_height = area.height.saturating_sub(2) as usize;
+        if list_height == 0 {
+            return;
+        }
+        let list_y = area.y + 2;
+        if self.matches.is_empty() {
+            let message = if self.entries.is_empty() {
+                "No history yet"
+            } else {
+                "No matching queries"
+            };
+            frame.render_widget(
+                Paragraph::new(message).style(theme.help_desc),
+                Rect::new(area.x, list_y, area.width, 1),
+            );
+            return;
+        }
+
+        // Scroll just far enough to keep the selection on screen
+        let offset = (self.selected + 1).saturating_sub(list_height);
+
+        for (row, (pos, &i)) in self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(list_height)
+            .enumerate()
+        {
+            let entry = &self.entries[i];
+            let selected = pos == self.selected;
+            let sql_style = if selected {
+                theme.dialog_selected
+            } else {
+                theme.help_desc
+            };
+            let outcome_style = if entry.succeeded == Some(false) {
+                theme.status_error
+            } else {
+                theme.help_key
+            };
+            let connection: String = entry
+                .connection
+                .as_deref()
+                .unwrap_or_default()
+                .chars()
+                .take(12)
+                .collect();
+            let meta = format!(
+                "{:<16}  {:<12}  {:>8}  ",
+                entry.ran_at_label(),
+                connection,
+                entry.duration_label(),
+            );
+            let line = Line::from(vec![
+                Span::styled(meta, theme.help_key),
+                Span::styled(format!("{:>10}  ", entry.outcome_label()), outcome_style),
+                Span::styled(entry.one_line_sql(), sql_style),
+            ]);
+            frame.render_widget(
+                Paragraph::new(line),
+                Rect::new(area.x, list_y + row as u16, area.width, 1),
+            );
+        }
+    }
+}
+
+impl Default for HistoryBrowser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(sql: &str) -> HistoryEntry {
+        HistoryEntry {
+            sql: sql.to_string(),
+            ran_at: None,
+            duration_ms: None,
+            rows: None,
+            connection: None,
+            succeeded: None,
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn browser() -> HistoryBrowser {
+        let mut browser = HistoryBrowser::new();
+        browser.show(vec![
+            entry("SELECT * FROM orders"),
+            entry("UPDATE users SET name = 'x'"),
+            entry("SELECT count(*) FROM users"),
+        ]);
+        browser
+    }
+
+    #[test]
+    fn test_typing_fuzzy_filters() {
+        let mut browser = browser();
+        assert_eq!(browser.match_count(), 3);
+        for c in "usr".chars() {
+            browser.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(browser.match_count(), 2);
+        assert_eq!(
+            browser.selected_entry().unwrap().sql,
+            "UPDATE users SET name = 'x'"
+        );
+        browser.handle_key(key(KeyCode::Backspace));
+        assert_eq!(browser.query(), "us");
+    }
+
+    #[test]
+    fn test_navigation_and_select() {
+        let mut browser = browser();
+        browser.handle_key(key(KeyCode::Up));
+        browser.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        browser.handle_key(key(KeyCode::Down));
+        browser.handle_key(key(KeyCode::Down));
+        assert_eq!(
+            browser.handle_key(key(KeyCode::Enter)),
+            HistoryBrowserAction::Select("SELECT count(*) FROM users".to_string())
+        );
+        assert_eq!(
+            browser.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT)),
+            HistoryBrowserAction::RunInNewTab("SELECT count(*) FROM users".to_string())
+        );
+        assert_eq!(
+            browser.handle_key(key(KeyCode::Esc)),
+            HistoryBrowserAction::Dismissed
+        );
+    }
+
+    #[test]
+    fn test_enter_without_match_is_consumed() {
+        let mut browser = browser();
+        browser.handle_key(key(KeyCode::Char('z')));
+        assert_eq!(browser.match_count(), 0);
+        assert_eq!(
+            browser.handle_key(key(KeyCode::Enter)),
+            HistoryBrowserAction::Consumed
+        );
+    }
+}
@@ -0,0 +1,515 @@
+//! Insert-row form
+//!
+//! A modal form with one input per column of a table, for adding a row to
+//! a small lookup or config table without writing the INSERT. Values are
+//! checked against the column's type before the INSERT is generated; an
+//! empty input leaves the column to its default (or NULL), and `NULL`
+//! sets NULL. Follows the ConnectionDialog pattern.
+
+use crate::db::schema::InsertColumn;
+use crate::db::types::DataType;
+use crate::sql::{quote_ident, quote_literal, quote_qualified};
+use crate::ui::connection_dialog::visible_slice;
+use crate::ui::theme::Theme;
+use crate::ui::unicode::{display_width, next_grapheme, pad_to_width, prev_grapheme};
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+/// Widest the column name and type columns get
+const NAME_WIDTH: usize = 24;
+const TYPE_WIDTH: usize = 20;
+
+/// Actions returned by the form to the parent
+pub enum InsertFormAction {
+    /// The INSERT built from the inputs, to confirm and run
+    Submit(String),
+    /// User dismissed the form (Esc)
+    Dismissed,
+    /// Key was consumed by the form
+    Consumed,
+}
+
+/// One column's input
+struct Field {
+    column: InsertColumn,
+    input: String,
+    cursor: usize,
+}
+
+/// Insert-row form state
+pub struct InsertForm {
+    visible: bool,
+    schema: String,
+    table: String,
+    fields: Vec<Field>,
+    selected: usize,
+    error: Option<String>,
+}
+
+impl InsertForm {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            schema: String::new(),
+            table: String::new(),
+            fields: Vec::new(),
+            selected: 0,
+            error: None,
+        }
+    }
+
+    /// Show an empty form for `schema.table`
+    pub fn show(&mut self, schema: String, table: String, columns: Vec<InsertColumn>) {
+        self.schema = schema;
+        self.table = table;
+        self.fields = columns
+            .into_iter()
+            .map(|column| Field {
+                column,
+                input: String::new(),
+                cursor: 0,
+            })
+            .collect();
+        self.selected = (0..self.fields.len())
+            .find(|&i| !self.fields[i].column.generated)
+            .unwrap_or(0);
+        self.error = None;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.fields.clear();
+        self.error = None;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// The table rows go into, quoted
+    pub fn target(&self) -> String {
+        quote_qualified(&self.schema, &self.table)
+    }
+
+    /// Show why the form can't be submitted yet
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handle a key event, returning an InsertFormAction
+    pub fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> InsertFormAction {
+        use crossterm::event::KeyCode;
+
+        match key.code {
+            KeyCode::Esc => return InsertFormAction::Dismissed,
+            KeyCode::Enter => {
+                return match self.insert_sql() {
+                    Ok(sql) => InsertFormAction::Submit(sql),
+                    Err(e) => {
+                        self.error = Some(e);
+                        InsertFormAction::Consumed
+                    }
+                };
+            }
+            KeyCode::Tab | KeyCode::Down => self.move_selection(true),
+            KeyCode::BackTab | KeyCode::Up => self.move_selection(false),
+            _ => self.handle_input_key(key),
+        }
+        InsertFormAction::Consumed
+    }
+
+    /// Select the next (or previous) column the server doesn't generate
+    fn move_selection(&mut self, forward: bool) {
+        let n = self.fields.len();
+        for step in 1..n {
+            let i = if forward {
+                (self.selected + step) % n
+            } else {
+                (self.selected + n - step) % n
+            };
+            if !self.fields[i].column.generated {
+                self.selected = i;
+                return;
+            }
+        }
+    }
+
+    fn handle_input_key(&mut self, key: crossterm::event::KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        let Some(field) = self.fields.get_mut(self.selected) else {
+            return;
+        };
+        if field.column.generated {
+            return;
+        }
+        let (input, cursor) = (&mut field.input, &mut field.cursor);
+        match key.code {
+            // Space steps a boolean through true, false, and empty
+            KeyCode::Char(' ') if field.column.data_type == DataType::Boolean => {
+                *input = match input.as_str() {
+                    "" => "true",
+                    "true" => "false",
+                    _ => "",
+                }
+                .to_string();
+                *cursor = input.len();
+            }
+            KeyCode::Char(c) => {
+                input.insert(*cursor, c);
+                *cursor += c.len_utf8();
+            }
+            KeyCode::Backspace if *cursor > 0 => {
+                let prev = prev_grapheme(input, *cursor);
+                input.drain(prev..*cursor);
+                *cursor = prev;
+            }
+            KeyCode::Delete if *cursor < input.len() => {
+                let next = next_grapheme(input, *cursor);
+                input.drain(*cursor..next);
+            }
+            KeyCode::Left => *cursor = prev_grapheme(input, *cursor),
+            KeyCode::Right => *cursor = next_grapheme(input, *cursor),
+            KeyCode::Home => *cursor = 0,
+            KeyCode::End => *cursor = input.len(),
+            _ => return,
+        }
+        self.error = None;
+    }
+
+    /// The INSERT for the inputs, or the first input that doesn't fit its
+    /// column. Columns left empty are left out, so they get their default.
+    pub fn insert_sql(&self) -> Result<String, String> {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        for field in &self.fields {
+            if let Some(value) = literal(&field.column, &field.input)? {
+                columns.push(quote_ident(&field.column.name));
+                values.push(value);
+            }
+        }
+        if columns.is_empty() {
+            return Ok(format!(
+                "INSERT INTO {}\nDEFAULT VALUES\nRETURNING *;",
+                self.target()
+            ));
+        }
+        Ok(format!(
+            "INSERT INTO {} ({})\nVALUES ({})\nRETURNING *;",
+            self.target(),
+            columns.join(", "),
+            values.join(", ")
+        ))
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if area.height < 3 || area.width < 20 {
+            return;
+        }
+        let x = area.x + 1;
+        let width = area.width.saturating_sub(2);
+        let name_width = self
+            .fields
+            .iter()
+            .map(|f| display_width(&f.column.name))
+            .max()
+            .unwrap_or(0)
+            .min(NAME_WIDTH);
+        let type_width = self
+            .fields
+            .iter()
+            .map(|f| display_width(&f.column.type_name))
+            .max()
+            .unwrap_or(0)
+            .min(TYPE_WIDTH);
+        let label_width = 2 + name_width + 1 + type_width + 2;
+        let input_width = (width as usize).saturating_sub(label_width);
+
+        // Rows for fields; the last two hold the error and the key hints
+        let rows = area.height.saturating_sub(2) as usize;
+        let first = (self.selected + 1).saturating_sub(rows);
+        for (row, (i, field)) in self
+            .fields
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(rows)
+            .enumerate()
+        {
+            let y = area.y + row as u16;
+            let selected = i == self.selected;
+            let label = format!(
+                "{} {} {}  ",
+                if selected { "\u{25b8}" } else { " " },
+                pad_to_width(&truncate(&field.column.name, name_width), name_width),
+                pad_to_width(&truncate(&field.column.type_name, type_width), type_width),
+            );
+            let value = if field.input.is_empty() {
+                Span::styled(
+                    truncate(&placeholder(&field.column), input_width),
+                    theme.dialog_hint,
+                )
+            } else {
+                let visible = visible_slice(&field.input, field.cursor, input_width);
+                if selected {
+                    let cursor_x = x + label_width as u16 + visible.cursor_offset as u16;
+                    frame.set_cursor_position((cursor_x.min(x + width - 1), y));
+                }
+                let style = if selected {
+                    theme.dialog_input_focused
+                } else {
+                    theme.dialog_input
+                };
+                Span::styled(visible.text, style)
+            };
+            if selected && field.input.is_empty() && !field.column.generated {
+                frame.set_cursor_position((x + label_width as u16, y));
+            }
+            frame.render_widget(
+                Paragraph::new(Line::from(vec![
+                    Span::styled(label, theme.dialog_label),
+                    value,
+                ])),
+                Rect::new(x, y, width, 1),
+            );
+        }
+
+        let bottom = area.y + area.height;
+        if let Some(ref err) = self.error {
+            frame.render_widget(
+                Paragraph::new(Span::styled(
+                    format!("  {}", truncate(err, width as usize - 2)),
+                    theme.dialog_warning,
+                )),
+                Rect::new(x, bottom - 2, width, 1),
+            );
+        }
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "  Enter=insert  Tab/\u{2191}\u{2193}=field  empty=default  NULL=null  Esc=cancel",
+                theme.dialog_hint,
+            )),
+            Rect::new(x, bottom - 1, width, 1),
+        );
+    }
+}
+
+impl Default for InsertForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What an empty input means for the column, with the expected format
+fn placeholder(column: &InsertColumn) -> String {
+    if column.generated {
+        return "generated".to_string();
+    }
+    let empty = match column.default {
+        Some(ref default) => format!("default {}", default),
+        None if column.not_null => "required".to_string(),
+        None => "NULL".to_string(),
+    };
+    match format_hint(&column.data_type) {
+        Some(hint) => format!("{} \u{00b7} {}", empty, hint),
+        None => empty,
+    }
+}
+
+/// How a value of the type is written, where it isn't obvious
+fn format_hint(data_type: &DataType) -> Option<&'static str> {
+    Some(match data_type {
+        DataType::Boolean => "true/false (Space toggles)",
+        DataType::Date => "YYYY-MM-DD",
+        DataType::Time => "HH:MM:SS",
+        DataType::Timestamp | DataType::TimestampTz => "YYYY-MM-DD HH:MM:SS",
+        DataType::Interval => "e.g. 1 day 2 hours",
+        DataType::Json | DataType::Jsonb => "JSON",
+        DataType::Uuid => "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx",
+        DataType::Bytea => "\\x followed by hex",
+        DataType::Array(_) => "{a,b,c}",
+        _ => return None,
+    })
+}
+
+/// The SQL for an input, checked against the column's type; None leaves the
+/// column out of the INSERT
+fn literal(column: &InsertColumn, input: &str) -> Result<Option<String>, String> {
+    let name = &column.name;
+    if column.generated {
+        return Ok(None);
+    }
+    if input.is_empty() {
+        if column.not_null && column.default.is_none() {
+            return Err(format!("{} is required", name));
+        }
+        return Ok(None);
+    }
+    if input == "NULL" {
+        if column.not_null {
+            return Err(format!("{} can't be NULL", name));
+        }
+        return Ok(Some("NULL".to_string()));
+    }
+    let trimmed = input.trim();
+    let value = match column.data_type {
+        DataType::SmallInt | DataType::Integer | DataType::BigInt => {
+            trimmed
+                .parse::<i64>()
+                .map_err(|_| format!("{} needs a whole number", name))?;
+            trimmed.to_string()
+        }
+        DataType::Real | DataType::Double | DataType::Numeric => {
+            trimmed
+                .parse::<f64>()
+                .map_err(|_| format!("{} needs a number", name))?;
+            quote_literal(trimmed)
+        }
+        DataType::Boolean => match trimmed.to_ascii_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "on" | "1" => "TRUE".to_string(),
+            "false" | "f" | "no" | "n" | "off" | "0" => "FALSE".to_string(),
+            _ => return Err(format!("{} needs true or false", name)),
+        },
+        DataType::Uuid => {
+            uuid::Uuid::parse_str(trimmed).map_err(|_| format!("{} needs a UUID", name))?;
+            quote_literal(trimmed)
+        }
+        DataType::Json | DataType::Jsonb => {
+            serde_json::from_str::<serde_json::Value>(input)
+                .map_err(|e| format!("{} isn't valid JSON: {}", name, e))?;
+            quote_literal(input)
+        }
+        // Everything else goes as a literal PostgreSQL casts to the column
+        _ => quote_literal(input),
+    };
+    Ok(Some(value))
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if display_width(text) > width {
+        crate::ui::unicode::truncate_to_width(text, width)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    fn column(
+        name: &str,
+        data_type: DataType,
+        not_null: bool,
+        default: Option<&str>,
+    ) -> InsertColumn {
+        InsertColumn {
+            name: name.to_string(),
+            type_name: data_type.display_name(),
+            data_type,
+            not_null,
+            default: default.map(str::to_string),
+            generated: false,
+        }
+    }
+
+    fn form() -> InsertForm {
+        let mut form = InsertForm::new();
+        form.show(
+            "public".to_string(),
+            "plans".to_string(),
+            vec![
+                InsertColumn {
+                    generated: true,
+                    ..column("id", DataType::Integer, true, Some("identity"))
+                },
+                column("code", DataType::Text, true, None),
+                column("price", DataType::Numeric, false, None),
+                column("active", DataType::Boolean, true, Some("true")),
+            ],
+        );
+        form
+    }
+
+    fn type_text(form: &mut InsertForm, text: &str) {
+        for c in text.chars() {
+            form.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_insert_sql_leaves_empty_columns_to_their_default() {
+        let mut form = form();
+        // The generated id is skipped
+        type_text(&mut form, "it's");
+        assert_eq!(
+            form.insert_sql().unwrap(),
+            "INSERT INTO \"public\".\"plans\" (\"code\")\nVALUES ('it''s')\nRETURNING *;"
+        );
+
+        form.handle_key(KeyEvent::from(KeyCode::Tab));
+        type_text(&mut form, "9.50");
+        form.handle_key(KeyEvent::from(KeyCode::Tab));
+        form.handle_key(KeyEvent::from(KeyCode::Char(' ')));
+        form.handle_key(KeyEvent::from(KeyCode::Char(' ')));
+        assert_eq!(
+            form.insert_sql().unwrap(),
+            "INSERT INTO \"public\".\"plans\" (\"code\", \"price\", \"active\")\n\
+             VALUES ('it''s', '9.50', FALSE)\nRETURNING *;"
+        );
+
+        // Tab wraps around past the generated id
+        form.handle_key(KeyEvent::from(KeyCode::Tab));
+        assert_eq!(form.selected, 1);
+    }
+
+    #[test]
+    fn test_inputs_are_checked_against_the_column() {
+        let mut form = form();
+        assert!(matches!(
+            form.handle_key(KeyEvent::from(KeyCode::Enter)),
+            InsertFormAction::Consumed
+        ));
+        assert_eq!(form.error.as_deref(), Some("code is required"));
+
+        type_text(&mut form, "NULL");
+        assert_eq!(form.insert_sql().unwrap_err(), "code can't be NULL");
+
+        form.handle_key(KeyEvent::from(KeyCode::Tab));
+        type_text(&mut form, "cheap");
+        assert_eq!(form.insert_sql().unwrap_err(), "code can't be NULL");
+        form.handle_key(KeyEvent::from(KeyCode::BackTab));
+        for _ in 0..4 {
+            form.handle_key(KeyEvent::from(KeyCode::Backspace));
+        }
+        type_text(&mut form, "basic");
+        assert_eq!(form.insert_sql().unwrap_err(), "price needs a number");
+
+        let uuid = column("key", DataType::Uuid, false, None);
+        assert!(literal(&uuid, "not-a-uuid").is_err());
+        let json = column("meta", DataType::Jsonb, false, None);
+        assert_eq!(
+            literal(&json, "{\"a\": 1}").unwrap().as_deref(),
+            Some("'{\"a\": 1}'")
+        );
+        assert!(literal(&json, "{a: 1}").is_err());
+    }
+
+    #[test]
+    fn test_placeholder_says_what_empty_means() {
+        let form = form();
+        let placeholders: Vec<String> =
+            form.fields.iter().map(|f| placeholder(&f.column)).collect();
+        assert_eq!(
+            placeholders,
+            vec![
+                "generated",
+                "required",
+                "NULL",
+                "default true \u{00b7} true/false (Space toggles)",
+            ]
+        );
+    }
+}
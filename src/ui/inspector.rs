@@ -84,6 +84,12 @@ impl Inspector {
         self.scroll_offset = self.total_lines.saturating_sub(1);
     }
 
+    /// Top visible line and line count (1-based)
+    pub fn position(&self) -> Option<(usize, usize)> {
+        self.content.as_ref()?;
+        Some((self.scroll_offset + 1, self.total_lines))
+    }
+
     /// Measure content dimensions (width, height) for variable-size popup.
     /// Returns (0, 0) if no content.
     pub fn content_size(&self) -> (u16, u16) {
@@ -0,0 +1,159 @@
+//! Background job list overlay
+//!
+//! Queries started with `/job` run on their own connection, detached from
+//! any tab; their results open in a new tab when they finish. `/jobs` shows
+//! the ones still running with their elapsed time. Follows the same overlay
+//! pattern as MessageLog.
+
+use std::time::{Duration, Instant};
+
+use crate::ui::theme::Theme;
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+/// A query running in the background
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub id: usize,
+    pub sql: String,
+    pub started: Instant,
+    /// Its query history entry, for recording the outcome
+    pub history_entry: Option<usize>,
+}
+
+/// Running background jobs, oldest first
+pub struct JobList {
+    jobs: Vec<Job>,
+    next_id: usize,
+    visible: bool,
+}
+
+impl JobList {
+    pub fn new() -> Self {
+        Self {
+            jobs: Vec::new(),
+            next_id: 1,
+            visible: false,
+        }
+    }
+
+    /// Record a job as started, returning its id
+    pub fn start(&mut self, sql: &str, history_entry: Option<usize>) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.jobs.push(Job {
+            id,
+            sql: sql.to_string(),
+            started: Instant::now(),
+            history_entry,
+        });
+        id
+    }
+
+    /// Remove a finished job
+    pub fn finish(&mut self, id: usize) -> Option<Job> {
+        let pos = self.jobs.iter().position(|j| j.id == id)?;
+        Some(self.jobs.remove(pos))
+    }
+
+    /// Forget all jobs (their results will be ignored)
+    pub fn clear(&mut self) {
+        self.jobs.clear();
+    }
+
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if area.height == 0 {
+            return;
+        }
+
+        if self.jobs.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No jobs running — /job <sql> starts one").style(theme.help_desc),
+                area,
+            );
+            return;
+        }
+
+        for (i, job) in self.jobs.iter().take(area.height as usize).enumerate() {
+            let sql = job.sql.split_whitespace().collect::<Vec<_>>().join(" ");
+            let line = Line::from(vec![
+                Span::styled(format!("#{:<4}", job.id), theme.help_key),
+                Span::styled(
+                    format!("{:>8}  ", format_elapsed(job.started.elapsed())),
+                    theme.status_info,
+                ),
+                Span::styled(sql, theme.help_desc),
+            ]);
+            frame.render_widget(
+                Paragraph::new(line),
+                Rect::new(area.x, area.y + i as u16, area.width, 1),
+            );
+        }
+    }
+}
+
+impl Default for JobList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Elapsed time as "m:ss", or "h:mm:ss" from an hour
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_and_finish() {
+        let mut jobs = JobList::new();
+        let a = jobs.start("SELECT 1", None);
+        let b = jobs.start("SELECT 2", Some(4));
+        assert_eq!((a, b), (1, 2));
+        assert_eq!(jobs.finish(a).unwrap().sql, "SELECT 1");
+        assert!(jobs.finish(a).is_none());
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs.finish(b).unwrap().history_entry, Some(4));
+        // Ids aren't reused
+        assert_eq!(jobs.start("SELECT 3", None), 3);
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_secs(7)), "0:07");
+        assert_eq!(format_elapsed(Duration::from_secs(125)), "2:05");
+        assert_eq!(format_elapsed(Duration::from_secs(3725)), "1:02:05");
+    }
+}
@@ -4,6 +4,25 @@
 
 use ratatui::layout::Rect;
 
+/// Below this width or height the screen switches to the compact layout
+pub const COMPACT_WIDTH: u16 = 80;
+pub const COMPACT_HEIGHT: u16 = 20;
+
+/// Narrowest and widest the tree gets next to the editor
+const MIN_TREE_WIDTH: u16 = 20;
+const MAX_TREE_WIDTH: u16 = 40;
+/// Fewest rows the editor and results get, borders included
+const MIN_EDITOR_HEIGHT: u16 = 3;
+const MIN_RESULTS_HEIGHT: u16 = 5;
+
+/// The main panels, for picking the one the compact layout shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainPanel {
+    Tree,
+    Editor,
+    Results,
+}
+
 /// Layout areas for the main screen
 pub struct AppLayout {
     /// Left panel: schema tree browser
@@ -16,50 +35,72 @@ pub struct AppLayout {
     pub results: Rect,
     /// Bottom row: command/status bar
     pub command_bar: Rect,
+    /// Only one panel is shown, and the others are empty
+    pub compact: bool,
 }
 
-/// Calculate panel layout for the main screen
-pub fn calculate_layout(area: Rect, show_tab_bar: bool) -> AppLayout {
-    if area.height < 4 || area.width < 20 {
-        // Too small - give everything to results
-        return AppLayout {
-            tree: Rect::new(area.x, area.y, 0, 0),
-            tab_bar: Rect::new(0, 0, 0, 0),
-            editor: Rect::new(area.x, area.y, 0, 0),
-            results: Rect::new(area.x, area.y, area.width, area.height.saturating_sub(1)),
-            command_bar: Rect::new(
-                area.x,
-                area.y + area.height.saturating_sub(1),
-                area.width,
-                1,
-            ),
-        };
-    }
+/// Whether `area` is too small for the panels side by side
+pub fn is_compact(area: Rect) -> bool {
+    area.width < COMPACT_WIDTH || area.height < COMPACT_HEIGHT
+}
 
+/// Calculate panel layout for the main screen. On a small screen (see
+/// `is_compact`) `shown` gets the whole area below the tab bar.
+pub fn calculate_layout(area: Rect, show_tab_bar: bool, shown: MainPanel) -> AppLayout {
     // Reserve bottom row for command bar
-    let main_height = area.height - 1;
+    let main_height = area.height.saturating_sub(1);
     let command_bar = Rect::new(area.x, area.y + main_height, area.width, 1);
 
-    // Left panel: tree (25% width, min 20, max 40)
-    let tree_width = (area.width / 4).clamp(20, 40).min(area.width / 2);
+    // Tab bar steals 1 row from the top of the panels when visible
+    let tab_bar_height: u16 = if show_tab_bar && main_height > 1 {
+        1
+    } else {
+        0
+    };
+    let tab_bar_at = |x, width| {
+        if tab_bar_height > 0 {
+            Rect::new(x, area.y, width, 1)
+        } else {
+            Rect::new(0, 0, 0, 0)
+        }
+    };
+
+    if is_compact(area) {
+        let tab_bar = tab_bar_at(area.x, area.width);
+        let panel = Rect::new(
+            area.x,
+            area.y + tab_bar_height,
+            area.width,
+            main_height - tab_bar_height,
+        );
+        let empty = Rect::new(area.x, area.y, 0, 0);
+        let pick = |p: MainPanel| if p == shown { panel } else { empty };
+        return AppLayout {
+            tree: pick(MainPanel::Tree),
+            tab_bar,
+            editor: pick(MainPanel::Editor),
+            results: pick(MainPanel::Results),
+            command_bar,
+            compact: true,
+        };
+    }
+
+    // Left panel: tree (25% width)
+    let tree_width = (area.width / 4).clamp(MIN_TREE_WIDTH, MAX_TREE_WIDTH);
     let tree = Rect::new(area.x, area.y, tree_width, main_height);
 
     // Right side
     let right_x = area.x + tree_width;
     let right_width = area.width - tree_width;
 
-    // Tab bar steals 1 row from the top of the right side when visible
-    let tab_bar_height: u16 = if show_tab_bar { 1 } else { 0 };
-    let tab_bar = if show_tab_bar {
-        Rect::new(right_x, area.y, right_width, 1)
-    } else {
-        Rect::new(0, 0, 0, 0)
-    };
+    let tab_bar = tab_bar_at(right_x, right_width);
     let right_top_y = area.y + tab_bar_height;
     let right_main_height = main_height - tab_bar_height;
 
     // Editor gets 40% of right side height, results gets 60%
-    let editor_height = (right_main_height * 2 / 5).max(3).min(right_main_height);
+    let editor_height = (right_main_height * 2 / 5)
+        .max(MIN_EDITOR_HEIGHT)
+        .min(right_main_height - MIN_RESULTS_HEIGHT);
     let results_height = right_main_height - editor_height;
 
     let editor = Rect::new(right_x, right_top_y, right_width, editor_height);
@@ -76,6 +117,7 @@ pub fn calculate_layout(area: Rect, show_tab_bar: bool) -> AppLayout {
         editor,
         results,
         command_bar,
+        compact: false,
     }
 }
 
@@ -86,7 +128,7 @@ mod tests {
     #[test]
     fn test_calculate_layout() {
         let area = Rect::new(0, 0, 100, 50);
-        let layout = calculate_layout(area, false);
+        let layout = calculate_layout(area, false, MainPanel::Editor);
 
         assert!(layout.tree.width > 0);
         assert!(layout.editor.width > 0);
@@ -98,7 +140,7 @@ mod tests {
     #[test]
     fn test_layout_reserves_command_bar() {
         let area = Rect::new(0, 0, 80, 40);
-        let layout = calculate_layout(area, false);
+        let layout = calculate_layout(area, false, MainPanel::Editor);
 
         // Command bar should be at the very bottom
         assert_eq!(layout.command_bar.y, area.height - 1);
@@ -109,8 +151,8 @@ mod tests {
     #[test]
     fn test_tab_bar_steals_from_editor() {
         let area = Rect::new(0, 0, 100, 50);
-        let without = calculate_layout(area, false);
-        let with = calculate_layout(area, true);
+        let without = calculate_layout(area, false, MainPanel::Editor);
+        let with = calculate_layout(area, true, MainPanel::Editor);
 
         // Tab bar should be 1 row, positioned at the top of the right side
         assert_eq!(with.tab_bar.height, 1);
@@ -125,12 +167,39 @@ mod tests {
         assert_eq!(with.command_bar, without.command_bar);
     }
 
+    #[test]
+    fn test_panels_keep_their_minimum_sizes() {
+        let area = Rect::new(0, 0, COMPACT_WIDTH, COMPACT_HEIGHT);
+        let layout = calculate_layout(area, true, MainPanel::Editor);
+        assert!(!layout.compact);
+        assert_eq!(layout.tree.width, MIN_TREE_WIDTH);
+        assert!(layout.editor.height >= MIN_EDITOR_HEIGHT);
+        assert!(layout.results.height >= MIN_RESULTS_HEIGHT);
+    }
+
+    #[test]
+    fn test_compact_layout_shows_one_panel() {
+        let area = Rect::new(0, 0, 60, 30);
+        let layout = calculate_layout(area, true, MainPanel::Results);
+        assert!(layout.compact);
+        assert_eq!(layout.tab_bar, Rect::new(0, 0, 60, 1));
+        assert_eq!(layout.results, Rect::new(0, 1, 60, 28));
+        assert_eq!(layout.tree.area(), 0);
+        assert_eq!(layout.editor.area(), 0);
+
+        // Too short, even if wide enough
+        let layout = calculate_layout(Rect::new(0, 0, 120, 15), true, MainPanel::Tree);
+        assert!(layout.compact);
+        assert_eq!(layout.tree.width, 120);
+        assert_eq!(layout.results.area(), 0);
+    }
+
     #[test]
     fn test_tab_bar_small_terminal_no_underflow() {
         // height=4 with tab bar: main_height=3, tab_bar=1, right_main_height=2
         // editor_height would be max(0,3)=3 which exceeds 2 → must be clamped
         let area = Rect::new(0, 0, 80, 4);
-        let layout = calculate_layout(area, true);
+        let layout = calculate_layout(area, true, MainPanel::Editor);
         // Should not panic; results_height should be 0
         assert!(layout.editor.height <= 2);
     }
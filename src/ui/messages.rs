@@ -0,0 +1,200 @@
+//! Status message log overlay
+//!
+//! Keeps the most recent status-bar messages so dismissed toasts (export
+//! paths, errors) can be re-read. Shown as a centered popup via `/messages`,
+//! newest first. Follows the same overlay pattern as HelpOverlay.
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+
+use crate::app::StatusLevel;
+use crate::ui::theme::Theme;
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+/// Maximum number of messages retained in the log
+const MESSAGE_LOG_CAPACITY: usize = 200;
+
+/// A status message captured when it was shown
+#[derive(Debug, Clone)]
+pub struct LoggedMessage {
+    pub time: DateTime<Local>,
+    pub level: StatusLevel,
+    pub message: String,
+}
+
+/// Scrollable log of past status messages
+pub struct MessageLog {
+    entries: VecDeque<LoggedMessage>,
+    visible: bool,
+    scroll_offset: usize,
+}
+
+impl MessageLog {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::with_capacity(MESSAGE_LOG_CAPACITY),
+            visible: false,
+            scroll_offset: 0,
+        }
+    }
+
+    /// Record a message, dropping the oldest once at capacity
+    pub fn push(&mut self, level: StatusLevel, message: &str) {
+        if self.entries.len() == MESSAGE_LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LoggedMessage {
+            time: Local::now(),
+            level,
+            message: message.to_string(),
+        });
+    }
+
+    /// Messages, newest first
+    pub fn entries(&self) -> impl Iterator<Item = &LoggedMessage> {
+        self.entries.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+        self.scroll_offset = 0;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.scroll_offset = 0;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        if self.scroll_offset + 1 < self.entries.len() {
+            self.scroll_offset += 1;
+        }
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(20);
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_offset = (self.scroll_offset + 20).min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_offset = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_offset = self.entries.len().saturating_sub(1);
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if area.height == 0 {
+            return;
+        }
+
+        if self.entries.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No messages yet").style(theme.help_desc),
+                area,
+            );
+            return;
+        }
+
+        for (i, entry) in self
+            .entries()
+            .skip(self.scroll_offset)
+            .take(area.height as usize)
+            .enumerate()
+        {
+            let (label, level_style) = match entry.level {
+                StatusLevel::Info => ("INFO ", theme.status_info),
+                StatusLevel::Success => ("OK   ", theme.status_success),
+                StatusLevel::Warning => ("WARN ", theme.status_warning),
+                StatusLevel::Error => ("ERROR", theme.status_error),
+            };
+            let line = Line::from(vec![
+                Span::styled(entry.time.format("%H:%M:%S ").to_string(), theme.help_key),
+                Span::styled(format!("{} ", label), level_style),
+                Span::styled(entry.message.as_str(), theme.help_desc),
+            ]);
+            frame.render_widget(
+                Paragraph::new(line),
+                Rect::new(area.x, area.y + i as u16, area.width, 1),
+            );
+        }
+    }
+}
+
+impl Default for MessageLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_log_newest_first() {
+        let mut log = MessageLog::new();
+        log.push(StatusLevel::Info, "first");
+        log.push(StatusLevel::Error, "second");
+        let messages: Vec<&str> = log.entries().map(|m| m.message.as_str()).collect();
+        assert_eq!(messages, vec!["second", "first"]);
+        assert_eq!(log.entries().next().unwrap().level, StatusLevel::Error);
+    }
+
+    #[test]
+    fn test_message_log_drops_oldest_at_capacity() {
+        let mut log = MessageLog::new();
+        for i in 0..MESSAGE_LOG_CAPACITY + 5 {
+            log.push(StatusLevel::Info, &format!("msg {}", i));
+        }
+        assert_eq!(log.len(), MESSAGE_LOG_CAPACITY);
+        assert_eq!(
+            log.entries().last().unwrap().message,
+            "msg 5",
+            "oldest five should have been dropped"
+        );
+    }
+
+    #[test]
+    fn test_message_log_show_hide_and_scroll() {
+        let mut log = MessageLog::new();
+        for i in 0..3 {
+            log.push(StatusLevel::Info, &format!("msg {}", i));
+        }
+        log.show();
+        assert!(log.is_visible());
+
+        log.scroll_up();
+        assert_eq!(log.scroll_offset, 0);
+        log.scroll_to_bottom();
+        assert_eq!(log.scroll_offset, 2);
+        log.scroll_down();
+        assert_eq!(log.scroll_offset, 2);
+
+        log.hide();
+        assert!(!log.is_visible());
+        assert_eq!(log.scroll_offset, 0);
+    }
+}
@@ -4,17 +4,31 @@
 
 pub mod command_bar;
 pub mod connection_dialog;
+pub mod dashboard;
 pub mod editor;
 pub mod explain;
+pub mod frame_pacer;
 pub mod help;
 pub mod highlight;
+pub mod histogram;
+pub mod history_browser;
+pub mod insert_form;
 pub mod inspector;
+pub mod jobs;
 pub mod layout;
+pub mod messages;
+pub mod notifications;
+pub mod numbers;
 pub mod render;
+pub mod replication;
 pub mod results;
+pub mod server_summary;
+pub mod sessions;
+pub mod table_preview;
 pub mod theme;
 pub mod tree;
 pub mod unicode;
+pub mod vim;
 
 use crossterm::event::KeyEvent;
 use ratatui::{Frame, layout::Rect};
@@ -0,0 +1,209 @@
+//! LISTEN/NOTIFY notifications overlay
+//!
+//! Collects the NOTIFY payloads that arrive on the channels given to
+//! `/listen`, newest first, as they come in. Shown as a centered popup via
+//! `/listen` without a channel. Follows the same overlay pattern as
+//! MessageLog.
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+
+use crate::db::Notification;
+use crate::ui::theme::Theme;
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+/// Maximum number of notifications retained
+const NOTIFICATION_CAPACITY: usize = 500;
+
+/// A notification with the time it arrived
+#[derive(Debug, Clone)]
+pub struct ReceivedNotification {
+    pub time: DateTime<Local>,
+    pub notification: Notification,
+}
+
+/// Scrollable list of received notifications
+pub struct NotificationLog {
+    entries: VecDeque<ReceivedNotification>,
+    /// Channels being listened to
+    channels: Vec<String>,
+    visible: bool,
+    scroll_offset: usize,
+}
+
+impl NotificationLog {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            channels: Vec::new(),
+            visible: false,
+            scroll_offset: 0,
+        }
+    }
+
+    /// Record a notification, dropping the oldest once at capacity. Keeps
+    /// the view on the same entries when scrolled down.
+    pub fn push(&mut self, notification: Notification) {
+        if self.entries.len() == NOTIFICATION_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(ReceivedNotification {
+            time: Local::now(),
+            notification,
+        });
+        if self.scroll_offset > 0 {
+            self.scroll_offset = (self.scroll_offset + 1).min(self.entries.len() - 1);
+        }
+    }
+
+    /// Notifications, newest first
+    pub fn entries(&self) -> impl Iterator<Item = &ReceivedNotification> {
+        self.entries.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn channels(&self) -> &[String] {
+        &self.channels
+    }
+
+    pub fn set_channels(&mut self, channels: Vec<String>) {
+        self.channels = channels;
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+        self.scroll_offset = 0;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.scroll_offset = 0;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        if self.scroll_offset + 1 < self.entries.len() {
+            self.scroll_offset += 1;
+        }
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(20);
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_offset = (self.scroll_offset + 20).min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_offset = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_offset = self.entries.len().saturating_sub(1);
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if area.height == 0 {
+            return;
+        }
+
+        if self.entries.is_empty() {
+            let message = if self.channels.is_empty() {
+                "Not listening — /listen <channel> to start"
+            } else {
+                "Waiting for notifications..."
+            };
+            frame.render_widget(Paragraph::new(message).style(theme.help_desc), area);
+            return;
+        }
+
+        for (i, entry) in self
+            .entries()
+            .skip(self.scroll_offset)
+            .take(area.height as usize)
+            .enumerate()
+        {
+            let n = &entry.notification;
+            // Multi-line payloads stay on their row
+            let payload = n.payload.replace(['\n', '\r'], " ");
+            let line = Line::from(vec![
+                Span::styled(entry.time.format("%H:%M:%S ").to_string(), theme.help_key),
+                Span::styled(format!("{} ", n.channel), theme.status_info),
+                Span::styled(payload, theme.help_desc),
+                Span::styled(format!("  (pid {})", n.process_id), theme.help_key),
+            ]);
+            frame.render_widget(
+                Paragraph::new(line),
+                Rect::new(area.x, area.y + i as u16, area.width, 1),
+            );
+        }
+    }
+}
+
+impl Default for NotificationLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notify(channel: &str, payload: &str) -> Notification {
+        Notification {
+            channel: channel.to_string(),
+            payload: payload.to_string(),
+            process_id: 42,
+        }
+    }
+
+    #[test]
+    fn test_notifications_newest_first_and_capped() {
+        let mut log = NotificationLog::new();
+        for i in 0..NOTIFICATION_CAPACITY + 3 {
+            log.push(notify("orders", &i.to_string()));
+        }
+        assert_eq!(log.len(), NOTIFICATION_CAPACITY);
+        let newest = log.entries().next().unwrap();
+        assert_eq!(
+            newest.notification.payload,
+            (NOTIFICATION_CAPACITY + 2).to_string()
+        );
+        assert_eq!(log.entries().last().unwrap().notification.payload, "3");
+    }
+
+    #[test]
+    fn test_scrolled_view_stays_put_as_notifications_arrive() {
+        let mut log = NotificationLog::new();
+        log.push(notify("orders", "a"));
+        log.push(notify("orders", "b"));
+        log.show();
+        log.scroll_down();
+        assert_eq!(log.scroll_offset, 1);
+        log.push(notify("orders", "c"));
+        assert_eq!(log.scroll_offset, 2);
+
+        // At the top, new notifications show up as they arrive
+        log.scroll_to_top();
+        log.push(notify("orders", "d"));
+        assert_eq!(log.scroll_offset, 0);
+    }
+}
@@ -0,0 +1,197 @@
+//! Locale-aware number formatting
+//!
+//! Row counts, compact counts (`1.2M`), and sizes (`340 MB`) in the tree,
+//! status bar, and overlays, and the decimal mark of numbers in the results
+//! grid, all follow one `NumberFormat` built from the `number_locale`
+//! setting. Copies and exports keep the values as PostgreSQL sent them.
+
+use std::borrow::Cow;
+
+/// Decimal mark and digit grouping separator for a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    decimal: char,
+    group: char,
+}
+
+impl Default for NumberFormat {
+    /// `1,234.5`
+    fn default() -> Self {
+        Self {
+            decimal: '.',
+            group: ',',
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Format for a locale name such as `de`, `fr_FR.UTF-8`, or `de-CH`;
+    /// `auto` reads `LC_ALL`, `LC_NUMERIC`, or `LANG`, and `""`, `C`, and
+    /// `POSIX` are the default
+    pub fn parse(locale: &str) -> Result<Self, String> {
+        let locale = locale.trim();
+        if locale.eq_ignore_ascii_case("auto") {
+            let env = ["LC_ALL", "LC_NUMERIC", "LANG"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|v| !v.is_empty())
+                .unwrap_or_default();
+            return Ok(Self::for_locale(&env).unwrap_or_default());
+        }
+        Self::for_locale(locale).ok_or_else(|| {
+            format!(
+                "unknown locale '{}' (use a language code like en, de, fr, or auto)",
+                locale
+            )
+        })
+    }
+
+    fn for_locale(locale: &str) -> Option<Self> {
+        // Drop the encoding and modifier: de_DE.UTF-8@euro -> de_DE
+        let tag = locale.split(['.', '@']).next().unwrap_or("");
+        let mut parts = tag.split(['_', '-']);
+        let language = parts.next().unwrap_or("").to_ascii_lowercase();
+        let region = parts.next().unwrap_or("").to_ascii_uppercase();
+        let (decimal, group) = match (language.as_str(), region.as_str()) {
+            ("" | "c" | "posix", _) => ('.', ','),
+            ("de" | "it" | "rm", "CH" | "LI") => ('.', '\''),
+            (
+                "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl"
+                | "sr" | "is" | "ca",
+                _,
+            ) => (',', '.'),
+            (
+                "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "nn" | "no" | "uk" | "hu"
+                | "bg" | "et" | "lv" | "lt",
+                _,
+            ) => (',', '\u{a0}'),
+            ("en" | "ja" | "zh" | "ko" | "he" | "th" | "hi" | "ms" | "ga" | "cy" | "mt", _) => {
+                ('.', ',')
+            }
+            _ => return None,
+        };
+        Some(Self { decimal, group })
+    }
+
+    /// A count in full with digit grouping (4523 → "4,523")
+    pub fn count(&self, n: usize) -> String {
+        let digits = n.to_string();
+        if digits.len() <= 3 {
+            return digits;
+        }
+        let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, ch) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                result.push(self.group);
+            }
+            result.push(ch);
+        }
+        result
+    }
+
+    /// A count rounded to K, M, or B (1234567 → "1.2M")
+    pub fn compact(&self, n: i64) -> String {
+        if n < 0 {
+            return format!("-{}", self.compact(n.saturating_neg()));
+        }
+        let (value, suffix) = match n {
+            0..1_000 => return n.to_string(),
+            1_000..1_000_000 => (n as f64 / 1_000.0, "K"),
+            1_000_000..1_000_000_000 => (n as f64 / 1_000_000.0, "M"),
+            _ => (n as f64 / 1_000_000_000.0, "B"),
+        };
+        format!("{}{}", self.one_decimal(value), suffix)
+    }
+
+    /// A byte count the way `pg_size_pretty` does (e.g. "16 kB",
+    /// "830 MB"), with one decimal below 10 of a unit
+    pub fn size(&self, bytes: i64) -> String {
+        const UNITS: [&str; 5] = ["kB", "MB", "GB", "TB", "PB"];
+        if bytes < 1024 {
+            return format!("{} bytes", bytes);
+        }
+        let mut size = bytes as f64 / 1024.0;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        if size < 10.0 {
+            format!("{} {}", self.one_decimal(size), UNITS[unit])
+        } else {
+            format!("{:.0} {}", size, UNITS[unit])
+        }
+    }
+
+    /// A number as PostgreSQL prints it ("3.14") with this locale's decimal
+    /// mark. Digits aren't grouped, so ids and years read as they are.
+    pub fn decimal<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.decimal == '.' || !text.contains('.') {
+            return Cow::Borrowed(text);
+        }
+        Cow::Owned(text.replace('.', &self.decimal.to_string()))
+    }
+
+    /// `value` to one decimal place, dropping a trailing ".0"
+    fn one_decimal(&self, value: f64) -> String {
+        let text = format!("{:.1}", value);
+        match text.strip_suffix(".0") {
+            Some(whole) => whole.to_string(),
+            None => self.decimal(&text).into_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count() {
+        let numbers = NumberFormat::default();
+        assert_eq!(numbers.count(0), "0");
+        assert_eq!(numbers.count(999), "999");
+        assert_eq!(numbers.count(1_000), "1,000");
+        assert_eq!(numbers.count(4_523), "4,523");
+        assert_eq!(numbers.count(12_345_678), "12,345,678");
+    }
+
+    #[test]
+    fn test_compact() {
+        let numbers = NumberFormat::default();
+        assert_eq!(numbers.compact(42), "42");
+        assert_eq!(numbers.compact(1_000), "1K");
+        assert_eq!(numbers.compact(1_234), "1.2K");
+        assert_eq!(numbers.compact(123_456), "123.5K");
+        assert_eq!(numbers.compact(1_234_567), "1.2M");
+        assert_eq!(numbers.compact(98_370_345), "98.4M");
+        assert_eq!(numbers.compact(-1_500), "-1.5K");
+    }
+
+    #[test]
+    fn test_size() {
+        let numbers = NumberFormat::default();
+        assert_eq!(numbers.size(0), "0 bytes");
+        assert_eq!(numbers.size(8_192), "8 kB");
+        assert_eq!(numbers.size(1_300_000), "1.2 MB");
+        assert_eq!(numbers.size(870_318_080), "830 MB");
+        assert_eq!(numbers.size(5 * 1024_i64.pow(4)), "5 TB");
+    }
+
+    #[test]
+    fn test_locales() {
+        let de = NumberFormat::parse("de_DE.UTF-8").unwrap();
+        assert_eq!(de.count(1_234_567), "1.234.567");
+        assert_eq!(de.compact(1_234_567), "1,2M");
+        assert_eq!(de.size(1_300_000), "1,2 MB");
+        assert_eq!(de.decimal("-3.25"), "-3,25");
+        assert_eq!(de.decimal("42"), "42");
+
+        let fr = NumberFormat::parse("fr").unwrap();
+        assert_eq!(fr.count(4_523), "4\u{a0}523");
+        assert_eq!(NumberFormat::parse("de-CH").unwrap().count(4_523), "4'523");
+        assert_eq!(NumberFormat::parse("C").unwrap(), NumberFormat::default());
+        assert_eq!(NumberFormat::parse("").unwrap(), NumberFormat::default());
+        assert!(NumberFormat::parse("klingon").is_err());
+    }
+}
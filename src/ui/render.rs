@@ -5,22 +5,51 @@
 use crate::app::{App, PanelFocus, StatusLevel, TransactionState};
 use crate::keymap::KeyAction;
 use crate::ui::Component;
-use crate::ui::layout::calculate_layout;
+use crate::ui::dashboard::DASHBOARD_INTERVAL;
+use crate::ui::jobs::format_elapsed;
+use crate::ui::layout::{MainPanel, calculate_layout};
+use crate::ui::numbers::NumberFormat;
 use crate::ui::theme::Theme;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use std::time::Duration;
+
+/// How long a tab's query runs before the tab bar colors it as slow, then
+/// as stuck
+const SLOW_TAB_AFTER: Duration = Duration::from_secs(10);
+const STUCK_TAB_AFTER: Duration = Duration::from_secs(60);
 
 /// Render the entire application
 pub fn render(frame: &mut Frame, app: &App) {
     let theme = &app.theme;
-    let layout = calculate_layout(frame.area(), true);
+    let layout = calculate_layout(frame.area(), true, shown_panel(app));
+    // On a small screen only one panel shows; its title says how to switch
+    let title = |title: &str| {
+        if layout.compact {
+            format!(
+                " {} \u{2014} {} next panel ",
+                title.trim(),
+                key_hint(&app.keymap, None, KeyAction::CycleFocus)
+            )
+        } else {
+            title.to_string()
+        }
+    };
 
     // Tree browser
+    let tree_title = if app.tree_browser.is_stale() {
+        " Schema (stale) "
+    } else {
+        " Schema "
+    };
     render_panel(
         frame,
         theme,
         layout.tree,
-        " Schema ",
+        &title(tree_title),
+        app.tree_browser
+            .position()
+            .map(|p| position_label("", p, &app.number_format)),
         app.focus == PanelFocus::TreeBrowser,
         |f, inner| {
             app.tree_browser
@@ -36,26 +65,51 @@ pub fn render(frame: &mut Frame, app: &App) {
         frame,
         theme,
         layout.editor,
-        " Query ",
+        &title(" Query "),
+        Some(position_label(
+            "line ",
+            app.tab().editor.position(),
+            &app.number_format,
+        )),
         app.focus == PanelFocus::QueryEditor,
         |f, inner| {
-            app.tab()
-                .editor
-                .render(f, inner, app.focus == PanelFocus::QueryEditor, theme);
+            let editor = &app.tab().editor;
+            editor.render(f, inner, app.focus == PanelFocus::QueryEditor, theme);
+            if app.editor_ruler > 0 {
+                editor.render_ruler(f, inner, app.editor_ruler, theme);
+            }
         },
     );
 
     // Results or EXPLAIN viewer (active tab)
-    let results_title = if app.tab().explain_viewer.is_some() {
-        " Explain "
-    } else {
-        " Results "
+    let (results_title, results_position) = match app.tab().explain_viewer {
+        Some(ref ev) => (
+            " Explain ".to_string(),
+            ev.position()
+                .map(|p| position_label("", p, &app.number_format)),
+        ),
+        None => (
+            match (&app.tab().tail, &app.tab().snapshot) {
+                (Some(tail), _) => format!(" Results \u{2014} tail {} ", tail.relation),
+                (None, Some(snapshot)) => format!(
+                    " Results \u{2014} snapshot of {} at {} ",
+                    snapshot.relation,
+                    snapshot.taken_at.format("%H:%M:%S")
+                ),
+                (None, None) => " Results ".to_string(),
+            },
+            app.tab()
+                .results_viewer
+                .position()
+                .map(|p| position_label("row ", p, &app.number_format)),
+        ),
     };
     render_panel(
         frame,
         theme,
         layout.results,
-        results_title,
+        &title(&results_title),
+        results_position,
         app.focus == PanelFocus::ResultsViewer,
         |f, inner| {
             if let Some(ref ev) = app.tab().explain_viewer {
@@ -81,6 +135,61 @@ pub fn render(frame: &mut Frame, app: &App) {
         render_help_popup(frame, theme, app);
     }
 
+    // Message log overlay
+    if app.messages.is_visible() {
+        render_messages_popup(frame, theme, app);
+    }
+
+    // Background jobs overlay
+    if app.jobs.is_visible() {
+        render_jobs_popup(frame, theme, app);
+    }
+
+    // Column value frequencies
+    if app.histogram.is_visible() {
+        render_histogram_popup(frame, theme, app);
+    }
+
+    // Columns of the table under the editor cursor
+    if app.table_preview.is_visible() {
+        render_table_preview_popup(frame, theme, app);
+    }
+
+    // LISTEN/NOTIFY notifications
+    if app.notifications.is_visible() {
+        render_notifications_popup(frame, theme, app);
+    }
+
+    // Server sessions from pg_stat_activity
+    if app.sessions.is_visible() {
+        render_sessions_popup(frame, theme, app);
+    }
+
+    // Server health from the pg_stat views
+    if app.dashboard.is_visible() {
+        render_dashboard_popup(frame, theme, app);
+    }
+
+    // Publications and subscriptions
+    if app.replication.is_visible() {
+        render_replication_popup(frame, theme, app);
+    }
+
+    // First-glance checks after connecting
+    if app.server_summary.is_visible() {
+        render_server_summary_popup(frame, theme, app);
+    }
+
+    // Insert-row form
+    if app.insert_form.is_visible() {
+        render_insert_form_popup(frame, theme, app);
+    }
+
+    // Query history browser
+    if app.history_browser.is_visible() {
+        render_history_browser_popup(frame, theme, app);
+    }
+
     // Connection dialog (on top of everything)
     if app.connection_dialog.is_visible() {
         render_connection_dialog_popup(frame, theme, app);
@@ -103,36 +212,73 @@ fn render_tab_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
         }
 
         let mut label = format!(" Tab {}", i + 1);
+        if let Some(name) = tab.file.as_deref().and_then(|f| f.file_name()) {
+            label.push_str(&format!(" {}", name.to_string_lossy()));
+        }
+        if let Some(conn) = &tab.connection {
+            label.push_str(&format!(" @{}", conn.name));
+        }
+        let running = tab
+            .query_start
+            .filter(|_| tab.query_running)
+            .map(|start| start.elapsed());
         if tab.query_running {
             label.push('*');
         }
+        if let Some(elapsed) = running {
+            label.push_str(&format!(" {}", format_elapsed(elapsed)));
+        }
+        if tab.pinned {
+            label.push_str(" [PIN]");
+        }
         match tab.transaction_state {
+            TransactionState::InTransaction if tab.snapshot.is_some() => label.push_str(" [SNAP]"),
             TransactionState::InTransaction => label.push_str(" [TXN]"),
             TransactionState::Failed => label.push_str(" [TXN!]"),
             TransactionState::Idle => {}
         }
         label.push(' ');
 
-        let style = if i == app.active_tab {
-            theme.tab_active
-        } else {
-            theme.tab_inactive
-        };
+        let style = tab_style(i == app.active_tab, running, theme);
         spans.push(Span::styled(label, style));
     }
 
     frame.render_widget(Paragraph::new(Line::from(spans)), area);
 }
 
+/// A tab's style, colored once its query has run long enough to look stuck.
+/// The active tab takes the color as its background.
+fn tab_style(active: bool, running: Option<Duration>, theme: &Theme) -> Style {
+    let base = if active {
+        theme.tab_active
+    } else {
+        theme.tab_inactive
+    };
+    let band = match running {
+        Some(elapsed) if elapsed >= STUCK_TAB_AFTER => theme.tab_stuck,
+        Some(elapsed) if elapsed >= SLOW_TAB_AFTER => theme.tab_slow,
+        _ => return base,
+    };
+    match (active, band.fg) {
+        (true, Some(color)) => base.bg(color),
+        _ => base.patch(band),
+    }
+}
+
 /// Render a panel with consistent focus indication
 fn render_panel(
     frame: &mut Frame,
     theme: &Theme,
     area: Rect,
     title: &str,
+    position: Option<String>,
     focused: bool,
     render_inner: impl FnOnce(&mut Frame, Rect),
 ) {
+    // Hidden by the compact layout
+    if area.is_empty() {
+        return;
+    }
     let title = if focused {
         format!(" \u{25b8} {}", title.trim())
     } else {
@@ -145,37 +291,25 @@ fn render_panel(
         theme.panel_title_unfocused
     };
 
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .title(Span::styled(format!(" {} ", title.trim()), title_style))
         .border_style(theme.border_style(focused));
+    if let Some(position) = position {
+        block = block.title_bottom(Line::from(position).right_aligned());
+    }
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
     render_inner(frame, inner);
 }
 
-/// Render the inspector as a centered floating popup with shadow.
-/// Popup sizes itself to fit the content, clamped to min/max bounds.
-fn render_inspector_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+/// Draw the shadow of a `width` x `height` popup centered on the screen
+/// (shrunk to fit) and clear the popup, returning its area.
+fn popup_area(frame: &mut Frame, width: u16, height: u16, theme: &Theme) -> Rect {
     let screen = frame.area();
-    let (content_w, content_h) = app.inspector.content_size();
-
-    // +2 for borders on each side, +1 for header line inside the popup
-    let desired_w = content_w + 4;
-    let desired_h = content_h + 4;
-
-    let min_w: u16 = 42;
-    let min_h: u16 = 6;
-    let max_w = screen.width * 4 / 5;
-    let max_h = screen.height * 3 / 4;
-
-    let popup_w = desired_w
-        .clamp(min_w, max_w)
-        .min(screen.width.saturating_sub(2));
-    let popup_h = desired_h
-        .clamp(min_h, max_h)
-        .min(screen.height.saturating_sub(2));
+    let popup_w = width.min(screen.width.saturating_sub(2));
+    let popup_h = height.min(screen.height.saturating_sub(2));
     let popup_x = (screen.width.saturating_sub(popup_w)) / 2;
     let popup_y = (screen.height.saturating_sub(popup_h)) / 2;
     let popup_area = Rect::new(popup_x, popup_y, popup_w, popup_h);
@@ -199,8 +333,44 @@ fn render_inspector_popup(frame: &mut Frame, theme: &Theme, app: &App) {
         }
     }
 
-    // Clear the popup area
     frame.render_widget(Clear, popup_area);
+    popup_area
+}
+
+/// Draw a centered popup with shadow and a border titled `title`,
+/// returning the area inside the border.
+fn popup_frame(frame: &mut Frame, width: u16, height: u16, title: &str, theme: &Theme) -> Rect {
+    let popup_area = popup_area(frame, width, height, theme);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(title, theme.popup_title))
+        .border_style(theme.popup_border);
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+    inner
+}
+
+/// Render the inspector as a centered floating popup with shadow.
+/// Popup sizes itself to fit the content, clamped to min/max bounds.
+fn render_inspector_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let screen = frame.area();
+    let (content_w, content_h) = app.inspector.content_size();
+
+    // +2 for borders on each side, +1 for header line inside the popup
+    let desired_w = content_w + 4;
+    let desired_h = content_h + 4;
+
+    let min_w: u16 = 42;
+    let min_h: u16 = 6;
+    let max_w = screen.width * 4 / 5;
+    let max_h = screen.height * 3 / 4;
+
+    let popup_area = popup_area(
+        frame,
+        desired_w.clamp(min_w, max_w),
+        desired_h.clamp(min_h, max_h),
+        theme,
+    );
 
     let dismiss_key = key_hint(&app.keymap, Some(PanelFocus::Inspector), KeyAction::Dismiss);
     let copy_key = key_hint(
@@ -212,10 +382,15 @@ fn render_inspector_popup(frame: &mut Frame, theme: &Theme, app: &App) {
         " Inspector \u{2014} {} to close, {} to copy ",
         dismiss_key, copy_key
     );
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .title(Span::styled(title, theme.popup_title))
         .border_style(theme.popup_border);
+    if let Some(position) = app.inspector.position() {
+        block = block.title_bottom(
+            Line::from(position_label("line ", position, &app.number_format)).right_aligned(),
+        );
+    }
 
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -225,89 +400,180 @@ fn render_inspector_popup(frame: &mut Frame, theme: &Theme, app: &App) {
 
 /// Render the help overlay as a centered floating popup with shadow.
 fn render_help_popup(frame: &mut Frame, theme: &Theme, app: &App) {
-    let screen = frame.area();
+    let dismiss_key = key_hint(&app.keymap, Some(PanelFocus::Help), KeyAction::Dismiss);
+    let title = format!(" Help \u{2014} {} to close ", dismiss_key);
+    let inner = popup_frame(frame, 60, 28, &title, theme);
+    app.help.render(frame, inner, theme, &app.keymap);
+}
 
-    let popup_w: u16 = 60.min(screen.width.saturating_sub(2));
-    let popup_h: u16 = 28.min(screen.height.saturating_sub(2));
-    let popup_x = (screen.width.saturating_sub(popup_w)) / 2;
-    let popup_y = (screen.height.saturating_sub(popup_h)) / 2;
-    let popup_area = Rect::new(popup_x, popup_y, popup_w, popup_h);
+/// Render the status message log as a centered floating popup with shadow.
+fn render_messages_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let dismiss_key = key_hint(&app.keymap, Some(PanelFocus::Messages), KeyAction::Dismiss);
+    let title = format!(
+        " Messages ({}) \u{2014} {} to close ",
+        app.messages.len(),
+        dismiss_key
+    );
+    let inner = popup_frame(frame, 90, 20, &title, theme);
+    app.messages.render(frame, inner, theme);
+}
 
-    // Shadow (1 cell right and down)
-    let shadow_area = Rect::new(
-        (popup_x + 1).min(screen.width.saturating_sub(1)),
-        (popup_y + 1).min(screen.height.saturating_sub(1)),
-        popup_w.min(screen.width.saturating_sub(popup_x + 1)),
-        popup_h.min(screen.height.saturating_sub(popup_y + 1)),
+/// Render the server's sessions as a centered floating popup with shadow.
+fn render_sessions_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let hint = |action| key_hint(&app.keymap, Some(PanelFocus::Sessions), action);
+    let title = format!(
+        " Sessions ({}) \u{2014} {} refresh \u{2014} {} cancel \u{2014} {} terminate \u{2014} {} to close ",
+        app.sessions.len(),
+        hint(KeyAction::RefreshSessions),
+        hint(KeyAction::CancelSession),
+        hint(KeyAction::TerminateSession),
+        hint(KeyAction::Dismiss)
     );
-    let shadow_style = theme.shadow;
-    for y in shadow_area.y..shadow_area.y + shadow_area.height {
-        for x in shadow_area.x..shadow_area.x + shadow_area.width {
-            if x < screen.width && y < screen.height {
-                frame.render_widget(
-                    Paragraph::new(" ").style(shadow_style),
-                    Rect::new(x, y, 1, 1),
-                );
-            }
-        }
-    }
+    let inner = popup_frame(frame, 120, 24, &title, theme);
+    app.sessions.render(frame, inner, theme);
+}
 
-    // Clear and draw border
-    frame.render_widget(Clear, popup_area);
+/// Render the health dashboard as a centered floating popup with shadow.
+fn render_dashboard_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let hint = |action| key_hint(&app.keymap, Some(PanelFocus::Dashboard), action);
+    let title = format!(
+        " Server health \u{2014} every {}s \u{2014} {} refresh \u{2014} {} to close ",
+        DASHBOARD_INTERVAL.as_secs(),
+        hint(KeyAction::RefreshDashboard),
+        hint(KeyAction::Dismiss)
+    );
+    let inner = popup_frame(frame, 110, 28, &title, theme);
+    app.dashboard.render(frame, inner, theme);
+}
 
-    let dismiss_key = key_hint(&app.keymap, Some(PanelFocus::Help), KeyAction::Dismiss);
-    let title = format!(" Help \u{2014} {} to close ", dismiss_key);
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title(Span::styled(title, theme.popup_title))
-        .border_style(theme.popup_border);
+/// Render the publications and subscriptions as a centered floating popup
+/// with shadow.
+fn render_replication_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let hint = |action| key_hint(&app.keymap, Some(PanelFocus::Replication), action);
+    let title = format!(
+        " Logical replication \u{2014} {} refresh \u{2014} {} to close ",
+        hint(KeyAction::RefreshReplication),
+        hint(KeyAction::Dismiss)
+    );
+    let inner = popup_frame(frame, 110, 28, &title, theme);
+    app.replication.render(frame, inner, theme);
+}
 
-    let inner = block.inner(popup_area);
-    frame.render_widget(block, popup_area);
-    app.help.render(frame, inner, theme, &app.keymap);
+/// Render the post-connect server summary as a centered floating popup with
+/// shadow.
+fn render_server_summary_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let title = format!(
+        " {} \u{2014} {} to close ",
+        app.connection_name.as_deref().unwrap_or("Server"),
+        key_hint(
+            &app.keymap,
+            Some(PanelFocus::ServerSummary),
+            KeyAction::Dismiss
+        )
+    );
+    let inner = popup_frame(frame, 90, 7, &title, theme);
+    app.server_summary.render(frame, inner, theme);
 }
 
-/// Render the connection dialog as a centered floating popup with shadow.
-fn render_connection_dialog_popup(frame: &mut Frame, theme: &Theme, app: &App) {
-    let screen = frame.area();
+/// Render the insert-row form as a centered floating popup with shadow.
+fn render_insert_form_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let title = format!(" Insert into {} ", app.insert_form.target());
+    let inner = popup_frame(frame, 90, 20, &title, theme);
+    app.insert_form.render(frame, inner, theme);
+}
 
-    let popup_w: u16 = 60.min(screen.width.saturating_sub(2));
-    let popup_h: u16 = 19.min(screen.height.saturating_sub(2));
-    let popup_x = (screen.width.saturating_sub(popup_w)) / 2;
-    let popup_y = (screen.height.saturating_sub(popup_h)) / 2;
-    let popup_area = Rect::new(popup_x, popup_y, popup_w, popup_h);
+/// Render the received notifications as a centered floating popup with shadow.
+fn render_notifications_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let dismiss_key = key_hint(
+        &app.keymap,
+        Some(PanelFocus::Notifications),
+        KeyAction::Dismiss,
+    );
+    let channels = app.notifications.channels();
+    let listening = if channels.is_empty() {
+        "not listening".to_string()
+    } else {
+        format!("listening on {}", channels.join(", "))
+    };
+    let title = format!(
+        " Notifications ({}) \u{2014} {} \u{2014} {} to close ",
+        app.notifications.len(),
+        listening,
+        dismiss_key
+    );
+    let inner = popup_frame(frame, 90, 20, &title, theme);
+    app.notifications.render(frame, inner, theme);
+}
 
-    // Shadow (1 cell right and down)
-    let shadow_area = Rect::new(
-        (popup_x + 1).min(screen.width.saturating_sub(1)),
-        (popup_y + 1).min(screen.height.saturating_sub(1)),
-        popup_w.min(screen.width.saturating_sub(popup_x + 1)),
-        popup_h.min(screen.height.saturating_sub(popup_y + 1)),
+/// Render the background job list as a centered floating popup with shadow.
+fn render_jobs_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let dismiss_key = key_hint(&app.keymap, Some(PanelFocus::Jobs), KeyAction::Dismiss);
+    let title = format!(
+        " Jobs ({}) \u{2014} {} to close ",
+        app.jobs.len(),
+        dismiss_key
     );
-    let shadow_style = theme.shadow;
-    for y in shadow_area.y..shadow_area.y + shadow_area.height {
-        for x in shadow_area.x..shadow_area.x + shadow_area.width {
-            if x < screen.width && y < screen.height {
-                frame.render_widget(
-                    Paragraph::new(" ").style(shadow_style),
-                    Rect::new(x, y, 1, 1),
-                );
-            }
-        }
-    }
+    let inner = popup_frame(frame, 90, 12, &title, theme);
+    app.jobs.render(frame, inner, theme);
+}
 
-    // Clear and draw border
-    frame.render_widget(Clear, popup_area);
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title(Span::styled(
-            " Connect \u{2014} Enter to connect, Esc to cancel ",
-            theme.popup_title,
-        ))
-        .border_style(theme.popup_border);
+/// Render a column's value frequencies as a centered floating popup with shadow.
+fn render_histogram_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let dismiss_key = key_hint(&app.keymap, Some(PanelFocus::Histogram), KeyAction::Dismiss);
+    let title = format!(
+        " {} \u{2014} {} rows, {} distinct \u{2014} {} to close ",
+        app.histogram.column(),
+        app.number_format.count(app.histogram.rows()),
+        app.number_format.count(app.histogram.distinct()),
+        dismiss_key
+    );
+    let inner = popup_frame(frame, 90, 20, &title, theme);
+    app.histogram.render(frame, inner, theme);
+}
 
-    let inner = block.inner(popup_area);
-    frame.render_widget(block, popup_area);
+/// Render the table preview as a centered floating popup with shadow.
+fn render_table_preview_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let rows = app.table_preview.columns().len().max(1) as u16;
+    let dismiss_key = key_hint(
+        &app.keymap,
+        Some(PanelFocus::TablePreview),
+        KeyAction::Dismiss,
+    );
+    let title = format!(
+        " {}{} \u{2014} {} columns \u{2014} {} to close ",
+        app.table_preview.name(),
+        if app.table_preview.is_view() {
+            " (view)"
+        } else {
+            ""
+        },
+        app.table_preview.columns().len(),
+        dismiss_key
+    );
+    let inner = popup_frame(frame, 80, (rows + 2).min(20), &title, theme);
+    app.table_preview.render(frame, inner, theme);
+}
+
+/// Render the query history browser as a centered floating popup with shadow.
+fn render_history_browser_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let title = format!(
+        " History ({}/{}) \u{2014} Enter to load, Alt+Enter to run in a new tab, Esc to cancel ",
+        app.history_browser.match_count(),
+        app.history_browser.entry_count()
+    );
+    let inner = popup_frame(frame, 110, 24, &title, theme);
+    app.history_browser.render(frame, inner, theme);
+}
+
+/// Render the connection dialog as a centered floating popup with shadow.
+fn render_connection_dialog_popup(frame: &mut Frame, theme: &Theme, app: &App) {
+    let inner = popup_frame(
+        frame,
+        60,
+        19,
+        " Connect \u{2014} Enter to connect, Esc to cancel ",
+        theme,
+    );
     app.connection_dialog.render(frame, inner, theme);
 }
 
@@ -328,27 +594,65 @@ fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
         TransactionState::Failed => Some((" TXN FAILED ", theme.status_txn_failed)),
     };
 
-    let ro_badge: Option<&str> = if app.read_only { Some(" RO ") } else { None };
+    // A read-only tab inside BEGIN READ WRITE can write until it ends
+    let ro_badge: Option<&str> = if app.tab_writes_blocked() {
+        Some(" RO ")
+    } else if app.tab_read_only() {
+        Some(" RW ")
+    } else {
+        None
+    };
+
+    // Row filter on the active tab's results: " N of M rows " (skipped on
+    // narrow terminals where it would push out the connection info)
+    let filter_badge = app
+        .tab()
+        .results_viewer
+        .filter_counts()
+        .filter(|_| area.width >= 60)
+        .map(|(visible, total)| format!(" {} of {} rows ", visible, total));
 
     let (conn_dot, conn_dot_style) = if app.connection_name.is_some() {
         ("\u{25cf} ", Style::default().fg(Color::Green))
     } else {
         ("\u{25cf} ", Style::default().fg(Color::Red))
     };
-    let conn_label = if let Some(ref name) = app.connection_name {
+    // A tab bound to its own connection shows that one
+    let conn_name = match &app.tab().connection {
+        Some(conn) => Some(&conn.name),
+        None => app.connection_name.as_ref(),
+    };
+    let conn_label = if let Some(name) = conn_name {
         format!("[{}]", name)
     } else {
         "[disconnected]".to_string()
     };
 
+    // Vim mode of the active tab's editor
+    let mode_badge = app
+        .tab()
+        .editor
+        .vim_mode()
+        .map(|mode| format!(" {} ", mode.label()));
+
     // Calculate total right-side width (badges + dot + label)
     let dot_len = 2u16; // "● " = 2 cols
     let txn_len = txn_badge
         .as_ref()
         .map_or(0, |(s, _)| super::unicode::display_width(s) as u16);
     let ro_len = ro_badge.map_or(0, |s| super::unicode::display_width(s) as u16);
+    let filter_len = filter_badge
+        .as_deref()
+        .map_or(0, |s| super::unicode::display_width(s) as u16);
+    let mode_len = mode_badge
+        .as_deref()
+        .map_or(0, |s| super::unicode::display_width(s) as u16);
     let badge_spacer = |len: u16| if len > 0 { 1u16 } else { 0 };
-    let right_total = ro_len
+    let right_total = mode_len
+        + badge_spacer(mode_len)
+        + filter_len
+        + badge_spacer(filter_len)
+        + ro_len
         + badge_spacer(ro_len)
         + txn_len
         + badge_spacer(txn_len)
@@ -356,8 +660,23 @@ fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
         + super::unicode::display_width(&conn_label) as u16;
     let right_x = area.x + area.width.saturating_sub(right_total);
 
-    // Render RO badge, TXN badge, then dot + connection info
+    // Render mode badge, filter badge, RO badge, TXN badge, then dot +
+    // connection info
     let mut cursor_x = right_x;
+    if let Some(mode_text) = mode_badge {
+        frame.render_widget(
+            Paragraph::new(mode_text).style(theme.status_vim_mode),
+            Rect::new(cursor_x, area.y, mode_len.min(area.width), 1),
+        );
+        cursor_x += mode_len + badge_spacer(mode_len);
+    }
+    if let Some(filter_text) = filter_badge {
+        frame.render_widget(
+            Paragraph::new(filter_text).style(theme.status_filter),
+            Rect::new(cursor_x, area.y, filter_len.min(area.width), 1),
+        );
+        cursor_x += filter_len + badge_spacer(filter_len);
+    }
     if let Some(ro_text) = ro_badge {
         frame.render_widget(
             Paragraph::new(ro_text).style(theme.status_read_only),
@@ -403,7 +722,7 @@ fn render_status_bar(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
         let msg = if let Some(rows) = active_tab.rows_streaming {
             format!(
                 "Streaming... {:>} rows ({:.1}s) - {} to cancel",
-                format_row_count(rows),
+                app.number_format.count(rows),
                 elapsed.as_secs_f64(),
                 cancel_key
             )
@@ -473,20 +792,32 @@ fn key_hint(
         .unwrap_or_else(|| "(unset)".to_string())
 }
 
-/// Format a row count with thousands separators (e.g., 4523 → "4,523")
-fn format_row_count(n: usize) -> String {
-    if n < 1_000 {
-        return n.to_string();
-    }
-    let s = n.to_string();
-    let mut result = String::with_capacity(s.len() + s.len() / 3);
-    for (i, ch) in s.chars().enumerate() {
-        if i > 0 && (s.len() - i).is_multiple_of(3) {
-            result.push(',');
-        }
-        result.push(ch);
-    }
-    result
+/// The main panel the compact layout shows: the focused one, or the one
+/// an overlay was opened over
+fn shown_panel(app: &App) -> MainPanel {
+    [app.focus, app.previous_focus]
+        .into_iter()
+        .find_map(|focus| match focus {
+            PanelFocus::TreeBrowser => Some(MainPanel::Tree),
+            PanelFocus::QueryEditor => Some(MainPanel::Editor),
+            PanelFocus::ResultsViewer => Some(MainPanel::Results),
+            _ => None,
+        })
+        .unwrap_or(MainPanel::Editor)
+}
+
+/// Bottom-border position indicator, e.g. " row 1,204/50,000 "
+fn position_label(
+    prefix: &str,
+    (current, total): (usize, usize),
+    numbers: &NumberFormat,
+) -> String {
+    format!(
+        " {}{}/{} ",
+        prefix,
+        numbers.count(current),
+        numbers.count(total)
+    )
 }
 
 #[cfg(test)]
@@ -494,22 +825,30 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_format_row_count_small() {
-        assert_eq!(format_row_count(0), "0");
-        assert_eq!(format_row_count(1), "1");
-        assert_eq!(format_row_count(999), "999");
-    }
-
-    #[test]
-    fn test_format_row_count_thousands() {
-        assert_eq!(format_row_count(1_000), "1,000");
-        assert_eq!(format_row_count(4_523), "4,523");
-        assert_eq!(format_row_count(999_999), "999,999");
+    fn test_tab_style_bands() {
+        let theme = Theme::default();
+        let secs = |s| Some(Duration::from_secs(s));
+        assert_eq!(tab_style(false, None, &theme), theme.tab_inactive);
+        assert_eq!(tab_style(false, secs(9), &theme), theme.tab_inactive);
+        assert_eq!(
+            tab_style(false, secs(10), &theme),
+            theme.tab_inactive.patch(theme.tab_slow)
+        );
+        assert_eq!(
+            tab_style(true, secs(75), &theme),
+            theme.tab_active.bg(theme.tab_stuck.fg.unwrap())
+        );
     }
 
     #[test]
-    fn test_format_row_count_millions() {
-        assert_eq!(format_row_count(1_000_000), "1,000,000");
-        assert_eq!(format_row_count(12_345_678), "12,345,678");
+    fn test_position_label() {
+        assert_eq!(
+            position_label("row ", (1_204, 50_000), &NumberFormat::default()),
+            " row 1,204/50,000 "
+        );
+        assert_eq!(
+            position_label("", (3, 12), &NumberFormat::default()),
+            " 3/12 "
+        );
     }
 }
@@ -0,0 +1,313 @@
+//! Logical replication overlay
+//!
+//! `/replication` shows the database's publications with the tables they
+//! publish, and its subscriptions with their apply worker's status. Follows
+//! the same overlay pattern as Dashboard, refreshed on request.
+
+use crate::db::Replication;
+use crate::db::replication::{Publication, Subscription};
+use crate::ui::jobs::format_elapsed;
+use crate::ui::theme::Theme;
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+/// Width of the name column
+const NAME_WIDTH: usize = 20;
+
+/// Publications and subscriptions of the current database
+pub struct ReplicationView {
+    replication: Option<Replication>,
+    /// Why the last load failed
+    error: Option<String>,
+    scroll: usize,
+    visible: bool,
+}
+
+impl ReplicationView {
+    pub fn new() -> Self {
+        Self {
+            replication: None,
+            error: None,
+            scroll: 0,
+            visible: false,
+        }
+    }
+
+    pub fn set_replication(&mut self, replication: Replication) {
+        self.replication = Some(replication);
+        self.error = None;
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    /// Show why loading failed, keeping what was shown before
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+        self.scroll = 0;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll = (self.scroll + 1).min(self.max_scroll());
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(10);
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll = (self.scroll + 10).min(self.max_scroll());
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines().len().saturating_sub(1)
+    }
+
+    /// The overlay as `(name, text)` lines; section headings have an empty
+    /// text
+    fn lines(&self) -> Vec<(String, String)> {
+        let mut lines = Vec::new();
+        if let Some(ref error) = self.error {
+            lines.push(("Error".to_string(), error.clone()));
+        }
+        let Some(ref replication) = self.replication else {
+            return lines;
+        };
+
+        lines.push(("Publications".to_string(), String::new()));
+        if replication.publications.is_empty() {
+            lines.push((String::new(), "none in this database".to_string()));
+        }
+        for publication in &replication.publications {
+            lines.push((publication.name.clone(), publication_line(publication)));
+            lines.extend(
+                publication
+                    .tables
+                    .iter()
+                    .map(|table| (String::new(), format!("  {}", table))),
+            );
+        }
+
+        lines.push((String::new(), String::new()));
+        lines.push(("Subscriptions".to_string(), String::new()));
+        if replication.subscriptions.is_empty() {
+            lines.push((String::new(), "none in this database".to_string()));
+        }
+        lines.extend(
+            replication
+                .subscriptions
+                .iter()
+                .map(|s| (s.name.clone(), subscription_line(s))),
+        );
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if area.height == 0 {
+            return;
+        }
+
+        if self.replication.is_none() && self.error.is_none() {
+            frame.render_widget(
+                Paragraph::new("Loading publications and subscriptions...").style(theme.help_desc),
+                area,
+            );
+            return;
+        }
+
+        let lines: Vec<Line> = self
+            .lines()
+            .into_iter()
+            .skip(self.scroll)
+            .take(area.height as usize)
+            .map(|(name, text)| {
+                if text.is_empty() {
+                    return Line::from(Span::styled(name, theme.results_header));
+                }
+                let style = if name == "Error" {
+                    theme.status_error
+                } else {
+                    theme.help_desc
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:<NAME_WIDTH$} ", name), theme.help_key),
+                    Span::styled(text, style),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+impl Default for ReplicationView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `all tables; insert, update; owner app`, with the table count when
+/// the publication lists its tables
+fn publication_line(publication: &Publication) -> String {
+    let tables = if publication.all_tables {
+        "all tables".to_string()
+    } else {
+        match publication.tables.len() {
+            1 => "1 table".to_string(),
+            n => format!("{} tables", n),
+        }
+    };
+    let operations = if publication.operations.is_empty() {
+        "no operations".to_string()
+    } else {
+        publication.operations.join(", ")
+    };
+    let mut line = format!("{}; {}", tables, operations);
+    if let Some(ref owner) = publication.owner {
+        line.push_str(&format!("; owner {}", owner));
+    }
+    line
+}
+
+/// The apply worker's state, then what it subscribes to
+fn subscription_line(subscription: &Subscription) -> String {
+    let mut line = match (subscription.enabled, subscription.worker_pid) {
+        (false, _) => "disabled".to_string(),
+        (true, None) => "enabled, no worker running".to_string(),
+        (true, Some(pid)) => {
+            let mut status = format!("streaming (pid {})", pid);
+            if let Some(ref lsn) = subscription.received_lsn {
+                status.push_str(&format!(", received {}", lsn));
+            }
+            if let Some(age) = subscription.last_message {
+                status.push_str(&format!(", last message {} ago", format_elapsed(age)));
+            }
+            status
+        }
+    };
+    if subscription.syncing_tables > 0 {
+        line.push_str(&format!(
+            "; {} table(s) still copying",
+            subscription.syncing_tables
+        ));
+    }
+    line.push_str(&format!("; from {}", subscription.publications.join(", ")));
+    if let Some(ref owner) = subscription.owner {
+        line.push_str(&format!("; owner {}", owner));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn replication() -> Replication {
+        Replication {
+            publications: vec![
+                Publication {
+                    name: "everything".to_string(),
+                    owner: Some("postgres".to_string()),
+                    all_tables: true,
+                    operations: Publication::operations([true; 4]),
+                    tables: Vec::new(),
+                },
+                Publication {
+                    name: "orders_pub".to_string(),
+                    owner: None,
+                    all_tables: false,
+                    operations: Publication::operations([true, true, false, false]),
+                    tables: vec!["public.orders".to_string(), "public.items".to_string()],
+                },
+            ],
+            subscriptions: vec![
+                Subscription {
+                    name: "from_primary".to_string(),
+                    owner: Some("app".to_string()),
+                    enabled: true,
+                    publications: vec!["orders_pub".to_string()],
+                    worker_pid: Some(4242),
+                    received_lsn: Some("0/16B3748".to_string()),
+                    last_message: Some(Duration::from_secs(3)),
+                    syncing_tables: 1,
+                },
+                Subscription {
+                    name: "paused".to_string(),
+                    enabled: false,
+                    publications: vec!["a".to_string(), "b".to_string()],
+                    ..Subscription::default()
+                },
+            ],
+        }
+    }
+
+    fn text(view: &ReplicationView, name: &str) -> String {
+        view.lines()
+            .into_iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, text)| text)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_lines() {
+        let mut view = ReplicationView::new();
+        view.set_replication(replication());
+        assert_eq!(
+            text(&view, "everything"),
+            "all tables; insert, update, delete, truncate; owner postgres"
+        );
+        assert_eq!(text(&view, "orders_pub"), "2 tables; insert, update");
+        assert!(
+            view.lines()
+                .iter()
+                .any(|(n, t)| n.is_empty() && t == "  public.items")
+        );
+        assert_eq!(
+            text(&view, "from_primary"),
+            "streaming (pid 4242), received 0/16B3748, last message 0:03 ago; \
+             1 table(s) still copying; from orders_pub; owner app"
+        );
+        assert_eq!(text(&view, "paused"), "disabled; from a, b");
+    }
+
+    #[test]
+    fn test_empty_and_error() {
+        let mut view = ReplicationView::new();
+        assert!(view.lines().is_empty());
+        view.set_replication(Replication::default());
+        let empty = view
+            .lines()
+            .iter()
+            .filter(|(_, t)| t == "none in this database")
+            .count();
+        assert_eq!(empty, 2);
+
+        // A failed refresh keeps what was shown
+        view.set_error("permission denied".to_string());
+        assert_eq!(text(&view, "Error"), "permission denied");
+        assert!(view.lines().iter().any(|(n, _)| n == "Subscriptions"));
+    }
+}
@@ -2,12 +2,18 @@
 //!
 //! Displays query results in a scrollable table with cell-level selection.
 
-use crate::db::types::{CellValue, QueryResults};
+use crate::db::provenance::CellUpdate;
+use crate::db::types::{CellValue, ColumnDef, DataType, QueryResults, Row};
+use crate::mask::mask_row;
 use crate::ui::Component;
+use crate::ui::numbers::NumberFormat;
 use crate::ui::theme::Theme;
+use crate::ui::tree::fuzzy_match;
 use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
-use std::cell::Cell;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 
 /// Display mode for query results
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,6 +24,143 @@ pub enum ViewMode {
     Vertical,
 }
 
+/// Direction of a client-side column sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// A cell edit sent to the database, waiting for the refreshed row
+#[derive(Debug, Clone)]
+struct PendingEdit {
+    /// Original position of the edited row
+    row_id: usize,
+    col: usize,
+    /// Result columns the UPDATE's RETURNING clause fills, in order
+    returning: Vec<usize>,
+}
+
+/// Fitted cell text for the rows last drawn, so frames that only move the
+/// selection or scroll a few rows don't re-format every visible cell
+#[derive(Debug, Default)]
+struct CellCache {
+    /// Layout the text was fitted for: (column index, drawn width) per
+    /// visible column
+    layout: Vec<(usize, u16)>,
+    /// Fitted text and truncation flag per visible column, by row index.
+    /// Only rows inside the last drawn window are kept.
+    rows: HashMap<usize, Vec<(String, bool)>>,
+}
+
+/// Compiled row filter predicate
+type RowPredicate<'a> = Box<dyn Fn(&Row) -> bool + 'a>;
+
+/// Client-side row filter over the loaded results
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowFilter {
+    /// Case-insensitive substring match against any cell
+    Contains(String),
+    /// `column = value` or `column != value`, case-insensitive on the cell
+    /// text (so `col = null` matches NULLs)
+    Compare {
+        column: String,
+        value: String,
+        negate: bool,
+    },
+}
+
+impl RowFilter {
+    /// Parse filter input. Returns None for blank input.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return None;
+        }
+        let (split, negate) = match input.find("!=") {
+            Some(pos) => (Some((pos, 2)), true),
+            None => (input.find('=').map(|pos| (pos, 1)), false),
+        };
+        if let Some((pos, len)) = split {
+            let column = input[..pos].trim();
+            if !column.is_empty() {
+                let value = strip_quotes(input[pos + len..].trim());
+                return Some(Self::Compare {
+                    column: column.to_string(),
+                    value: value.to_string(),
+                    negate,
+                });
+            }
+        }
+        Some(Self::Contains(input.to_string()))
+    }
+
+    /// Build a row predicate for the given columns. Fails if a comparison
+    /// names a column that isn't in the results.
+    fn matcher<'a>(&'a self, columns: &[ColumnDef]) -> Result<RowPredicate<'a>, String> {
+        match self {
+            Self::Contains(needle) => {
+                let needle = needle.to_lowercase();
+                Ok(Box::new(move |row: &Row| {
+                    row.values.iter().any(|cell| {
+                        cell.display_string(FILTER_TEXT_LIMIT)
+                            .to_lowercase()
+                            .contains(&needle)
+                    })
+                }))
+            }
+            Self::Compare {
+                column,
+                value,
+                negate,
+            } => {
+                let idx = columns
+                    .iter()
+                    .position(|c| c.name.eq_ignore_ascii_case(column))
+                    .ok_or_else(|| format!("Unknown column: {}", column))?;
+                let value = value.to_lowercase();
+                Ok(Box::new(move |row: &Row| {
+                    let equal = row.values.get(idx).is_some_and(|cell| {
+                        cell.display_string(FILTER_TEXT_LIMIT).to_lowercase() == value
+                    });
+                    equal != *negate
+                }))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for RowFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Contains(needle) => write!(f, "{}", needle),
+            Self::Compare {
+                column,
+                value,
+                negate,
+            } => write!(
+                f,
+                "{} {} {}",
+                column,
+                if *negate { "!=" } else { "=" },
+                value
+            ),
+        }
+    }
+}
+
+/// Strip one pair of matching single or double quotes
+fn strip_quotes(s: &str) -> &str {
+    for q in ['\'', '"'] {
+        if s.len() >= 2
+            && let Some(inner) = s.strip_prefix(q).and_then(|r| r.strip_suffix(q))
+        {
+            return inner;
+        }
+    }
+    s
+}
+
 /// Pagination display info passed from App to ResultsViewer
 #[derive(Debug, Clone)]
 pub struct PaginationInfo {
@@ -29,23 +172,58 @@ pub struct PaginationInfo {
     pub has_prev: bool,
 }
 
+/// How much of each cell's text row filters and searches look at
+const FILTER_TEXT_LIMIT: usize = 10000;
+
 /// Results table viewer
 pub struct ResultsViewer {
     results: Option<QueryResults>,
     selected_row: usize,
     selected_col: usize,
-    scroll_offset: usize,
-    h_scroll_offset: usize,
-    /// Computed column widths
+    /// First visible row and column, remembered by render so the viewport
+    /// only moves when the selection leaves it
+    scroll_offset: Cell<usize>,
+    h_scroll_offset: Cell<usize>,
+    /// Computed column widths (measured once per result set)
     col_widths: Vec<u16>,
     /// Last query error (shown in results area)
     error: Option<String>,
+    /// Guidance shown under the error (from its SQLSTATE)
+    error_hint: Option<String>,
     /// Current display mode
     view_mode: ViewMode,
     /// Pagination info for footer display
     pagination: Option<PaginationInfo>,
     /// Visible height for adaptive page jumps (updated during render)
     page_height: Cell<usize>,
+    /// Visible width for horizontal page jumps (updated during render)
+    page_width: Cell<u16>,
+    /// Column temporarily expanded to fit its content, with its prior width
+    expanded_col: Option<(usize, u16)>,
+    /// Active client-side sort (column index, direction)
+    sort: Option<(usize, SortOrder)>,
+    /// Active client-side row filter
+    filter: Option<RowFilter>,
+    /// Original position of each visible row while a sort or filter is active
+    row_ids: Option<Vec<usize>>,
+    /// Rows hidden by the filter, tagged with their original position
+    filtered_out: Vec<(usize, Row)>,
+    /// Active cell search term
+    search: Option<String>,
+    /// Cells matching the search, as (row, col) in row-major order
+    search_matches: Vec<(usize, usize)>,
+    /// Cell edit awaiting the database's response
+    pending_edit: Option<PendingEdit>,
+    /// Cells changed by successful edits, as (original row position, col)
+    edited_cells: HashSet<(usize, usize)>,
+    /// Formatted text for the visible window (table view)
+    cell_cache: RefCell<CellCache>,
+    /// Columns showing the mask placeholder
+    masked: Vec<usize>,
+    /// Values behind the placeholder, by (original row position, col)
+    hidden: HashMap<(usize, usize), CellValue>,
+    /// Decimal mark for numbers on screen
+    numbers: NumberFormat,
 }
 
 impl ResultsViewer {
@@ -54,30 +232,188 @@ impl ResultsViewer {
             results: None,
             selected_row: 0,
             selected_col: 0,
-            scroll_offset: 0,
-            h_scroll_offset: 0,
+            scroll_offset: Cell::new(0),
+            h_scroll_offset: Cell::new(0),
             col_widths: Vec::new(),
             error: None,
+            error_hint: None,
             view_mode: ViewMode::Table,
             pagination: None,
             page_height: Cell::new(20),
+            page_width: Cell::new(80),
+            expanded_col: None,
+            sort: None,
+            filter: None,
+            row_ids: None,
+            filtered_out: Vec::new(),
+            search: None,
+            search_matches: Vec::new(),
+            pending_edit: None,
+            edited_cells: HashSet::new(),
+            cell_cache: RefCell::new(CellCache::default()),
+            masked: Vec::new(),
+            hidden: HashMap::new(),
+            numbers: NumberFormat::default(),
+        }
+    }
+
+    pub fn set_number_format(&mut self, numbers: NumberFormat) {
+        self.numbers = numbers;
+        self.cell_cache.borrow_mut().rows.clear();
+    }
+
+    /// `text` shown for `cell` in column `col`, with the locale's decimal
+    /// mark when the cell is a float or a NUMERIC value
+    fn localize<'a>(&self, col: usize, cell: &CellValue, text: &'a str) -> Cow<'a, str> {
+        let numeric = match cell {
+            CellValue::Float(_) => true,
+            CellValue::Text(_) => self.results.as_ref().is_some_and(|r| {
+                r.columns
+                    .get(col)
+                    .is_some_and(|c| c.data_type == DataType::Numeric)
+            }),
+            _ => false,
+        };
+        if numeric {
+            self.numbers.decimal(text)
+        } else {
+            Cow::Borrowed(text)
         }
     }
 
     pub fn set_results(&mut self, results: QueryResults) {
         self.col_widths = compute_column_widths(&results);
+        self.expanded_col = None;
+        self.sort = None;
+        self.filter = None;
+        self.row_ids = None;
+        self.filtered_out.clear();
+        self.search = None;
+        self.search_matches.clear();
+        self.pending_edit = None;
+        self.edited_cells.clear();
+        self.masked.clear();
+        self.hidden.clear();
         self.results = Some(results);
         self.error = None;
+        self.error_hint = None;
         self.selected_row = 0;
         self.selected_col = 0;
-        self.scroll_offset = 0;
-        self.h_scroll_offset = 0;
+        self.scroll_offset.set(0);
+        self.h_scroll_offset.set(0);
+        self.invalidate_cell_cache();
+    }
+
+    /// Guidance shown under the current error
+    pub fn error_hint(&self) -> Option<&str> {
+        self.error_hint.as_deref()
     }
 
-    /// Set an error to display in the results area
-    pub fn set_error(&mut self, error: String) {
+    /// Set an error, and a hint on what to do about it, to display in the
+    /// results area
+    pub fn set_error(&mut self, error: String, hint: Option<String>) {
         self.error = Some(error);
+        self.error_hint = hint;
         self.results = None;
+        self.invalidate_cell_cache();
+    }
+
+    /// Remove the results, back in query order with any masked values put
+    /// back, leaving the viewer empty
+    pub fn take_results(&mut self) -> Option<QueryResults> {
+        self.reveal();
+        self.sort = None;
+        self.filter = None;
+        self.apply_view();
+        let results = self.results.take();
+        self.invalidate_cell_cache();
+        results
+    }
+
+    /// Add rows after the last one (in query order), masked like the rest.
+    /// A selection on the last row moves down to the newest.
+    pub fn append_rows(&mut self, rows: Vec<Row>) {
+        let Some(results) = self.results.as_mut() else {
+            return;
+        };
+        let following = self.selected_row + 1 >= results.rows.len();
+        let first_id = results.rows.len() + self.filtered_out.len();
+        for (i, mut row) in rows.into_iter().enumerate() {
+            let id = first_id + i;
+            for (col, value) in mask_row(&mut row, &self.masked) {
+                self.hidden.insert((id, col), value);
+            }
+            if let Some(ids) = self.row_ids.as_mut() {
+                ids.push(id);
+            }
+            results.rows.push(row);
+            results.row_count += 1;
+        }
+        self.col_widths = compute_column_widths(results);
+        self.expanded_col = None;
+        self.apply_view();
+        if following && let Some(results) = &self.results {
+            self.selected_row = results.rows.len().saturating_sub(1);
+        }
+    }
+
+    /// Show the mask placeholder in `columns` until `reveal`, keeping the
+    /// values aside
+    pub fn mask_columns(&mut self, columns: Vec<usize>) {
+        let Some(results) = self.results.as_mut() else {
+            return;
+        };
+        if columns.is_empty() {
+            return;
+        }
+        self.masked = columns;
+        let ids = self.row_ids.as_deref();
+        for (pos, row) in results.rows.iter_mut().enumerate() {
+            let id = ids.and_then(|ids| ids.get(pos).copied()).unwrap_or(pos);
+            for (col, value) in mask_row(row, &self.masked) {
+                self.hidden.insert((id, col), value);
+            }
+        }
+        for (id, row) in &mut self.filtered_out {
+            for (col, value) in mask_row(row, &self.masked) {
+                self.hidden.insert((*id, col), value);
+            }
+        }
+        self.col_widths = compute_column_widths(results);
+        self.expanded_col = None;
+        self.apply_view();
+    }
+
+    /// Put the masked values back
+    pub fn reveal(&mut self) {
+        let Some(results) = self.results.as_mut() else {
+            return;
+        };
+        if self.masked.is_empty() {
+            return;
+        }
+        let ids = self.row_ids.as_deref();
+        let rows = results.rows.iter_mut().enumerate().map(|(pos, row)| {
+            let id = ids.and_then(|ids| ids.get(pos).copied()).unwrap_or(pos);
+            (id, row)
+        });
+        let hidden_rows = self.filtered_out.iter_mut().map(|(id, row)| (*id, row));
+        for (id, row) in rows.chain(hidden_rows) {
+            for &col in &self.masked {
+                if let Some(value) = self.hidden.remove(&(id, col)) {
+                    row.values[col] = value;
+                }
+            }
+        }
+        self.masked.clear();
+        self.col_widths = compute_column_widths(results);
+        self.expanded_col = None;
+        self.apply_view();
+    }
+
+    /// Columns showing the mask placeholder
+    pub fn masked_columns(&self) -> &[usize] {
+        &self.masked
     }
 
     /// Access the underlying query results (for export)
@@ -85,6 +421,13 @@ impl ResultsViewer {
         self.results.as_ref()
     }
 
+    /// Selected row and row count (1-based), for the panel's position
+    /// indicator
+    pub fn position(&self) -> Option<(usize, usize)> {
+        let results = self.results.as_ref().filter(|r| !r.rows.is_empty())?;
+        Some((self.selected_row + 1, results.rows.len()))
+    }
+
     /// Get text of the selected cell
     pub fn selected_cell_text(&self) -> Option<String> {
         let results = self.results.as_ref()?;
@@ -125,6 +468,41 @@ impl ResultsViewer {
         Some(parts.join("\t"))
     }
 
+    /// The selected column's distinct non-NULL values (of the rows the
+    /// filter leaves) as `IN (...)`, numbers and booleans bare and
+    /// everything else quoted, with how many values it holds
+    pub fn column_in_list(&self) -> Result<(String, usize), String> {
+        let results = self.results.as_ref().ok_or("No results")?;
+        let col = self.selected_col;
+        let name = &results.columns.get(col).ok_or("No column selected")?.name;
+        if self.masked.contains(&col) {
+            return Err(format!("{} is masked — /reveal it first", name));
+        }
+        let mut seen = HashSet::new();
+        let mut values = Vec::new();
+        for cell in results.rows.iter().filter_map(|row| row.values.get(col)) {
+            let literal = match cell {
+                CellValue::Null => continue,
+                CellValue::Binary(_) => return Err(format!("{} holds binary values", name)),
+                CellValue::Float(f) if !f.is_finite() => {
+                    crate::sql::quote_literal(&cell.display_string(usize::MAX))
+                }
+                CellValue::Integer(_) | CellValue::Float(_) | CellValue::Boolean(_) => {
+                    cell.display_string(usize::MAX)
+                }
+                other => crate::sql::quote_literal(&other.display_string(usize::MAX)),
+            };
+            if seen.insert(literal.clone()) {
+                values.push(literal);
+            }
+        }
+        if values.is_empty() {
+            return Err(format!("{} has no non-NULL values", name));
+        }
+        let count = values.len();
+        Ok((format!("IN ({})", values.join(", ")), count))
+    }
+
     /// Toggle between table and vertical view modes
     pub fn toggle_view_mode(&mut self) {
         self.view_mode = match self.view_mode {
@@ -153,14 +531,14 @@ impl ResultsViewer {
 
     pub fn move_left(&mut self) {
         if self.selected_col > 0 {
-            self.selected_col -= 1;
+            self.select_col(self.selected_col - 1);
         }
     }
 
     pub fn move_right(&mut self) {
         let count = self.col_count();
         if self.selected_col < count.saturating_sub(1) {
-            self.selected_col += 1;
+            self.select_col(self.selected_col + 1);
         }
     }
 
@@ -195,20 +573,125 @@ impl ResultsViewer {
     }
 
     pub fn go_to_home(&mut self) {
-        self.selected_col = 0;
-        self.h_scroll_offset = 0;
+        self.select_col(0);
+        self.h_scroll_offset.set(0);
     }
 
     pub fn go_to_end(&mut self) {
         let count = self.col_count();
-        self.selected_col = count.saturating_sub(1);
+        self.select_col(count.saturating_sub(1));
+    }
+
+    /// Scroll a screenful of columns right, selecting the first column that
+    /// wasn't fully shown
+    pub fn page_right(&mut self) {
+        let count = self.col_count();
+        if count == 0 {
+            return;
+        }
+        let width = u32::from(self.page_width.get());
+        let start = self.h_scroll_offset.get();
+        let mut next = start;
+        let mut x = 0u32;
+        while next < count && x + u32::from(self.col_width(next)) <= width {
+            x += u32::from(self.col_width(next)) + 1;
+            next += 1;
+        }
+        let next = next.max(start + 1).min(count - 1);
+        // Don't scroll past the page that ends at the last column
+        let last_page = self.page_start_before(count);
+        self.select_col(next);
+        self.h_scroll_offset.set(next.min(last_page).max(start));
+    }
+
+    /// Scroll a screenful of columns left, selecting the first column shown
+    pub fn page_left(&mut self) {
+        let start = self.page_start_before(self.h_scroll_offset.get());
+        self.select_col(start);
+        self.h_scroll_offset.set(start);
+    }
+
+    /// First column of the page that ends just before column `end`
+    fn page_start_before(&self, end: usize) -> usize {
+        let width = u32::from(self.page_width.get());
+        let mut start = end;
+        // n columns take their widths plus n - 1 separators
+        let mut used = 0u32;
+        while start > 0 {
+            let w = u32::from(self.col_width(start - 1)) + 1;
+            if used + w > width + 1 {
+                break;
+            }
+            used += w;
+            start -= 1;
+        }
+        if start == end {
+            end.saturating_sub(1)
+        } else {
+            start
+        }
+    }
+
+    fn col_width(&self, col: usize) -> u16 {
+        self.col_widths.get(col).copied().unwrap_or(10)
+    }
+
+    /// Index of the selected column
+    pub fn selected_column(&self) -> usize {
+        self.selected_col
+    }
+
+    /// Select a column by index (out of range is ignored)
+    pub fn select_column(&mut self, col: usize) {
+        if col < self.col_count() {
+            self.select_col(col);
+        }
+    }
+
+    /// Best column for a jump-to-column pattern: an exact name, then a
+    /// prefix, then a substring, then a fuzzy match; leftmost wins a tie
+    pub fn find_column(&self, pattern: &str) -> Option<usize> {
+        let pattern = pattern.trim().to_lowercase();
+        if pattern.is_empty() {
+            return None;
+        }
+        let results = self.results.as_ref()?;
+        results
+            .columns
+            .iter()
+            .enumerate()
+            .filter_map(|(i, col)| {
+                let name = col.name.to_lowercase();
+                let rank = if name == pattern {
+                    0
+                } else if name.starts_with(&pattern) {
+                    1
+                } else if name.contains(&pattern) {
+                    2
+                } else if fuzzy_match(&name, &pattern) {
+                    3
+                } else {
+                    return None;
+                };
+                Some((rank, i))
+            })
+            .min()
+            .map(|(_, i)| i)
+    }
+
+    /// Change the selected column, dropping any temporary expansion
+    fn select_col(&mut self, col: usize) {
+        if col != self.selected_col {
+            self.restore_expanded_column();
+            self.selected_col = col;
+        }
     }
 
     /// Widen the currently selected column by a fixed step
     pub fn widen_column(&mut self) {
         if self.selected_col < self.col_widths.len() {
             let w = &mut self.col_widths[self.selected_col];
-            *w = (*w + 4).min(200);
+            *w = (*w + 4).min(MAX_COLUMN_WIDTH);
         }
     }
 
@@ -222,11 +705,548 @@ impl ResultsViewer {
 
     /// Reset all column widths to auto-computed values
     pub fn reset_column_widths(&mut self) {
+        self.expanded_col = None;
         if let Some(ref results) = self.results {
             self.col_widths = compute_column_widths(results);
         }
     }
 
+    /// Toggle a temporary expansion of the selected column so the longest
+    /// value in the visible rows fits without truncation. Pressing again, or
+    /// moving to another column, restores the previous width.
+    pub fn toggle_expand_column(&mut self) {
+        if self.restore_expanded_column() {
+            return;
+        }
+        let Some(ref results) = self.results else {
+            return;
+        };
+        let col = self.selected_col;
+        if col >= self.col_widths.len() {
+            return;
+        }
+        let header = &results.columns[col];
+        let header_width = super::unicode::display_width(&format!(
+            "{}: {}",
+            header.name,
+            header.data_type.display_name()
+        ));
+        let start = self.scroll_start(self.page_height.get());
+        let fit = results
+            .rows
+            .iter()
+            .skip(start)
+            .take(self.page_height.get())
+            .filter_map(|row| row.values.get(col))
+            .map(|cell| {
+                super::unicode::display_width(&cell.display_string(MAX_COLUMN_WIDTH as usize))
+            })
+            .fold(header_width, usize::max) as u16
+            + 1;
+        let previous = self.col_widths[col];
+        self.col_widths[col] = fit.clamp(previous, MAX_COLUMN_WIDTH);
+        self.expanded_col = Some((col, previous));
+    }
+
+    /// Whether a column is currently expanded via `toggle_expand_column`
+    pub fn is_column_expanded(&self) -> bool {
+        self.expanded_col.is_some()
+    }
+
+    /// Undo a temporary column expansion. Returns true if one was active.
+    fn restore_expanded_column(&mut self) -> bool {
+        match self.expanded_col.take() {
+            Some((col, width)) => {
+                if let Some(w) = self.col_widths.get_mut(col) {
+                    *w = width;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sort the rows by the selected column: ascending first, then toggling
+    /// between ascending and descending on repeated presses.
+    pub fn sort_by_selected_column(&mut self) {
+        let order = match self.sort {
+            Some((col, SortOrder::Ascending)) if col == self.selected_col => SortOrder::Descending,
+            _ => SortOrder::Ascending,
+        };
+        if self.selected_col < self.col_count() {
+            self.sort = Some((self.selected_col, order));
+            self.apply_view();
+        }
+    }
+
+    /// Restore the rows to the order the query returned them in
+    pub fn clear_sort(&mut self) {
+        if self.sort.take().is_some() {
+            self.apply_view();
+        }
+    }
+
+    /// Active sort column and direction, if any
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+
+    /// Filter the rows client-side. Blank input clears the filter. Returns
+    /// an error (leaving the current filter in place) for an unknown column.
+    pub fn set_filter(&mut self, input: &str) -> Result<(), String> {
+        let Some(filter) = RowFilter::parse(input) else {
+            self.clear_filter();
+            return Ok(());
+        };
+        if let Some(ref results) = self.results {
+            let _ = filter.matcher(&results.columns)?;
+        }
+        self.filter = Some(filter);
+        self.apply_view();
+        Ok(())
+    }
+
+    /// Remove the row filter. Returns true if one was active.
+    pub fn clear_filter(&mut self) -> bool {
+        if self.filter.take().is_some() {
+            self.apply_view();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Active row filter, if any
+    pub fn filter(&self) -> Option<&RowFilter> {
+        self.filter.as_ref()
+    }
+
+    /// (visible, total) row counts while a filter is active
+    pub fn filter_counts(&self) -> Option<(usize, usize)> {
+        self.filter.as_ref()?;
+        let visible = self.row_count();
+        Some((visible, visible + self.filtered_out.len()))
+    }
+
+    /// Highlight every cell containing `term` (case-insensitive). Blank
+    /// input clears the search.
+    pub fn set_search(&mut self, term: &str) {
+        if term.is_empty() {
+            self.clear_search();
+            return;
+        }
+        self.search = Some(term.to_string());
+        self.refresh_search_matches();
+    }
+
+    /// Remove search highlights. Returns true if a search was active.
+    pub fn clear_search(&mut self) -> bool {
+        self.search_matches.clear();
+        self.search.take().is_some()
+    }
+
+    /// Active search term, if any
+    pub fn search(&self) -> Option<&str> {
+        self.search.as_deref()
+    }
+
+    /// Number of cells matching the active search
+    pub fn search_match_count(&self) -> usize {
+        self.search_matches.len()
+    }
+
+    /// Select the next matching cell after the selection (wrapping).
+    /// Returns the 1-based match index and total, or None if nothing matches.
+    pub fn find_next(&mut self) -> Option<(usize, usize)> {
+        let current = (self.selected_row, self.selected_col);
+        let idx = self
+            .search_matches
+            .iter()
+            .position(|&m| m > current)
+            .unwrap_or(0);
+        self.select_match(idx)
+    }
+
+    /// Select the previous matching cell before the selection (wrapping).
+    pub fn find_prev(&mut self) -> Option<(usize, usize)> {
+        let current = (self.selected_row, self.selected_col);
+        let idx = self
+            .search_matches
+            .iter()
+            .rposition(|&m| m < current)
+            .unwrap_or(self.search_matches.len().checked_sub(1)?);
+        self.select_match(idx)
+    }
+
+    fn select_match(&mut self, idx: usize) -> Option<(usize, usize)> {
+        let &(row, col) = self.search_matches.get(idx)?;
+        self.selected_row = row;
+        self.select_col(col);
+        Some((idx + 1, self.search_matches.len()))
+    }
+
+    fn is_search_match(&self, row: usize, col: usize) -> bool {
+        self.search_matches.binary_search(&(row, col)).is_ok()
+    }
+
+    /// Column name and current value (None for NULL) of the selected cell,
+    /// or why it can't be edited.
+    pub fn edit_target(&self) -> Result<(String, Option<String>), String> {
+        let results = self.results.as_ref().ok_or("No results to edit")?;
+        if !self.masked.is_empty() {
+            return Err("Masked results can't be edited — /reveal shows the values".to_string());
+        }
+        let source = results.source.as_ref().ok_or(
+            "Results aren't editable: run a single-table SELECT that includes the primary key",
+        )?;
+        let name = results
+            .columns
+            .get(self.selected_col)
+            .map(|c| c.name.clone())
+            .unwrap_or_default();
+        if source.column(self.selected_col).is_none() {
+            return Err(format!("Column {} is computed and can't be edited", name));
+        }
+        match results
+            .rows
+            .get(self.selected_row)
+            .and_then(|r| r.values.get(self.selected_col))
+        {
+            None => Err("No cell selected".to_string()),
+            Some(CellValue::Binary(_)) => Err("Binary values can't be edited".to_string()),
+            Some(CellValue::Null) => Ok((name, None)),
+            Some(cell) => Ok((name, Some(cell.display_string(usize::MAX)))),
+        }
+    }
+
+    /// Source schema, table, and table column of the selected cell, with its
+    /// value (None for NULL), or why it can't be traced back to a table.
+    pub fn cell_origin(&self) -> Result<(String, String, String, Option<String>), String> {
+        let results = self.results.as_ref().ok_or("No results")?;
+        if self.masked.contains(&self.selected_col) {
+            return Err("This value is masked — /reveal shows it".to_string());
+        }
+        let source = results.source.as_ref().ok_or(
+            "Can't tell which table this came from: run a single-table SELECT that includes the primary key",
+        )?;
+        let column = source.column(self.selected_col).ok_or_else(|| {
+            let name = results
+                .columns
+                .get(self.selected_col)
+                .map(|c| c.name.as_str())
+                .unwrap_or_default();
+            format!("Column {} is computed, not read from a table", name)
+        })?;
+        let value = match results
+            .rows
+            .get(self.selected_row)
+            .and_then(|r| r.values.get(self.selected_col))
+        {
+            None => return Err("No cell selected".to_string()),
+            Some(CellValue::Null) => None,
+            Some(cell) => Some(cell.display_string(usize::MAX)),
+        };
+        Ok((
+            source.schema.clone(),
+            source.table.clone(),
+            column.name.clone(),
+            value,
+        ))
+    }
+
+    /// Generate the UPDATE setting the selected cell to `value` (None =
+    /// NULL), and track the edit until `finish_edit` or `cancel_edit`.
+    pub fn begin_edit(&mut self, value: Option<&str>) -> Result<CellUpdate, String> {
+        let results = self.results.as_ref().ok_or("No results to edit")?;
+        if !self.masked.is_empty() {
+            return Err("Masked results can't be edited".to_string());
+        }
+        let source = results.source.as_ref().ok_or("Results aren't editable")?;
+        let row = results
+            .rows
+            .get(self.selected_row)
+            .ok_or("No cell selected")?;
+        let update = source.update_cell(row, self.selected_col, value)?;
+        self.pending_edit = Some(PendingEdit {
+            row_id: self.row_id(self.selected_row),
+            col: self.selected_col,
+            returning: update.returning.clone(),
+        });
+        Ok(update)
+    }
+
+    /// Apply the row returned by the UPDATE to the edited row. Returns false
+    /// if no edit was pending or the UPDATE matched no row.
+    pub fn finish_edit(&mut self, returned: Option<Row>) -> bool {
+        let Some(edit) = self.pending_edit.take() else {
+            return false;
+        };
+        let Some(returned) = returned else {
+            return false;
+        };
+        let Some(row) = self.row_by_id_mut(edit.row_id) else {
+            return false;
+        };
+        for (value, &col) in returned.values.into_iter().zip(&edit.returning) {
+            if let Some(cell) = row.values.get_mut(col) {
+                *cell = value;
+            }
+        }
+        self.edited_cells.insert((edit.row_id, edit.col));
+        self.invalidate_cell_cache();
+        // The new value may change where the row sorts or whether it
+        // passes the filter
+        if self.sort.is_some() || self.filter.is_some() || self.search.is_some() {
+            self.apply_view();
+        }
+        true
+    }
+
+    /// Forget the pending edit (the UPDATE failed)
+    pub fn cancel_edit(&mut self) {
+        self.pending_edit = None;
+    }
+
+    /// Whether the cell at visible position (row, col) was edited
+    fn is_edited(&self, row: usize, col: usize) -> bool {
+        !self.edited_cells.is_empty() && self.edited_cells.contains(&(self.row_id(row), col))
+    }
+
+    /// Original position of the row shown at `row`
+    fn row_id(&self, row: usize) -> usize {
+        self.row_ids
+            .as_ref()
+            .and_then(|ids| ids.get(row).copied())
+            .unwrap_or(row)
+    }
+
+    /// Row by original position, whether visible or hidden by the filter
+    fn row_by_id_mut(&mut self, id: usize) -> Option<&mut Row> {
+        let results = self.results.as_mut()?;
+        let Some(ids) = &self.row_ids else {
+            return results.rows.get_mut(id);
+        };
+        match ids.iter().position(|&i| i == id) {
+            Some(pos) => results.rows.get_mut(pos),
+            None => self
+                .filtered_out
+                .iter_mut()
+                .find(|(i, _)| *i == id)
+                .map(|(_, row)| row),
+        }
+    }
+
+    /// Rescan the visible rows for the search term
+    fn refresh_search_matches(&mut self) {
+        self.search_matches.clear();
+        let (Some(term), Some(results)) = (&self.search, &self.results) else {
+            return;
+        };
+        let needle = term.to_lowercase();
+        for (r, row) in results.rows.iter().enumerate() {
+            for (c, cell) in row.values.iter().enumerate() {
+                if cell
+                    .display_string(FILTER_TEXT_LIMIT)
+                    .to_lowercase()
+                    .contains(&needle)
+                {
+                    self.search_matches.push((r, c));
+                }
+            }
+        }
+    }
+
+    /// Rebuild the visible rows for the current sort and filter, then
+    /// recompute search matches against the new row order.
+    fn apply_view(&mut self) {
+        self.rebuild_rows();
+        self.refresh_search_matches();
+        self.invalidate_cell_cache();
+    }
+
+    /// Drop cached cell text after the rows change
+    fn invalidate_cell_cache(&mut self) {
+        self.cell_cache.get_mut().rows.clear();
+    }
+
+    /// Reorder and partition rows for the current sort and filter, keeping
+    /// the selection on the same row when it stays visible. Sort ties keep
+    /// their original relative order.
+    fn rebuild_rows(&mut self) {
+        let Some(ref mut results) = self.results else {
+            return;
+        };
+        let rows = std::mem::take(&mut results.rows);
+        let ids = self
+            .row_ids
+            .take()
+            .unwrap_or_else(|| (0..rows.len()).collect());
+        let selected_id = ids.get(self.selected_row).copied();
+
+        let mut tagged: Vec<_> = ids
+            .into_iter()
+            .zip(rows)
+            .chain(self.filtered_out.drain(..))
+            .collect();
+        match self.sort {
+            Some((col, order)) => {
+                let data_type = &results.columns[col].data_type;
+                tagged.sort_by(|(ia, a), (ib, b)| {
+                    let ord = match (a.values.get(col), b.values.get(col)) {
+                        (Some(x), Some(y)) => compare_cells(x, y, data_type),
+                        _ => std::cmp::Ordering::Equal,
+                    };
+                    let ord = match order {
+                        SortOrder::Ascending => ord,
+                        SortOrder::Descending => ord.reverse(),
+                    };
+                    ord.then(ia.cmp(ib))
+                });
+            }
+            None => tagged.sort_by_key(|(id, _)| *id),
+        }
+
+        let matcher = self
+            .filter
+            .as_ref()
+            .and_then(|f| f.matcher(&results.columns).ok());
+        let visible = match matcher {
+            Some(matches) => {
+                let (visible, hidden): (Vec<_>, Vec<_>) =
+                    tagged.into_iter().partition(|(_, row)| matches(row));
+                self.filtered_out = hidden;
+                visible
+            }
+            None => tagged,
+        };
+
+        let (ids, rows): (Vec<usize>, Vec<_>) = visible.into_iter().unzip();
+        self.selected_row = selected_id
+            .and_then(|sel| ids.iter().position(|&id| id == sel))
+            .unwrap_or(0);
+        results.rows = rows;
+        if self.sort.is_some() || self.filter.is_some() {
+            self.row_ids = Some(ids);
+        }
+    }
+
+    /// First visible row for a viewport of `visible_height` rows, keeping
+    /// the selected row on screen.
+    fn scroll_start(&self, visible_height: usize) -> usize {
+        let offset = self.scroll_offset.get();
+        if self.selected_row < offset {
+            self.selected_row
+        } else if self.selected_row >= offset + visible_height {
+            self.selected_row + 1 - visible_height
+        } else {
+            offset
+        }
+    }
+
+    /// First visible column for a viewport `width` cells wide, keeping the
+    /// selected column on screen.
+    fn h_scroll_start(&self, width: u16) -> usize {
+        let widths = &self.col_widths;
+        let width_of = |ci: usize| widths.get(ci).copied().unwrap_or(10);
+        let hs = self.h_scroll_offset.get();
+        // If selected column is before the scroll offset, snap left
+        if self.selected_col < hs {
+            return self.selected_col;
+        }
+        // Check if selected_col fits in viewport from current offset
+        let mut x: u16 = 0;
+        for ci in hs..widths.len() {
+            if ci == self.selected_col {
+                if x + width_of(ci) <= width {
+                    return hs;
+                }
+                break;
+            }
+            x += width_of(ci) + 1;
+            if x >= width {
+                break;
+            }
+        }
+        // Scroll right: find min offset that shows selected_col
+        let mut new_hs = self.selected_col;
+        let mut total = width_of(self.selected_col);
+        while new_hs > 0 {
+            let prev_w = width_of(new_hs - 1);
+            if total + prev_w + 1 > width {
+                break;
+            }
+            total += prev_w + 1;
+            new_hs -= 1;
+        }
+        new_hs
+    }
+
+    /// Columns drawn from `h_scroll` in a viewport `width` cells wide, as
+    /// (column index, drawn width). The last column may be clipped.
+    fn visible_columns(&self, h_scroll: usize, width: u16) -> Vec<(usize, u16)> {
+        let mut columns = Vec::new();
+        let mut x: u16 = 0;
+        for col in h_scroll..self.col_count() {
+            if x >= width {
+                break;
+            }
+            let w = self
+                .col_widths
+                .get(col)
+                .copied()
+                .unwrap_or(10)
+                .min(width - x);
+            columns.push((col, w));
+            x = x.saturating_add(w + 1); // +1 for column separator
+        }
+        columns
+    }
+
+    /// Fitted text of the visible cells in rows `first..first + count`,
+    /// reusing text cached by earlier frames for the same layout.
+    fn with_fitted_rows<R>(
+        &self,
+        first: usize,
+        count: usize,
+        layout: &[(usize, u16)],
+        draw: impl FnOnce(&HashMap<usize, Vec<(String, bool)>>) -> R,
+    ) -> R {
+        let mut cache = self.cell_cache.borrow_mut();
+        if cache.layout != layout {
+            cache.layout = layout.to_vec();
+            cache.rows.clear();
+        }
+        let window = first..first + count;
+        cache.rows.retain(|row, _| window.contains(row));
+        if let Some(results) = &self.results {
+            for row_idx in window.clone() {
+                let Some(row) = results.rows.get(row_idx) else {
+                    break;
+                };
+                cache.rows.entry(row_idx).or_insert_with(|| {
+                    layout
+                        .iter()
+                        .map(|&(col, w)| {
+                            let (text, truncated) = match row.values.get(col) {
+                                Some(cell) => {
+                                    let (text, truncated) = fit_cell(cell, w as usize);
+                                    (self.localize(col, cell, &text).into_owned(), truncated)
+                                }
+                                None => (String::new(), false),
+                            };
+                            if truncated {
+                                (text, true)
+                            } else {
+                                (super::unicode::pad_to_width(&text, w as usize), false)
+                            }
+                        })
+                        .collect()
+                });
+            }
+        }
+        draw(&cache.rows)
+    }
+
     fn row_count(&self) -> usize {
         self.results.as_ref().map_or(0, |r| r.rows.len())
     }
@@ -246,11 +1266,18 @@ impl Component for ResultsViewer {
     fn render(&self, frame: &mut Frame, area: Rect, focused: bool, theme: &Theme) {
         // Show error if present
         if let Some(ref error) = self.error {
-            let lines: Vec<Line> = vec![
+            let mut lines: Vec<Line> = vec![
                 Line::from(Span::styled("Query Error", theme.results_error_title)),
                 Line::from(""),
                 Line::from(Span::styled(error.as_str(), theme.results_error_text)),
             ];
+            if let Some(hint) = &self.error_hint {
+                lines.push(Line::from(""));
+                lines.push(Line::from(vec![
+                    Span::styled("Hint: ", theme.results_error_title),
+                    Span::styled(hint.as_str(), theme.results_empty),
+                ]));
+            }
             let p = Paragraph::new(lines).wrap(ratatui::widgets::Wrap { trim: false });
             frame.render_widget(p, area);
             return;
@@ -259,10 +1286,21 @@ impl Component for ResultsViewer {
         let results = match &self.results {
             Some(r) if !r.columns.is_empty() => r,
             _ => {
-                let msg = if self.results.is_some() {
-                    "Query returned no columns"
-                } else {
-                    "No results yet. Write a query and press F5 to execute."
+                let msg = match self.results {
+                    // DML without RETURNING: what it did instead of a grid
+                    Some(QueryResults {
+                        command: Some(ref tag),
+                        execution_time,
+                        ..
+                    }) => format!(
+                        "{} \u{2014} {} row{} affected in {:.1}ms",
+                        tag,
+                        tag.rows,
+                        if tag.rows == 1 { "" } else { "s" },
+                        execution_time.as_secs_f64() * 1000.0
+                    ),
+                    Some(_) => "Query returned no columns".to_string(),
+                    None => "No results yet. Write a query and press F5 to execute.".to_string(),
                 };
                 let p = Paragraph::new(msg).style(theme.results_empty);
                 frame.render_widget(p, area);
@@ -281,131 +1319,87 @@ impl Component for ResultsViewer {
 
         let visible_height = (area.height as usize).saturating_sub(2); // header + footer
         self.page_height.set(visible_height.max(1));
+        self.page_width.set(area.width);
         let viewer = self;
 
-        // Ensure selected row is visible
-        let scroll_offset = if viewer.selected_row < viewer.scroll_offset {
-            viewer.selected_row
-        } else if viewer.selected_row >= viewer.scroll_offset + visible_height {
-            viewer.selected_row - visible_height + 1
-        } else {
-            viewer.scroll_offset
-        };
+        // Keep the selection visible, moving the viewport only when needed
+        let scroll_offset = viewer.scroll_start(visible_height);
+        viewer.scroll_offset.set(scroll_offset);
+        let h_scroll = viewer.h_scroll_start(area.width);
+        viewer.h_scroll_offset.set(h_scroll);
+        let layout = viewer.visible_columns(h_scroll, area.width);
 
-        let col_widths = &self.col_widths;
-
-        // Auto-adjust h_scroll to keep selected_col visible
-        let h_scroll = {
-            let mut hs = viewer.h_scroll_offset;
-            // If selected column is before the scroll offset, snap left
-            if viewer.selected_col < hs {
-                hs = viewer.selected_col;
-            } else {
-                // Check if selected_col fits in viewport from current offset
-                let mut x: u16 = 0;
-                let mut visible = false;
-                for ci in hs..col_widths.len() {
-                    let w = col_widths.get(ci).copied().unwrap_or(10);
-                    if ci == viewer.selected_col {
-                        visible = x + w <= area.width;
-                        break;
-                    }
-                    x += w + 1;
-                    if x >= area.width {
-                        break;
-                    }
-                }
-                if !visible {
-                    // Scroll right: find min offset that shows selected_col
-                    let mut new_hs = viewer.selected_col;
-                    let mut total: u16 = col_widths.get(viewer.selected_col).copied().unwrap_or(10);
-                    while new_hs > 0 {
-                        let prev_w = col_widths.get(new_hs - 1).copied().unwrap_or(10);
-                        if total + prev_w + 1 > area.width {
-                            break;
-                        }
-                        total += prev_w + 1;
-                        new_hs -= 1;
-                    }
-                    hs = new_hs;
-                }
-            }
-            hs
-        };
+        let buf = frame.buffer_mut();
 
         // Render header row
-        let header_y = area.y;
         let mut x = area.x;
-        for (col_idx, col_def) in results.columns.iter().enumerate().skip(h_scroll) {
-            if x >= area.x + area.width {
-                break;
-            }
-            let w = col_widths
-                .get(col_idx)
-                .copied()
-                .unwrap_or(10)
-                .min(area.x + area.width - x);
+        for &(col_idx, w) in &layout {
+            let col_def = &results.columns[col_idx];
             let style = if focused && col_idx == viewer.selected_col {
                 theme.results_header_selected
             } else {
                 theme.results_header
             };
-            // Show "name: type" in header for better context
+            // Show "name: type" in header for better context, prefixed with
+            // the sort direction so the indicator survives truncation
             let header_text = format!("{}: {}", col_def.name, col_def.data_type.display_name());
+            let header_text = match viewer.sort {
+                Some((col, order)) if col == col_idx => {
+                    format!("{} {}", sort_indicator(order), header_text)
+                }
+                _ => header_text,
+            };
             let header = truncate_str(&header_text, w as usize);
             let padded = super::unicode::pad_to_width(&header, w as usize);
-            frame.render_widget(
-                Paragraph::new(padded).style(style),
-                Rect::new(x, header_y, w, 1),
-            );
+            buf.set_stringn(x, area.y, padded, w as usize, style);
             x += w + 1; // +1 for column separator
         }
 
         // Render rows
-        for vis_row in 0..visible_height {
-            let row_idx = scroll_offset + vis_row;
-            let y = area.y + 1 + vis_row as u16;
-            if y >= area.y + area.height - 1 {
-                break;
-            }
-
-            if row_idx >= results.rows.len() {
-                break;
-            }
-
-            let row = &results.rows[row_idx];
-            let is_selected_row = row_idx == viewer.selected_row;
-            let row_base_style = if vis_row % 2 == 0 {
-                theme.results_row_even
-            } else {
-                theme.results_row_odd
-            };
-
-            let mut x = area.x;
-            for (col_idx, cell) in row.values.iter().enumerate().skip(h_scroll) {
-                if x >= area.x + area.width {
+        let row_count = visible_height.min(results.rows.len().saturating_sub(scroll_offset));
+        viewer.with_fitted_rows(scroll_offset, row_count, &layout, |fitted| {
+            for vis_row in 0..row_count {
+                let row_idx = scroll_offset + vis_row;
+                let Some(cells) = fitted.get(&row_idx) else {
                     break;
-                }
-                let w = col_widths
-                    .get(col_idx)
-                    .copied()
-                    .unwrap_or(10)
-                    .min(area.x + area.width - x);
-
-                let style = if focused && is_selected_row && col_idx == viewer.selected_col {
-                    theme.results_selected
-                } else if cell.is_null() {
-                    theme.results_null
+                };
+                let y = area.y + 1 + vis_row as u16;
+                let row = &results.rows[row_idx];
+                let is_selected_row = row_idx == viewer.selected_row;
+                let row_base_style = if vis_row % 2 == 0 {
+                    theme.results_row_even
                 } else {
-                    row_base_style
+                    theme.results_row_odd
                 };
 
-                let text = cell.display_string(w as usize);
-                let padded = super::unicode::pad_to_width(&text, w as usize);
-                frame.render_widget(Paragraph::new(padded).style(style), Rect::new(x, y, w, 1));
-                x += w + 1;
+                let mut x = area.x;
+                for (&(col_idx, w), (text, truncated)) in layout.iter().zip(cells) {
+                    let is_null = row.values.get(col_idx).is_some_and(CellValue::is_null);
+                    let style = if focused && is_selected_row && col_idx == viewer.selected_col {
+                        theme.results_selected
+                    } else if viewer.is_search_match(row_idx, col_idx) {
+                        theme.results_search_match
+                    } else if viewer.is_edited(row_idx, col_idx) {
+                        theme.results_edited
+                    } else if is_null {
+                        theme.results_null
+                    } else {
+                        row_base_style
+                    };
+
+                    let (cx, _) = buf.set_stringn(x, y, text, w as usize, style);
+                    if *truncated {
+                        let marker_style = if style == theme.results_selected {
+                            style
+                        } else {
+                            theme.results_truncated
+                        };
+                        buf.set_stringn(cx, y, TRUNCATION_MARKER, 1, marker_style);
+                    }
+                    x += w + 1;
+                }
             }
-        }
+        });
 
         // Footer with row count, pagination, and timing
         let footer_y = area.y + area.height - 1;
@@ -418,12 +1412,62 @@ impl Component for ResultsViewer {
     }
 }
 
+/// Upper bound for a single column's width (manual widen or expand-to-fit)
+const MAX_COLUMN_WIDTH: u16 = 200;
+
+/// Marker drawn in the last column of a cell whose value was cut off
+const TRUNCATION_MARKER: &str = "\u{2026}";
+
+/// Fit a cell's display text into `width` columns.
+///
+/// Returns the text and whether it was truncated. Truncated text is exactly
+/// `width - 1` columns wide so the caller can append `TRUNCATION_MARKER`.
+fn fit_cell(cell: &CellValue, width: usize) -> (String, bool) {
+    use super::unicode::display_width;
+    use unicode_truncate::UnicodeTruncateStr;
+
+    // `display_string` keeps `max - 3` real columns before its own "...", so
+    // asking for `width + 2` leaves the `width - 1` columns we need intact
+    let text = cell.display_string(width + 2);
+    if display_width(&text) <= width || width < 2 {
+        return (text, false);
+    }
+    let (head, _) = text.unicode_truncate(width - 1);
+    (super::unicode::pad_to_width(head, width - 1), true)
+}
+
+/// Compare two cells of a column for sorting. NUMERIC values arrive as
+/// text, so they are compared by value rather than lexically.
+fn compare_cells(a: &CellValue, b: &CellValue, data_type: &DataType) -> std::cmp::Ordering {
+    if let (DataType::Numeric, CellValue::Text(x), CellValue::Text(y)) = (data_type, a, b)
+        && let (Ok(x), Ok(y)) = (x.parse::<f64>(), y.parse::<f64>())
+    {
+        return x.total_cmp(&y);
+    }
+    a.sort_cmp(b)
+}
+
+/// Header arrow for a sorted column
+fn sort_indicator(order: SortOrder) -> &'static str {
+    match order {
+        SortOrder::Ascending => "\u{25b2}",
+        SortOrder::Descending => "\u{25bc}",
+    }
+}
+
 /// Build footer text with pagination-aware row display
 fn build_footer(viewer: &ResultsViewer, results: &QueryResults) -> String {
     let time_ms = results.execution_time.as_secs_f64() * 1000.0;
     let col_info = format!("Col {}/{}", viewer.selected_col + 1, results.columns.len());
 
-    let row_info = if let Some(ref pg) = viewer.pagination {
+    let row_info = if let Some((visible, total)) = viewer.filter_counts() {
+        format!(
+            "Row {}/{} (filtered from {})",
+            (viewer.selected_row + 1).min(visible),
+            visible,
+            total
+        )
+    } else if let Some(ref pg) = viewer.pagination {
         if results.rows.is_empty() {
             "0 rows".to_string()
         } else {
@@ -574,6 +1618,7 @@ fn render_vertical(
         let truncated_name = super::unicode::truncate_to_width(&col_def.name, label_width);
         let label = super::unicode::rpad_to_width(&truncated_name, label_width);
         let value = cell.display_string(value_width);
+        let value = viewer.localize(col_idx, cell, &value);
         let padded_value = super::unicode::pad_to_width(&value, value_width);
 
         let label_style = if is_selected {
@@ -584,6 +1629,10 @@ fn render_vertical(
         let sep_style = theme.results_footer;
         let value_style = if is_selected {
             theme.results_selected
+        } else if viewer.is_search_match(viewer.selected_row, col_idx) {
+            theme.results_search_match
+        } else if viewer.is_edited(viewer.selected_row, col_idx) {
+            theme.results_edited
         } else if cell.is_null() {
             theme.results_null
         } else if col_idx % 2 == 0 {
@@ -701,6 +1750,42 @@ mod tests {
         assert_eq!(viewer.selected_row_text(), Some("1\tAlice".to_string()));
     }
 
+    #[test]
+    fn test_column_in_list() {
+        let mut viewer = ResultsViewer::new();
+        let mut results = sample_results();
+        results.rows.push(Row {
+            values: vec![
+                CellValue::Integer(1),
+                CellValue::Text("O'Brien".to_string()),
+            ],
+        });
+        results.rows.push(Row {
+            values: vec![CellValue::Integer(3), CellValue::Null],
+        });
+        viewer.set_results(results);
+        assert_eq!(viewer.column_in_list(), Ok(("IN (1, 2, 3)".to_string(), 3)));
+        viewer.selected_col = 1;
+        assert_eq!(
+            viewer.column_in_list(),
+            Ok(("IN ('Alice', 'Bob', 'O''Brien')".to_string(), 3))
+        );
+
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(QueryResults::new(
+            sample_results().columns,
+            vec![Row {
+                values: vec![CellValue::Null, CellValue::Null],
+            }],
+            Duration::from_millis(1),
+            1,
+        ));
+        assert_eq!(
+            viewer.column_in_list(),
+            Err("id has no non-NULL values".to_string())
+        );
+    }
+
     fn json_results() -> QueryResults {
         QueryResults::new(
             vec![
@@ -761,7 +1846,7 @@ mod tests {
         viewer.set_results(sample_results());
         assert!(viewer.results.is_some());
 
-        viewer.set_error("relation \"foo\" does not exist".to_string());
+        viewer.set_error("relation \"foo\" does not exist".to_string(), None);
         assert!(viewer.results.is_none());
         assert!(viewer.error.is_some());
     }
@@ -769,7 +1854,7 @@ mod tests {
     #[test]
     fn test_set_results_clears_error() {
         let mut viewer = ResultsViewer::new();
-        viewer.set_error("some error".to_string());
+        viewer.set_error("some error".to_string(), None);
         assert!(viewer.error.is_some());
 
         viewer.set_results(sample_results());
@@ -843,23 +1928,34 @@ mod tests {
         assert_eq!(viewer.selected_col, 0);
     }
 
+    #[test]
+    fn test_position_follows_selected_row() {
+        let mut viewer = ResultsViewer::new();
+        assert_eq!(viewer.position(), None);
+        viewer.set_results(sample_results());
+        let rows = viewer.results().unwrap().rows.len();
+        assert_eq!(viewer.position(), Some((1, rows)));
+        viewer.go_to_bottom();
+        assert_eq!(viewer.position(), Some((rows, rows)));
+    }
+
     #[test]
     fn test_h_scroll_resets_on_set_results() {
         let mut viewer = ResultsViewer::new();
-        viewer.h_scroll_offset = 5;
+        viewer.h_scroll_offset.set(5);
         viewer.set_results(sample_results());
-        assert_eq!(viewer.h_scroll_offset, 0);
+        assert_eq!(viewer.h_scroll_offset.get(), 0);
     }
 
     #[test]
     fn test_go_to_home_resets_h_scroll() {
         let mut viewer = ResultsViewer::new();
         viewer.set_results(sample_results());
-        viewer.h_scroll_offset = 3;
+        viewer.h_scroll_offset.set(3);
         viewer.selected_col = 1;
         viewer.go_to_home();
         assert_eq!(viewer.selected_col, 0);
-        assert_eq!(viewer.h_scroll_offset, 0);
+        assert_eq!(viewer.h_scroll_offset.get(), 0);
     }
 
     // UTF-8 truncation tests
@@ -1040,6 +2136,557 @@ mod tests {
         assert_eq!(viewer.col_widths, auto_widths);
     }
 
+    fn wide_results() -> QueryResults {
+        QueryResults::new(
+            vec![ColumnDef {
+                name: "note".to_string(),
+                data_type: DataType::Text,
+                nullable: true,
+            }],
+            vec![
+                Row {
+                    values: vec![CellValue::Text("short".to_string())],
+                },
+                Row {
+                    values: vec![CellValue::Text("x".repeat(60))],
+                },
+            ],
+            Duration::from_millis(1),
+            2,
+        )
+    }
+
+    #[test]
+    fn test_toggle_expand_column_fits_longest_visible_value() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(wide_results());
+        assert_eq!(viewer.col_widths[0], 40);
+        viewer.toggle_expand_column();
+        assert!(viewer.is_column_expanded());
+        assert_eq!(viewer.col_widths[0], 61);
+        // Second press restores the previous width
+        viewer.toggle_expand_column();
+        assert!(!viewer.is_column_expanded());
+        assert_eq!(viewer.col_widths[0], 40);
+    }
+
+    #[test]
+    fn test_expand_column_restored_on_column_change() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(sample_results());
+        let original = viewer.col_widths[0];
+        viewer.col_widths[0] = 4;
+        viewer.toggle_expand_column();
+        assert_eq!(viewer.col_widths[0], original);
+        viewer.move_right();
+        assert!(!viewer.is_column_expanded());
+        assert_eq!(viewer.col_widths[0], 4);
+    }
+
+    #[test]
+    fn test_fit_cell_marks_truncation() {
+        let cell = CellValue::Text("hello world".to_string());
+        assert_eq!(fit_cell(&cell, 20), ("hello world".to_string(), false));
+        assert_eq!(fit_cell(&cell, 11), ("hello world".to_string(), false));
+        assert_eq!(fit_cell(&cell, 8), ("hello w".to_string(), true));
+    }
+
+    #[test]
+    fn test_fit_cell_wide_chars_keep_width() {
+        // Cutting a 2-column char in half pads instead of overflowing
+        let cell = CellValue::Text("日本語テキスト".to_string());
+        let (text, truncated) = fit_cell(&cell, 6);
+        assert!(truncated);
+        assert_eq!(super::super::unicode::display_width(&text), 5);
+    }
+
+    fn unsorted_results() -> QueryResults {
+        let row = |id: Option<i64>, amount: &str| Row {
+            values: vec![
+                id.map_or(CellValue::Null, CellValue::Integer),
+                CellValue::Text(amount.to_string()),
+            ],
+        };
+        QueryResults::new(
+            vec![
+                ColumnDef {
+                    name: "id".to_string(),
+                    data_type: DataType::Integer,
+                    nullable: true,
+                },
+                ColumnDef {
+                    name: "amount".to_string(),
+                    data_type: DataType::Numeric,
+                    nullable: false,
+                },
+            ],
+            vec![
+                row(Some(10), "9.5"),
+                row(None, "100"),
+                row(Some(2), "20"),
+                row(Some(7), "-3"),
+            ],
+            Duration::from_millis(1),
+            4,
+        )
+    }
+
+    fn column_ints(viewer: &ResultsViewer) -> Vec<Option<i64>> {
+        viewer
+            .results()
+            .unwrap()
+            .rows
+            .iter()
+            .map(|r| match r.values[0] {
+                CellValue::Integer(i) => Some(i),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_by_selected_column_toggles_direction() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+
+        viewer.sort_by_selected_column();
+        assert_eq!(viewer.sort(), Some((0, SortOrder::Ascending)));
+        assert_eq!(column_ints(&viewer), vec![Some(2), Some(7), Some(10), None]);
+
+        viewer.sort_by_selected_column();
+        assert_eq!(viewer.sort(), Some((0, SortOrder::Descending)));
+        assert_eq!(column_ints(&viewer), vec![None, Some(10), Some(7), Some(2)]);
+    }
+
+    #[test]
+    fn test_sort_numeric_text_by_value() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.move_right();
+        viewer.sort_by_selected_column();
+        assert_eq!(column_ints(&viewer), vec![Some(7), Some(10), Some(2), None]);
+    }
+
+    #[test]
+    fn test_clear_sort_restores_original_order() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.sort_by_selected_column();
+        viewer.move_right();
+        viewer.sort_by_selected_column();
+        viewer.clear_sort();
+        assert_eq!(viewer.sort(), None);
+        assert_eq!(column_ints(&viewer), vec![Some(10), None, Some(2), Some(7)]);
+    }
+
+    #[test]
+    fn test_sort_keeps_selection_on_same_row() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.move_down(); // NULL id row
+        viewer.sort_by_selected_column();
+        assert_eq!(viewer.selected_row, 3);
+        viewer.clear_sort();
+        assert_eq!(viewer.selected_row, 1);
+    }
+
+    #[test]
+    fn test_set_results_clears_sort() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.sort_by_selected_column();
+        viewer.set_results(unsorted_results());
+        assert_eq!(viewer.sort(), None);
+        assert_eq!(column_ints(&viewer), vec![Some(10), None, Some(2), Some(7)]);
+    }
+
+    #[test]
+    fn test_row_filter_parse() {
+        assert_eq!(RowFilter::parse("   "), None);
+        assert_eq!(
+            RowFilter::parse("alice"),
+            Some(RowFilter::Contains("alice".to_string()))
+        );
+        assert_eq!(
+            RowFilter::parse("status = 'active'"),
+            Some(RowFilter::Compare {
+                column: "status".to_string(),
+                value: "active".to_string(),
+                negate: false,
+            })
+        );
+        assert_eq!(
+            RowFilter::parse("status!=active"),
+            Some(RowFilter::Compare {
+                column: "status".to_string(),
+                value: "active".to_string(),
+                negate: true,
+            })
+        );
+        // No column before '=' — plain text search
+        assert_eq!(
+            RowFilter::parse("=x"),
+            Some(RowFilter::Contains("=x".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_filter_substring_case_insensitive() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(sample_results());
+        viewer.set_filter("BOB").unwrap();
+        assert_eq!(viewer.filter_counts(), Some((1, 2)));
+        assert_eq!(viewer.selected_cell_text().as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_filter_column_compare() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.set_filter("id = null").unwrap();
+        assert_eq!(column_ints(&viewer), vec![None]);
+        viewer.set_filter("ID != 10").unwrap();
+        assert_eq!(column_ints(&viewer), vec![None, Some(2), Some(7)]);
+    }
+
+    #[test]
+    fn test_filter_unknown_column_keeps_previous_filter() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.set_filter("10").unwrap();
+        let err = viewer.set_filter("nope = 1").unwrap_err();
+        assert!(err.contains("nope"));
+        assert_eq!(
+            viewer.filter(),
+            Some(&RowFilter::Contains("10".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_clear_filter_restores_rows_in_sort_order() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.set_filter("id != 2").unwrap();
+        viewer.sort_by_selected_column();
+        assert_eq!(column_ints(&viewer), vec![Some(7), Some(10), None]);
+        assert!(viewer.clear_filter());
+        assert_eq!(viewer.filter_counts(), None);
+        assert_eq!(column_ints(&viewer), vec![Some(2), Some(7), Some(10), None]);
+        assert!(!viewer.clear_filter());
+    }
+
+    #[test]
+    fn test_filter_blank_input_clears() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.set_filter("7").unwrap();
+        viewer.set_filter("  ").unwrap();
+        assert!(viewer.filter().is_none());
+        assert_eq!(viewer.row_count(), 4);
+    }
+
+    #[test]
+    fn test_search_finds_cells_and_wraps() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.set_search("0");
+        // 10 (row 0 col 0), 100 (row 1 col 1), 20 (row 2 col 1)
+        assert_eq!(viewer.search_match_count(), 3);
+        assert_eq!(viewer.find_next(), Some((2, 3)));
+        assert_eq!((viewer.selected_row, viewer.selected_col), (1, 1));
+        assert_eq!(viewer.find_next(), Some((3, 3)));
+        assert_eq!(viewer.find_next(), Some((1, 3)));
+        assert_eq!((viewer.selected_row, viewer.selected_col), (0, 0));
+        assert_eq!(viewer.find_prev(), Some((3, 3)));
+        assert_eq!((viewer.selected_row, viewer.selected_col), (2, 1));
+    }
+
+    #[test]
+    fn test_search_no_matches() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.set_search("zzz");
+        assert_eq!(viewer.find_next(), None);
+        assert_eq!(viewer.find_prev(), None);
+        assert_eq!((viewer.selected_row, viewer.selected_col), (0, 0));
+    }
+
+    #[test]
+    fn test_search_follows_sort_and_clears() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.set_search("-3");
+        assert!(viewer.is_search_match(3, 1));
+        viewer.sort_by_selected_column();
+        // id 7 row moves to position 1 after sorting by id
+        assert!(viewer.is_search_match(1, 1));
+        assert!(viewer.clear_search());
+        assert_eq!(viewer.search_match_count(), 0);
+        assert!(!viewer.clear_search());
+    }
+
+    #[test]
+    fn test_cell_edit_refreshes_row_under_sort() {
+        use crate::db::provenance::{ResultSource, SourceColumn};
+
+        let mut results = unsorted_results();
+        let source_col = |name: &str, type_name: &str| {
+            Some(SourceColumn {
+                name: name.to_string(),
+                type_name: type_name.to_string(),
+            })
+        };
+        results.source = ResultSource::new(
+            "public".to_string(),
+            "orders".to_string(),
+            vec![source_col("id", "integer"), source_col("amount", "numeric")],
+            &["id".to_string()],
+        );
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(results);
+        viewer.sort_by_selected_column();
+        // Sorted by id: 2, 7, 10, NULL — edit amount of id 7
+        viewer.selected_row = 1;
+        viewer.selected_col = 1;
+        assert_eq!(
+            viewer.edit_target(),
+            Ok(("amount".to_string(), Some("-3".to_string())))
+        );
+        let update = viewer.begin_edit(Some("4")).unwrap();
+        assert_eq!(update.params[1].as_deref(), Some("7"));
+
+        assert!(viewer.finish_edit(Some(Row {
+            values: vec![CellValue::Integer(7), CellValue::Text("4".to_string())],
+        })));
+        assert_eq!(viewer.selected_cell_text().as_deref(), Some("4"));
+        assert!(viewer.is_edited(1, 1));
+        assert!(!viewer.is_edited(0, 1));
+
+        // NULL key can't identify its row
+        viewer.selected_row = 3;
+        assert!(viewer.begin_edit(Some("1")).is_err());
+    }
+
+    #[test]
+    fn test_set_results_clears_search() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(unsorted_results());
+        viewer.set_search("7");
+        viewer.set_results(unsorted_results());
+        assert_eq!(viewer.search(), None);
+        assert_eq!(viewer.search_match_count(), 0);
+    }
+
+    fn grid_results(cols: usize, rows: usize) -> QueryResults {
+        QueryResults::new(
+            (0..cols)
+                .map(|c| ColumnDef {
+                    name: format!("c{}", c),
+                    data_type: DataType::Integer,
+                    nullable: false,
+                })
+                .collect(),
+            (0..rows)
+                .map(|r| Row {
+                    values: (0..cols)
+                        .map(|c| CellValue::Integer((r * cols + c) as i64))
+                        .collect(),
+                })
+                .collect(),
+            Duration::from_millis(1),
+            rows,
+        )
+    }
+
+    /// Render the viewer into a `width` x `height` buffer, returning its lines
+    fn render_lines(viewer: &ResultsViewer, width: u16, height: u16) -> Vec<String> {
+        let mut terminal =
+            Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|f| viewer.render(f, f.area(), true, &Theme::default()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_render_draws_header_cells_and_truncation_marker() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(wide_results());
+        let lines = render_lines(&viewer, 30, 5);
+        assert!(lines[0].starts_with("note: text"));
+        assert!(lines[1].starts_with("short "));
+        assert!(lines[2].ends_with(TRUNCATION_MARKER));
+        assert!(lines[4].starts_with("Row 1/2"));
+    }
+
+    #[test]
+    fn test_render_uses_locale_decimal_mark() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_number_format(NumberFormat::parse("de").unwrap());
+        viewer.set_results(QueryResults::new(
+            vec![
+                ColumnDef {
+                    name: "price".to_string(),
+                    data_type: DataType::Numeric,
+                    nullable: false,
+                },
+                ColumnDef {
+                    name: "sku".to_string(),
+                    data_type: DataType::Text,
+                    nullable: false,
+                },
+            ],
+            vec![Row {
+                values: vec![
+                    CellValue::Text("3.14".to_string()),
+                    CellValue::Text("A.1".to_string()),
+                ],
+            }],
+            Duration::from_millis(1),
+            1,
+        ));
+        let lines = render_lines(&viewer, 30, 4);
+        assert!(lines[1].starts_with("3,14"), "{}", lines[1]);
+        assert!(lines[1].contains("A.1"), "text columns are left alone");
+        // Copies keep PostgreSQL's format
+        assert_eq!(viewer.selected_cell_text().as_deref(), Some("3.14"));
+    }
+
+    #[test]
+    fn test_viewport_stays_put_while_selection_moves_inside_it() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(grid_results(3, 50));
+        // 10 rows tall: header + 8 data rows + footer
+        for _ in 0..20 {
+            viewer.move_down();
+        }
+        render_lines(&viewer, 40, 10);
+        assert_eq!(viewer.scroll_offset.get(), 13);
+
+        viewer.move_up();
+        let lines = render_lines(&viewer, 40, 10);
+        assert_eq!(
+            viewer.scroll_offset.get(),
+            13,
+            "moving up inside the window"
+        );
+        assert!(lines[1].starts_with("39 "), "first data row is row 13");
+    }
+
+    #[test]
+    fn test_horizontal_viewport_follows_selection() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(grid_results(200, 3));
+        viewer.go_to_end();
+        let lines = render_lines(&viewer, 40, 5);
+        let h_scroll = viewer.h_scroll_offset.get();
+        assert!(h_scroll > 190);
+        assert!(lines[0].contains("c199"));
+
+        // Stepping left stays within the window
+        viewer.move_left();
+        render_lines(&viewer, 40, 5);
+        assert_eq!(viewer.h_scroll_offset.get(), h_scroll);
+    }
+
+    #[test]
+    fn test_page_right_and_left_move_a_screen_of_columns() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(grid_results(200, 3));
+        render_lines(&viewer, 40, 5);
+        let per_page = viewer.visible_columns(0, 40).len() - 1; // last is clipped
+
+        viewer.page_right();
+        let lines = render_lines(&viewer, 40, 5);
+        assert_eq!(viewer.selected_column(), per_page);
+        assert_eq!(viewer.h_scroll_offset.get(), per_page);
+        assert!(lines[0].starts_with(&format!("c{}:", per_page)));
+
+        viewer.page_left();
+        render_lines(&viewer, 40, 5);
+        assert_eq!(viewer.selected_column(), 0);
+        assert_eq!(viewer.h_scroll_offset.get(), 0);
+        viewer.page_left();
+        assert_eq!(viewer.selected_column(), 0);
+
+        // Paging right stops on the last column with a full last page
+        for _ in 0..100 {
+            viewer.page_right();
+            render_lines(&viewer, 40, 5);
+        }
+        assert_eq!(viewer.selected_column(), 199);
+        let lines = render_lines(&viewer, 40, 5);
+        assert!(lines[0].contains("c199"));
+        assert!(!lines[0].trim_start().starts_with("c199"), "page is full");
+    }
+
+    #[test]
+    fn test_find_column_prefers_exact_then_prefix_then_fuzzy() {
+        let mut viewer = ResultsViewer::new();
+        let column = |name: &str| ColumnDef {
+            name: name.to_string(),
+            data_type: DataType::Text,
+            nullable: true,
+        };
+        viewer.set_results(QueryResults::new(
+            vec![
+                column("user_id"),
+                column("id"),
+                column("Email_Address"),
+                column("created_at"),
+            ],
+            vec![],
+            Duration::from_millis(1),
+            0,
+        ));
+        assert_eq!(viewer.find_column("id"), Some(1));
+        assert_eq!(viewer.find_column("EM"), Some(2));
+        assert_eq!(viewer.find_column("_at"), Some(3));
+        assert_eq!(viewer.find_column("uid"), Some(0));
+        assert_eq!(viewer.find_column("zzz"), None);
+        assert_eq!(viewer.find_column("  "), None);
+    }
+
+    #[test]
+    fn test_cell_cache_holds_only_the_visible_window() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(grid_results(200, 5000));
+        render_lines(&viewer, 80, 12);
+        {
+            let cache = viewer.cell_cache.borrow();
+            assert_eq!(cache.rows.len(), 10);
+            assert!(cache.layout.len() < 20, "only on-screen columns are fitted");
+        }
+
+        viewer.go_to_bottom();
+        render_lines(&viewer, 80, 12);
+        let cache = viewer.cell_cache.borrow();
+        assert_eq!(cache.rows.len(), 10);
+        assert!(cache.rows.contains_key(&4999));
+        assert!(!cache.rows.contains_key(&0));
+    }
+
+    #[test]
+    fn test_cell_cache_refits_after_resize_and_data_change() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(wide_results());
+        render_lines(&viewer, 80, 5);
+        let fitted = |viewer: &ResultsViewer| viewer.cell_cache.borrow().rows[&1][0].clone();
+        let (before, truncated) = fitted(&viewer);
+        assert!(truncated);
+
+        viewer.widen_column();
+        render_lines(&viewer, 80, 5);
+        let (after, _) = fitted(&viewer);
+        assert!(after.len() > before.len());
+
+        viewer.set_results(sample_results());
+        assert!(viewer.cell_cache.borrow().rows.is_empty());
+    }
+
     #[test]
     fn test_resize_noop_without_results() {
         let mut viewer = ResultsViewer::new();
@@ -1049,4 +2696,33 @@ mod tests {
         viewer.reset_column_widths();
         assert!(viewer.col_widths.is_empty());
     }
+
+    #[test]
+    fn test_mask_and_reveal_under_filter() {
+        let mut viewer = ResultsViewer::new();
+        viewer.set_results(sample_results());
+        viewer.set_filter("BOB").unwrap();
+        viewer.mask_columns(vec![1]);
+        assert_eq!(viewer.masked_columns(), &[1]);
+        // The filter now sees only the placeholder
+        assert_eq!(viewer.filter_counts(), Some((0, 2)));
+        assert!(viewer.edit_target().is_err());
+
+        viewer.clear_filter();
+        viewer.move_right();
+        assert_eq!(
+            viewer.selected_cell_text().as_deref(),
+            Some(crate::mask::MASK)
+        );
+        let export = crate::export::to_csv(viewer.results().unwrap());
+        assert!(!export.contains("Alice"), "{}", export);
+
+        viewer.set_filter("BOB").unwrap();
+        viewer.reveal();
+        assert!(viewer.masked_columns().is_empty());
+        assert_eq!(viewer.filter_counts(), Some((1, 2)));
+        assert_eq!(viewer.selected_cell_text().as_deref(), Some("Bob"));
+        viewer.clear_filter();
+        assert_eq!(column_ints(&viewer), vec![Some(1), Some(2)]);
+    }
 }
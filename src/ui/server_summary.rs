@@ -0,0 +1,210 @@
+//! Server summary shown on connect
+//!
+//! A few first-glance checks of a server just connected to: its version,
+//! round-trip latency, whether it's a primary or a standby, the database's
+//! cache hit ratio, and the oldest open transaction. Values worth a second
+//! look are highlighted. Esc dismisses it.
+
+use crate::db::ServerSummary;
+use crate::ui::jobs::format_elapsed;
+use crate::ui::theme::Theme;
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+use std::time::Duration;
+
+/// Cache hit ratio below which it's highlighted, in percent
+const LOW_HIT_RATIO: f64 = 90.0;
+/// Transaction age above which it's highlighted
+const OLD_TRANSACTION: Duration = Duration::from_secs(5 * 60);
+/// Latency above which it's highlighted
+const SLOW_LATENCY: Duration = Duration::from_millis(100);
+/// Standby replay lag above which it's highlighted
+const REPLAY_LAG: Duration = Duration::from_secs(60);
+
+/// Width of the label column
+const LABEL_WIDTH: usize = 14;
+
+/// The summary popup
+pub struct ServerSummaryPopup {
+    summary: Option<ServerSummary>,
+    visible: bool,
+}
+
+impl ServerSummaryPopup {
+    pub fn new() -> Self {
+        Self {
+            summary: None,
+            visible: false,
+        }
+    }
+
+    /// Show the checks for a new connection
+    pub fn show(&mut self, summary: ServerSummary) {
+        self.summary = Some(summary);
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Lines as `(label, value, needs a look)`
+    fn lines(&self) -> Vec<(&'static str, String, bool)> {
+        let Some(ref s) = self.summary else {
+            return Vec::new();
+        };
+        let mut lines = vec![
+            ("Server", format!("PostgreSQL {}", s.version), false),
+            (
+                "Latency",
+                format!("{:.1} ms", s.latency.as_secs_f64() * 1000.0),
+                s.latency > SLOW_LATENCY,
+            ),
+        ];
+
+        lines.push(if s.standby {
+            match s.replay_lag {
+                Some(lag) => (
+                    "Role",
+                    format!("standby, last replay {} ago", format_elapsed(lag)),
+                    lag > REPLAY_LAG,
+                ),
+                None => ("Role", "standby, nothing replayed yet".to_string(), true),
+            }
+        } else {
+            ("Role", "primary".to_string(), false)
+        });
+
+        if let Some(ref db) = s.database {
+            let (text, low) = match db.hit_ratio() {
+                Some(ratio) => (
+                    format!("{:.2}% in {}", ratio, db.name),
+                    ratio < LOW_HIT_RATIO,
+                ),
+                None => (format!("no reads yet in {}", db.name), false),
+            };
+            lines.push(("Cache hits", text, low));
+        }
+
+        lines.push(match s.oldest_transaction {
+            Some(ref q) => {
+                let age = q.duration.unwrap_or_default();
+                let query = q
+                    .query
+                    .as_deref()
+                    .unwrap_or("")
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                (
+                    "Oldest xact",
+                    format!(
+                        "{} open \u{2014} pid {} ({}): {}",
+                        format_elapsed(age),
+                        q.pid,
+                        q.user.as_deref().unwrap_or("?"),
+                        query
+                    ),
+                    age > OLD_TRANSACTION,
+                )
+            }
+            None => ("Oldest xact", "none open".to_string(), false),
+        });
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let lines: Vec<Line> = self
+            .lines()
+            .into_iter()
+            .map(|(label, text, warn)| {
+                let style = if warn {
+                    theme.status_warning
+                } else {
+                    theme.help_desc
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:<LABEL_WIDTH$}", label), theme.help_key),
+                    Span::styled(text, style),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+impl Default for ServerSummaryPopup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::health::{DatabaseStats, ServerQuery};
+
+    fn summary() -> ServerSummary {
+        ServerSummary {
+            version: "16.2".to_string(),
+            latency: Duration::from_micros(1800),
+            standby: false,
+            replay_lag: None,
+            database: Some(DatabaseStats {
+                name: "shop".to_string(),
+                blocks_hit: 80,
+                blocks_read: 20,
+                ..DatabaseStats::default()
+            }),
+            oldest_transaction: Some(ServerQuery {
+                pid: 4312,
+                user: Some("app".to_string()),
+                duration: Some(Duration::from_secs(3600)),
+                query: Some("UPDATE orders\n SET total = 0".to_string()),
+                blocked_by: Vec::new(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_summary_lines_flag_what_needs_a_look() {
+        let mut popup = ServerSummaryPopup::new();
+        popup.show(summary());
+        assert_eq!(
+            popup.lines(),
+            vec![
+                ("Server", "PostgreSQL 16.2".to_string(), false),
+                ("Latency", "1.8 ms".to_string(), false),
+                ("Role", "primary".to_string(), false),
+                ("Cache hits", "80.00% in shop".to_string(), true),
+                (
+                    "Oldest xact",
+                    "1:00:00 open \u{2014} pid 4312 (app): UPDATE orders SET total = 0".to_string(),
+                    true
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_standby_and_quiet_server() {
+        let mut popup = ServerSummaryPopup::new();
+        popup.show(ServerSummary {
+            standby: true,
+            replay_lag: Some(Duration::from_secs(2)),
+            database: None,
+            oldest_transaction: None,
+            ..summary()
+        });
+        let lines = popup.lines();
+        assert_eq!(
+            lines[2],
+            ("Role", "standby, last replay 0:02 ago".to_string(), false)
+        );
+        assert_eq!(lines[3], ("Oldest xact", "none open".to_string(), false));
+    }
+}
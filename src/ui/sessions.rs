@@ -0,0 +1,240 @@
+//! Server sessions overlay
+//!
+//! `/sessions` lists the server's other client backends from
+//! `pg_stat_activity`: state, how long they've been at it, what they're
+//! waiting on, and their query. The selected one can be cancelled or
+//! terminated. Follows the same overlay pattern as MessageLog.
+
+use crate::db::Session;
+use crate::ui::jobs::format_elapsed;
+use crate::ui::theme::Theme;
+use crate::ui::unicode::{pad_to_width, truncate_to_width};
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+/// Selectable list of server sessions
+pub struct SessionList {
+    sessions: Vec<Session>,
+    selected: usize,
+    /// Sessions have been fetched at least once
+    loaded: bool,
+    /// Session the next terminate key press ends (the first press only
+    /// arms it)
+    armed_terminate: Option<i32>,
+    visible: bool,
+}
+
+impl SessionList {
+    pub fn new() -> Self {
+        Self {
+            sessions: Vec::new(),
+            selected: 0,
+            loaded: false,
+            armed_terminate: None,
+            visible: false,
+        }
+    }
+
+    /// Replace the list, keeping the same session selected if it's still
+    /// there
+    pub fn set_sessions(&mut self, sessions: Vec<Session>) {
+        let pid = self.selected().map(|s| s.pid);
+        self.selected = pid
+            .and_then(|pid| sessions.iter().position(|s| s.pid == pid))
+            .unwrap_or(0);
+        self.sessions = sessions;
+        self.loaded = true;
+        self.armed_terminate = None;
+    }
+
+    pub fn selected(&self) -> Option<&Session> {
+        self.sessions.get(self.selected)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Arm terminating `pid`, or return true when it was already armed
+    pub fn confirm_terminate(&mut self, pid: i32) -> bool {
+        if self.armed_terminate == Some(pid) {
+            self.armed_terminate = None;
+            return true;
+        }
+        self.armed_terminate = Some(pid);
+        false
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+        self.armed_terminate = None;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.armed_terminate = None;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.move_by(-1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.move_by(1);
+    }
+
+    pub fn page_up(&mut self) {
+        self.move_by(-20);
+    }
+
+    pub fn page_down(&mut self) {
+        self.move_by(20);
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.move_by(isize::MIN);
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.move_by(isize::MAX);
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let last = self.sessions.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+        self.armed_terminate = None;
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if area.height == 0 {
+            return;
+        }
+
+        if self.sessions.is_empty() {
+            let message = if self.loaded {
+                "No other sessions"
+            } else {
+                "Loading sessions..."
+            };
+            frame.render_widget(Paragraph::new(message).style(theme.help_desc), area);
+            return;
+        }
+
+        let header = Line::from(Span::styled(
+            format!(
+                "{:>7}  {:<12} {:<10} {:>8}  {:<18} query",
+                "pid", "user", "state", "time", "waiting on"
+            ),
+            theme.results_header,
+        ));
+        frame.render_widget(
+            Paragraph::new(header),
+            Rect::new(area.x, area.y, area.width, 1),
+        );
+
+        let list_height = area.height.saturating_sub(1) as usize;
+        // Scroll just far enough to keep the selection on screen
+        let offset = (self.selected + 1).saturating_sub(list_height);
+        for (row, (i, session)) in self
+            .sessions
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(list_height)
+            .enumerate()
+        {
+            let state = session.state.as_deref().unwrap_or("?");
+            let state = state.replace("idle in transaction", "idle in tx");
+            let query = session
+                .query
+                .as_deref()
+                .unwrap_or("")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let cell =
+                |text: &str, width: usize| pad_to_width(&truncate_to_width(text, width), width);
+            let text = format!(
+                "{:>7}  {} {} {:>8}  {} {}",
+                session.pid,
+                cell(session.user.as_deref().unwrap_or(""), 12),
+                cell(&state, 10),
+                session.duration.map(format_elapsed).unwrap_or_default(),
+                cell(session.wait_event.as_deref().unwrap_or(""), 18),
+                query
+            );
+            let style = if i == self.selected {
+                theme.dialog_selected
+            } else if state == "active" {
+                theme.help_key
+            } else {
+                theme.help_desc
+            };
+            frame.render_widget(
+                Paragraph::new(Line::from(Span::styled(text, style))),
+                Rect::new(area.x, area.y + 1 + row as u16, area.width, 1),
+            );
+        }
+    }
+}
+
+impl Default for SessionList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(pid: i32) -> Session {
+        Session {
+            pid,
+            user: Some("app".to_string()),
+            database: Some("shop".to_string()),
+            application: None,
+            client: None,
+            state: Some("active".to_string()),
+            wait_event: None,
+            duration: None,
+            query: Some("SELECT 1".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_refresh_keeps_selected_session() {
+        let mut list = SessionList::new();
+        list.set_sessions(vec![session(10), session(20), session(30)]);
+        list.scroll_down();
+        assert_eq!(list.selected().unwrap().pid, 20);
+
+        list.set_sessions(vec![session(5), session(20)]);
+        assert_eq!(list.selected().unwrap().pid, 20);
+
+        // Gone: back to the top
+        list.set_sessions(vec![session(5), session(30)]);
+        assert_eq!(list.selected().unwrap().pid, 5);
+    }
+
+    #[test]
+    fn test_terminate_needs_a_second_press() {
+        let mut list = SessionList::new();
+        list.set_sessions(vec![session(10), session(20)]);
+        assert!(!list.confirm_terminate(10));
+        assert!(list.confirm_terminate(10));
+
+        // Moving the selection disarms it
+        assert!(!list.confirm_terminate(10));
+        list.scroll_down();
+        assert!(!list.confirm_terminate(10));
+    }
+}
@@ -0,0 +1,242 @@
+//! Table preview overlay
+//!
+//! Lists the columns of the table or view named under the editor cursor,
+//! straight from the loaded schema tree, so a query can be written against
+//! a table without switching to the tree to look its columns up.
+//! Follows the same overlay pattern as ColumnHistogram.
+
+use crate::db::schema::Table;
+use crate::ui::theme::Theme;
+use crate::ui::unicode::{display_width, pad_to_width, truncate_to_width};
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+/// Widest column-name label, in terminal columns
+const MAX_NAME_WIDTH: usize = 40;
+
+/// One column of the previewed table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewColumn {
+    pub name: String,
+    pub data_type: String,
+    pub is_primary_key: bool,
+    /// Referenced `table.column`, for a foreign key
+    pub references: Option<String>,
+}
+
+/// The columns of one table or view
+pub struct TablePreview {
+    /// Qualified name (`schema.table`)
+    name: String,
+    is_view: bool,
+    columns: Vec<PreviewColumn>,
+    visible: bool,
+    scroll_offset: usize,
+}
+
+impl TablePreview {
+    pub fn new() -> Self {
+        Self {
+            name: String::new(),
+            is_view: false,
+            columns: Vec::new(),
+            visible: false,
+            scroll_offset: 0,
+        }
+    }
+
+    /// Show the columns of `table` in `schema`
+    pub fn show(&mut self, schema: &str, table: &Table, is_view: bool) {
+        self.name = format!("{}.{}", schema, table.name);
+        self.is_view = is_view;
+        self.columns = table
+            .columns
+            .iter()
+            .map(|c| PreviewColumn {
+                name: c.name.clone(),
+                data_type: c.data_type.display_name(),
+                is_primary_key: c.is_primary_key,
+                references: c
+                    .foreign_key
+                    .as_ref()
+                    .map(|fk| format!("{}.{}", fk.target_table, fk.target_column)),
+            })
+            .collect();
+        self.visible = true;
+        self.scroll_offset = 0;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.columns.clear();
+        self.scroll_offset = 0;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Qualified name of the previewed table
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_view(&self) -> bool {
+        self.is_view
+    }
+
+    pub fn columns(&self) -> &[PreviewColumn] {
+        &self.columns
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        if self.scroll_offset + 1 < self.columns.len() {
+            self.scroll_offset += 1;
+        }
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(20);
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_offset = (self.scroll_offset + 20).min(self.columns.len().saturating_sub(1));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_offset = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_offset = self.columns.len().saturating_sub(1);
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if area.height == 0 {
+            return;
+        }
+
+        if self.columns.is_empty() {
+            frame.render_widget(Paragraph::new("No columns").style(theme.help_desc), area);
+            return;
+        }
+
+        let name_width = self
+            .columns
+            .iter()
+            .map(|c| display_width(&c.name))
+            .max()
+            .unwrap_or(0)
+            .min(MAX_NAME_WIDTH)
+            .min(area.width as usize / 2);
+        let type_width = self
+            .columns
+            .iter()
+            .map(|c| display_width(&c.data_type))
+            .max()
+            .unwrap_or(0);
+
+        for (i, column) in self
+            .columns
+            .iter()
+            .skip(self.scroll_offset)
+            .take(area.height as usize)
+            .enumerate()
+        {
+            let mut spans = vec![
+                Span::styled(
+                    if column.is_primary_key { "* " } else { "  " },
+                    theme.help_key,
+                ),
+                Span::styled(
+                    pad_to_width(&truncate_to_width(&column.name, name_width), name_width),
+                    theme.tree_column,
+                ),
+                Span::raw("  "),
+                Span::styled(pad_to_width(&column.data_type, type_width), theme.help_desc),
+            ];
+            if let Some(target) = &column.references {
+                spans.push(Span::styled(
+                    format!("  \u{2192} {}", target),
+                    theme.help_key,
+                ));
+            }
+            frame.render_widget(
+                Paragraph::new(Line::from(spans)),
+                Rect::new(area.x, area.y + i as u16, area.width, 1),
+            );
+        }
+    }
+}
+
+impl Default for TablePreview {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::{Column, ForeignKey};
+    use crate::db::types::DataType;
+
+    fn orders() -> Table {
+        Table {
+            name: "orders".to_string(),
+            columns: vec![
+                Column {
+                    name: "id".to_string(),
+                    data_type: DataType::Integer,
+                    is_primary_key: true,
+                    foreign_key: None,
+                },
+                Column {
+                    name: "customer_id".to_string(),
+                    data_type: DataType::Integer,
+                    is_primary_key: false,
+                    foreign_key: Some(ForeignKey {
+                        target_table: "customers".to_string(),
+                        target_column: "id".to_string(),
+                    }),
+                },
+            ],
+            row_count: Some(150),
+            size_bytes: None,
+            select_denied: false,
+        }
+    }
+
+    #[test]
+    fn test_show_lists_columns_with_keys() {
+        let mut preview = TablePreview::new();
+        preview.show("public", &orders(), false);
+        assert!(preview.is_visible());
+        assert_eq!(preview.name(), "public.orders");
+        assert!(!preview.is_view());
+        let columns = preview.columns();
+        assert_eq!(columns.len(), 2);
+        assert!(columns[0].is_primary_key);
+        assert_eq!(columns[0].data_type, "integer");
+        assert_eq!(columns[1].references.as_deref(), Some("customers.id"));
+
+        preview.hide();
+        assert!(!preview.is_visible());
+        assert!(preview.columns().is_empty());
+    }
+
+    #[test]
+    fn test_scroll_stays_within_columns() {
+        let mut preview = TablePreview::new();
+        preview.show("public", &orders(), false);
+        preview.scroll_to_bottom();
+        preview.scroll_down();
+        assert_eq!(preview.scroll_offset, 1);
+        preview.page_up();
+        assert_eq!(preview.scroll_offset, 0);
+    }
+}
@@ -74,9 +74,19 @@ pub struct Theme {
     pub editor_number: Style,
     pub editor_comment: Style,
     pub editor_ghost: Style,
+    pub editor_search_match: Style,
+    pub editor_search_current: Style,
     pub editor_cursor: Style,
     pub editor_line_number: Style,
     pub editor_tilde: Style,
+    /// Trailing whitespace and tabs
+    pub editor_whitespace: Style,
+    /// The `editor_ruler` column
+    pub editor_ruler: Style,
+    /// Vim visual-mode selection
+    pub editor_selection: Style,
+    /// The bracket under the cursor and its partner
+    pub editor_bracket_match: Style,
 
     // Results table
     pub results_header: Style,
@@ -85,6 +95,9 @@ pub struct Theme {
     pub results_row_odd: Style,
     pub results_selected: Style,
     pub results_null: Style,
+    pub results_truncated: Style,
+    pub results_search_match: Style,
+    pub results_edited: Style,
     pub results_empty: Style,
     pub results_error_title: Style,
     pub results_error_text: Style,
@@ -109,6 +122,9 @@ pub struct Theme {
     pub tab_active: Style,
     pub tab_inactive: Style,
     pub tab_separator: Style,
+    /// Color of a tab whose query has run past 10s / 60s
+    pub tab_slow: Style,
+    pub tab_stuck: Style,
 
     // Connection dialog
     pub dialog_label: Style,
@@ -128,6 +144,9 @@ pub struct Theme {
     pub status_txn_active: Style,
     pub status_txn_failed: Style,
     pub status_read_only: Style,
+    pub status_filter: Style,
+    /// Vim mode indicator
+    pub status_vim_mode: Style,
     pub status_confirm: Style,
 }
 
@@ -194,9 +213,17 @@ impl Theme {
             editor_number: Style::default().fg(Color::Cyan),
             editor_comment: Style::default().fg(Color::DarkGray),
             editor_ghost: Style::default().fg(Color::DarkGray),
+            editor_search_match: Style::default().fg(Color::White).bg(Color::DarkGray),
+            editor_search_current: Style::default().fg(Color::Black).bg(Color::Yellow),
             editor_cursor: Style::default().bg(Color::White).fg(Color::Black),
             editor_line_number: Style::default().fg(Color::DarkGray),
             editor_tilde: Style::default().fg(Color::DarkGray),
+            editor_whitespace: Style::default().bg(Color::Red),
+            editor_ruler: Style::default().bg(Color::Rgb(48, 48, 48)),
+            editor_selection: Style::default().bg(Color::Rgb(60, 60, 90)),
+            editor_bracket_match: Style::default()
+                .bg(Color::Rgb(70, 70, 70))
+                .add_modifier(bold),
             results_header: Style::default().fg(Color::Yellow).add_modifier(bold),
             results_header_selected: Style::default()
                 .fg(Color::Yellow)
@@ -207,6 +234,9 @@ impl Theme {
             results_null: Style::default()
                 .fg(Color::DarkGray)
                 .add_modifier(Modifier::ITALIC),
+            results_truncated: Style::default().fg(Color::DarkGray),
+            results_search_match: Style::default().fg(Color::Black).bg(Color::Yellow),
+            results_edited: Style::default().fg(Color::Green).add_modifier(bold),
             results_empty: Style::default().fg(Color::DarkGray),
             results_error_title: Style::default().fg(Color::Red).add_modifier(bold),
             results_error_text: Style::default().fg(Color::Red),
@@ -235,6 +265,8 @@ impl Theme {
                 .add_modifier(bold),
             tab_inactive: Style::default().fg(Color::DarkGray),
             tab_separator: Style::default().fg(Color::DarkGray),
+            tab_slow: Style::default().fg(Color::Yellow),
+            tab_stuck: Style::default().fg(Color::Red),
             status_success: Style::default().fg(Color::Green),
             status_error: Style::default().fg(Color::Red),
             status_info: Style::default().fg(Color::Blue),
@@ -253,6 +285,14 @@ impl Theme {
                 .fg(Color::White)
                 .bg(Color::Blue)
                 .add_modifier(bold),
+            status_filter: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(bold),
+            status_vim_mode: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Green)
+                .add_modifier(bold),
             status_confirm: Style::default().fg(Color::Yellow).add_modifier(bold),
         }
     }
@@ -306,9 +346,19 @@ impl Theme {
             editor_number: Style::default().fg(Color::Rgb(0, 120, 150)),
             editor_comment: Style::default().fg(Color::Gray),
             editor_ghost: Style::default().fg(Color::Gray),
+            editor_search_match: Style::default().bg(Color::Rgb(255, 240, 160)),
+            editor_search_current: Style::default()
+                .fg(Color::White)
+                .bg(Color::Rgb(200, 120, 0)),
             editor_cursor: Style::default().bg(Color::Rgb(30, 30, 30)).fg(Color::White),
             editor_line_number: Style::default().fg(Color::Gray),
             editor_tilde: Style::default().fg(Color::Gray),
+            editor_whitespace: Style::default().bg(Color::Rgb(255, 200, 200)),
+            editor_ruler: Style::default().bg(Color::Rgb(235, 235, 235)),
+            editor_selection: Style::default().bg(Color::Rgb(200, 215, 245)),
+            editor_bracket_match: Style::default()
+                .bg(Color::Rgb(210, 210, 210))
+                .add_modifier(bold),
             results_header: Style::default()
                 .fg(Color::Rgb(0, 0, 180))
                 .add_modifier(bold),
@@ -321,6 +371,13 @@ impl Theme {
             results_null: Style::default()
                 .fg(Color::Gray)
                 .add_modifier(Modifier::ITALIC),
+            results_truncated: Style::default().fg(Color::Gray),
+            results_search_match: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Rgb(255, 220, 100)),
+            results_edited: Style::default()
+                .fg(Color::Rgb(0, 130, 0))
+                .add_modifier(bold),
             results_empty: Style::default().fg(Color::Gray),
             results_error_title: Style::default()
                 .fg(Color::Rgb(180, 0, 0))
@@ -357,6 +414,8 @@ impl Theme {
                 .add_modifier(bold),
             tab_inactive: Style::default().fg(Color::Gray),
             tab_separator: Style::default().fg(Color::Gray),
+            tab_slow: Style::default().fg(Color::Rgb(200, 120, 0)),
+            tab_stuck: Style::default().fg(Color::Rgb(180, 0, 0)),
             status_success: Style::default().fg(Color::Rgb(0, 130, 0)),
             status_error: Style::default().fg(Color::Rgb(180, 0, 0)),
             status_info: Style::default().fg(Color::Blue),
@@ -375,6 +434,14 @@ impl Theme {
                 .fg(Color::White)
                 .bg(Color::Blue)
                 .add_modifier(bold),
+            status_filter: Style::default()
+                .fg(Color::White)
+                .bg(Color::Rgb(0, 130, 130))
+                .add_modifier(bold),
+            status_vim_mode: Style::default()
+                .fg(Color::White)
+                .bg(Color::Rgb(0, 130, 0))
+                .add_modifier(bold),
             status_confirm: Style::default()
                 .fg(Color::Rgb(200, 120, 0))
                 .add_modifier(bold),
@@ -428,11 +495,20 @@ impl Theme {
             editor_number: Style::default().fg(peach),
             editor_comment: Style::default().fg(dim),
             editor_ghost: Style::default().fg(dim),
+            editor_search_match: Style::default().bg(dim),
+            editor_search_current: Style::default().fg(Color::Rgb(20, 20, 40)).bg(lavender),
             editor_cursor: Style::default()
                 .bg(Color::Rgb(210, 215, 230))
                 .fg(Color::Rgb(20, 20, 40)),
             editor_line_number: Style::default().fg(dim),
             editor_tilde: Style::default().fg(dim),
+            editor_whitespace: Style::default().bg(pale_pink),
+            editor_ruler: Style::default().bg(Color::Rgb(35, 40, 60)),
+            editor_selection: Style::default().bg(Color::Rgb(50, 55, 90)),
+            editor_bracket_match: Style::default()
+                .fg(lavender)
+                .bg(Color::Rgb(60, 60, 100))
+                .add_modifier(bold),
             results_header: Style::default().fg(lavender).add_modifier(bold),
             results_header_selected: Style::default()
                 .fg(lavender)
@@ -441,6 +517,9 @@ impl Theme {
             results_row_odd: Style::default().fg(muted),
             results_selected: Style::default().fg(Color::Rgb(20, 20, 40)).bg(lavender),
             results_null: Style::default().fg(dim).add_modifier(Modifier::ITALIC),
+            results_truncated: Style::default().fg(dim),
+            results_search_match: Style::default().fg(Color::Rgb(20, 20, 40)).bg(peach),
+            results_edited: Style::default().fg(mint).add_modifier(bold),
             results_empty: Style::default().fg(dim),
             results_error_title: Style::default()
                 .fg(Color::Rgb(255, 100, 100))
@@ -471,6 +550,8 @@ impl Theme {
                 .add_modifier(bold),
             tab_inactive: Style::default().fg(dim),
             tab_separator: Style::default().fg(dim),
+            tab_slow: Style::default().fg(peach),
+            tab_stuck: Style::default().fg(Color::Rgb(255, 100, 100)),
             status_success: Style::default().fg(mint),
             status_error: Style::default().fg(Color::Rgb(255, 100, 100)),
             status_info: Style::default().fg(soft_blue),
@@ -489,6 +570,14 @@ impl Theme {
                 .fg(Color::White)
                 .bg(soft_blue)
                 .add_modifier(bold),
+            status_filter: Style::default()
+                .fg(Color::Rgb(20, 20, 40))
+                .bg(mint)
+                .add_modifier(bold),
+            status_vim_mode: Style::default()
+                .fg(Color::Rgb(20, 20, 40))
+                .bg(peach)
+                .add_modifier(bold),
             status_confirm: Style::default().fg(peach).add_modifier(bold),
         }
     }
@@ -536,9 +625,18 @@ impl Theme {
             editor_number: Style::default().fg(amber),
             editor_comment: Style::default().fg(dim),
             editor_ghost: Style::default().fg(dim),
+            editor_search_match: Style::default().bg(dim),
+            editor_search_current: Style::default().fg(coal).bg(amber),
             editor_cursor: Style::default().bg(sand).fg(coal),
             editor_line_number: Style::default().fg(dim),
             editor_tilde: Style::default().fg(dim),
+            editor_whitespace: Style::default().bg(warm_red),
+            editor_ruler: Style::default().bg(Color::Rgb(50, 42, 35)),
+            editor_selection: Style::default().bg(Color::Rgb(75, 60, 45)),
+            editor_bracket_match: Style::default()
+                .fg(amber)
+                .bg(Color::Rgb(85, 65, 45))
+                .add_modifier(bold),
             results_header: Style::default().fg(amber).add_modifier(bold),
             results_header_selected: Style::default()
                 .fg(amber)
@@ -547,6 +645,9 @@ impl Theme {
             results_row_odd: Style::default().fg(muted),
             results_selected: Style::default().fg(coal).bg(amber),
             results_null: Style::default().fg(dim).add_modifier(Modifier::ITALIC),
+            results_truncated: Style::default().fg(dim),
+            results_search_match: Style::default().fg(coal).bg(orange),
+            results_edited: Style::default().fg(sage).add_modifier(bold),
             results_empty: Style::default().fg(dim),
             results_error_title: Style::default().fg(warm_red).add_modifier(bold),
             results_error_text: Style::default().fg(warm_red),
@@ -569,6 +670,8 @@ impl Theme {
             tab_active: Style::default().fg(coal).bg(amber).add_modifier(bold),
             tab_inactive: Style::default().fg(dim),
             tab_separator: Style::default().fg(dim),
+            tab_slow: Style::default().fg(orange),
+            tab_stuck: Style::default().fg(warm_red),
             status_success: Style::default().fg(sage),
             status_error: Style::default().fg(warm_red),
             status_info: Style::default().fg(orange),
@@ -581,6 +684,8 @@ impl Theme {
                 .bg(warm_red)
                 .add_modifier(bold),
             status_read_only: Style::default().fg(coal).bg(orange).add_modifier(bold),
+            status_filter: Style::default().fg(coal).bg(sage).add_modifier(bold),
+            status_vim_mode: Style::default().fg(coal).bg(amber).add_modifier(bold),
             status_confirm: Style::default().fg(amber).add_modifier(bold),
         }
     }
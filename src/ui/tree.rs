@@ -1,15 +1,21 @@
 //! Database tree browser widget
 //!
-//! Displays database schemas, tables, views, functions, indexes, and columns
-//! in a hierarchical tree grouped by category.
+//! Displays database schemas, tables, views, functions, indexes, sequences,
+//! types, and columns in a hierarchical tree grouped by category, with the
+//! installed extensions and the server's databases listed below the schemas.
 
 use crate::config::SavedQuery;
-use crate::db::schema::SchemaTree;
+use crate::db::ddl::DdlTarget;
+use crate::db::schema::{Schema, SchemaTree, Table};
+use crate::sql::{quote_ident, quote_qualified};
 use crate::ui::Component;
+use crate::ui::numbers::NumberFormat;
 use crate::ui::theme::Theme;
+use crate::ui::unicode::{next_grapheme, prev_grapheme};
 use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
 /// Node kind in the flattened tree
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,9 +27,16 @@ enum NodeKind {
     Column,
     Function,
     Index,
+    Sequence,
+    EnumType,
+    Domain,
     LoadMore,
     SavedQueryHeader,
     SavedQuery,
+    ExtensionHeader,
+    Extension,
+    DatabaseHeader,
+    Database,
 }
 
 /// A single item in the flattened tree view
@@ -71,6 +84,13 @@ pub struct TreeBrowser {
     searching: bool,
     /// Saved queries for the current connection (shown at top of tree)
     saved_queries: Vec<SavedQuery>,
+    /// Whether the schema came from the disk cache and hasn't been refreshed
+    stale: bool,
+    /// Schemas whose objects are being fetched, with when the load started
+    /// (drives the spinner)
+    loading: HashMap<String, Instant>,
+    /// How row estimates and sizes are written (`number_locale`)
+    numbers: NumberFormat,
 }
 
 impl TreeBrowser {
@@ -99,10 +119,15 @@ impl TreeBrowser {
             pre_search_schema: None,
             searching: false,
             saved_queries: Vec::new(),
+            stale: false,
+            loading: HashMap::new(),
+            numbers: NumberFormat::default(),
         }
     }
 
     pub fn set_schema(&mut self, schema: SchemaTree) {
+        self.stale = false;
+        self.loading.clear();
         self.schema = Some(schema);
         self.selected = 0;
         self.scroll_offset = 0;
@@ -118,6 +143,110 @@ impl TreeBrowser {
         self.rebuild_items();
     }
 
+    /// Mark the shown schema as a disk-cached copy awaiting refresh
+    pub fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+
+    /// Whether the shown schema is a cached copy awaiting refresh
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Replace a cached schema with a freshly introspected one without
+    /// disturbing navigation: expanded nodes are kept, the selection is
+    /// clamped, and an active search keeps showing its results.
+    pub fn refresh_schema(&mut self, mut schema: SchemaTree) {
+        self.stale = false;
+        if let Some(old) = self.pre_search_schema.take() {
+            carry_over_objects(old, &mut schema);
+            self.pre_search_schema = Some(schema);
+            return;
+        }
+        if let Some(old) = self.schema.take() {
+            carry_over_objects(old, &mut schema);
+        }
+        self.schema = Some(schema);
+        self.rebuild_items();
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+    }
+
+    /// Expanded schemas whose objects haven't been fetched yet. They are
+    /// marked as loading, so each one is only requested once.
+    pub fn take_pending_loads(&mut self) -> Vec<String> {
+        let Some(tree) = self.pre_search_schema.as_ref().or(self.schema.as_ref()) else {
+            return Vec::new();
+        };
+        let pending: Vec<String> = tree
+            .schemas
+            .iter()
+            .filter(|s| {
+                !s.loaded && self.expanded.contains(&s.name) && !self.loading.contains_key(&s.name)
+            })
+            .map(|s| s.name.clone())
+            .collect();
+        let now = Instant::now();
+        for name in &pending {
+            self.loading.insert(name.clone(), now);
+        }
+        pending
+    }
+
+    /// Whether any schema's objects are being fetched
+    pub fn is_loading(&self) -> bool {
+        !self.loading.is_empty()
+    }
+
+    /// Fill in a schema's objects once they've been fetched, keeping the
+    /// selection on the same node
+    pub fn apply_loaded_schema(&mut self, loaded: Schema) {
+        self.loading.remove(&loaded.name);
+        let name = loaded.name.clone();
+        let tree = match self.pre_search_schema.as_mut() {
+            Some(tree) => tree,
+            None => match self.schema.as_mut() {
+                Some(tree) => tree,
+                None => return,
+            },
+        };
+        let Some(position) = tree.schemas.items.iter().position(|s| s.name == name) else {
+            return;
+        };
+        let has_tables = !loaded.tables.is_empty();
+        tree.schemas.items[position] = loaded;
+        if self.pre_search_schema.is_some() {
+            return;
+        }
+
+        // Like set_schema, open the first schema's tables when it arrives
+        let category_open = self.expanded.iter().any(|p| {
+            p.strip_prefix(&name)
+                .is_some_and(|rest| rest.starts_with('.'))
+        });
+        if position == 0 && has_tables && !category_open && self.expanded.contains(&name) {
+            self.expanded.insert(format!("{}.Tables", name));
+        }
+
+        let selected_path = self.items.get(self.selected).map(|i| i.path.clone());
+        if !self.filter_text.is_empty() {
+            self.compute_filter_matches();
+        }
+        self.rebuild_items();
+        if let Some(index) =
+            selected_path.and_then(|path| self.items.iter().position(|i| i.path == path))
+        {
+            self.selected = index;
+        }
+    }
+
+    /// A schema's objects failed to load: collapse it so expanding it again
+    /// retries
+    pub fn schema_load_failed(&mut self, name: &str) {
+        self.loading.remove(name);
+        self.expanded.remove(name);
+        self.rebuild_items();
+    }
+
     fn rebuild_items(&mut self) {
         self.items.clear();
         let schema_tree = match &self.schema {
@@ -142,8 +271,7 @@ impl TreeBrowser {
             if self.expanded.contains(&sq_path) {
                 for q in &self.saved_queries {
                     let item_path = format!("__saved_queries__.{}", q.name);
-                    let matches =
-                        !filter_lower.is_empty() && q.name.to_lowercase().contains(&filter_lower);
+                    let matches = !filter_lower.is_empty() && fuzzy_match(&q.name, &filter_lower);
                     self.items.push(TreeItem {
                         label: q.name.clone(),
                         kind: NodeKind::SavedQuery,
@@ -162,10 +290,14 @@ impl TreeBrowser {
 
         for schema in &schema_tree.schemas {
             let schema_path = schema.name.clone();
-            let has_children = !schema.tables.items.is_empty()
+            let has_children = !schema.loaded
+                || !schema.tables.items.is_empty()
                 || !schema.views.items.is_empty()
                 || !schema.functions.items.is_empty()
-                || !schema.indexes.items.is_empty();
+                || !schema.indexes.items.is_empty()
+                || !schema.sequences.items.is_empty()
+                || !schema.enums.items.is_empty()
+                || !schema.domains.items.is_empty();
 
             // Schema label with optional count indicator
             let label = if show_schema_count && schema_tree.schemas.is_truncated() {
@@ -212,10 +344,7 @@ impl TreeBrowser {
                 if self.expanded.contains(&cat_path) {
                     for table in schema.tables.iter() {
                         let table_path = format!("{}.{}", cat_path, table.name);
-                        let label = match table.row_count {
-                            Some(n) => format!("{} (~{})", table.name, format_count(n)),
-                            None => table.name.clone(),
-                        };
+                        let label = table_label(table, &self.numbers);
                         self.items.push(TreeItem {
                             label,
                             kind: NodeKind::Table,
@@ -277,7 +406,7 @@ impl TreeBrowser {
                     for view in schema.views.iter() {
                         let view_path = format!("{}.{}", cat_path, view.name);
                         self.items.push(TreeItem {
-                            label: view.name.clone(),
+                            label: relation_name(view),
                             kind: NodeKind::View,
                             depth: 2,
                             path: view_path.clone(),
@@ -417,6 +546,50 @@ impl TreeBrowser {
                     }
                 }
             }
+
+            // ── Sequences, enums, and domains (always fully loaded) ──
+            let sequences = schema.sequences.items.iter().map(|seq| {
+                let label = match seq.last_value {
+                    Some(v) => format!("{} = {}", seq.name, v),
+                    None => seq.name.clone(),
+                };
+                (seq.name.as_str(), label)
+            });
+            push_leaf_category(
+                &mut self.items,
+                &self.expanded,
+                &self.filter_match_paths,
+                &schema.name,
+                "Sequences",
+                NodeKind::Sequence,
+                sequences,
+            );
+            let enums = schema.enums.items.iter().map(|e| {
+                let label = format!("{} ({})", e.name, e.labels.join(", "));
+                (e.name.as_str(), label)
+            });
+            push_leaf_category(
+                &mut self.items,
+                &self.expanded,
+                &self.filter_match_paths,
+                &schema.name,
+                "Enums",
+                NodeKind::EnumType,
+                enums,
+            );
+            let domains = schema.domains.items.iter().map(|d| {
+                let label = format!("{} ({})", d.name, d.base_type);
+                (d.name.as_str(), label)
+            });
+            push_leaf_category(
+                &mut self.items,
+                &self.expanded,
+                &self.filter_match_paths,
+                &schema.name,
+                "Domains",
+                NodeKind::Domain,
+                domains,
+            );
         }
 
         // Add "Load more schemas" item if truncated
@@ -434,6 +607,62 @@ impl TreeBrowser {
             });
         }
 
+        // Installed extensions (below schemas, collapsed until opened)
+        if !schema_tree.extensions.is_empty() {
+            let ext_path = "__extensions__".to_string();
+            self.items.push(TreeItem {
+                label: format!("Extensions ({})", schema_tree.extensions.len()),
+                kind: NodeKind::ExtensionHeader,
+                depth: 0,
+                path: ext_path.clone(),
+                expandable: true,
+                matches_filter: false,
+            });
+            if self.expanded.contains(&ext_path) {
+                for ext in &schema_tree.extensions {
+                    let item_path = format!("__extensions__.{}", ext.name);
+                    self.items.push(TreeItem {
+                        label: format!("{} {} ({})", ext.name, ext.version, ext.schema),
+                        kind: NodeKind::Extension,
+                        depth: 1,
+                        path: item_path.clone(),
+                        expandable: false,
+                        matches_filter: self.filter_match_paths.contains(&item_path),
+                    });
+                }
+            }
+        }
+
+        // Databases on the server (collapsed until opened)
+        if !schema_tree.databases.is_empty() {
+            let db_path = "__databases__".to_string();
+            self.items.push(TreeItem {
+                label: format!("Databases ({})", schema_tree.databases.len()),
+                kind: NodeKind::DatabaseHeader,
+                depth: 0,
+                path: db_path.clone(),
+                expandable: true,
+                matches_filter: false,
+            });
+            if self.expanded.contains(&db_path) {
+                for db in &schema_tree.databases {
+                    let item_path = format!("__databases__.{}", db.name);
+                    self.items.push(TreeItem {
+                        label: if db.is_current {
+                            format!("{} (current)", db.name)
+                        } else {
+                            db.name.clone()
+                        },
+                        kind: NodeKind::Database,
+                        depth: 1,
+                        path: item_path.clone(),
+                        expandable: false,
+                        matches_filter: self.filter_match_paths.contains(&item_path),
+                    });
+                }
+            }
+        }
+
         // Apply filter if filter text is not empty - keep only matching items and ancestors
         if !filter_lower.is_empty() && !self.filter_match_paths.is_empty() {
             // Build set of paths to keep (matches + their ancestors)
@@ -441,10 +670,14 @@ impl TreeBrowser {
 
             for path in &self.filter_match_paths {
                 paths_to_keep.insert(path.clone());
-                // Add all ancestor paths (skip saved queries namespace
-                // since query names may contain dots)
+                // Add all ancestor paths (skip saved queries, extensions,
+                // and databases namespaces since their names may contain dots)
                 if path.starts_with("__saved_queries__") {
                     paths_to_keep.insert("__saved_queries__".to_string());
+                } else if path.starts_with("__extensions__") {
+                    paths_to_keep.insert("__extensions__".to_string());
+                } else if path.starts_with("__databases__") {
+                    paths_to_keep.insert("__databases__".to_string());
                 } else {
                     let mut p = path.as_str();
                     while let Some((parent, _)) = p.rsplit_once('.') {
@@ -527,7 +760,7 @@ impl TreeBrowser {
                 if parts.len() == 3 {
                     let schema = parts[0];
                     let name = parts[2];
-                    Some(format!("SELECT * FROM \"{}\".\"{}\"", schema, name))
+                    Some(format!("SELECT * FROM {}", quote_qualified(schema, name)))
                 } else {
                     None
                 }
@@ -536,22 +769,49 @@ impl TreeBrowser {
         }
     }
 
+    /// Whether the selected table or view is one the role can't SELECT
+    /// from
+    pub fn selected_select_denied(&self) -> bool {
+        let Some(item) = self.items.get(self.selected) else {
+            return false;
+        };
+        if !matches!(item.kind, NodeKind::Table | NodeKind::View) {
+            return false;
+        }
+        let parts: Vec<&str> = item.path.splitn(3, '.').collect();
+        parts.len() == 3
+            && self
+                .find_relation(&format!("{}.{}", parts[0], parts[2]))
+                .is_some_and(|(_, table, _)| table.select_denied)
+    }
+
     /// Number of rows for preview queries
     pub fn preview_rows(&self) -> usize {
         self.preview_rows
     }
 
+    /// Change the preview row count (per-connection setting overrides)
+    pub fn set_preview_rows(&mut self, preview_rows: usize) {
+        self.preview_rows = preview_rows;
+    }
+
+    /// Change how row estimates and sizes are written
+    pub fn set_number_format(&mut self, numbers: NumberFormat) {
+        self.numbers = numbers;
+        self.rebuild_items();
+    }
+
     /// Return the qualified name of the selected item for copying.
     /// Returns schema.table, schema.table.column, function name, etc.
     pub fn selected_qualified_name(&self) -> Option<String> {
         let item = self.items.get(self.selected)?;
         match item.kind {
-            NodeKind::Schema => Some(format!("\"{}\"", item.label)),
+            NodeKind::Schema => Some(quote_ident(&item.label)),
             NodeKind::Table | NodeKind::View => {
                 // Path format: "schema.Tables.tablename" or "schema.Views.viewname"
                 let parts: Vec<&str> = item.path.splitn(3, '.').collect();
                 if parts.len() == 3 {
-                    Some(format!("\"{}\".\"{}\"", parts[0], parts[2]))
+                    Some(quote_qualified(parts[0], parts[2]))
                 } else {
                     None
                 }
@@ -562,7 +822,7 @@ impl TreeBrowser {
                 if parts.len() == 4 {
                     // Return just the column name (most common use case)
                     // User can copy table separately if they need qualified
-                    Some(format!("\"{}\"", parts[3]))
+                    Some(quote_ident(parts[3]))
                 } else {
                     None
                 }
@@ -571,7 +831,7 @@ impl TreeBrowser {
                 // Path format: "schema.Functions.funcname"
                 let parts: Vec<&str> = item.path.splitn(3, '.').collect();
                 if parts.len() == 3 {
-                    Some(format!("\"{}\".\"{}\"", parts[0], parts[2]))
+                    Some(quote_qualified(parts[0], parts[2]))
                 } else {
                     None
                 }
@@ -580,15 +840,28 @@ impl TreeBrowser {
                 // Path format: "schema.Indexes.indexname"
                 let parts: Vec<&str> = item.path.splitn(3, '.').collect();
                 if parts.len() == 3 {
-                    Some(format!("\"{}\"", parts[2]))
+                    Some(quote_ident(parts[2]))
                 } else {
                     None
                 }
             }
+            NodeKind::Sequence | NodeKind::EnumType | NodeKind::Domain => {
+                // Path format: "schema.Sequences.name", "schema.Enums.name", ...
+                let parts: Vec<&str> = item.path.splitn(3, '.').collect();
+                if parts.len() == 3 {
+                    Some(quote_qualified(parts[0], parts[2]))
+                } else {
+                    None
+                }
+            }
+            NodeKind::Extension => item.path.strip_prefix("__extensions__.").map(quote_ident),
+            NodeKind::Database => item.path.strip_prefix("__databases__.").map(quote_ident),
             NodeKind::Category
             | NodeKind::LoadMore
             | NodeKind::SavedQueryHeader
-            | NodeKind::SavedQuery => None,
+            | NodeKind::SavedQuery
+            | NodeKind::ExtensionHeader
+            | NodeKind::DatabaseHeader => None,
         }
     }
 
@@ -608,11 +881,120 @@ impl TreeBrowser {
         }
     }
 
+    /// The selected table, view, index, or function as a DDL target.
+    pub fn selected_ddl_target(&self) -> Option<DdlTarget> {
+        let item = self.items.get(self.selected)?;
+        let parts: Vec<&str> = item.path.splitn(3, '.').collect();
+        if parts.len() != 3 {
+            return None;
+        }
+        let (schema, name) = (parts[0].to_string(), parts[2].to_string());
+        match item.kind {
+            NodeKind::Table | NodeKind::View | NodeKind::Index => {
+                Some(DdlTarget::Relation { schema, name })
+            }
+            NodeKind::Function => Some(DdlTarget::Function { schema, name }),
+            _ => None,
+        }
+    }
+
+    /// Return schema, table, and column name if a column is selected.
+    pub fn selected_column_info(&self) -> Option<(String, String, String)> {
+        let item = self.items.get(self.selected)?;
+        if item.kind != NodeKind::Column {
+            return None;
+        }
+        // Path format: "schema.Tables.tablename.columnname"
+        let parts: Vec<&str> = item.path.splitn(4, '.').collect();
+        if parts.len() == 4 {
+            Some((
+                parts[0].to_string(),
+                parts[2].to_string(),
+                parts[3].to_string(),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// The selected table (not view) with its schema name, for generating
+    /// statements against its columns.
+    pub fn selected_table(&self) -> Option<(&str, &Table)> {
+        let item = self.items.get(self.selected)?;
+        if item.kind != NodeKind::Table {
+            return None;
+        }
+        let parts: Vec<&str> = item.path.splitn(3, '.').collect();
+        if parts.len() != 3 {
+            return None;
+        }
+        let schema = self
+            .schema
+            .as_ref()?
+            .schemas
+            .items
+            .iter()
+            .find(|s| s.name == parts[0])?;
+        let table = schema.tables.items.iter().find(|t| t.name == parts[2])?;
+        Some((&schema.name, table))
+    }
+
+    /// A table by `schema.table` or bare name (searching `public` first),
+    /// with its schema name
+    pub fn find_table(&self, name: &str) -> Option<(&str, &Table)> {
+        self.find_in_schemas(name, |s, table| {
+            s.tables.items.iter().find(|t| t.name == table)
+        })
+    }
+
+    /// A table or view by `schema.name` or bare name (searching `public`
+    /// first, tables before views), with its schema name and whether it's
+    /// a view
+    pub fn find_relation(&self, name: &str) -> Option<(&str, &Table, bool)> {
+        self.find_table(name)
+            .map(|(schema, table)| (schema, table, false))
+            .or_else(|| {
+                self.find_in_schemas(name, |s, view| {
+                    s.views.items.iter().find(|v| v.name == view)
+                })
+                .map(|(schema, view)| (schema, view, true))
+            })
+    }
+
+    /// Look `name` up with `find` in its named schema, or in `public` and
+    /// then the other schemas when unqualified
+    fn find_in_schemas<'a, T>(
+        &'a self,
+        name: &str,
+        find: impl Fn(&'a Schema, &str) -> Option<&'a T>,
+    ) -> Option<(&'a str, &'a T)> {
+        let schemas = &self.schema.as_ref()?.schemas.items;
+        let (schema, table) = match name.split_once('.') {
+            Some((schema, table)) => (Some(schema), table),
+            None => (None, name),
+        };
+        schemas
+            .iter()
+            .filter(|s| schema.is_none_or(|name| s.name == name))
+            .filter(|s| schema.is_some() || s.name == "public")
+            .chain(
+                schemas
+                    .iter()
+                    .filter(|s| schema.is_none() && s.name != "public"),
+            )
+            .find_map(|s| Some((s.name.as_str(), find(s, table)?)))
+    }
+
     /// Expose the loaded schema tree for use by the completer.
     pub fn schema(&self) -> Option<&SchemaTree> {
         self.schema.as_ref()
     }
 
+    /// The schema tree behind any search results, as it should be cached
+    pub fn base_schema(&self) -> Option<&SchemaTree> {
+        self.pre_search_schema.as_ref().or(self.schema.as_ref())
+    }
+
     /// Set saved queries for the current connection. Rebuilds the tree.
     pub fn set_saved_queries(&mut self, queries: Vec<SavedQuery>) {
         self.saved_queries = queries;
@@ -632,6 +1014,23 @@ impl TreeBrowser {
         self.saved_queries.iter().find(|q| q.name == name)
     }
 
+    /// The selected database in the Databases list, with whether it's the
+    /// one connected to
+    pub fn selected_database(&self) -> Option<(&str, bool)> {
+        let item = self.items.get(self.selected)?;
+        if item.kind != NodeKind::Database {
+            return None;
+        }
+        let name = item.path.strip_prefix("__databases__.")?;
+        let db = self
+            .schema
+            .as_ref()?
+            .databases
+            .iter()
+            .find(|d| d.name == name)?;
+        Some((&db.name, db.is_current))
+    }
+
     /// If the selected node is a saved query, return its name for deletion.
     pub fn selected_saved_query_name(&self) -> Option<&str> {
         let item = self.items.get(self.selected)?;
@@ -786,14 +1185,9 @@ impl TreeBrowser {
     /// Delete the character before the cursor (backspace)
     pub fn filter_backspace(&mut self) {
         if self.filter_cursor > 0 {
-            // Find the previous character boundary
-            let prev_boundary = self.filter_text[..self.filter_cursor]
-                .char_indices()
-                .next_back()
-                .map(|(i, _)| i)
-                .unwrap_or(0);
-            self.filter_text.remove(prev_boundary);
-            self.filter_cursor = prev_boundary;
+            let prev = prev_grapheme(&self.filter_text, self.filter_cursor);
+            self.filter_text.drain(prev..self.filter_cursor);
+            self.filter_cursor = prev;
             self.apply_filter();
         }
     }
@@ -801,31 +1195,20 @@ impl TreeBrowser {
     /// Delete the character at the cursor (delete key)
     pub fn filter_delete(&mut self) {
         if self.filter_cursor < self.filter_text.len() {
-            self.filter_text.remove(self.filter_cursor);
+            let next = next_grapheme(&self.filter_text, self.filter_cursor);
+            self.filter_text.drain(self.filter_cursor..next);
             self.apply_filter();
         }
     }
 
     /// Move filter cursor left
     pub fn filter_cursor_left(&mut self) {
-        if self.filter_cursor > 0 {
-            self.filter_cursor = self.filter_text[..self.filter_cursor]
-                .char_indices()
-                .next_back()
-                .map(|(i, _)| i)
-                .unwrap_or(0);
-        }
+        self.filter_cursor = prev_grapheme(&self.filter_text, self.filter_cursor);
     }
 
     /// Move filter cursor right
     pub fn filter_cursor_right(&mut self) {
-        if self.filter_cursor < self.filter_text.len() {
-            self.filter_cursor += self.filter_text[self.filter_cursor..]
-                .chars()
-                .next()
-                .map(|c| c.len_utf8())
-                .unwrap_or(0);
-        }
+        self.filter_cursor = next_grapheme(&self.filter_text, self.filter_cursor);
     }
 
     /// Apply the current filter text to rebuild visible items
@@ -835,12 +1218,48 @@ impl TreeBrowser {
         self.rebuild_items();
         // Reset selection to first matching item
         if !self.items.is_empty() {
-            self.selected = 0;
+            self.selected = self
+                .items
+                .iter()
+                .position(|item| item.matches_filter)
+                .unwrap_or(0);
             self.scroll_offset = 0;
         }
     }
 
-    /// Scan schema data to find all paths that match the filter text.
+    /// Selected item and visible item count (1-based)
+    pub fn position(&self) -> Option<(usize, usize)> {
+        (!self.items.is_empty()).then(|| (self.selected + 1, self.items.len()))
+    }
+
+    /// Number of visible items matching the filter
+    pub fn filter_match_count(&self) -> usize {
+        self.items.iter().filter(|item| item.matches_filter).count()
+    }
+
+    /// Select the next item matching the filter, wrapping around
+    pub fn next_match(&mut self) {
+        let len = self.items.len();
+        if let Some(i) = (1..=len)
+            .map(|step| (self.selected + step) % len)
+            .find(|&i| self.items[i].matches_filter)
+        {
+            self.selected = i;
+        }
+    }
+
+    /// Select the previous item matching the filter, wrapping around
+    pub fn prev_match(&mut self) {
+        let len = self.items.len();
+        if let Some(i) = (1..=len)
+            .map(|step| (self.selected + len - step) % len)
+            .find(|&i| self.items[i].matches_filter)
+        {
+            self.selected = i;
+        }
+    }
+
+    /// Scan schema data to find all paths that fuzzy-match the filter text.
     /// Also auto-expands parent paths so matches become visible.
     fn compute_filter_matches(&mut self) {
         self.filter_match_paths.clear();
@@ -862,7 +1281,7 @@ impl TreeBrowser {
             let schema_path = schema.name.clone();
 
             // Check schema name
-            if schema.name.to_lowercase().contains(&filter_lower) {
+            if fuzzy_match(&schema.name, &filter_lower) {
                 self.filter_match_paths.insert(schema_path.clone());
             }
 
@@ -871,7 +1290,7 @@ impl TreeBrowser {
             for table in &schema.tables {
                 let table_path = format!("{}.{}", tables_cat_path, table.name);
 
-                if table.name.to_lowercase().contains(&filter_lower) {
+                if fuzzy_match(&table.name, &filter_lower) {
                     self.filter_match_paths.insert(table_path.clone());
                     // Expand parents to show this match
                     paths_to_expand.insert(schema_path.clone());
@@ -881,7 +1300,7 @@ impl TreeBrowser {
                 // Check columns
                 for col in &table.columns {
                     let col_path = format!("{}.{}", table_path, col.name);
-                    if col.name.to_lowercase().contains(&filter_lower) {
+                    if fuzzy_match(&col.name, &filter_lower) {
                         self.filter_match_paths.insert(col_path);
                         // Expand parents to show this match
                         paths_to_expand.insert(schema_path.clone());
@@ -896,7 +1315,7 @@ impl TreeBrowser {
             for view in &schema.views {
                 let view_path = format!("{}.{}", views_cat_path, view.name);
 
-                if view.name.to_lowercase().contains(&filter_lower) {
+                if fuzzy_match(&view.name, &filter_lower) {
                     self.filter_match_paths.insert(view_path.clone());
                     paths_to_expand.insert(schema_path.clone());
                     paths_to_expand.insert(views_cat_path.clone());
@@ -905,7 +1324,7 @@ impl TreeBrowser {
                 // Check view columns
                 for col in &view.columns {
                     let col_path = format!("{}.{}", view_path, col.name);
-                    if col.name.to_lowercase().contains(&filter_lower) {
+                    if fuzzy_match(&col.name, &filter_lower) {
                         self.filter_match_paths.insert(col_path);
                         paths_to_expand.insert(schema_path.clone());
                         paths_to_expand.insert(views_cat_path.clone());
@@ -918,7 +1337,7 @@ impl TreeBrowser {
             let funcs_cat_path = format!("{}.Functions", schema.name);
             for func in &schema.functions {
                 let func_path = format!("{}.{}", funcs_cat_path, func.name);
-                if func.name.to_lowercase().contains(&filter_lower) {
+                if fuzzy_match(&func.name, &filter_lower) {
                     self.filter_match_paths.insert(func_path);
                     paths_to_expand.insert(schema_path.clone());
                     paths_to_expand.insert(funcs_cat_path.clone());
@@ -929,12 +1348,54 @@ impl TreeBrowser {
             let idx_cat_path = format!("{}.Indexes", schema.name);
             for idx in &schema.indexes {
                 let idx_path = format!("{}.{}", idx_cat_path, idx.name);
-                if idx.name.to_lowercase().contains(&filter_lower) {
+                if fuzzy_match(&idx.name, &filter_lower) {
                     self.filter_match_paths.insert(idx_path);
                     paths_to_expand.insert(schema_path.clone());
                     paths_to_expand.insert(idx_cat_path.clone());
                 }
             }
+
+            // Check sequences, enums, and domains
+            let leaves = [
+                (
+                    "Sequences",
+                    schema.sequences.iter().map(|s| &s.name).collect(),
+                ),
+                ("Enums", schema.enums.iter().map(|e| &e.name).collect()),
+                (
+                    "Domains",
+                    schema.domains.iter().map(|d| &d.name).collect::<Vec<_>>(),
+                ),
+            ];
+            for (category, names) in leaves {
+                let cat_path = format!("{}.{}", schema.name, category);
+                for name in names {
+                    if fuzzy_match(name, &filter_lower) {
+                        self.filter_match_paths
+                            .insert(format!("{}.{}", cat_path, name));
+                        paths_to_expand.insert(schema_path.clone());
+                        paths_to_expand.insert(cat_path.clone());
+                    }
+                }
+            }
+        }
+
+        // Check extensions
+        for ext in &schema_tree.extensions {
+            if fuzzy_match(&ext.name, &filter_lower) {
+                self.filter_match_paths
+                    .insert(format!("__extensions__.{}", ext.name));
+                paths_to_expand.insert("__extensions__".to_string());
+            }
+        }
+
+        // Check databases
+        for db in &schema_tree.databases {
+            if fuzzy_match(&db.name, &filter_lower) {
+                self.filter_match_paths
+                    .insert(format!("__databases__.{}", db.name));
+                paths_to_expand.insert("__databases__".to_string());
+            }
         }
 
         // Auto-expand paths to show matches
@@ -1067,6 +1528,45 @@ fn push_columns(
     }
 }
 
+/// Push a category of leaf objects (name and display label pairs) under a
+/// schema, with its items when expanded. Empty categories are hidden.
+fn push_leaf_category<'a>(
+    items: &mut Vec<TreeItem>,
+    expanded: &HashSet<String>,
+    filter_match_paths: &HashSet<String>,
+    schema_name: &str,
+    category: &str,
+    kind: NodeKind,
+    leaves: impl ExactSizeIterator<Item = (&'a str, String)>,
+) {
+    if leaves.len() == 0 {
+        return;
+    }
+    let cat_path = format!("{}.{}", schema_name, category);
+    items.push(TreeItem {
+        label: format!("{} ({})", category, leaves.len()),
+        kind: NodeKind::Category,
+        depth: 1,
+        path: cat_path.clone(),
+        expandable: true,
+        matches_filter: false,
+    });
+    if !expanded.contains(&cat_path) {
+        return;
+    }
+    for (name, label) in leaves {
+        let path = format!("{}.{}", cat_path, name);
+        items.push(TreeItem {
+            label,
+            kind,
+            depth: 2,
+            matches_filter: filter_match_paths.contains(&path),
+            path,
+            expandable: false,
+        });
+    }
+}
+
 /// Format a column label with PK/FK annotations
 fn format_column_label(col: &crate::db::schema::Column) -> String {
     let prefix = if col.is_primary_key { "* " } else { "" };
@@ -1084,28 +1584,59 @@ fn format_column_label(col: &crate::db::schema::Column) -> String {
     )
 }
 
-/// Format a row count with thousands separators (e.g. 1234567 → "1,234,567")
-fn format_count(n: i64) -> String {
-    if n < 0 {
-        return format!("-{}", format_count(n.saturating_neg()));
-    }
-    match n {
-        0..1_000 => n.to_string(),
-        1_000..1_000_000 => {
-            let k = n as f64 / 1_000.0;
-            format!("{:.1}K", k).replace(".0K", "K")
-        }
-        1_000_000..1_000_000_000 => {
-            let m = n as f64 / 1_000_000.0;
-            format!("{:.1}M", m).replace(".0M", "M")
-        }
-        _ => {
-            let b = n as f64 / 1_000_000_000.0;
-            format!("{:.1}B", b).replace(".0B", "B")
+/// Frames for the loading indicator on a schema node
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Keep objects of schemas that were loaded before a refresh, so the tree
+/// and completer don't go blank while expanded schemas reload. The fresh
+/// schemas stay marked unloaded and are fetched again when expanded.
+fn carry_over_objects(old: SchemaTree, fresh: &mut SchemaTree) {
+    let mut old: HashMap<String, Schema> = old
+        .schemas
+        .items
+        .into_iter()
+        .map(|s| (s.name.clone(), s))
+        .collect();
+    for schema in fresh.schemas.items.iter_mut().filter(|s| !s.loaded) {
+        if let Some(previous) = old.remove(&schema.name) {
+            *schema = Schema {
+                loaded: false,
+                ..previous
+            };
         }
     }
 }
 
+/// Whether every character of `pattern_lower` appears in `name`, in order
+/// and case-insensitively (so `uem` matches `user_email`)
+pub(crate) fn fuzzy_match(name: &str, pattern_lower: &str) -> bool {
+    let mut name_chars = name.chars().flat_map(char::to_lowercase);
+    pattern_lower.chars().all(|p| name_chars.any(|c| c == p))
+}
+
+/// A table or view's name, with a lock when the current role can't
+/// SELECT from it
+fn relation_name(table: &Table) -> String {
+    if table.select_denied {
+        format!("{} \u{1f512}", table.name)
+    } else {
+        table.name.clone()
+    }
+}
+
+/// A table's name with its row estimate and on-disk size, when known
+/// (e.g. "orders  ~1.2M rows  830 MB")
+fn table_label(table: &Table, numbers: &NumberFormat) -> String {
+    let mut label = relation_name(table);
+    if let Some(n) = table.row_count {
+        label.push_str(&format!("  ~{} rows", numbers.compact(n)));
+    }
+    if let Some(bytes) = table.size_bytes {
+        label.push_str(&format!("  {}", numbers.size(bytes)));
+    }
+    label
+}
+
 impl Default for TreeBrowser {
     fn default() -> Self {
         Self::new()
@@ -1136,7 +1667,16 @@ impl Component for TreeBrowser {
             let status_hint = if self.searching {
                 Span::styled(" Searching...", Style::default().fg(Color::Yellow))
             } else if !self.filter_text.is_empty() {
-                Span::styled(" (Enter=search)", Style::default().fg(Color::DarkGray))
+                Span::styled(
+                    format!(
+                        " {} (Tab=next, Enter=search)",
+                        match self.filter_match_count() {
+                            1 => "1 match".to_string(),
+                            n => format!("{} matches", n),
+                        }
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                )
             } else {
                 Span::raw("")
             };
@@ -1210,7 +1750,14 @@ impl Component for TreeBrowser {
                 "  "
             };
 
-            let display = format!("{}{}{}", indent, indicator, item.label);
+            let spinner = match self.loading.get(&item.path) {
+                Some(since) if item.kind == NodeKind::Schema => {
+                    let frame = since.elapsed().as_millis() / 100;
+                    format!(" {}", SPINNER[frame as usize % SPINNER.len()])
+                }
+                _ => String::new(),
+            };
+            let display = format!("{}{}{}{}", indent, indicator, item.label, spinner);
             let max_cols = tree_area.width as usize;
             let truncated = if super::unicode::display_width(&display) > max_cols {
                 super::unicode::truncate_to_width(&display, max_cols)
@@ -1233,8 +1780,14 @@ impl Component for TreeBrowser {
                     NodeKind::Function => theme.tree_function,
                     NodeKind::Index => theme.tree_index,
                     NodeKind::LoadMore => theme.tree_load_more,
-                    NodeKind::SavedQueryHeader => theme.tree_category,
+                    NodeKind::Sequence => theme.tree_index,
+                    NodeKind::EnumType | NodeKind::Domain => theme.tree_column,
+                    NodeKind::SavedQueryHeader
+                    | NodeKind::ExtensionHeader
+                    | NodeKind::DatabaseHeader => theme.tree_category,
                     NodeKind::SavedQuery => theme.tree_table,
+                    NodeKind::Extension => theme.tree_function,
+                    NodeKind::Database => theme.tree_schema,
                 }
             };
 
@@ -1249,7 +1802,10 @@ impl Component for TreeBrowser {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::schema::{Column, ForeignKey, Function, Index, PaginatedVec, Schema, Table};
+    use crate::db::schema::{
+        Column, Domain, EnumType, Extension, ForeignKey, Function, Index, PaginatedVec, Schema,
+        Sequence, Table,
+    };
     use crate::db::types::DataType;
 
     fn sample_schema() -> SchemaTree {
@@ -1274,6 +1830,8 @@ mod tests {
                             },
                         ],
                         row_count: Some(1500),
+                        size_bytes: Some(16_384),
+                        select_denied: false,
                     },
                     Table {
                         name: "orders".to_string(),
@@ -1295,6 +1853,8 @@ mod tests {
                             },
                         ],
                         row_count: Some(42000),
+                        size_bytes: None,
+                        select_denied: false,
                     },
                 ]),
                 views: PaginatedVec::from_vec(vec![Table {
@@ -1306,6 +1866,8 @@ mod tests {
                         foreign_key: None,
                     }],
                     row_count: None,
+                    size_bytes: None,
+                    select_denied: false,
                 }]),
                 indexes: PaginatedVec::from_vec(vec![Index {
                     name: "users_pkey".to_string(),
@@ -1319,7 +1881,13 @@ mod tests {
                     args: "integer".to_string(),
                     return_type: "users".to_string(),
                 }]),
+                sequences: PaginatedVec::default(),
+                enums: PaginatedVec::default(),
+                domains: PaginatedVec::default(),
+                loaded: true,
             }]),
+            extensions: Vec::new(),
+            databases: Vec::new(),
         }
     }
 
@@ -1340,6 +1908,100 @@ mod tests {
         assert!(tree.items.len() >= 5);
     }
 
+    #[test]
+    fn test_refresh_stale_schema_keeps_navigation() {
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(sample_schema());
+        tree.mark_stale();
+        assert!(tree.is_stale());
+
+        let users_idx = tree
+            .items
+            .iter()
+            .position(|i| i.label.starts_with("users"))
+            .unwrap();
+        tree.selected = users_idx;
+        tree.toggle_expand();
+        let expanded = tree.expanded.clone();
+
+        tree.refresh_schema(sample_schema());
+        assert!(!tree.is_stale());
+        assert_eq!(tree.selected, users_idx);
+        assert_eq!(tree.expanded, expanded);
+
+        // A fresh schema with fewer items clamps the selection
+        tree.mark_stale();
+        tree.selected = tree.items.len() - 1;
+        tree.refresh_schema(SchemaTree::new());
+        assert_eq!(tree.selected, 0);
+
+        // set_schema always clears the stale flag
+        tree.mark_stale();
+        tree.set_schema(sample_schema());
+        assert!(!tree.is_stale());
+    }
+
+    #[test]
+    fn test_unloaded_schema_is_expandable_and_fills_in_when_loaded() {
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(SchemaTree {
+            schemas: PaginatedVec::from_vec(vec![
+                Schema::unloaded("public".to_string()),
+                Schema::unloaded("sales".to_string()),
+            ]),
+            extensions: Vec::new(),
+            databases: Vec::new(),
+        });
+        assert!(tree.items.iter().all(|i| i.expandable));
+        assert_eq!(tree.take_pending_loads(), vec!["public"]);
+
+        // Expanding another schema queues it too
+        tree.selected = tree.items.iter().position(|i| i.label == "sales").unwrap();
+        tree.toggle_expand();
+        assert_eq!(tree.take_pending_loads(), vec!["sales"]);
+
+        tree.selected = 0;
+        tree.apply_loaded_schema(sample_schema().schemas.items.remove(0));
+        assert!(tree.is_loading(), "sales is still loading");
+        assert_eq!(tree.items[tree.selected].label, "public");
+        // The first schema opens its tables once they arrive
+        assert!(tree.items.iter().any(|i| i.label.starts_with("users")));
+    }
+
+    #[test]
+    fn test_refresh_keeps_loaded_objects_until_reloaded() {
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(sample_schema());
+        tree.mark_stale();
+
+        tree.refresh_schema(SchemaTree {
+            schemas: PaginatedVec::from_vec(vec![Schema::unloaded("public".to_string())]),
+            extensions: Vec::new(),
+            databases: Vec::new(),
+        });
+        let public = &tree.schema().unwrap().schemas.items[0];
+        assert!(!public.loaded);
+        assert_eq!(public.tables.len(), 2);
+        assert!(tree.items.iter().any(|i| i.label.starts_with("users")));
+        // Still expanded, so it's fetched again
+        assert_eq!(tree.take_pending_loads(), vec!["public"]);
+    }
+
+    #[test]
+    fn test_refresh_stale_schema_during_search_updates_restore_target() {
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(SchemaTree::new());
+        tree.mark_stale();
+        tree.activate_filter();
+        tree.filter_insert_char('x');
+        tree.apply_search_results(SchemaTree::new());
+
+        tree.refresh_schema(sample_schema());
+        assert!(tree.items.iter().all(|i| i.label != "public"));
+        tree.deactivate_filter();
+        assert!(tree.items.iter().any(|i| i.label == "public"));
+    }
+
     #[test]
     fn test_category_nodes_appear() {
         let mut tree = TreeBrowser::new();
@@ -1441,6 +2103,138 @@ mod tests {
         assert!(labels.iter().any(|l| l.contains("users_pkey")));
     }
 
+    /// The sample schema plus a sequence, an enum, a domain, and an extension
+    fn schema_with_types() -> SchemaTree {
+        let mut tree = sample_schema();
+        let public = &mut tree.schemas.items[0];
+        public.sequences = PaginatedVec::from_vec(vec![
+            Sequence {
+                name: "orders_id_seq".to_string(),
+                last_value: Some(42),
+            },
+            Sequence {
+                name: "unused_seq".to_string(),
+                last_value: None,
+            },
+        ]);
+        public.enums = PaginatedVec::from_vec(vec![EnumType {
+            name: "mood".to_string(),
+            labels: vec!["sad".to_string(), "ok".to_string(), "happy".to_string()],
+        }]);
+        public.domains = PaginatedVec::from_vec(vec![Domain {
+            name: "email".to_string(),
+            base_type: "text".to_string(),
+        }]);
+        tree.extensions = vec![Extension {
+            name: "pgcrypto".to_string(),
+            version: "1.3".to_string(),
+            schema: "public".to_string(),
+        }];
+        tree
+    }
+
+    fn expand_label(tree: &mut TreeBrowser, prefix: &str) {
+        tree.selected = tree
+            .items
+            .iter()
+            .position(|i| i.label.starts_with(prefix))
+            .unwrap();
+        tree.toggle_expand();
+    }
+
+    #[test]
+    fn test_sequences_enums_and_domains_categories() {
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(schema_with_types());
+        expand_label(&mut tree, "Sequences (2)");
+        expand_label(&mut tree, "Enums (1)");
+        expand_label(&mut tree, "Domains (1)");
+        let labels: Vec<&str> = tree.items.iter().map(|i| i.label.as_str()).collect();
+        assert!(labels.contains(&"orders_id_seq = 42"));
+        assert!(labels.contains(&"unused_seq"));
+        assert!(labels.contains(&"mood (sad, ok, happy)"));
+        assert!(labels.contains(&"email (text)"));
+
+        tree.selected = labels.iter().position(|l| *l == "unused_seq").unwrap();
+        assert_eq!(
+            tree.selected_qualified_name().as_deref(),
+            Some("\"public\".\"unused_seq\"")
+        );
+        assert!(tree.preview_query().is_none());
+    }
+
+    #[test]
+    fn test_extensions_listed_below_schemas() {
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(schema_with_types());
+        let header = tree.items.last().unwrap();
+        assert_eq!(header.label, "Extensions (1)");
+        assert!(!tree.expanded.contains(&header.path));
+
+        expand_label(&mut tree, "Extensions");
+        let ext = tree.items.last().unwrap();
+        assert_eq!(ext.label, "pgcrypto 1.3 (public)");
+        tree.selected = tree.items.len() - 1;
+        assert_eq!(
+            tree.selected_qualified_name().as_deref(),
+            Some("\"pgcrypto\"")
+        );
+    }
+
+    #[test]
+    fn test_databases_listed_below_extensions() {
+        use crate::db::schema::ServerDatabase;
+
+        let mut schema = schema_with_types();
+        schema.databases = ["app", "analytics"]
+            .iter()
+            .map(|name| ServerDatabase {
+                name: name.to_string(),
+                is_current: *name == "app",
+            })
+            .collect();
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(schema);
+        assert_eq!(tree.items.last().unwrap().label, "Databases (2)");
+
+        expand_label(&mut tree, "Databases");
+        let labels: Vec<&str> = tree.items.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels[labels.len() - 2..], ["app (current)", "analytics"]);
+        tree.selected = tree.items.len() - 1;
+        assert_eq!(tree.selected_database(), Some(("analytics", false)));
+        tree.selected -= 1;
+        assert_eq!(tree.selected_database(), Some(("app", true)));
+    }
+
+    #[test]
+    fn test_filter_matches_types_and_extensions() {
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(schema_with_types());
+        tree.activate_filter();
+        for c in "mood".chars() {
+            tree.filter_insert_char(c);
+        }
+        assert!(
+            tree.items
+                .iter()
+                .any(|i| i.matches_filter && i.kind == NodeKind::EnumType)
+        );
+
+        tree.deactivate_filter();
+        tree.activate_filter();
+        for c in "crypto".chars() {
+            tree.filter_insert_char(c);
+        }
+        let matched: Vec<&str> = tree
+            .items
+            .iter()
+            .filter(|i| i.matches_filter)
+            .map(|i| i.label.as_str())
+            .collect();
+        assert_eq!(matched, vec!["pgcrypto 1.3 (public)"]);
+        assert!(tree.items.iter().any(|i| i.label == "Extensions (1)"));
+    }
+
     #[test]
     fn test_preview_query_for_table() {
         let mut tree = TreeBrowser::new();
@@ -1458,6 +2252,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_preview_query_quotes_exotic_identifiers() {
+        let mut schema = sample_schema();
+        let public = &mut schema.schemas.items[0];
+        public.name = "Sales".to_string();
+        public.tables.items[0].name = "Order\"Items".to_string();
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(schema);
+        tree.selected = tree
+            .items
+            .iter()
+            .position(|i| i.label.starts_with("Order"))
+            .unwrap();
+        assert_eq!(
+            tree.preview_query(),
+            Some("SELECT * FROM \"Sales\".\"Order\"\"Items\" LIMIT 100".to_string())
+        );
+    }
+
     #[test]
     fn test_preview_query_for_view() {
         let mut tree = TreeBrowser::new();
@@ -1538,11 +2351,19 @@ mod tests {
                     name: "t".to_string(),
                     columns: vec![],
                     row_count: None,
+                    size_bytes: None,
+                    select_denied: false,
                 }]),
                 views: PaginatedVec::default(),
                 indexes: PaginatedVec::default(),
                 functions: PaginatedVec::default(),
+                sequences: PaginatedVec::default(),
+                enums: PaginatedVec::default(),
+                domains: PaginatedVec::default(),
+                loaded: true,
             }]),
+            extensions: Vec::new(),
+            databases: Vec::new(),
         };
         let mut tree = TreeBrowser::new();
         tree.set_schema(schema);
@@ -1669,6 +2490,80 @@ mod tests {
             .unwrap();
         tree.selected = col_idx;
         assert_eq!(tree.selected_table_info(), None);
+        assert_eq!(
+            tree.selected_column_info(),
+            Some(("public".to_string(), "users".to_string(), "id".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_selected_table_only_for_tables() {
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(sample_schema());
+        tree.selected = tree
+            .items
+            .iter()
+            .position(|i| i.label.starts_with("users"))
+            .unwrap();
+        let (schema, table) = tree.selected_table().unwrap();
+        assert_eq!(schema, "public");
+        assert_eq!(table.columns.len(), 2);
+
+        tree.selected = tree
+            .items
+            .iter()
+            .position(|i| i.label.starts_with("Views"))
+            .unwrap();
+        tree.expand_current();
+        tree.selected = tree
+            .items
+            .iter()
+            .position(|i| i.label.starts_with("active_users"))
+            .unwrap();
+        assert!(tree.selected_table().is_none());
+    }
+
+    #[test]
+    fn test_unreadable_relations_show_a_lock() {
+        let mut schema = sample_schema();
+        schema.schemas.items[0].tables.items[0].select_denied = true;
+        schema.schemas.items[0].views.items[0].select_denied = true;
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(schema);
+        tree.selected = tree
+            .items
+            .iter()
+            .position(|i| i.label.starts_with("users"))
+            .unwrap();
+        assert!(
+            tree.items[tree.selected]
+                .label
+                .starts_with("users \u{1f512}")
+        );
+        assert!(tree.selected_select_denied());
+
+        tree.selected = tree
+            .items
+            .iter()
+            .position(|i| i.label.starts_with("Views"))
+            .unwrap();
+        tree.expand_current();
+        tree.selected = tree
+            .items
+            .iter()
+            .position(|i| i.label.starts_with("active_users"))
+            .unwrap();
+        assert_eq!(tree.items[tree.selected].label, "active_users \u{1f512}");
+        assert!(tree.selected_select_denied());
+
+        // Readable ones are left alone
+        tree.selected = tree
+            .items
+            .iter()
+            .position(|i| i.label.starts_with("orders"))
+            .unwrap();
+        assert!(!tree.items[tree.selected].label.contains('\u{1f512}'));
+        assert!(!tree.selected_select_denied());
     }
 
     // ── Filter tests ────────────────────────────────────────────────
@@ -1728,6 +2623,56 @@ mod tests {
         assert!(!public_item.matches_filter);
     }
 
+    #[test]
+    fn test_filter_matches_fuzzily_and_jumps_between_matches() {
+        let mut tree = TreeBrowser::new();
+        tree.set_schema(sample_schema());
+        tree.activate_filter();
+        for c in "uid".chars() {
+            tree.filter_insert_char(c);
+        }
+
+        // "uid" is a subsequence of user_id but not a substring
+        let matches: Vec<&str> = tree
+            .items
+            .iter()
+            .filter(|i| i.matches_filter)
+            .map(|i| i.label.as_str())
+            .collect();
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].starts_with("user_id"), "{:?}", matches);
+        // Non-matching branches are collapsed away
+        assert!(!tree.items.iter().any(|i| i.label.starts_with("users")));
+        assert!(tree.items[tree.selected].matches_filter);
+
+        // Tab cycles forward through matches, Shift+Tab back, wrapping
+        tree.deactivate_filter();
+        tree.activate_filter();
+        for c in "usr".chars() {
+            tree.filter_insert_char(c);
+        }
+        assert!(tree.filter_match_count() > 2);
+        let first = tree.selected;
+        assert!(tree.items[first].matches_filter);
+        tree.next_match();
+        assert!(tree.selected > first);
+        assert!(tree.items[tree.selected].matches_filter);
+        tree.prev_match();
+        assert_eq!(tree.selected, first);
+        tree.prev_match();
+        assert!(tree.selected > first, "wraps to the last match");
+        assert!(tree.items[tree.selected].matches_filter);
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert!(fuzzy_match("user_email", "uem"));
+        assert!(fuzzy_match("OrderItems", "oit"));
+        assert!(fuzzy_match("users", "users"));
+        assert!(!fuzzy_match("users", "usx"));
+        assert!(!fuzzy_match("abc", "cba"));
+    }
+
     #[test]
     fn test_filter_clears_on_deactivate() {
         let mut tree = TreeBrowser::new();
@@ -1788,11 +2733,19 @@ mod tests {
                     name: "search_table".to_string(),
                     columns: vec![],
                     row_count: None,
+                    size_bytes: None,
+                    select_denied: false,
                 }]),
                 views: PaginatedVec::default(),
                 indexes: PaginatedVec::default(),
                 functions: PaginatedVec::default(),
+                sequences: PaginatedVec::default(),
+                enums: PaginatedVec::default(),
+                domains: PaginatedVec::default(),
+                loaded: true,
             }]),
+            extensions: Vec::new(),
+            databases: Vec::new(),
         };
 
         tree.apply_search_results(search_results);
@@ -1821,11 +2774,19 @@ mod tests {
                     name: "other_table".to_string(),
                     columns: vec![],
                     row_count: None,
+                    size_bytes: None,
+                    select_denied: false,
                 }]),
                 views: PaginatedVec::default(),
                 indexes: PaginatedVec::default(),
                 functions: PaginatedVec::default(),
+                sequences: PaginatedVec::default(),
+                enums: PaginatedVec::default(),
+                domains: PaginatedVec::default(),
+                loaded: true,
             }]),
+            extensions: Vec::new(),
+            databases: Vec::new(),
         };
         tree.apply_search_results(search_results);
 
@@ -1853,6 +2814,8 @@ mod tests {
                 tables: PaginatedVec::from_vec(vec![Table {
                     name: "test_table".to_string(),
                     row_count: None,
+                    size_bytes: None,
+                    select_denied: false,
                     columns: vec![Column {
                         name: "test_col".to_string(),
                         data_type: DataType::Text,
@@ -1867,7 +2830,13 @@ mod tests {
                     args: "".to_string(),
                     return_type: "void".to_string(),
                 }]),
+                sequences: PaginatedVec::default(),
+                enums: PaginatedVec::default(),
+                domains: PaginatedVec::default(),
+                loaded: true,
             }]),
+            extensions: Vec::new(),
+            databases: Vec::new(),
         };
 
         tree.apply_search_results(search_results);
@@ -1956,6 +2925,8 @@ mod tests {
             name: "new_table".to_string(),
             columns: vec![],
             row_count: None,
+            size_bytes: None,
+            select_denied: false,
         };
         tree.extend_tables("public", vec![new_table]);
 
@@ -1973,6 +2944,8 @@ mod tests {
                 name: "table1".to_string(),
                 columns: vec![],
                 row_count: None,
+                size_bytes: None,
+                select_denied: false,
             }],
             10, // total is 10, but only 1 loaded
         );
@@ -2001,30 +2974,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_format_count_small() {
-        assert_eq!(format_count(0), "0");
-        assert_eq!(format_count(42), "42");
-        assert_eq!(format_count(999), "999");
-    }
-
-    #[test]
-    fn test_format_count_thousands() {
-        assert_eq!(format_count(1_000), "1K");
-        assert_eq!(format_count(1_234), "1.2K");
-        assert_eq!(format_count(1_500), "1.5K");
-        assert_eq!(format_count(12_345), "12.3K");
-        assert_eq!(format_count(123_456), "123.5K");
-    }
-
-    #[test]
-    fn test_format_count_millions() {
-        assert_eq!(format_count(1_000_000), "1M");
-        assert_eq!(format_count(1_234_567), "1.2M");
-        assert_eq!(format_count(12_363_693), "12.4M");
-        assert_eq!(format_count(98_370_345), "98.4M");
-    }
-
     #[test]
     fn test_table_label_with_row_count() {
         let mut tree = TreeBrowser::new();
@@ -2039,7 +2988,7 @@ mod tests {
             .iter()
             .find(|i| matches!(i.kind, NodeKind::Table) && i.label.contains("users"))
             .expect("should find users table");
-        assert_eq!(table_item.label, "users (~1.5K)");
+        assert_eq!(table_item.label, "users  ~1.5K rows  16 kB");
     }
 
     #[test]
@@ -2062,7 +3011,7 @@ mod tests {
 
         let view_item = tree.items.iter().find(|i| i.label.contains("active_users"));
         if let Some(item) = view_item {
-            // Views have row_count: None, so no (~N) suffix
+            // Views have no row estimate or size, so no suffix
             assert_eq!(item.label, "active_users");
         }
     }
@@ -3,7 +3,13 @@
 //! CJK and other full-width characters occupy 2 terminal columns but are
 //! counted as 1 by `.chars().count()` and as 3 bytes by `.len()`. All UI
 //! width calculations must use these helpers instead.
+//!
+//! Likewise, one visible character can be several `char`s (an accented
+//! letter built from combining marks, a flag, a ZWJ emoji sequence). Cursor
+//! movement and deletion step over whole grapheme clusters with
+//! `prev_grapheme` / `next_grapheme` so the cursor never lands inside one.
 
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_truncate::UnicodeTruncateStr;
 use unicode_width::UnicodeWidthStr;
 
@@ -27,6 +33,35 @@ pub fn truncate_to_width(s: &str, max_cols: usize) -> String {
     format!("{}...", t)
 }
 
+/// Byte length of the longest prefix of `s` that fits within `max_cols`
+/// terminal columns without splitting a grapheme cluster.
+pub fn fit_width(s: &str, max_cols: usize) -> usize {
+    let mut cols = 0;
+    for (i, g) in s.grapheme_indices(true) {
+        cols += display_width(g);
+        if cols > max_cols {
+            return i;
+        }
+    }
+    s.len()
+}
+
+/// Byte index of the grapheme boundary before `byte` (0 at the start).
+pub fn prev_grapheme(s: &str, byte: usize) -> usize {
+    s[..byte]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+/// Byte index of the grapheme boundary after `byte` (`s.len()` at the end).
+pub fn next_grapheme(s: &str, byte: usize) -> usize {
+    s[byte..]
+        .graphemes(true)
+        .next()
+        .map_or(s.len(), |g| byte + g.len())
+}
+
 /// Pad a string with trailing spaces so it occupies exactly `target_cols`
 /// terminal columns. If the string is already wider, returns it as-is.
 pub fn pad_to_width(s: &str, target_cols: usize) -> String {
@@ -121,6 +156,29 @@ mod tests {
         assert_eq!(pad_to_width("hello", 3), "hello");
     }
 
+    #[test]
+    fn test_fit_width() {
+        assert_eq!(fit_width("hello", 3), 3);
+        assert_eq!(fit_width("hello", 10), 5);
+        // A wide character that would straddle the edge is left out
+        assert_eq!(fit_width("a日本", 4), 4);
+        assert_eq!(fit_width("a日本", 5), 7);
+        // "e" + combining acute is one column and stays whole
+        assert_eq!(fit_width("e\u{301}x", 1), 3);
+    }
+
+    #[test]
+    fn test_grapheme_steps() {
+        let s = "ae\u{301}\u{1f1f3}\u{1f1f4}b";
+        assert_eq!(next_grapheme(s, 0), 1);
+        assert_eq!(next_grapheme(s, 1), 4); // e + combining acute
+        assert_eq!(next_grapheme(s, 4), 12); // regional-indicator flag
+        assert_eq!(next_grapheme(s, s.len()), s.len());
+        assert_eq!(prev_grapheme(s, 12), 4);
+        assert_eq!(prev_grapheme(s, 4), 1);
+        assert_eq!(prev_grapheme(s, 0), 0);
+    }
+
     #[test]
     fn test_rpad_to_width() {
         assert_eq!(rpad_to_width("hi", 5), "   hi");
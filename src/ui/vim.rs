@@ -0,0 +1,459 @@
+//! Vim-style modal editing for the query editor
+//!
+//! `VimState` turns normal- and visual-mode keystrokes into commands —
+//! counts, operators, and motions — and `QueryEditor` applies them to its
+//! text. Enabled with `editor_mode = "vim"`; insert mode types as usual.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Which keys do what in the editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimMode {
+    Normal,
+    Insert,
+    /// Character-wise selection (`v`)
+    Visual,
+    /// Line-wise selection (`V`)
+    VisualLine,
+}
+
+impl VimMode {
+    /// Label for the status bar's mode indicator
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Normal => "NORMAL",
+            Self::Insert => "INSERT",
+            Self::Visual => "VISUAL",
+            Self::VisualLine => "V-LINE",
+        }
+    }
+
+    /// Whether a selection is being made
+    pub fn is_visual(self) -> bool {
+        matches!(self, Self::Visual | Self::VisualLine)
+    }
+}
+
+/// Where a motion moves the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Left,
+    Right,
+    Up,
+    Down,
+    /// `w`: start of the next word
+    WordForward,
+    /// `b`: start of the previous word
+    WordBackward,
+    /// `e`: end of the word
+    WordEnd,
+    /// `0`
+    LineStart,
+    /// `^`: first non-blank character
+    FirstNonBlank,
+    /// `$`
+    LineEnd,
+    /// `gg`: first line, or line `count`
+    FirstLine,
+    /// `G`: last line, or line `count`
+    LastLine,
+}
+
+impl Motion {
+    /// An operator on this motion acts on whole lines
+    pub fn linewise(self) -> bool {
+        matches!(
+            self,
+            Self::Up | Self::Down | Self::FirstLine | Self::LastLine
+        )
+    }
+
+    /// An operator on this motion includes the character it lands on
+    pub fn inclusive(self) -> bool {
+        matches!(self, Self::WordEnd | Self::LineEnd)
+    }
+}
+
+/// What `d`, `c`, and `y` do with the text they cover
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Delete,
+    /// Delete, then insert in its place
+    Change,
+    Yank,
+}
+
+/// Where `i`, `a`, `o` and friends start inserting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertAt {
+    /// `i`
+    Cursor,
+    /// `a`
+    AfterCursor,
+    /// `I`
+    LineStart,
+    /// `A`
+    LineEnd,
+    /// `o`
+    LineBelow,
+    /// `O`
+    LineAbove,
+}
+
+/// A complete normal- or visual-mode command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimCommand {
+    Move {
+        motion: Motion,
+        count: Option<usize>,
+    },
+    /// An operator over a motion (`d2w`, `c$`, `yj`)
+    Operate {
+        op: Operator,
+        motion: Motion,
+        count: Option<usize>,
+    },
+    /// An operator over `count` lines (`dd`, `cc`, `yy`)
+    OperateLines {
+        op: Operator,
+        count: usize,
+    },
+    /// An operator over the visual selection
+    OperateSelection(Operator),
+    /// `x`: delete characters under the cursor
+    DeleteChars(usize),
+    Insert(InsertAt),
+    /// `p` (after the cursor) and `P` (before it)
+    Paste {
+        before: bool,
+        count: usize,
+    },
+    Undo(usize),
+    /// `v`, `V`, or Esc out of visual mode
+    SetMode(VimMode),
+}
+
+/// Keystrokes typed so far in normal and visual mode
+#[derive(Debug, Clone)]
+pub struct VimState {
+    mode: VimMode,
+    count: Option<usize>,
+    /// An operator waiting for its motion, with the count typed before it
+    operator: Option<(Operator, Option<usize>)>,
+    /// `g` typed, waiting for the second `g`
+    g_pending: bool,
+}
+
+impl VimState {
+    pub fn new() -> Self {
+        Self {
+            mode: VimMode::Normal,
+            count: None,
+            operator: None,
+            g_pending: false,
+        }
+    }
+
+    pub fn mode(&self) -> VimMode {
+        self.mode
+    }
+
+    /// Switch modes, dropping any half-typed command
+    pub fn set_mode(&mut self, mode: VimMode) {
+        self.mode = mode;
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        self.count = None;
+        self.operator = None;
+        self.g_pending = false;
+    }
+
+    /// Feed a normal- or visual-mode key. Returns the command it completes,
+    /// or None while one is still being typed (or the key means nothing).
+    pub fn key(&mut self, key: KeyEvent) -> Option<VimCommand> {
+        if key.code == KeyCode::Esc {
+            self.reset();
+            return self
+                .mode
+                .is_visual()
+                .then_some(VimCommand::SetMode(VimMode::Normal));
+        }
+        let c = match key.code {
+            KeyCode::Char(c) => Some(c),
+            _ => None,
+        };
+
+        if let Some(d) = c.and_then(|c| c.to_digit(10))
+            && (d > 0 || self.count.is_some())
+        {
+            let count = self.count.unwrap_or(0).saturating_mul(10);
+            self.count = Some(count.saturating_add(d as usize).min(9999));
+            return None;
+        }
+
+        if std::mem::take(&mut self.g_pending) {
+            return match c {
+                Some('g') => self.motion(Motion::FirstLine),
+                _ => {
+                    self.reset();
+                    None
+                }
+            };
+        }
+
+        if let Some(motion) = motion_for(key.code) {
+            return self.motion(motion);
+        }
+
+        let Some(c) = c else {
+            self.reset();
+            return None;
+        };
+        let visual = self.mode.is_visual();
+        let command = match c {
+            'g' => {
+                self.g_pending = true;
+                return None;
+            }
+            'd' | 'c' | 'y' | 'x' if visual => {
+                let op = match c {
+                    'c' => Operator::Change,
+                    'y' => Operator::Yank,
+                    _ => Operator::Delete,
+                };
+                Some(VimCommand::OperateSelection(op))
+            }
+            'v' | 'V' if visual => {
+                let mode = if c == 'v' {
+                    VimMode::Visual
+                } else {
+                    VimMode::VisualLine
+                };
+                // The same key again leaves visual mode; the other switches
+                Some(VimCommand::SetMode(if mode == self.mode {
+                    VimMode::Normal
+                } else {
+                    mode
+                }))
+            }
+            _ if visual => None,
+            'd' | 'c' | 'y' => {
+                let op = match c {
+                    'd' => Operator::Delete,
+                    'c' => Operator::Change,
+                    _ => Operator::Yank,
+                };
+                match self.operator.take() {
+                    // Doubled (`dd`): whole lines
+                    Some((pending, before)) if pending == op => {
+                        let count = times(before, self.count.take()).unwrap_or(1);
+                        Some(VimCommand::OperateLines { op, count })
+                    }
+                    Some(_) => None,
+                    None => {
+                        self.operator = Some((op, self.count.take()));
+                        return None;
+                    }
+                }
+            }
+            _ if self.operator.is_some() => None,
+            'D' | 'C' => Some(VimCommand::Operate {
+                op: if c == 'D' {
+                    Operator::Delete
+                } else {
+                    Operator::Change
+                },
+                motion: Motion::LineEnd,
+                count: self.count,
+            }),
+            'Y' => Some(VimCommand::OperateLines {
+                op: Operator::Yank,
+                count: self.count.unwrap_or(1),
+            }),
+            'x' => Some(VimCommand::DeleteChars(self.count.unwrap_or(1))),
+            'i' => Some(VimCommand::Insert(InsertAt::Cursor)),
+            'a' => Some(VimCommand::Insert(InsertAt::AfterCursor)),
+            'I' => Some(VimCommand::Insert(InsertAt::LineStart)),
+            'A' => Some(VimCommand::Insert(InsertAt::LineEnd)),
+            'o' => Some(VimCommand::Insert(InsertAt::LineBelow)),
+            'O' => Some(VimCommand::Insert(InsertAt::LineAbove)),
+            'p' | 'P' => Some(VimCommand::Paste {
+                before: c == 'P',
+                count: self.count.unwrap_or(1),
+            }),
+            'u' => Some(VimCommand::Undo(self.count.unwrap_or(1))),
+            'v' => Some(VimCommand::SetMode(VimMode::Visual)),
+            'V' => Some(VimCommand::SetMode(VimMode::VisualLine)),
+            _ => None,
+        };
+        self.reset();
+        command
+    }
+
+    /// A motion completes a move, or the pending operator
+    fn motion(&mut self, motion: Motion) -> Option<VimCommand> {
+        let count = self.count.take();
+        let command = match self.operator.take() {
+            Some((op, before)) => VimCommand::Operate {
+                op,
+                motion,
+                count: times(before, count),
+            },
+            None => VimCommand::Move { motion, count },
+        };
+        self.reset();
+        Some(command)
+    }
+}
+
+impl Default for VimState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The motion a key stands for, if any
+fn motion_for(code: KeyCode) -> Option<Motion> {
+    Some(match code {
+        KeyCode::Char('h') | KeyCode::Left | KeyCode::Backspace => Motion::Left,
+        KeyCode::Char('l') | KeyCode::Right | KeyCode::Char(' ') => Motion::Right,
+        KeyCode::Char('k') | KeyCode::Up => Motion::Up,
+        KeyCode::Char('j') | KeyCode::Down | KeyCode::Enter => Motion::Down,
+        KeyCode::Char('w') => Motion::WordForward,
+        KeyCode::Char('b') => Motion::WordBackward,
+        KeyCode::Char('e') => Motion::WordEnd,
+        KeyCode::Char('0') | KeyCode::Home => Motion::LineStart,
+        KeyCode::Char('^') => Motion::FirstNonBlank,
+        KeyCode::Char('$') | KeyCode::End => Motion::LineEnd,
+        KeyCode::Char('G') => Motion::LastLine,
+        _ => return None,
+    })
+}
+
+/// Counts typed before and after an operator multiply (`2d3w` is `d6w`)
+fn times(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (None, None) => None,
+        _ => Some(a.unwrap_or(1).saturating_mul(b.unwrap_or(1))),
+    }
+}
+
+/// Whether vim takes `key` before the keymap: Esc out of insert and visual
+/// mode, and plain characters outside insert mode
+pub fn claims_key(mode: VimMode, key: KeyEvent) -> bool {
+    let plain = key.modifiers.difference(KeyModifiers::SHIFT).is_empty();
+    match key.code {
+        KeyCode::Esc => mode != VimMode::Normal,
+        KeyCode::Char(_) => plain && mode != VimMode::Insert,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(state: &mut VimState, keys: &str) -> Option<VimCommand> {
+        let mut last = None;
+        for c in keys.chars() {
+            last = state.key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        last
+    }
+
+    #[test]
+    fn test_counts_motions_and_operators() {
+        let mut vim = VimState::new();
+        assert_eq!(
+            feed(&mut vim, "3w"),
+            Some(VimCommand::Move {
+                motion: Motion::WordForward,
+                count: Some(3)
+            })
+        );
+        assert_eq!(
+            feed(&mut vim, "2d3w"),
+            Some(VimCommand::Operate {
+                op: Operator::Delete,
+                motion: Motion::WordForward,
+                count: Some(6)
+            })
+        );
+        assert_eq!(
+            feed(&mut vim, "c$"),
+            Some(VimCommand::Operate {
+                op: Operator::Change,
+                motion: Motion::LineEnd,
+                count: None
+            })
+        );
+        assert_eq!(
+            feed(&mut vim, "10yy"),
+            Some(VimCommand::OperateLines {
+                op: Operator::Yank,
+                count: 10
+            })
+        );
+        // 0 is a motion unless it continues a count
+        assert_eq!(
+            feed(&mut vim, "0"),
+            Some(VimCommand::Move {
+                motion: Motion::LineStart,
+                count: None
+            })
+        );
+        assert_eq!(
+            feed(&mut vim, "dgg"),
+            Some(VimCommand::Operate {
+                op: Operator::Delete,
+                motion: Motion::FirstLine,
+                count: None
+            })
+        );
+        // A mismatched operator or unknown key drops the command
+        assert_eq!(feed(&mut vim, "dy"), None);
+        assert_eq!(
+            feed(&mut vim, "x"),
+            Some(VimCommand::DeleteChars(1)),
+            "state was reset"
+        );
+    }
+
+    #[test]
+    fn test_visual_mode_keys() {
+        let mut vim = VimState::new();
+        vim.set_mode(VimMode::Visual);
+        assert_eq!(
+            feed(&mut vim, "d"),
+            Some(VimCommand::OperateSelection(Operator::Delete))
+        );
+        assert_eq!(
+            feed(&mut vim, "V"),
+            Some(VimCommand::SetMode(VimMode::VisualLine))
+        );
+        assert_eq!(
+            feed(&mut vim, "v"),
+            Some(VimCommand::SetMode(VimMode::Normal))
+        );
+        assert_eq!(
+            vim.key(KeyEvent::from(KeyCode::Esc)),
+            Some(VimCommand::SetMode(VimMode::Normal))
+        );
+        // Inserting isn't a visual-mode command
+        assert_eq!(feed(&mut vim, "i"), None);
+    }
+
+    #[test]
+    fn test_claims_key() {
+        let key = |code| KeyEvent::from(code);
+        let ctrl_e = KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL);
+        assert!(claims_key(VimMode::Normal, key(KeyCode::Char('j'))));
+        assert!(!claims_key(VimMode::Normal, ctrl_e));
+        assert!(!claims_key(VimMode::Normal, key(KeyCode::Esc)));
+        assert!(claims_key(VimMode::Insert, key(KeyCode::Esc)));
+        assert!(!claims_key(VimMode::Insert, key(KeyCode::Char('j'))));
+        assert!(!claims_key(VimMode::Visual, key(KeyCode::Tab)));
+    }
+}
@@ -3,7 +3,7 @@
 //! Shared test infrastructure for integration and unit tests.
 
 use vizgres::config::ConnectionConfig;
-use vizgres::config::connections::SslMode;
+use vizgres::config::connections::{Backend, SslMode};
 use vizgres::db::schema::{Column, PaginatedVec, Schema, SchemaTree, Table};
 use vizgres::db::types::DataType;
 
@@ -16,6 +16,8 @@ pub fn test_schema() -> SchemaTree {
                 Table {
                     name: "users".to_string(),
                     row_count: Some(100),
+                    size_bytes: None,
+                    select_denied: false,
                     columns: vec![
                         Column {
                             name: "id".to_string(),
@@ -46,6 +48,8 @@ pub fn test_schema() -> SchemaTree {
                 Table {
                     name: "orders".to_string(),
                     row_count: Some(50),
+                    size_bytes: None,
+                    select_denied: false,
                     columns: vec![
                         Column {
                             name: "id".to_string(),
@@ -65,7 +69,13 @@ pub fn test_schema() -> SchemaTree {
             views: PaginatedVec::default(),
             indexes: PaginatedVec::default(),
             functions: PaginatedVec::default(),
+            sequences: PaginatedVec::default(),
+            enums: PaginatedVec::default(),
+            domains: PaginatedVec::default(),
+            loaded: true,
         }]),
+        extensions: Vec::new(),
+        databases: Vec::new(),
     }
 }
 
@@ -75,11 +85,16 @@ pub fn test_connection_config() -> ConnectionConfig {
         name: "test".to_string(),
         host: "localhost".to_string(),
         port: 5432,
+        backend: Backend::Postgres,
         database: "test_db".to_string(),
         username: "test_user".to_string(),
         password: Some("test_password".to_string()),
         ssl_mode: SslMode::Disable,
+        ssl_root_cert: None,
+        ssl_cert: None,
+        ssl_key: None,
         read_only: false,
+        prefer_simple_protocol: false,
         is_saved: false,
     }
 }
@@ -48,6 +48,10 @@ CREATE TABLE test_schema.settings (
     updated_at TIMESTAMPTZ DEFAULT NOW()
 );
 
+-- Types in test_schema for schema tree testing
+CREATE TYPE test_schema.mood AS ENUM ('sad', 'ok', 'happy');
+CREATE DOMAIN test_schema.email_address AS VARCHAR(255) CHECK (VALUE LIKE '%@%');
+
 -- Insert test data for users
 INSERT INTO users (name, email, active, metadata) VALUES
     ('Alice Smith', 'alice@example.com', true, '{"role": "admin", "permissions": ["read", "write", "delete"]}'),
@@ -19,7 +19,7 @@ use std::env;
 use std::sync::OnceLock;
 
 use vizgres::config::ConnectionConfig;
-use vizgres::config::connections::SslMode;
+use vizgres::config::connections::{Backend, SslMode};
 use vizgres::db::Database;
 use vizgres::db::postgres::PostgresProvider;
 use vizgres::db::types::{CellValue, DataType};
@@ -32,11 +32,16 @@ fn imdb_config(read_only: bool) -> ConnectionConfig {
             .ok()
             .and_then(|p| p.parse().ok())
             .unwrap_or(5433),
+        backend: Backend::Postgres,
         database: "imdb".into(),
         username: env::var("IMDB_DB_USER").unwrap_or_else(|_| "test_user".into()),
         password: Some(env::var("IMDB_DB_PASSWORD").unwrap_or_else(|_| "test_password".into())),
         ssl_mode: SslMode::Disable,
+        ssl_root_cert: None,
+        ssl_cert: None,
+        ssl_key: None,
         read_only,
+        prefer_simple_protocol: false,
         is_saved: false,
     }
 }
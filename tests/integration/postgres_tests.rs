@@ -4,7 +4,7 @@
 //! Start it with: docker-compose -f docker-compose.test.yml up -d
 
 use vizgres::config::ConnectionConfig;
-use vizgres::config::connections::SslMode;
+use vizgres::config::connections::{Backend, SslMode};
 use vizgres::db::Database;
 use vizgres::db::postgres::PostgresProvider;
 use vizgres::db::types::CellValue;
@@ -18,13 +18,18 @@ fn test_config() -> ConnectionConfig {
             .ok()
             .and_then(|p| p.parse().ok())
             .unwrap_or(5433),
+        backend: Backend::Postgres,
         database: std::env::var("TEST_DB_NAME").unwrap_or_else(|_| "test_db".to_string()),
         username: std::env::var("TEST_DB_USER").unwrap_or_else(|_| "test_user".to_string()),
         password: Some(
             std::env::var("TEST_DB_PASSWORD").unwrap_or_else(|_| "test_password".to_string()),
         ),
         ssl_mode: SslMode::Disable,
+        ssl_root_cert: None,
+        ssl_cert: None,
+        ssl_key: None,
         read_only: false,
+        prefer_simple_protocol: false,
         is_saved: false,
     }
 }
@@ -159,6 +164,32 @@ async fn test_query_null_values() {
     assert!(first_row.values[1].is_null(), "Metadata should be NULL");
 }
 
+#[tokio::test]
+async fn test_dml_reports_affected_rows() {
+    let provider = connect().await;
+
+    provider.execute_query("BEGIN", 0, 0).await.unwrap();
+    let results = provider
+        .execute_query("UPDATE users SET name = name WHERE metadata IS NULL", 0, 0)
+        .await
+        .unwrap();
+    provider.execute_query("ROLLBACK", 0, 0).await.unwrap();
+
+    let tag = results.command.expect("UPDATE should have a command tag");
+    assert_eq!(tag.command, "UPDATE");
+    assert!(
+        tag.rows > 0,
+        "Should have updated the users with NULL metadata"
+    );
+    assert!(results.columns.is_empty());
+
+    let results = provider
+        .execute_query("SELECT name FROM users", 0, 0)
+        .await
+        .unwrap();
+    assert!(results.command.is_none());
+}
+
 #[tokio::test]
 async fn test_query_numeric_types() {
     let provider = connect().await;
@@ -508,3 +539,223 @@ async fn test_search_schema_special_characters() {
         "Search with special chars should not error"
     );
 }
+
+#[tokio::test]
+async fn test_query_with_named_params() {
+    let provider = connect().await;
+    let (progress, _rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let results = provider
+        .execute_query_with_progress(
+            "SELECT name FROM users WHERE id = :id AND active = :active AND id <= :id",
+            &[Some("1".to_string()), Some("true".to_string())],
+            0,
+            0,
+            progress,
+        )
+        .await
+        .expect("parameterized query should succeed");
+
+    assert_eq!(results.row_count, 1);
+    match &results.rows[0].values[0] {
+        CellValue::Text(s) => assert_eq!(s, "Alice Smith"),
+        other => panic!("Expected Text for name, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_query_with_null_param() {
+    let provider = connect().await;
+    let (progress, _rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let results = provider
+        .execute_query_with_progress("SELECT $1::int IS NULL AS missing", &[None], 0, 0, progress)
+        .await
+        .expect("NULL parameter should bind");
+
+    match &results.rows[0].values[0] {
+        CellValue::Boolean(b) => assert!(*b),
+        other => panic!("Expected Boolean, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_object_ddl_for_table_view_index_and_function() {
+    use vizgres::db::ddl::DdlTarget;
+
+    let provider = connect().await;
+    let relation = |name: &str| DdlTarget::Relation {
+        schema: "public".to_string(),
+        name: name.to_string(),
+    };
+
+    let table = provider.object_ddl(&relation("orders")).await.unwrap();
+    assert!(
+        table.starts_with("CREATE TABLE public.orders ("),
+        "{}",
+        table
+    );
+    assert!(table.contains("amount numeric(10,2) NOT NULL"), "{}", table);
+    assert!(
+        table.contains("FOREIGN KEY (user_id) REFERENCES users(id)"),
+        "{}",
+        table
+    );
+    assert!(
+        table.contains("CREATE INDEX idx_orders_user_id"),
+        "{}",
+        table
+    );
+
+    let view = provider
+        .object_ddl(&relation("user_order_summary"))
+        .await
+        .unwrap();
+    assert!(
+        view.starts_with("CREATE OR REPLACE VIEW public.user_order_summary AS\n"),
+        "{}",
+        view
+    );
+
+    let index = provider
+        .object_ddl(&relation("idx_users_email"))
+        .await
+        .unwrap();
+    assert!(
+        index.starts_with("CREATE INDEX idx_users_email"),
+        "{}",
+        index
+    );
+
+    let function = provider
+        .object_ddl(&DdlTarget::Function {
+            schema: "public".to_string(),
+            name: "format_user_name".to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(
+        function.starts_with("CREATE OR REPLACE FUNCTION public.format_user_name"),
+        "{}",
+        function
+    );
+
+    assert!(
+        provider
+            .object_ddl(&relation("no_such_table"))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_list_schemas_then_load_one() {
+    let provider = connect().await;
+
+    let names = provider.list_schemas().await.unwrap();
+    let public = names
+        .schemas
+        .iter()
+        .find(|s| s.name == "public")
+        .expect("public schema listed");
+    assert!(!public.loaded);
+    assert!(public.tables.is_empty());
+
+    let public = provider.load_schema("public", 1).await.unwrap();
+    assert!(public.loaded);
+    assert_eq!(public.tables.len(), 1);
+    assert!(public.tables.is_truncated(), "total counts all tables");
+    assert!(!public.tables.items[0].columns.is_empty());
+}
+
+#[tokio::test]
+async fn test_load_schema_table_sizes() {
+    let provider = connect().await;
+
+    let public = provider.load_schema("public", 0).await.unwrap();
+    let users = public
+        .tables
+        .iter()
+        .find(|t| t.name == "users")
+        .expect("users table loaded");
+    assert!(users.size_bytes.is_some_and(|b| b > 0));
+    assert!(public.views.iter().all(|v| v.size_bytes.is_none()));
+
+    let more = provider.load_more_tables("public", 0, 10).await.unwrap();
+    assert!(more.iter().all(|t| t.size_bytes.is_some()));
+}
+
+#[tokio::test]
+async fn test_load_schema_sequences_types_and_extensions() {
+    let provider = connect().await;
+
+    let names = provider.list_schemas().await.unwrap();
+    assert!(names.extensions.iter().any(|e| e.name == "plpgsql"));
+
+    let public = provider.load_schema("public", 0).await.unwrap();
+    let users_seq = public
+        .sequences
+        .iter()
+        .find(|s| s.name == "users_id_seq")
+        .expect("serial sequence listed");
+    assert!(users_seq.last_value.is_some_and(|v| v >= 4));
+
+    let test_schema = provider.load_schema("test_schema", 0).await.unwrap();
+    let mood = test_schema.enums.first().expect("enum listed");
+    assert_eq!(mood.name, "mood");
+    assert_eq!(mood.labels, vec!["sad", "ok", "happy"]);
+    let domain = test_schema.domains.first().expect("domain listed");
+    assert_eq!(domain.name, "email_address");
+    assert_eq!(domain.base_type, "character varying(255)");
+}
+
+#[tokio::test]
+async fn test_copy_out_streams_csv() {
+    let provider = connect().await;
+    let options = vizgres::export::CsvOptions::csv();
+    let mut out = Vec::new();
+    let bytes = provider
+        .copy_out(
+            "SELECT n, 'row ' || n AS label FROM generate_series(1, 3) n;",
+            &options.copy_options(),
+            |chunk| {
+                out.extend_from_slice(chunk);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(bytes, out.len() as u64);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "n,label\n1,row 1\n2,row 2\n3,row 3\n"
+    );
+}
+
+#[tokio::test]
+async fn test_job_pool_reuses_released_connection() {
+    let mgr = vizgres::connection_manager::ConnectionManager::new(Some(test_config()), 0);
+    let pool = mgr.job_pool().expect("configured manager has a job pool");
+
+    let first = pool.acquire(1).await.expect("job connection");
+    assert!(pool.running(1).is_some());
+    let results = first
+        .execute_query("SELECT pg_backend_pid()", 0, 0)
+        .await
+        .unwrap();
+    let CellValue::Integer(pid) = results.rows[0].values[0] else {
+        panic!("expected integer pid");
+    };
+    pool.release(1, first);
+    assert!(pool.running(1).is_none());
+
+    let second = pool.acquire(2).await.expect("job connection");
+    let results = second
+        .execute_query("SELECT pg_backend_pid()", 0, 0)
+        .await
+        .unwrap();
+    assert!(
+        matches!(results.rows[0].values[0], CellValue::Integer(n) if n == pid),
+        "second job should reuse the first job's connection"
+    );
+}